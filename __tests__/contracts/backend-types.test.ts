@@ -31,6 +31,7 @@ import type {
   Session,
   SessionSummary,
   SessionStatus,
+  BugStatusCounts,
   Bug,
   BugUpdate,
   BugType,
@@ -45,6 +46,7 @@ import type {
   CreateTicketResponse,
   ConnectionStatus,
   LinearTemplate,
+  TicketStatusCategory,
 } from '../../src/types/backend'
 
 // ---------------------------------------------------------------------------
@@ -157,6 +159,8 @@ describe('Session type contract', () => {
     original_snip_path: null,
     created_at: '2024-01-15T10:00:00Z',
     profile_id: null,
+    unlocked_at: null,
+    timezone: 'Europe/Berlin',
   }
 
   it('mock satisfies Session interface (compile-time + runtime check)', () => {
@@ -173,13 +177,15 @@ describe('Session type contract', () => {
       original_snip_path: 'nullable-string',
       created_at: 'string',
       profile_id: 'nullable-string',
+      unlocked_at: 'nullable-string',
+      timezone: 'nullable-string',
     }
     assertShape(mockSession as unknown as Record<string, unknown>, spec, 'Session')
   })
 
-  it('has exactly 10 fields matching the Rust struct', () => {
-    // Rust Session has 10 pub fields; count here must match.
-    expect(Object.keys(mockSession)).toHaveLength(10)
+  it('has exactly 12 fields matching the Rust struct', () => {
+    // Rust Session has 12 pub fields; count here must match.
+    expect(Object.keys(mockSession)).toHaveLength(12)
   })
 
   it('field names are snake_case (no camelCase conversion at IPC boundary)', () => {
@@ -193,7 +199,9 @@ describe('Session type contract', () => {
     expect(keys).not.toContain('originalSnipPath')
     expect(keys).not.toContain('createdAt')
     expect(keys).not.toContain('profileId')
+    expect(keys).not.toContain('unlockedAt')
     expect(keys).toContain('profile_id')
+    expect(keys).toContain('unlocked_at')
   })
 
   it('status field accepts all valid SessionStatus values', () => {
@@ -221,6 +229,9 @@ describe('SessionSummary type contract', () => {
     ended_at: null,
     status: 'active',
     bug_count: 3,
+    status_counts: { capturing: 1, captured: 1, reviewed: 1, ready: 0 },
+    reviewed_count: 1,
+    ticketed_count: 0,
   }
 
   it('mock satisfies SessionSummary interface', () => {
@@ -230,18 +241,37 @@ describe('SessionSummary type contract', () => {
       ended_at: 'nullable-string',
       status: 'string',
       bug_count: 'number',
+      status_counts: 'object',
+      reviewed_count: 'number',
+      ticketed_count: 'number',
     }
     assertShape(mockSummary as unknown as Record<string, unknown>, spec, 'SessionSummary')
   })
 
-  it('has exactly 5 fields matching the Rust struct', () => {
-    // Rust SessionSummary has 5 pub fields: id, started_at, ended_at, status, bug_count
-    expect(Object.keys(mockSummary)).toHaveLength(5)
+  it('has exactly 8 fields matching the Rust struct', () => {
+    // Rust SessionSummary has 8 pub fields: id, started_at, ended_at, status, bug_count,
+    // status_counts, reviewed_count, ticketed_count
+    expect(Object.keys(mockSummary)).toHaveLength(8)
+  })
+
+  it('status_counts has one number per BugStatus', () => {
+    const spec: FieldSpec = {
+      capturing: 'number',
+      captured: 'number',
+      reviewed: 'number',
+      ready: 'number',
+    }
+    const counts: BugStatusCounts = mockSummary.status_counts
+    assertShape(counts as unknown as Record<string, unknown>, spec, 'BugStatusCounts')
+    expect(Object.keys(counts)).toHaveLength(4)
   })
 
   it('bug_count uses snake_case not camelCase', () => {
     expect('bug_count' in mockSummary).toBe(true)
     expect('bugCount' in mockSummary).toBe(false)
+    expect('statusCounts' in mockSummary).toBe(false)
+    expect('reviewedCount' in mockSummary).toBe(false)
+    expect('ticketedCount' in mockSummary).toBe(false)
   })
 })
 
@@ -268,6 +298,11 @@ describe('Bug type contract', () => {
     folder_path: '/captures/session-1/bug-1',
     created_at: '2024-01-15T10:05:00Z',
     updated_at: '2024-01-15T10:05:00Z',
+    external_ticket_id: null,
+    external_ticket_key: null,
+    external_ticket_url: null,
+    external_status: null,
+    external_status_category: null,
   }
 
   it('mock satisfies Bug interface', () => {
@@ -289,13 +324,19 @@ describe('Bug type contract', () => {
       folder_path: 'string',
       created_at: 'string',
       updated_at: 'string',
+      external_ticket_id: 'nullable-string',
+      external_ticket_key: 'nullable-string',
+      external_ticket_url: 'nullable-string',
+      external_status: 'nullable-string',
+      external_status_category: 'nullable-string',
     }
     assertShape(mockBug as unknown as Record<string, unknown>, spec, 'Bug')
   })
 
-  it('has exactly 17 fields matching the Rust struct', () => {
-    // Rust Bug has 17 pub fields (bug_type serialized as "type" via #[serde(rename)])
-    expect(Object.keys(mockBug)).toHaveLength(17)
+  it('has exactly 22 fields matching the Rust struct', () => {
+    // Rust Bug has 23 pub fields (bug_type serialized as "type" via #[serde(rename)]);
+    // custom_metadata is optional and left out of this mock.
+    expect(Object.keys(mockBug)).toHaveLength(22)
   })
 
   it('type field is "type" not "bug_type" (Rust uses #[serde(rename = "type")])', () => {
@@ -317,6 +358,24 @@ describe('Bug type contract', () => {
     expect(keys).toContain('folder_path')
     expect(keys).toContain('created_at')
     expect(keys).toContain('updated_at')
+    expect(keys).toContain('external_ticket_key')
+    expect(keys).toContain('external_status_category')
+  })
+
+  it('external ticket fields are populated once the bug is ticketed', () => {
+    const categories: TicketStatusCategory[] = ['open', 'in_progress', 'done', 'canceled']
+    for (const category of categories) {
+      const b: Bug = {
+        ...mockBug,
+        external_ticket_id: 'issue-uuid-1',
+        external_ticket_key: 'QA-42',
+        external_ticket_url: 'https://linear.app/team/issue/QA-42',
+        external_status: 'In Review',
+        external_status_category: category,
+      }
+      expect(b.external_ticket_key).toBe('QA-42')
+      expect(b.external_status_category).toBe(category)
+    }
   })
 
   it('type field accepts all BugType values', () => {
@@ -2,22 +2,22 @@
  * Contract test: Tauri command registration
  *
  * Verifies that every command the frontend invokes (src/api/tauri.ts) has a
- * corresponding registered handler in the Rust backend invoke_handler macro
- * (src-tauri/src/lib.rs).
+ * corresponding registered handler in the Rust backend's command_registry!
+ * invocation (src-tauri/src/lib.rs).
  *
  * Missing backend commands cause silent runtime failures at the Tauri IPC
  * boundary, so we catch them statically here instead of at runtime.
  *
  * Approach:
  *  1. Parse src/api/tauri.ts with a regex to extract all invoke() call strings
- *  2. Parse src-tauri/src/lib.rs with a regex to extract all identifiers inside
- *     the tauri::generate_handler![ … ] macro
+ *  2. Parse src-tauri/src/lib.rs with a regex to extract all command identifiers
+ *     from the domain lists of the command_registry! { … } invocation
  *  3. Assert every frontend command appears in the backend registration list
  *
  * Note: Both files use snake_case for command names at the IPC boundary.
  * The frontend TypeScript wrapper functions may use camelCase names, but the
  * string literals passed to invoke() are always snake_case and must match the
- * Rust function names registered in invoke_handler.
+ * Rust function names registered in command_registry!.
  */
 
 import { describe, it, expect } from 'vitest'
@@ -51,36 +51,52 @@ function extractFrontendCommands(source: string): string[] {
 }
 
 /**
- * Extract every command identifier listed inside tauri::generate_handler![ … ]
- * in the given Rust source.
+ * Extract every command identifier listed in the domain lists of the
+ * command_registry! { … } invocation in the given Rust source:
  *
- * The macro block is terminated by the first `]` that closes it. Identifiers
- * are separated by commas and optional whitespace/newlines.
+ *   command_registry::command_registry! {
+ *       Session => [open_session_folder, start_session, …],
+ *       Bug => [ … ],
+ *   }
+ *
+ * The invocation is terminated by the first `}` at the start of a line.
+ * Identifiers inside each `Domain => [ … ]` list are separated by commas and
+ * optional whitespace/newlines.
  *
  * Returns unique command names in sorted order.
  */
 function extractBackendHandlers(source: string): string[] {
-  // Find the generate_handler![ ... ] block
-  const startMarker = 'tauri::generate_handler!['
+  // Find the command_registry! { ... } block
+  const startMarker = 'command_registry::command_registry! {'
   const startIdx = source.indexOf(startMarker)
   if (startIdx === -1) {
-    throw new Error('Could not find tauri::generate_handler![ in lib.rs')
+    throw new Error('Could not find command_registry::command_registry! { in lib.rs')
   }
 
   const blockStart = startIdx + startMarker.length
-  const blockEnd = source.indexOf(']', blockStart)
+  const blockEnd = source.indexOf('\n}', blockStart)
   if (blockEnd === -1) {
-    throw new Error('Could not find closing ] of tauri::generate_handler! macro')
+    throw new Error('Could not find closing } of command_registry! invocation')
   }
 
   const block = source.slice(blockStart, blockEnd)
   const handlers = new Set<string>()
 
-  // Each entry is a Rust identifier (word chars and underscores)
-  const identPattern = /\b([a-z][a-z0-9_]*)\b/g
-  let match: RegExpExecArray | null
-  while ((match = identPattern.exec(block)) !== null) {
-    handlers.add(match[1]!)
+  // Each domain list is `Domain => [ ... ]`
+  const domainPattern = /\b[A-Z][A-Za-z]*\s*=>\s*\[([^\]]*)\]/g
+  let domains = 0
+  let domainMatch: RegExpExecArray | null
+  while ((domainMatch = domainPattern.exec(block)) !== null) {
+    domains++
+    // Each entry is a Rust identifier (word chars and underscores)
+    const identPattern = /\b([a-z][a-z0-9_]*)\b/g
+    let match: RegExpExecArray | null
+    while ((match = identPattern.exec(domainMatch[1]!)) !== null) {
+      handlers.add(match[1]!)
+    }
+  }
+  if (domains === 0) {
+    throw new Error('Could not find any `Domain => [ … ]` list in command_registry!')
   }
 
   return [...handlers].sort()
@@ -118,8 +134,8 @@ describe('Tauri command registration contract', () => {
       const detail = missing.map(cmd => `  - "${cmd}"`).join('\n')
       throw new Error(
         `The following commands are called from src/api/tauri.ts but are NOT registered in ` +
-        `src-tauri/src/lib.rs invoke_handler:\n${detail}\n\n` +
-        `Add them to the tauri::generate_handler![] macro in lib.rs, or remove the invoke() call.`
+        `src-tauri/src/lib.rs command_registry!:\n${detail}\n\n` +
+        `Add them to a domain list of command_registry! in lib.rs, or remove the invoke() call.`
       )
     }
 
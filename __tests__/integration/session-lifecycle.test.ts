@@ -374,8 +374,14 @@ describe('Session Lifecycle Integration', () => {
       const sessionStore = useSessionStore()
 
       const summaries: SessionSummary[] = [
-        { id: 'session-1', started_at: '2024-01-01T10:00:00Z', ended_at: null, status: 'active', bug_count: 3 },
-        { id: 'session-2', started_at: '2024-01-02T10:00:00Z', ended_at: '2024-01-02T12:00:00Z', status: 'ended', bug_count: 7 },
+        {
+          id: 'session-1', started_at: '2024-01-01T10:00:00Z', ended_at: null, status: 'active', bug_count: 3,
+          status_counts: { capturing: 1, captured: 2, reviewed: 0, ready: 0 }, reviewed_count: 0, ticketed_count: 0,
+        },
+        {
+          id: 'session-2', started_at: '2024-01-02T10:00:00Z', ended_at: '2024-01-02T12:00:00Z', status: 'ended', bug_count: 7,
+          status_counts: { capturing: 0, captured: 3, reviewed: 2, ready: 2 }, reviewed_count: 4, ticketed_count: 2,
+        },
       ]
       vi.mocked(tauri.getSessionSummaries).mockResolvedValue(summaries)
 
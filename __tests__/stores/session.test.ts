@@ -28,6 +28,7 @@ vi.mock('@tauri-apps/api/core', () => ({
 
 import * as tauri from '@/api/tauri'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 
 const mockSession: Session = {
   id: 'session-1',
@@ -48,6 +49,9 @@ const mockSessionSummary: SessionSummary = {
   ended_at: null,
   status: 'active',
   bug_count: 3,
+  status_counts: { capturing: 1, captured: 2, reviewed: 0, ready: 0 },
+  reviewed_count: 0,
+  ticketed_count: 0,
 }
 
 describe('Session Store', () => {
@@ -486,4 +490,27 @@ describe('Session Store', () => {
     })
   })
 
+  describe('session:started event', () => {
+    it('should load the active session when a session is started elsewhere', async () => {
+      const handlers: Record<string, (event: { payload: unknown }) => unknown> = {}
+      vi.mocked(listen).mockImplementation(async (name, handler) => {
+        handlers[name as string] = handler as (event: { payload: unknown }) => unknown
+        return () => {}
+      })
+      vi.mocked(tauri.getActiveSession).mockResolvedValue(mockSession)
+
+      const store = useSessionStore()
+      await store.setupEventListeners()
+      expect(store.isSessionActive).toBe(false)
+
+      await handlers['session:started']!({
+        payload: { sessionId: 'session-1', folderPath: '/test/sessions/session1', startedAt: '2024-01-01T10:00:00Z' },
+      })
+
+      expect(tauri.getActiveSession).toHaveBeenCalledTimes(1)
+      expect(store.isSessionActive).toBe(true)
+      expect(store.activeSessionId).toBe('session-1')
+    })
+  })
+
 })
@@ -11,6 +11,15 @@ vi.mock('@/api/tauri', () => ({
   deleteSetting: vi.fn(),
 }))
 
+// Mock Tauri event API, keeping the handlers so tests can emit events
+const eventHandlers = vi.hoisted(() => ({} as Record<string, (event: { payload: unknown }) => void>))
+vi.mock('@tauri-apps/api/event', () => ({
+  listen: vi.fn((name: string, handler: (event: { payload: unknown }) => void) => {
+    eventHandlers[name] = handler
+    return Promise.resolve(() => {})
+  }),
+}))
+
 import * as tauri from '@/api/tauri'
 
 const mockSettings: Setting[] = [
@@ -362,4 +371,21 @@ describe('Settings Store', () => {
       expect(store.settings[SETTINGS_KEYS.THEME]).toBe('light')
     })
   })
+
+  describe('Event Listeners', () => {
+    it('should follow quick toggles switched from the tray', async () => {
+      const store = useSettingsStore()
+      expect(store.notificationsEnabled).toBe(true)
+      expect(store.consoleAutoParse).toBe(true)
+
+      await store.setupEventListeners()
+      eventHandlers['settings:quick-toggle-changed']?.({
+        payload: { key: SETTINGS_KEYS.NOTIFICATIONS_ENABLED, enabled: false },
+      })
+
+      expect(store.notificationsEnabled).toBe(false)
+      expect(store.settings[SETTINGS_KEYS.NOTIFICATIONS_ENABLED]).toBe('false')
+      expect(store.isDirty).toBe(false)
+    })
+  })
 })
@@ -38,10 +38,13 @@ vi.mock('@/api/tauri', () => ({
   assignCaptureToBug: vi.fn().mockResolvedValue(undefined),
   suggestCaptureAssignment: vi.fn().mockResolvedValue(undefined),
   ticketingGetCredentials: vi.fn(),
+  ticketingGetProvider: vi.fn().mockResolvedValue('linear'),
+  ticketingGetJiraConfig: vi.fn(),
   ticketingSaveCredentials: vi.fn(),
   ticketingAuthenticate: vi.fn(),
   ticketingCreateTicket: vi.fn(),
   ticketingCheckConnection: vi.fn(),
+  getLinearProfileDefaults: vi.fn().mockResolvedValue(null),
   getClaudeStatus: vi.fn(),
   generateBugDescription: vi.fn(),
   refineBugDescription: vi.fn(),
@@ -730,4 +733,40 @@ describe('SessionReview', () => {
       }
     })
   })
+
+  describe('Linear push', () => {
+    it('should send the bug id with each ticket so the backend records it on the bug', async () => {
+      const sessionStore = useSessionStore()
+      sessionStore.activeSession = createMockSession('session-1')
+
+      const bug = { ...createMockBug('bug-1', 'session-1', 'BUG-001'), status: 'ready' as const }
+      vi.mocked(tauri.getBugsBySession).mockResolvedValue([bug])
+      vi.mocked(tauri.getBugCaptures).mockResolvedValue([])
+      vi.mocked(tauri.ticketingGetCredentials).mockResolvedValue({ api_key: 'lin_api_key' })
+      vi.mocked(tauri.ticketingCreateTicket).mockResolvedValue({
+        id: 'ticket-1',
+        identifier: 'QA-1',
+        url: 'https://linear.app/team/issue/QA-1',
+        attachment_results: []
+      })
+
+      const wrapper = await mountComponent()
+      await flushPromises()
+
+      const openButton = wrapper.findAll('button').find(btn => btn.text().includes('Push to Linear'))
+      expect(openButton).toBeDefined()
+      await openButton!.trigger('click')
+      await flushPromises()
+
+      const pushButton = wrapper.findAll('button').find(btn => btn.text().includes('Push Now'))
+      expect(pushButton).toBeDefined()
+      await pushButton!.trigger('click')
+      await flushPromises()
+
+      expect(tauri.ticketingCreateTicket).toHaveBeenCalledWith(
+        expect.objectContaining({ title: 'Test Bug bug-1' }),
+        'bug-1'
+      )
+    })
+  })
 })
@@ -0,0 +1,142 @@
+//! Annotating a bug's screenshots one after another.
+//!
+//! `open_annotation_queue` lists the screenshots of the active bug, or of the
+//! bug the reviewer picked, that have no annotated version yet, oldest first.
+//! The reviewer works through them in a single annotation window and calls
+//! `mark_annotation_done` for each one, saved or skipped, which returns the
+//! progress and the next screenshot. One queue is open at a time; opening
+//! another replaces it.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::database::{Capture, CaptureType};
+
+/// A screenshot in the queue.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationQueueItem {
+    pub capture_id: String,
+    pub file_name: String,
+    pub file_path: String,
+    pub done: bool,
+}
+
+/// The queue as shown to the reviewer.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationQueueProgress {
+    pub session_id: String,
+    pub bug_id: String,
+    pub items: Vec<AnnotationQueueItem>,
+    pub done: usize,
+    pub total: usize,
+    /// First screenshot not yet done; None once the queue is finished
+    pub next: Option<AnnotationQueueItem>,
+}
+
+/// Screenshots of one bug waiting for annotation.
+pub struct AnnotationQueue {
+    session_id: String,
+    bug_id: String,
+    /// `(capture id, file name, file path)` in annotation order
+    items: Vec<(String, String, String)>,
+    done: HashSet<String>,
+}
+
+impl AnnotationQueue {
+    /// Queue the screenshots among `captures` that have not been annotated,
+    /// in capture order.
+    pub fn new(session_id: &str, bug_id: &str, captures: &[Capture]) -> Self {
+        let mut pending: Vec<&Capture> = captures
+            .iter()
+            .filter(|c| c.file_type == CaptureType::Screenshot && c.annotated_path.is_none())
+            .collect();
+        pending.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Self {
+            session_id: session_id.to_string(),
+            bug_id: bug_id.to_string(),
+            items: pending
+                .into_iter()
+                .map(|c| (c.id.clone(), c.file_name.clone(), c.file_path.clone()))
+                .collect(),
+            done: HashSet::new(),
+        }
+    }
+
+    /// Record `capture_id` as annotated or skipped.
+    pub fn mark_done(&mut self, capture_id: &str) -> Result<(), String> {
+        if !self.items.iter().any(|(id, _, _)| id == capture_id) {
+            return Err(format!("Capture {} is not in the annotation queue", capture_id));
+        }
+        self.done.insert(capture_id.to_string());
+        Ok(())
+    }
+
+    pub fn progress(&self) -> AnnotationQueueProgress {
+        let items: Vec<AnnotationQueueItem> = self
+            .items
+            .iter()
+            .map(|(id, file_name, file_path)| AnnotationQueueItem {
+                capture_id: id.clone(),
+                file_name: file_name.clone(),
+                file_path: file_path.clone(),
+                done: self.done.contains(id),
+            })
+            .collect();
+        AnnotationQueueProgress {
+            session_id: self.session_id.clone(),
+            bug_id: self.bug_id.clone(),
+            done: self.done.len(),
+            total: items.len(),
+            next: items.iter().find(|item| !item.done).cloned(),
+            items,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(id: &str, created_at: &str, file_type: CaptureType, annotated: bool) -> Capture {
+        Capture {
+            id: id.to_string(),
+            bug_id: Some("b-1".to_string()),
+            session_id: "s-1".to_string(),
+            file_name: format!("{}.png", id),
+            file_path: format!("/qa/s-1/bug_001/{}.png", id),
+            file_type,
+            annotated_path: annotated.then(|| format!("/qa/s-1/bug_001/{}_annotated.png", id)),
+            created_at: created_at.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_queue_orders_unannotated_screenshots_and_tracks_progress() {
+        let captures = vec![
+            capture("c-3", "2024-01-01T10:03:00Z", CaptureType::Screenshot, false),
+            capture("c-1", "2024-01-01T10:01:00Z", CaptureType::Screenshot, false),
+            capture("c-2", "2024-01-01T10:02:00Z", CaptureType::Screenshot, true),
+            capture("c-4", "2024-01-01T10:04:00Z", CaptureType::Video, false),
+        ];
+        let mut queue = AnnotationQueue::new("s-1", "b-1", &captures);
+
+        let progress = queue.progress();
+        let ids: Vec<&str> = progress.items.iter().map(|i| i.capture_id.as_str()).collect();
+        assert_eq!(ids, vec!["c-1", "c-3"]);
+        assert_eq!(progress.next.unwrap().capture_id, "c-1");
+
+        queue.mark_done("c-1").unwrap();
+        queue.mark_done("c-1").unwrap();
+        let progress = queue.progress();
+        assert_eq!((progress.done, progress.total), (1, 2));
+        assert_eq!(progress.next.unwrap().capture_id, "c-3");
+
+        assert!(queue.mark_done("c-4").is_err());
+        queue.mark_done("c-3").unwrap();
+        assert_eq!(queue.progress().next, None);
+    }
+}
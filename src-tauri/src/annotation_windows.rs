@@ -0,0 +1,184 @@
+//! Bookkeeping for annotation windows and their temporary files.
+//!
+//! Each annotation window gets its own label. The registry remembers which
+//! session a window belongs to so that windows left open when the session ends
+//! can be closed. Annotated images are written to a temporary file and renamed
+//! into place; if a window is closed mid-save the temporary file is left
+//! behind, and the reaper removes such files once they are older than a day.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Suffix appended to annotated images while they are being written.
+pub const TEMP_SUFFIX: &str = ".annotating.tmp";
+
+/// Temporary annotation files older than this are considered abandoned.
+pub const STALE_TEMP_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// An open annotation window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotationWindow {
+    pub label: String,
+    pub image_path: String,
+    pub session_id: Option<String>,
+}
+
+/// Registry of open annotation windows, keyed by window label.
+#[derive(Default)]
+pub struct AnnotationWindowRegistry {
+    windows: HashMap<String, AnnotationWindow>,
+}
+
+impl AnnotationWindowRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, window: AnnotationWindow) {
+        self.windows.insert(window.label.clone(), window);
+    }
+
+    /// Forget a window (called when it is destroyed). Returns true if it was registered.
+    pub fn unregister(&mut self, label: &str) -> bool {
+        self.windows.remove(label).is_some()
+    }
+
+    /// Remove and return the labels of all windows belonging to `session_id`,
+    /// plus windows that were opened without a session (which are orphaned
+    /// once no session is active).
+    pub fn take_for_session(&mut self, session_id: &str) -> Vec<String> {
+        let labels: Vec<String> = self
+            .windows
+            .values()
+            .filter(|w| w.session_id.as_deref().is_none_or(|id| id == session_id))
+            .map(|w| w.label.clone())
+            .collect();
+        for label in &labels {
+            self.windows.remove(label);
+        }
+        labels
+    }
+}
+
+/// Path of the temporary file used while writing `save_path`.
+pub fn temp_path_for(save_path: &Path) -> PathBuf {
+    let mut name = save_path.as_os_str().to_os_string();
+    name.push(TEMP_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Write `bytes` to `save_path` via a temporary file so a forcibly closed
+/// window never leaves a truncated image in place.
+pub fn write_atomically(save_path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let temp_path = temp_path_for(save_path);
+    std::fs::write(&temp_path, bytes)?;
+    std::fs::rename(&temp_path, save_path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp_path);
+    })
+}
+
+/// Delete temporary annotation files under `root` that were last modified
+/// more than `max_age` before `now`. Returns the paths that were removed.
+pub fn reap_stale_temp_files(root: &Path, max_age: Duration, now: SystemTime) -> Vec<PathBuf> {
+    let mut removed = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                pending.push(path);
+                continue;
+            }
+
+            let is_temp = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with(TEMP_SUFFIX));
+            if !is_temp {
+                continue;
+            }
+
+            let age = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok());
+            if age.is_some_and(|age| age > max_age) && std::fs::remove_file(&path).is_ok() {
+                removed.push(path);
+            }
+        }
+    }
+
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(label: &str, session_id: Option<&str>) -> AnnotationWindow {
+        AnnotationWindow {
+            label: label.to_string(),
+            image_path: format!("/tmp/{}.png", label),
+            session_id: session_id.map(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_take_for_session_returns_owned_and_orphaned_windows() {
+        let mut registry = AnnotationWindowRegistry::new();
+        registry.register(window("annotation-a", Some("s-1")));
+        registry.register(window("annotation-b", Some("s-2")));
+        registry.register(window("annotation-c", None));
+
+        let mut labels = registry.take_for_session("s-1");
+        labels.sort();
+        assert_eq!(labels, vec!["annotation-a", "annotation-c"]);
+
+        // Windows from other sessions stay registered
+        assert!(!registry.unregister("annotation-a"));
+        assert!(registry.unregister("annotation-b"));
+        assert!(registry.take_for_session("s-2").is_empty());
+    }
+
+    #[test]
+    fn test_write_atomically_leaves_no_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("capture-001_annotated.png");
+
+        write_atomically(&target, b"png-bytes").unwrap();
+
+        assert_eq!(std::fs::read(&target).unwrap(), b"png-bytes");
+        assert!(!temp_path_for(&target).exists());
+    }
+
+    #[test]
+    fn test_reaper_removes_only_stale_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let bug_dir = dir.path().join("session").join("bug_001");
+        std::fs::create_dir_all(&bug_dir).unwrap();
+
+        let temp = temp_path_for(&bug_dir.join("capture-001_annotated.png"));
+        let image = bug_dir.join("capture-001.png");
+        std::fs::write(&temp, b"partial").unwrap();
+        std::fs::write(&image, b"png").unwrap();
+
+        // Nothing is stale yet
+        assert!(reap_stale_temp_files(dir.path(), STALE_TEMP_MAX_AGE, SystemTime::now()).is_empty());
+
+        // Two days later the temp file is reaped but the capture is kept
+        let later = SystemTime::now() + Duration::from_secs(2 * 24 * 60 * 60);
+        let removed = reap_stale_temp_files(dir.path(), STALE_TEMP_MAX_AGE, later);
+        assert_eq!(removed, vec![temp.clone()]);
+        assert!(!temp.exists());
+        assert!(image.exists());
+    }
+}
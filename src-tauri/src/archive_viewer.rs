@@ -0,0 +1,226 @@
+//! Read-only viewer for exported session archives (`.qacap`).
+//!
+//! A `.qacap` file is a session ZIP as written by
+//! [`crate::session_archive::write_archive`]. Opening one extracts it into a
+//! temporary workspace and reads the session's bugs and captures from
+//! `.session.json` and the bug folders — nothing is imported into the local
+//! database, and the workspace is deleted when the viewer closes or the app
+//! quits.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use zip::ZipArchive;
+
+use crate::bug_metadata;
+use crate::session_archive::{verify_archive, ArchiveVerification, ManifestLink};
+use crate::session_json::{BugJson, SessionJson};
+
+/// File extension of exported session archives.
+pub const ARCHIVE_EXTENSION: &str = "qacap";
+
+/// Session metadata file inside each archive.
+pub(crate) const SESSION_JSON_FILE: &str = ".session.json";
+
+/// Folder under the system temp dir holding viewer workspaces.
+const WORKSPACE_DIR: &str = "unbroken-qa-viewer";
+
+/// A bug as shown in the viewer, with absolute paths into the workspace.
+#[derive(Debug, Clone, Serialize)]
+pub struct ViewerBug {
+    #[serde(flatten)]
+    pub bug: BugJson,
+    pub folder_path: Option<String>,
+    pub capture_paths: Vec<String>,
+}
+
+/// Everything the frontend needs to render an archived session.
+#[derive(Debug, Clone, Serialize)]
+pub struct ViewerSession {
+    pub archive_path: String,
+    pub workspace_path: String,
+    pub read_only: bool,
+    pub session: SessionJson,
+    pub bugs: Vec<ViewerBug>,
+    /// Recordings that were linked rather than included in the archive.
+    pub links: Vec<ManifestLink>,
+    pub verification: ArchiveVerification,
+}
+
+/// An extracted archive. The workspace directory is removed on drop.
+pub struct ArchiveWorkspace {
+    pub session: ViewerSession,
+    _dir: tempfile::TempDir,
+}
+
+/// Locate a bug's folder in the workspace: the folder whose `metadata.json`
+/// names the bug, else the conventional `bug_NNN` folder.
+pub(crate) fn find_bug_folder(root: &Path, bug: &BugJson) -> Option<PathBuf> {
+    let dirs: Vec<PathBuf> = std::fs::read_dir(root)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+
+    let by_metadata = dirs.iter().find(|dir| {
+        bug_metadata::read_metadata_object(dir)
+            .get("bug_id")
+            .and_then(|v| v.as_str())
+            == Some(bug.id.as_str())
+    });
+    if let Some(dir) = by_metadata {
+        return Some(dir.clone());
+    }
+
+    let number = bug.display_id.rsplit('-').next()?.parse::<u32>().ok()?;
+    let conventional = root.join(format!("bug_{:03}", number));
+    conventional.is_dir().then_some(conventional)
+}
+
+/// Extract `archive_path` into a fresh directory under `workspace_parent`
+/// and load its session.
+pub fn open_workspace_in(archive_path: &Path, workspace_parent: &Path) -> Result<ArchiveWorkspace, String> {
+    let verification = verify_archive(archive_path)?;
+
+    let file = File::open(archive_path)
+        .map_err(|e| format!("Failed to open archive {:?}: {}", archive_path, e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Not a valid ZIP archive: {}", e))?;
+    let links = crate::session_archive::read_manifest(&mut archive)
+        .map(|m| m.links)
+        .unwrap_or_default();
+
+    let create_error = |e: std::io::Error| format!("Cannot create viewer workspace in {:?}: {}", workspace_parent, e);
+    std::fs::create_dir_all(workspace_parent).map_err(create_error)?;
+    let dir = tempfile::tempdir_in(workspace_parent).map_err(create_error)?;
+    let root = dir.path().to_path_buf();
+    // Build the workspace before any early return so a failed open cleans up
+    let mut workspace = ArchiveWorkspace {
+        _dir: dir,
+        session: ViewerSession {
+            archive_path: archive_path.to_string_lossy().to_string(),
+            workspace_path: root.to_string_lossy().to_string(),
+            read_only: true,
+            session: SessionJson {
+                id: String::new(),
+                started_at: String::new(),
+                ended_at: None,
+                timezone: None,
+                status: String::new(),
+                environment: None,
+                bugs: Vec::new(),
+            },
+            bugs: Vec::new(),
+            links,
+            verification,
+        },
+    };
+
+    // Entry names are sanitised by the zip crate; nothing escapes `root`
+    archive
+        .extract(&root)
+        .map_err(|e| format!("Failed to extract archive: {}", e))?;
+
+    let session_json = std::fs::read_to_string(root.join(SESSION_JSON_FILE))
+        .map_err(|_| format!("Archive has no {}; is it a session export?", SESSION_JSON_FILE))?;
+    let session: SessionJson = serde_json::from_str(&session_json)
+        .map_err(|e| format!("Invalid {}: {}", SESSION_JSON_FILE, e))?;
+
+    workspace.session.bugs = session
+        .bugs
+        .iter()
+        .map(|bug| {
+            let folder = find_bug_folder(&root, bug);
+            let capture_paths = folder
+                .as_ref()
+                .map(|dir| {
+                    bug.captures
+                        .iter()
+                        .map(|name| dir.join(name))
+                        .filter(|p| p.exists())
+                        .map(|p| p.to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            ViewerBug {
+                bug: bug.clone(),
+                folder_path: folder.map(|p| p.to_string_lossy().to_string()),
+                capture_paths,
+            }
+        })
+        .collect();
+    workspace.session.session = session;
+
+    Ok(workspace)
+}
+
+/// Extract `archive_path` into a temporary workspace and load its session.
+pub fn open_workspace(archive_path: &Path) -> Result<ArchiveWorkspace, String> {
+    open_workspace_in(archive_path, &std::env::temp_dir().join(WORKSPACE_DIR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media_offload::VideoExportMode;
+    use crate::session_archive::{collect_session_sources, write_archive};
+
+    fn export_session(root: &Path) -> PathBuf {
+        let session = root.join("session");
+        let bug = session.join("bug_001");
+        std::fs::create_dir_all(&bug).unwrap();
+        std::fs::write(bug.join("capture-001.png"), b"png").unwrap();
+        std::fs::write(
+            session.join(SESSION_JSON_FILE),
+            r#"{
+                "id": "s-1", "startedAt": "2024-01-01T10:00:00Z", "endedAt": null,
+                "status": "ended", "environment": null,
+                "bugs": [{"id": "b-1", "displayId": "BUG-001", "type": "bug", "title": "Crash",
+                          "description": null, "captures": ["capture-001.png"], "metadata": {}}]
+            }"#,
+        )
+        .unwrap();
+
+        let (sources, links) = collect_session_sources(&session, &[], VideoExportMode::IncludeAll, None);
+        let dest = root.join("session.qacap");
+        write_archive(&dest, "s-1", &sources, &[], links, &mut |_| {}).unwrap();
+        dest
+    }
+
+    #[test]
+    fn test_open_workspace_loads_bugs_and_cleans_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = export_session(dir.path());
+
+        let workspace = open_workspace_in(&archive, &dir.path().join("viewer")).unwrap();
+        let root = PathBuf::from(&workspace.session.workspace_path);
+        let session = &workspace.session;
+        assert!(session.read_only);
+        assert!(session.verification.valid);
+        assert_eq!(session.session.id, "s-1");
+        assert_eq!(session.bugs.len(), 1);
+        assert!(session.bugs[0].folder_path.as_deref().unwrap().ends_with("bug_001"));
+        assert_eq!(session.bugs[0].capture_paths.len(), 1);
+        assert!(Path::new(&session.bugs[0].capture_paths[0]).exists());
+
+        drop(workspace);
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn test_open_workspace_requires_session_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("loose");
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("notes.md"), b"notes").unwrap();
+        let (sources, _) = collect_session_sources(&folder, &[], VideoExportMode::IncludeAll, None);
+        let archive = dir.path().join("loose.qacap");
+        write_archive(&archive, "s-1", &sources, &[], vec![], &mut |_| {}).unwrap();
+
+        let viewer_root = dir.path().join("viewer");
+        let err = open_workspace_in(&archive, &viewer_root).err().unwrap();
+        assert!(err.contains(".session.json"));
+        assert_eq!(std::fs::read_dir(&viewer_root).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,156 @@
+//! Ending a bug capture that has gone quiet.
+//!
+//! Testers sometimes forget to end a bug capture, and every later screenshot
+//! then lands in the wrong bug. When enabled in the `capture.auto_stop`
+//! setting, a background check ends the active bug once it has had no
+//! activity for `idle_minutes`. Activity is read from the database: the
+//! bug's `updated_at` (notes, title, status changes) and the creation time of
+//! its newest capture. The last auto-stop is remembered so
+//! `undo_auto_stop` can resume the bug.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Settings key holding [`AutoStopSettings`] as JSON.
+pub const AUTO_STOP_KEY: &str = "capture.auto_stop";
+
+/// How often the active bug is checked.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Accepted range for `idle_minutes`.
+pub const MIN_IDLE_MINUTES: u32 = 1;
+pub const MAX_IDLE_MINUTES: u32 = 240;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutoStopSettings {
+    pub enabled: bool,
+    pub idle_minutes: u32,
+}
+
+impl Default for AutoStopSettings {
+    fn default() -> Self {
+        Self { enabled: false, idle_minutes: 15 }
+    }
+}
+
+impl AutoStopSettings {
+    /// Stored settings; missing or unreadable settings give the defaults.
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(AUTO_STOP_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(AUTO_STOP_KEY, &json)
+            .map_err(|e| format!("Failed to save auto-stop settings: {}", e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_IDLE_MINUTES..=MAX_IDLE_MINUTES).contains(&self.idle_minutes) {
+            return Err(format!(
+                "Idle time must be between {} and {} minutes",
+                MIN_IDLE_MINUTES, MAX_IDLE_MINUTES
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether a bug idle for `idle` should be ended.
+    pub fn should_stop(&self, idle: Duration) -> bool {
+        self.enabled && idle >= Duration::from_secs(self.idle_minutes as u64 * 60)
+    }
+}
+
+/// Time since the last activity on `bug_id`, or None if the bug does not exist.
+pub fn idle_time(conn: &Connection, bug_id: &str, now: DateTime<Utc>) -> Result<Option<Duration>, String> {
+    // julianday() reads both datetime('now') and RFC 3339 timestamps
+    let idle_days: Option<f64> = conn
+        .query_row(
+            "SELECT julianday(?2) - MAX(j) FROM (
+                 SELECT julianday(updated_at) AS j FROM bugs WHERE id = ?1
+                 UNION ALL
+                 SELECT julianday(created_at) FROM captures WHERE bug_id = ?1
+             )",
+            params![bug_id, now.to_rfc3339()],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to read bug activity: {}", e))?;
+    // Rounded to whole seconds; julian days carry floating-point noise
+    Ok(idle_days.map(|days| Duration::from_secs((days * 86_400.0).round().max(0.0) as u64)))
+}
+
+/// A bug ended by the auto-stop check, kept for `undo_auto_stop`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoStopped {
+    pub bug_id: String,
+    pub session_id: String,
+    pub display_id: String,
+    pub stopped_at: String,
+    pub idle_minutes: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/qa/s-1');
+             INSERT INTO bugs (id, session_id, bug_number, display_id, status, folder_path, updated_at)
+                 VALUES ('b-1', 's-1', 1, 'Bug-01', 'capturing', '/qa/s-1/bug_001', '2024-01-01 10:00:00');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_idle_time_uses_latest_activity() {
+        let conn = setup();
+        let now: DateTime<Utc> = "2024-01-01T10:30:00Z".parse().unwrap();
+
+        let idle = idle_time(&conn, "b-1", now).unwrap().unwrap();
+        assert_eq!(idle.as_secs() / 60, 30);
+
+        // A newer capture resets the clock
+        conn.execute_batch(
+            "INSERT INTO captures (id, bug_id, session_id, file_name, file_path, file_type, created_at)
+                 VALUES ('c-1', 'b-1', 's-1', 'shot.png', '/qa/s-1/bug_001/shot.png', 'screenshot', '2024-01-01T10:25:00+00:00');",
+        )
+        .unwrap();
+        let idle = idle_time(&conn, "b-1", now).unwrap().unwrap();
+        assert_eq!(idle.as_secs() / 60, 5);
+
+        assert_eq!(idle_time(&conn, "missing", now).unwrap(), None);
+    }
+
+    #[test]
+    fn test_settings() {
+        let conn = setup();
+        assert_eq!(AutoStopSettings::load(&conn), AutoStopSettings::default());
+        assert!(!AutoStopSettings::default().should_stop(Duration::from_secs(86_400)));
+
+        let settings = AutoStopSettings { enabled: true, idle_minutes: 10 };
+        settings.validate().unwrap();
+        settings.save(&conn).unwrap();
+        assert_eq!(AutoStopSettings::load(&conn), settings);
+        assert!(!settings.should_stop(Duration::from_secs(9 * 60)));
+        assert!(settings.should_stop(Duration::from_secs(10 * 60)));
+
+        assert!(AutoStopSettings { enabled: true, idle_minutes: 0 }.validate().is_err());
+    }
+}
@@ -0,0 +1,143 @@
+//! `metadata.json` in each bug folder.
+//!
+//! The file mirrors the template data for a bug so an exported folder can be
+//! rendered without the database. It is regenerated from the DB on demand;
+//! keys written by other features (e.g. `consoleExports`) are preserved.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::template::BugData;
+
+/// File in each bug folder describing its contents for DB-less consumers.
+pub const METADATA_FILE: &str = "metadata.json";
+
+/// Read the existing `metadata.json` as a JSON object (empty if missing or invalid).
+pub fn read_metadata_object(bug_folder: &Path) -> serde_json::Map<String, Value> {
+    std::fs::read_to_string(bug_folder.join(METADATA_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .and_then(|value| match value {
+            Value::Object(map) => Some(map),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Write a JSON object to the bug folder's `metadata.json`.
+pub fn write_metadata_object(bug_folder: &Path, object: serde_json::Map<String, Value>) -> Result<PathBuf, String> {
+    let path = bug_folder.join(METADATA_FILE);
+    let text = serde_json::to_string_pretty(&Value::Object(object))
+        .map_err(|e| format!("Failed to serialize {}: {}", METADATA_FILE, e))?;
+    std::fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", METADATA_FILE, e))?;
+    Ok(path)
+}
+
+/// Regenerate `metadata.json` for a bug from its template data.
+pub fn write_bug_metadata(
+    bug_folder: &Path,
+    bug_id: &str,
+    display_id: &str,
+    data: &BugData,
+) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(bug_folder)
+        .map_err(|e| format!("Cannot create bug folder {:?}: {}", bug_folder, e))?;
+
+    let mut object = read_metadata_object(bug_folder);
+    if let Value::Object(fields) =
+        serde_json::to_value(data).map_err(|e| format!("Failed to serialize bug data: {}", e))?
+    {
+        object.extend(fields);
+    }
+    object.insert("bug_id".to_string(), Value::String(bug_id.to_string()));
+    object.insert("display_id".to_string(), Value::String(display_id.to_string()));
+    object.insert(
+        "generated_at".to_string(),
+        Value::String(chrono::Utc::now().to_rfc3339()),
+    );
+
+    write_metadata_object(bug_folder, object)
+}
+
+/// Load template data from a bug folder's `metadata.json`.
+pub fn read_bug_metadata(bug_folder: &Path) -> Result<BugData, String> {
+    let path = bug_folder.join(METADATA_FILE);
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", METADATA_FILE, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::{BugMetadata, Environment};
+
+    fn sample(folder: &Path) -> BugData {
+        BugData {
+            title: "Login fails".to_string(),
+            bug_type: "bug".to_string(),
+            description_steps: "1. Log in".to_string(),
+            description_expected: String::new(),
+            description_actual: String::new(),
+            metadata: BugMetadata {
+                meeting_id: None,
+                software_version: Some("1.2.3".to_string()),
+                environment: Environment {
+                    os: "Windows 11".to_string(),
+                    display_resolution: "1920x1080".to_string(),
+                    dpi_scaling: "100%".to_string(),
+                    ram: "16GB".to_string(),
+                    cpu: "i7".to_string(),
+                    foreground_app: "App".to_string(),
+                    display_language: "en-US".to_string(),
+                    keyboard_layout: "00000409".to_string(),
+                },
+                console_captures: vec![],
+                custom_fields: Default::default(),
+            },
+            folder_path: folder.to_string_lossy().to_string(),
+            captures: vec!["capture-001.png".to_string()],
+            console_output: None,
+            created_at: String::new(),
+            design_details: vec![],
+        }
+    }
+
+    #[test]
+    fn test_write_and_read_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        write_bug_metadata(dir.path(), "b-1", "BUG-001", &sample(dir.path())).unwrap();
+
+        let data = read_bug_metadata(dir.path()).unwrap();
+        assert_eq!(data.title, "Login fails");
+        assert_eq!(data.metadata.software_version.as_deref(), Some("1.2.3"));
+
+        let object = read_metadata_object(dir.path());
+        assert_eq!(object["display_id"], "BUG-001");
+        assert!(object.contains_key("generated_at"));
+    }
+
+    #[test]
+    fn test_regeneration_preserves_other_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(METADATA_FILE),
+            r#"{"consoleExports": [{"file": "console-001.md"}], "title": "Stale"}"#,
+        )
+        .unwrap();
+
+        write_bug_metadata(dir.path(), "b-1", "BUG-001", &sample(dir.path())).unwrap();
+
+        let object = read_metadata_object(dir.path());
+        assert_eq!(object["title"], "Login fails");
+        assert_eq!(object["consoleExports"][0]["file"], "console-001.md");
+    }
+
+    #[test]
+    fn test_read_missing_metadata_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(read_bug_metadata(dir.path()).is_err());
+        assert!(read_metadata_object(dir.path()).is_empty());
+    }
+}
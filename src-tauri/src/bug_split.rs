@@ -0,0 +1,208 @@
+//! Splitting some captures and notes of a bug off into a new bug.
+//!
+//! `split_bug_from_captures` creates the new bug, moves the chosen captures
+//! into its folder and the chosen note lines into its notes. The captures
+//! left behind are renumbered so their file names stay sequential
+//! (`capture-001`, `capture-002`, ...) and new captures do not collide with
+//! an existing name.
+
+use std::path::{Path, PathBuf};
+
+use crate::capture_naming::NamingContext;
+use crate::database::Capture;
+use crate::storage_paths::annotated_path_for;
+
+/// Split `notes` into the lines to keep and the lines to move (zero-based
+/// `line_indices`), each joined with newlines.
+pub fn split_note_lines(notes: &str, line_indices: &[usize]) -> Result<(String, String), String> {
+    let lines: Vec<&str> = notes.lines().collect();
+    if let Some(bad) = line_indices.iter().find(|&&i| i >= lines.len()) {
+        return Err(format!("Note line {} does not exist (the notes have {} lines)", bad, lines.len()));
+    }
+    let (moved, kept): (Vec<_>, Vec<_>) = lines
+        .iter()
+        .enumerate()
+        .partition(|(i, _)| line_indices.contains(i));
+    let join = |lines: Vec<(usize, &&str)>| lines.into_iter().map(|(_, l)| *l).collect::<Vec<_>>().join("\n");
+    Ok((join(kept), join(moved)))
+}
+
+/// Rename every `(from, to)` pair. Files go through temporary names first,
+/// so renaming capture-003 to capture-002 never overwrites a file that has
+/// yet to move. On failure the files renamed so far are put back.
+pub fn rename_all(renames: &[(PathBuf, PathBuf)]) -> Result<(), String> {
+    let temp = |path: &Path| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(".renumbering");
+        PathBuf::from(name)
+    };
+
+    let (mut staged, mut placed) = (0, 0);
+    let mut result = Ok(());
+    for (from, _) in renames {
+        if let Err(e) = std::fs::rename(from, temp(from)) {
+            result = Err(format!("Failed to rename {:?}: {}", from, e));
+            break;
+        }
+        staged += 1;
+    }
+    if result.is_ok() {
+        for (from, to) in renames {
+            if let Err(e) = std::fs::rename(temp(from), to) {
+                result = Err(format!("Failed to rename {:?}: {}", to, e));
+                break;
+            }
+            placed += 1;
+        }
+    }
+
+    if result.is_err() {
+        for (from, to) in &renames[..placed] {
+            let _ = std::fs::rename(to, temp(from));
+        }
+        for (from, _) in &renames[..staged] {
+            if let Err(e) = std::fs::rename(temp(from), from) {
+                eprintln!("Warning: Failed to restore {:?}: {}", from, e);
+            }
+        }
+    }
+    result
+}
+
+/// Undo a successful [`rename_all`].
+pub fn undo_renames(renames: &[(PathBuf, PathBuf)]) -> Result<(), String> {
+    let reversed: Vec<_> = renames.iter().map(|(from, to)| (to.clone(), from.clone())).collect();
+    rename_all(&reversed)
+}
+
+/// Rename the files of `captures` (oldest first) in `bug_folder` to
+/// consecutive capture numbers, together with their annotated copies.
+/// Offloaded recordings keep their names but still take a number. Time
+/// tokens in the naming pattern use each capture's creation time. Updates
+/// the captures in place; the caller persists them.
+///
+/// Returns the `(old, new)` path of every renamed file, for
+/// [`undo_renames`]. Nothing is renamed when it fails.
+pub fn renumber_captures(
+    captures: &mut [Capture],
+    bug_folder: &Path,
+    naming: &NamingContext,
+) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    captures.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    let mut renames = Vec::new();
+    let mut updates = Vec::new();
+    for (index, capture) in captures.iter().enumerate() {
+        let old_path = PathBuf::from(&capture.file_path);
+        if capture.media_link.is_some() || old_path.parent() != Some(bug_folder) || !old_path.exists() {
+            continue;
+        }
+        let naming = naming.clone().with_created_at(&capture.created_at);
+        let (new_name, _) = crate::make_capture_filename(&old_path, index as u32 + 1, &naming);
+        let new_path = bug_folder.join(&new_name);
+        if new_path == old_path {
+            continue;
+        }
+        let annotated = capture
+            .annotated_path
+            .as_deref()
+            .map(PathBuf::from)
+            .filter(|p| p.parent() == Some(bug_folder) && p.exists())
+            .map(|p| (p, annotated_path_for(&new_path)));
+        renames.push((old_path, new_path.clone()));
+        if let Some((old_annotated, new_annotated)) = &annotated {
+            renames.push((old_annotated.clone(), new_annotated.clone()));
+        }
+        updates.push((index, new_path, annotated.map(|(_, new_annotated)| new_annotated)));
+    }
+
+    rename_all(&renames)?;
+    for (index, new_path, new_annotated) in updates {
+        let capture = &mut captures[index];
+        capture.file_name = new_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        capture.file_path = new_path.to_string_lossy().to_string();
+        if let Some(new_annotated) = new_annotated {
+            capture.annotated_path = Some(new_annotated.to_string_lossy().to_string());
+        }
+    }
+    Ok(renames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::CaptureType;
+
+    fn capture(id: &str, path: &Path, created_at: &str) -> Capture {
+        Capture {
+            id: id.to_string(),
+            bug_id: Some("b-1".to_string()),
+            session_id: "s-1".to_string(),
+            file_name: path.file_name().unwrap().to_string_lossy().to_string(),
+            file_path: path.to_string_lossy().to_string(),
+            file_type: CaptureType::Screenshot,
+            created_at: created_at.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_split_note_lines() {
+        let notes = "Login fails\nSteps: open app\nAlso: avatar is blurry";
+        let (kept, moved) = split_note_lines(notes, &[2]).unwrap();
+        assert_eq!(kept, "Login fails\nSteps: open app");
+        assert_eq!(moved, "Also: avatar is blurry");
+        assert!(split_note_lines(notes, &[3]).is_err());
+    }
+
+    #[test]
+    fn test_renumber_closes_gaps() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path();
+        // capture-001 and capture-003 moved out, leaving gaps
+        for name in ["capture-002.png", "capture-002_annotated.png", "capture-004.png"] {
+            std::fs::write(folder.join(name), name).unwrap();
+        }
+        let mut second = capture("c-2", &folder.join("capture-002.png"), "2024-01-01T10:00:02Z");
+        second.annotated_path = Some(folder.join("capture-002_annotated.png").to_string_lossy().to_string());
+        let mut captures = vec![
+            capture("c-4", &folder.join("capture-004.png"), "2024-01-01T10:00:04Z"),
+            second,
+        ];
+
+        let renames = renumber_captures(&mut captures, folder, &NamingContext::default()).unwrap();
+
+        assert_eq!(captures[0].id, "c-2");
+        assert_eq!(captures[0].file_name, "capture-001.png");
+        assert_eq!(std::fs::read_to_string(folder.join("capture-001.png")).unwrap(), "capture-002.png");
+        assert_eq!(
+            std::fs::read_to_string(folder.join("capture-001_annotated.png")).unwrap(),
+            "capture-002_annotated.png"
+        );
+        assert_eq!(captures[1].file_name, "capture-002.png");
+        assert_eq!(std::fs::read_to_string(folder.join("capture-002.png")).unwrap(), "capture-004.png");
+        assert!(!folder.join("capture-004.png").exists());
+
+        // Undoing restores the original names
+        undo_renames(&renames).unwrap();
+        for name in ["capture-002.png", "capture-002_annotated.png", "capture-004.png"] {
+            assert_eq!(std::fs::read_to_string(folder.join(name)).unwrap(), name);
+        }
+        assert!(!folder.join("capture-001.png").exists());
+    }
+
+    #[test]
+    fn test_failed_rename_puts_files_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path();
+        std::fs::write(folder.join("capture-002.png"), "2").unwrap();
+        let renames = vec![
+            (folder.join("capture-002.png"), folder.join("capture-001.png")),
+            (folder.join("capture-003.png"), folder.join("capture-002.png")),
+        ];
+
+        assert!(rename_all(&renames).is_err());
+        assert_eq!(std::fs::read_to_string(folder.join("capture-002.png")).unwrap(), "2");
+        assert_eq!(std::fs::read_dir(folder).unwrap().count(), 1);
+    }
+}
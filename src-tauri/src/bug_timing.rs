@@ -0,0 +1,135 @@
+//! Time spent capturing each bug, for session retros.
+//!
+//! Starting or resuming capture opens a period under `capture_periods` in the
+//! bug's `metadata_json`; ending capture closes it. A bug's time spent is the
+//! sum of its periods, with a period still open counted up to the end of the
+//! session. Summaries show it as `{bug.timeSpent}` and the CSV export as the
+//! "Time Spent (min)" column, so leads can see which areas took the session.
+//! Bugs captured before periods were recorded have no time spent.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::database::Bug;
+
+/// Key of the periods in a bug's `metadata_json`.
+pub const CAPTURE_PERIODS_KEY: &str = "capture_periods";
+
+/// One stretch of capturing, from start or resume to end.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturePeriod {
+    pub started_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+fn metadata(bug: &Bug) -> Map<String, Value> {
+    bug.metadata_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<Map<String, Value>>(json).ok())
+        .unwrap_or_default()
+}
+
+/// The periods recorded on `bug`, oldest first.
+pub fn periods(bug: &Bug) -> Vec<CapturePeriod> {
+    metadata(bug)
+        .remove(CAPTURE_PERIODS_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn set_periods(bug: &mut Bug, periods: &[CapturePeriod]) {
+    bug.set_metadata(CAPTURE_PERIODS_KEY, serde_json::to_value(periods).unwrap_or(Value::Null));
+}
+
+/// Open a period at `at`, unless one is open already. The caller saves the bug.
+pub fn start_period(bug: &mut Bug, at: DateTime<Utc>) {
+    let mut periods = periods(bug);
+    if periods.last().is_some_and(|p| p.ended_at.is_none()) {
+        return;
+    }
+    periods.push(CapturePeriod { started_at: at, ended_at: None });
+    set_periods(bug, &periods);
+}
+
+/// Close the open period at `at`, if any. The caller saves the bug.
+pub fn end_period(bug: &mut Bug, at: DateTime<Utc>) {
+    let mut periods = periods(bug);
+    let Some(open) = periods.last_mut().filter(|p| p.ended_at.is_none()) else {
+        return;
+    };
+    open.ended_at = Some(at.max(open.started_at));
+    set_periods(bug, &periods);
+}
+
+/// Total capture time of `bug`, counting an open period up to `until`. None
+/// when no periods were recorded.
+pub fn time_spent(bug: &Bug, until: DateTime<Utc>) -> Option<Duration> {
+    let periods = periods(bug);
+    if periods.is_empty() {
+        return None;
+    }
+    Some(periods.iter().fold(Duration::zero(), |total, period| {
+        let end = period.ended_at.unwrap_or(until);
+        total + (end - period.started_at).max(Duration::zero())
+    }))
+}
+
+/// e.g. `1h 05m`, or `12m` under an hour.
+pub fn format_time_spent(duration: Duration) -> String {
+    let minutes = (duration.num_seconds() + 30) / 60;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{BugStatus, BugType};
+
+    fn bug() -> Bug {
+        Bug {
+            id: "b-1".to_string(),
+            session_id: "s-1".to_string(),
+            bug_number: 1,
+            display_id: "BUG-001".to_string(),
+            bug_type: BugType::Bug,
+            status: BugStatus::Capturing,
+            metadata_json: Some(r#"{"foreground":{"title":"Checkout","processName":"Shop.exe"}}"#.to_string()),
+            folder_path: "/qa/s-1/bug_001".to_string(),
+            created_at: "2024-01-15T10:00:00Z".to_string(),
+            updated_at: "2024-01-15T10:00:00Z".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(&format!("2024-01-15T{}Z", time)).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_periods_sum_across_resumes() {
+        let mut bug = bug();
+        assert_eq!(time_spent(&bug, at("12:00:00")), None);
+
+        start_period(&mut bug, at("10:00:00"));
+        start_period(&mut bug, at("10:05:00"));
+        end_period(&mut bug, at("10:20:00"));
+        end_period(&mut bug, at("10:30:00"));
+        start_period(&mut bug, at("11:00:00"));
+
+        assert_eq!(periods(&bug).len(), 2);
+        // Still capturing: counted up to the end of the session
+        let spent = time_spent(&bug, at("11:45:30")).unwrap();
+        assert_eq!(spent, Duration::minutes(65) + Duration::seconds(30));
+        assert_eq!(format_time_spent(spent), "1h 06m");
+        assert_eq!(format_time_spent(Duration::seconds(20)), "0m");
+        // Other metadata is kept
+        assert!(bug.metadata_json.unwrap().contains("Shop.exe"));
+    }
+}
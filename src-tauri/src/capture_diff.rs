@@ -0,0 +1,295 @@
+//! Changed regions between consecutive screenshots of a bug, highlighted.
+//!
+//! A before/after pair of the same window is hard to compare by eye. When the
+//! `capture.diff_annotation` setting is enabled, every screenshot routed to a
+//! bug is compared with the bug's previous screenshot, and the regions that
+//! changed are boxed in an annotated copy (`shot.png` -> `shot_annotated.png`)
+//! stored as the capture's `annotated_path`.
+//!
+//! Captures carry no window identity, so two screenshots count as the same
+//! window when they have the same pixel size. Pairs where most of the image
+//! changed are a different screen rather than a before/after and get no copy.
+//! A capture that already has an annotated copy is never overwritten.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use image::RgbaImage;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Capture, CaptureOps, CaptureRepository, CaptureType, SettingsOps, SettingsRepository};
+use crate::storage_paths::annotated_path_for;
+use crate::window_capture::{draw_highlight, HIGHLIGHT_COLOR, HIGHLIGHT_THICKNESS};
+
+/// Settings key holding [`DiffAnnotationSettings`] as JSON.
+pub const DIFF_ANNOTATION_KEY: &str = "capture.diff_annotation";
+
+/// Side of the square cells pixels are compared in.
+const CELL: u32 = 8;
+
+/// Space between a changed region and its box.
+const PADDING: u32 = 6;
+
+/// Above this share of changed cells the pair is not a before/after.
+const MAX_CHANGED_FRACTION: f64 = 0.6;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiffAnnotationSettings {
+    /// Annotate new screenshots with what changed since the previous one
+    pub enabled: bool,
+    /// Largest per-channel difference still treated as unchanged, so
+    /// compression noise and anti-aliasing do not light up
+    pub tolerance: u8,
+}
+
+impl Default for DiffAnnotationSettings {
+    fn default() -> Self {
+        Self { enabled: false, tolerance: 24 }
+    }
+}
+
+impl DiffAnnotationSettings {
+    /// Stored settings; missing or unreadable settings give the defaults.
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(DIFF_ANNOTATION_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(DIFF_ANNOTATION_KEY, &json)
+            .map_err(|e| format!("Failed to save diff annotation settings: {}", e))
+    }
+}
+
+/// Whether any pixel of the cell at (`cx`, `cy`) differs by more than `tolerance`.
+fn cell_changed(before: &RgbaImage, after: &RgbaImage, cx: u32, cy: u32, tolerance: u8) -> bool {
+    let (width, height) = after.dimensions();
+    (cy * CELL..((cy + 1) * CELL).min(height)).any(|y| {
+        (cx * CELL..((cx + 1) * CELL).min(width)).any(|x| {
+            let (a, b) = (before.get_pixel(x, y), after.get_pixel(x, y));
+            (0..4).any(|c| a[c].abs_diff(b[c]) > tolerance)
+        })
+    })
+}
+
+fn overlaps(a: &[u32; 4], b: &[u32; 4]) -> bool {
+    a[0] <= b[2] && b[0] <= a[2] && a[1] <= b[3] && b[1] <= a[3]
+}
+
+/// Boxes (`[left, top, right, bottom]`, inclusive, padded) around the regions
+/// that differ between two images of the same size. Touching or overlapping
+/// boxes are merged. None when the images differ in size or most of the
+/// image changed.
+pub fn diff_regions(before: &RgbaImage, after: &RgbaImage, tolerance: u8) -> Option<Vec<[u32; 4]>> {
+    if before.dimensions() != after.dimensions() || after.width() == 0 || after.height() == 0 {
+        return None;
+    }
+    let (width, height) = after.dimensions();
+    let (cols, rows) = (width.div_ceil(CELL), height.div_ceil(CELL));
+    let changed: Vec<bool> = (0..rows)
+        .flat_map(|cy| (0..cols).map(move |cx| (cx, cy)))
+        .map(|(cx, cy)| cell_changed(before, after, cx, cy, tolerance))
+        .collect();
+    if changed.iter().filter(|c| **c).count() as f64 > changed.len() as f64 * MAX_CHANGED_FRACTION {
+        return None;
+    }
+
+    // Bounding box of each group of neighbouring changed cells
+    let mut seen = vec![false; changed.len()];
+    let mut boxes: Vec<[u32; 4]> = Vec::new();
+    for start in 0..changed.len() {
+        if !changed[start] || seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut stack = vec![start];
+        let mut cells = [u32::MAX, u32::MAX, 0, 0];
+        while let Some(index) = stack.pop() {
+            let (cx, cy) = (index as u32 % cols, index as u32 / cols);
+            cells = [cells[0].min(cx), cells[1].min(cy), cells[2].max(cx), cells[3].max(cy)];
+            for ny in cy.saturating_sub(1)..=(cy + 1).min(rows - 1) {
+                for nx in cx.saturating_sub(1)..=(cx + 1).min(cols - 1) {
+                    let neighbour = (ny * cols + nx) as usize;
+                    if changed[neighbour] && !seen[neighbour] {
+                        seen[neighbour] = true;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+        boxes.push([
+            (cells[0] * CELL).saturating_sub(PADDING),
+            (cells[1] * CELL).saturating_sub(PADDING),
+            ((cells[2] + 1) * CELL - 1 + PADDING).min(width - 1),
+            ((cells[3] + 1) * CELL - 1 + PADDING).min(height - 1),
+        ]);
+    }
+
+    // Padding can make boxes overlap; one box reads better than two crossing
+    let mut merged = true;
+    while merged {
+        merged = false;
+        'outer: for i in 0..boxes.len() {
+            for j in i + 1..boxes.len() {
+                if overlaps(&boxes[i], &boxes[j]) {
+                    let other = boxes.remove(j);
+                    let b = &mut boxes[i];
+                    *b = [b[0].min(other[0]), b[1].min(other[1]), b[2].max(other[2]), b[3].max(other[3])];
+                    merged = true;
+                    break 'outer;
+                }
+            }
+        }
+    }
+    boxes.sort_by_key(|b| (b[1], b[0]));
+    Some(boxes)
+}
+
+/// The screenshot of the same bug taken before `capture`, if any.
+pub fn previous_screenshot(conn: &Connection, capture: &Capture) -> Option<Capture> {
+    let bug_id = capture.bug_id.as_deref()?;
+    CaptureRepository::new(conn)
+        .list_by_bug(bug_id)
+        .ok()?
+        .into_iter()
+        .rfind(|c| c.id != capture.id && c.file_type == CaptureType::Screenshot && c.created_at <= capture.created_at)
+}
+
+fn load_rgba(path: &Path) -> Result<RgbaImage, String> {
+    image::open(path)
+        .map(|image| image.to_rgba8())
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))
+}
+
+/// Write the annotated copy of `after` with the changes since `before` boxed.
+/// Returns its path, or None when the pair is not a before/after or nothing
+/// changed.
+pub fn annotate_changes(before: &Path, after: &Path, tolerance: u8) -> Result<Option<PathBuf>, String> {
+    let previous = load_rgba(before)?;
+    let mut image = load_rgba(after)?;
+    let regions = match diff_regions(&previous, &image, tolerance) {
+        Some(regions) if !regions.is_empty() => regions,
+        _ => return Ok(None),
+    };
+    for rect in regions {
+        draw_highlight(&mut image, rect, HIGHLIGHT_COLOR, HIGHLIGHT_THICKNESS);
+    }
+    let path = annotated_path_for(after);
+    image
+        .save_with_format(&path, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(Some(path))
+}
+
+/// Annotate a newly routed `capture` against the bug's previous screenshot, if
+/// enabled, and store the copy as its `annotated_path`. The images are
+/// compared without holding the connection.
+pub fn annotate_new_capture(conn: &Mutex<Connection>, capture: &Capture) -> Result<Option<PathBuf>, String> {
+    if capture.file_type != CaptureType::Screenshot || capture.annotated_path.is_some() {
+        return Ok(None);
+    }
+    let (settings, previous) = {
+        let conn = conn.lock().unwrap();
+        (DiffAnnotationSettings::load(&conn), previous_screenshot(&conn, capture))
+    };
+    let Some(previous) = previous.filter(|_| settings.enabled) else {
+        return Ok(None);
+    };
+
+    let Some(path) = annotate_changes(Path::new(&previous.file_path), Path::new(&capture.file_path), settings.tolerance)?
+    else {
+        return Ok(None);
+    };
+
+    let conn = conn.lock().unwrap();
+    let repo = CaptureRepository::new(&conn);
+    if let Some(mut stored) = repo.get(&capture.id).map_err(|e| e.to_string())? {
+        // The user may have annotated it by hand in the meantime
+        if stored.annotated_path.is_none() {
+            stored.annotated_path = Some(path.to_string_lossy().to_string());
+            repo.update(&stored).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const GREY: Rgba<u8> = Rgba([200, 200, 200, 255]);
+
+    fn fill(image: &mut RgbaImage, rect: [u32; 4], color: Rgba<u8>) {
+        for y in rect[1]..=rect[3] {
+            for x in rect[0]..=rect[2] {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+
+    #[test]
+    fn test_separate_changes_get_separate_boxes() {
+        let before = RgbaImage::from_pixel(200, 120, GREY);
+        let mut after = before.clone();
+        fill(&mut after, [20, 20, 29, 25], Rgba([0, 0, 0, 255]));
+        fill(&mut after, [150, 90, 170, 100], Rgba([255, 0, 0, 255]));
+
+        let regions = diff_regions(&before, &after, 24).unwrap();
+        assert_eq!(regions.len(), 2);
+        // Cells 16..32 on both axes, padded
+        assert_eq!(regions[0], [10, 10, 37, 37]);
+        let [left, top, right, bottom] = regions[1];
+        assert!(left <= 150 && top <= 90 && right >= 170 && bottom >= 100);
+    }
+
+    #[test]
+    fn test_nearby_changes_merge_and_noise_is_ignored() {
+        let before = RgbaImage::from_pixel(100, 100, GREY);
+        let mut after = before.clone();
+        // Slight colour shift everywhere, within tolerance
+        fill(&mut after, [0, 0, 99, 99], Rgba([210, 195, 200, 255]));
+        fill(&mut after, [10, 10, 12, 12], Rgba([0, 0, 0, 255]));
+        fill(&mut after, [30, 10, 32, 12], Rgba([0, 0, 0, 255]));
+
+        let regions = diff_regions(&before, &after, 24).unwrap();
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0], [2, 2, 45, 21]);
+    }
+
+    #[test]
+    fn test_unrelated_images_get_no_regions() {
+        let before = RgbaImage::from_pixel(64, 64, GREY);
+        assert_eq!(diff_regions(&before, &RgbaImage::from_pixel(64, 32, GREY), 24), None);
+        assert_eq!(diff_regions(&before, &RgbaImage::from_pixel(64, 64, Rgba([0, 0, 0, 255])), 24), None);
+        assert_eq!(diff_regions(&before, &before.clone(), 24), Some(vec![]));
+    }
+
+    #[test]
+    fn test_annotate_changes_writes_alongside_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let (before_path, after_path) = (dir.path().join("before.png"), dir.path().join("after.png"));
+        let before = RgbaImage::from_pixel(80, 80, GREY);
+        let mut after = before.clone();
+        fill(&mut after, [40, 40, 47, 47], Rgba([0, 0, 255, 255]));
+        before.save(&before_path).unwrap();
+        after.save(&after_path).unwrap();
+
+        let path = annotate_changes(&before_path, &after_path, 24).unwrap().unwrap();
+        assert_eq!(path, dir.path().join("after_annotated.png"));
+        let annotated = image::open(&path).unwrap().to_rgba8();
+        assert_eq!(*annotated.get_pixel(34, 34), HIGHLIGHT_COLOR);
+        assert_eq!(*annotated.get_pixel(5, 5), GREY);
+
+        // Nothing changed: no copy
+        assert_eq!(annotate_changes(&before_path, &before_path, 24).unwrap(), None);
+    }
+}
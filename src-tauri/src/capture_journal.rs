@@ -0,0 +1,219 @@
+//! On-disk journal of capture records that could not be written.
+//!
+//! When the capture watcher has moved a file into a bug folder but the
+//! `captures` insert fails (typically `SQLITE_BUSY` while another process
+//! holds the database), the record is appended to `.capture_journal.jsonl`
+//! in the session folder instead of being dropped. The watcher retries the
+//! journal every [`RETRY_INTERVAL`], and once on start so records survive a
+//! crash. Records that fail with a non-transient error, or still fail after
+//! [`MAX_ATTEMPTS`], are given up on and reported as lost.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use rusqlite::{Connection, ErrorCode};
+use serde::{Deserialize, Serialize};
+
+use crate::annotation_windows::write_atomically;
+use crate::database::{Capture, CaptureOps, CaptureRepository};
+
+/// File name of the journal in the session folder.
+pub const JOURNAL_FILE_NAME: &str = ".capture_journal.jsonl";
+
+pub const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Attempts (including the first insert) before a record is given up on.
+pub const MAX_ATTEMPTS: u32 = 60;
+
+/// One journaled record.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub capture: Capture,
+    pub attempts: u32,
+    pub last_error: String,
+}
+
+/// A record that will not be retried again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LostCapture {
+    pub capture: Capture,
+    pub error: String,
+}
+
+/// Result of one pass over the journal.
+#[derive(Debug, Default)]
+pub struct RetryOutcome {
+    pub recovered: Vec<Capture>,
+    pub pending: usize,
+    pub lost: Vec<LostCapture>,
+}
+
+pub fn journal_path(session_folder: &Path) -> PathBuf {
+    session_folder.join(JOURNAL_FILE_NAME)
+}
+
+/// Whether an insert may succeed when tried again later.
+pub fn is_transient(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Append a record whose first insert failed with `error`.
+pub fn append(path: &Path, capture: &Capture, error: &str) -> Result<(), String> {
+    let entry = JournalEntry {
+        capture: capture.clone(),
+        attempts: 1,
+        last_error: error.to_string(),
+    };
+    let line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open capture journal {}: {}", path.display(), e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write capture journal {}: {}", path.display(), e))
+}
+
+fn read(path: &Path) -> Result<Vec<JournalEntry>, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read capture journal {}: {}", path.display(), e)),
+    };
+    Ok(text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                eprintln!("Warning: skipping unreadable capture journal line: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Try to insert every journaled record once. Written records and records
+/// given up on leave the journal; the file is removed once it is empty.
+pub fn retry(conn: &Connection, path: &Path) -> Result<RetryOutcome, String> {
+    let entries = read(path)?;
+    if entries.is_empty() {
+        return Ok(RetryOutcome::default());
+    }
+
+    let repo = CaptureRepository::new(conn);
+    let mut outcome = RetryOutcome::default();
+    let mut remaining = Vec::new();
+    for mut entry in entries {
+        // A previous pass may have inserted it before failing to rewrite the journal
+        if matches!(repo.get(&entry.capture.id), Ok(Some(_))) {
+            outcome.recovered.push(entry.capture);
+            continue;
+        }
+        match repo.create(&entry.capture) {
+            Ok(()) => outcome.recovered.push(entry.capture),
+            Err(e) => {
+                entry.attempts += 1;
+                entry.last_error = e.to_string();
+                if is_transient(&e) && entry.attempts < MAX_ATTEMPTS {
+                    remaining.push(entry);
+                } else {
+                    outcome.lost.push(LostCapture { capture: entry.capture, error: entry.last_error });
+                }
+            }
+        }
+    }
+
+    outcome.pending = remaining.len();
+    if remaining.is_empty() {
+        std::fs::remove_file(path).map_err(|e| format!("Failed to remove capture journal: {}", e))?;
+    } else {
+        let mut text = String::new();
+        for entry in &remaining {
+            text.push_str(&serde_json::to_string(entry).map_err(|e| e.to_string())?);
+            text.push('\n');
+        }
+        write_atomically(path, text.as_bytes())
+            .map_err(|e| format!("Failed to rewrite capture journal: {}", e))?;
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::CaptureType;
+
+    fn capture(id: &str, session_id: &str) -> Capture {
+        Capture {
+            id: id.to_string(),
+            session_id: session_id.to_string(),
+            file_name: format!("{}.png", id),
+            file_path: format!("/qa/s-1/_unsorted/{}.png", id),
+            file_type: CaptureType::Screenshot,
+            file_size_bytes: Some(10),
+            created_at: "2024-01-01T10:00:00Z".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_retry_recovers_and_drops_unrecoverable_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = journal_path(dir.path());
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/qa/s-1')",
+            [],
+        )
+        .unwrap();
+
+        append(&path, &capture("c-1", "s-1"), "database is locked").unwrap();
+        // The session was deleted meanwhile: the foreign key will never hold
+        append(&path, &capture("c-2", "s-gone"), "database is locked").unwrap();
+
+        let outcome = retry(&conn, &path).unwrap();
+        assert_eq!(outcome.recovered.len(), 1);
+        assert_eq!(outcome.recovered[0].id, "c-1");
+        assert_eq!(outcome.lost.len(), 1);
+        assert_eq!(outcome.lost[0].capture.id, "c-2");
+        assert_eq!(outcome.pending, 0);
+        assert!(!path.exists());
+        assert!(CaptureRepository::new(&conn).get("c-1").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_busy_database_keeps_record_pending() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("qa.db");
+        let path = journal_path(dir.path());
+
+        let conn = Connection::open(&db_path).unwrap();
+        conn.busy_timeout(Duration::ZERO).unwrap();
+        crate::database::init_database(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/qa/s-1')",
+            [],
+        )
+        .unwrap();
+        append(&path, &capture("c-1", "s-1"), "database is locked").unwrap();
+
+        // Another connection holds a write lock
+        let other = Connection::open(&db_path).unwrap();
+        other.execute_batch("BEGIN IMMEDIATE").unwrap();
+        let outcome = retry(&conn, &path).unwrap();
+        assert_eq!(outcome.pending, 1);
+        assert!(outcome.recovered.is_empty() && outcome.lost.is_empty());
+        assert_eq!(read(&path).unwrap()[0].attempts, 2);
+
+        other.execute_batch("ROLLBACK").unwrap();
+        let outcome = retry(&conn, &path).unwrap();
+        assert_eq!(outcome.recovered.len(), 1);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,315 @@
+//! File name pattern for captures.
+//!
+//! Captures are named `capture-001.png` / `recording-001.mp4` by default.
+//! Teams that need other conventions (e.g. `143025_capture-001.png` for
+//! traceability against logs) set a pattern in the `capture.filename_pattern`
+//! setting. The pattern names the file without its extension, which is kept
+//! from the source file. Tokens:
+//!
+//! - `{kind}`: `capture` for images, `recording` for videos
+//! - `{seq}`: sequence number in the folder, at least three digits
+//! - `{time}`: local time the capture was taken, `HHMMSS`
+//! - `{date}`: local date, `YYYYMMDD`
+//! - `{bug}`: display ID of the bug (`BUG-004`), `unsorted` outside a bug
+//!
+//! A pattern must contain `{seq}` so names stay unique, and may only hold
+//! characters that are valid in file names on every platform. An invalid
+//! stored pattern falls back to the default.
+
+use std::path::Path;
+
+use chrono::{Local, NaiveDateTime};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Settings key holding the capture file name pattern.
+pub const FILENAME_PATTERN_KEY: &str = "capture.filename_pattern";
+
+pub const DEFAULT_PATTERN: &str = "{kind}-{seq}";
+
+/// Longest accepted pattern.
+pub const MAX_PATTERN_LEN: usize = 100;
+
+/// Characters rejected in file names on Windows (and `/` everywhere).
+const FORBIDDEN_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Kind,
+    Seq,
+    Time,
+    Date,
+    Bug,
+}
+
+impl Token {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "kind" => Some(Token::Kind),
+            "seq" => Some(Token::Seq),
+            "time" => Some(Token::Time),
+            "date" => Some(Token::Date),
+            "bug" => Some(Token::Bug),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Token(Token),
+}
+
+fn parse_pattern(pattern: &str) -> Result<Vec<Part>, String> {
+    let mut parts = Vec::new();
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        match rest.find(['{', '}']) {
+            Some(0) if rest.starts_with('{') => {
+                let end = rest.find('}').ok_or_else(|| format!("Unclosed '{{' in pattern '{}'", pattern))?;
+                let name = &rest[1..end];
+                let token = Token::parse(name).ok_or_else(|| format!("Unknown token '{{{}}}' in file name pattern", name))?;
+                parts.push(Part::Token(token));
+                rest = &rest[end + 1..];
+            }
+            Some(0) => return Err(format!("Unmatched '}}' in pattern '{}'", pattern)),
+            Some(i) => {
+                parts.push(Part::Literal(rest[..i].to_string()));
+                rest = &rest[i..];
+            }
+            None => {
+                parts.push(Part::Literal(rest.to_string()));
+                rest = "";
+            }
+        }
+    }
+    Ok(parts)
+}
+
+/// Check a pattern for tokens and file system safety.
+pub fn validate(pattern: &str) -> Result<(), String> {
+    if pattern.trim().is_empty() {
+        return Err("The file name pattern is empty".to_string());
+    }
+    if pattern.chars().count() > MAX_PATTERN_LEN {
+        return Err(format!("The file name pattern is longer than {} characters", MAX_PATTERN_LEN));
+    }
+    let parts = parse_pattern(pattern)?;
+    if !parts.contains(&Part::Token(Token::Seq)) {
+        return Err("The file name pattern must contain {seq} so names stay unique".to_string());
+    }
+    for part in &parts {
+        if let Part::Literal(text) = part {
+            if let Some(c) = text.chars().find(|c| FORBIDDEN_CHARS.contains(c) || c.is_control()) {
+                return Err(format!("'{}' is not allowed in file names", c.escape_default()));
+            }
+        }
+    }
+    if pattern.starts_with('.') || pattern.starts_with(' ') || pattern.ends_with(' ') {
+        return Err("The file name pattern cannot start with a dot or start or end with a space".to_string());
+    }
+    Ok(())
+}
+
+/// Display IDs come from profiles; keep them to characters safe in names.
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| if FORBIDDEN_CHARS.contains(&c) || c.is_control() || c == ' ' { '_' } else { c })
+        .collect()
+}
+
+/// Everything needed to name a capture in one folder.
+#[derive(Debug, Clone)]
+pub struct NamingContext {
+    parts: Vec<Part>,
+    /// Display ID of the bug owning the folder
+    pub bug_display_id: Option<String>,
+    /// Local time used for `{time}` and `{date}`
+    pub time: NaiveDateTime,
+}
+
+impl Default for NamingContext {
+    /// The default pattern, outside any bug, at the current time.
+    fn default() -> Self {
+        Self::new(DEFAULT_PATTERN, None).expect("default pattern is valid")
+    }
+}
+
+impl NamingContext {
+    pub fn new(pattern: &str, bug_display_id: Option<String>) -> Result<Self, String> {
+        validate(pattern)?;
+        Ok(Self {
+            parts: parse_pattern(pattern)?,
+            bug_display_id,
+            time: Local::now().naive_local(),
+        })
+    }
+
+    /// The stored pattern and the bug whose folder is `folder` (if any).
+    pub fn for_folder(conn: &Connection, folder: &Path) -> Self {
+        let bug_display_id = conn
+            .query_row(
+                "SELECT display_id FROM bugs WHERE folder_path = ?1",
+                params![folder.to_string_lossy()],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten();
+        Self::new(&load_pattern(conn), bug_display_id).unwrap_or_default()
+    }
+
+    pub fn with_time(mut self, time: NaiveDateTime) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Name as of a capture's RFC 3339 `created_at`, in local time. An
+    /// unreadable timestamp keeps the current time.
+    pub fn with_created_at(self, created_at: &str) -> Self {
+        match chrono::DateTime::parse_from_rfc3339(created_at) {
+            Ok(created) => self.with_time(created.with_timezone(&Local).naive_local()),
+            Err(_) => self,
+        }
+    }
+
+    /// File name stem for a capture of `kind` (`capture`/`recording`).
+    pub fn stem(&self, kind: &str, capture_number: u32) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Literal(text) => text.clone(),
+                Part::Token(Token::Kind) => kind.to_string(),
+                Part::Token(Token::Seq) => format!("{:03}", capture_number),
+                Part::Token(Token::Time) => self.time.format("%H%M%S").to_string(),
+                Part::Token(Token::Date) => self.time.format("%Y%m%d").to_string(),
+                Part::Token(Token::Bug) => {
+                    sanitize(self.bug_display_id.as_deref().unwrap_or("unsorted"))
+                }
+            })
+            .collect()
+    }
+
+    /// Whether `file_name` starts with a name this pattern produces
+    /// (annotated copies such as `capture-001_annotated.png` included).
+    pub fn matches(&self, file_name: &str) -> bool {
+        matches_from(&self.parts, file_name)
+    }
+}
+
+fn digits(text: &str) -> usize {
+    text.bytes().take_while(u8::is_ascii_digit).count()
+}
+
+fn matches_from(parts: &[Part], text: &str) -> bool {
+    let Some((part, rest)) = parts.split_first() else {
+        return true;
+    };
+    match part {
+        Part::Literal(literal) => text.strip_prefix(literal.as_str()).is_some_and(|t| matches_from(rest, t)),
+        Part::Token(Token::Kind) => ["capture", "recording"]
+            .iter()
+            .any(|kind| text.strip_prefix(kind).is_some_and(|t| matches_from(rest, t))),
+        Part::Token(Token::Seq) => (1..=digits(text)).rev().any(|n| matches_from(rest, &text[n..])),
+        Part::Token(Token::Time) => digits(text) >= 6 && matches_from(rest, &text[6..]),
+        Part::Token(Token::Date) => digits(text) >= 8 && matches_from(rest, &text[8..]),
+        Part::Token(Token::Bug) => (1..=text.len())
+            .filter(|&i| text.is_char_boundary(i))
+            .any(|i| matches_from(rest, &text[i..])),
+    }
+}
+
+/// The stored pattern, or the default when unset or invalid.
+pub fn load_pattern(conn: &Connection) -> String {
+    SettingsRepository::new(conn)
+        .get(FILENAME_PATTERN_KEY)
+        .ok()
+        .flatten()
+        .filter(|pattern| validate(pattern).is_ok())
+        .unwrap_or_else(|| DEFAULT_PATTERN.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(pattern: &str, bug: Option<&str>) -> NamingContext {
+        let time = NaiveDateTime::parse_from_str("2024-02-17 14:30:25", "%Y-%m-%d %H:%M:%S").unwrap();
+        NamingContext::new(pattern, bug.map(String::from)).unwrap().with_time(time)
+    }
+
+    #[test]
+    fn test_stem_tokens() {
+        assert_eq!(at(DEFAULT_PATTERN, None).stem("capture", 7), "capture-007");
+        assert_eq!(at("{time}_{kind}-{seq}", None).stem("capture", 12), "143025_capture-012");
+        assert_eq!(at("{date}_{bug}_{seq}", Some("BUG-004")).stem("recording", 1), "20240217_BUG-004_001");
+        assert_eq!(at("{bug}-{seq}", None).stem("capture", 1000), "unsorted-1000");
+        assert_eq!(at("{bug}-{seq}", Some("QA: 7")).stem("capture", 1), "QA__7-001");
+    }
+
+    #[test]
+    fn test_with_created_at_uses_capture_time() {
+        let created = chrono::DateTime::parse_from_rfc3339("2024-02-17T14:30:25Z").unwrap();
+        let local = created.with_timezone(&Local).format("%H%M%S").to_string();
+        let naming = at("{time}_{seq}", None).with_created_at("2024-02-17T14:30:25Z");
+        assert_eq!(naming.stem("capture", 1), format!("{}_001", local));
+        assert_eq!(at("{time}_{seq}", None).with_created_at("not a date").stem("capture", 1), "143025_001");
+    }
+
+    #[test]
+    fn test_validate_rejects_unsafe_patterns() {
+        assert!(validate("{time}_{kind}-{seq}").is_ok());
+        assert!(validate("{kind}").is_err(), "needs {{seq}}");
+        assert!(validate("{kind}/{seq}").is_err());
+        assert!(validate("{seq}:{time}").is_err());
+        assert!(validate("{seq}\t").is_err());
+        assert!(validate(".{seq}").is_err());
+        assert!(validate("{seq}{user}").is_err());
+        assert!(validate("{seq").is_err());
+        assert!(validate("seq}").is_err());
+        assert!(validate(&format!("{{seq}}{}", "a".repeat(MAX_PATTERN_LEN))).is_err());
+    }
+
+    #[test]
+    fn test_matches_names_from_pattern() {
+        let default = at(DEFAULT_PATTERN, None);
+        assert!(default.matches("capture-001.png"));
+        assert!(default.matches("recording-012.mp4"));
+        assert!(default.matches("capture-001_annotated.png"));
+        assert!(!default.matches("notes.md"));
+        assert!(!default.matches("capture-.png"));
+
+        let timed = at("{time}_{kind}-{seq}", None);
+        assert!(timed.matches("091500_capture-003.png"));
+        assert!(!timed.matches("capture-003.png"));
+
+        let by_bug = at("{bug}-{seq}", Some("BUG-004"));
+        assert!(by_bug.matches("BUG-004-002.png"));
+        assert!(!by_bug.matches("-002.png"));
+    }
+
+    #[test]
+    fn test_for_folder_reads_setting_and_bug() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/qa/s-1');
+             INSERT INTO bugs (id, session_id, bug_number, display_id, folder_path)
+             VALUES ('b-1', 's-1', 4, 'BUG-004', '/qa/s-1/bug_004');",
+        )
+        .unwrap();
+        let repo = SettingsRepository::new(&conn);
+
+        assert_eq!(NamingContext::for_folder(&conn, Path::new("/qa/s-1/bug_004")).stem("capture", 1), "capture-001");
+
+        repo.set(FILENAME_PATTERN_KEY, "{bug}_{seq}").unwrap();
+        assert_eq!(NamingContext::for_folder(&conn, Path::new("/qa/s-1/bug_004")).stem("capture", 2), "BUG-004_002");
+        assert_eq!(NamingContext::for_folder(&conn, Path::new("/qa/s-1/_unsorted")).stem("capture", 2), "unsorted_002");
+
+        // An invalid stored pattern falls back to the default
+        repo.set(FILENAME_PATTERN_KEY, "{bug}").unwrap();
+        assert_eq!(NamingContext::for_folder(&conn, Path::new("/qa/s-1/bug_004")).stem("capture", 3), "capture-003");
+    }
+}
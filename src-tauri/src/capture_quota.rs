@@ -0,0 +1,204 @@
+//! Soft limits on how many captures a bug or session collects.
+//!
+//! A ticket with forty screenshots is hard to review; usually it holds more
+//! than one problem. The `capture.quota` setting sets a count and a size limit
+//! per bug and per session. Captures are never refused: when a new capture
+//! takes a bug or session over a limit, [`check`] returns a warning suggesting
+//! to split it. Each limit warns once, when it is crossed, so later captures
+//! stay quiet.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Capture, SettingsOps, SettingsRepository};
+use crate::events::CaptureQuotaExceeded;
+
+/// Settings key holding [`CaptureQuota`] as JSON.
+pub const CAPTURE_QUOTA_KEY: &str = "capture.quota";
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Limits for one bug or one session; `None` means no limit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuotaLimits {
+    pub max_captures: Option<u32>,
+    pub max_megabytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptureQuota {
+    pub enabled: bool,
+    pub per_bug: QuotaLimits,
+    pub per_session: QuotaLimits,
+}
+
+impl Default for CaptureQuota {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            per_bug: QuotaLimits { max_captures: Some(25), max_megabytes: Some(200) },
+            per_session: QuotaLimits { max_captures: Some(300), max_megabytes: Some(2048) },
+        }
+    }
+}
+
+impl CaptureQuota {
+    /// Stored settings; missing or unreadable settings give the defaults.
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(CAPTURE_QUOTA_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(CAPTURE_QUOTA_KEY, &json)
+            .map_err(|e| format!("Failed to save capture quota: {}", e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for limits in [&self.per_bug, &self.per_session] {
+            if limits.max_captures == Some(0) || limits.max_megabytes == Some(0) {
+                return Err("Capture limits must be at least 1; leave a limit empty to turn it off".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Number and total size of the captures matching `filter` (`bug_id = ?1`
+/// or `session_id = ?1`).
+fn usage(conn: &Connection, filter: &str, id: &str) -> Result<(u64, u64), String> {
+    conn.query_row(
+        &format!("SELECT COUNT(*), COALESCE(SUM(file_size_bytes), 0) FROM captures WHERE {}", filter),
+        params![id],
+        |row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, i64>(1)?.max(0) as u64)),
+    )
+    .map_err(|e| format!("Failed to count captures: {}", e))
+}
+
+/// Whether going from `before` to `after` passes `limit`.
+fn crossed(limit: Option<u64>, before: u64, after: u64) -> bool {
+    limit.is_some_and(|limit| before <= limit && after > limit)
+}
+
+fn megabytes(bytes: u64) -> u64 {
+    bytes.div_ceil(BYTES_PER_MB)
+}
+
+/// Warnings for the limits `capture`, already stored, took its bug or
+/// session over.
+pub fn check(conn: &Connection, capture: &Capture) -> Result<Vec<CaptureQuotaExceeded>, String> {
+    let quota = CaptureQuota::load(conn);
+    if !quota.enabled {
+        return Ok(Vec::new());
+    }
+    let size = capture.file_size_bytes.unwrap_or(0).max(0) as u64;
+
+    let mut warnings = Vec::new();
+    let mut check_scope = |limits: &QuotaLimits, count: u64, bytes: u64, name: &str, advice: &str, bug_id: Option<&str>| {
+        let warning = if crossed(limits.max_captures.map(u64::from), count - 1, count) {
+            Some(format!("{} has {} captures — {}", name, count, advice))
+        } else if crossed(limits.max_megabytes.map(|mb| mb * BYTES_PER_MB), bytes.saturating_sub(size), bytes) {
+            Some(format!("{} has {} MB of captures — {}", name, megabytes(bytes), advice))
+        } else {
+            None
+        };
+        if let Some(message) = warning {
+            warnings.push(CaptureQuotaExceeded {
+                session_id: capture.session_id.clone(),
+                bug_id: bug_id.map(str::to_string),
+                captures: count,
+                total_bytes: bytes,
+                message,
+            });
+        }
+    };
+
+    if let Some(bug_id) = &capture.bug_id {
+        let display_id: Option<String> = conn
+            .query_row("SELECT display_id FROM bugs WHERE id = ?1", params![bug_id], |row| row.get(0))
+            .optional()
+            .map_err(|e| format!("Failed to query bug: {}", e))?;
+        let (count, bytes) = usage(conn, "bug_id = ?1", bug_id)?;
+        let name = display_id.unwrap_or_else(|| "This bug".to_string());
+        check_scope(&quota.per_bug, count, bytes, &name, "consider splitting it", Some(bug_id));
+    }
+    let (count, bytes) = usage(conn, "session_id = ?1", &capture.session_id)?;
+    check_scope(&quota.per_session, count, bytes, "This session", "consider ending it and starting a new one", None);
+
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{init_database, CaptureOps, CaptureRepository, CaptureType};
+
+    fn add_capture(conn: &Connection, n: u32, bug_id: Option<&str>, size: i64) -> Capture {
+        let capture = Capture {
+            id: format!("c-{}", n),
+            bug_id: bug_id.map(str::to_string),
+            session_id: "s-1".to_string(),
+            file_name: format!("capture-{:03}.png", n),
+            file_path: format!("/qa/capture-{:03}.png", n),
+            file_type: CaptureType::Screenshot,
+            file_size_bytes: Some(size),
+            created_at: "2024-01-01T10:00:00Z".to_string(),
+            ..Default::default()
+        };
+        CaptureRepository::new(conn).create(&capture).unwrap();
+        capture
+    }
+
+    #[test]
+    fn test_warns_once_when_a_limit_is_crossed() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, started_at, status, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', 'active', '/qa');
+             INSERT INTO bugs (id, session_id, bug_number, display_id, folder_path) VALUES ('b-1', 's-1', 4, 'BUG-004', '/qa/bug_004');",
+        )
+        .unwrap();
+        CaptureQuota {
+            enabled: true,
+            per_bug: QuotaLimits { max_captures: Some(2), max_megabytes: None },
+            per_session: QuotaLimits { max_captures: None, max_megabytes: Some(1) },
+        }
+        .save(&conn)
+        .unwrap();
+
+        for n in 1..=2 {
+            let capture = add_capture(&conn, n, Some("b-1"), 1000);
+            assert!(check(&conn, &capture).unwrap().is_empty());
+        }
+        let third = add_capture(&conn, 3, Some("b-1"), 1000);
+        let warnings = check(&conn, &third).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "BUG-004 has 3 captures — consider splitting it");
+        assert_eq!(warnings[0].bug_id.as_deref(), Some("b-1"));
+
+        let fourth = add_capture(&conn, 4, Some("b-1"), 1000);
+        assert!(check(&conn, &fourth).unwrap().is_empty());
+
+        let large = add_capture(&conn, 5, None, BYTES_PER_MB as i64);
+        let warnings = check(&conn, &large).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].bug_id, None);
+        assert_eq!(warnings[0].message, "This session has 2 MB of captures — consider ending it and starting a new one");
+    }
+
+    #[test]
+    fn test_zero_limits_are_rejected() {
+        let mut quota = CaptureQuota::default();
+        assert!(quota.validate().is_ok());
+        quota.per_session.max_captures = Some(0);
+        assert!(quota.validate().is_err());
+    }
+}
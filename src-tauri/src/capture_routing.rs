@@ -0,0 +1,203 @@
+//! Which bug a new capture file belongs to.
+//!
+//! Captures go to the active bug, or to `_unsorted/` when none is active.
+//! Capture tools can save a file a couple of seconds after it was taken (the
+//! Snipping Tool's save lag), so a screenshot taken just before pressing F4
+//! would otherwise land in `_unsorted/`. A file that appears within the
+//! grace window after a bug capture ended, while no other bug is active,
+//! still goes to the ended bug. How a capture was routed, and the screenshot
+//! trigger it answers (see `capture_trigger`), is recorded in its
+//! `source_metadata` as a [`CaptureSource`].
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::capture_trigger::{PendingCapture, SharedPendingCaptures, TriggerSource};
+use crate::database::{SettingsOps, SettingsRepository};
+use crate::display_info::DisplaySnapshot;
+use crate::platform::ForegroundWindow;
+
+/// Settings key holding the grace window in seconds (0 disables it).
+pub const GRACE_WINDOW_KEY: &str = "capture.grace_window_secs";
+
+pub const DEFAULT_GRACE_WINDOW: Duration = Duration::from_secs(5);
+
+/// Longest accepted grace window.
+pub const MAX_GRACE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Shared handle to the most recently ended bug capture.
+pub type SharedEndedBug = Arc<Mutex<Option<EndedBug>>>;
+
+/// Live routing state shared by the session manager and the capture watcher.
+#[derive(Debug, Clone, Default)]
+pub struct RoutingHandles {
+    pub active_bug: Arc<Mutex<Option<String>>>,
+    pub recently_ended: SharedEndedBug,
+    pub pending_captures: SharedPendingCaptures,
+}
+
+/// The bug capture that ended most recently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndedBug {
+    pub bug_id: String,
+    pub ended_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Routing {
+    ActiveBug,
+    /// Arrived shortly after the bug capture ended
+    GraceWindow,
+    Unsorted,
+}
+
+/// Stored as JSON in `Capture::source_metadata`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureSource {
+    /// What ingested the file, e.g. `capture_watcher`
+    pub source: String,
+    pub routing: Routing,
+    /// For grace-window routing: milliseconds between the bug ending and the file appearing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ms_after_bug_end: Option<i64>,
+    /// The screenshot trigger this file answers; None for files saved into
+    /// the folder without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<TriggerSource>,
+    /// Milliseconds between the trigger and the file appearing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ms_after_trigger: Option<i64>,
+    /// Bug active at the trigger, when it is not the bug the file went to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_bug_id: Option<String>,
+    /// Monitor layout and DPI scaling when the file was detected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<DisplaySnapshot>,
+    /// Window in the foreground when the file was taken or detected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreground: Option<ForegroundWindow>,
+}
+
+impl CaptureSource {
+    /// Record the claimed trigger marker for a file seen at `seen_at`.
+    pub fn with_trigger(mut self, marker: Option<PendingCapture>, seen_at: DateTime<Utc>, bug_id: Option<&str>) -> Self {
+        if let Some(marker) = marker {
+            self.trigger = Some(marker.source);
+            self.ms_after_trigger = Some(seen_at.signed_duration_since(marker.triggered_at).num_milliseconds().max(0));
+            self.trigger_bug_id = marker.bug_id.filter(|id| Some(id.as_str()) != bug_id);
+        }
+        self
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+pub fn load_grace_window(conn: &Connection) -> Duration {
+    SettingsRepository::new(conn)
+        .get(GRACE_WINDOW_KEY)
+        .ok()
+        .flatten()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(|secs| Duration::from_secs(secs).min(MAX_GRACE_WINDOW))
+        .unwrap_or(DEFAULT_GRACE_WINDOW)
+}
+
+/// Pick the bug for a file that appeared at `seen_at`. Returns the bug (None
+/// for `_unsorted/`), the routing decision and, for grace-window routing,
+/// milliseconds since the bug ended.
+pub fn route(
+    active_bug: Option<String>,
+    recently_ended: Option<&EndedBug>,
+    grace_window: Duration,
+    seen_at: DateTime<Utc>,
+) -> (Option<String>, Routing, Option<i64>) {
+    if let Some(bug_id) = active_bug {
+        return (Some(bug_id), Routing::ActiveBug, None);
+    }
+    if let Some(ended) = recently_ended {
+        let after_end = seen_at.signed_duration_since(ended.ended_at);
+        let within = after_end
+            .to_std()
+            .map(|elapsed| elapsed <= grace_window)
+            // Seen before the end was recorded: the capture belongs to that bug
+            .unwrap_or(true);
+        if within && !grace_window.is_zero() {
+            return (Some(ended.bug_id.clone()), Routing::GraceWindow, Some(after_end.num_milliseconds().max(0)));
+        }
+    }
+    (None, Routing::Unsorted, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route() {
+        let ended_at: DateTime<Utc> = "2024-01-01T10:00:00Z".parse().unwrap();
+        let ended = EndedBug { bug_id: "b-1".to_string(), ended_at };
+        let at = |secs: i64| ended_at + chrono::Duration::seconds(secs);
+        let grace = Duration::from_secs(5);
+
+        assert_eq!(
+            route(Some("b-2".to_string()), Some(&ended), grace, at(1)),
+            (Some("b-2".to_string()), Routing::ActiveBug, None)
+        );
+        assert_eq!(
+            route(None, Some(&ended), grace, at(2)),
+            (Some("b-1".to_string()), Routing::GraceWindow, Some(2000))
+        );
+        assert_eq!(route(None, Some(&ended), grace, at(6)), (None, Routing::Unsorted, None));
+        assert_eq!(route(None, Some(&ended), Duration::ZERO, at(0)), (None, Routing::Unsorted, None));
+        assert_eq!(route(None, None, grace, at(0)), (None, Routing::Unsorted, None));
+    }
+
+    #[test]
+    fn test_grace_window_setting_and_source_json() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        assert_eq!(load_grace_window(&conn), DEFAULT_GRACE_WINDOW);
+
+        let repo = SettingsRepository::new(&conn);
+        repo.set(GRACE_WINDOW_KEY, "0").unwrap();
+        assert_eq!(load_grace_window(&conn), Duration::ZERO);
+        repo.set(GRACE_WINDOW_KEY, "3600").unwrap();
+        assert_eq!(load_grace_window(&conn), MAX_GRACE_WINDOW);
+
+        let source = CaptureSource {
+            source: "capture_watcher".to_string(),
+            routing: Routing::GraceWindow,
+            ms_after_bug_end: Some(1800),
+            trigger: None,
+            ms_after_trigger: None,
+            trigger_bug_id: None,
+            display: None,
+            foreground: None,
+        };
+        assert_eq!(
+            source.to_json(),
+            r#"{"source":"capture_watcher","routing":"grace_window","msAfterBugEnd":1800}"#
+        );
+
+        let triggered_at: DateTime<Utc> = "2024-01-01T10:00:00Z".parse().unwrap();
+        let marker = PendingCapture {
+            triggered_at,
+            bug_id: Some("b-1".to_string()),
+            source: TriggerSource::Hotkey,
+        };
+        let seen_at = triggered_at + chrono::Duration::milliseconds(4250);
+        let source = source.with_trigger(Some(marker), seen_at, Some("b-1"));
+        assert_eq!(
+            source.to_json(),
+            r#"{"source":"capture_watcher","routing":"grace_window","msAfterBugEnd":1800,"trigger":"hotkey","msAfterTrigger":4250}"#
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! Offline suggestions for sorting `_unsorted/` captures into bugs.
+//!
+//! Each bug of the session spans a capture window: from the bug's creation
+//! (when its capture started) to its latest capture, or to now while it is
+//! still capturing. An unsorted capture taken inside a window most likely
+//! belongs to that bug; one taken shortly before or after it gets a score
+//! that decays with the distance. Late saves are more common than early ones
+//! (see `capture_routing`), so scores decay more slowly after a window.
+//!
+//! Hints raise a bug's confidence further: the bug that was active when the
+//! capture was triggered (`CaptureSource::trigger_bug_id`), and the bug's
+//! display ID or folder name appearing in the file name (e.g. a file named
+//! `bug-003 login.png` dropped into the session by hand).
+//!
+//! Unlike the AI `suggest_capture_assignment`, this needs no credentials and
+//! runs instantly, so the triage view can rank every unsorted capture.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::capture_routing::CaptureSource;
+use crate::database::{Bug, BugOps, BugRepository, BugStatus, Capture, CaptureOps, CaptureRepository};
+
+/// Seconds for the temporal score to fall to 1/e before a window starts.
+const DECAY_BEFORE_SECS: f64 = 60.0;
+
+/// Seconds for the temporal score to fall to 1/e after a window ends.
+const DECAY_AFTER_SECS: f64 = 180.0;
+
+/// Confidence a matching file name adds on its own.
+const FILE_NAME_HINT: f64 = 0.8;
+
+/// Confidence the bug active at the screenshot trigger adds on its own.
+const TRIGGER_HINT: f64 = 0.9;
+
+/// Candidates below this confidence are left out.
+const MIN_CONFIDENCE: f64 = 0.05;
+
+/// Most candidates returned.
+const MAX_CANDIDATES: usize = 5;
+
+/// A bug an unsorted capture may belong to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignmentCandidate {
+    pub bug_id: String,
+    /// For UI display (e.g. "BUG-001")
+    pub display_id: String,
+    pub title: Option<String>,
+    /// 0.0–1.0
+    pub confidence: f32,
+    /// Why the bug was suggested, e.g. "taken during the bug's capture"
+    pub reasons: Vec<String>,
+}
+
+/// When a bug was being captured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureWindow {
+    pub start: DateTime<Utc>,
+    /// None while the bug is still capturing
+    pub end: Option<DateTime<Utc>>,
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// The capture window of `bug`, given the captures filed to it.
+pub fn capture_window(bug: &Bug, bug_captures: &[Capture]) -> Option<CaptureWindow> {
+    let start = parse_time(&bug.created_at)?;
+    let end = if bug.status == BugStatus::Capturing {
+        None
+    } else {
+        let latest = bug_captures.iter().filter_map(|c| parse_time(&c.created_at)).max();
+        Some(latest.map_or(start, |latest| latest.max(start)))
+    };
+    Some(CaptureWindow { start, end })
+}
+
+/// 1.0 inside `window`, decaying exponentially with the distance outside it,
+/// with a reason when the score is worth showing.
+pub fn temporal_score(window: &CaptureWindow, taken_at: DateTime<Utc>) -> (f64, Option<String>) {
+    let secs = |from: DateTime<Utc>, to: DateTime<Utc>| to.signed_duration_since(from).num_milliseconds() as f64 / 1000.0;
+    if taken_at < window.start {
+        let before = secs(taken_at, window.start);
+        let score = (-before / DECAY_BEFORE_SECS).exp();
+        return (score, (score >= MIN_CONFIDENCE).then(|| format!("taken {} before the bug's capture", describe_secs(before))));
+    }
+    match window.end {
+        Some(end) if taken_at > end => {
+            let after = secs(end, taken_at);
+            let score = (-after / DECAY_AFTER_SECS).exp();
+            (score, (score >= MIN_CONFIDENCE).then(|| format!("taken {} after the bug's capture", describe_secs(after))))
+        }
+        _ => (1.0, Some("taken during the bug's capture".to_string())),
+    }
+}
+
+fn describe_secs(secs: f64) -> String {
+    if secs < 90.0 {
+        format!("{}s", secs.round() as i64)
+    } else {
+        format!("{}m", (secs / 60.0).round() as i64)
+    }
+}
+
+/// Normalize for name matching: lowercase, separators dropped, leading zeros
+/// of numbers dropped, so "BUG-003", "bug_3" and "Bug 03" all read alike.
+fn normalize(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            let mut digits = String::from(c);
+            while let Some(d) = chars.peek().copied().filter(|d| d.is_ascii_digit()) {
+                digits.push(d);
+                chars.next();
+            }
+            let trimmed = digits.trim_start_matches('0');
+            out.push_str(if trimmed.is_empty() { "0" } else { trimmed });
+            // Keep "bug1" from matching inside "bug12"
+            out.push('#');
+        } else if c.is_alphanumeric() {
+            out.extend(c.to_lowercase());
+        }
+    }
+    out
+}
+
+/// Whether `file_name` names `bug` by display ID or folder name.
+pub fn file_name_mentions(file_name: &str, bug: &Bug) -> bool {
+    let name = normalize(file_name);
+    let folder = std::path::Path::new(&bug.folder_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    [bug.display_id.as_str(), folder.as_str()]
+        .iter()
+        .map(|id| normalize(id))
+        .any(|id| !id.is_empty() && name.contains(&id))
+}
+
+/// Rank `bugs` as homes for `capture`. `captures` are the session's captures,
+/// used for the bugs' capture windows.
+pub fn rank(capture: &Capture, bugs: &[Bug], captures: &[Capture]) -> Vec<AssignmentCandidate> {
+    let taken_at = parse_time(&capture.created_at);
+    let trigger_bug_id = capture
+        .source_metadata
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<CaptureSource>(json).ok())
+        .and_then(|source| source.trigger_bug_id);
+
+    let mut candidates: Vec<AssignmentCandidate> = bugs
+        .iter()
+        .filter_map(|bug| {
+            let mut reasons = Vec::new();
+            // Probability the capture does not belong to the bug, per signal
+            let mut miss = 1.0;
+
+            let bug_captures: Vec<Capture> =
+                captures.iter().filter(|c| c.bug_id.as_deref() == Some(bug.id.as_str())).cloned().collect();
+            if let (Some(window), Some(taken_at)) = (capture_window(bug, &bug_captures), taken_at) {
+                let (score, reason) = temporal_score(&window, taken_at);
+                miss *= 1.0 - score;
+                reasons.extend(reason);
+            }
+            if file_name_mentions(&capture.file_name, bug) {
+                miss *= 1.0 - FILE_NAME_HINT;
+                reasons.push("file name mentions the bug".to_string());
+            }
+            if trigger_bug_id.as_deref() == Some(bug.id.as_str()) {
+                miss *= 1.0 - TRIGGER_HINT;
+                reasons.push("bug was active when the screenshot was triggered".to_string());
+            }
+
+            let confidence = 1.0 - miss;
+            (confidence >= MIN_CONFIDENCE).then(|| AssignmentCandidate {
+                bug_id: bug.id.clone(),
+                display_id: bug.display_id.clone(),
+                title: bug.title.clone(),
+                confidence: confidence as f32,
+                reasons,
+            })
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    candidates.truncate(MAX_CANDIDATES);
+    candidates
+}
+
+/// Ranked candidate bugs for capture `capture_id`, from its session.
+pub fn suggest(conn: &Connection, capture_id: &str) -> Result<Vec<AssignmentCandidate>, String> {
+    let capture_repo = CaptureRepository::new(conn);
+    let capture = capture_repo
+        .get(capture_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Capture not found: {}", capture_id))?;
+    let bugs = BugRepository::new(conn).list_by_session(&capture.session_id).map_err(|e| e.to_string())?;
+    let captures = capture_repo.list_by_session(&capture.session_id).map_err(|e| e.to_string())?;
+    Ok(rank(&capture, &bugs, &captures))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{BugType, CaptureType};
+
+    fn bug(id: &str, number: i32, created_at: &str, status: BugStatus) -> Bug {
+        Bug {
+            id: id.to_string(),
+            session_id: "s-1".to_string(),
+            bug_number: number,
+            display_id: format!("BUG-{:03}", number),
+            bug_type: BugType::Bug,
+            status,
+            folder_path: format!("/qa/s-1/bug_{:03}", number),
+            created_at: created_at.to_string(),
+            updated_at: created_at.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn capture(id: &str, bug_id: Option<&str>, file_name: &str, created_at: &str) -> Capture {
+        Capture {
+            id: id.to_string(),
+            bug_id: bug_id.map(str::to_string),
+            session_id: "s-1".to_string(),
+            file_name: file_name.to_string(),
+            file_path: format!("/qa/s-1/_unsorted/{}", file_name),
+            file_type: CaptureType::Screenshot,
+            created_at: created_at.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_temporal_score() {
+        let window = CaptureWindow {
+            start: parse_time("2024-01-01T10:00:00Z").unwrap(),
+            end: parse_time("2024-01-01T10:05:00Z"),
+        };
+        let at = |t: &str| parse_time(t).unwrap();
+        assert_eq!(temporal_score(&window, at("2024-01-01T10:02:00Z")).0, 1.0);
+
+        // Decays faster before the window than after it
+        let (before, reason) = temporal_score(&window, at("2024-01-01T09:59:00Z"));
+        let (after, _) = temporal_score(&window, at("2024-01-01T10:06:00Z"));
+        assert!(before < after && after < 1.0);
+        assert_eq!(reason.as_deref(), Some("taken 60s before the bug's capture"));
+
+        let (far, reason) = temporal_score(&window, at("2024-01-01T11:00:00Z"));
+        assert!(far < MIN_CONFIDENCE);
+        assert_eq!(reason, None);
+
+        // Still capturing: open-ended
+        let open = CaptureWindow { end: None, ..window };
+        assert_eq!(temporal_score(&open, at("2024-01-01T12:00:00Z")).0, 1.0);
+    }
+
+    #[test]
+    fn test_file_name_mentions() {
+        let b3 = bug("b-3", 3, "2024-01-01T10:00:00Z", BugStatus::Captured);
+        assert!(file_name_mentions("bug-003 login.png", &b3));
+        assert!(file_name_mentions("Bug_3.png", &b3));
+        assert!(file_name_mentions("BUG 03 - crash.jpg", &b3));
+        assert!(!file_name_mentions("bug_30.png", &b3));
+        assert!(!file_name_mentions("capture-003.png", &b3));
+    }
+
+    #[test]
+    fn test_rank() {
+        let bugs = vec![
+            bug("b-1", 1, "2024-01-01T10:00:00Z", BugStatus::Captured),
+            bug("b-2", 2, "2024-01-01T10:10:00Z", BugStatus::Captured),
+            bug("b-3", 3, "2024-01-01T11:00:00Z", BugStatus::Capturing),
+        ];
+        let captures = vec![
+            capture("c-1", Some("b-1"), "capture-001.png", "2024-01-01T10:03:00Z"),
+            capture("c-2", Some("b-2"), "capture-001.png", "2024-01-01T10:12:00Z"),
+        ];
+
+        // A minute after bug 1's last capture, six before bug 2 started
+        let unsorted = capture("u-1", None, "capture-007.png", "2024-01-01T10:04:00Z");
+        let ranked = rank(&unsorted, &bugs, &captures);
+        let ids: Vec<&str> = ranked.iter().map(|c| c.bug_id.as_str()).collect();
+        assert_eq!(ids, vec!["b-1"]);
+        assert!(ranked[0].confidence > 0.6);
+        assert_eq!(ranked[0].reasons, vec!["taken 60s after the bug's capture"]);
+
+        // A file name hint outweighs time
+        let unsorted = capture("u-2", None, "bug_002.png", "2024-01-01T10:04:00Z");
+        let ranked = rank(&unsorted, &bugs, &captures);
+        assert_eq!(ranked[0].bug_id, "b-2");
+        assert!(ranked[0].reasons.contains(&"file name mentions the bug".to_string()));
+
+        // The bug active at the trigger
+        let mut unsorted = capture("u-3", None, "capture-008.png", "2024-01-01T10:30:00Z");
+        unsorted.source_metadata =
+            Some(r#"{"source":"capture_watcher","routing":"unsorted","triggerBugId":"b-1"}"#.to_string());
+        let ranked = rank(&unsorted, &bugs, &captures);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].bug_id, "b-1");
+        assert!((ranked[0].confidence - 0.9).abs() < 0.01);
+    }
+}
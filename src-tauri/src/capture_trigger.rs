@@ -0,0 +1,98 @@
+//! Pending-capture markers for screenshot triggers.
+//!
+//! The screenshot hotkey (and `trigger_screenshot`) only opens the OS capture
+//! tool; the file shows up in `_captures/` once the user has selected a region
+//! and the tool has saved it, often several seconds later. When the trigger
+//! fires, a [`PendingCapture`] marker is recorded with the time and the bug
+//! that was active. The capture watcher claims the oldest marker for each new
+//! file and stores the trigger and its delay in the capture's
+//! `source_metadata`, so hotkey-triggered captures can be told apart from
+//! files that were saved into the folder by other means.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How long a marker waits for its file before it is discarded.
+pub const MARKER_TTL: Duration = Duration::from_secs(120);
+
+/// Shared handle to the markers that have not been matched to a file yet.
+pub type SharedPendingCaptures = Arc<Mutex<Vec<PendingCapture>>>;
+
+/// What asked for the screenshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerSource {
+    /// The app's screenshot hotkey
+    Hotkey,
+    /// `trigger_screenshot` from the UI
+    Command,
+}
+
+/// A screenshot that was triggered but whose file has not appeared yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingCapture {
+    pub triggered_at: DateTime<Utc>,
+    /// Bug that was active when the trigger fired
+    pub bug_id: Option<String>,
+    pub source: TriggerSource,
+}
+
+fn expired(marker: &PendingCapture, now: DateTime<Utc>) -> bool {
+    now.signed_duration_since(marker.triggered_at)
+        .to_std()
+        .is_ok_and(|age| age > MARKER_TTL)
+}
+
+/// Add a marker, dropping expired ones.
+pub fn record(pending: &Mutex<Vec<PendingCapture>>, marker: PendingCapture) {
+    let mut pending = pending.lock().unwrap();
+    let now = marker.triggered_at;
+    pending.retain(|m| !expired(m, now));
+    pending.push(marker);
+}
+
+/// Take the oldest marker triggered before `seen_at` and still within
+/// [`MARKER_TTL`]. None means the file was not triggered by the app.
+pub fn claim(pending: &Mutex<Vec<PendingCapture>>, seen_at: DateTime<Utc>) -> Option<PendingCapture> {
+    let mut pending = pending.lock().unwrap();
+    pending.retain(|m| !expired(m, seen_at));
+    let index = pending
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.triggered_at <= seen_at)
+        .min_by_key(|(_, m)| m.triggered_at)
+        .map(|(i, _)| i)?;
+    Some(pending.remove(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_oldest_unexpired_marker() {
+        let start: DateTime<Utc> = "2024-01-01T10:00:00Z".parse().unwrap();
+        let at = |secs: i64| start + chrono::Duration::seconds(secs);
+        let marker = |secs: i64, bug: Option<&str>| PendingCapture {
+            triggered_at: at(secs),
+            bug_id: bug.map(String::from),
+            source: TriggerSource::Hotkey,
+        };
+        let pending = Mutex::new(Vec::new());
+
+        record(&pending, marker(0, None));
+        // A file seen long after the trigger is an incidental save
+        assert_eq!(claim(&pending, at(121)), None);
+        assert!(pending.lock().unwrap().is_empty());
+
+        record(&pending, marker(200, Some("b-1")));
+        record(&pending, marker(205, Some("b-2")));
+        assert_eq!(claim(&pending, at(199)), None, "file appeared before the trigger");
+        assert_eq!(claim(&pending, at(210)), Some(marker(200, Some("b-1"))));
+        assert_eq!(claim(&pending, at(211)), Some(marker(205, Some("b-2"))));
+        assert_eq!(claim(&pending, at(212)), None);
+    }
+}
@@ -6,11 +6,20 @@
 //!
 //! 1. Waits briefly for the write to finish.
 //! 2. Moves the file into the active bug folder (or `_unsorted/` when no bug
-//!    is active).
-//! 3. Creates a `Capture` DB record linking the file to the bug/session.
+//!    is active, unless a bug ended within the grace window; see
+//!    `capture_routing`).
+//! 3. Creates a `Capture` DB record linking the file to the bug/session, noting
+//!    the screenshot trigger it answers, if any (see `capture_trigger`), and
+//!    the monitor layout and DPI scaling at detection time (`display_info`)
+//!    and the foreground window (`foreground_app`).
 //! 4. Emits a `screenshot:captured` Tauri event so the frontend can refresh.
+//!
+//! A record that cannot be written is kept in the session's capture journal
+//! and retried in the background (see `capture_journal`); one that is given
+//! up on raises a `capture:write-lost` event.
 
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
@@ -18,10 +27,18 @@ use std::time::Duration;
 use chrono::Utc;
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use rusqlite::Connection;
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 use uuid::Uuid;
 
-use crate::database::{BugOps, BugRepository, Capture, CaptureOps, CaptureRepository};
+use crate::capture_journal;
+use crate::capture_routing::{self, CaptureSource, RoutingHandles};
+use crate::capture_trigger;
+use crate::display_info;
+use crate::database::{record_audit, BugOps, BugRepository, Capture, CaptureOps, CaptureRepository, CaptureType};
+use crate::events;
+use crate::media_offload::MediaOffload;
+use crate::metrics::{self, Counter};
+use crate::video_metadata;
 
 type SharedConn = Arc<Mutex<Connection>>;
 
@@ -33,9 +50,16 @@ const VIDEO_EXTENSIONS: &[&str] = &["mp4", "webm", "mkv", "avi", "mov"];
 
 /// Watches `_captures/` and routes new files to the correct bug folder.
 ///
-/// Dropping the struct stops the watcher.
+/// Dropping the struct stops the watcher and its journal retries.
 pub struct CaptureWatcher {
     _watcher: RecommendedWatcher,
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl Drop for CaptureWatcher {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
 }
 
 impl CaptureWatcher {
@@ -44,7 +68,7 @@ impl CaptureWatcher {
         captures_dir: PathBuf,
         session_id: String,
         session_folder: PathBuf,
-        active_bug: Arc<Mutex<Option<String>>>,
+        routing: RoutingHandles,
         db_conn: SharedConn,
         app_handle: AppHandle,
     ) -> Result<Self, String> {
@@ -53,15 +77,27 @@ impl CaptureWatcher {
             &captures_dir,
             &session_id,
             &session_folder,
-            &active_bug,
+            &routing,
             &db_conn,
             &app_handle,
         );
 
+        // Retry records journaled by an earlier run, then keep retrying.
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        {
+            let (flag, sf, dc, ah) = (Arc::clone(&stop_flag), session_folder.clone(), Arc::clone(&db_conn), app_handle.clone());
+            thread::spawn(move || {
+                while !flag.load(Ordering::Relaxed) {
+                    Self::retry_journal(&sf, &dc, &ah);
+                    thread::sleep(capture_journal::RETRY_INTERVAL);
+                }
+            });
+        }
+
         // Clones for the closure (must be 'static + Send).
         let sid = session_id;
         let sf = session_folder;
-        let ab = active_bug;
+        let rh = routing;
         let dc = db_conn;
         let ah = app_handle;
 
@@ -75,11 +111,11 @@ impl CaptureWatcher {
                     let path = path.clone();
                     let sid = sid.clone();
                     let sf = sf.clone();
-                    let ab = Arc::clone(&ab);
+                    let rh = rh.clone();
                     let dc = Arc::clone(&dc);
                     let ah = ah.clone();
                     thread::spawn(move || {
-                        Self::process_new_capture(&path, &sid, &sf, &ab, &dc, &ah);
+                        Self::process_new_capture(&path, &sid, &sf, &rh, &dc, &ah);
                     });
                 }
             },
@@ -91,7 +127,7 @@ impl CaptureWatcher {
             .watch(&captures_dir, RecursiveMode::NonRecursive)
             .map_err(|e| format!("Failed to watch captures directory: {e}"))?;
 
-        Ok(Self { _watcher: watcher })
+        Ok(Self { _watcher: watcher, stop_flag })
     }
 
     // ------------------------------------------------------------------
@@ -102,7 +138,7 @@ impl CaptureWatcher {
         captures_dir: &Path,
         session_id: &str,
         session_folder: &Path,
-        active_bug: &Arc<Mutex<Option<String>>>,
+        routing: &RoutingHandles,
         db_conn: &SharedConn,
         app_handle: &AppHandle,
     ) {
@@ -116,7 +152,7 @@ impl CaptureWatcher {
                     &path,
                     session_id,
                     session_folder,
-                    active_bug,
+                    routing,
                     db_conn,
                     app_handle,
                 );
@@ -214,10 +250,15 @@ impl CaptureWatcher {
         source_path: &Path,
         session_id: &str,
         session_folder: &Path,
-        active_bug: &Arc<Mutex<Option<String>>>,
+        routing: &RoutingHandles,
         db_conn: &SharedConn,
         app_handle: &AppHandle,
     ) {
+        // When the file appeared; waiting for the writer below can take seconds
+        let seen_at = Utc::now();
+        let display = display_info::snapshot(app_handle);
+        let foreground = crate::platform::foreground_window();
+
         // Poll until the writing application finishes flushing (size stable for 300ms).
         if !Self::wait_for_write_complete(source_path, Duration::from_secs(5)) {
             eprintln!(
@@ -247,8 +288,27 @@ impl CaptureWatcher {
             _ => return,
         };
 
-        // Snapshot the current active bug.
-        let bug_id = active_bug.lock().unwrap().clone();
+        // Snapshot the current active bug, falling back to a bug that ended
+        // just before the file appeared.
+        let grace_window = capture_routing::load_grace_window(&db_conn.lock().unwrap());
+        let (bug_id, routed, ms_after_bug_end) = capture_routing::route(
+            routing.active_bug.lock().unwrap().clone(),
+            routing.recently_ended.lock().unwrap().as_ref(),
+            grace_window,
+            seen_at,
+        );
+        let marker = capture_trigger::claim(&routing.pending_captures, seen_at);
+        let source = CaptureSource {
+            source: "capture_watcher".to_string(),
+            routing: routed,
+            ms_after_bug_end,
+            trigger: None,
+            ms_after_trigger: None,
+            trigger_bug_id: None,
+            display,
+            foreground,
+        }
+        .with_trigger(marker, seen_at, bug_id.as_deref());
 
         // Destination: bug folder if capturing, else _unsorted/.
         let dest_dir = match bug_id {
@@ -258,21 +318,41 @@ impl CaptureWatcher {
             None => session_folder.join("_unsorted"),
         };
 
-        if let Err(e) = std::fs::create_dir_all(&dest_dir) {
-            eprintln!("CaptureWatcher: cannot create dir {dest_dir:?}: {e}");
+        // Large recordings can be offloaded to a separate media root that
+        // mirrors the session folder layout.
+        let offload = if Self::is_video_file(source_path) {
+            let storage_root = session_folder.parent().unwrap_or(session_folder);
+            let conn = db_conn.lock().unwrap();
+            MediaOffload::from_settings(&conn, storage_root)
+                .map(|o| (o.mirror_dir(storage_root, &dest_dir), o))
+        } else {
+            None
+        };
+
+        let target_dir = offload.as_ref().map(|(dir, _)| dir.clone()).unwrap_or_else(|| dest_dir.clone());
+        if let Err(e) = std::fs::create_dir_all(&target_dir) {
+            eprintln!("CaptureWatcher: cannot create dir {target_dir:?}: {e}");
+            metrics::increment(Counter::RoutingErrors);
             return;
         }
 
-        // Generate a sequential, PRD-compliant filename.
-        let capture_number = crate::next_capture_number(&dest_dir);
+        // Generate a sequential, PRD-compliant filename. Numbering spans the
+        // bug folder and its media-root mirror so offloaded files never collide.
+        let naming = crate::capture_naming::NamingContext::for_folder(&db_conn.lock().unwrap(), &dest_dir);
+        let mut capture_number = crate::next_capture_number(&dest_dir, &naming);
+        if offload.is_some() {
+            capture_number += crate::next_capture_number(&target_dir, &naming) - 1;
+        }
         let (file_name, capture_type) =
-            crate::make_capture_filename(source_path, capture_number);
-        let dest_path = dest_dir.join(&file_name);
+            crate::make_capture_filename(source_path, capture_number, &naming);
+        let dest_path = target_dir.join(&file_name);
+        let media_link = offload.as_ref().and_then(|(_, o)| o.link_for(&dest_path));
 
         // Move (rename) the file; fall back to copy+delete for cross-volume.
         if std::fs::rename(source_path, &dest_path).is_err() {
             if let Err(e) = std::fs::copy(source_path, &dest_path) {
                 eprintln!("CaptureWatcher: copy failed {source_path:?} -> {dest_path:?}: {e}");
+                metrics::increment(Counter::RoutingErrors);
                 return;
             }
             let _ = std::fs::remove_file(source_path);
@@ -292,26 +372,123 @@ impl CaptureWatcher {
             is_console_capture: false,
             parsed_content: None,
             created_at: Utc::now().to_rfc3339(),
+            edited_at: None,
+            media_link,
+            video_duration_ms: None,
+            video_width: None,
+            video_height: None,
+            video_codec: None,
+            derived_from: None,
+            frame_timestamp_ms: None,
+            source_metadata: Some(source.to_json()),
+            alt_text: None,
         };
 
-        {
-            let conn = db_conn.lock().unwrap();
-            let repo = CaptureRepository::new(&conn);
-            if let Err(e) = repo.create(&capture) {
-                eprintln!("CaptureWatcher: DB insert failed: {e}");
+        let inserted = CaptureRepository::new(&db_conn.lock().unwrap()).create(&capture);
+        if let Err(e) = inserted {
+            eprintln!("CaptureWatcher: DB insert failed, journaling {:?}: {e}", capture.file_path);
+            metrics::increment(Counter::RoutingErrors);
+            let journal = capture_journal::journal_path(session_folder);
+            if let Err(journal_error) = capture_journal::append(&journal, &capture, &e.to_string()) {
+                eprintln!("CaptureWatcher: {journal_error}");
+                Self::report_lost(&capture, &format!("{e}; {journal_error}"), db_conn, app_handle);
             }
+            return;
         }
+        Self::finish_capture(&capture, db_conn, app_handle);
+    }
+
+    /// Steps after a capture record is written: metadata sync, video probing
+    /// and the frontend event. Also used for captures written without the
+    /// watcher (see `native_capture`).
+    pub(crate) fn finish_capture(capture: &Capture, db_conn: &SharedConn, app_handle: &AppHandle) {
+        metrics::increment(Counter::CapturesRouted);
+        if let Some(bug_id) = &capture.bug_id {
+            crate::queue_metadata_sync(bug_id);
+        }
+        let capture_id = &capture.id;
+        let dest_path = PathBuf::from(&capture.file_path);
+
+        // Extract duration/resolution/codec for recordings.
+        if capture.file_type == CaptureType::Video {
+            let ffprobe = video_metadata::ffprobe_path(&db_conn.lock().unwrap());
+            match video_metadata::probe(&ffprobe, &dest_path) {
+                Ok(metadata) => {
+                    let conn = db_conn.lock().unwrap();
+                    if let Err(e) = video_metadata::apply_to_capture(&conn, capture_id, &metadata) {
+                        eprintln!("CaptureWatcher: failed to store video metadata: {e}");
+                    }
+                }
+                Err(e) => eprintln!("CaptureWatcher: video metadata unavailable for {dest_path:?}: {e}"),
+            }
+        }
+
+        // Record where the bug occurred on the bug itself.
+        if let (Some(bug_id), Some(window)) = (&capture.bug_id, crate::foreground_app::from_capture(capture)) {
+            if let Err(e) = crate::foreground_app::attach_to_bug(&db_conn.lock().unwrap(), bug_id, &window) {
+                eprintln!("CaptureWatcher: failed to record foreground window: {e}");
+            }
+        }
+
+        // Box what changed since the bug's previous screenshot (if enabled).
+        if let Err(e) = crate::capture_diff::annotate_new_capture(db_conn, capture) {
+            eprintln!("CaptureWatcher: diff annotation failed for {dest_path:?}: {e}");
+        }
+
+        // Suggest splitting a bug or session that has grown too large to review.
+        crate::check_capture_quota(db_conn, capture, app_handle);
 
         // Notify the frontend.
-        let _ = app_handle.emit(
-            "screenshot:captured",
-            serde_json::json!({
-                "filePath": dest_path.to_string_lossy(),
-                "captureId": capture_id,
-                "bugId": bug_id,
-                "sessionId": session_id,
-                "timestamp": Utc::now().timestamp_millis(),
-            }),
+        let _ = events::emit(app_handle, &events::CaptureFileDetected::from_capture(capture, false));
+        let _ = events::emit(
+            app_handle,
+            &events::ScreenshotCaptured {
+                file_path: dest_path.to_string_lossy().to_string(),
+                capture_id: Some(capture_id.clone()),
+                bug_id: capture.bug_id.clone(),
+                session_id: Some(capture.session_id.clone()),
+                timestamp: Utc::now().timestamp_millis(),
+            },
+        );
+    }
+
+    /// One pass over the session's capture journal.
+    fn retry_journal(session_folder: &Path, db_conn: &SharedConn, app_handle: &AppHandle) {
+        let journal = capture_journal::journal_path(session_folder);
+        if !journal.exists() {
+            return;
+        }
+        let outcome = match capture_journal::retry(&db_conn.lock().unwrap(), &journal) {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                eprintln!("CaptureWatcher: {e}");
+                return;
+            }
+        };
+        for capture in &outcome.recovered {
+            Self::finish_capture(capture, db_conn, app_handle);
+        }
+        for lost in &outcome.lost {
+            Self::report_lost(&lost.capture, &lost.error, db_conn, app_handle);
+        }
+    }
+
+    /// Record a capture whose DB record was given up on. The file stays in
+    /// place; the audit entry and event tell the user where it is.
+    fn report_lost(capture: &Capture, error: &str, db_conn: &SharedConn, app_handle: &AppHandle) {
+        eprintln!("CaptureWatcher: giving up on the record for {:?}: {error}", capture.file_path);
+        let details = serde_json::json!({ "filePath": capture.file_path, "error": error });
+        if let Err(e) = record_audit(&db_conn.lock().unwrap(), "capture.write_lost", "session", &capture.session_id, Some(details)) {
+            eprintln!("CaptureWatcher: {e}");
+        }
+        let _ = events::emit(
+            app_handle,
+            &events::CaptureWriteLost {
+                session_id: capture.session_id.clone(),
+                capture_id: capture.id.clone(),
+                file_path: capture.file_path.clone(),
+                error: error.to_string(),
+            },
         );
     }
 
@@ -323,8 +500,18 @@ impl CaptureWatcher {
         Some(bug.folder_path)
     }
 
+    /// Return `true` when the file extension looks like a video recording.
+    pub(crate) fn is_video_file(path: &Path) -> bool {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        VIDEO_EXTENSIONS.contains(&ext.as_str())
+    }
+
     /// Return `true` when the file extension looks like an image or video.
-    fn is_media_file(path: &Path) -> bool {
+    pub(crate) fn is_media_file(path: &Path) -> bool {
         let ext = path
             .extension()
             .and_then(|e| e.to_str())
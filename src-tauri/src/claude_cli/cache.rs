@@ -0,0 +1,200 @@
+//! Response cache for Claude requests
+//!
+//! Regenerating a description after a crash or an accidental navigation
+//! repeats the same request. Responses are stored in the
+//! `claude_response_cache` table under a hash of the task, the model
+//! parameters, the prompt and the contents of every attached image, and an
+//! identical request within the TTL is answered from the cache. Requests built
+//! with [`ClaudeRequest::bypassing_cache`] always go to the API (and refresh
+//! the cached entry).
+
+use super::subprocess::ClaudeInvoker;
+use super::types::{ClaudeError, ClaudeRequest, ClaudeResponse};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long a cached response is reused when no TTL is configured.
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Hash identifying a request: task, model parameters, prompt, and the
+/// SHA-256 of each image in order.
+pub fn cache_key(request: &ClaudeRequest) -> Result<String, ClaudeError> {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{:?}", request.task).as_bytes());
+    hasher.update([0]);
+    hasher.update(serde_json::to_string(&request.params).unwrap_or_default().as_bytes());
+    hasher.update([0]);
+    hasher.update(request.prompt.as_bytes());
+    for path in &request.image_paths {
+        let bytes = std::fs::read(path).map_err(|e| {
+            ClaudeError::InvocationFailed(format!("Failed to read image {}: {}", path.display(), e))
+        })?;
+        hasher.update([0]);
+        hasher.update(Sha256::digest(&bytes));
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Cached content for `key` if it was stored within `ttl` of `now`.
+fn lookup(conn: &Connection, key: &str, ttl: Duration, now: DateTime<Utc>) -> rusqlite::Result<Option<String>> {
+    let row: Option<(String, String)> = conn
+        .query_row(
+            "SELECT content, created_at FROM claude_response_cache WHERE cache_key = ?1",
+            params![key],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+
+    Ok(row.and_then(|(content, created_at)| {
+        let created = DateTime::parse_from_rfc3339(&created_at).ok()?.with_timezone(&Utc);
+        let age = now.signed_duration_since(created).to_std().ok()?;
+        (age <= ttl).then_some(content)
+    }))
+}
+
+fn store(conn: &Connection, key: &str, response: &ClaudeResponse, now: DateTime<Utc>) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO claude_response_cache (cache_key, task, content, created_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![key, format!("{:?}", response.task), response.content, now.to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// Invoker that answers repeated requests from the response cache.
+pub struct CachedClaudeInvoker {
+    inner: Arc<dyn ClaudeInvoker>,
+    db: Arc<Mutex<Connection>>,
+    ttl: Duration,
+}
+
+impl CachedClaudeInvoker {
+    pub fn new(inner: Arc<dyn ClaudeInvoker>, db: Arc<Mutex<Connection>>) -> Self {
+        Self {
+            inner,
+            db,
+            ttl: DEFAULT_CACHE_TTL,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+impl ClaudeInvoker for CachedClaudeInvoker {
+    fn invoke(&self, request: ClaudeRequest) -> Result<ClaudeResponse, ClaudeError> {
+        let key = cache_key(&request)?;
+
+        if !request.bypass_cache {
+            let cached = lookup(&self.db.lock().unwrap(), &key, self.ttl, Utc::now());
+            match cached {
+                Ok(Some(content)) => {
+                    return Ok(ClaudeResponse {
+                        content,
+                        task: request.task,
+                        bug_id: request.bug_id,
+                        cached: true,
+                    })
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("Claude response cache lookup failed: {}", e),
+            }
+        }
+
+        let response = self.inner.invoke(request)?;
+        // The database lock is only taken after the API call returns
+        if let Err(e) = store(&self.db.lock().unwrap(), &key, &response, Utc::now()) {
+            eprintln!("Failed to cache Claude response: {}", e);
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude_cli::types::PromptTask;
+    use crate::database::init_database;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingInvoker {
+        calls: AtomicUsize,
+    }
+
+    impl ClaudeInvoker for CountingInvoker {
+        fn invoke(&self, request: ClaudeRequest) -> Result<ClaudeResponse, ClaudeError> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(ClaudeResponse {
+                content: format!("response {}", n),
+                task: request.task,
+                bug_id: request.bug_id,
+                cached: false,
+            })
+        }
+    }
+
+    fn setup() -> (Arc<CountingInvoker>, CachedClaudeInvoker) {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let counter = Arc::new(CountingInvoker { calls: AtomicUsize::new(0) });
+        let cached = CachedClaudeInvoker::new(counter.clone(), Arc::new(Mutex::new(conn)));
+        (counter, cached)
+    }
+
+    #[test]
+    fn test_identical_requests_are_served_from_cache() {
+        let (counter, invoker) = setup();
+        let request = || ClaudeRequest::new_text("Describe".to_string(), PromptTask::DescribeBug);
+
+        let first = invoker.invoke(request()).unwrap();
+        let second = invoker.invoke(request().with_bug_id("bug-1".to_string())).unwrap();
+        assert!(!first.cached);
+        assert!(second.cached);
+        assert_eq!(second.content, "response 1");
+        assert_eq!(second.bug_id.as_deref(), Some("bug-1"));
+
+        let refreshed = invoker.invoke(request().bypassing_cache()).unwrap();
+        assert_eq!(refreshed.content, "response 2");
+        assert_eq!(invoker.invoke(request()).unwrap().content, "response 2");
+
+        invoker
+            .invoke(ClaudeRequest::new_text("Describe".to_string(), PromptTask::RefineDescription))
+            .unwrap();
+        assert_eq!(counter.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_expired_entries_and_changed_images_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("capture-001.png");
+        std::fs::write(&image, b"first").unwrap();
+        let request = || ClaudeRequest::new_with_images("Parse".to_string(), vec![image.clone()], PromptTask::ParseConsole);
+
+        let key = cache_key(&request()).unwrap();
+        let other_model = request().with_params(crate::claude_cli::ModelParams {
+            model: Some("claude-haiku".to_string()),
+            ..Default::default()
+        });
+        assert_ne!(cache_key(&other_model).unwrap(), key);
+        std::fs::write(&image, b"second").unwrap();
+        assert_ne!(cache_key(&request()).unwrap(), key);
+
+        let (_, invoker) = setup();
+        let conn = invoker.db.lock().unwrap();
+        let response = ClaudeResponse {
+            content: "old".to_string(),
+            task: PromptTask::ParseConsole,
+            bug_id: None,
+            cached: false,
+        };
+        let stored_at = Utc::now() - chrono::Duration::hours(2);
+        store(&conn, &key, &response, stored_at).unwrap();
+        assert_eq!(lookup(&conn, &key, Duration::from_secs(3 * 3600), Utc::now()).unwrap().as_deref(), Some("old"));
+        assert_eq!(lookup(&conn, &key, Duration::from_secs(3600), Utc::now()).unwrap(), None);
+    }
+}
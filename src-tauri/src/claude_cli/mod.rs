@@ -8,6 +8,10 @@
 //! - Construct focused prompts with bug data (screenshots, notes, metadata)
 //! - Call Anthropic Messages API with timeout and error handling
 //! - Queue multiple requests (max 1 concurrent)
+//! - Cache responses to identical requests
+//! - Per-task model, token and temperature settings
+//! - Severity suggestions against a team rubric
+//! - Translation of notes to the team's ticket language
 //! - Parse and return responses
 //! - Graceful degradation when no credentials configured
 
@@ -16,13 +20,24 @@ use std::sync::Mutex;
 mod types;
 mod subprocess;
 mod prompts;
+mod cache;
+mod models;
+mod severity;
+mod translation;
 
 #[cfg(test)]
 mod tests;
 
 pub use types::{ClaudeError, ClaudeStatus, BugContext, PromptTask, ClaudeResponse, ClaudeRequest, ClaudeCredentials, CaptureAssignmentSuggestion};
-pub use subprocess::{ClaudeInvoker, RealClaudeInvoker};
+pub use subprocess::{ClaudeInvoker, RealClaudeInvoker, DEFAULT_MAX_IMAGE_DIMENSION};
 pub use prompts::{PromptBuilder, BugSummary};
+pub use cache::{CachedClaudeInvoker, DEFAULT_CACHE_TTL};
+pub use models::{AiModelSettings, ModelInfo, ModelParams, ModelTask, MODEL_SETTINGS_KEY};
+pub use severity::{
+    levels_for_session, load_rubric, parse_severity_response, validate_rubric, SeverityContext,
+    SeveritySuggestion, SEVERITY_RUBRIC_KEY,
+};
+pub use translation::{TranslationSettings, Translator, TRANSLATION_KEY};
 
 /// Global Claude status
 static CLAUDE_STATUS: Mutex<Option<ClaudeStatus>> = Mutex::new(None);
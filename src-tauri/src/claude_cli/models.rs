@@ -0,0 +1,246 @@
+//! Model selection and sampling parameters for AI tasks
+//!
+//! Each task (describe, parse console, summarize, refine, classify,
+//! translate) can use its own model, max-token budget and temperature. The
+//! settings are stored as one JSON value under `claude.model_settings`; unset
+//! fields fall back to the built-in defaults. Commands may pass per-request
+//! overrides, which win over the stored settings.
+
+use crate::database::{SettingsOps, SettingsRepository};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// Settings key holding [`AiModelSettings`] as JSON.
+pub const MODEL_SETTINGS_KEY: &str = "claude.model_settings";
+
+/// Model used when neither the settings nor the request name one.
+pub const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
+
+/// Response token budget used when none is configured.
+pub const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// Largest accepted `max_tokens` value.
+pub const MAX_TOKENS_LIMIT: u32 = 64_000;
+
+/// AI tasks with their own model settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelTask {
+    Describe,
+    ParseConsole,
+    Summarize,
+    Refine,
+    Classify,
+    Translate,
+}
+
+/// Model and sampling parameters; unset fields fall back to the next level.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+}
+
+impl ModelParams {
+    /// `self`, with unset fields taken from `fallback`.
+    pub fn or(self, fallback: &ModelParams) -> ModelParams {
+        ModelParams {
+            model: self.model.or_else(|| fallback.model.clone()),
+            max_tokens: self.max_tokens.or(fallback.max_tokens),
+            temperature: self.temperature.or(fallback.temperature),
+        }
+    }
+
+    /// Check the numeric ranges accepted by the API.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(model) = &self.model {
+            if model.trim().is_empty() {
+                return Err("Model name must not be empty".to_string());
+            }
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            if max_tokens == 0 || max_tokens > MAX_TOKENS_LIMIT {
+                return Err(format!("Max tokens must be between 1 and {}", MAX_TOKENS_LIMIT));
+            }
+        }
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=1.0).contains(&temperature) {
+                return Err("Temperature must be between 0 and 1".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Per-task model settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AiModelSettings {
+    pub describe: ModelParams,
+    pub parse_console: ModelParams,
+    pub summarize: ModelParams,
+    pub refine: ModelParams,
+    pub classify: ModelParams,
+    pub translate: ModelParams,
+}
+
+impl AiModelSettings {
+    pub fn for_task(&self, task: ModelTask) -> &ModelParams {
+        match task {
+            ModelTask::Describe => &self.describe,
+            ModelTask::ParseConsole => &self.parse_console,
+            ModelTask::Summarize => &self.summarize,
+            ModelTask::Refine => &self.refine,
+            ModelTask::Classify => &self.classify,
+            ModelTask::Translate => &self.translate,
+        }
+    }
+
+    /// Parameters for one request: `overrides` first, then the task's settings.
+    pub fn resolve(&self, task: ModelTask, overrides: Option<ModelParams>) -> ModelParams {
+        overrides.unwrap_or_default().or(self.for_task(task))
+    }
+
+    /// Stored settings; missing or unreadable settings give the defaults.
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(MODEL_SETTINGS_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(MODEL_SETTINGS_KEY, &json)
+            .map_err(|e| format!("Failed to save model settings: {}", e))
+    }
+
+    /// Models named by any task, without duplicates.
+    pub fn models(&self) -> Vec<String> {
+        let mut models: Vec<String> = Vec::new();
+        for task in [
+            ModelTask::Describe,
+            ModelTask::ParseConsole,
+            ModelTask::Summarize,
+            ModelTask::Refine,
+            ModelTask::Classify,
+            ModelTask::Translate,
+        ] {
+            if let Some(model) = &self.for_task(task).model {
+                if !models.contains(model) {
+                    models.push(model.clone());
+                }
+            }
+        }
+        models
+    }
+
+    /// Check every task's parameters, requiring configured models to appear in
+    /// `known_models`. All problems are reported together.
+    pub fn validate(&self, known_models: &[String]) -> Result<(), String> {
+        let tasks = [
+            ("describe", &self.describe),
+            ("parse console", &self.parse_console),
+            ("summarize", &self.summarize),
+            ("refine", &self.refine),
+            ("classify", &self.classify),
+            ("translate", &self.translate),
+        ];
+        let problems: Vec<String> = tasks
+            .iter()
+            .filter_map(|(name, params)| {
+                let result = params.validate().and_then(|_| match &params.model {
+                    Some(model) if !known_models.contains(model) => Err(format!("Unknown model '{}'", model)),
+                    _ => Ok(()),
+                });
+                result.err().map(|e| format!("{}: {}", name, e))
+            })
+            .collect();
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
+    }
+}
+
+/// A model offered by the provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+    pub id: String,
+    pub display_name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_prefers_overrides_then_task_settings() {
+        let settings = AiModelSettings {
+            summarize: ModelParams {
+                model: Some("claude-haiku".to_string()),
+                max_tokens: Some(1024),
+                temperature: None,
+            },
+            ..Default::default()
+        };
+
+        let resolved = settings.resolve(
+            ModelTask::Summarize,
+            Some(ModelParams { temperature: Some(0.2), max_tokens: Some(512), model: None }),
+        );
+        assert_eq!(resolved.model.as_deref(), Some("claude-haiku"));
+        assert_eq!(resolved.max_tokens, Some(512));
+        assert_eq!(resolved.temperature, Some(0.2));
+        assert_eq!(settings.resolve(ModelTask::Describe, None), ModelParams::default());
+    }
+
+    #[test]
+    fn test_validate_reports_unknown_models_and_ranges() {
+        let known = vec!["claude-sonnet".to_string()];
+        let mut settings = AiModelSettings::default();
+        settings.describe.model = Some("claude-sonnet".to_string());
+        assert!(settings.validate(&known).is_ok());
+
+        settings.refine.model = Some("gpt-4".to_string());
+        settings.parse_console.temperature = Some(1.5);
+        settings.summarize.max_tokens = Some(0);
+        let err = settings.validate(&known).unwrap_err();
+        assert!(err.contains("refine: Unknown model 'gpt-4'"));
+        assert!(err.contains("parse console: Temperature must be between 0 and 1"));
+        assert!(err.contains("summarize: Max tokens must be between 1"));
+    }
+
+    #[test]
+    fn test_models_lists_each_configured_model_once() {
+        let mut settings = AiModelSettings::default();
+        assert!(settings.models().is_empty());
+        settings.describe.model = Some("claude-sonnet".to_string());
+        settings.translate.model = Some("claude-haiku".to_string());
+        settings.refine.model = Some("claude-sonnet".to_string());
+        assert_eq!(settings.models(), vec!["claude-sonnet".to_string(), "claude-haiku".to_string()]);
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        assert_eq!(AiModelSettings::load(&conn), AiModelSettings::default());
+
+        let mut settings = AiModelSettings::default();
+        settings.parse_console.max_tokens = Some(2048);
+        settings.save(&conn).unwrap();
+        assert_eq!(AiModelSettings::load(&conn), settings);
+    }
+}
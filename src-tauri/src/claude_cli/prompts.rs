@@ -6,6 +6,7 @@
 //! - Description refinement
 //! - Capture-to-bug assignment suggestion
 
+use super::severity::SeverityContext;
 use super::types::{BugContext, PromptTask};
 
 /// Summary of a bug used in capture assignment prompts
@@ -52,6 +53,17 @@ impl PromptBuilder {
             }
         }
 
+        // Real control names beat names guessed from pixels
+        if let Some(tree) = context.ui_tree.as_deref().filter(|t| !t.trim().is_empty()) {
+            prompt.push_str("UI elements of the focused window (from its accessibility tree). ");
+            prompt.push_str("Refer to controls by these names and include AutomationIds where relevant:\n");
+            prompt.push_str(&format!("```\n{}\n```\n\n", tree));
+        }
+
+        if let Some(glossary) = &context.glossary {
+            prompt.push_str(&Self::glossary_section(glossary));
+        }
+
         // Add screenshot count
         let screenshot_count = context.screenshot_paths.len();
         if screenshot_count > 0 {
@@ -86,6 +98,18 @@ impl PromptBuilder {
         prompt
     }
 
+    /// Instructions to spell out project abbreviations, given as
+    /// "ABBR: expansion" lines, for readers outside the team.
+    pub fn glossary_section(glossary: &str) -> String {
+        if glossary.trim().is_empty() {
+            return String::new();
+        }
+        format!(
+            "Project abbreviations (write out the full name at first use, e.g. \"CPQ (Configure, Price, Quote)\"):\n{}\n\n",
+            glossary.trim()
+        )
+    }
+
     /// Build a prompt for console screenshot parsing
     pub fn build_console_parse_prompt() -> String {
         let mut prompt = String::new();
@@ -97,6 +121,7 @@ impl PromptBuilder {
         prompt.push_str("{\n");
         prompt.push_str("  \"errors\": [\"error message 1\", \"error message 2\"],\n");
         prompt.push_str("  \"warnings\": [\"warning message 1\", \"warning message 2\"],\n");
+        prompt.push_str("  \"stack_traces\": [\"full stack trace 1\"],\n");
         prompt.push_str("  \"logs\": [\"important log 1\", \"important log 2\"]\n");
         prompt.push_str("}\n\n");
 
@@ -106,6 +131,24 @@ impl PromptBuilder {
         prompt
     }
 
+    /// Build a prompt asking for screen-reader alt text for a screenshot
+    pub fn build_alt_text_prompt(bug_title: Option<&str>) -> String {
+        let mut prompt = String::new();
+
+        prompt.push_str("Write alt text for this screenshot from a software bug report, ");
+        prompt.push_str("for readers using a screen reader.\n\n");
+        if let Some(title) = bug_title.filter(|t| !t.trim().is_empty()) {
+            prompt.push_str(&format!("The bug is titled: {}\n\n", title.trim()));
+        }
+        prompt.push_str("Describe what the screenshot shows that matters for the bug: the screen or dialog, ");
+        prompt.push_str("any error messages (quoted exactly), and anything marked with annotations. ");
+        prompt.push_str("Use one to three plain sentences, at most 300 characters. ");
+        prompt.push_str("Do not start with \"Screenshot of\" or \"Image of\". ");
+        prompt.push_str("Reply with the alt text only.\n");
+
+        prompt
+    }
+
     /// Build a prompt for description refinement
     pub fn build_refinement_prompt(
         current_description: &str,
@@ -178,6 +221,38 @@ impl PromptBuilder {
         prompt
     }
 
+    /// Build a prompt asking for a severity suggestion, as JSON, judged by
+    /// the team's rubric and limited to `levels`.
+    pub fn build_severity_prompt(context: &SeverityContext, rubric: &str, levels: &[String]) -> String {
+        let mut prompt = String::new();
+
+        prompt.push_str("You are a QA lead triaging a bug report. Suggest its severity using the team's rubric.\n\n");
+        prompt.push_str("Severity rubric:\n---\n");
+        prompt.push_str(rubric.trim());
+        prompt.push_str("\n---\n\n");
+
+        prompt.push_str(&format!("Bug: {}\n", context.display_id));
+        if let Some(title) = context.title.as_deref().filter(|t| !t.trim().is_empty()) {
+            prompt.push_str(&format!("Title: {}\n", title));
+        }
+        prompt.push_str(&format!("Captures attached to the bug: {}\n\n", context.capture_count));
+        match context.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            Some(description) => prompt.push_str(&format!("Description:\n{}\n\n", description)),
+            None => prompt.push_str("No description has been written yet.\n\n"),
+        }
+        if let Some(console) = context.console_parse.as_deref().filter(|c| !c.trim().is_empty()) {
+            prompt.push_str(&format!("Parsed console output:\n```\n{}\n```\n\n", console));
+        }
+
+        prompt.push_str("Respond with ONLY a JSON object (no markdown fences, no explanation outside the JSON):\n\n");
+        prompt.push_str("{\n");
+        prompt.push_str(&format!("  \"severity\": one of {},\n", levels.iter().map(|l| format!("\"{}\"", l)).collect::<Vec<_>>().join(", ")));
+        prompt.push_str("  \"rationale\": \"One or two sentences citing the rubric.\"\n");
+        prompt.push_str("}\n");
+
+        prompt
+    }
+
     /// Build a custom prompt (user-provided)
     pub fn build_custom_prompt(user_prompt: &str) -> String {
         user_prompt.to_string()
@@ -234,6 +309,8 @@ mod tests {
             meeting_id: None,
             environment: None,
             bug_type: None,
+            ui_tree: None,
+            glossary: None,
         };
 
         let prompt = PromptBuilder::build_bug_description_prompt(&context);
@@ -259,11 +336,17 @@ mod tests {
             meeting_id: Some("SESSION-001".to_string()),
             environment: Some("Windows 11".to_string()),
             bug_type: Some("bug".to_string()),
+            ui_tree: Some("button \"Submit\" [AutomationId=btnSubmit]".to_string()),
+            glossary: Some("CPQ: Configure, Price, Quote".to_string()),
         };
 
         let prompt = PromptBuilder::build_bug_description_prompt(&context);
 
         assert!(prompt.contains("Application: TestApp"));
+        assert!(prompt.contains("accessibility tree"));
+        assert!(prompt.contains("[AutomationId=btnSubmit]"));
+        assert!(prompt.contains("Project abbreviations"));
+        assert!(prompt.contains("CPQ: Configure, Price, Quote"));
         assert!(prompt.contains("Version: 1.2.3"));
         assert!(prompt.contains("Environment: Windows 11"));
         assert!(prompt.contains("Session/Meeting ID: SESSION-001"));
@@ -271,6 +354,27 @@ mod tests {
         assert!(prompt.contains("2 screenshot(s)"));
     }
 
+    #[test]
+    fn test_build_severity_prompt() {
+        let context = SeverityContext {
+            display_id: "BUG-004".to_string(),
+            title: Some("Checkout button does nothing".to_string()),
+            description: None,
+            console_parse: Some(r#"{"errors":["TypeError: cart is undefined"]}"#.to_string()),
+            capture_count: 3,
+        };
+        let levels = vec!["S1".to_string(), "S2".to_string()];
+
+        let prompt = PromptBuilder::build_severity_prompt(&context, "S1: outage\nS2: other", &levels);
+
+        assert!(prompt.contains("S1: outage\nS2: other"));
+        assert!(prompt.contains("Title: Checkout button does nothing"));
+        assert!(prompt.contains("Captures attached to the bug: 3"));
+        assert!(prompt.contains("No description has been written yet."));
+        assert!(prompt.contains("TypeError: cart is undefined"));
+        assert!(prompt.contains(r#""severity": one of "S1", "S2""#));
+    }
+
     #[test]
     fn test_build_console_parse_prompt() {
         let prompt = PromptBuilder::build_console_parse_prompt();
@@ -313,6 +417,8 @@ mod tests {
             meeting_id: None,
             environment: None,
             bug_type: None,
+            ui_tree: None,
+            glossary: None,
         };
 
         let prompt = PromptBuilder::build_prompt(
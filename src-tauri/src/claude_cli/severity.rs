@@ -0,0 +1,190 @@
+//! AI-suggested bug severity
+//!
+//! `classify_bug_severity` sends a bug's description, parsed console output
+//! and capture count to the model together with the team's severity rubric
+//! (stored under `claude.severity_rubric`, editable text). The answer is kept
+//! as a suggestion next to the bug; it never changes the bug's own severity.
+
+use crate::database::{SessionOps, SessionRepository, SettingsOps, SettingsRepository};
+use crate::profile::{ProfileRepository, QaProfile, SqliteProfileRepository, ACTIVE_PROFILE_KEY};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use super::types::{parse_json_response, ClaudeError};
+
+/// Settings key holding the severity rubric text.
+pub const SEVERITY_RUBRIC_KEY: &str = "claude.severity_rubric";
+
+/// Levels offered when the profile does not define `severity` options.
+pub const DEFAULT_SEVERITY_LEVELS: &[&str] = &["critical", "high", "medium", "low"];
+
+/// Rubric used until the team saves its own.
+pub const DEFAULT_SEVERITY_RUBRIC: &str = "\
+critical: data loss, security issue, crash, or a core workflow is blocked with no workaround.
+high: a core workflow is broken or wrong, but a workaround exists.
+medium: a secondary feature misbehaves, or a core one is degraded without blocking work.
+low: cosmetic issues, typos, minor layout problems.";
+
+/// Largest accepted rubric, to keep prompts small.
+pub const MAX_RUBRIC_LEN: usize = 8_000;
+
+/// The stored rubric, or [`DEFAULT_SEVERITY_RUBRIC`].
+pub fn load_rubric(conn: &Connection) -> String {
+    SettingsRepository::new(conn)
+        .get(SEVERITY_RUBRIC_KEY)
+        .ok()
+        .flatten()
+        .filter(|rubric| !rubric.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_SEVERITY_RUBRIC.to_string())
+}
+
+pub fn validate_rubric(rubric: &str) -> Result<(), String> {
+    if rubric.trim().is_empty() {
+        return Err("The severity rubric cannot be empty".to_string());
+    }
+    if rubric.len() > MAX_RUBRIC_LEN {
+        return Err(format!("The severity rubric must be at most {} characters", MAX_RUBRIC_LEN));
+    }
+    Ok(())
+}
+
+/// Allowed levels: the options of the profile's `severity` field, or
+/// [`DEFAULT_SEVERITY_LEVELS`].
+pub fn severity_levels(profile: Option<&QaProfile>) -> Vec<String> {
+    profile
+        .and_then(|p| p.custom_fields.iter().find(|f| f.key == "severity"))
+        .and_then(|field| field.options.clone())
+        .filter(|options| !options.is_empty())
+        .unwrap_or_else(|| DEFAULT_SEVERITY_LEVELS.iter().map(|l| l.to_string()).collect())
+}
+
+/// [`severity_levels`] for the session's profile, or the active profile when
+/// the session has none.
+pub fn levels_for_session(conn: &Connection, session_id: &str) -> Vec<String> {
+    let profile_id = SessionRepository::new(conn)
+        .get(session_id)
+        .ok()
+        .flatten()
+        .and_then(|session| session.profile_id)
+        .or_else(|| SettingsRepository::new(conn).get(ACTIVE_PROFILE_KEY).ok().flatten());
+    let profile = profile_id.and_then(|id| SqliteProfileRepository::new(conn).get(&id).ok().flatten());
+    severity_levels(profile.as_ref())
+}
+
+/// What the model is told about a bug.
+pub struct SeverityContext {
+    pub display_id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Parsed console output (errors, warnings, stack traces) as JSON or text
+    pub console_parse: Option<String>,
+    pub capture_count: usize,
+}
+
+/// Suggested severity for a bug. Stored as JSON in `bugs.severity_suggestion`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeveritySuggestion {
+    pub bug_id: String,
+    /// One of the allowed levels
+    pub severity: String,
+    pub rationale: String,
+    pub suggested_at: String,
+}
+
+/// Read the model's JSON answer, accepting only one of `levels`.
+pub fn parse_severity_response(
+    bug_id: &str,
+    content: &str,
+    levels: &[String],
+    suggested_at: &str,
+) -> Result<SeveritySuggestion, ClaudeError> {
+    let parsed = parse_json_response(content)?;
+
+    let answer = parsed.get("severity").and_then(|v| v.as_str()).unwrap_or_default().trim();
+    let severity = levels
+        .iter()
+        .find(|level| level.eq_ignore_ascii_case(answer))
+        .ok_or_else(|| ClaudeError::ParseError(format!("Unknown severity \"{}\"", answer)))?;
+    let rationale = parsed
+        .get("rationale")
+        .and_then(|v| v.as_str())
+        .unwrap_or("No rationale provided")
+        .to_string();
+
+    Ok(SeveritySuggestion {
+        bug_id: bug_id.to_string(),
+        severity: severity.clone(),
+        rationale,
+        suggested_at: suggested_at.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::{CustomFieldType, CustomMetadataField};
+
+    fn levels() -> Vec<String> {
+        DEFAULT_SEVERITY_LEVELS.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_severity_response() {
+        let suggestion = parse_severity_response(
+            "bug-1",
+            "```json\n{\"severity\": \"High\", \"rationale\": \"Checkout fails; retrying works\"}\n```",
+            &levels(),
+            "2024-01-01T10:00:00Z",
+        )
+        .unwrap();
+        assert_eq!(suggestion.severity, "high");
+        assert_eq!(suggestion.rationale, "Checkout fails; retrying works");
+
+        let unknown = parse_severity_response("bug-1", r#"{"severity": "blocker"}"#, &levels(), "");
+        assert!(matches!(unknown, Err(ClaudeError::ParseError(_))));
+        assert!(parse_severity_response("bug-1", "It is high", &levels(), "").is_err());
+    }
+
+    #[test]
+    fn test_severity_levels_follow_profile_field() {
+        assert_eq!(severity_levels(None), levels());
+
+        let mut profile = QaProfile {
+            id: "p-1".to_string(),
+            name: "Team".to_string(),
+            linear_config: None,
+            area_categories: Vec::new(),
+            custom_fields: vec![CustomMetadataField {
+                key: "severity".to_string(),
+                label: "Severity".to_string(),
+                field_type: CustomFieldType::Select,
+                default_value: None,
+                required: false,
+                options: Some(vec!["S1".to_string(), "S2".to_string(), "S3".to_string()]),
+            }],
+            title_conventions: None,
+            post_session_actions: Vec::new(),
+            glossary: Vec::new(),
+            glossary_style: Default::default(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        assert_eq!(severity_levels(Some(&profile)), vec!["S1", "S2", "S3"]);
+
+        profile.custom_fields[0].options = Some(Vec::new());
+        assert_eq!(severity_levels(Some(&profile)), levels());
+    }
+
+    #[test]
+    fn test_rubric_defaults_and_validation() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        assert_eq!(load_rubric(&conn), DEFAULT_SEVERITY_RUBRIC);
+
+        SettingsRepository::new(&conn).set(SEVERITY_RUBRIC_KEY, "S1: outage\nS2: everything else").unwrap();
+        assert_eq!(load_rubric(&conn), "S1: outage\nS2: everything else");
+        assert!(validate_rubric("  ").is_err());
+        assert!(validate_rubric(&"x".repeat(MAX_RUBRIC_LEN + 1)).is_err());
+    }
+}
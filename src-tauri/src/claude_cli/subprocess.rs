@@ -2,14 +2,18 @@
 //!
 //! Handles:
 //! - Building multimodal API requests (text + images)
+//! - Downscaling large screenshots before upload and rejecting non-image attachments
 //! - Authentication via API key or OAuth token
 //! - Response parsing
 //! - Timeout enforcement
 //! - Queue management (max 1 concurrent request)
 
+use super::models::{ModelInfo, DEFAULT_MAX_TOKENS, DEFAULT_MODEL};
 use super::types::{ClaudeCredentials, ClaudeError, ClaudeRequest, ClaudeResponse};
 use base64::Engine;
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, ImageFormat};
 use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
@@ -19,19 +23,107 @@ pub trait ClaudeInvoker: Send + Sync {
     fn invoke(&self, request: ClaudeRequest) -> Result<ClaudeResponse, ClaudeError>;
 }
 
+/// Longest image edge sent to the API; larger images are scaled down.
+/// The API resizes anything bigger anyway, so extra pixels only cost upload time.
+pub const DEFAULT_MAX_IMAGE_DIMENSION: u32 = 1568;
+
+/// Per-image size limit of the Messages API (base64 payload excluded).
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Quality for screenshots that have to be re-encoded as JPEG to fit.
+const JPEG_QUALITY: u8 = 85;
+
+/// An attachment ready to be sent as an image content block.
+#[derive(Debug)]
+pub(crate) struct PreparedImage {
+    pub media_type: &'static str,
+    pub data: Vec<u8>,
+}
+
+/// Read an image attachment, scaling it down so neither edge exceeds
+/// `max_dimension` and re-encoding it when it is still over the API limit.
+/// Images already within bounds are sent unchanged.
+pub(crate) fn prepare_image(path: &Path, max_dimension: u32) -> Result<PreparedImage, String> {
+    let format = ImageFormat::from_path(path)
+        .ok()
+        .filter(|f| matches!(f, ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::WebP))
+        .ok_or("not a supported image type (png, jpg, gif, webp)")?;
+    let bytes = std::fs::read(path).map_err(|e| format!("cannot be read: {}", e))?;
+    let (width, height) = image::ImageReader::with_format(std::io::Cursor::new(&bytes), format)
+        .into_dimensions()
+        .map_err(|e| format!("cannot be decoded: {}", e))?;
+
+    if width.max(height) <= max_dimension && bytes.len() <= MAX_IMAGE_BYTES {
+        return Ok(PreparedImage { media_type: format.to_mime_type(), data: bytes });
+    }
+
+    let mut img = image::load_from_memory_with_format(&bytes, format)
+        .map_err(|e| format!("cannot be decoded: {}", e))?;
+    if width.max(height) > max_dimension {
+        img = img.resize(max_dimension, max_dimension, FilterType::Triangle);
+    }
+
+    // PNG keeps screenshot text crisp; fall back to JPEG when that is still too big
+    let mut png = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("cannot be re-encoded: {}", e))?;
+    if png.len() <= MAX_IMAGE_BYTES {
+        return Ok(PreparedImage { media_type: "image/png", data: png });
+    }
+    let mut jpeg = Vec::new();
+    img.to_rgb8()
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY))
+        .map_err(|e| format!("cannot be re-encoded: {}", e))?;
+    if jpeg.len() > MAX_IMAGE_BYTES {
+        return Err(format!("is still {} bytes after compression", jpeg.len()));
+    }
+    Ok(PreparedImage { media_type: "image/jpeg", data: jpeg })
+}
+
+/// Prepare every attachment of a request. Nothing is sent when any file has
+/// to be excluded; the error lists each excluded file and why.
+pub(crate) fn prepare_images(paths: &[PathBuf], max_dimension: u32) -> Result<Vec<PreparedImage>, ClaudeError> {
+    let mut prepared = Vec::with_capacity(paths.len());
+    let mut excluded = Vec::new();
+    for path in paths {
+        match prepare_image(path, max_dimension) {
+            Ok(image) => prepared.push(image),
+            Err(reason) => excluded.push(format!("{} ({})", path.display(), reason)),
+        }
+    }
+    if excluded.is_empty() {
+        Ok(prepared)
+    } else {
+        Err(ClaudeError::AttachmentsExcluded { files: excluded })
+    }
+}
+
 /// Real implementation that calls the Anthropic Messages API via HTTP
 pub struct RealClaudeInvoker {
     /// Pre-loaded credentials — set at construction time from settings + OAuth fallback
     credentials: ClaudeCredentials,
+    /// Longest image edge sent to the API
+    max_image_dimension: u32,
 }
 
 impl RealClaudeInvoker {
     pub fn new(credentials: ClaudeCredentials) -> Self {
-        Self { credentials }
+        Self {
+            credentials,
+            max_image_dimension: DEFAULT_MAX_IMAGE_DIMENSION,
+        }
+    }
+
+    pub fn with_max_image_dimension(mut self, max_image_dimension: u32) -> Self {
+        self.max_image_dimension = max_image_dimension.max(1);
+        self
     }
 
     /// Call the Anthropic Messages API
     fn call_anthropic_api(&self, request: &ClaudeRequest) -> Result<ClaudeResponse, ClaudeError> {
+        // Check attachments before anything goes over the network
+        let images = prepare_images(&request.image_paths, self.max_image_dimension)?;
+
         let client = reqwest::blocking::Client::builder()
             .timeout(Duration::from_secs(request.timeout_secs))
             .build()
@@ -41,31 +133,13 @@ impl RealClaudeInvoker {
         let mut content = Vec::new();
 
         // Add images as base64-encoded content blocks
-        for image_path in &request.image_paths {
-            let bytes = std::fs::read(image_path)
-                .map_err(|e| ClaudeError::InvocationFailed(format!(
-                    "Failed to read image {}: {}", image_path.display(), e
-                )))?;
-            let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
-
-            let ext = image_path
-                .extension()
-                .and_then(|e| e.to_str())
-                .unwrap_or("png")
-                .to_lowercase();
-            let media_type = match ext.as_str() {
-                "jpg" | "jpeg" => "image/jpeg",
-                "gif" => "image/gif",
-                "webp" => "image/webp",
-                _ => "image/png", // default to PNG
-            };
-
+        for image in &images {
             content.push(serde_json::json!({
                 "type": "image",
                 "source": {
                     "type": "base64",
-                    "media_type": media_type,
-                    "data": b64
+                    "media_type": image.media_type,
+                    "data": base64::engine::general_purpose::STANDARD.encode(&image.data)
                 }
             }));
         }
@@ -76,14 +150,17 @@ impl RealClaudeInvoker {
             "text": request.prompt
         }));
 
-        let body = serde_json::json!({
-            "model": "claude-sonnet-4-20250514",
-            "max_tokens": 4096,
+        let mut body = serde_json::json!({
+            "model": request.params.model.as_deref().unwrap_or(DEFAULT_MODEL),
+            "max_tokens": request.params.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
             "messages": [{
                 "role": "user",
                 "content": content
             }]
         });
+        if let Some(temperature) = request.params.temperature {
+            body["temperature"] = serde_json::json!(temperature);
+        }
 
         // Build the request with OAuth bearer auth
         let req_builder = client
@@ -116,20 +193,7 @@ impl RealClaudeInvoker {
             .map_err(|e| ClaudeError::ApiError(format!("Failed to read response body: {}", e)))?;
 
         if !status.is_success() {
-            if status.as_u16() == 401 {
-                return Err(ClaudeError::NotAuthenticated(
-                    "Invalid or expired API credentials. Check your API key.".to_string(),
-                ));
-            }
-            if status.as_u16() == 429 {
-                return Err(ClaudeError::ApiError(
-                    "Rate limit exceeded. Please wait and try again.".to_string(),
-                ));
-            }
-            return Err(ClaudeError::ApiError(format!(
-                "HTTP {}: {}",
-                status, resp_text
-            )));
+            return Err(status_error(status, &resp_text));
         }
 
         // Parse Messages API response: { "content": [{ "type": "text", "text": "..." }] }
@@ -153,12 +217,64 @@ impl RealClaudeInvoker {
             content: text.to_string(),
             task: request.task.clone(),
             bug_id: request.bug_id.clone(),
+            cached: false,
         })
     }
 }
 
+impl RealClaudeInvoker {
+    /// Models available to these credentials, newest first.
+    pub fn list_models(&self) -> Result<Vec<ModelInfo>, ClaudeError> {
+        let response = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .map_err(|e| ClaudeError::ApiError(format!("Failed to create HTTP client: {}", e)))?
+            .get("https://api.anthropic.com/v1/models?limit=1000")
+            .header("anthropic-version", "2023-06-01")
+            .header("Authorization", format!("Bearer {}", self.credentials.access_token))
+            .send()
+            .map_err(|e| ClaudeError::ApiError(format!("HTTP request failed: {}", e)))?;
+
+        let status = response.status();
+        let resp_text = response
+            .text()
+            .map_err(|e| ClaudeError::ApiError(format!("Failed to read response body: {}", e)))?;
+        if !status.is_success() {
+            return Err(status_error(status, &resp_text));
+        }
+
+        // Models API response: { "data": [{ "id": "...", "display_name": "..." }] }
+        let resp_json: serde_json::Value = serde_json::from_str(&resp_text)
+            .map_err(|e| ClaudeError::ParseError(format!("Invalid JSON response: {}", e)))?;
+        let models = resp_json
+            .get("data")
+            .and_then(|d| d.as_array())
+            .ok_or_else(|| ClaudeError::ParseError("Model list missing 'data'".to_string()))?
+            .iter()
+            .filter_map(|m| {
+                let id = m.get("id")?.as_str()?.to_string();
+                let display_name = m.get("display_name").and_then(|n| n.as_str()).unwrap_or(&id).to_string();
+                Some(ModelInfo { id, display_name })
+            })
+            .collect();
+        Ok(models)
+    }
+}
+
+/// Map a non-success HTTP status to an error.
+fn status_error(status: reqwest::StatusCode, body: &str) -> ClaudeError {
+    match status.as_u16() {
+        401 => ClaudeError::NotAuthenticated(
+            "Invalid or expired API credentials. Check your API key.".to_string(),
+        ),
+        429 => ClaudeError::ApiError("Rate limit exceeded. Please wait and try again.".to_string()),
+        _ => ClaudeError::ApiError(format!("HTTP {}: {}", status, body)),
+    }
+}
+
 impl ClaudeInvoker for RealClaudeInvoker {
     fn invoke(&self, request: ClaudeRequest) -> Result<ClaudeResponse, ClaudeError> {
+        crate::metrics::increment(crate::metrics::Counter::AiRequests);
         self.call_anthropic_api(&request)
     }
 }
@@ -285,6 +401,7 @@ pub mod tests {
                     content: self.response_content.clone(),
                     task: request.task,
                     bug_id: request.bug_id,
+                    cached: false,
                 })
             } else {
                 Err(ClaudeError::InvocationFailed("Mock failure".to_string()))
@@ -336,4 +453,49 @@ pub mod tests {
         let result = queued.invoke(request);
         assert!(result.is_ok());
     }
+
+    fn write_png(path: &Path, width: u32, height: u32) {
+        image::RgbImage::from_pixel(width, height, image::Rgb([200, 40, 40]))
+            .save(path)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_prepare_image_downscales_large_screenshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let large = dir.path().join("4k.png");
+        write_png(&large, 3840, 2160);
+
+        let prepared = prepare_image(&large, 1568).unwrap();
+        assert_eq!(prepared.media_type, "image/png");
+        let decoded = image::load_from_memory(&prepared.data).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (1568, 882));
+
+        let small = dir.path().join("small.png");
+        write_png(&small, 400, 300);
+        let prepared = prepare_image(&small, 1568).unwrap();
+        assert_eq!(prepared.data, std::fs::read(&small).unwrap());
+    }
+
+    #[test]
+    fn test_prepare_images_lists_excluded_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let good = dir.path().join("capture-001.png");
+        write_png(&good, 10, 10);
+        let video = dir.path().join("capture-002.mp4");
+        std::fs::write(&video, b"not an image").unwrap();
+        let broken = dir.path().join("capture-003.png");
+        std::fs::write(&broken, b"not a png").unwrap();
+
+        assert_eq!(prepare_images(std::slice::from_ref(&good), 1568).unwrap().len(), 1);
+        match prepare_images(&[good, video.clone(), broken.clone()], 1568) {
+            Err(ClaudeError::AttachmentsExcluded { files }) => {
+                assert_eq!(files.len(), 2);
+                assert!(files[0].starts_with(&video.display().to_string()));
+                assert!(files[0].contains("not a supported image type"));
+                assert!(files[1].starts_with(&broken.display().to_string()));
+            }
+            other => panic!("expected excluded attachments, got {:?}", other.map(|v| v.len())),
+        }
+    }
 }
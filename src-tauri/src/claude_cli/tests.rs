@@ -37,6 +37,8 @@ mod claude_cli_tests {
             meeting_id: Some("MEETING-001".to_string()),
             environment: Some("Windows 11".to_string()),
             bug_type: Some("bug".to_string()),
+            ui_tree: None,
+            glossary: None,
         };
 
         let json = serde_json::to_string(&context).unwrap();
@@ -61,6 +63,17 @@ mod claude_cli_tests {
         assert!(api_error.to_string().contains("API error"));
     }
 
+    #[test]
+    fn test_parse_json_response_strips_code_fence() {
+        use crate::claude_cli::types::parse_json_response;
+
+        let fenced = parse_json_response("```json\n{\"a\": 1}\n```").unwrap();
+        assert_eq!(fenced["a"], 1);
+        let bare = parse_json_response("  {\"a\": 2}  ").unwrap();
+        assert_eq!(bare["a"], 2);
+        assert!(matches!(parse_json_response("not json"), Err(ClaudeError::ParseError(_))));
+    }
+
     #[test]
     fn test_claude_request_builder() {
         let request = ClaudeRequest::new_text("test prompt".to_string(), PromptTask::DescribeBug)
@@ -102,6 +115,8 @@ mod claude_cli_tests {
             meeting_id: None,
             environment: None,
             bug_type: None,
+            ui_tree: None,
+            glossary: None,
         };
 
         let prompt = PromptBuilder::build_bug_description_prompt(&context);
@@ -127,6 +142,8 @@ mod claude_cli_tests {
             meeting_id: Some("SESSION-123".to_string()),
             environment: Some("Windows 11".to_string()),
             bug_type: Some("bug".to_string()),
+            ui_tree: None,
+            glossary: None,
         };
 
         let prompt = PromptBuilder::build_bug_description_prompt(&context);
@@ -149,6 +166,15 @@ mod claude_cli_tests {
         assert!(prompt.contains("JSON"));
     }
 
+    #[test]
+    fn test_prompt_builder_alt_text() {
+        let prompt = PromptBuilder::build_alt_text_prompt(Some("Pay button disabled"));
+
+        assert!(prompt.contains("screen reader"));
+        assert!(prompt.contains("The bug is titled: Pay button disabled"));
+        assert!(!PromptBuilder::build_alt_text_prompt(None).contains("titled"));
+    }
+
     #[test]
     fn test_prompt_builder_refinement() {
         let current = "Original description";
@@ -227,6 +253,7 @@ mod claude_cli_tests {
             content: "Test content".to_string(),
             task: PromptTask::DescribeBug,
             bug_id: Some("BUG-001".to_string()),
+            cached: false,
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -264,6 +291,8 @@ mod claude_cli_tests {
             meeting_id: None,
             environment: None,
             bug_type: None,
+            ui_tree: None,
+            glossary: None,
         };
 
         // DescribeBug
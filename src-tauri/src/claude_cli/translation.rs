@@ -0,0 +1,197 @@
+//! Translating notes and descriptions for tickets
+//!
+//! Some testers write in their native language. With translation enabled in
+//! the `claude.translation` setting, descriptions are translated to the
+//! target language when a ticket is created and when tickets-ready.md is
+//! written, and a bug's tester notes are translated into the ticket too. The model detects the language; text already in the target
+//! language is left alone. The original stays below a divider, so nothing
+//! the tester wrote is lost, and text that already has one is not sent again.
+
+use std::sync::Arc;
+
+use crate::database::{SettingsOps, SettingsRepository};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use super::models::ModelParams;
+use super::subprocess::ClaudeInvoker;
+use super::types::{parse_json_response, ClaudeError, ClaudeRequest, PromptTask};
+
+/// Settings key holding [`TranslationSettings`] as JSON.
+pub const TRANSLATION_KEY: &str = "claude.translation";
+
+/// Start of the line introducing the original text below a translation.
+const ORIGINAL_MARKER: &str = "**Original (";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TranslationSettings {
+    pub enabled: bool,
+    /// Language tickets are written in, e.g. "English"
+    pub target_language: String,
+}
+
+impl Default for TranslationSettings {
+    fn default() -> Self {
+        Self { enabled: false, target_language: "English".to_string() }
+    }
+}
+
+impl TranslationSettings {
+    /// Stored settings; missing or unreadable settings give the defaults.
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(TRANSLATION_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        if self.target_language.trim().is_empty() {
+            return Err("The target language cannot be empty".to_string());
+        }
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(TRANSLATION_KEY, &json)
+            .map_err(|e| format!("Failed to save translation settings: {}", e))
+    }
+}
+
+/// The model's answer for one text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Translation {
+    /// Detected language of the original, e.g. "German"
+    pub source_language: String,
+    /// None when the text is already in the target language
+    pub text: Option<String>,
+}
+
+/// Build a prompt asking for the language of `text` and its translation, as JSON.
+pub fn build_translation_prompt(text: &str, target_language: &str) -> String {
+    let mut prompt = String::new();
+    prompt.push_str("You are translating a QA tester's bug notes for a ticket tracker.\n");
+    prompt.push_str(&format!(
+        "Detect the language of the text below and translate it to {}. Keep Markdown, code, \
+         file paths, URLs, error messages and product names unchanged.\n\n",
+        target_language
+    ));
+    prompt.push_str("Text:\n---\n");
+    prompt.push_str(text.trim());
+    prompt.push_str("\n---\n\n");
+    prompt.push_str("Respond with ONLY a JSON object (no markdown fences, no explanation outside the JSON):\n\n");
+    prompt.push_str("{\n");
+    prompt.push_str("  \"language\": \"English name of the text's language\",\n");
+    prompt.push_str(&format!(
+        "  \"translation\": \"the translated text, or null if the text is already in {}\"\n",
+        target_language
+    ));
+    prompt.push_str("}\n");
+    prompt
+}
+
+/// Read the model's JSON answer.
+pub fn parse_translation_response(content: &str) -> Result<Translation, ClaudeError> {
+    let parsed = parse_json_response(content)?;
+
+    Ok(Translation {
+        source_language: parsed
+            .get("language")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .unwrap_or("unknown language")
+            .to_string(),
+        text: parsed
+            .get("translation")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(String::from),
+    })
+}
+
+/// `translated`, then a divider and `original`.
+pub fn with_original(translated: &str, original: &str, source_language: &str) -> String {
+    format!("{}\n\n---\n\n{}{}):**\n\n{}", translated.trim_end(), ORIGINAL_MARKER, source_language, original.trim())
+}
+
+/// Whether `text` already carries a translation made by [`with_original`].
+pub fn is_translated(text: &str) -> bool {
+    text.lines().any(|line| line.starts_with(ORIGINAL_MARKER))
+}
+
+/// Translates text to the configured language through the model.
+pub struct Translator {
+    invoker: Arc<dyn ClaudeInvoker>,
+    target_language: String,
+    params: ModelParams,
+}
+
+impl Translator {
+    pub fn new(invoker: Arc<dyn ClaudeInvoker>, target_language: &str, params: ModelParams) -> Self {
+        Self { invoker, target_language: target_language.to_string(), params }
+    }
+
+    /// `text` in the target language with the original below a divider.
+    /// Empty text, text already in the target language and text translated
+    /// before come back unchanged.
+    pub fn translate(&self, text: &str) -> Result<String, ClaudeError> {
+        if text.trim().is_empty() || is_translated(text) {
+            return Ok(text.to_string());
+        }
+        let prompt = build_translation_prompt(text, &self.target_language);
+        let request = ClaudeRequest::new_text(prompt, PromptTask::Custom).with_params(self.params.clone());
+        let translation = parse_translation_response(&self.invoker.invoke(request)?.content)?;
+        Ok(match translation.text {
+            Some(translated) => with_original(&translated, text, &translation.source_language),
+            None => text.to_string(),
+        })
+    }
+
+    /// [`Self::translate`], keeping `text` as it is when translation fails.
+    pub fn translate_or_keep(&self, text: &str) -> String {
+        self.translate(text).unwrap_or_else(|e| {
+            eprintln!("Warning: translation skipped: {}", e);
+            text.to_string()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude_cli::subprocess::tests::MockClaudeInvoker;
+
+    fn translator(response: &str) -> Translator {
+        let invoker = MockClaudeInvoker {
+            should_succeed: true,
+            response_content: response.to_string(),
+            delay_ms: 0,
+        };
+        Translator::new(Arc::new(invoker), "English", ModelParams::default())
+    }
+
+    #[test]
+    fn test_translation_keeps_original_below_divider() {
+        let translator = translator(
+            "```json\n{\"language\": \"German\", \"translation\": \"The save button does nothing.\"}\n```",
+        );
+        let translated = translator.translate("Der Speichern-Knopf macht nichts.").unwrap();
+        assert_eq!(
+            translated,
+            "The save button does nothing.\n\n---\n\n**Original (German):**\n\nDer Speichern-Knopf macht nichts."
+        );
+        // Not sent again on the next export
+        assert!(is_translated(&translated));
+        assert_eq!(translator.translate(&translated).unwrap(), translated);
+    }
+
+    #[test]
+    fn test_text_in_target_language_is_unchanged() {
+        let translator = translator(r#"{"language": "English", "translation": null}"#);
+        assert_eq!(translator.translate("Save does nothing.").unwrap(), "Save does nothing.");
+        assert!(parse_translation_response("not json").is_err());
+    }
+}
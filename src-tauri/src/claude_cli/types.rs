@@ -1,5 +1,6 @@
 //! Type definitions for Claude CLI / Anthropic API integration
 
+use super::models::ModelParams;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -56,6 +57,10 @@ pub enum ClaudeError {
     ApiError(String),
     /// Queue is full
     QueueFull(String),
+    /// Attachments that cannot be sent as images (each entry names the file and the reason)
+    AttachmentsExcluded {
+        files: Vec<String>,
+    },
 }
 
 impl std::fmt::Display for ClaudeError {
@@ -70,12 +75,29 @@ impl std::fmt::Display for ClaudeError {
             ClaudeError::ParseError(msg) => write!(f, "Failed to parse Claude response: {}", msg),
             ClaudeError::ApiError(msg) => write!(f, "Claude API error: {}", msg),
             ClaudeError::QueueFull(msg) => write!(f, "Claude request queue full: {}", msg),
+            ClaudeError::AttachmentsExcluded { files } => {
+                write!(f, "{} attachment(s) cannot be sent to Claude: {}", files.len(), files.join("; "))
+            }
         }
     }
 }
 
 impl std::error::Error for ClaudeError {}
 
+/// Parse a JSON answer from the model, allowing it to be wrapped in a
+/// Markdown code fence.
+pub(crate) fn parse_json_response(content: &str) -> Result<serde_json::Value, ClaudeError> {
+    let raw = content.trim();
+    let json_str = raw
+        .strip_prefix("```json")
+        .or_else(|| raw.strip_prefix("```"))
+        .map(|rest| rest.strip_suffix("```").unwrap_or(rest).trim())
+        .unwrap_or(raw);
+    serde_json::from_str(json_str).map_err(|e| {
+        ClaudeError::ParseError(format!("{}. Raw response: {}", e, raw.chars().take(300).collect::<String>()))
+    })
+}
+
 /// Context data for a bug to be described by Claude
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -96,6 +118,12 @@ pub struct BugContext {
     pub environment: Option<String>,
     /// Bug type (bug, feature, feedback)
     pub bug_type: Option<String>,
+    /// Outline of the focused window's accessibility tree (control names, AutomationIds)
+    #[serde(default)]
+    pub ui_tree: Option<String>,
+    /// Project abbreviations from the profile, one "ABBR: expansion" per line
+    #[serde(default)]
+    pub glossary: Option<String>,
 }
 
 /// The type of AI task to perform
@@ -136,6 +164,9 @@ pub struct ClaudeResponse {
     pub task: PromptTask,
     /// Bug ID this response is for (if applicable)
     pub bug_id: Option<String>,
+    /// Served from the response cache instead of a new API call
+    #[serde(default)]
+    pub cached: bool,
 }
 
 /// Request to invoke Claude CLI
@@ -151,6 +182,10 @@ pub struct ClaudeRequest {
     pub bug_id: Option<String>,
     /// Timeout in seconds (15 for text, 30 for images)
     pub timeout_secs: u64,
+    /// Skip the response cache and always call the API
+    pub bypass_cache: bool,
+    /// Model and sampling parameters (unset fields use the defaults)
+    pub params: ModelParams,
 }
 
 impl ClaudeRequest {
@@ -161,6 +196,8 @@ impl ClaudeRequest {
             task,
             bug_id: None,
             timeout_secs: 15,
+            bypass_cache: false,
+            params: ModelParams::default(),
         }
     }
 
@@ -175,6 +212,8 @@ impl ClaudeRequest {
             task,
             bug_id: None,
             timeout_secs: 30,
+            bypass_cache: false,
+            params: ModelParams::default(),
         }
     }
 
@@ -183,6 +222,16 @@ impl ClaudeRequest {
         self
     }
 
+    pub fn with_params(mut self, params: ModelParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub fn bypassing_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
     #[allow(dead_code)]
     pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
         self.timeout_secs = timeout_secs;
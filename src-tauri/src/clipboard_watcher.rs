@@ -133,7 +133,7 @@ fn hash_image(rgba: &[u8], width: u32, height: u32) -> u64 {
 }
 
 /// Encode raw RGBA pixel data as an 8-bit PNG.
-fn encode_png(rgba: &[u8], width: u32, height: u32) -> Result<Vec<u8>, String> {
+pub(crate) fn encode_png(rgba: &[u8], width: u32, height: u32) -> Result<Vec<u8>, String> {
     let mut buf = Vec::new();
     {
         let mut encoder = png::Encoder::new(&mut buf, width, height);
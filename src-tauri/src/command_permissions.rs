@@ -0,0 +1,84 @@
+//! Which windows may invoke sensitive commands.
+//!
+//! Every webview can reach every registered command, but the secondary
+//! windows (session notes, status bar, annotation) never need to delete
+//! settings, change startup registration or read ticketing credentials.
+//! Commands listed in [`SENSITIVE_COMMANDS`] are only served to windows whose
+//! allowlist in [`WINDOW_ALLOWLISTS`] names them; other commands are open
+//! to all windows. The check runs in the command dispatcher before the
+//! command itself.
+
+/// Destructive or credential-related commands.
+pub const SENSITIVE_COMMANDS: &[&str] = &[
+    "delete_setting",
+    "get_secret",
+    "set_post_export_hook",
+    "set_metrics_settings",
+    "reset_setup",
+    "enable_startup",
+    "disable_startup",
+    "seed_demo_data",
+    "unlock_session",
+    "open_in_terminal",
+    "profile_delete",
+    "ticketing_authenticate",
+    "ticketing_get_credentials",
+    "ticketing_save_credentials",
+    "ticketing_set_provider",
+    "ticketing_get_jira_config",
+    "ticketing_save_jira_config",
+];
+
+/// Sensitive commands each window may invoke, by window label. A label
+/// ending in `*` matches every label with that prefix. Windows without an
+/// entry get none.
+pub const WINDOW_ALLOWLISTS: &[(&str, &[&str])] = &[("main", SENSITIVE_COMMANDS)];
+
+pub fn is_sensitive(command: &str) -> bool {
+    SENSITIVE_COMMANDS.contains(&command)
+}
+
+fn label_matches(pattern: &str, label: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => label.starts_with(prefix),
+        None => pattern == label,
+    }
+}
+
+/// Whether the window labelled `window_label` may invoke `command`.
+pub fn is_allowed(window_label: &str, command: &str) -> bool {
+    !is_sensitive(command)
+        || WINDOW_ALLOWLISTS
+            .iter()
+            .any(|(pattern, allowed)| label_matches(pattern, window_label) && allowed.contains(&command))
+}
+
+/// Error returned to a window that invoked a command it may not use.
+pub fn denied_error(window_label: &str, command: &str) -> String {
+    format!("Command '{}' is not allowed from window '{}'", command, window_label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sensitive_commands_only_from_main_window() {
+        assert!(is_allowed("main", "delete_setting"));
+        assert!(is_allowed("main", "ticketing_get_credentials"));
+        assert!(!is_allowed("session-notes", "delete_setting"));
+        assert!(!is_allowed("annotation-capture-001-png", "ticketing_get_credentials"));
+
+        // Everything else stays open to secondary windows
+        assert!(is_allowed("session-notes", "update_session_notes"));
+        assert!(is_allowed("annotation-capture-001-png", "save_annotated_image"));
+    }
+
+    #[test]
+    fn test_label_patterns() {
+        assert!(label_matches("annotation-*", "annotation-shot-png"));
+        assert!(!label_matches("annotation-*", "main"));
+        assert!(label_matches("main", "main"));
+        assert!(!label_matches("main", "main-2"));
+    }
+}
@@ -0,0 +1,195 @@
+//! Command registration grouped by domain, with deprecated names.
+//!
+//! Commands are listed by domain in one `command_registry!` invocation,
+//! which defines the name catalog and one generated handler per domain.
+//! Invokes are dispatched to the handler of the domain that declares the
+//! command.
+//!
+//! Renaming a command without breaking older frontends:
+//! 1. register the command under its new name;
+//! 2. keep a thin `#[tauri::command]` wrapper with the old name that calls
+//!    the new one, registered in the same group;
+//! 3. add the old name to [`DEPRECATED_COMMANDS`].
+//!
+//! Sensitive commands are checked against the calling window's allowlist
+//! (see `command_permissions`) before dispatch.
+//!
+//! Calls to a deprecated name are still served, but each one logs a warning
+//! and emits `command:deprecated` so the frontend can surface it during
+//! development.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+use crate::command_permissions;
+use crate::events;
+
+/// Domain a command belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandDomain {
+    Session,
+    Bug,
+    Capture,
+    Ticketing,
+    Ai,
+    Settings,
+    Templates,
+    /// Windows, tray, startup and other application plumbing
+    App,
+}
+
+/// A command name kept for compatibility.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeprecatedCommand {
+    pub name: &'static str,
+    /// Command to call instead; None when the command is going away entirely
+    pub replacement: Option<&'static str>,
+    /// App version that deprecated the name
+    pub since: &'static str,
+}
+
+/// Deprecated command names. Each must still be registered in a group.
+pub const DEPRECATED_COMMANDS: &[DeprecatedCommand] = &[DeprecatedCommand {
+    // Left over from the Tauri project template
+    name: "greet",
+    replacement: None,
+    since: "0.1.0",
+}];
+
+pub fn deprecation(name: &str) -> Option<&'static DeprecatedCommand> {
+    DEPRECATED_COMMANDS.iter().find(|d| d.name == name)
+}
+
+pub type Handler<R> = Box<dyn Fn(Invoke<R>) -> bool + Send + Sync>;
+
+/// Command names by domain, without the handlers.
+pub struct CommandCatalog {
+    groups: Vec<(CommandDomain, &'static [&'static str])>,
+}
+
+impl CommandCatalog {
+    pub fn new(groups: Vec<(CommandDomain, &'static [&'static str])>) -> Self {
+        Self { groups }
+    }
+
+    pub fn domain_of(&self, command: &str) -> Option<CommandDomain> {
+        self.groups
+            .iter()
+            .find(|(_, commands)| commands.contains(&command))
+            .map(|(domain, _)| *domain)
+    }
+
+    /// Names registered in more than one place (only the first would be reachable).
+    pub fn duplicates(&self) -> Vec<&'static str> {
+        let mut seen = HashMap::new();
+        let mut duplicates = Vec::new();
+        for name in self.groups.iter().flat_map(|(_, commands)| commands.iter().copied()) {
+            if seen.insert(name, ()).is_some() {
+                duplicates.push(name);
+            }
+        }
+        duplicates
+    }
+
+    /// Deprecations that point at unregistered commands.
+    pub fn invalid_deprecations(&self) -> Vec<&'static str> {
+        DEPRECATED_COMMANDS
+            .iter()
+            .filter(|d| {
+                self.domain_of(d.name).is_none() || d.replacement.is_some_and(|r| self.domain_of(r).is_none())
+            })
+            .map(|d| d.name)
+            .collect()
+    }
+}
+
+/// Define `command_catalog()` and `command_registry()` from a list of
+/// domains and their command functions.
+macro_rules! command_registry {
+    ($($domain:ident => [$($command:ident),* $(,)?]),* $(,)?) => {
+        fn command_catalog() -> $crate::command_registry::CommandCatalog {
+            $crate::command_registry::CommandCatalog::new(vec![$((
+                $crate::command_registry::CommandDomain::$domain,
+                &[$(stringify!($command)),*] as &'static [&'static str],
+            )),*])
+        }
+
+        fn command_registry() -> $crate::command_registry::CommandRegistry<tauri::Wry> {
+            let handlers: Vec<$crate::command_registry::Handler<tauri::Wry>> =
+                vec![$(Box::new(tauri::generate_handler![$($command),*])),*];
+            $crate::command_registry::CommandRegistry::new(command_catalog(), handlers)
+        }
+    };
+}
+pub(crate) use command_registry;
+
+/// Command catalog plus one generated handler per domain.
+pub struct CommandRegistry<R: Runtime> {
+    catalog: CommandCatalog,
+    handlers: Vec<Handler<R>>,
+}
+
+impl<R: Runtime> CommandRegistry<R> {
+    /// `handlers` must be in the same order as the catalog's groups.
+    pub fn new(catalog: CommandCatalog, handlers: Vec<Handler<R>>) -> Self {
+        debug_assert_eq!(catalog.groups.len(), handlers.len());
+        Self { catalog, handlers }
+    }
+
+    /// The invoke handler for `tauri::Builder::invoke_handler`.
+    pub fn into_handler(self) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+        let catalog = &self.catalog;
+        debug_assert!(catalog.duplicates().is_empty(), "Commands registered twice: {:?}", catalog.duplicates());
+        debug_assert!(
+            catalog.invalid_deprecations().is_empty(),
+            "Deprecated commands not registered: {:?}",
+            catalog.invalid_deprecations()
+        );
+        let index: HashMap<&'static str, usize> = catalog
+            .groups
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (_, commands))| commands.iter().map(move |name| (*name, i)))
+            .collect();
+        let handlers = self.handlers;
+
+        move |invoke| {
+            let command = invoke.message.command().to_string();
+            let Some(&group) = index.get(command.as_str()) else {
+                return false;
+            };
+            let window = invoke.message.webview().label().to_string();
+            if !command_permissions::is_allowed(&window, &command) {
+                eprintln!("Warning: rejected '{}' from window '{}'", command, window);
+                invoke.resolver.reject(command_permissions::denied_error(&window, &command));
+                return true;
+            }
+            if let Some(deprecated) = deprecation(&command) {
+                warn_deprecated(&invoke.message.webview(), deprecated);
+            }
+            (handlers[group])(invoke)
+        }
+    }
+}
+
+fn warn_deprecated<R: Runtime>(webview: &tauri::Webview<R>, deprecated: &DeprecatedCommand) {
+    match deprecated.replacement {
+        Some(replacement) => eprintln!(
+            "Warning: command '{}' is deprecated since {}, use '{}'",
+            deprecated.name, deprecated.since, replacement
+        ),
+        None => eprintln!("Warning: command '{}' is deprecated since {}", deprecated.name, deprecated.since),
+    }
+    let _ = events::emit(
+        webview,
+        &events::CommandDeprecated {
+            command: deprecated.name.to_string(),
+            replacement: deprecated.replacement.map(str::to_string),
+            since: deprecated.since.to_string(),
+        },
+    );
+}
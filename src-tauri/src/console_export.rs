@@ -0,0 +1,216 @@
+//! Export of parsed console captures into the bug folder.
+//!
+//! When a console screenshot is parsed, the structured result is written to
+//! `console-NNN.md` next to the screenshot (NNN matches the capture number
+//! when known) and referenced from the bug folder's `metadata.json`, so an
+//! exported bug folder is readable without the database.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::bug_metadata::{read_metadata_object, write_metadata_object};
+
+/// Structured console parse result, as produced by the console parse prompt.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ConsoleParse {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    #[serde(alias = "stackTraces")]
+    pub stack_traces: Vec<String>,
+    pub logs: Vec<String>,
+}
+
+impl ConsoleParse {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid console parse JSON: {}", e))
+    }
+}
+
+/// `console-NNN.md`
+pub fn console_file_name(number: u32) -> String {
+    format!("console-{:03}.md", number)
+}
+
+/// Capture number embedded in a capture file name (`capture-003.png` -> 3).
+pub fn capture_number_from_name(file_name: &str) -> Option<u32> {
+    let stem = Path::new(file_name).file_stem()?.to_str()?;
+    let (_, digits) = stem.split_once('-')?;
+    digits
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+/// Next unused console export number in `dir`.
+pub fn next_console_number(dir: &Path) -> u32 {
+    let max = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let name = e.file_name().to_string_lossy().to_string();
+                    name.starts_with("console-")
+                        .then(|| capture_number_from_name(&name))
+                        .flatten()
+                })
+                .max()
+                .unwrap_or(0)
+        })
+        .unwrap_or(0);
+    max + 1
+}
+
+/// Render a console parse as Markdown with one section per category.
+pub fn render_console_markdown(display_id: &str, source_file: Option<&str>, parse: &ConsoleParse) -> String {
+    let mut md = format!("# Console Output — {}\n\n", display_id);
+    if let Some(source) = source_file {
+        md.push_str(&format!("Source: `{}`\n\n", source));
+    }
+
+    let sections: [(&str, &[String], bool); 4] = [
+        ("Errors", &parse.errors, false),
+        ("Warnings", &parse.warnings, false),
+        ("Stack Traces", &parse.stack_traces, true),
+        ("Logs", &parse.logs, false),
+    ];
+
+    for (heading, items, as_code) in sections {
+        md.push_str(&format!("## {}\n\n", heading));
+        if items.is_empty() {
+            md.push_str("_None_\n\n");
+            continue;
+        }
+        for item in items {
+            if as_code {
+                md.push_str(&format!("```\n{}\n```\n\n", item.trim_end()));
+            } else {
+                md.push_str(&format!("- {}\n", item));
+            }
+        }
+        if !as_code {
+            md.push('\n');
+        }
+    }
+
+    md
+}
+
+/// Add (or replace) a `consoleExports` entry in the bug folder's `metadata.json`,
+/// creating the file if needed. Other keys in the file are preserved.
+pub fn reference_in_metadata(bug_folder: &Path, console_file: &str, source_file: Option<&str>) -> Result<(), String> {
+    let mut root = read_metadata_object(bug_folder);
+
+    let exports = root
+        .entry("consoleExports")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if !exports.is_array() {
+        *exports = Value::Array(Vec::new());
+    }
+    let list = exports.as_array_mut().expect("consoleExports is an array");
+    list.retain(|entry| entry.get("file").and_then(Value::as_str) != Some(console_file));
+    list.push(serde_json::json!({ "file": console_file, "source": source_file }));
+
+    write_metadata_object(bug_folder, root).map(|_| ())
+}
+
+/// Write `console-NNN.md` for a parse result into `bug_folder` and reference it
+/// from `metadata.json`. `source_file` is the console screenshot's file name;
+/// its capture number is reused for NNN when present. Returns the written path.
+pub fn write_console_export(
+    bug_folder: &Path,
+    display_id: &str,
+    source_file: Option<&str>,
+    parse: &ConsoleParse,
+) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(bug_folder)
+        .map_err(|e| format!("Cannot create bug folder {:?}: {}", bug_folder, e))?;
+
+    let number = source_file
+        .and_then(capture_number_from_name)
+        .unwrap_or_else(|| next_console_number(bug_folder));
+    let file_name = console_file_name(number);
+    let path = bug_folder.join(&file_name);
+
+    std::fs::write(&path, render_console_markdown(display_id, source_file, parse))
+        .map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+    reference_in_metadata(bug_folder, &file_name, source_file)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bug_metadata::METADATA_FILE;
+
+    fn sample() -> ConsoleParse {
+        ConsoleParse::from_json(
+            r#"{
+                "errors": ["TypeError: x is undefined"],
+                "warnings": [],
+                "stackTraces": ["at render (app.js:10:5)\nat main (app.js:2:1)"],
+                "logs": ["GET /api/items 500"]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_accepts_missing_sections() {
+        let parse = ConsoleParse::from_json(r#"{"errors": ["boom"]}"#).unwrap();
+        assert_eq!(parse.errors, vec!["boom"]);
+        assert!(parse.stack_traces.is_empty());
+        assert!(ConsoleParse::from_json("nope").is_err());
+    }
+
+    #[test]
+    fn test_capture_number_from_name() {
+        assert_eq!(capture_number_from_name("capture-003.png"), Some(3));
+        assert_eq!(capture_number_from_name("capture-012_annotated.png"), Some(12));
+        assert_eq!(capture_number_from_name("screenshot.png"), None);
+    }
+
+    #[test]
+    fn test_render_console_markdown_sections() {
+        let md = render_console_markdown("BUG-001", Some("capture-002.png"), &sample());
+        assert!(md.starts_with("# Console Output — BUG-001"));
+        assert!(md.contains("Source: `capture-002.png`"));
+        assert!(md.contains("## Errors\n\n- TypeError: x is undefined"));
+        assert!(md.contains("## Warnings\n\n_None_"));
+        assert!(md.contains("## Stack Traces\n\n```\nat render (app.js:10:5)"));
+        assert!(md.contains("## Logs\n\n- GET /api/items 500"));
+    }
+
+    #[test]
+    fn test_write_console_export_uses_capture_number_and_updates_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(METADATA_FILE), r#"{"displayId": "BUG-001"}"#).unwrap();
+
+        let path = write_console_export(dir.path(), "BUG-001", Some("capture-002.png"), &sample()).unwrap();
+        assert_eq!(path.file_name().unwrap(), "console-002.md");
+
+        // Re-export replaces the reference rather than duplicating it
+        write_console_export(dir.path(), "BUG-001", Some("capture-002.png"), &sample()).unwrap();
+
+        let metadata: Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join(METADATA_FILE)).unwrap()).unwrap();
+        assert_eq!(metadata["displayId"], "BUG-001");
+        let exports = metadata["consoleExports"].as_array().unwrap();
+        assert_eq!(exports.len(), 1);
+        assert_eq!(exports[0]["file"], "console-002.md");
+        assert_eq!(exports[0]["source"], "capture-002.png");
+    }
+
+    #[test]
+    fn test_write_console_export_without_source_picks_next_number() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("console-004.md"), "old").unwrap();
+
+        let path = write_console_export(dir.path(), "BUG-001", None, &sample()).unwrap();
+        assert_eq!(path.file_name().unwrap(), "console-005.md");
+    }
+}
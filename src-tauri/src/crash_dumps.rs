@@ -0,0 +1,492 @@
+//! Collection of crash dumps written for the app under test.
+//!
+//! When `crash_dumps.process` names an executable (e.g. `Shop.exe`), the
+//! folder Windows Error Reporting writes local dumps to is watched while a
+//! session is running. WER names dumps `<process>.<pid>.dmp`; once a matching
+//! dump stops growing it is copied into the active bug's folder (or the
+//! session's `_unsorted/` when no bug is capturing). The bug is tagged as a
+//! crash in its `metadata_json`, with the faulting module and exception code
+//! read from the minidump.
+//!
+//! WER only writes local dumps when `LocalDumps` is enabled in the registry
+//! (`HKLM\SOFTWARE\Microsoft\Windows\Windows Error Reporting\LocalDumps`).
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::database::{self, BugOps, BugRepository, SettingsOps, SettingsRepository};
+use crate::events::{self, CrashDumpCollected};
+
+/// Settings key: executable name of the app under test; unset disables collection.
+pub const CRASH_PROCESS_KEY: &str = "crash_dumps.process";
+
+/// Settings key: folder WER writes dumps to, when not the default.
+pub const CRASH_DUMP_FOLDER_KEY: &str = "crash_dumps.folder";
+
+/// How long a dump must stay the same size before it is copied.
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// WER's default `LocalDumps` folder: `%LOCALAPPDATA%\CrashDumps`.
+pub fn default_dump_folder() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("CrashDumps"))
+}
+
+/// Which process's dumps to collect, and where WER writes them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashDumpSettings {
+    /// Executable name, e.g. `Shop.exe`; `None` disables collection
+    pub process: Option<String>,
+    /// `None` means [`default_dump_folder`]
+    pub folder: Option<String>,
+}
+
+impl CrashDumpSettings {
+    pub fn load(conn: &Connection) -> Self {
+        let repo = SettingsRepository::new(conn);
+        let get = |key| repo.get(key).ok().flatten().filter(|v: &String| !v.trim().is_empty());
+        Self { process: get(CRASH_PROCESS_KEY), folder: get(CRASH_DUMP_FOLDER_KEY) }
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let repo = SettingsRepository::new(conn);
+        for (key, value) in [(CRASH_PROCESS_KEY, &self.process), (CRASH_DUMP_FOLDER_KEY, &self.folder)] {
+            match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+                Some(value) => repo.set(key, value),
+                None => repo.delete(key),
+            }
+            .map_err(|e| format!("Failed to save crash dump settings: {}", e))?;
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(process) = self.process.as_deref().map(str::trim) {
+            if process.contains(['/', '\\']) {
+                return Err("Crash dump process must be an executable name, not a path".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Process name and dump folder to watch, or `None` when collection is off.
+    pub fn watch_target(&self) -> Option<(String, PathBuf)> {
+        let process = self.process.as_deref().map(str::trim).filter(|p| !p.is_empty())?;
+        let folder = self.folder.as_deref().map(PathBuf::from).or_else(default_dump_folder)?;
+        Some((process.to_string(), folder))
+    }
+}
+
+/// Whether `path` is a WER dump of `process` (`Shop.exe.1234.dmp`). The
+/// `.exe` suffix of `process` is optional.
+pub fn is_dump_for(path: &Path, process: &str) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let name = name.to_lowercase();
+    let process = process.trim().to_lowercase();
+    let process = process.strip_suffix(".exe").unwrap_or(&process);
+    let Some(stem) = name.strip_suffix(".dmp") else {
+        return false;
+    };
+    let Some(rest) = stem.strip_prefix(process) else {
+        return false;
+    };
+    let rest = rest.strip_prefix(".exe").unwrap_or(rest);
+    // Exactly the process name, optionally followed by `.<pid>`
+    rest.is_empty()
+        || rest
+            .strip_prefix('.')
+            .is_some_and(|pid| !pid.is_empty() && pid.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// What the minidump says about the crash.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashInfo {
+    /// File name of the module containing the exception address
+    pub faulting_module: Option<String>,
+    /// e.g. `0xC0000005` (access violation)
+    pub exception_code: Option<String>,
+}
+
+const MINIDUMP_SIGNATURE: u32 = 0x504D_444D; // "MDMP"
+const MODULE_LIST_STREAM: u32 = 4;
+const EXCEPTION_STREAM: u32 = 6;
+/// sizeof(MINIDUMP_MODULE)
+const MODULE_ENTRY_SIZE: usize = 108;
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(bytes.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+/// MINIDUMP_STRING at `rva`: a byte length followed by UTF-16LE text.
+fn read_minidump_string(bytes: &[u8], rva: usize) -> Option<String> {
+    let len = read_u32(bytes, rva)? as usize;
+    let units: Vec<u16> = bytes
+        .get(rva + 4..rva + 4 + len)?
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16(&units).ok()
+}
+
+/// Read the exception code and faulting module from a minidump. Fields that
+/// cannot be found are left `None`; a file that is not a minidump gives the
+/// default.
+pub fn read_crash_info(bytes: &[u8]) -> CrashInfo {
+    let mut info = CrashInfo::default();
+    if read_u32(bytes, 0) != Some(MINIDUMP_SIGNATURE) {
+        return info;
+    }
+    let (Some(stream_count), Some(directory)) = (read_u32(bytes, 8), read_u32(bytes, 12)) else {
+        return info;
+    };
+
+    // Stream directory: (type, size, rva) per stream
+    let mut exception_address = None;
+    let mut module_list = None;
+    for i in 0..stream_count as usize {
+        let entry = directory as usize + i * 12;
+        let (Some(kind), Some(rva)) = (read_u32(bytes, entry), read_u32(bytes, entry + 8)) else {
+            break;
+        };
+        match kind {
+            // MINIDUMP_EXCEPTION_STREAM: thread id, padding, then MINIDUMP_EXCEPTION
+            EXCEPTION_STREAM => {
+                let exception = rva as usize + 8;
+                info.exception_code = read_u32(bytes, exception).map(|code| format!("0x{:08X}", code));
+                exception_address = read_u64(bytes, exception + 16);
+            }
+            MODULE_LIST_STREAM => module_list = Some(rva as usize),
+            _ => {}
+        }
+    }
+
+    if let (Some(address), Some(list)) = (exception_address, module_list) {
+        let count = read_u32(bytes, list).unwrap_or(0) as usize;
+        info.faulting_module = (0..count)
+            .map(|i| list + 4 + i * MODULE_ENTRY_SIZE)
+            .find(|&module| {
+                let (Some(base), Some(size)) = (read_u64(bytes, module), read_u32(bytes, module + 8)) else {
+                    return false;
+                };
+                address >= base && address < base.saturating_add(size as u64)
+            })
+            .and_then(|module| read_u32(bytes, module + 20))
+            .and_then(|name_rva| read_minidump_string(bytes, name_rva as usize))
+            .map(|path| path.rsplit(['\\', '/']).next().unwrap_or(&path).to_string());
+    }
+    info
+}
+
+/// Stored under `crash` in the bug's `metadata_json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashTag {
+    pub process: String,
+    pub dump_file: String,
+    #[serde(flatten)]
+    pub info: CrashInfo,
+    pub detected_at: String,
+}
+
+/// Copy `dump` into the bug's folder, or `{session_folder}/_unsorted/` when
+/// `bug_id` is `None`, and tag the bug as a crash. Returns the copied path
+/// and the crash details. `db` is only locked to look up the bug and to tag
+/// it, not while the dump is copied.
+pub fn collect_dump(
+    db: &Mutex<Connection>,
+    session_folder: &Path,
+    bug_id: Option<&str>,
+    dump: &Path,
+    process: &str,
+) -> Result<(PathBuf, CrashInfo), String> {
+    let bug_folder = match bug_id {
+        Some(id) => Some(
+            BugRepository::new(&db.lock().unwrap())
+                .get(id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Bug not found: {}", id))?
+                .folder_path,
+        ),
+        None => None,
+    };
+    let dest_dir = bug_folder
+        .map(PathBuf::from)
+        .unwrap_or_else(|| session_folder.join("_unsorted"));
+    std::fs::create_dir_all(&dest_dir).map_err(|e| format!("Cannot create {:?}: {}", dest_dir, e))?;
+
+    let file_name = dump
+        .file_name()
+        .ok_or_else(|| format!("Not a file: {:?}", dump))?;
+    let dest = dest_dir.join(file_name);
+    std::fs::copy(dump, &dest).map_err(|e| format!("Failed to copy {:?}: {}", dump, e))?;
+
+    let info = std::fs::read(&dest).map(|bytes| read_crash_info(&bytes)).unwrap_or_default();
+
+    let conn = db.lock().unwrap();
+    if let Some(id) = bug_id {
+        let tag = CrashTag {
+            process: process.to_string(),
+            dump_file: file_name.to_string_lossy().to_string(),
+            info: info.clone(),
+            detected_at: Utc::now().to_rfc3339(),
+        };
+        let value = serde_json::to_value(&tag).map_err(|e| e.to_string())?;
+        BugRepository::new(&conn).merge_metadata(id, "crash", value).map_err(|e| e.to_string())?;
+    }
+
+    database::record_audit(
+        &conn,
+        "crash_dump.collect",
+        "bug",
+        bug_id.unwrap_or(""),
+        Some(serde_json::json!({ "source": dump.to_string_lossy(), "faultingModule": info.faulting_module })),
+    )?;
+    Ok((dest, info))
+}
+
+/// Session context a collected dump is filed under.
+pub struct CrashSession {
+    pub session_id: String,
+    pub session_folder: PathBuf,
+    pub active_bug: Arc<Mutex<Option<String>>>,
+}
+
+/// Watches the dump folder during a session.
+///
+/// Dropping the struct stops the watcher and its worker thread.
+pub struct CrashDumpWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl CrashDumpWatcher {
+    pub fn start(
+        folder: PathBuf,
+        process: String,
+        session: CrashSession,
+        db: Arc<Mutex<Connection>>,
+        app_handle: AppHandle,
+    ) -> Result<Self, String> {
+        // WER creates the folder on its first dump; watch it from the start
+        std::fs::create_dir_all(&folder)
+            .map_err(|e| format!("Cannot create crash dump folder {:?}: {}", folder, e))?;
+
+        let (tx, rx) = mpsc::channel::<PathBuf>();
+        let matches = process.clone();
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<Event, notify::Error>| {
+                let Ok(event) = res else { return };
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    return;
+                }
+                for path in event.paths {
+                    if is_dump_for(&path, &matches) {
+                        let _ = tx.send(path);
+                    }
+                }
+            },
+            notify::Config::default(),
+        )
+        .map_err(|e| format!("Failed to create crash dump watcher: {e}"))?;
+        watcher
+            .watch(&folder, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch crash dump folder: {e}"))?;
+
+        // The worker exits once the watcher (and with it `tx`) is dropped
+        std::thread::spawn(move || {
+            let mut pending: HashMap<PathBuf, (u64, Instant)> = HashMap::new();
+            let mut collected: HashSet<PathBuf> = HashSet::new();
+            loop {
+                match rx.recv_timeout(Duration::from_millis(500)) {
+                    Ok(path) if !collected.contains(&path) => {
+                        pending.entry(path).or_insert((0, Instant::now()));
+                    }
+                    Ok(_) => {}
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+
+                // A dump is complete once its size has settled
+                let mut ready = Vec::new();
+                pending.retain(|path, (size, since)| {
+                    let Ok(current) = std::fs::metadata(path).map(|m| m.len()) else {
+                        return false;
+                    };
+                    if current != *size {
+                        *size = current;
+                        *since = Instant::now();
+                    } else if current > 0 && since.elapsed() >= SETTLE_TIME {
+                        ready.push(path.clone());
+                        return false;
+                    }
+                    true
+                });
+
+                for dump in ready {
+                    collected.insert(dump.clone());
+                    let bug_id = session.active_bug.lock().unwrap().clone();
+                    match collect_dump(&db, &session.session_folder, bug_id.as_deref(), &dump, &process) {
+                        Ok((dest, info)) => {
+                            let _ = events::emit(
+                                &app_handle,
+                                &CrashDumpCollected {
+                                    session_id: session.session_id.clone(),
+                                    bug_id,
+                                    dump_path: dest.to_string_lossy().to_string(),
+                                    process: process.clone(),
+                                    faulting_module: info.faulting_module,
+                                    exception_code: info.exception_code,
+                                },
+                            );
+                        }
+                        Err(e) => eprintln!("Warning: failed to collect crash dump {:?}: {}", dump, e),
+                    }
+                }
+            }
+        });
+
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{init_database, Bug, BugStatus, BugType, Session, SessionOps, SessionRepository, SessionStatus};
+
+    /// Minimal minidump: an exception at `address` and one module `name`
+    /// loaded at 0x1000 with size 0x1000.
+    fn minidump(address: u64, name: &str) -> Vec<u8> {
+        let mut bytes = vec![0u8; 32];
+        bytes[0..4].copy_from_slice(&MINIDUMP_SIGNATURE.to_le_bytes());
+        bytes[8..12].copy_from_slice(&2u32.to_le_bytes());
+        bytes[12..16].copy_from_slice(&32u32.to_le_bytes());
+
+        let exception_rva = 32 + 24;
+        let module_list_rva = exception_rva + 32;
+        let name_rva = module_list_rva + 4 + MODULE_ENTRY_SIZE;
+        for (kind, rva) in [(EXCEPTION_STREAM, exception_rva), (MODULE_LIST_STREAM, module_list_rva)] {
+            bytes.extend_from_slice(&kind.to_le_bytes());
+            bytes.extend_from_slice(&0u32.to_le_bytes());
+            bytes.extend_from_slice(&(rva as u32).to_le_bytes());
+        }
+
+        // Exception stream: thread id, padding, code, flags, record, address
+        bytes.extend_from_slice(&[0u8; 8]);
+        bytes.extend_from_slice(&0xC000_0005u32.to_le_bytes());
+        bytes.extend_from_slice(&[0u8; 12]);
+        bytes.extend_from_slice(&address.to_le_bytes());
+
+        let mut module = vec![0u8; MODULE_ENTRY_SIZE];
+        module[0..8].copy_from_slice(&0x1000u64.to_le_bytes());
+        module[8..12].copy_from_slice(&0x1000u32.to_le_bytes());
+        module[20..24].copy_from_slice(&(name_rva as u32).to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&module);
+
+        let utf16: Vec<u8> = name.encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+        bytes.extend_from_slice(&(utf16.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&utf16);
+        bytes
+    }
+
+    #[test]
+    fn test_is_dump_for() {
+        assert!(is_dump_for(Path::new("C:/dumps/Shop.exe.4312.dmp"), "Shop.exe"));
+        assert!(is_dump_for(Path::new("C:/dumps/shop.exe.4312.dmp"), "Shop"));
+        assert!(is_dump_for(Path::new("shop.dmp"), "shop.exe"));
+        assert!(!is_dump_for(Path::new("ShopHelper.exe.4312.dmp"), "Shop.exe"));
+        assert!(!is_dump_for(Path::new("Shop.exe.4312.txt"), "Shop.exe"));
+    }
+
+    #[test]
+    fn test_read_crash_info() {
+        let info = read_crash_info(&minidump(0x1800, "C:\\Program Files\\Shop\\render.dll"));
+        assert_eq!(info.faulting_module.as_deref(), Some("render.dll"));
+        assert_eq!(info.exception_code.as_deref(), Some("0xC0000005"));
+
+        // Address outside every module: the code is still known
+        let info = read_crash_info(&minidump(0x9000, "render.dll"));
+        assert_eq!(info.faulting_module, None);
+        assert!(info.exception_code.is_some());
+
+        assert_eq!(read_crash_info(b"not a dump"), CrashInfo::default());
+    }
+
+    #[test]
+    fn test_collect_dump_tags_bug_or_goes_to_unsorted() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let now = "2024-01-01T10:00:00Z".to_string();
+        SessionRepository::new(&conn)
+            .create(&Session {
+                id: "s-1".to_string(),
+                started_at: now.clone(),
+                ended_at: None,
+                status: SessionStatus::Active,
+                folder_path: dir.path().to_string_lossy().to_string(),
+                session_notes: None,
+                environment_json: None,
+                original_snip_path: None,
+                created_at: now.clone(),
+                profile_id: None,
+                unlocked_at: None,
+                timezone: None,
+            })
+            .unwrap();
+        let bug_folder = dir.path().join("bug_001");
+        BugRepository::new(&conn)
+            .create(&Bug {
+                id: "b-1".to_string(),
+                session_id: "s-1".to_string(),
+                bug_number: 1,
+                display_id: "BUG-001".to_string(),
+                bug_type: BugType::Bug,
+                status: BugStatus::Capturing,
+                metadata_json: Some(r#"{"build":"1.2"}"#.to_string()),
+                folder_path: bug_folder.to_string_lossy().to_string(),
+                created_at: now.clone(),
+                updated_at: now,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let dumps = tempfile::tempdir().unwrap();
+        let dump = dumps.path().join("Shop.exe.77.dmp");
+        std::fs::write(&dump, minidump(0x1010, "render.dll")).unwrap();
+
+        let db = Mutex::new(conn);
+        let (dest, info) = collect_dump(&db, dir.path(), Some("b-1"), &dump, "Shop.exe").unwrap();
+        assert_eq!(dest, bug_folder.join("Shop.exe.77.dmp"));
+        assert_eq!(info.faulting_module.as_deref(), Some("render.dll"));
+
+        let metadata: serde_json::Value = serde_json::from_str(
+            &BugRepository::new(&db.lock().unwrap()).get("b-1").unwrap().unwrap().metadata_json.unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata["build"], "1.2");
+        assert_eq!(metadata["crash"]["process"], "Shop.exe");
+        assert_eq!(metadata["crash"]["faultingModule"], "render.dll");
+        assert_eq!(metadata["crash"]["dumpFile"], "Shop.exe.77.dmp");
+
+        let (dest, _) = collect_dump(&db, dir.path(), None, &dump, "Shop.exe").unwrap();
+        assert_eq!(dest, dir.path().join("_unsorted").join("Shop.exe.77.dmp"));
+        assert!(dest.exists());
+    }
+}
@@ -0,0 +1,304 @@
+//! Cursor and click marker burned into native screenshots.
+//!
+//! Snipping Tool leaves the cursor out, yet many UI bugs are about what was
+//! clicked. When enabled in the `capture.cursor_overlay` setting, the native
+//! capture paths (`window_capture`, `display_capture`) draw an arrow at the
+//! cursor position and a ripple where the mouse was last clicked. Clicks are
+//! tracked by [`ClickTracker`], which polls the mouse buttons while the click
+//! marker is enabled; a click older than [`CLICK_MAX_AGE`] is not drawn.
+//! Reading the cursor and buttons is Windows-only, like the native capture.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use image::{Rgba, RgbaImage};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Settings key holding [`CursorOverlaySettings`] as JSON.
+pub const CURSOR_OVERLAY_KEY: &str = "capture.cursor_overlay";
+
+/// Clicks older than this when the screenshot is taken get no marker.
+pub const CLICK_MAX_AGE: Duration = Duration::from_secs(5);
+
+/// How often the mouse buttons are polled.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+const RIPPLE_COLOR: Rgba<u8> = Rgba([255, 196, 0, 255]);
+
+/// Radii of the ripple rings at 100% scaling.
+const RIPPLE_RADII: [f64; 2] = [10.0, 18.0];
+
+const RIPPLE_THICKNESS: f64 = 2.5;
+
+/// Arrow cursor at 100% scaling: `#` outline, `.` fill, space transparent.
+/// The hot spot is the top-left pixel.
+const ARROW: [&str; 17] = [
+    "#",
+    "##",
+    "#.#",
+    "#..#",
+    "#...#",
+    "#....#",
+    "#.....#",
+    "#......#",
+    "#.......#",
+    "#........#",
+    "#.....#####",
+    "#..#..#",
+    "#.# #..#",
+    "##  #..#",
+    "#    #..#",
+    "     #..#",
+    "      ##",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CursorOverlaySettings {
+    /// Draw the cursor into native screenshots
+    pub draw_cursor: bool,
+    /// Draw a ripple where the mouse was last clicked
+    pub click_marker: bool,
+}
+
+impl CursorOverlaySettings {
+    /// Stored settings; missing or unreadable settings give the defaults.
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(CURSOR_OVERLAY_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(CURSOR_OVERLAY_KEY, &json)
+            .map_err(|e| format!("Failed to save cursor overlay settings: {}", e))
+    }
+}
+
+/// What to draw into one screenshot, in screen coordinates.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CursorOverlay {
+    pub cursor: Option<(i32, i32)>,
+    pub click: Option<(i32, i32)>,
+    /// Monitor scaling, so the marks match the size of the real cursor
+    pub scale: f64,
+}
+
+impl CursorOverlay {
+    /// The overlay for a screenshot taken now, as far as `settings` enable it.
+    pub fn current(settings: &CursorOverlaySettings, clicks: Option<&ClickTracker>) -> Self {
+        Self {
+            cursor: if settings.draw_cursor { cursor_position() } else { None },
+            click: if settings.click_marker { clicks.and_then(|t| t.recent_click(CLICK_MAX_AGE)) } else { None },
+            scale: 1.0,
+        }
+    }
+
+    /// Draw the overlay into `image`, whose top-left pixel is at `origin` on
+    /// screen. Marks outside the image are skipped.
+    pub fn burn_in(&self, image: &mut RgbaImage, origin: (i32, i32)) {
+        let scale = if self.scale > 0.0 { self.scale } else { 1.0 };
+        // The click goes under the cursor, which usually still points at it
+        if let Some((x, y)) = self.click {
+            draw_ripple(image, (x - origin.0) as f64, (y - origin.1) as f64, scale);
+        }
+        if let Some((x, y)) = self.cursor {
+            draw_arrow(image, x - origin.0, y - origin.1, scale);
+        }
+    }
+}
+
+/// Alpha-blend `color` at `alpha` (0..=1) over the pixel, if it is in the image.
+fn blend(image: &mut RgbaImage, x: i64, y: i64, color: Rgba<u8>, alpha: f64) {
+    if x < 0 || y < 0 || x >= image.width() as i64 || y >= image.height() as i64 || alpha <= 0.0 {
+        return;
+    }
+    let alpha = alpha.min(1.0);
+    let pixel = image.get_pixel_mut(x as u32, y as u32);
+    for channel in 0..3 {
+        let mixed = pixel[channel] as f64 * (1.0 - alpha) + color[channel] as f64 * alpha;
+        pixel[channel] = mixed.round() as u8;
+    }
+}
+
+/// Concentric anti-aliased rings around (`cx`, `cy`).
+pub fn draw_ripple(image: &mut RgbaImage, cx: f64, cy: f64, scale: f64) {
+    let outer = RIPPLE_RADII[RIPPLE_RADII.len() - 1] * scale + RIPPLE_THICKNESS * scale;
+    let half_width = RIPPLE_THICKNESS * scale / 2.0;
+    for y in (cy - outer).floor() as i64..=(cy + outer).ceil() as i64 {
+        for x in (cx - outer).floor() as i64..=(cx + outer).ceil() as i64 {
+            let distance = ((x as f64 - cx).powi(2) + (y as f64 - cy).powi(2)).sqrt();
+            // Inner ring opaque, outer ring fading out
+            for (i, radius) in RIPPLE_RADII.iter().enumerate() {
+                let coverage = (half_width + 0.5 - (distance - radius * scale).abs()).clamp(0.0, 1.0);
+                let strength = if i == 0 { 0.9 } else { 0.5 };
+                blend(image, x, y, RIPPLE_COLOR, coverage * strength);
+            }
+        }
+    }
+}
+
+/// The arrow cursor with its hot spot at (`x`, `y`), scaled by `scale`.
+pub fn draw_arrow(image: &mut RgbaImage, x: i32, y: i32, scale: f64) {
+    let factor = scale.round().max(1.0) as i64;
+    for (row, line) in ARROW.iter().enumerate() {
+        for (col, mark) in line.chars().enumerate() {
+            let color = match mark {
+                '#' => Rgba([0, 0, 0, 255]),
+                '.' => Rgba([255, 255, 255, 255]),
+                _ => continue,
+            };
+            for dy in 0..factor {
+                for dx in 0..factor {
+                    let px = x as i64 + col as i64 * factor + dx;
+                    let py = y as i64 + row as i64 * factor + dy;
+                    blend(image, px, py, color, 1.0);
+                }
+            }
+        }
+    }
+}
+
+/// Screen position and time of a click.
+type Click = ((i32, i32), Instant);
+
+/// Remembers where the mouse was last clicked, by polling the buttons on a
+/// background thread.
+///
+/// Dropping the struct stops the thread.
+pub struct ClickTracker {
+    last_click: Arc<Mutex<Option<Click>>>,
+    stop_flag: Arc<AtomicBool>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl ClickTracker {
+    pub fn start() -> Self {
+        let last_click = Arc::new(Mutex::new(None));
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (flag, clicks) = (Arc::clone(&stop_flag), Arc::clone(&last_click));
+
+        let worker = thread::spawn(move || {
+            let mut was_down = false;
+            while !flag.load(Ordering::Relaxed) {
+                let down = any_button_down();
+                if down && !was_down {
+                    if let Some(position) = cursor_position() {
+                        *clicks.lock().unwrap() = Some((position, Instant::now()));
+                    }
+                }
+                was_down = down;
+                thread::sleep(POLL_INTERVAL);
+            }
+        });
+
+        Self { last_click, stop_flag, worker: Some(worker) }
+    }
+
+    /// Where the last click happened, if it was within `max_age`.
+    pub fn recent_click(&self, max_age: Duration) -> Option<(i32, i32)> {
+        self.last_click
+            .lock()
+            .unwrap()
+            .filter(|(_, at)| at.elapsed() <= max_age)
+            .map(|(position, _)| position)
+    }
+}
+
+impl Drop for ClickTracker {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(windows)]
+fn cursor_position() -> Option<(i32, i32)> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
+
+    let mut point = POINT::default();
+    unsafe { GetCursorPos(&mut point) }.ok()?;
+    Some((point.x, point.y))
+}
+
+#[cfg(not(windows))]
+fn cursor_position() -> Option<(i32, i32)> {
+    None
+}
+
+#[cfg(windows)]
+fn any_button_down() -> bool {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_LBUTTON, VK_MBUTTON, VK_RBUTTON};
+
+    [VK_LBUTTON, VK_RBUTTON, VK_MBUTTON]
+        .iter()
+        .any(|key| unsafe { GetAsyncKeyState(key.0 as i32) } as u16 & 0x8000 != 0)
+}
+
+#[cfg(not(windows))]
+fn any_button_down() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    #[test]
+    fn test_arrow_hot_spot_and_scaling() {
+        let mut image = RgbaImage::from_pixel(60, 60, Rgba([40, 40, 40, 255]));
+        draw_arrow(&mut image, 10, 10, 1.0);
+        assert_eq!(*image.get_pixel(10, 10), Rgba([0, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(11, 12), WHITE);
+        assert_eq!(*image.get_pixel(9, 9), Rgba([40, 40, 40, 255]));
+
+        let mut image = RgbaImage::from_pixel(60, 60, Rgba([40, 40, 40, 255]));
+        draw_arrow(&mut image, 10, 10, 2.0);
+        // Row 2 col 1 of the mask is fill; at 2x it covers (12..14, 14..16)
+        assert_eq!(*image.get_pixel(13, 15), WHITE);
+    }
+
+    #[test]
+    fn test_ripple_rings_leave_centre_untouched() {
+        let background = Rgba([0, 0, 0, 255]);
+        let mut image = RgbaImage::from_pixel(60, 60, background);
+        draw_ripple(&mut image, 30.0, 30.0, 1.0);
+        assert_eq!(*image.get_pixel(30, 30), background);
+        // On the inner ring
+        assert_ne!(*image.get_pixel(40, 30), background);
+        // Between the rings
+        assert_eq!(*image.get_pixel(44, 30), background);
+        // On the outer ring
+        assert_ne!(*image.get_pixel(30, 48), background);
+    }
+
+    #[test]
+    fn test_burn_in_translates_screen_coordinates() {
+        let background = Rgba([40, 40, 40, 255]);
+        let mut image = RgbaImage::from_pixel(40, 40, background);
+        let overlay = CursorOverlay { cursor: Some((-1900, 210)), click: None, scale: 1.0 };
+        // Image of a monitor left of the primary, starting at (-1920, 200)
+        overlay.burn_in(&mut image, (-1920, 200));
+        assert_eq!(*image.get_pixel(20, 10), Rgba([0, 0, 0, 255]));
+
+        // A cursor on another monitor leaves the image alone
+        let mut image = RgbaImage::from_pixel(40, 40, background);
+        CursorOverlay { cursor: Some((500, 500)), click: Some((600, 600)), scale: 1.0 }.burn_in(&mut image, (0, 0));
+        assert!(image.pixels().all(|p| *p == background));
+    }
+}
@@ -0,0 +1,190 @@
+use rusqlite::{Connection, Result as SqlResult, Row, params};
+use crate::database::models::{Annotation, AnnotationData, InkLayer};
+
+/// Trait defining annotation operations
+#[allow(dead_code)]
+pub trait AnnotationOps {
+    fn add(&self, capture_id: &str, label: Option<&str>, data: &AnnotationData) -> SqlResult<i64>;
+    fn get(&self, id: i64) -> SqlResult<Option<Annotation>>;
+    fn delete(&self, id: i64) -> SqlResult<()>;
+    fn list_by_capture(&self, capture_id: &str) -> SqlResult<Vec<Annotation>>;
+    fn list_by_bug(&self, bug_id: &str) -> SqlResult<Vec<Annotation>>;
+    /// Freehand ink saved for a capture, if any.
+    fn get_ink(&self, capture_id: &str) -> SqlResult<Option<InkLayer>>;
+    /// Replace the capture's ink.
+    fn set_ink(&self, capture_id: &str, layer: &InkLayer) -> SqlResult<()>;
+}
+
+/// Annotation repository implementation
+#[allow(dead_code)]
+pub struct AnnotationRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> AnnotationRepository<'a> {
+    #[allow(dead_code)]
+    pub fn new(conn: &'a Connection) -> Self {
+        AnnotationRepository { conn }
+    }
+
+    fn from_row(row: &Row) -> SqlResult<Annotation> {
+        let data: String = row.get(3)?;
+        Ok(Annotation {
+            id: row.get(0)?,
+            capture_id: row.get(1)?,
+            label: row.get(2)?,
+            data: serde_json::from_str(&data).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+            })?,
+            created_at: row.get(4)?,
+        })
+    }
+}
+
+impl<'a> AnnotationOps for AnnotationRepository<'a> {
+    fn add(&self, capture_id: &str, label: Option<&str>, data: &AnnotationData) -> SqlResult<i64> {
+        let json = serde_json::to_string(data)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        self.conn.execute(
+            "INSERT INTO annotations (capture_id, kind, label, data, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![capture_id, data.kind(), label, json, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    fn get(&self, id: i64) -> SqlResult<Option<Annotation>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, capture_id, label, data, created_at FROM annotations WHERE id = ?1"
+        )?;
+        let mut rows = stmt.query_map(params![id], Self::from_row)?;
+        rows.next().transpose()
+    }
+
+    fn delete(&self, id: i64) -> SqlResult<()> {
+        self.conn.execute("DELETE FROM annotations WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    fn list_by_capture(&self, capture_id: &str) -> SqlResult<Vec<Annotation>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, capture_id, label, data, created_at FROM annotations
+             WHERE capture_id = ?1 ORDER BY id"
+        )?;
+        let rows = stmt.query_map(params![capture_id], Self::from_row)?;
+        rows.collect()
+    }
+
+    fn list_by_bug(&self, bug_id: &str) -> SqlResult<Vec<Annotation>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.id, a.capture_id, a.label, a.data, a.created_at FROM annotations a
+             JOIN captures c ON c.id = a.capture_id
+             WHERE c.bug_id = ?1 ORDER BY c.created_at, a.id"
+        )?;
+        let rows = stmt.query_map(params![bug_id], Self::from_row)?;
+        rows.collect()
+    }
+
+    fn get_ink(&self, capture_id: &str) -> SqlResult<Option<InkLayer>> {
+        let mut stmt = self.conn.prepare("SELECT data FROM annotation_ink WHERE capture_id = ?1")?;
+        let mut rows = stmt.query_map(params![capture_id], |row| {
+            let data: String = row.get(0)?;
+            serde_json::from_str(&data).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+            })
+        })?;
+        rows.next().transpose()
+    }
+
+    fn set_ink(&self, capture_id: &str, layer: &InkLayer) -> SqlResult<()> {
+        let json = serde_json::to_string(layer)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        self.conn.execute(
+            "INSERT INTO annotation_ink (capture_id, data, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(capture_id) DO UPDATE SET data = excluded.data, updated_at = excluded.updated_at",
+            params![capture_id, json, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{CaptureOps, CaptureRepository, Database};
+
+    fn insert_capture(db: &Database, id: &str, bug_id: &str) {
+        db.connection()
+            .execute_batch(&format!(
+                "INSERT OR IGNORE INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/tmp/s-1');
+                 INSERT OR IGNORE INTO bugs (id, session_id, bug_number, display_id, folder_path)
+                 VALUES ('{0}', 's-1', 1, 'Bug-01', '/tmp/s-1/{0}');",
+                bug_id
+            ))
+            .unwrap();
+        db.connection()
+            .execute(
+                "INSERT INTO captures (id, bug_id, session_id, file_name, file_path, file_type)
+                 VALUES (?1, ?2, 's-1', 'shot.png', '/tmp/shot.png', 'screenshot')",
+                params![id, bug_id],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_add_and_list_by_capture_and_bug() {
+        let db = Database::in_memory().unwrap();
+        insert_capture(&db, "c-1", "bug-1");
+        insert_capture(&db, "c-2", "bug-2");
+        let repo = AnnotationRepository::new(db.connection());
+
+        let color = AnnotationData::Color { x: 10, y: 20, hex: "#FF0000".to_string() };
+        let id = repo.add("c-1", Some("Button"), &color).unwrap();
+        repo.add("c-1", None, &AnnotationData::Distance { from: [0, 0], to: [0, 16] }).unwrap();
+        repo.add("c-2", None, &color).unwrap();
+
+        let stored = repo.get(id).unwrap().unwrap();
+        assert_eq!(stored.label.as_deref(), Some("Button"));
+        assert_eq!(stored.data, color);
+        assert_eq!(repo.list_by_capture("c-1").unwrap().len(), 2);
+        assert_eq!(repo.list_by_bug("bug-2").unwrap().len(), 1);
+
+        repo.delete(id).unwrap();
+        assert!(repo.get(id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_deleting_capture_removes_annotations() {
+        let db = Database::in_memory().unwrap();
+        insert_capture(&db, "c-1", "bug-1");
+        let repo = AnnotationRepository::new(db.connection());
+        repo.add("c-1", None, &AnnotationData::Distance { from: [1, 1], to: [5, 1] }).unwrap();
+
+        CaptureRepository::new(db.connection()).delete("c-1").unwrap();
+        assert!(repo.list_by_capture("c-1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ink_is_replaced_and_removed_with_capture() {
+        use crate::database::{InkPoint, InkStroke, InkTool};
+
+        let db = Database::in_memory().unwrap();
+        insert_capture(&db, "c-1", "bug-1");
+        let repo = AnnotationRepository::new(db.connection());
+        assert_eq!(repo.get_ink("c-1").unwrap(), None);
+
+        let stroke = |tool| InkStroke {
+            tool,
+            color: "#FF0000".to_string(),
+            width: 4.0,
+            points: vec![InkPoint { x: 1.0, y: 1.0, pressure: 0.7 }],
+        };
+        repo.set_ink("c-1", &InkLayer { strokes: vec![stroke(InkTool::Pen)], ..Default::default() }).unwrap();
+        let edited = InkLayer { strokes: vec![stroke(InkTool::Pen), stroke(InkTool::Eraser)], ..Default::default() };
+        repo.set_ink("c-1", &edited).unwrap();
+        assert_eq!(repo.get_ink("c-1").unwrap(), Some(edited));
+
+        CaptureRepository::new(db.connection()).delete("c-1").unwrap();
+        assert_eq!(repo.get_ink("c-1").unwrap(), None);
+    }
+}
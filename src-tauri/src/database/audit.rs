@@ -0,0 +1,222 @@
+use rusqlite::{Connection, Result as SqlResult, params};
+use crate::database::models::{AuditEntry, AuditFilter};
+use crate::database::settings::{SettingsOps, SettingsRepository};
+
+/// Settings key holding the tester's display name, recorded as the audit actor.
+pub const TESTER_NAME_KEY: &str = "tester_name";
+
+/// Trait defining audit log operations.
+///
+/// The audit log is append-only: entries can be recorded and read back, but
+/// never updated or deleted (enforced by triggers in the schema).
+#[allow(dead_code)]
+pub trait AuditOps {
+    fn record(&self, actor: &str, action: &str, entity_type: &str, entity_id: &str, details: Option<&str>) -> SqlResult<i64>;
+    fn list(&self, filter: &AuditFilter) -> SqlResult<Vec<AuditEntry>>;
+    fn list_for_entity(&self, entity_type: &str, entity_id: &str) -> SqlResult<Vec<AuditEntry>>;
+}
+
+/// Audit log repository implementation
+#[allow(dead_code)]
+pub struct AuditRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> AuditRepository<'a> {
+    #[allow(dead_code)]
+    pub fn new(conn: &'a Connection) -> Self {
+        AuditRepository { conn }
+    }
+}
+
+impl<'a> AuditOps for AuditRepository<'a> {
+    fn record(&self, actor: &str, action: &str, entity_type: &str, entity_id: &str, details: Option<&str>) -> SqlResult<i64> {
+        self.conn.execute(
+            "INSERT INTO audit_log (actor, action, entity_type, entity_id, details, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![actor, action, entity_type, entity_id, details, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    fn list(&self, filter: &AuditFilter) -> SqlResult<Vec<AuditEntry>> {
+        // Build dynamic WHERE clause based on which filter fields are present
+        let mut query = String::from(
+            "SELECT id, actor, action, entity_type, entity_id, details, created_at FROM audit_log WHERE 1 = 1"
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(ref action) = filter.action {
+            query.push_str(" AND action = ?");
+            params_vec.push(Box::new(action.clone()));
+        }
+        if let Some(ref entity_type) = filter.entity_type {
+            query.push_str(" AND entity_type = ?");
+            params_vec.push(Box::new(entity_type.clone()));
+        }
+        if let Some(ref entity_id) = filter.entity_id {
+            query.push_str(" AND entity_id = ?");
+            params_vec.push(Box::new(entity_id.clone()));
+        }
+        if let Some(ref actor) = filter.actor {
+            query.push_str(" AND actor = ?");
+            params_vec.push(Box::new(actor.clone()));
+        }
+        if let Some(ref since) = filter.since {
+            query.push_str(" AND created_at >= ?");
+            params_vec.push(Box::new(since.clone()));
+        }
+        if let Some(ref until) = filter.until {
+            query.push_str(" AND created_at < ?");
+            params_vec.push(Box::new(until.clone()));
+        }
+
+        query.push_str(" ORDER BY id DESC");
+        if let Some(limit) = filter.limit {
+            query.push_str(" LIMIT ?");
+            params_vec.push(Box::new(limit));
+        }
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = self.conn.prepare(&query)?;
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            Ok(AuditEntry {
+                id: row.get(0)?,
+                actor: row.get(1)?,
+                action: row.get(2)?,
+                entity_type: row.get(3)?,
+                entity_id: row.get(4)?,
+                details: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    fn list_for_entity(&self, entity_type: &str, entity_id: &str) -> SqlResult<Vec<AuditEntry>> {
+        let mut entries = self.list(&AuditFilter {
+            entity_type: Some(entity_type.to_string()),
+            entity_id: Some(entity_id.to_string()),
+            ..Default::default()
+        })?;
+        entries.reverse();
+        Ok(entries)
+    }
+}
+
+/// Resolve the tester identity recorded on audit entries: the configured
+/// `tester_name` setting, falling back to the OS user name.
+pub fn tester_identity(conn: &Connection) -> String {
+    SettingsRepository::new(conn)
+        .get(TESTER_NAME_KEY)
+        .ok()
+        .flatten()
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .or_else(|| std::env::var("USER").ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Record an audit entry attributed to the current tester.
+pub fn record_audit(
+    conn: &Connection,
+    action: &str,
+    entity_type: &str,
+    entity_id: &str,
+    details: Option<serde_json::Value>,
+) -> Result<(), String> {
+    let actor = tester_identity(conn);
+    let details = details.map(|d| d.to_string());
+    AuditRepository::new(conn)
+        .record(&actor, action, entity_type, entity_id, details.as_deref())
+        .map(|_| ())
+        .map_err(|e| format!("Failed to write audit entry: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    #[test]
+    fn test_record_and_list() {
+        let db = Database::in_memory().unwrap();
+        let repo = AuditRepository::new(db.connection());
+
+        let id = repo.record("alice", "session.unlock", "session", "s-1", Some(r#"{"reason":"typo"}"#)).unwrap();
+        assert!(id > 0);
+
+        let entries = repo.list_for_entity("session", "s-1").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].actor, "alice");
+        assert_eq!(entries[0].action, "session.unlock");
+        assert_eq!(entries[0].details.as_deref(), Some(r#"{"reason":"typo"}"#));
+    }
+
+    #[test]
+    fn test_list_filters_by_entity() {
+        let db = Database::in_memory().unwrap();
+        let repo = AuditRepository::new(db.connection());
+
+        repo.record("alice", "session.unlock", "session", "s-1", None).unwrap();
+        repo.record("alice", "session.unlock", "session", "s-2", None).unwrap();
+        repo.record("alice", "session.unlock", "session", "s-1", None).unwrap();
+
+        assert_eq!(repo.list_for_entity("session", "s-1").unwrap().len(), 2);
+        assert_eq!(repo.list_for_entity("session", "s-2").unwrap().len(), 1);
+        assert!(repo.list_for_entity("bug", "s-1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_list_with_filter() {
+        let db = Database::in_memory().unwrap();
+        let repo = AuditRepository::new(db.connection());
+
+        repo.record("alice", "setting.update", "setting", "theme", None).unwrap();
+        repo.record("bob", "setting.delete", "setting", "theme", None).unwrap();
+        repo.record("bob", "profile.delete", "profile", "p-1", None).unwrap();
+
+        let all = repo.list(&AuditFilter::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        // Newest first
+        assert_eq!(all[0].action, "profile.delete");
+
+        let by_actor = repo.list(&AuditFilter { actor: Some("bob".to_string()), ..Default::default() }).unwrap();
+        assert_eq!(by_actor.len(), 2);
+
+        let by_action = repo.list(&AuditFilter { action: Some("setting.update".to_string()), ..Default::default() }).unwrap();
+        assert_eq!(by_action.len(), 1);
+        assert_eq!(by_action[0].actor, "alice");
+
+        let limited = repo.list(&AuditFilter { limit: Some(1), ..Default::default() }).unwrap();
+        assert_eq!(limited.len(), 1);
+
+        let future = repo.list(&AuditFilter { since: Some("2999-01-01T00:00:00Z".to_string()), ..Default::default() }).unwrap();
+        assert!(future.is_empty());
+    }
+
+    #[test]
+    fn test_audit_log_is_append_only() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        AuditRepository::new(conn).record("alice", "profile.delete", "profile", "p-1", None).unwrap();
+
+        assert!(conn.execute("UPDATE audit_log SET actor = 'mallory'", []).is_err());
+        assert!(conn.execute("DELETE FROM audit_log", []).is_err());
+        assert_eq!(AuditRepository::new(conn).list(&AuditFilter::default()).unwrap()[0].actor, "alice");
+    }
+
+    #[test]
+    fn test_record_audit_uses_tester_name_setting() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        SettingsRepository::new(conn).set(TESTER_NAME_KEY, "Jordan QA").unwrap();
+
+        record_audit(conn, "setting.update", "setting", "theme", Some(serde_json::json!({"key": "theme"}))).unwrap();
+
+        let entries = AuditRepository::new(conn).list_for_entity("setting", "theme").unwrap();
+        assert_eq!(entries[0].actor, "Jordan QA");
+        assert_eq!(entries[0].details.as_deref(), Some(r#"{"key":"theme"}"#));
+    }
+}
@@ -1,5 +1,5 @@
-use rusqlite::{Connection, Result as SqlResult, params};
-use crate::database::models::{Bug, BugType, BugStatus, BugUpdate};
+use rusqlite::{Connection, OptionalExtension, Result as SqlResult, Row, params};
+use crate::database::models::{Bug, BugFilter, BugType, BugStatus, BugUpdate};
 
 /// Trait defining bug operations
 #[allow(dead_code)]
@@ -9,8 +9,15 @@ pub trait BugOps {
     fn update(&self, bug: &Bug) -> SqlResult<()>;
     fn delete(&self, id: &str) -> SqlResult<()>;
     fn list_by_session(&self, session_id: &str) -> SqlResult<Vec<Bug>>;
+    fn list_filtered(&self, session_id: &str, filter: &BugFilter) -> SqlResult<Vec<Bug>>;
     fn update_partial(&self, id: &str, update: &BugUpdate) -> SqlResult<()>;
     fn get_next_bug_number(&self, session_id: &str) -> SqlResult<i32>;
+    fn reserve_bug_numbers(&self, session_id: &str, range_start: i32, range_end: i32, source: Option<&str>) -> SqlResult<()>;
+    fn set_external_ticket(&self, id: &str, ticket_id: &str, ticket_key: &str, ticket_url: &str) -> SqlResult<()>;
+    fn set_external_status(&self, id: &str, status: &str, category: &str) -> SqlResult<()>;
+    fn set_severity_suggestion(&self, id: &str, suggestion_json: &str) -> SqlResult<()>;
+    fn get_severity_suggestion(&self, id: &str) -> SqlResult<Option<String>>;
+    fn merge_metadata(&self, id: &str, key: &str, value: serde_json::Value) -> SqlResult<()>;
 }
 
 /// Bug repository implementation
@@ -26,11 +33,44 @@ impl<'a> BugRepository<'a> {
     }
 }
 
+const BUG_COLUMNS: &str = "id, session_id, bug_number, display_id, type, title, notes, description, ai_description, status, meeting_id, software_version, console_parse_json, metadata_json, custom_metadata, folder_path, created_at, updated_at, external_ticket_id, external_ticket_key, external_ticket_url, external_status, external_status_category";
+
+/// Map a row selected with [`BUG_COLUMNS`].
+fn bug_from_row(row: &Row) -> SqlResult<Bug> {
+    let type_str: String = row.get(4)?;
+    let status_str: String = row.get(9)?;
+    Ok(Bug {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        bug_number: row.get(2)?,
+        display_id: row.get(3)?,
+        bug_type: BugType::from_str(&type_str).unwrap_or(BugType::Bug),
+        title: row.get(5)?,
+        notes: row.get(6)?,
+        description: row.get(7)?,
+        ai_description: row.get(8)?,
+        status: BugStatus::from_str(&status_str).unwrap_or(BugStatus::Captured),
+        meeting_id: row.get(10)?,
+        software_version: row.get(11)?,
+        console_parse_json: row.get(12)?,
+        metadata_json: row.get(13)?,
+        custom_metadata: row.get(14)?,
+        folder_path: row.get(15)?,
+        created_at: row.get(16)?,
+        updated_at: row.get(17)?,
+        external_ticket_id: row.get(18)?,
+        external_ticket_key: row.get(19)?,
+        external_ticket_url: row.get(20)?,
+        external_status: row.get(21)?,
+        external_status_category: row.get(22)?,
+    })
+}
+
 impl<'a> BugOps for BugRepository<'a> {
     fn create(&self, bug: &Bug) -> SqlResult<()> {
         self.conn.execute(
-            "INSERT INTO bugs (id, session_id, bug_number, display_id, type, title, notes, description, ai_description, status, meeting_id, software_version, console_parse_json, metadata_json, custom_metadata, folder_path, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+            "INSERT INTO bugs (id, session_id, bug_number, display_id, type, title, notes, description, ai_description, status, meeting_id, software_version, console_parse_json, metadata_json, custom_metadata, folder_path, created_at, updated_at, external_ticket_id, external_ticket_key, external_ticket_url)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
             params![
                 bug.id,
                 bug.session_id,
@@ -50,6 +90,9 @@ impl<'a> BugOps for BugRepository<'a> {
                 bug.folder_path,
                 bug.created_at,
                 bug.updated_at,
+                bug.external_ticket_id,
+                bug.external_ticket_key,
+                bug.external_ticket_url,
             ],
         )?;
         Ok(())
@@ -57,7 +100,7 @@ impl<'a> BugOps for BugRepository<'a> {
 
     fn get(&self, id: &str) -> SqlResult<Option<Bug>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, session_id, bug_number, display_id, type, title, notes, description, ai_description, status, meeting_id, software_version, console_parse_json, metadata_json, custom_metadata, folder_path, created_at, updated_at
+            "SELECT id, session_id, bug_number, display_id, type, title, notes, description, ai_description, status, meeting_id, software_version, console_parse_json, metadata_json, custom_metadata, folder_path, created_at, updated_at, external_ticket_id, external_ticket_key, external_ticket_url, external_status, external_status_category
              FROM bugs WHERE id = ?1"
         )?;
 
@@ -85,6 +128,11 @@ impl<'a> BugOps for BugRepository<'a> {
                 folder_path: row.get(15)?,
                 created_at: row.get(16)?,
                 updated_at: row.get(17)?,
+                external_ticket_id: row.get(18)?,
+                external_ticket_key: row.get(19)?,
+                external_ticket_url: row.get(20)?,
+                external_status: row.get(21)?,
+                external_status_category: row.get(22)?,
             }))
         } else {
             Ok(None)
@@ -93,7 +141,7 @@ impl<'a> BugOps for BugRepository<'a> {
 
     fn update(&self, bug: &Bug) -> SqlResult<()> {
         self.conn.execute(
-            "UPDATE bugs SET session_id = ?2, bug_number = ?3, display_id = ?4, type = ?5, title = ?6, notes = ?7, description = ?8, ai_description = ?9, status = ?10, meeting_id = ?11, software_version = ?12, console_parse_json = ?13, metadata_json = ?14, custom_metadata = ?15, folder_path = ?16, updated_at = datetime('now')
+            "UPDATE bugs SET session_id = ?2, bug_number = ?3, display_id = ?4, type = ?5, title = ?6, notes = ?7, description = ?8, ai_description = ?9, status = ?10, meeting_id = ?11, software_version = ?12, console_parse_json = ?13, metadata_json = ?14, custom_metadata = ?15, folder_path = ?16, external_ticket_id = ?17, external_ticket_key = ?18, external_ticket_url = ?19, updated_at = datetime('now')
              WHERE id = ?1",
             params![
                 bug.id,
@@ -112,47 +160,75 @@ impl<'a> BugOps for BugRepository<'a> {
                 bug.metadata_json,
                 bug.custom_metadata,
                 bug.folder_path,
+                bug.external_ticket_id,
+                bug.external_ticket_key,
+                bug.external_ticket_url,
             ],
         )?;
         Ok(())
     }
 
     fn delete(&self, id: &str) -> SqlResult<()> {
+        self.conn.execute("DELETE FROM bug_links WHERE bug_id = ?1 OR related_bug_id = ?1", params![id])?;
         self.conn.execute("DELETE FROM bugs WHERE id = ?1", params![id])?;
         Ok(())
     }
 
     fn list_by_session(&self, session_id: &str) -> SqlResult<Vec<Bug>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, session_id, bug_number, display_id, type, title, notes, description, ai_description, status, meeting_id, software_version, console_parse_json, metadata_json, custom_metadata, folder_path, created_at, updated_at
-             FROM bugs WHERE session_id = ?1 ORDER BY bug_number ASC"
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM bugs WHERE session_id = ?1 ORDER BY bug_number ASC",
+            BUG_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![session_id], bug_from_row)?;
+        rows.collect()
+    }
 
-        let rows = stmt.query_map(params![session_id], |row| {
-            let type_str: String = row.get(4)?;
-            let status_str: String = row.get(9)?;
-            Ok(Bug {
-                id: row.get(0)?,
-                session_id: row.get(1)?,
-                bug_number: row.get(2)?,
-                display_id: row.get(3)?,
-                bug_type: BugType::from_str(&type_str).unwrap_or(BugType::Bug),
-                title: row.get(5)?,
-                notes: row.get(6)?,
-                description: row.get(7)?,
-                ai_description: row.get(8)?,
-                status: BugStatus::from_str(&status_str).unwrap_or(BugStatus::Captured),
-                meeting_id: row.get(10)?,
-                software_version: row.get(11)?,
-                console_parse_json: row.get(12)?,
-                metadata_json: row.get(13)?,
-                custom_metadata: row.get(14)?,
-                folder_path: row.get(15)?,
-                created_at: row.get(16)?,
-                updated_at: row.get(17)?,
-            })
-        })?;
+    fn list_filtered(&self, session_id: &str, filter: &BugFilter) -> SqlResult<Vec<Bug>> {
+        // Build the WHERE clause from the filter fields that are set
+        let mut query = format!("SELECT {} FROM bugs WHERE session_id = ?", BUG_COLUMNS);
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(session_id.to_string())];
+
+        if let Some(ref status) = filter.status {
+            query.push_str(" AND status = ?");
+            params_vec.push(Box::new(status.as_str().to_string()));
+        }
+        if let Some(ref bug_type) = filter.bug_type {
+            query.push_str(" AND type = ?");
+            params_vec.push(Box::new(bug_type.as_str().to_string()));
+        }
+        if let Some(ref severity) = filter.severity {
+            // Matches the expression of idx_bugs_session_severity
+            query.push_str(" AND json_extract(custom_metadata, '$.severity') = ?");
+            params_vec.push(Box::new(severity.clone()));
+        }
+        match filter.has_ticket {
+            Some(true) => query.push_str(" AND external_ticket_id IS NOT NULL"),
+            Some(false) => query.push_str(" AND external_ticket_id IS NULL"),
+            None => {}
+        }
+        match filter.has_description {
+            Some(true) => query.push_str(" AND TRIM(COALESCE(description, '')) != ''"),
+            Some(false) => query.push_str(" AND TRIM(COALESCE(description, '')) = ''"),
+            None => {}
+        }
+        if let Some(text) = filter.text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            let pattern = format!(
+                "%{}%",
+                text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+            );
+            query.push_str(" AND (display_id LIKE ? ESCAPE '\\' OR title LIKE ? ESCAPE '\\' OR notes LIKE ? ESCAPE '\\' OR description LIKE ? ESCAPE '\\')");
+            for _ in 0..4 {
+                params_vec.push(Box::new(pattern.clone()));
+            }
+        }
+        query.push_str(" ORDER BY bug_number ASC");
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter()
+            .map(|p| p.as_ref() as &dyn rusqlite::ToSql)
+            .collect();
 
+        let mut stmt = self.conn.prepare(&query)?;
+        let rows = stmt.query_map(params_refs.as_slice(), bug_from_row)?;
         rows.collect()
     }
 
@@ -210,14 +286,87 @@ impl<'a> BugOps for BugRepository<'a> {
         Ok(())
     }
 
+    /// Next free bug number for a session.
+    ///
+    /// Takes the maximum over the stored bug numbers, the numeric suffix of
+    /// display IDs (imported bugs may carry a display ID from another machine
+    /// that disagrees with their `bug_number`) and any reserved ranges, so a new
+    /// bug never collides with imported or merged numbering.
     fn get_next_bug_number(&self, session_id: &str) -> SqlResult<i32> {
         let mut stmt = self.conn.prepare(
-            "SELECT COALESCE(MAX(bug_number), 0) + 1 FROM bugs WHERE session_id = ?1"
+            "SELECT MAX(
+                 COALESCE((SELECT MAX(bug_number) FROM bugs WHERE session_id = ?1), 0),
+                 COALESCE((SELECT MAX(CAST(SUBSTR(display_id, INSTR(display_id, '-') + 1) AS INTEGER))
+                           FROM bugs WHERE session_id = ?1 AND INSTR(display_id, '-') > 0), 0),
+                 COALESCE((SELECT MAX(range_end) FROM bug_number_reservations WHERE session_id = ?1), 0)
+             ) + 1"
         )?;
 
         let next_number: i32 = stmt.query_row(params![session_id], |row| row.get(0))?;
         Ok(next_number)
     }
+
+    /// Reserve an inclusive range of bug numbers for a session so that
+    /// `get_next_bug_number` never hands them out (e.g. numbers already used by
+    /// an imported copy of the session on another machine).
+    fn reserve_bug_numbers(&self, session_id: &str, range_start: i32, range_end: i32, source: Option<&str>) -> SqlResult<()> {
+        let (start, end) = if range_start <= range_end {
+            (range_start, range_end)
+        } else {
+            (range_end, range_start)
+        };
+        self.conn.execute(
+            "INSERT INTO bug_number_reservations (session_id, range_start, range_end, source)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![session_id, start, end, source],
+        )?;
+        Ok(())
+    }
+
+    /// Record the ticket filed for a bug. The status of a previous ticket is cleared.
+    fn set_external_ticket(&self, id: &str, ticket_id: &str, ticket_key: &str, ticket_url: &str) -> SqlResult<()> {
+        self.conn.execute(
+            "UPDATE bugs SET external_ticket_id = ?2, external_ticket_key = ?3, external_ticket_url = ?4,
+                external_status = NULL, external_status_category = NULL, updated_at = datetime('now')
+             WHERE id = ?1",
+            params![id, ticket_id, ticket_key, ticket_url],
+        )?;
+        Ok(())
+    }
+
+    /// Store the ticket status fetched from the provider. `updated_at` is left
+    /// alone: a sync is not an edit of the bug.
+    fn set_external_status(&self, id: &str, status: &str, category: &str) -> SqlResult<()> {
+        self.conn.execute(
+            "UPDATE bugs SET external_status = ?2, external_status_category = ?3 WHERE id = ?1",
+            params![id, status, category],
+        )?;
+        Ok(())
+    }
+
+    /// Store the AI severity suggestion (JSON). The bug's own severity is untouched.
+    fn set_severity_suggestion(&self, id: &str, suggestion_json: &str) -> SqlResult<()> {
+        self.conn.execute(
+            "UPDATE bugs SET severity_suggestion = ?2 WHERE id = ?1",
+            params![id, suggestion_json],
+        )?;
+        Ok(())
+    }
+
+    fn get_severity_suggestion(&self, id: &str) -> SqlResult<Option<String>> {
+        self.conn
+            .query_row("SELECT severity_suggestion FROM bugs WHERE id = ?1", params![id], |row| row.get(0))
+            .optional()
+            .map(Option::flatten)
+    }
+
+    /// Set one key of the bug's `metadata_json` (see [`Bug::set_metadata`]).
+    /// Fails with `QueryReturnedNoRows` when there is no such bug.
+    fn merge_metadata(&self, id: &str, key: &str, value: serde_json::Value) -> SqlResult<()> {
+        let mut bug = self.get(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        bug.set_metadata(key, value);
+        self.update(&bug)
+    }
 }
 
 #[cfg(test)]
@@ -238,6 +387,8 @@ mod tests {
             original_snip_path: None,
             created_at: "2024-01-01T10:00:00Z".to_string(),
             profile_id: None,
+            unlocked_at: None,
+            timezone: None,
         };
         let repo = SessionRepository::new(db.connection());
         repo.create(&session).unwrap();
@@ -252,17 +403,11 @@ mod tests {
             bug_type: BugType::Bug,
             title: Some("Test bug".to_string()),
             notes: Some("Test notes".to_string()),
-            description: None,
-            ai_description: None,
             status: BugStatus::Captured,
-            meeting_id: None,
-            software_version: None,
-            console_parse_json: None,
-            metadata_json: None,
-            custom_metadata: None,
             folder_path: format!("/test/bugs/bug-{}", bug_number),
             created_at: "2024-01-01T10:00:00Z".to_string(),
             updated_at: "2024-01-01T10:00:00Z".to_string(),
+            ..Default::default()
         }
     }
 
@@ -381,6 +526,52 @@ mod tests {
         assert_eq!(next, 2);
     }
 
+    #[test]
+    fn test_get_next_bug_number_after_imported_bugs() {
+        let db = Database::in_memory().unwrap();
+        create_test_session(&db, "session-imp");
+        let repo = BugRepository::new(db.connection());
+
+        // Bugs imported from another machine, with a gap in the numbering
+        repo.create(&create_test_bug("session-imp", "bug-imp-1", 1)).unwrap();
+        repo.create(&create_test_bug("session-imp", "bug-imp-5", 5)).unwrap();
+
+        assert_eq!(repo.get_next_bug_number("session-imp").unwrap(), 6);
+    }
+
+    #[test]
+    fn test_get_next_bug_number_respects_imported_display_ids() {
+        let db = Database::in_memory().unwrap();
+        create_test_session(&db, "session-disp");
+        let repo = BugRepository::new(db.connection());
+
+        // Imported bug whose display ID was assigned on another machine
+        let mut bug = create_test_bug("session-disp", "bug-disp-1", 2);
+        bug.display_id = "BUG-009".to_string();
+        repo.create(&bug).unwrap();
+
+        assert_eq!(repo.get_next_bug_number("session-disp").unwrap(), 10);
+    }
+
+    #[test]
+    fn test_get_next_bug_number_skips_reserved_ranges() {
+        let db = Database::in_memory().unwrap();
+        create_test_session(&db, "session-res");
+        create_test_session(&db, "session-other");
+        let repo = BugRepository::new(db.connection());
+
+        repo.create(&create_test_bug("session-res", "bug-res-1", 1)).unwrap();
+        repo.reserve_bug_numbers("session-res", 2, 12, Some("import:laptop-b")).unwrap();
+        assert_eq!(repo.get_next_bug_number("session-res").unwrap(), 13);
+
+        // Reversed ranges are normalised
+        repo.reserve_bug_numbers("session-res", 20, 15, None).unwrap();
+        assert_eq!(repo.get_next_bug_number("session-res").unwrap(), 21);
+
+        // Reservations are scoped to their session
+        assert_eq!(repo.get_next_bug_number("session-other").unwrap(), 1);
+    }
+
     #[test]
     fn test_update_bug_title() {
         let db = Database::in_memory().unwrap();
@@ -423,4 +614,116 @@ mod tests {
         let updated = repo.get("bug-title-2").unwrap().unwrap();
         assert_eq!(updated.title, Some(String::new()));
     }
+
+    #[test]
+    fn test_set_external_ticket() {
+        let db = Database::in_memory().unwrap();
+        create_test_session(&db, "session-10");
+        let repo = BugRepository::new(db.connection());
+        repo.create(&create_test_bug("session-10", "bug-ticket-1", 1)).unwrap();
+        assert!(repo.get("bug-ticket-1").unwrap().unwrap().external_ticket_key.is_none());
+
+        repo.set_external_ticket("bug-ticket-1", "issue-uuid", "QA-42", "https://linear.app/t/QA-42").unwrap();
+
+        let bug = repo.get("bug-ticket-1").unwrap().unwrap();
+        assert_eq!(bug.external_ticket_id.as_deref(), Some("issue-uuid"));
+        assert_eq!(bug.external_ticket_key.as_deref(), Some("QA-42"));
+        assert_eq!(bug.external_ticket_url.as_deref(), Some("https://linear.app/t/QA-42"));
+    }
+
+    #[test]
+    fn test_set_external_status_cleared_by_new_ticket() {
+        let db = Database::in_memory().unwrap();
+        create_test_session(&db, "session-10");
+        let repo = BugRepository::new(db.connection());
+        repo.create(&create_test_bug("session-10", "bug-ticket-2", 1)).unwrap();
+        repo.set_external_ticket("bug-ticket-2", "issue-uuid", "QA-42", "https://linear.app/t/QA-42").unwrap();
+
+        repo.set_external_status("bug-ticket-2", "Done", "done").unwrap();
+        let bug = repo.get("bug-ticket-2").unwrap().unwrap();
+        assert_eq!(bug.external_status.as_deref(), Some("Done"));
+        assert_eq!(bug.external_status_category.as_deref(), Some("done"));
+
+        repo.set_external_ticket("bug-ticket-2", "issue-uuid-2", "QA-43", "https://linear.app/t/QA-43").unwrap();
+        let bug = repo.get("bug-ticket-2").unwrap().unwrap();
+        assert!(bug.external_status.is_none());
+        assert!(bug.external_status_category.is_none());
+    }
+
+    #[test]
+    fn test_severity_suggestion_is_kept_apart() {
+        let db = Database::in_memory().unwrap();
+        create_test_session(&db, "session-12");
+        let repo = BugRepository::new(db.connection());
+        let mut bug = create_test_bug("session-12", "bug-sev-1", 1);
+        bug.custom_metadata = Some(r#"{"severity":"low"}"#.to_string());
+        repo.create(&bug).unwrap();
+        assert_eq!(repo.get_severity_suggestion("bug-sev-1").unwrap(), None);
+        assert_eq!(repo.get_severity_suggestion("missing").unwrap(), None);
+
+        repo.set_severity_suggestion("bug-sev-1", r#"{"severity":"high"}"#).unwrap();
+        assert_eq!(repo.get_severity_suggestion("bug-sev-1").unwrap().as_deref(), Some(r#"{"severity":"high"}"#));
+        // The bug's own severity is not changed
+        assert_eq!(repo.get("bug-sev-1").unwrap().unwrap().custom_metadata.as_deref(), Some(r#"{"severity":"low"}"#));
+    }
+
+    #[test]
+    fn test_merge_metadata_keeps_other_keys() {
+        let db = Database::in_memory().unwrap();
+        create_test_session(&db, "session-13");
+        let repo = BugRepository::new(db.connection());
+        let mut bug = create_test_bug("session-13", "bug-meta-1", 1);
+        bug.metadata_json = Some(r#"{"build":"1.2"}"#.to_string());
+        repo.create(&bug).unwrap();
+
+        repo.merge_metadata("bug-meta-1", "locale", serde_json::json!({"language": "de-DE"})).unwrap();
+        repo.merge_metadata("bug-meta-1", "build", serde_json::json!("1.3")).unwrap();
+
+        let stored = repo.get("bug-meta-1").unwrap().unwrap().metadata_json.unwrap();
+        assert_eq!(stored, r#"{"build":"1.3","locale":{"language":"de-DE"}}"#);
+        assert!(matches!(
+            repo.merge_metadata("missing", "build", serde_json::json!("1.3")),
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        ));
+    }
+
+    #[test]
+    fn test_list_filtered() {
+        let db = Database::in_memory().unwrap();
+        create_test_session(&db, "session-11");
+        let repo = BugRepository::new(db.connection());
+
+        let mut crash = create_test_bug("session-11", "bug-f1", 1);
+        crash.title = Some("Crash on 100% zoom".to_string());
+        crash.description = Some("Steps to reproduce".to_string());
+        crash.custom_metadata = Some(r#"{"severity":"high"}"#.to_string());
+        crash.status = BugStatus::Reviewed;
+        repo.create(&crash).unwrap();
+        let mut feature = create_test_bug("session-11", "bug-f2", 2);
+        feature.bug_type = BugType::Feature;
+        feature.description = Some("   ".to_string());
+        feature.custom_metadata = Some(r#"{"severity":"low"}"#.to_string());
+        repo.create(&feature).unwrap();
+        repo.create(&create_test_bug("session-11", "bug-f3", 3)).unwrap();
+        repo.set_external_ticket("bug-f3", "issue-uuid", "QA-1", "https://linear.app/t/QA-1").unwrap();
+
+        let ids = |filter: BugFilter| -> Vec<String> {
+            repo.list_filtered("session-11", &filter).unwrap().into_iter().map(|b| b.id).collect()
+        };
+        assert_eq!(ids(BugFilter::default()), vec!["bug-f1", "bug-f2", "bug-f3"]);
+        assert_eq!(ids(BugFilter { status: Some(BugStatus::Reviewed), ..Default::default() }), vec!["bug-f1"]);
+        assert_eq!(ids(BugFilter { bug_type: Some(BugType::Feature), ..Default::default() }), vec!["bug-f2"]);
+        assert_eq!(ids(BugFilter { severity: Some("low".to_string()), ..Default::default() }), vec!["bug-f2"]);
+        assert_eq!(ids(BugFilter { has_ticket: Some(true), ..Default::default() }), vec!["bug-f3"]);
+        assert_eq!(ids(BugFilter { has_description: Some(true), ..Default::default() }), vec!["bug-f1"]);
+        assert_eq!(
+            ids(BugFilter { has_ticket: Some(false), has_description: Some(false), ..Default::default() }),
+            vec!["bug-f2"]
+        );
+        // Case-insensitive, and LIKE wildcards in the text are literal
+        assert_eq!(ids(BugFilter { text: Some("crash".to_string()), ..Default::default() }), vec!["bug-f1"]);
+        assert_eq!(ids(BugFilter { text: Some("100%".to_string()), ..Default::default() }), vec!["bug-f1"]);
+        assert!(ids(BugFilter { text: Some("_".to_string()), ..Default::default() }).is_empty());
+        assert_eq!(ids(BugFilter { text: Some("bug-03".to_string()), ..Default::default() }), vec!["bug-f3"]);
+    }
 }
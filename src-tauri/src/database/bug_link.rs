@@ -0,0 +1,142 @@
+use rusqlite::{Connection, Result as SqlResult, Row, params};
+use crate::database::models::{BugLink, BugLinkKind};
+
+/// Trait defining bug link operations
+#[allow(dead_code)]
+pub trait BugLinkOps {
+    /// Link `bug_id` to `related_bug_id`; linking the same pair twice is a no-op.
+    fn create(&self, bug_id: &str, related_bug_id: &str, kind: BugLinkKind) -> SqlResult<()>;
+    /// Remove a link; returns whether it existed.
+    fn remove(&self, bug_id: &str, related_bug_id: &str, kind: BugLinkKind) -> SqlResult<bool>;
+    /// Links in either direction that involve `bug_id`
+    fn list_by_bug(&self, bug_id: &str) -> SqlResult<Vec<BugLink>>;
+    /// Links between bugs of a session
+    fn list_by_session(&self, session_id: &str) -> SqlResult<Vec<BugLink>>;
+}
+
+/// Bug link repository implementation
+#[allow(dead_code)]
+pub struct BugLinkRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> BugLinkRepository<'a> {
+    #[allow(dead_code)]
+    pub fn new(conn: &'a Connection) -> Self {
+        BugLinkRepository { conn }
+    }
+
+    fn from_row(row: &Row) -> SqlResult<BugLink> {
+        let kind: String = row.get(3)?;
+        Ok(BugLink {
+            id: row.get(0)?,
+            bug_id: row.get(1)?,
+            related_bug_id: row.get(2)?,
+            kind: BugLinkKind::from_str(&kind).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, e.into())
+            })?,
+            created_at: row.get(4)?,
+        })
+    }
+}
+
+impl<'a> BugLinkOps for BugLinkRepository<'a> {
+    fn create(&self, bug_id: &str, related_bug_id: &str, kind: BugLinkKind) -> SqlResult<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO bug_links (bug_id, related_bug_id, kind, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![bug_id, related_bug_id, kind.as_str(), chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn remove(&self, bug_id: &str, related_bug_id: &str, kind: BugLinkKind) -> SqlResult<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM bug_links WHERE bug_id = ?1 AND related_bug_id = ?2 AND kind = ?3",
+            params![bug_id, related_bug_id, kind.as_str()],
+        )?;
+        Ok(removed > 0)
+    }
+
+    fn list_by_bug(&self, bug_id: &str) -> SqlResult<Vec<BugLink>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, bug_id, related_bug_id, kind, created_at FROM bug_links
+             WHERE bug_id = ?1 OR related_bug_id = ?1 ORDER BY id"
+        )?;
+        let rows = stmt.query_map(params![bug_id], Self::from_row)?;
+        rows.collect()
+    }
+
+    fn list_by_session(&self, session_id: &str) -> SqlResult<Vec<BugLink>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT l.id, l.bug_id, l.related_bug_id, l.kind, l.created_at FROM bug_links l
+             JOIN bugs b ON b.id = l.bug_id
+             WHERE b.session_id = ?1 ORDER BY l.id"
+        )?;
+        let rows = stmt.query_map(params![session_id], Self::from_row)?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{BugOps, BugRepository, Database};
+
+    fn insert_bugs(db: &Database) {
+        db.connection()
+            .execute_batch(
+                "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/tmp/s-1');
+                 INSERT INTO bugs (id, session_id, bug_number, display_id, folder_path)
+                 VALUES ('b-1', 's-1', 1, 'BUG-001', '/tmp/s-1/bug_001'),
+                        ('b-2', 's-1', 2, 'BUG-002', '/tmp/s-1/bug_002');",
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_create_and_list_links() {
+        let db = Database::in_memory().unwrap();
+        insert_bugs(&db);
+        let repo = BugLinkRepository::new(db.connection());
+
+        repo.create("b-2", "b-1", BugLinkKind::RelatesTo).unwrap();
+        repo.create("b-2", "b-1", BugLinkKind::RelatesTo).unwrap();
+
+        let links = repo.list_by_bug("b-1").unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].bug_id, "b-2");
+        assert_eq!(links[0].related_bug_id, "b-1");
+        assert_eq!(links[0].kind, BugLinkKind::RelatesTo);
+        assert_eq!(repo.list_by_bug("b-2").unwrap(), links);
+    }
+
+    #[test]
+    fn test_remove_and_list_by_session() {
+        let db = Database::in_memory().unwrap();
+        insert_bugs(&db);
+        let repo = BugLinkRepository::new(db.connection());
+        repo.create("b-2", "b-1", BugLinkKind::Duplicates).unwrap();
+        repo.create("b-1", "b-2", BugLinkKind::Blocks).unwrap();
+
+        let kinds: Vec<BugLinkKind> = repo.list_by_session("s-1").unwrap().iter().map(|l| l.kind).collect();
+        assert_eq!(kinds, vec![BugLinkKind::Duplicates, BugLinkKind::Blocks]);
+        assert!(repo.list_by_session("s-2").unwrap().is_empty());
+
+        // Direction and kind must match
+        assert!(!repo.remove("b-1", "b-2", BugLinkKind::Duplicates).unwrap());
+        assert!(repo.remove("b-2", "b-1", BugLinkKind::Duplicates).unwrap());
+        assert_eq!(repo.list_by_session("s-1").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_deleting_bug_removes_its_links() {
+        let db = Database::in_memory().unwrap();
+        insert_bugs(&db);
+        let repo = BugLinkRepository::new(db.connection());
+        repo.create("b-2", "b-1", BugLinkKind::RelatesTo).unwrap();
+
+        BugRepository::new(db.connection()).delete("b-2").unwrap();
+        assert!(repo.list_by_bug("b-1").unwrap().is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use rusqlite::{Connection, Result as SqlResult, params};
+use rusqlite::{Connection, OptionalExtension, Result as SqlResult, params};
 use crate::database::models::{Capture, CaptureType};
 
 /// Trait defining capture operations
@@ -12,6 +12,11 @@ pub trait CaptureOps {
     fn list_by_session(&self, session_id: &str) -> SqlResult<Vec<Capture>>;
     fn list_console_captures(&self, bug_id: &str) -> SqlResult<Vec<Capture>>;
     fn list_unsorted(&self, session_id: &str) -> SqlResult<Vec<Capture>>;
+    fn set_created_at(&self, id: &str, created_at: &str) -> SqlResult<()>;
+    fn set_attachment_urls(&self, id: &str, url: Option<&str>, annotated_url: Option<&str>) -> SqlResult<()>;
+    fn get_attachment_urls(&self, id: &str) -> SqlResult<(Option<String>, Option<String>)>;
+    fn set_alt_text(&self, id: &str, alt_text: Option<&str>) -> SqlResult<()>;
+    fn get_alt_text(&self, id: &str) -> SqlResult<Option<String>>;
 }
 
 /// Capture repository implementation
@@ -30,8 +35,8 @@ impl<'a> CaptureRepository<'a> {
 impl<'a> CaptureOps for CaptureRepository<'a> {
     fn create(&self, capture: &Capture) -> SqlResult<()> {
         self.conn.execute(
-            "INSERT INTO captures (id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            "INSERT INTO captures (id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at, edited_at, media_link, video_duration_ms, video_width, video_height, video_codec, derived_from, frame_timestamp_ms, source_metadata, alt_text)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
             params![
                 capture.id,
                 capture.bug_id,
@@ -44,6 +49,16 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
                 capture.is_console_capture,
                 capture.parsed_content,
                 capture.created_at,
+                capture.edited_at,
+                capture.media_link,
+                capture.video_duration_ms,
+                capture.video_width,
+                capture.video_height,
+                capture.video_codec,
+                capture.derived_from,
+                capture.frame_timestamp_ms,
+                capture.source_metadata,
+                capture.alt_text,
             ],
         )?;
         Ok(())
@@ -51,7 +66,7 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
 
     fn get(&self, id: &str) -> SqlResult<Option<Capture>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at
+            "SELECT id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at, edited_at, media_link, video_duration_ms, video_width, video_height, video_codec, derived_from, frame_timestamp_ms, source_metadata, alt_text
              FROM captures WHERE id = ?1"
         )?;
 
@@ -71,6 +86,16 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
                 is_console_capture: row.get(8)?,
                 parsed_content: row.get(9)?,
                 created_at: row.get(10)?,
+                edited_at: row.get(11)?,
+                media_link: row.get(12)?,
+                video_duration_ms: row.get(13)?,
+                video_width: row.get(14)?,
+                video_height: row.get(15)?,
+                video_codec: row.get(16)?,
+                derived_from: row.get(17)?,
+                frame_timestamp_ms: row.get(18)?,
+                source_metadata: row.get(19)?,
+                alt_text: row.get(20)?,
             }))
         } else {
             Ok(None)
@@ -79,7 +104,7 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
 
     fn update(&self, capture: &Capture) -> SqlResult<()> {
         self.conn.execute(
-            "UPDATE captures SET bug_id = ?2, session_id = ?3, file_name = ?4, file_path = ?5, file_type = ?6, annotated_path = ?7, file_size_bytes = ?8, is_console_capture = ?9, parsed_content = ?10
+            "UPDATE captures SET bug_id = ?2, session_id = ?3, file_name = ?4, file_path = ?5, file_type = ?6, annotated_path = ?7, file_size_bytes = ?8, is_console_capture = ?9, parsed_content = ?10, edited_at = ?11, media_link = ?12, video_duration_ms = ?13, video_width = ?14, video_height = ?15, video_codec = ?16, derived_from = ?17, frame_timestamp_ms = ?18, source_metadata = ?19, alt_text = ?20
              WHERE id = ?1",
             params![
                 capture.id,
@@ -92,19 +117,75 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
                 capture.file_size_bytes,
                 capture.is_console_capture,
                 capture.parsed_content,
+                capture.edited_at,
+                capture.media_link,
+                capture.video_duration_ms,
+                capture.video_width,
+                capture.video_height,
+                capture.video_codec,
+                capture.derived_from,
+                capture.frame_timestamp_ms,
+                capture.source_metadata,
+                capture.alt_text,
             ],
         )?;
         Ok(())
     }
 
     fn delete(&self, id: &str) -> SqlResult<()> {
+        self.conn.execute("DELETE FROM annotations WHERE capture_id = ?1", params![id])?;
+        self.conn.execute("DELETE FROM annotation_ink WHERE capture_id = ?1", params![id])?;
         self.conn.execute("DELETE FROM captures WHERE id = ?1", params![id])?;
         Ok(())
     }
 
+    fn set_created_at(&self, id: &str, created_at: &str) -> SqlResult<()> {
+        self.conn.execute(
+            "UPDATE captures SET created_at = ?2 WHERE id = ?1",
+            params![id, created_at],
+        )?;
+        Ok(())
+    }
+
+    /// Record where the capture (and its annotated version) was uploaded when
+    /// filing a ticket. `None` keeps the previous value.
+    fn set_attachment_urls(&self, id: &str, url: Option<&str>, annotated_url: Option<&str>) -> SqlResult<()> {
+        self.conn.execute(
+            "UPDATE captures SET attachment_url = COALESCE(?2, attachment_url),
+                annotated_attachment_url = COALESCE(?3, annotated_attachment_url)
+             WHERE id = ?1",
+            params![id, url, annotated_url],
+        )?;
+        Ok(())
+    }
+
+    fn get_attachment_urls(&self, id: &str) -> SqlResult<(Option<String>, Option<String>)> {
+        self.conn
+            .query_row(
+                "SELECT attachment_url, annotated_attachment_url FROM captures WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map(Option::unwrap_or_default)
+    }
+
+    /// Set the capture's alt text; `None` clears it.
+    fn set_alt_text(&self, id: &str, alt_text: Option<&str>) -> SqlResult<()> {
+        self.conn.execute("UPDATE captures SET alt_text = ?2 WHERE id = ?1", params![id, alt_text])?;
+        Ok(())
+    }
+
+    fn get_alt_text(&self, id: &str) -> SqlResult<Option<String>> {
+        self.conn
+            .query_row("SELECT alt_text FROM captures WHERE id = ?1", params![id], |row| row.get(0))
+            .optional()
+            .map(Option::flatten)
+    }
+
     fn list_by_bug(&self, bug_id: &str) -> SqlResult<Vec<Capture>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at
+            "SELECT id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at, edited_at, media_link, video_duration_ms, video_width, video_height, video_codec, derived_from, frame_timestamp_ms, source_metadata, alt_text
              FROM captures WHERE bug_id = ?1 ORDER BY created_at ASC"
         )?;
 
@@ -122,6 +203,16 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
                 is_console_capture: row.get(8)?,
                 parsed_content: row.get(9)?,
                 created_at: row.get(10)?,
+                edited_at: row.get(11)?,
+                media_link: row.get(12)?,
+                video_duration_ms: row.get(13)?,
+                video_width: row.get(14)?,
+                video_height: row.get(15)?,
+                video_codec: row.get(16)?,
+                derived_from: row.get(17)?,
+                frame_timestamp_ms: row.get(18)?,
+                source_metadata: row.get(19)?,
+                alt_text: row.get(20)?,
             })
         })?;
 
@@ -130,7 +221,7 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
 
     fn list_by_session(&self, session_id: &str) -> SqlResult<Vec<Capture>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at
+            "SELECT id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at, edited_at, media_link, video_duration_ms, video_width, video_height, video_codec, derived_from, frame_timestamp_ms, source_metadata, alt_text
              FROM captures WHERE session_id = ?1 ORDER BY created_at ASC"
         )?;
 
@@ -148,6 +239,16 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
                 is_console_capture: row.get(8)?,
                 parsed_content: row.get(9)?,
                 created_at: row.get(10)?,
+                edited_at: row.get(11)?,
+                media_link: row.get(12)?,
+                video_duration_ms: row.get(13)?,
+                video_width: row.get(14)?,
+                video_height: row.get(15)?,
+                video_codec: row.get(16)?,
+                derived_from: row.get(17)?,
+                frame_timestamp_ms: row.get(18)?,
+                source_metadata: row.get(19)?,
+                alt_text: row.get(20)?,
             })
         })?;
 
@@ -156,7 +257,7 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
 
     fn list_console_captures(&self, bug_id: &str) -> SqlResult<Vec<Capture>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at
+            "SELECT id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at, edited_at, media_link, video_duration_ms, video_width, video_height, video_codec, derived_from, frame_timestamp_ms, source_metadata, alt_text
              FROM captures WHERE bug_id = ?1 AND is_console_capture = TRUE ORDER BY created_at ASC"
         )?;
 
@@ -174,6 +275,16 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
                 is_console_capture: row.get(8)?,
                 parsed_content: row.get(9)?,
                 created_at: row.get(10)?,
+                edited_at: row.get(11)?,
+                media_link: row.get(12)?,
+                video_duration_ms: row.get(13)?,
+                video_width: row.get(14)?,
+                video_height: row.get(15)?,
+                video_codec: row.get(16)?,
+                derived_from: row.get(17)?,
+                frame_timestamp_ms: row.get(18)?,
+                source_metadata: row.get(19)?,
+                alt_text: row.get(20)?,
             })
         })?;
 
@@ -182,7 +293,7 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
 
     fn list_unsorted(&self, session_id: &str) -> SqlResult<Vec<Capture>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at
+            "SELECT id, bug_id, session_id, file_name, file_path, file_type, annotated_path, file_size_bytes, is_console_capture, parsed_content, created_at, edited_at, media_link, video_duration_ms, video_width, video_height, video_codec, derived_from, frame_timestamp_ms, source_metadata, alt_text
              FROM captures WHERE session_id = ?1 AND bug_id IS NULL ORDER BY created_at ASC"
         )?;
 
@@ -200,6 +311,16 @@ impl<'a> CaptureOps for CaptureRepository<'a> {
                 is_console_capture: row.get(8)?,
                 parsed_content: row.get(9)?,
                 created_at: row.get(10)?,
+                edited_at: row.get(11)?,
+                media_link: row.get(12)?,
+                video_duration_ms: row.get(13)?,
+                video_width: row.get(14)?,
+                video_height: row.get(15)?,
+                video_codec: row.get(16)?,
+                derived_from: row.get(17)?,
+                frame_timestamp_ms: row.get(18)?,
+                source_metadata: row.get(19)?,
+                alt_text: row.get(20)?,
             })
         })?;
 
@@ -225,6 +346,8 @@ mod tests {
             original_snip_path: None,
             created_at: "2024-01-01T10:00:00Z".to_string(),
             profile_id: None,
+            unlocked_at: None,
+            timezone: None,
         };
         let repo = SessionRepository::new(db.connection());
         repo.create(&session).unwrap();
@@ -238,18 +361,11 @@ mod tests {
             display_id: "Bug-01".to_string(),
             bug_type: BugType::Bug,
             title: Some("Test bug".to_string()),
-            notes: None,
-            description: None,
-            ai_description: None,
             status: BugStatus::Captured,
-            meeting_id: None,
-            software_version: None,
-            console_parse_json: None,
-            metadata_json: None,
-            custom_metadata: None,
             folder_path: "/test/bugs/bug-1".to_string(),
             created_at: "2024-01-01T10:00:00Z".to_string(),
             updated_at: "2024-01-01T10:00:00Z".to_string(),
+            ..Default::default()
         };
         let repo = BugRepository::new(db.connection());
         repo.create(&bug).unwrap();
@@ -263,11 +379,10 @@ mod tests {
             file_name: "screenshot.png".to_string(),
             file_path: "captures/screenshot.png".to_string(),
             file_type: CaptureType::Screenshot,
-            annotated_path: None,
             file_size_bytes: Some(1024),
             is_console_capture: is_console,
-            parsed_content: None,
             created_at: "2024-01-01T10:00:00Z".to_string(),
+            ..Default::default()
         }
     }
 
@@ -319,6 +434,62 @@ mod tests {
         assert_eq!(updated.parsed_content, Some("Console error text".to_string()));
     }
 
+    #[test]
+    fn test_set_created_at_reorders_captures() {
+        let db = Database::in_memory().unwrap();
+        create_test_session(&db, "session-ts");
+        create_test_bug(&db, "session-ts", "bug-ts");
+        let repo = CaptureRepository::new(db.connection());
+        repo.create(&create_test_capture("session-ts", "bug-ts", "first", false)).unwrap();
+        let mut second = create_test_capture("session-ts", "bug-ts", "second", false);
+        second.created_at = "2024-01-01T11:00:00Z".to_string();
+        repo.create(&second).unwrap();
+
+        repo.set_created_at("second", "2024-01-01T09:00:00+00:00").unwrap();
+
+        let ids: Vec<String> = repo.list_by_bug("bug-ts").unwrap().into_iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec!["second", "first"]);
+    }
+
+    #[test]
+    fn test_attachment_urls() {
+        let db = Database::in_memory().unwrap();
+        create_test_session(&db, "session-att");
+        create_test_bug(&db, "session-att", "bug-att");
+        let repo = CaptureRepository::new(db.connection());
+        repo.create(&create_test_capture("session-att", "bug-att", "cap-att", false)).unwrap();
+        assert_eq!(repo.get_attachment_urls("cap-att").unwrap(), (None, None));
+
+        repo.set_attachment_urls("cap-att", Some("https://cdn/a.png"), None).unwrap();
+        repo.set_attachment_urls("cap-att", None, Some("https://cdn/a_annotated.png")).unwrap();
+        assert_eq!(
+            repo.get_attachment_urls("cap-att").unwrap(),
+            (Some("https://cdn/a.png".to_string()), Some("https://cdn/a_annotated.png".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_alt_text() {
+        let db = Database::in_memory().unwrap();
+        create_test_session(&db, "session-alt");
+        create_test_bug(&db, "session-alt", "bug-alt");
+        let repo = CaptureRepository::new(db.connection());
+        repo.create(&create_test_capture("session-alt", "bug-alt", "cap-alt", false)).unwrap();
+        repo.create(&create_test_capture("session-alt", "bug-alt", "cap-plain", false)).unwrap();
+        assert_eq!(repo.get_alt_text("cap-alt").unwrap(), None);
+
+        repo.set_alt_text("cap-alt", Some("Login form with a red error banner")).unwrap();
+        assert_eq!(repo.get_alt_text("cap-alt").unwrap().as_deref(), Some("Login form with a red error banner"));
+        let listed = repo.list_by_bug("bug-alt").unwrap();
+        let alt_text = |id: &str| listed.iter().find(|c| c.id == id).unwrap().alt_text.clone();
+        assert_eq!(alt_text("cap-alt").as_deref(), Some("Login form with a red error banner"));
+        assert_eq!(alt_text("cap-plain"), None);
+
+        repo.set_alt_text("cap-alt", None).unwrap();
+        assert_eq!(repo.get("cap-alt").unwrap().unwrap().alt_text, None);
+        assert_eq!(repo.get_alt_text("missing").unwrap(), None);
+    }
+
     #[test]
     fn test_delete_capture() {
         let db = Database::in_memory().unwrap();
@@ -391,16 +562,13 @@ mod tests {
         // Create an unsorted capture (bug_id = None)
         let unsorted = Capture {
             id: "capture-13".to_string(),
-            bug_id: None,
             session_id: "session-8".to_string(),
             file_name: "orphan.png".to_string(),
             file_path: "/test/_unsorted/orphan.png".to_string(),
             file_type: CaptureType::Screenshot,
-            annotated_path: None,
             file_size_bytes: Some(512),
-            is_console_capture: false,
-            parsed_content: None,
             created_at: "2024-01-01T10:00:00Z".to_string(),
+            ..Default::default()
         };
         repo.create(&unsorted).unwrap();
 
@@ -0,0 +1,258 @@
+use rusqlite::{Connection, Result as SqlResult};
+use serde::Serialize;
+
+/// ON DELETE behaviour of each foreign key: (table, column, parent table, action).
+///
+/// Children of a session go with it. A capture whose bug is deleted falls back
+/// to the session's unsorted captures instead of disappearing. Ordered so that
+/// cleaning orphans in sequence also catches rows orphaned by an earlier step.
+pub const FOREIGN_KEY_RULES: &[(&str, &str, &str, &str)] = &[
+    ("bugs", "session_id", "sessions", "CASCADE"),
+    ("captures", "session_id", "sessions", "CASCADE"),
+    ("captures", "bug_id", "bugs", "SET NULL"),
+    ("bug_number_reservations", "session_id", "sessions", "CASCADE"),
+    ("annotations", "capture_id", "captures", "CASCADE"),
+    ("annotation_ink", "capture_id", "captures", "CASCADE"),
+    ("bug_links", "bug_id", "bugs", "CASCADE"),
+    ("bug_links", "related_bug_id", "bugs", "CASCADE"),
+];
+
+/// A row whose foreign key points at a row that does not exist.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Orphan {
+    pub table: String,
+    /// `id` of the orphaned row
+    pub id: String,
+    pub parent_table: String,
+}
+
+/// Rows violating a foreign key, as reported by `PRAGMA foreign_key_check`.
+/// Empty unless data was written with enforcement off (e.g. by an older
+/// version or an external tool).
+pub fn find_orphans(conn: &Connection) -> SqlResult<Vec<Orphan>> {
+    let violations: Vec<(String, i64, String)> = conn
+        .prepare("PRAGMA foreign_key_check")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<SqlResult<_>>()?;
+
+    violations
+        .into_iter()
+        .map(|(table, rowid, parent_table)| {
+            let id = conn.query_row(
+                &format!("SELECT CAST(id AS TEXT) FROM \"{}\" WHERE rowid = ?1", table),
+                [rowid],
+                |row| row.get(0),
+            )?;
+            Ok(Orphan { table, id, parent_table })
+        })
+        .collect()
+}
+
+/// The rules whose table exists; tables added after a database was created
+/// are missing until the schema is initialized.
+fn existing_rules(conn: &Connection) -> SqlResult<Vec<(&'static str, &'static str, &'static str, &'static str)>> {
+    let mut rules = Vec::new();
+    for &rule in FOREIGN_KEY_RULES {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+            [rule.0],
+            |row| row.get(0),
+        )?;
+        if exists {
+            rules.push(rule);
+        }
+    }
+    Ok(rules)
+}
+
+/// Tables whose foreign keys were created without the ON DELETE action in
+/// [`FOREIGN_KEY_RULES`].
+fn tables_needing_rebuild(conn: &Connection) -> SqlResult<Vec<&'static str>> {
+    let mut tables = Vec::new();
+    for (table, column, _, action) in existing_rules(conn)? {
+        let on_delete: Option<String> = conn
+            .query_row(
+                "SELECT on_delete FROM pragma_foreign_key_list(?1) WHERE \"from\" = ?2",
+                [table, column],
+                |row| row.get(0),
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(e),
+            })?;
+        if on_delete.as_deref() != Some(action) && !tables.contains(&table) {
+            tables.push(table);
+        }
+    }
+    Ok(tables)
+}
+
+/// Migration: bring databases created before ON DELETE rules were defined in
+/// line with [`FOREIGN_KEY_RULES`].
+///
+/// Existing orphans are cleaned up the way the rule would have handled them,
+/// then each affected table is rebuilt from its stored definition with the
+/// actions added (SQLite cannot alter a constraint in place). Must run before
+/// indices are created, since dropping a table drops its indices.
+pub fn migrate_foreign_keys(conn: &Connection) -> SqlResult<()> {
+    let tables = tables_needing_rebuild(conn)?;
+    if tables.is_empty() {
+        return Ok(());
+    }
+
+    // Enforcement cannot be toggled inside a transaction, and must be off
+    // while a parent table is dropped and recreated
+    let enforced: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+    conn.pragma_update(None, "foreign_keys", false)?;
+    conn.execute_batch("BEGIN IMMEDIATE")?;
+    let result = rebuild_tables(conn, &tables);
+    let result = match result {
+        Ok(()) => conn.execute_batch("COMMIT"),
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            Err(e)
+        }
+    };
+    conn.pragma_update(None, "foreign_keys", enforced)?;
+    result
+}
+
+fn rebuild_tables(conn: &Connection, tables: &[&str]) -> SqlResult<()> {
+    let mut cleaned = 0;
+    for (table, column, parent, action) in existing_rules(conn)? {
+        let orphaned = format!("{0} IS NOT NULL AND {0} NOT IN (SELECT id FROM {1})", column, parent);
+        cleaned += if action == "SET NULL" {
+            conn.execute(&format!("UPDATE {} SET {} = NULL WHERE {}", table, column, orphaned), [])?
+        } else {
+            conn.execute(&format!("DELETE FROM {} WHERE {}", table, orphaned), [])?
+        };
+    }
+    if cleaned > 0 {
+        eprintln!("Database migration: cleaned up {} orphaned row(s)", cleaned);
+    }
+
+    for &table in tables {
+        let sql: String = conn.query_row(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [table],
+            |row| row.get(0),
+        )?;
+        let columns = sql.find('(').map(|i| &sql[i..]).unwrap_or_default();
+        let mut definition = format!("CREATE TABLE {}_new {}", table, columns);
+        for &(_, _, parent, action) in FOREIGN_KEY_RULES.iter().filter(|r| r.0 == table) {
+            let reference = format!("REFERENCES {}(id)", parent);
+            definition = definition.replace(&reference, &format!("{} ON DELETE {}", reference, action));
+        }
+
+        conn.execute_batch(&format!(
+            "{definition};
+             INSERT INTO {table}_new SELECT * FROM {table};
+             DROP TABLE {table};
+             ALTER TABLE {table}_new RENAME TO {table};"
+        ))?;
+    }
+
+    if !find_orphans(conn)?.is_empty() {
+        return Err(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT_FOREIGNKEY),
+            Some("foreign key violations remain after migration".to_string()),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{configure_connection, init_database};
+
+    /// A database as created before ON DELETE rules existed, with orphaned
+    /// rows written while enforcement was off.
+    fn legacy_database() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn.pragma_update(None, "foreign_keys", false).unwrap();
+        for table in ["bugs", "captures"] {
+            let sql: String = conn
+                .query_row("SELECT sql FROM sqlite_master WHERE name = ?1", [table], |row| row.get(0))
+                .unwrap();
+            let legacy = sql
+                .replace(" ON DELETE CASCADE", "")
+                .replace(" ON DELETE SET NULL", "")
+                .replacen(&format!("CREATE TABLE {}", table), &format!("CREATE TABLE {}_old", table), 1);
+            conn.execute_batch(&format!(
+                "{legacy}; DROP TABLE {table}; ALTER TABLE {table}_old RENAME TO {table};"
+            ))
+            .unwrap();
+        }
+        // Not yet migrated
+        conn.execute("DELETE FROM schema_migrations WHERE name = 'foreign_key_actions'", []).unwrap();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/qa/s-1');
+             INSERT INTO bugs (id, session_id, bug_number, display_id, folder_path) VALUES
+               ('b-1', 's-1', 1, 'BUG-001', '/qa/s-1/bug_001'),
+               ('b-gone', 's-gone', 1, 'BUG-001', '/qa/s-gone/bug_001');
+             INSERT INTO captures (id, bug_id, session_id, file_name, file_path, file_type) VALUES
+               ('c-1', 'b-1', 's-1', 'a.png', '/qa/s-1/bug_001/a.png', 'screenshot'),
+               ('c-2', 'b-deleted', 's-1', 'b.png', '/qa/s-1/bug_002/b.png', 'screenshot');",
+        )
+        .unwrap();
+        assert_eq!(tables_needing_rebuild(&conn).unwrap(), vec!["bugs", "captures"]);
+        conn
+    }
+
+    #[test]
+    fn test_find_orphans() {
+        let conn = legacy_database();
+        let mut orphans = find_orphans(&conn).unwrap();
+        orphans.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(
+            orphans,
+            vec![
+                Orphan { table: "bugs".to_string(), id: "b-gone".to_string(), parent_table: "sessions".to_string() },
+                Orphan { table: "captures".to_string(), id: "c-2".to_string(), parent_table: "bugs".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migration_cleans_orphans_and_adds_delete_rules() {
+        let conn = legacy_database();
+        configure_connection(&conn).unwrap();
+        init_database(&conn).unwrap();
+
+        assert!(find_orphans(&conn).unwrap().is_empty());
+        assert!(tables_needing_rebuild(&conn).unwrap().is_empty());
+        let bug_ids: Vec<String> = conn
+            .prepare("SELECT id FROM bugs")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<SqlResult<_>>()
+            .unwrap();
+        assert_eq!(bug_ids, vec!["b-1"]);
+        let c2_bug: Option<String> =
+            conn.query_row("SELECT bug_id FROM captures WHERE id = 'c-2'", [], |row| row.get(0)).unwrap();
+        assert_eq!(c2_bug, None, "capture of a deleted bug becomes unsorted");
+        // Columns added by later migrations survive the rebuild
+        conn.execute("UPDATE bugs SET custom_metadata = '{}' WHERE id = 'b-1'", []).unwrap();
+
+        // Deleting a bug unsorts its captures; deleting the session removes everything
+        conn.execute("DELETE FROM bugs WHERE id = 'b-1'", []).unwrap();
+        let c1_bug: Option<String> =
+            conn.query_row("SELECT bug_id FROM captures WHERE id = 'c-1'", [], |row| row.get(0)).unwrap();
+        assert_eq!(c1_bug, None);
+        conn.execute("DELETE FROM sessions WHERE id = 's-1'", []).unwrap();
+        let captures: i64 = conn.query_row("SELECT COUNT(*) FROM captures", [], |row| row.get(0)).unwrap();
+        assert_eq!(captures, 0);
+    }
+
+    #[test]
+    fn test_fresh_database_needs_no_rebuild() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        assert!(tables_needing_rebuild(&conn).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,380 @@
+//! Versioned schema migrations.
+//!
+//! `init_database` creates any missing table with its current definition, then
+//! [`run_migrations`] applies each entry of [`MIGRATIONS`] not yet recorded in
+//! `schema_migrations`, in version order, so fresh databases and existing
+//! installs end up with the same schema. Schema changes are made by appending
+//! a migration; released migrations are never edited.
+//!
+//! Databases created before this table existed have no versions recorded.
+//! Every migration is written to be a no-op when its change is already there,
+//! so such installs just record the versions they already have.
+
+use rusqlite::{Connection, Result as SqlResult};
+
+/// One schema change.
+pub struct Migration {
+    /// Position in the upgrade order; never reused or renumbered
+    pub version: i64,
+    pub name: &'static str,
+    /// Run inside a transaction together with recording the version. Off for
+    /// migrations that manage their own transaction (e.g. ones that toggle
+    /// `PRAGMA foreign_keys`, which has no effect inside a transaction).
+    pub transactional: bool,
+    pub apply: fn(&Connection) -> SqlResult<()>,
+}
+
+/// All migrations, oldest first.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "bugs_custom_metadata", transactional: true, apply: bugs_custom_metadata },
+    Migration { version: 2, name: "sessions_profile_id", transactional: true, apply: sessions_profile_id },
+    Migration { version: 3, name: "sessions_unlocked_at", transactional: true, apply: sessions_unlocked_at },
+    Migration { version: 4, name: "sessions_timezone", transactional: true, apply: sessions_timezone },
+    Migration { version: 5, name: "captures_edited_at", transactional: true, apply: captures_edited_at },
+    Migration { version: 6, name: "captures_media_link", transactional: true, apply: captures_media_link },
+    Migration { version: 7, name: "captures_video_metadata", transactional: true, apply: captures_video_metadata },
+    Migration { version: 8, name: "captures_derived_frames", transactional: true, apply: captures_derived_frames },
+    Migration { version: 9, name: "captures_source_metadata", transactional: true, apply: captures_source_metadata },
+    Migration { version: 10, name: "captures_attachment_urls", transactional: true, apply: captures_attachment_urls },
+    Migration { version: 11, name: "bugs_external_ticket", transactional: true, apply: bugs_external_ticket },
+    Migration { version: 12, name: "bugs_severity_suggestion", transactional: true, apply: bugs_severity_suggestion },
+    Migration { version: 13, name: "audit_log_actor", transactional: true, apply: audit_log_actor },
+    Migration { version: 14, name: "foreign_key_actions", transactional: false, apply: foreign_key_actions },
+    Migration { version: 15, name: "captures_alt_text", transactional: true, apply: captures_alt_text },
+];
+
+/// Version of the newest migration.
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Versions recorded in `schema_migrations`, ascending.
+pub fn applied_versions(conn: &Connection) -> SqlResult<Vec<i64>> {
+    conn.prepare("SELECT version FROM schema_migrations ORDER BY version")?
+        .query_map([], |row| row.get(0))?
+        .collect()
+}
+
+/// Apply every migration not yet recorded. Tables the migrations alter must
+/// exist already.
+pub fn run_migrations(conn: &Connection) -> SqlResult<()> {
+    apply_migrations(conn, MIGRATIONS, latest_version())
+}
+
+fn apply_migrations(conn: &Connection, migrations: &[Migration], target: i64) -> SqlResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+
+    let applied = applied_versions(conn)?;
+    if let Some(&newest) = applied.last().filter(|&&v| v > latest_version()) {
+        // Written by a newer version of the app; its changes only add to the schema
+        eprintln!("Database schema version {} is newer than this build ({})", newest, latest_version());
+    }
+
+    for migration in migrations.iter().filter(|m| m.version <= target && !applied.contains(&m.version)) {
+        let record = |conn: &Connection| {
+            conn.execute(
+                "INSERT INTO schema_migrations (version, name) VALUES (?1, ?2)",
+                rusqlite::params![migration.version, migration.name],
+            )
+            .map(|_| ())
+        };
+        if migration.transactional {
+            let tx = conn.unchecked_transaction()?;
+            (migration.apply)(&tx)?;
+            record(&tx)?;
+            tx.commit()?;
+        } else {
+            (migration.apply)(conn)?;
+            record(conn)?;
+        }
+    }
+    Ok(())
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> SqlResult<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+        [table, column],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|c| c > 0)
+}
+
+/// `ALTER TABLE table ADD COLUMN column definition`, unless the column exists.
+/// Returns whether it was added.
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> SqlResult<bool> {
+    if has_column(conn, table, column)? {
+        return Ok(false);
+    }
+    conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    Ok(true)
+}
+
+/// Profile-driven custom field values as a JSON blob. The legacy meeting_id
+/// and software_version columns are kept, and their values copied in.
+fn bugs_custom_metadata(conn: &Connection) -> SqlResult<()> {
+    if add_column(conn, "bugs", "custom_metadata", "TEXT")? {
+        conn.execute(
+            "UPDATE bugs SET custom_metadata = json_object('meeting_id', meeting_id, 'software_version', software_version)
+             WHERE meeting_id IS NOT NULL OR software_version IS NOT NULL",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// The QA profile that was active when a session was started.
+fn sessions_profile_id(conn: &Connection) -> SqlResult<()> {
+    add_column(conn, "sessions", "profile_id", "TEXT").map(|_| ())
+}
+
+/// When a reviewed/synced session was explicitly unlocked for editing.
+fn sessions_unlocked_at(conn: &Connection) -> SqlResult<()> {
+    add_column(conn, "sessions", "unlocked_at", "TEXT").map(|_| ())
+}
+
+/// The tester's IANA time zone, so summaries can show local times.
+fn sessions_timezone(conn: &Connection) -> SqlResult<()> {
+    add_column(conn, "sessions", "timezone", "TEXT").map(|_| ())
+}
+
+/// When a capture file was changed after capture, e.g. in an external editor.
+fn captures_edited_at(conn: &Connection) -> SqlResult<()> {
+    add_column(conn, "captures", "edited_at", "TEXT").map(|_| ())
+}
+
+/// Relative link for captures offloaded to the media root.
+fn captures_media_link(conn: &Connection) -> SqlResult<()> {
+    add_column(conn, "captures", "media_link", "TEXT").map(|_| ())
+}
+
+/// Duration, resolution and codec extracted when a recording is ingested.
+fn captures_video_metadata(conn: &Connection) -> SqlResult<()> {
+    for (column, definition) in [
+        ("video_duration_ms", "INTEGER"),
+        ("video_width", "INTEGER"),
+        ("video_height", "INTEGER"),
+        ("video_codec", "TEXT"),
+    ] {
+        add_column(conn, "captures", column, definition)?;
+    }
+    Ok(())
+}
+
+/// Links frames extracted from a recording back to their source capture.
+fn captures_derived_frames(conn: &Connection) -> SqlResult<()> {
+    add_column(conn, "captures", "derived_from", "TEXT")?;
+    add_column(conn, "captures", "frame_timestamp_ms", "INTEGER").map(|_| ())
+}
+
+/// How a capture was ingested and routed, e.g. into a just-ended bug.
+fn captures_source_metadata(conn: &Connection) -> SqlResult<()> {
+    add_column(conn, "captures", "source_metadata", "TEXT").map(|_| ())
+}
+
+/// URLs the capture and its annotated version were uploaded to when filing a ticket.
+fn captures_attachment_urls(conn: &Connection) -> SqlResult<()> {
+    add_column(conn, "captures", "attachment_url", "TEXT")?;
+    add_column(conn, "captures", "annotated_attachment_url", "TEXT").map(|_| ())
+}
+
+/// The ticket filed for a bug and its status as of the last sync.
+fn bugs_external_ticket(conn: &Connection) -> SqlResult<()> {
+    for column in [
+        "external_ticket_id",
+        "external_ticket_key",
+        "external_ticket_url",
+        "external_status",
+        "external_status_category",
+    ] {
+        add_column(conn, "bugs", column, "TEXT")?;
+    }
+    Ok(())
+}
+
+/// The AI-suggested severity as JSON; it is never applied automatically.
+fn bugs_severity_suggestion(conn: &Connection) -> SqlResult<()> {
+    add_column(conn, "bugs", "severity_suggestion", "TEXT").map(|_| ())
+}
+
+fn audit_log_actor(conn: &Connection) -> SqlResult<()> {
+    add_column(conn, "audit_log", "actor", "TEXT NOT NULL DEFAULT ''").map(|_| ())
+}
+
+/// ON DELETE rules for databases created without them (see
+/// `integrity::migrate_foreign_keys`).
+fn foreign_key_actions(conn: &Connection) -> SqlResult<()> {
+    super::integrity::migrate_foreign_keys(conn)
+}
+
+/// Text alternative for screen readers, shown with the capture in exports and tickets.
+fn captures_alt_text(conn: &Connection) -> SqlResult<()> {
+    add_column(conn, "captures", "alt_text", "TEXT").map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{configure_connection, init_database};
+
+    /// The schema of the first release, before any migration.
+    const SCHEMA_V0: &str = "
+        CREATE TABLE sessions (
+            id TEXT PRIMARY KEY,
+            started_at TEXT NOT NULL,
+            ended_at TEXT,
+            status TEXT NOT NULL DEFAULT 'active',
+            folder_path TEXT NOT NULL,
+            session_notes TEXT,
+            environment_json TEXT,
+            original_snip_path TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE TABLE bugs (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL REFERENCES sessions(id),
+            bug_number INTEGER NOT NULL,
+            display_id TEXT NOT NULL,
+            type TEXT DEFAULT 'bug',
+            title TEXT,
+            notes TEXT,
+            description TEXT,
+            ai_description TEXT,
+            status TEXT NOT NULL DEFAULT 'captured',
+            meeting_id TEXT,
+            software_version TEXT,
+            console_parse_json TEXT,
+            metadata_json TEXT,
+            folder_path TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE TABLE captures (
+            id TEXT PRIMARY KEY,
+            bug_id TEXT REFERENCES bugs(id),
+            session_id TEXT NOT NULL REFERENCES sessions(id),
+            file_name TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            file_type TEXT NOT NULL,
+            annotated_path TEXT,
+            file_size_bytes INTEGER,
+            is_console_capture BOOLEAN DEFAULT FALSE,
+            parsed_content TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE TABLE settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE TABLE audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action TEXT NOT NULL,
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            details TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );";
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut columns: Vec<String> = conn
+            .prepare("SELECT name FROM pragma_table_info(?1)")
+            .unwrap()
+            .query_map([table], |row| row.get(0))
+            .unwrap()
+            .collect::<SqlResult<_>>()
+            .unwrap();
+        columns.sort();
+        columns
+    }
+
+    #[test]
+    fn test_versions_strictly_increase() {
+        assert!(MIGRATIONS.windows(2).all(|pair| pair[0].version < pair[1].version));
+        assert_eq!(MIGRATIONS[0].version, 1);
+    }
+
+    #[test]
+    fn test_fresh_database_records_every_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        init_database(&conn).unwrap();
+        let all: Vec<i64> = MIGRATIONS.iter().map(|m| m.version).collect();
+        assert_eq!(applied_versions(&conn).unwrap(), all);
+    }
+
+    #[test]
+    fn test_upgrade_from_each_historical_version() {
+        let fresh = Connection::open_in_memory().unwrap();
+        init_database(&fresh).unwrap();
+
+        // A database at the latest version is a fresh one
+        for version in 0..latest_version() {
+            let conn = Connection::open_in_memory().unwrap();
+            conn.execute_batch(SCHEMA_V0).unwrap();
+            apply_migrations(&conn, MIGRATIONS, version).unwrap();
+            conn.execute_batch(
+                "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/qa/s-1');
+                 INSERT INTO bugs (id, session_id, bug_number, display_id, folder_path, meeting_id)
+                   VALUES ('b-1', 's-1', 1, 'BUG-001', '/qa/s-1/bug_001', 'standup');
+                 INSERT INTO captures (id, bug_id, session_id, file_name, file_path, file_type)
+                   VALUES ('c-1', 'b-1', 's-1', 'a.png', '/qa/s-1/bug_001/a.png', 'screenshot');",
+            )
+            .unwrap();
+
+            configure_connection(&conn).unwrap();
+            init_database(&conn).unwrap();
+
+            assert_eq!(applied_versions(&conn).unwrap().last(), Some(&latest_version()), "from v{}", version);
+            for table in ["sessions", "bugs", "captures", "settings", "audit_log"] {
+                assert_eq!(columns(&conn, table), columns(&fresh, table), "{} from v{}", table, version);
+            }
+            if version == 0 {
+                let custom: String = conn
+                    .query_row("SELECT json_extract(custom_metadata, '$.meeting_id') FROM bugs", [], |row| row.get(0))
+                    .unwrap();
+                assert_eq!(custom, "standup");
+            }
+
+            // Data survives, and the ON DELETE rules are in place
+            conn.execute("DELETE FROM bugs WHERE id = 'b-1'", []).unwrap();
+            let bug_id: Option<String> =
+                conn.query_row("SELECT bug_id FROM captures WHERE id = 'c-1'", [], |row| row.get(0)).unwrap();
+            assert_eq!(bug_id, None, "from v{}", version);
+        }
+    }
+
+    #[test]
+    fn test_unversioned_install_records_versions() {
+        // Current tables, but created before schema_migrations existed
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn.execute_batch("DROP TABLE schema_migrations").unwrap();
+
+        init_database(&conn).unwrap();
+        assert_eq!(applied_versions(&conn).unwrap().len(), MIGRATIONS.len());
+    }
+
+    fn failing(conn: &Connection) -> SqlResult<()> {
+        add_column(conn, "settings", "scratch", "TEXT")?;
+        conn.execute("INSERT INTO missing_table VALUES (1)", []).map(|_| ())
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let broken = [Migration { version: 100, name: "broken", transactional: true, apply: failing }];
+
+        assert!(apply_migrations(&conn, &broken, 100).is_err());
+        assert!(!has_column(&conn, "settings", "scratch").unwrap());
+        assert!(!applied_versions(&conn).unwrap().contains(&100));
+    }
+}
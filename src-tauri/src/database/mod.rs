@@ -4,6 +4,13 @@ mod session;
 mod bug;
 mod capture;
 mod settings;
+mod audit;
+mod annotation;
+mod bug_link;
+mod ticket_queue;
+mod unit_of_work;
+mod integrity;
+mod migrations;
 pub mod state;
 
 // Public exports for external module use
@@ -20,10 +27,41 @@ pub use capture::{CaptureOps, CaptureRepository};
 #[allow(unused_imports)]
 pub use settings::{SettingsOps, SettingsRepository};
 #[allow(unused_imports)]
+pub use audit::{AuditOps, AuditRepository, record_audit, tester_identity, TESTER_NAME_KEY};
+#[allow(unused_imports)]
+pub use annotation::{AnnotationOps, AnnotationRepository};
+#[allow(unused_imports)]
+pub use bug_link::{BugLinkOps, BugLinkRepository};
+#[allow(unused_imports)]
+pub use ticket_queue::{TicketQueueOps, TicketQueueRepository};
+#[allow(unused_imports)]
+pub use unit_of_work::UnitOfWork;
+#[allow(unused_imports)]
+pub use integrity::{find_orphans, Orphan};
+#[allow(unused_imports)]
+pub use migrations::{applied_versions, latest_version};
+#[allow(unused_imports)]
 pub use state::DbState;
 
 use rusqlite::{Connection, Result as SqlResult};
 use std::path::Path;
+use std::time::Duration;
+
+/// How long a statement waits for another connection's lock before failing
+/// with "database is locked".
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Apply the settings every connection to the app database needs: WAL so the
+/// capture watcher's writes don't block UI reads, a busy timeout instead of
+/// failing immediately on contention, and foreign key enforcement (off by
+/// default in SQLite).
+pub fn configure_connection(conn: &Connection) -> SqlResult<()> {
+    // In-memory databases report "memory" and keep their journal mode
+    conn.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.pragma_update(None, "foreign_keys", true)?;
+    Ok(())
+}
 
 /// Database connection manager
 pub struct Database {
@@ -32,28 +70,36 @@ pub struct Database {
 }
 
 impl Database {
-    /// Create a new database connection
+    /// Create a new database connection (alias for open)
     #[allow(dead_code)]
     pub fn new<P: AsRef<Path>>(path: P) -> SqlResult<Self> {
-        let conn = Connection::open(path)?;
-        schema::init_database(&conn)?;
-        Ok(Database { conn })
+        Self::open(path)
     }
 
-    /// Open an existing database connection (alias for new)
+    /// Open (or create) the database at `path`, configure the connection
+    /// (see [`configure_connection`]) and initialize the schema.
     #[allow(dead_code)]
     pub fn open<P: AsRef<Path>>(path: P) -> SqlResult<Self> {
-        Self::new(path)
+        let conn = Connection::open(path)?;
+        configure_connection(&conn)?;
+        schema::init_database(&conn)?;
+        Ok(Database { conn })
     }
 
     /// Create an in-memory database (for testing)
     #[allow(dead_code)]
     pub fn in_memory() -> SqlResult<Self> {
         let conn = Connection::open_in_memory()?;
+        configure_connection(&conn)?;
         schema::init_database(&conn)?;
         Ok(Database { conn })
     }
 
+    /// Take the configured connection, e.g. to share it behind a mutex
+    pub fn into_connection(self) -> Connection {
+        self.conn
+    }
+
     /// Get a reference to the underlying connection
     #[allow(dead_code)]
     pub fn connection(&self) -> &Connection {
@@ -90,4 +136,43 @@ mod tests {
         assert!(tables.contains(&"captures".to_string()));
         assert!(tables.contains(&"settings".to_string()));
     }
+
+    #[test]
+    fn test_open_configures_connection() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::open(dir.path().join("qa.db")).unwrap();
+        let conn = db.connection();
+
+        let mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+        let timeout: i64 = conn.query_row("PRAGMA busy_timeout", [], |row| row.get(0)).unwrap();
+        assert_eq!(timeout, BUSY_TIMEOUT.as_millis() as i64);
+        let foreign_keys: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+        assert!(foreign_keys);
+    }
+
+    #[test]
+    fn test_foreign_keys_enforced() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+
+        let orphan_bug = conn.execute(
+            "INSERT INTO bugs (id, session_id, bug_number, display_id, folder_path)
+             VALUES ('b-1', 's-missing', 1, 'BUG-001', '/qa/bug_001')",
+            [],
+        );
+        assert!(orphan_bug.is_err(), "bug with a missing session must be rejected");
+
+        conn.execute(
+            "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/qa/s-1')",
+            [],
+        )
+        .unwrap();
+        let orphan_capture = conn.execute(
+            "INSERT INTO captures (id, session_id, bug_id, file_name, file_path, file_type)
+             VALUES ('c-1', 's-1', 'b-missing', 'a.png', '/qa/s-1/a.png', 'screenshot')",
+            [],
+        );
+        assert!(orphan_capture.is_err(), "capture with a missing bug must be rejected");
+    }
 }
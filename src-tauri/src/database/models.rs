@@ -16,6 +16,25 @@ pub struct Session {
     /// The QA profile active when this session was started. None if no profile
     /// was active (e.g. sessions created before profiles were introduced).
     pub profile_id: Option<String>,
+    /// Set when a reviewed/synced session has been explicitly unlocked for
+    /// editing via `unlock_session`. Cleared again when the session is moved
+    /// back into a locked status.
+    #[serde(default)]
+    pub unlocked_at: Option<String>,
+    /// IANA time zone of the tester when the session started (e.g.
+    /// `Europe/Berlin`). Timestamps stay UTC in the DB; this only drives how
+    /// they are displayed. None for sessions recorded before it was tracked.
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+impl Session {
+    /// A session is locked once it has been reviewed or synced, unless it has
+    /// been explicitly unlocked since. Locked sessions reject edits to notes,
+    /// captures and descriptions so exported evidence is not changed by accident.
+    pub fn is_locked(&self) -> bool {
+        self.status.is_locked_status() && self.unlocked_at.is_none()
+    }
 }
 
 /// Session status enum
@@ -50,10 +69,15 @@ impl SessionStatus {
             _ => Err(format!("Invalid session status: {}", s)),
         }
     }
+
+    /// Statuses whose sessions are locked against edits by default.
+    pub fn is_locked_status(&self) -> bool {
+        matches!(self, SessionStatus::Reviewed | SessionStatus::Synced)
+    }
 }
 
 /// Bug card represents an individual bug/issue
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Bug {
     pub id: String,
     pub session_id: String,
@@ -77,15 +101,51 @@ pub struct Bug {
     pub folder_path: String,
     pub created_at: String,
     pub updated_at: String,
+    /// Provider ID of the ticket filed for this bug, once one exists.
+    #[serde(default)]
+    pub external_ticket_id: Option<String>,
+    /// Human-readable ticket key (e.g. "PROJ-123").
+    #[serde(default)]
+    pub external_ticket_key: Option<String>,
+    #[serde(default)]
+    pub external_ticket_url: Option<String>,
+    /// Ticket status in the provider (e.g. "In Review"), as of the last status sync.
+    #[serde(default)]
+    pub external_status: Option<String>,
+    /// Provider-independent group of `external_status`; see `ticketing::TicketStatusCategory`.
+    #[serde(default)]
+    pub external_status_category: Option<String>,
+}
+
+impl Bug {
+    /// Scratch bugs hold evidence grabbed before it is classified and take
+    /// no bug number until promoted (see `SessionManager::start_scratch_capture`).
+    pub fn is_scratch(&self) -> bool {
+        self.bug_number == 0
+    }
+
+    /// Set `key` in `metadata_json` to `value`, keeping the other keys. A
+    /// missing or unreadable object starts empty.
+    pub fn set_metadata(&mut self, key: &str, value: serde_json::Value) {
+        let mut metadata = self
+            .metadata_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(json).ok())
+            .unwrap_or_default();
+        metadata.insert(key.to_string(), value);
+        self.metadata_json = Some(serde_json::Value::Object(metadata).to_string());
+    }
 }
 
 /// Bug type enum
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum BugType {
+    #[default]
     Bug,
     Feature,
     Feedback,
+    Question,
 }
 
 impl BugType {
@@ -95,6 +155,7 @@ impl BugType {
             BugType::Bug => "bug",
             BugType::Feature => "feature",
             BugType::Feedback => "feedback",
+            BugType::Question => "question",
         }
     }
 
@@ -105,15 +166,17 @@ impl BugType {
             "bug" => Ok(BugType::Bug),
             "feature" => Ok(BugType::Feature),
             "feedback" => Ok(BugType::Feedback),
+            "question" => Ok(BugType::Question),
             _ => Err(format!("Invalid bug type: {}", s)),
         }
     }
 }
 
 /// Bug status enum
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum BugStatus {
+    #[default]
     Capturing,
     Captured,
     Reviewed,
@@ -146,7 +209,7 @@ impl BugStatus {
 
 /// Capture represents a media file (screenshot, video, console output)
 #[allow(dead_code)]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Capture {
     pub id: String,
     /// None when capture is unsorted (no active bug at capture time)
@@ -160,13 +223,42 @@ pub struct Capture {
     pub is_console_capture: bool,
     pub parsed_content: Option<String>,
     pub created_at: String,
+    /// Set when the file was modified after capture (e.g. in an external editor)
+    #[serde(default)]
+    pub edited_at: Option<String>,
+    /// Path relative to the media root when the file is stored outside the
+    /// session folder (large video offloading); `file_path` is the resolved path
+    #[serde(default)]
+    pub media_link: Option<String>,
+    /// Video metadata extracted on ingest (None for screenshots or when probing failed)
+    #[serde(default)]
+    pub video_duration_ms: Option<i64>,
+    #[serde(default)]
+    pub video_width: Option<i64>,
+    #[serde(default)]
+    pub video_height: Option<i64>,
+    #[serde(default)]
+    pub video_codec: Option<String>,
+    /// ID of the capture this one was derived from (e.g. a frame extracted from a recording)
+    #[serde(default)]
+    pub derived_from: Option<String>,
+    /// Position in the source recording for extracted frames
+    #[serde(default)]
+    pub frame_timestamp_ms: Option<i64>,
+    /// JSON describing how the capture was ingested and routed (see `CaptureSource`)
+    #[serde(default)]
+    pub source_metadata: Option<String>,
+    /// Short description of the image for screen readers and ticket attachments
+    #[serde(default)]
+    pub alt_text: Option<String>,
 }
 
 /// Capture type enum
 #[allow(dead_code)]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum CaptureType {
+    #[default]
     Screenshot,
     Video,
     Console,
@@ -234,6 +326,293 @@ pub struct SessionSummary {
     pub ended_at: Option<String>,
     pub status: SessionStatus,
     pub bug_count: i32,
+    #[serde(default)]
+    pub status_counts: BugStatusCounts,
+    /// Bugs that have been through review (status `reviewed` or `ready`)
+    #[serde(default)]
+    pub reviewed_count: i32,
+    /// Bugs with a ticket filed in the ticketing integration
+    #[serde(default)]
+    pub ticketed_count: i32,
+}
+
+/// Number of a session's bugs in each [`BugStatus`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BugStatusCounts {
+    pub capturing: i32,
+    pub captured: i32,
+    pub reviewed: i32,
+    pub ready: i32,
+}
+
+/// Audit log entry recording a sensitive operation (deletes, merges, renumbering,
+/// unlocks, settings and credential changes)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    pub id: i64,
+    /// Tester identity at the time of the operation
+    pub actor: String,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    /// Free-form JSON with operation-specific context
+    pub details: Option<String>,
+    pub created_at: String,
+}
+
+/// Filter for querying the audit log. All fields are optional; unset fields match everything.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct AuditFilter {
+    pub action: Option<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub actor: Option<String>,
+    /// Inclusive lower bound on `created_at` (RFC 3339)
+    pub since: Option<String>,
+    /// Exclusive upper bound on `created_at` (RFC 3339)
+    pub until: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// Design-QA measurement recorded on a capture: a colour picked from the
+/// image or a distance measured between two points (image pixel coordinates)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum AnnotationData {
+    Color { x: u32, y: u32, hex: String },
+    Distance { from: [u32; 2], to: [u32; 2] },
+}
+
+impl AnnotationData {
+    #[allow(dead_code)]
+    pub fn kind(&self) -> &str {
+        match self {
+            AnnotationData::Color { .. } => "color",
+            AnnotationData::Distance { .. } => "distance",
+        }
+    }
+
+    /// Check the values and normalise picked colours to `#RRGGBB`.
+    pub fn normalized(self) -> Result<Self, String> {
+        match self {
+            AnnotationData::Color { x, y, hex } => {
+                let digits = hex.trim().trim_start_matches('#');
+                let digits = match digits.len() {
+                    3 => digits.chars().flat_map(|c| [c, c]).collect(),
+                    6 => digits.to_string(),
+                    _ => return Err(format!("Invalid colour: {}", hex)),
+                };
+                if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(format!("Invalid colour: {}", hex));
+                }
+                Ok(AnnotationData::Color { x, y, hex: format!("#{}", digits.to_ascii_uppercase()) })
+            }
+            AnnotationData::Distance { from, to } if from == to => {
+                Err("A distance needs two different points".to_string())
+            }
+            distance => Ok(distance),
+        }
+    }
+
+    /// Length of a measured distance in pixels (None for colours).
+    pub fn length_px(&self) -> Option<f64> {
+        match self {
+            AnnotationData::Distance { from, to } => {
+                let dx = to[0] as f64 - from[0] as f64;
+                let dy = to[1] as f64 - from[1] as f64;
+                Some((dx * dx + dy * dy).sqrt())
+            }
+            AnnotationData::Color { .. } => None,
+        }
+    }
+
+    /// One-line description used in templates, e.g. `#1A2B3C at (4, 8)`.
+    pub fn summary(&self) -> String {
+        match self {
+            AnnotationData::Color { x, y, hex } => format!("{} at ({}, {})", hex, x, y),
+            AnnotationData::Distance { from, to } => format!(
+                "{:.0} px from ({}, {}) to ({}, {})",
+                self.length_px().unwrap_or_default(),
+                from[0], from[1], to[0], to[1]
+            ),
+        }
+    }
+}
+
+/// Tool an ink stroke was drawn with
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InkTool {
+    Pen,
+    /// Uncovers the screenshot under earlier strokes
+    Eraser,
+}
+
+/// Point of an ink stroke in image pixel coordinates
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct InkPoint {
+    pub x: f32,
+    pub y: f32,
+    /// Pen pressure from 0.0 to 1.0; 0.5 for devices that don't report it
+    #[serde(default = "default_pressure")]
+    pub pressure: f32,
+}
+
+fn default_pressure() -> f32 {
+    0.5
+}
+
+/// Freehand stroke; its width at full pressure is `width` pixels
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InkStroke {
+    pub tool: InkTool,
+    /// `#RRGGBB`; ignored for the eraser
+    #[serde(default)]
+    pub color: String,
+    pub width: f32,
+    pub points: Vec<InkPoint>,
+}
+
+/// Stamp from the team's stamp library placed on a screenshot; the image is
+/// scaled into the box whose top-left corner is at `x`, `y` (image pixels)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StampPlacement {
+    pub stamp_id: String,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Freehand ink drawn on a screenshot, kept so it can be edited again later.
+/// Strokes are in drawing order; stamps are drawn over them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct InkLayer {
+    pub strokes: Vec<InkStroke>,
+    #[serde(default)]
+    pub stamps: Vec<StampPlacement>,
+}
+
+impl InkLayer {
+    /// Check the strokes, normalise pen colours to `#RRGGBB` and clamp
+    /// pressures to 0.0–1.0. Strokes without points are dropped. Stamps are
+    /// shrunk to fit an image of `width` × `height` pixels, so a placement
+    /// can never ask for a larger image than the screenshot itself.
+    pub fn normalized(self, width: u32, height: u32) -> Result<Self, String> {
+        let mut strokes = Vec::with_capacity(self.strokes.len());
+        for mut stroke in self.strokes.into_iter().filter(|s| !s.points.is_empty()) {
+            if !(stroke.width > 0.0 && stroke.width <= 500.0) {
+                return Err(format!("Invalid stroke width: {}", stroke.width));
+            }
+            if stroke.points.iter().any(|p| !p.x.is_finite() || !p.y.is_finite() || !p.pressure.is_finite()) {
+                return Err("Stroke points must be finite numbers".to_string());
+            }
+            stroke.color = match stroke.tool {
+                InkTool::Pen => match (AnnotationData::Color { x: 0, y: 0, hex: stroke.color }).normalized()? {
+                    AnnotationData::Color { hex, .. } => hex,
+                    _ => unreachable!("a colour normalises to a colour"),
+                },
+                InkTool::Eraser => String::new(),
+            };
+            for point in &mut stroke.points {
+                point.pressure = point.pressure.clamp(0.0, 1.0);
+            }
+            strokes.push(stroke);
+        }
+        let mut stamps = self.stamps;
+        for stamp in &mut stamps {
+            let finite = [stamp.x, stamp.y, stamp.width, stamp.height].iter().all(|v| v.is_finite());
+            if !(finite && stamp.width >= 1.0 && stamp.height >= 1.0) {
+                return Err(format!("Invalid stamp placement for {}", stamp.stamp_id));
+            }
+            stamp.width = stamp.width.min(width.max(1) as f32);
+            stamp.height = stamp.height.min(height.max(1) as f32);
+        }
+        Ok(InkLayer { strokes, stamps })
+    }
+}
+
+/// Annotation attached to a capture
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Annotation {
+    pub id: i64,
+    pub capture_id: String,
+    /// Optional tester label, e.g. "Primary button"
+    pub label: Option<String>,
+    pub data: AnnotationData,
+    pub created_at: String,
+}
+
+/// How two bugs are related
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BugLinkKind {
+    RelatesTo,
+    /// `bug_id` reports the same problem as `related_bug_id`
+    Duplicates,
+    /// `bug_id` has to be fixed before `related_bug_id`
+    Blocks,
+}
+
+impl BugLinkKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            BugLinkKind::RelatesTo => "relates-to",
+            BugLinkKind::Duplicates => "duplicates",
+            BugLinkKind::Blocks => "blocks",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "relates-to" => Ok(BugLinkKind::RelatesTo),
+            "duplicates" => Ok(BugLinkKind::Duplicates),
+            "blocks" => Ok(BugLinkKind::Blocks),
+            _ => Err(format!("Invalid bug link kind: {}", s)),
+        }
+    }
+
+    /// How the link reads from one of its bugs: from `bug_id` when
+    /// `outgoing`, otherwise from `related_bug_id`.
+    pub fn label(&self, outgoing: bool) -> &str {
+        match (self, outgoing) {
+            (BugLinkKind::RelatesTo, _) => "related",
+            (BugLinkKind::Duplicates, true) => "duplicate",
+            (BugLinkKind::Duplicates, false) => "duplicated by",
+            (BugLinkKind::Blocks, true) => "blocks",
+            (BugLinkKind::Blocks, false) => "blocked by",
+        }
+    }
+}
+
+/// A directed link between two bugs of the same session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BugLink {
+    pub id: i64,
+    pub bug_id: String,
+    pub related_bug_id: String,
+    pub kind: BugLinkKind,
+    pub created_at: String,
+}
+
+/// A bug in a session's link graph
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BugLinkNode {
+    pub bug_id: String,
+    pub display_id: String,
+    pub title: Option<String>,
+    pub status: BugStatus,
+}
+
+/// The bugs of a session and the links between them, for visualization.
+/// Every bug of the session is a node, linked or not.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BugLinkGraph {
+    pub session_id: String,
+    pub nodes: Vec<BugLinkNode>,
+    pub links: Vec<BugLink>,
 }
 
 /// Bug update struct for partial updates
@@ -252,6 +631,76 @@ pub struct BugUpdate {
     pub custom_metadata: Option<String>,
 }
 
+/// Review-screen filter for `list_bugs_filtered`. Unset fields match every bug.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct BugFilter {
+    #[serde(default)]
+    pub status: Option<BugStatus>,
+    #[serde(rename = "type", default)]
+    pub bug_type: Option<BugType>,
+    /// Value of the profile's `severity` custom field
+    #[serde(default)]
+    pub severity: Option<String>,
+    #[serde(default)]
+    pub has_ticket: Option<bool>,
+    /// Whether the bug has a non-blank description
+    #[serde(default)]
+    pub has_description: Option<bool>,
+    /// Case-insensitive substring of the display ID, title, notes or description
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// Delivery state of a queued ticket
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum QueuedTicketStatus {
+    /// Waiting for its next attempt
+    Pending,
+    Sent,
+    /// Gave up; only retried when asked to
+    Failed,
+}
+
+impl QueuedTicketStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            QueuedTicketStatus::Pending => "pending",
+            QueuedTicketStatus::Sent => "sent",
+            QueuedTicketStatus::Failed => "failed",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "pending" => Ok(QueuedTicketStatus::Pending),
+            "sent" => Ok(QueuedTicketStatus::Sent),
+            "failed" => Ok(QueuedTicketStatus::Failed),
+            _ => Err(format!("Invalid queued ticket status: {}", s)),
+        }
+    }
+}
+
+/// A ticket whose creation failed and is retried in the background
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueuedTicket {
+    pub id: String,
+    pub bug_id: Option<String>,
+    pub title: String,
+    /// The `CreateTicketRequest` as JSON
+    pub request_json: String,
+    pub status: QueuedTicketStatus,
+    pub attempts: i64,
+    pub next_attempt_at: String,
+    pub last_error: Option<String>,
+    /// Identifier of the created ticket once sent (e.g. "PROJ-123")
+    pub ticket_identifier: Option<String>,
+    pub ticket_url: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,6 +718,7 @@ mod tests {
         assert_eq!(BugType::Bug.as_str(), "bug");
         assert_eq!(BugType::from_str("bug").unwrap(), BugType::Bug);
         assert_eq!(BugType::from_str("feature").unwrap(), BugType::Feature);
+        assert_eq!(BugType::from_str("question").unwrap(), BugType::Question);
         assert!(BugType::from_str("invalid").is_err());
     }
 
@@ -286,6 +736,59 @@ mod tests {
         assert!(CaptureType::from_str("invalid").is_err());
     }
 
+    #[test]
+    fn test_annotation_data_normalization() {
+        let color = AnnotationData::Color { x: 4, y: 8, hex: "#1a2b3c".to_string() };
+        assert_eq!(color.normalized().unwrap(), AnnotationData::Color { x: 4, y: 8, hex: "#1A2B3C".to_string() });
+        let short = AnnotationData::Color { x: 0, y: 0, hex: "f0a".to_string() };
+        assert_eq!(short.normalized().unwrap(), AnnotationData::Color { x: 0, y: 0, hex: "#FF00AA".to_string() });
+        assert!(AnnotationData::Color { x: 0, y: 0, hex: "#12345g".to_string() }.normalized().is_err());
+        assert!(AnnotationData::Distance { from: [3, 3], to: [3, 3] }.normalized().is_err());
+
+        let distance = AnnotationData::Distance { from: [10, 10], to: [13, 14] };
+        assert_eq!(distance.length_px(), Some(5.0));
+        assert_eq!(distance.summary(), "5 px from (10, 10) to (13, 14)");
+        let json = serde_json::to_string(&distance).unwrap();
+        assert_eq!(json, r#"{"kind":"distance","from":[10,10],"to":[13,14]}"#);
+    }
+
+    #[test]
+    fn test_ink_layer_normalization() {
+        let layer: InkLayer = serde_json::from_str(
+            r##"{"strokes": [
+                {"tool": "pen", "color": "#f00", "width": 4, "points": [{"x": 1, "y": 2, "pressure": 1.4}, {"x": 3, "y": 4}]},
+                {"tool": "eraser", "color": "#00ff00", "width": 10, "points": [{"x": 2, "y": 2, "pressure": 0.2}]},
+                {"tool": "pen", "color": "#000", "width": 4, "points": []}
+            ]}"##,
+        )
+        .unwrap();
+        let layer = layer.normalized(100, 100).unwrap();
+        assert_eq!(layer.strokes.len(), 2);
+        assert_eq!(layer.strokes[0].color, "#FF0000");
+        assert_eq!(layer.strokes[0].points[0].pressure, 1.0);
+        assert_eq!(layer.strokes[0].points[1].pressure, 0.5);
+        assert_eq!(layer.strokes[1].tool, InkTool::Eraser);
+        assert_eq!(layer.strokes[1].color, "");
+
+        let too_thin = InkLayer {
+            strokes: vec![InkStroke { tool: InkTool::Pen, color: "#000".to_string(), width: 0.0, points: layer.strokes[0].points.clone() }],
+            stamps: vec![],
+        };
+        assert!(too_thin.normalized(100, 100).is_err());
+        let flat_stamp = InkLayer {
+            stamps: vec![StampPlacement { stamp_id: "s-1".to_string(), x: 5.0, y: 5.0, width: 40.0, height: 0.0 }],
+            ..Default::default()
+        };
+        assert!(flat_stamp.normalized(100, 100).is_err());
+
+        let huge_stamp = InkLayer {
+            stamps: vec![StampPlacement { stamp_id: "s-1".to_string(), x: 0.0, y: 0.0, width: 1e9, height: 30.0 }],
+            ..Default::default()
+        };
+        let stamp = &huge_stamp.normalized(640, 480).unwrap().stamps[0];
+        assert_eq!((stamp.width, stamp.height), (640.0, 30.0));
+    }
+
     #[test]
     fn test_session_serialization() {
         let session = Session {
@@ -299,6 +802,8 @@ mod tests {
             original_snip_path: None,
             created_at: "2024-01-01T00:00:00Z".to_string(),
             profile_id: None,
+            unlocked_at: None,
+            timezone: None,
         };
 
         let json = serde_json::to_string(&session).unwrap();
@@ -315,18 +820,11 @@ mod tests {
             display_id: "Bug-01".to_string(),
             bug_type: BugType::Bug,
             title: Some("Test bug".to_string()),
-            notes: None,
-            description: None,
-            ai_description: None,
             status: BugStatus::Captured,
-            meeting_id: None,
-            software_version: None,
-            console_parse_json: None,
-            metadata_json: None,
-            custom_metadata: None,
             folder_path: "/test/bug".to_string(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&bug).unwrap();
@@ -14,7 +14,8 @@ pub fn init_database(conn: &Connection) -> SqlResult<()> {
             environment_json TEXT,
             original_snip_path TEXT,
             created_at TEXT NOT NULL DEFAULT (datetime('now')),
-            profile_id TEXT
+            profile_id TEXT,
+            unlocked_at TEXT
         )",
         [],
     )?;
@@ -23,7 +24,7 @@ pub fn init_database(conn: &Connection) -> SqlResult<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS bugs (
             id TEXT PRIMARY KEY,
-            session_id TEXT NOT NULL REFERENCES sessions(id),
+            session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
             bug_number INTEGER NOT NULL,
             display_id TEXT NOT NULL,
             type TEXT DEFAULT 'bug',
@@ -47,8 +48,8 @@ pub fn init_database(conn: &Connection) -> SqlResult<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS captures (
             id TEXT PRIMARY KEY,
-            bug_id TEXT REFERENCES bugs(id),
-            session_id TEXT NOT NULL REFERENCES sessions(id),
+            bug_id TEXT REFERENCES bugs(id) ON DELETE SET NULL,
+            session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
             file_name TEXT NOT NULL,
             file_path TEXT NOT NULL,
             file_type TEXT NOT NULL,
@@ -56,7 +57,16 @@ pub fn init_database(conn: &Connection) -> SqlResult<()> {
             file_size_bytes INTEGER,
             is_console_capture BOOLEAN DEFAULT FALSE,
             parsed_content TEXT,
-            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            edited_at TEXT,
+            media_link TEXT,
+            video_duration_ms INTEGER,
+            video_width INTEGER,
+            video_height INTEGER,
+            video_codec TEXT,
+            derived_from TEXT,
+            frame_timestamp_ms INTEGER,
+            source_metadata TEXT
         )",
         [],
     )?;
@@ -83,45 +93,117 @@ pub fn init_database(conn: &Connection) -> SqlResult<()> {
         [],
     )?;
 
-    // Migration: add custom_metadata column to bugs table (if not already present)
-    // This column stores profile-driven custom field values as a JSON blob.
-    // The legacy meeting_id and software_version columns are kept for backwards compatibility.
-    let has_custom_metadata: bool = {
-        let mut stmt = conn.prepare(
-            "SELECT COUNT(*) FROM pragma_table_info('bugs') WHERE name = 'custom_metadata'"
-        )?;
-        stmt.query_row([], |row| row.get::<_, i64>(0)).map(|c| c > 0)?
-    };
-
-    if !has_custom_metadata {
-        conn.execute(
-            "ALTER TABLE bugs ADD COLUMN custom_metadata TEXT",
-            [],
-        )?;
-
-        // Migrate existing meeting_id / software_version data into the JSON blob
-        conn.execute(
-            "UPDATE bugs SET custom_metadata = json_object('meeting_id', meeting_id, 'software_version', software_version)
-             WHERE meeting_id IS NOT NULL OR software_version IS NOT NULL",
-            [],
-        )?;
-    }
+    // Create audit_log table (append-only record of sensitive operations)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            actor TEXT NOT NULL DEFAULT '',
+            action TEXT NOT NULL,
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            details TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
 
-    // Migration: add profile_id column to sessions table (if not already present)
-    // Links a session to the QA profile that was active when it was started.
-    let has_profile_id: bool = {
-        let mut stmt = conn.prepare(
-            "SELECT COUNT(*) FROM pragma_table_info('sessions') WHERE name = 'profile_id'"
-        )?;
-        stmt.query_row([], |row| row.get::<_, i64>(0)).map(|c| c > 0)?
-    };
-
-    if !has_profile_id {
-        conn.execute(
-            "ALTER TABLE sessions ADD COLUMN profile_id TEXT",
-            [],
-        )?;
-    }
+    // The audit log is append-only: reject any UPDATE or DELETE at the SQL level.
+    conn.execute_batch(
+        "CREATE TRIGGER IF NOT EXISTS audit_log_no_update
+            BEFORE UPDATE ON audit_log
+            BEGIN SELECT RAISE(ABORT, 'audit_log is append-only'); END;
+         CREATE TRIGGER IF NOT EXISTS audit_log_no_delete
+            BEFORE DELETE ON audit_log
+            BEGIN SELECT RAISE(ABORT, 'audit_log is append-only'); END;",
+    )?;
+
+    // Create bug_number_reservations table (numbers claimed by imported or
+    // merged sessions that must never be handed out to new bugs)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS bug_number_reservations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+            range_start INTEGER NOT NULL,
+            range_end INTEGER NOT NULL,
+            source TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+
+    // Create claude_response_cache table (AI responses keyed by a hash of
+    // task, prompt and attached images)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS claude_response_cache (
+            cache_key TEXT PRIMARY KEY,
+            task TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Create annotations table (design-QA measurements on captures; `data`
+    // holds the kind-specific JSON, e.g. picked colour or distance endpoints)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS annotations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            capture_id TEXT NOT NULL REFERENCES captures(id) ON DELETE CASCADE,
+            kind TEXT NOT NULL,
+            label TEXT,
+            data TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Create annotation_ink table (freehand pen and eraser strokes on a
+    // screenshot, one layer per capture, re-rendered into the annotated copy)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS annotation_ink (
+            capture_id TEXT PRIMARY KEY REFERENCES captures(id) ON DELETE CASCADE,
+            data TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Create bug_links table (relates-to, duplicates, blocks; a bug split off another relates to it)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS bug_links (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            bug_id TEXT NOT NULL REFERENCES bugs(id) ON DELETE CASCADE,
+            related_bug_id TEXT NOT NULL REFERENCES bugs(id) ON DELETE CASCADE,
+            kind TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            UNIQUE (bug_id, related_bug_id, kind)
+        )",
+        [],
+    )?;
+
+    // Create ticket_queue table (tickets whose creation failed, retried in the background)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS ticket_queue (
+            id TEXT PRIMARY KEY,
+            bug_id TEXT REFERENCES bugs(id) ON DELETE SET NULL,
+            title TEXT NOT NULL,
+            request_json TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            next_attempt_at TEXT NOT NULL,
+            last_error TEXT,
+            ticket_identifier TEXT,
+            ticket_url TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Bring tables created by older versions up to date (adds columns, ON
+    // DELETE rules). Table rebuilds drop indices, so this runs before they
+    // are created.
+    super::migrations::run_migrations(conn)?;
 
     // Create indices
     conn.execute(
@@ -129,6 +211,29 @@ pub fn init_database(conn: &Connection) -> SqlResult<()> {
         [],
     )?;
 
+    // Bug lists are ordered by number within a session; this also serves
+    // get_next_bug_number's MAX(bug_number) without a scan
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bugs_session_number ON bugs(session_id, bug_number)",
+        [],
+    )?;
+
+    // Review-screen filters (list_filtered)
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bugs_session_status ON bugs(session_id, status)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bugs_session_type ON bugs(session_id, type)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bugs_session_severity ON bugs(session_id, json_extract(custom_metadata, '$.severity'))",
+        [],
+    )?;
+
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_captures_bug ON captures(bug_id)",
         [],
@@ -139,6 +244,31 @@ pub fn init_database(conn: &Connection) -> SqlResult<()> {
         [],
     )?;
 
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_audit_log_entity ON audit_log(entity_type, entity_id)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bug_number_reservations_session ON bug_number_reservations(session_id)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_annotations_capture ON annotations(capture_id)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bug_links_related ON bug_links(related_bug_id)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_ticket_queue_due ON ticket_queue(status, next_attempt_at)",
+        [],
+    )?;
+
     Ok(())
 }
 
@@ -166,6 +296,13 @@ mod tests {
         assert!(tables.contains(&"captures".to_string()));
         assert!(tables.contains(&"settings".to_string()));
         assert!(tables.contains(&"profiles".to_string()));
+        assert!(tables.contains(&"audit_log".to_string()));
+        assert!(tables.contains(&"bug_number_reservations".to_string()));
+        assert!(tables.contains(&"claude_response_cache".to_string()));
+        assert!(tables.contains(&"annotations".to_string()));
+        assert!(tables.contains(&"annotation_ink".to_string()));
+        assert!(tables.contains(&"bug_links".to_string()));
+        assert!(tables.contains(&"ticket_queue".to_string()));
     }
 
     #[test]
@@ -185,6 +322,57 @@ mod tests {
         assert!(indices.contains(&"idx_bugs_session".to_string()));
         assert!(indices.contains(&"idx_captures_bug".to_string()));
         assert!(indices.contains(&"idx_captures_session".to_string()));
+        assert!(indices.contains(&"idx_audit_log_entity".to_string()));
+        assert!(indices.contains(&"idx_bug_number_reservations_session".to_string()));
+        assert!(indices.contains(&"idx_annotations_capture".to_string()));
+        assert!(indices.contains(&"idx_bug_links_related".to_string()));
+        assert!(indices.contains(&"idx_bugs_session_number".to_string()));
+        assert!(indices.contains(&"idx_bugs_session_status".to_string()));
+    }
+
+    /// `EXPLAIN QUERY PLAN` details, one step per line.
+    fn query_plan(conn: &Connection, sql: &str) -> String {
+        conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql))
+            .unwrap()
+            .query_map(
+                rusqlite::params_from_iter(std::iter::repeat_n("x", sql.matches('?').count())),
+                |row| row.get::<_, String>(3),
+            )
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_hot_queries_use_indexes() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        // (query, index its plan must use)
+        let hot_queries = [
+            ("SELECT * FROM bugs WHERE session_id = ? ORDER BY bug_number ASC", "idx_bugs_session_number"),
+            ("SELECT MAX(bug_number) FROM bugs WHERE session_id = ?", "idx_bugs_session_number"),
+            ("SELECT * FROM bugs WHERE session_id = ? AND status = ?", "idx_bugs_session_status"),
+            ("SELECT * FROM bugs WHERE session_id = ? AND type = ?", "idx_bugs_session_type"),
+            (
+                "SELECT * FROM bugs WHERE session_id = ? AND json_extract(custom_metadata, '$.severity') = ?",
+                "idx_bugs_session_severity",
+            ),
+            ("SELECT * FROM captures WHERE bug_id = ? ORDER BY created_at ASC", "idx_captures_bug"),
+            ("SELECT * FROM captures WHERE session_id = ? ORDER BY created_at ASC", "idx_captures_session"),
+            ("SELECT value FROM settings WHERE key = ?", "sqlite_autoindex_settings_1"),
+            ("SELECT * FROM annotations WHERE capture_id = ?", "idx_annotations_capture"),
+            ("SELECT * FROM audit_log WHERE entity_type = ? AND entity_id = ?", "idx_audit_log_entity"),
+        ];
+        for (sql, index) in hot_queries {
+            let plan = query_plan(&conn, sql);
+            assert!(plan.contains(index), "{} should use {}, plan:\n{}", sql, index, plan);
+        }
+
+        // Bug lists come back in index order without a separate sort
+        let plan = query_plan(&conn, "SELECT * FROM bugs WHERE session_id = ? ORDER BY bug_number ASC");
+        assert!(!plan.contains("TEMP B-TREE"), "plan:\n{}", plan);
     }
 
     #[test]
@@ -1,5 +1,5 @@
 use rusqlite::{Connection, Result as SqlResult, params};
-use crate::database::models::{Session, SessionStatus, SessionSummary};
+use crate::database::models::{BugStatusCounts, Session, SessionStatus, SessionSummary};
 
 /// Trait defining session operations
 #[allow(dead_code)]
@@ -12,6 +12,8 @@ pub trait SessionOps {
     fn get_active_session(&self) -> SqlResult<Option<Session>>;
     fn get_summaries(&self) -> SqlResult<Vec<SessionSummary>>;
     fn update_status(&self, id: &str, status: SessionStatus) -> SqlResult<()>;
+    fn unlock(&self, id: &str, unlocked_at: &str) -> SqlResult<()>;
+    fn update_notes(&self, id: &str, notes: &str) -> SqlResult<()>;
 }
 
 /// Session repository implementation
@@ -30,8 +32,8 @@ impl<'a> SessionRepository<'a> {
 impl<'a> SessionOps for SessionRepository<'a> {
     fn create(&self, session: &Session) -> SqlResult<()> {
         self.conn.execute(
-            "INSERT INTO sessions (id, started_at, ended_at, status, folder_path, session_notes, environment_json, original_snip_path, created_at, profile_id)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT INTO sessions (id, started_at, ended_at, status, folder_path, session_notes, environment_json, original_snip_path, created_at, profile_id, unlocked_at, timezone)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
             params![
                 session.id,
                 session.started_at,
@@ -43,6 +45,8 @@ impl<'a> SessionOps for SessionRepository<'a> {
                 session.original_snip_path,
                 session.created_at,
                 session.profile_id,
+                session.unlocked_at,
+                session.timezone,
             ],
         )?;
         Ok(())
@@ -50,7 +54,7 @@ impl<'a> SessionOps for SessionRepository<'a> {
 
     fn get(&self, id: &str) -> SqlResult<Option<Session>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, started_at, ended_at, status, folder_path, session_notes, environment_json, original_snip_path, created_at, profile_id
+            "SELECT id, started_at, ended_at, status, folder_path, session_notes, environment_json, original_snip_path, created_at, profile_id, unlocked_at, timezone
              FROM sessions WHERE id = ?1"
         )?;
 
@@ -69,6 +73,8 @@ impl<'a> SessionOps for SessionRepository<'a> {
                 original_snip_path: row.get(7)?,
                 created_at: row.get(8)?,
                 profile_id: row.get(9)?,
+                unlocked_at: row.get(10)?,
+                timezone: row.get(11)?,
             }))
         } else {
             Ok(None)
@@ -78,7 +84,7 @@ impl<'a> SessionOps for SessionRepository<'a> {
     fn update(&self, session: &Session) -> SqlResult<()> {
         self.conn.execute(
             "UPDATE sessions SET started_at = ?2, ended_at = ?3, status = ?4, folder_path = ?5,
-             session_notes = ?6, environment_json = ?7, original_snip_path = ?8, profile_id = ?9
+             session_notes = ?6, environment_json = ?7, original_snip_path = ?8, profile_id = ?9, unlocked_at = ?10, timezone = ?11
              WHERE id = ?1",
             params![
                 session.id,
@@ -90,6 +96,8 @@ impl<'a> SessionOps for SessionRepository<'a> {
                 session.environment_json,
                 session.original_snip_path,
                 session.profile_id,
+                session.unlocked_at,
+                session.timezone,
             ],
         )?;
         Ok(())
@@ -102,7 +110,7 @@ impl<'a> SessionOps for SessionRepository<'a> {
 
     fn list(&self) -> SqlResult<Vec<Session>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, started_at, ended_at, status, folder_path, session_notes, environment_json, original_snip_path, created_at, profile_id
+            "SELECT id, started_at, ended_at, status, folder_path, session_notes, environment_json, original_snip_path, created_at, profile_id, unlocked_at, timezone
              FROM sessions ORDER BY started_at DESC"
         )?;
 
@@ -119,6 +127,8 @@ impl<'a> SessionOps for SessionRepository<'a> {
                 original_snip_path: row.get(7)?,
                 created_at: row.get(8)?,
                 profile_id: row.get(9)?,
+                unlocked_at: row.get(10)?,
+                timezone: row.get(11)?,
             })
         })?;
 
@@ -127,7 +137,7 @@ impl<'a> SessionOps for SessionRepository<'a> {
 
     fn get_active_session(&self) -> SqlResult<Option<Session>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, started_at, ended_at, status, folder_path, session_notes, environment_json, original_snip_path, created_at, profile_id
+            "SELECT id, started_at, ended_at, status, folder_path, session_notes, environment_json, original_snip_path, created_at, profile_id, unlocked_at, timezone
              FROM sessions WHERE status = 'active' ORDER BY started_at DESC LIMIT 1"
         )?;
 
@@ -146,6 +156,8 @@ impl<'a> SessionOps for SessionRepository<'a> {
                 original_snip_path: row.get(7)?,
                 created_at: row.get(8)?,
                 profile_id: row.get(9)?,
+                unlocked_at: row.get(10)?,
+                timezone: row.get(11)?,
             }))
         } else {
             Ok(None)
@@ -154,7 +166,12 @@ impl<'a> SessionOps for SessionRepository<'a> {
 
     fn get_summaries(&self) -> SqlResult<Vec<SessionSummary>> {
         let mut stmt = self.conn.prepare(
-            "SELECT s.id, s.started_at, s.ended_at, s.status, COUNT(b.id) as bug_count
+            "SELECT s.id, s.started_at, s.ended_at, s.status, COUNT(b.id) as bug_count,
+                    SUM(CASE WHEN b.status = 'capturing' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN b.status = 'captured' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN b.status = 'reviewed' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN b.status = 'ready' THEN 1 ELSE 0 END),
+                    COUNT(b.external_ticket_id)
              FROM sessions s
              LEFT JOIN bugs b ON s.id = b.session_id
              GROUP BY s.id
@@ -163,12 +180,21 @@ impl<'a> SessionOps for SessionRepository<'a> {
 
         let rows = stmt.query_map([], |row| {
             let status_str: String = row.get(3)?;
+            let status_counts = BugStatusCounts {
+                capturing: row.get(5)?,
+                captured: row.get(6)?,
+                reviewed: row.get(7)?,
+                ready: row.get(8)?,
+            };
             Ok(SessionSummary {
                 id: row.get(0)?,
                 started_at: row.get(1)?,
                 ended_at: row.get(2)?,
                 status: SessionStatus::from_str(&status_str).unwrap_or(SessionStatus::Active),
                 bug_count: row.get(4)?,
+                reviewed_count: status_counts.reviewed + status_counts.ready,
+                status_counts,
+                ticketed_count: row.get(9)?,
             })
         })?;
 
@@ -176,12 +202,32 @@ impl<'a> SessionOps for SessionRepository<'a> {
     }
 
     fn update_status(&self, id: &str, status: SessionStatus) -> SqlResult<()> {
+        // Moving into a locked status (reviewed/synced) re-locks the session,
+        // discarding any earlier explicit unlock.
         self.conn.execute(
-            "UPDATE sessions SET status = ?1 WHERE id = ?2",
+            "UPDATE sessions SET status = ?1,
+             unlocked_at = CASE WHEN ?1 IN ('reviewed', 'synced') THEN NULL ELSE unlocked_at END
+             WHERE id = ?2",
             params![status.as_str(), id],
         )?;
         Ok(())
     }
+
+    fn unlock(&self, id: &str, unlocked_at: &str) -> SqlResult<()> {
+        self.conn.execute(
+            "UPDATE sessions SET unlocked_at = ?1 WHERE id = ?2",
+            params![unlocked_at, id],
+        )?;
+        Ok(())
+    }
+
+    fn update_notes(&self, id: &str, notes: &str) -> SqlResult<()> {
+        self.conn.execute(
+            "UPDATE sessions SET session_notes = ?1 WHERE id = ?2",
+            params![notes, id],
+        )?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -201,6 +247,8 @@ mod tests {
             original_snip_path: None,
             created_at: "2024-01-01T10:00:00Z".to_string(),
             profile_id: None,
+            unlocked_at: None,
+            timezone: None,
         }
     }
 
@@ -311,6 +359,27 @@ mod tests {
         assert_eq!(updated.status, SessionStatus::Reviewed);
     }
 
+    #[test]
+    fn test_unlock_and_relock() {
+        let db = Database::in_memory().unwrap();
+        let repo = SessionRepository::new(db.connection());
+        repo.create(&create_test_session("test-id-lock")).unwrap();
+
+        repo.update_status("test-id-lock", SessionStatus::Reviewed).unwrap();
+        assert!(repo.get("test-id-lock").unwrap().unwrap().is_locked());
+
+        repo.unlock("test-id-lock", "2024-01-02T00:00:00Z").unwrap();
+        let unlocked = repo.get("test-id-lock").unwrap().unwrap();
+        assert_eq!(unlocked.unlocked_at, Some("2024-01-02T00:00:00Z".to_string()));
+        assert!(!unlocked.is_locked());
+
+        // Syncing the session locks it again
+        repo.update_status("test-id-lock", SessionStatus::Synced).unwrap();
+        let relocked = repo.get("test-id-lock").unwrap().unwrap();
+        assert_eq!(relocked.unlocked_at, None);
+        assert!(relocked.is_locked());
+    }
+
     #[test]
     fn test_get_summaries() {
         let db = Database::in_memory().unwrap();
@@ -322,6 +391,32 @@ mod tests {
         let summaries = repo.get_summaries().unwrap();
         assert_eq!(summaries.len(), 2);
         assert_eq!(summaries[0].bug_count, 0);
+        assert_eq!(summaries[0].status_counts, BugStatusCounts::default());
+    }
+
+    #[test]
+    fn test_get_summaries_counts_by_status() {
+        let db = Database::in_memory().unwrap();
+        let repo = SessionRepository::new(db.connection());
+        repo.create(&create_test_session("test-id-12")).unwrap();
+        db.connection()
+            .execute_batch(
+                "INSERT INTO bugs (id, session_id, bug_number, display_id, folder_path, status, external_ticket_id) VALUES
+                   ('b-1', 'test-id-12', 1, 'BUG-001', '/qa/b-1', 'captured', NULL),
+                   ('b-2', 'test-id-12', 2, 'BUG-002', '/qa/b-2', 'reviewed', NULL),
+                   ('b-3', 'test-id-12', 3, 'BUG-003', '/qa/b-3', 'ready', 'T-1'),
+                   ('b-4', 'test-id-12', 4, 'BUG-004', '/qa/b-4', 'ready', 'T-2');",
+            )
+            .unwrap();
+
+        let summary = repo.get_summaries().unwrap().remove(0);
+        assert_eq!(summary.bug_count, 4);
+        assert_eq!(
+            summary.status_counts,
+            BugStatusCounts { capturing: 0, captured: 1, reviewed: 1, ready: 2 }
+        );
+        assert_eq!(summary.reviewed_count, 3);
+        assert_eq!(summary.ticketed_count, 2);
     }
 
     #[test]
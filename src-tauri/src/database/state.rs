@@ -2,14 +2,14 @@ use rusqlite::Connection;
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::path::Path;
 
-use super::schema::init_database;
+use super::Database;
 
 /// Shared database state for Tauri managed state.
 ///
 /// Wraps a `rusqlite::Connection` in `Arc<Mutex<Connection>>` so it can be
 /// registered with `app.manage()` and accessed by Tauri commands via
-/// `State<DbState>`. WAL mode is enabled for better concurrent read
-/// performance.
+/// `State<DbState>`. The connection is configured by [`Database::open`]
+/// (WAL, busy timeout, foreign keys).
 ///
 /// # Usage in a Tauri command
 ///
@@ -26,39 +26,24 @@ pub struct DbState {
 }
 
 impl DbState {
-    /// Open (or create) the SQLite database at `path`, enable WAL mode, and
-    /// initialize the schema.  Returns an error if any of these steps fail.
+    /// Open (or create) the SQLite database at `path`, configure the
+    /// connection and initialize the schema.  Returns an error if any of these
+    /// steps fail.
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, String> {
-        let conn = Connection::open(path)
-            .map_err(|e| format!("Failed to open database: {}", e))?;
-
-        // Enable WAL mode for better concurrent read performance.
-        conn.execute_batch("PRAGMA journal_mode=WAL;")
-            .map_err(|e| format!("Failed to set WAL mode: {}", e))?;
-
-        // Ensure all tables and indices are present.
-        init_database(&conn)
-            .map_err(|e| format!("Failed to initialize database schema: {}", e))?;
+        let db = Database::open(path).map_err(|e| format!("Failed to open database: {}", e))?;
 
         Ok(DbState {
-            inner: Arc::new(Mutex::new(conn)),
+            inner: Arc::new(Mutex::new(db.into_connection())),
         })
     }
 
     /// Create an in-memory database (primarily for testing).
     #[cfg(test)]
     pub fn in_memory() -> Result<Self, String> {
-        let conn = Connection::open_in_memory()
-            .map_err(|e| format!("Failed to open in-memory database: {}", e))?;
-
-        conn.execute_batch("PRAGMA journal_mode=WAL;")
-            .map_err(|e| format!("Failed to set WAL mode: {}", e))?;
-
-        init_database(&conn)
-            .map_err(|e| format!("Failed to initialize database schema: {}", e))?;
+        let db = Database::in_memory().map_err(|e| format!("Failed to open in-memory database: {}", e))?;
 
         Ok(DbState {
-            inner: Arc::new(Mutex::new(conn)),
+            inner: Arc::new(Mutex::new(db.into_connection())),
         })
     }
 
@@ -0,0 +1,207 @@
+use rusqlite::{Connection, OptionalExtension, Result as SqlResult, Row, params};
+use crate::database::models::{QueuedTicket, QueuedTicketStatus};
+
+const COLUMNS: &str = "id, bug_id, title, request_json, status, attempts, next_attempt_at, last_error, ticket_identifier, ticket_url, created_at, updated_at";
+
+/// Trait defining ticket queue operations
+#[allow(dead_code)]
+pub trait TicketQueueOps {
+    fn create(&self, ticket: &QueuedTicket) -> SqlResult<()>;
+    fn get(&self, id: &str) -> SqlResult<Option<QueuedTicket>>;
+    /// All entries, oldest first
+    fn list(&self) -> SqlResult<Vec<QueuedTicket>>;
+    /// Pending entries whose next attempt is at or before `now` (RFC 3339, UTC)
+    fn list_due(&self, now: &str) -> SqlResult<Vec<QueuedTicket>>;
+    /// Count a failed attempt; the entry stays pending until `next_attempt_at`
+    fn record_retry(&self, id: &str, error: &str, next_attempt_at: &str) -> SqlResult<()>;
+    /// Count a failed attempt and give up on the entry
+    fn mark_failed(&self, id: &str, error: &str) -> SqlResult<()>;
+    fn mark_sent(&self, id: &str, ticket_identifier: &str, ticket_url: &str) -> SqlResult<()>;
+    /// Make an entry pending again with its next attempt at `next_attempt_at`
+    fn reschedule(&self, id: &str, next_attempt_at: &str) -> SqlResult<bool>;
+    fn delete(&self, id: &str) -> SqlResult<bool>;
+}
+
+/// Ticket queue repository implementation
+#[allow(dead_code)]
+pub struct TicketQueueRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> TicketQueueRepository<'a> {
+    #[allow(dead_code)]
+    pub fn new(conn: &'a Connection) -> Self {
+        TicketQueueRepository { conn }
+    }
+
+    fn from_row(row: &Row) -> SqlResult<QueuedTicket> {
+        let status: String = row.get(4)?;
+        Ok(QueuedTicket {
+            id: row.get(0)?,
+            bug_id: row.get(1)?,
+            title: row.get(2)?,
+            request_json: row.get(3)?,
+            status: QueuedTicketStatus::from_str(&status).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, e.into())
+            })?,
+            attempts: row.get(5)?,
+            next_attempt_at: row.get(6)?,
+            last_error: row.get(7)?,
+            ticket_identifier: row.get(8)?,
+            ticket_url: row.get(9)?,
+            created_at: row.get(10)?,
+            updated_at: row.get(11)?,
+        })
+    }
+
+    fn now() -> String {
+        chrono::Utc::now().to_rfc3339()
+    }
+}
+
+impl<'a> TicketQueueOps for TicketQueueRepository<'a> {
+    fn create(&self, ticket: &QueuedTicket) -> SqlResult<()> {
+        self.conn.execute(
+            &format!("INSERT INTO ticket_queue ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)", COLUMNS),
+            params![
+                ticket.id,
+                ticket.bug_id,
+                ticket.title,
+                ticket.request_json,
+                ticket.status.as_str(),
+                ticket.attempts,
+                ticket.next_attempt_at,
+                ticket.last_error,
+                ticket.ticket_identifier,
+                ticket.ticket_url,
+                ticket.created_at,
+                ticket.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn get(&self, id: &str) -> SqlResult<Option<QueuedTicket>> {
+        self.conn
+            .query_row(&format!("SELECT {} FROM ticket_queue WHERE id = ?1", COLUMNS), params![id], Self::from_row)
+            .optional()
+    }
+
+    fn list(&self) -> SqlResult<Vec<QueuedTicket>> {
+        let mut stmt = self.conn.prepare(&format!("SELECT {} FROM ticket_queue ORDER BY created_at, id", COLUMNS))?;
+        let rows = stmt.query_map([], Self::from_row)?;
+        rows.collect()
+    }
+
+    fn list_due(&self, now: &str) -> SqlResult<Vec<QueuedTicket>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM ticket_queue WHERE status = 'pending' AND next_attempt_at <= ?1 ORDER BY next_attempt_at, id",
+            COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![now], Self::from_row)?;
+        rows.collect()
+    }
+
+    fn record_retry(&self, id: &str, error: &str, next_attempt_at: &str) -> SqlResult<()> {
+        self.conn.execute(
+            "UPDATE ticket_queue SET attempts = attempts + 1, last_error = ?2, next_attempt_at = ?3, updated_at = ?4
+             WHERE id = ?1",
+            params![id, error, next_attempt_at, Self::now()],
+        )?;
+        Ok(())
+    }
+
+    fn mark_failed(&self, id: &str, error: &str) -> SqlResult<()> {
+        self.conn.execute(
+            "UPDATE ticket_queue SET status = 'failed', attempts = attempts + 1, last_error = ?2, updated_at = ?3
+             WHERE id = ?1",
+            params![id, error, Self::now()],
+        )?;
+        Ok(())
+    }
+
+    fn mark_sent(&self, id: &str, ticket_identifier: &str, ticket_url: &str) -> SqlResult<()> {
+        self.conn.execute(
+            "UPDATE ticket_queue SET status = 'sent', attempts = attempts + 1, last_error = NULL,
+                ticket_identifier = ?2, ticket_url = ?3, updated_at = ?4
+             WHERE id = ?1",
+            params![id, ticket_identifier, ticket_url, Self::now()],
+        )?;
+        Ok(())
+    }
+
+    fn reschedule(&self, id: &str, next_attempt_at: &str) -> SqlResult<bool> {
+        let updated = self.conn.execute(
+            "UPDATE ticket_queue SET status = 'pending', next_attempt_at = ?2, updated_at = ?3
+             WHERE id = ?1 AND status != 'sent'",
+            params![id, next_attempt_at, Self::now()],
+        )?;
+        Ok(updated > 0)
+    }
+
+    fn delete(&self, id: &str) -> SqlResult<bool> {
+        let removed = self.conn.execute("DELETE FROM ticket_queue WHERE id = ?1", params![id])?;
+        Ok(removed > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    fn queued(id: &str, next_attempt_at: &str) -> QueuedTicket {
+        QueuedTicket {
+            id: id.to_string(),
+            bug_id: None,
+            title: format!("Ticket {}", id),
+            request_json: "{}".to_string(),
+            status: QueuedTicketStatus::Pending,
+            attempts: 1,
+            next_attempt_at: next_attempt_at.to_string(),
+            last_error: Some("Network error: timed out".to_string()),
+            ticket_identifier: None,
+            ticket_url: None,
+            created_at: "2024-01-01T10:00:00+00:00".to_string(),
+            updated_at: "2024-01-01T10:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_list_due_only_returns_pending_entries_that_are_due() {
+        let db = Database::in_memory().unwrap();
+        let repo = TicketQueueRepository::new(db.connection());
+        repo.create(&queued("q-1", "2024-01-01T10:00:30+00:00")).unwrap();
+        repo.create(&queued("q-2", "2024-01-01T10:05:00+00:00")).unwrap();
+        repo.create(&queued("q-3", "2024-01-01T10:00:10+00:00")).unwrap();
+        repo.mark_failed("q-3", "Authentication failed").unwrap();
+
+        let due: Vec<String> = repo.list_due("2024-01-01T10:01:00+00:00").unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(due, vec!["q-1"]);
+        assert_eq!(repo.get("q-3").unwrap().unwrap().status, QueuedTicketStatus::Failed);
+    }
+
+    #[test]
+    fn test_retry_sent_and_reschedule() {
+        let db = Database::in_memory().unwrap();
+        let repo = TicketQueueRepository::new(db.connection());
+        repo.create(&queued("q-1", "2024-01-01T10:00:30+00:00")).unwrap();
+
+        repo.record_retry("q-1", "Network error: refused", "2024-01-01T10:01:30+00:00").unwrap();
+        let entry = repo.get("q-1").unwrap().unwrap();
+        assert_eq!(entry.attempts, 2);
+        assert_eq!(entry.next_attempt_at, "2024-01-01T10:01:30+00:00");
+        assert_eq!(entry.last_error.as_deref(), Some("Network error: refused"));
+
+        repo.mark_sent("q-1", "ENG-42", "https://linear.app/acme/issue/ENG-42").unwrap();
+        let entry = repo.get("q-1").unwrap().unwrap();
+        assert_eq!(entry.status, QueuedTicketStatus::Sent);
+        assert_eq!(entry.ticket_identifier.as_deref(), Some("ENG-42"));
+        assert!(entry.last_error.is_none());
+
+        // A sent ticket is never queued again
+        assert!(!repo.reschedule("q-1", "2024-01-01T11:00:00+00:00").unwrap());
+        assert!(repo.delete("q-1").unwrap());
+        assert!(repo.list().unwrap().is_empty());
+    }
+}
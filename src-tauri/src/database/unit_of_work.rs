@@ -0,0 +1,109 @@
+use rusqlite::{Connection, Result as SqlResult, Transaction, TransactionBehavior};
+
+/// A transaction spanning several repository calls, plus compensating actions
+/// for side effects outside the database (e.g. folders created on disk).
+///
+/// Repositories are built on [`UnitOfWork::connection`] as usual. Nothing is
+/// visible to other code until [`UnitOfWork::commit`]; dropping the unit of
+/// work without committing (an early `?` return) rolls the transaction back
+/// and runs the compensations, newest first.
+pub struct UnitOfWork<'conn> {
+    tx: Option<Transaction<'conn>>,
+    compensations: Vec<Box<dyn FnOnce() + 'conn>>,
+}
+
+impl<'conn> UnitOfWork<'conn> {
+    /// Start an immediate transaction, so reads that decide a write (such as
+    /// the next bug number) cannot race another writer.
+    pub fn begin(conn: &'conn mut Connection) -> SqlResult<Self> {
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        Ok(UnitOfWork { tx: Some(tx), compensations: Vec::new() })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        self.tx.as_ref().expect("transaction is only taken on commit or drop")
+    }
+
+    /// Register an action that undoes a side effect if the work is rolled back.
+    pub fn on_rollback(&mut self, compensation: impl FnOnce() + 'conn) {
+        self.compensations.push(Box::new(compensation));
+    }
+
+    /// Commit the transaction. Compensations are discarded on success and run
+    /// if the commit itself fails.
+    pub fn commit(mut self) -> SqlResult<()> {
+        let tx = self.tx.take().expect("transaction is only taken on commit or drop");
+        tx.commit()?;
+        self.compensations.clear();
+        Ok(())
+    }
+}
+
+impl Drop for UnitOfWork<'_> {
+    fn drop(&mut self) {
+        // Roll back before undoing side effects that the rows may refer to
+        drop(self.tx.take());
+        while let Some(compensation) = self.compensations.pop() {
+            compensation();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn test_conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        conn
+    }
+
+    fn session_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM sessions", [], |row| row.get(0)).unwrap()
+    }
+
+    const INSERT_SESSION: &str =
+        "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/qa/s-1')";
+
+    #[test]
+    fn test_commit_keeps_rows_and_skips_compensations() {
+        let mut conn = test_conn();
+        let undone = RefCell::new(Vec::new());
+
+        let mut uow = UnitOfWork::begin(&mut conn).unwrap();
+        uow.connection().execute(INSERT_SESSION, []).unwrap();
+        uow.on_rollback(|| undone.borrow_mut().push("folder"));
+        uow.commit().unwrap();
+
+        assert!(undone.borrow().is_empty());
+        assert_eq!(session_count(&conn), 1);
+    }
+
+    #[test]
+    fn test_drop_rolls_back_and_compensates_newest_first() {
+        let mut conn = test_conn();
+        let undone = RefCell::new(Vec::new());
+
+        let result: Result<(), String> = (|| {
+            let mut uow = UnitOfWork::begin(&mut conn).map_err(|e| e.to_string())?;
+            uow.on_rollback(|| undone.borrow_mut().push("session folder"));
+            uow.connection().execute(INSERT_SESSION, []).map_err(|e| e.to_string())?;
+            uow.on_rollback(|| undone.borrow_mut().push("bug folder"));
+            // Injected failure: the bug's session does not exist
+            uow.connection()
+                .execute(
+                    "INSERT INTO bugs (id, session_id, bug_number, display_id, folder_path)
+                     VALUES ('b-1', 's-missing', 1, 'BUG-001', '/qa/s-1/bug_001')",
+                    [],
+                )
+                .map_err(|e| e.to_string())?;
+            uow.commit().map_err(|e| e.to_string())
+        })();
+
+        assert!(result.is_err());
+        assert_eq!(*undone.borrow(), vec!["bug folder", "session folder"]);
+        assert_eq!(session_count(&conn), 0);
+    }
+}
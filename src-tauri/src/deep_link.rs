@@ -0,0 +1,102 @@
+//! `qacapture://` deep links and `.qacap` launch arguments.
+//!
+//! The OS hands these to the app as a command-line argument, either on first
+//! launch or (via the single-instance plugin) forwarded from a second launch.
+//! Ticket descriptions can embed [`bug_url`] to link back to local evidence.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::archive_viewer::ARCHIVE_EXTENSION;
+
+/// URL scheme registered with the OS.
+pub const URL_SCHEME: &str = "qacapture";
+
+/// What a launch argument asks the app to show.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "target", rename_all = "snake_case")]
+pub enum LaunchTarget {
+    /// `qacapture://bug/<id>`
+    Bug(String),
+    /// `qacapture://session/<id>`
+    Session(String),
+    /// A `.qacap` archive to open in the read-only viewer.
+    Archive(PathBuf),
+}
+
+/// Parse a `qacapture://` URL.
+pub fn parse_url(url: &str) -> Result<LaunchTarget, String> {
+    let prefix = format!("{}://", URL_SCHEME);
+    let rest = url
+        .get(..prefix.len())
+        .filter(|scheme| scheme.eq_ignore_ascii_case(&prefix))
+        .map(|_| &url[prefix.len()..])
+        .ok_or_else(|| format!("Not a {} link: {}", URL_SCHEME, url))?;
+
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+    let decode = |id: &str| {
+        urlencoding::decode(id)
+            .map(|id| id.into_owned())
+            .map_err(|e| format!("Invalid id in {}: {}", url, e))
+    };
+
+    match parts.as_slice() {
+        [kind, id] if kind.eq_ignore_ascii_case("bug") => Ok(LaunchTarget::Bug(decode(id)?)),
+        [kind, id] if kind.eq_ignore_ascii_case("session") => Ok(LaunchTarget::Session(decode(id)?)),
+        _ => Err(format!("Unsupported {} link: {}", URL_SCHEME, url)),
+    }
+}
+
+/// The first deep link or `.qacap` path among process arguments (argv[0] skipped).
+pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Option<LaunchTarget> {
+    args.into_iter().skip(1).find_map(|arg| {
+        if arg.to_lowercase().starts_with(&format!("{}:", URL_SCHEME)) {
+            return parse_url(&arg).ok();
+        }
+        let path = PathBuf::from(&arg);
+        path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case(ARCHIVE_EXTENSION))
+            .then_some(LaunchTarget::Archive(path))
+    })
+}
+
+/// Link that opens `bug_id` in the app.
+pub fn bug_url(bug_id: &str) -> String {
+    format!("{}://bug/{}", URL_SCHEME, urlencoding::encode(bug_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        assert_eq!(parse_url("qacapture://bug/abc-123"), Ok(LaunchTarget::Bug("abc-123".to_string())));
+        assert_eq!(parse_url("QACapture://Bug/abc/?from=linear"), Ok(LaunchTarget::Bug("abc".to_string())));
+        assert_eq!(parse_url("qacapture://session/s%201"), Ok(LaunchTarget::Session("s 1".to_string())));
+        assert!(parse_url("qacapture://bug").is_err());
+        assert!(parse_url("qacapture://settings/x").is_err());
+        assert!(parse_url("https://bug/abc").is_err());
+    }
+
+    #[test]
+    fn test_bug_url_round_trips() {
+        let url = bug_url("id with/slash");
+        assert_eq!(parse_url(&url), Ok(LaunchTarget::Bug("id with/slash".to_string())));
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args = ["app", "--minimized", "qacapture://bug/b-1"].map(String::from);
+        assert_eq!(from_args(args), Some(LaunchTarget::Bug("b-1".to_string())));
+
+        let args = ["app", "C:\\exports\\Session.QACAP"].map(String::from);
+        assert_eq!(from_args(args), Some(LaunchTarget::Archive(PathBuf::from("C:\\exports\\Session.QACAP"))));
+
+        assert_eq!(from_args(["app.qacap".to_string()]), None);
+        assert_eq!(from_args(["app", "qacapture://nope"].map(String::from)), None);
+    }
+}
@@ -0,0 +1,339 @@
+//! Demo data seeding.
+//!
+//! Creates a realistic-looking, fully populated session (bugs, placeholder PNG
+//! captures, session notes, bug descriptions) without running a real capture
+//! session. Used by trainers to demo the review flow and by developers to
+//! profile the UI against a non-trivial data set.
+//!
+//! The generated session is marked `ended` so it opens straight into review.
+//! Everything written to disk follows the same layout as a real session
+//! (`bug_NNN/capture-NNN.png`, `_captures/`, `_unsorted/`, `session-notes.md`).
+
+use chrono::{Duration, Utc};
+use rusqlite::Connection;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::clipboard_watcher::encode_png;
+use crate::database::{
+    Bug, BugOps, BugRepository, BugStatus, BugType, Capture, CaptureOps, CaptureRepository,
+    CaptureType, Session, SessionOps, SessionRepository, SessionStatus,
+};
+
+/// Width/height of the generated placeholder screenshots.
+const PLACEHOLDER_WIDTH: u32 = 640;
+const PLACEHOLDER_HEIGHT: u32 = 400;
+
+/// A canned bug used to populate the demo session.
+struct DemoBug {
+    bug_type: BugType,
+    title: &'static str,
+    notes: &'static str,
+    description: &'static str,
+    status: BugStatus,
+    capture_count: u32,
+    /// Accent colour (RGB) drawn into the placeholder captures.
+    color: [u8; 3],
+}
+
+const DEMO_BUGS: &[DemoBug] = &[
+    DemoBug {
+        bug_type: BugType::Bug,
+        title: "Save button unresponsive after editing meeting agenda",
+        notes: "Edited agenda item, clicked Save — nothing happened. Second click worked.",
+        description: "## Steps to Reproduce\n1. Open a meeting\n2. Edit the first agenda item\n3. Click **Save**\n\n## Expected\nChanges are saved on the first click.\n\n## Actual\nThe first click is ignored; a second click is required.",
+        status: BugStatus::Reviewed,
+        capture_count: 3,
+        color: [220, 68, 55],
+    },
+    DemoBug {
+        bug_type: BugType::Bug,
+        title: "Participant avatars overlap at 150% display scaling",
+        notes: "Only at 150% DPI. 100% and 200% look fine.",
+        description: "## Steps to Reproduce\n1. Set display scaling to 150%\n2. Join a meeting with 5+ participants\n\n## Expected\nAvatars are laid out in a row.\n\n## Actual\nAvatars overlap by roughly half their width.",
+        status: BugStatus::Captured,
+        capture_count: 2,
+        color: [66, 133, 244],
+    },
+    DemoBug {
+        bug_type: BugType::Feature,
+        title: "Allow exporting action items to CSV",
+        notes: "Customer asked for this during the call.",
+        description: "Users would like to export the action item list of a meeting as CSV so it can be imported into their tracker.",
+        status: BugStatus::Ready,
+        capture_count: 1,
+        color: [15, 157, 88],
+    },
+    DemoBug {
+        bug_type: BugType::Feedback,
+        title: "Onboarding tooltip copy is confusing",
+        notes: "\"Sync your workspace\" — unclear which workspace is meant.",
+        description: "The onboarding tooltip on the sidebar says \"Sync your workspace\" without explaining which workspace (calendar vs. team). Suggest clarifying the wording.",
+        status: BugStatus::Captured,
+        capture_count: 1,
+        color: [244, 180, 0],
+    },
+];
+
+const DEMO_SESSION_NOTES: &str = "Demo session — regression pass on the meeting editor.\n\n\
+- Build: 2.4.0-demo\n\
+- Focus: agenda editing, participant list, onboarding\n\
+- Tester: Demo User\n";
+
+/// Seed a complete demo session into `conn`, writing its folder under `storage_root`.
+///
+/// Returns the created session. Bugs, captures and placeholder images are all
+/// created in one go; the session summary and `.session.json` are left to the
+/// caller so it can decide whether to include an AI overview.
+pub fn seed_demo_session(conn: &Connection, storage_root: &Path) -> Result<Session, String> {
+    let session_id = Uuid::new_v4().to_string();
+    let ended = Utc::now();
+    let started = ended - Duration::minutes(47);
+
+    let folder_name = format!("{}_{}", started.format("%Y-%m-%d"), &session_id[..8]);
+    let session_folder = storage_root.join(folder_name);
+    std::fs::create_dir_all(session_folder.join("_captures"))
+        .map_err(|e| format!("Failed to create demo session folder: {}", e))?;
+    std::fs::create_dir_all(session_folder.join("_unsorted"))
+        .map_err(|e| format!("Failed to create demo session folder: {}", e))?;
+
+    std::fs::write(session_folder.join("session-notes.md"), DEMO_SESSION_NOTES)
+        .map_err(|e| format!("Failed to write session-notes.md: {}", e))?;
+
+    let environment = serde_json::json!({
+        "os": "Windows 11 Pro 23H2",
+        "display_resolution": "2560x1440",
+        "dpi_scaling": "150%",
+        "ram": "32 GB",
+        "cpu": "Intel Core i7-12700H",
+        "foreground_app": "MeetingOS.exe",
+    });
+
+    let session = Session {
+        id: session_id.clone(),
+        started_at: started.to_rfc3339(),
+        ended_at: Some(ended.to_rfc3339()),
+        status: SessionStatus::Ended,
+        folder_path: session_folder.to_string_lossy().to_string(),
+        session_notes: Some(DEMO_SESSION_NOTES.to_string()),
+        environment_json: Some(environment.to_string()),
+        original_snip_path: None,
+        created_at: started.to_rfc3339(),
+        profile_id: None,
+        unlocked_at: None,
+        timezone: None,
+    };
+
+    SessionRepository::new(conn)
+        .create(&session)
+        .map_err(|e| format!("Failed to create demo session: {}", e))?;
+
+    let bug_repo = BugRepository::new(conn);
+    let capture_repo = CaptureRepository::new(conn);
+
+    for (index, demo) in DEMO_BUGS.iter().enumerate() {
+        let bug_number = index as i32 + 1;
+        let bug_folder = session_folder.join(format!("bug_{:03}", bug_number));
+        std::fs::create_dir_all(&bug_folder)
+            .map_err(|e| format!("Failed to create demo bug folder: {}", e))?;
+
+        std::fs::write(bug_folder.join("description.md"), demo.description)
+            .map_err(|e| format!("Failed to write description.md: {}", e))?;
+
+        let bug_created = started + Duration::minutes(5 + 10 * index as i64);
+        let bug = Bug {
+            id: Uuid::new_v4().to_string(),
+            session_id: session_id.clone(),
+            bug_number,
+            display_id: format!("BUG-{:03}", bug_number),
+            bug_type: demo.bug_type.clone(),
+            title: Some(demo.title.to_string()),
+            notes: Some(demo.notes.to_string()),
+            description: Some(demo.description.to_string()),
+            ai_description: None,
+            status: demo.status.clone(),
+            meeting_id: None,
+            software_version: Some("2.4.0-demo".to_string()),
+            console_parse_json: None,
+            metadata_json: None,
+            custom_metadata: None,
+            folder_path: bug_folder.to_string_lossy().to_string(),
+            created_at: bug_created.to_rfc3339(),
+            updated_at: bug_created.to_rfc3339(),
+            external_ticket_id: None,
+            external_ticket_key: None,
+            external_ticket_url: None,
+            external_status: None,
+            external_status_category: None,
+        };
+
+        bug_repo
+            .create(&bug)
+            .map_err(|e| format!("Failed to create demo bug: {}", e))?;
+
+        for capture_number in 1..=demo.capture_count {
+            let file_name = format!("capture-{:03}.png", capture_number);
+            let file_path = bug_folder.join(&file_name);
+            let png_bytes = placeholder_png(demo.color, capture_number)?;
+            std::fs::write(&file_path, &png_bytes)
+                .map_err(|e| format!("Failed to write placeholder capture: {}", e))?;
+
+            let capture = Capture {
+                id: Uuid::new_v4().to_string(),
+                bug_id: Some(bug.id.clone()),
+                session_id: session_id.clone(),
+                file_name,
+                file_path: file_path.to_string_lossy().to_string(),
+                file_type: CaptureType::Screenshot,
+                annotated_path: None,
+                file_size_bytes: Some(png_bytes.len() as i64),
+                is_console_capture: false,
+                parsed_content: None,
+                created_at: (bug_created + Duration::seconds(30 * capture_number as i64)).to_rfc3339(),
+                edited_at: None,
+                media_link: None,
+                video_duration_ms: None,
+                video_width: None,
+                video_height: None,
+                video_codec: None,
+                derived_from: None,
+                frame_timestamp_ms: None,
+                source_metadata: None,
+                alt_text: None,
+            };
+
+            capture_repo
+                .create(&capture)
+                .map_err(|e| format!("Failed to create demo capture: {}", e))?;
+        }
+    }
+
+    // One unsorted capture so the "assign to bug" flow can be demoed too.
+    let unsorted_path = session_folder.join("_unsorted").join("capture-001.png");
+    let png_bytes = placeholder_png([128, 128, 128], 1)?;
+    std::fs::write(&unsorted_path, &png_bytes)
+        .map_err(|e| format!("Failed to write placeholder capture: {}", e))?;
+
+    capture_repo
+        .create(&Capture {
+            id: Uuid::new_v4().to_string(),
+            bug_id: None,
+            session_id: session_id.clone(),
+            file_name: "capture-001.png".to_string(),
+            file_path: unsorted_path.to_string_lossy().to_string(),
+            file_type: CaptureType::Screenshot,
+            annotated_path: None,
+            file_size_bytes: Some(png_bytes.len() as i64),
+            is_console_capture: false,
+            parsed_content: None,
+            created_at: (ended - Duration::minutes(2)).to_rfc3339(),
+            edited_at: None,
+            media_link: None,
+            video_duration_ms: None,
+            video_width: None,
+            video_height: None,
+            video_codec: None,
+            derived_from: None,
+            frame_timestamp_ms: None,
+            source_metadata: None,
+            alt_text: None,
+        })
+        .map_err(|e| format!("Failed to create demo capture: {}", e))?;
+
+    Ok(session)
+}
+
+/// Generate a placeholder screenshot: a light "window" with a coloured title
+/// bar and `variant` content blocks, so consecutive captures look different.
+fn placeholder_png(color: [u8; 3], variant: u32) -> Result<Vec<u8>, String> {
+    let (w, h) = (PLACEHOLDER_WIDTH, PLACEHOLDER_HEIGHT);
+    let mut rgba = Vec::with_capacity((w * h * 4) as usize);
+
+    for y in 0..h {
+        for x in 0..w {
+            let pixel: [u8; 3] = if y < 40 {
+                color
+            } else if block_contains(x, y, variant) {
+                [color[0] / 2 + 64, color[1] / 2 + 64, color[2] / 2 + 64]
+            } else {
+                [245, 245, 245]
+            };
+            rgba.extend_from_slice(&[pixel[0], pixel[1], pixel[2], 255]);
+        }
+    }
+
+    encode_png(&rgba, w, h)
+}
+
+/// Whether (x, y) falls inside one of the `variant` content blocks.
+fn block_contains(x: u32, y: u32, variant: u32) -> bool {
+    (0..variant).any(|i| {
+        let top = 70 + i * 90;
+        (40..PLACEHOLDER_WIDTH - 40).contains(&x) && (top..top + 60).contains(&y)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    #[test]
+    fn test_seed_demo_session_creates_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+
+        let session = seed_demo_session(conn, dir.path()).unwrap();
+        assert_eq!(session.status, SessionStatus::Ended);
+
+        let bugs = BugRepository::new(conn).list_by_session(&session.id).unwrap();
+        assert_eq!(bugs.len(), DEMO_BUGS.len());
+        assert_eq!(bugs[0].display_id, "BUG-001");
+
+        let captures = CaptureRepository::new(conn).list_by_session(&session.id).unwrap();
+        let expected: u32 = DEMO_BUGS.iter().map(|b| b.capture_count).sum::<u32>() + 1;
+        assert_eq!(captures.len() as u32, expected);
+
+        let unsorted = CaptureRepository::new(conn).list_unsorted(&session.id).unwrap();
+        assert_eq!(unsorted.len(), 1);
+    }
+
+    #[test]
+    fn test_seed_demo_session_writes_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+
+        let session = seed_demo_session(conn, dir.path()).unwrap();
+        let folder = Path::new(&session.folder_path);
+
+        assert!(folder.join("session-notes.md").exists());
+        assert!(folder.join("bug_001").join("description.md").exists());
+
+        for capture in CaptureRepository::new(conn).list_by_session(&session.id).unwrap() {
+            let bytes = std::fs::read(&capture.file_path).unwrap();
+            assert_eq!(&bytes[..8], &[137, 80, 78, 71, 13, 10, 26, 10]);
+            assert_eq!(capture.file_size_bytes, Some(bytes.len() as i64));
+        }
+    }
+
+    #[test]
+    fn test_seed_demo_session_twice_creates_distinct_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+
+        let first = seed_demo_session(conn, dir.path()).unwrap();
+        let second = seed_demo_session(conn, dir.path()).unwrap();
+        assert_ne!(first.id, second.id);
+        assert_ne!(first.folder_path, second.folder_path);
+    }
+
+    #[test]
+    fn test_placeholder_png_variants_differ() {
+        let a = placeholder_png([10, 20, 30], 1).unwrap();
+        let b = placeholder_png([10, 20, 30], 2).unwrap();
+        assert_ne!(a, b);
+    }
+}
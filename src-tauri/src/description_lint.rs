@@ -0,0 +1,368 @@
+//! Spelling and terminology checks for bug descriptions.
+//!
+//! When enabled in the `lint.descriptions` setting, descriptions are checked
+//! while `tickets-ready.md` is written and when a ticket is previewed. Words
+//! are flagged if they are a common misspelling or, with a local word list
+//! configured (one word per line, e.g. `/usr/share/dict/words` or a hunspell
+//! `.dic` file), if the list does not know them. The team glossary marks terms
+//! that must not be used or that need clarifying. The result is a list of
+//! warnings; nothing is ever blocked.
+//!
+//! Code blocks, inline code, URLs, paths, acronyms and identifiers such as
+//! `camelCase` or `snake_case` are not spell-checked.
+
+use std::collections::HashSet;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Settings key holding [`LintSettings`] as JSON.
+pub const LINT_KEY: &str = "lint.descriptions";
+
+/// Misspellings flagged even without a word list, with their correction.
+const COMMON_MISSPELLINGS: &[(&str, &str)] = &[
+    ("accross", "across"),
+    ("acheive", "achieve"),
+    ("adress", "address"),
+    ("begining", "beginning"),
+    ("beleive", "believe"),
+    ("calender", "calendar"),
+    ("definately", "definitely"),
+    ("dissapear", "disappear"),
+    ("dissapears", "disappears"),
+    ("enviroment", "environment"),
+    ("existant", "existent"),
+    ("occured", "occurred"),
+    ("occurence", "occurrence"),
+    ("occurrs", "occurs"),
+    ("recieve", "receive"),
+    ("recieved", "received"),
+    ("refered", "referred"),
+    ("reproducable", "reproducible"),
+    ("seperate", "separate"),
+    ("succesful", "successful"),
+    ("succesfully", "successfully"),
+    ("teh", "the"),
+    ("untill", "until"),
+    ("wierd", "weird"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TermKind {
+    /// Must not appear in a description
+    Banned,
+    /// Allowed, but too vague on its own
+    Ambiguous,
+}
+
+/// A glossary entry. `term` may be several words and matches case-insensitively.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlossaryTerm {
+    pub term: String,
+    pub kind: TermKind,
+    #[serde(default)]
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LintSettings {
+    pub enabled: bool,
+    /// Word list used to find misspellings beyond the common ones
+    pub dictionary_path: Option<String>,
+    /// Product names and jargon that are spelled correctly
+    pub custom_words: Vec<String>,
+    pub glossary: Vec<GlossaryTerm>,
+}
+
+impl LintSettings {
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(LINT_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(LINT_KEY, &json)
+            .map_err(|e| format!("Failed to save lint settings: {}", e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(path) = &self.dictionary_path {
+            if !std::path::Path::new(path).is_file() {
+                return Err(format!("Dictionary file not found: {}", path));
+            }
+        }
+        if self.glossary.iter().any(|t| t.term.trim().is_empty()) {
+            return Err("Glossary terms cannot be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintKind {
+    Misspelling,
+    Banned,
+    Ambiguous,
+}
+
+/// One flagged word or term, reported once per description at its first line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintWarning {
+    pub kind: LintKind,
+    /// The text as written
+    pub word: String,
+    /// 1-based line in the description
+    pub line: usize,
+    pub suggestion: Option<String>,
+}
+
+/// Warnings for one bug of a session export.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BugLintWarnings {
+    pub bug_number: i32,
+    pub warnings: Vec<LintWarning>,
+}
+
+/// Checks descriptions against [`LintSettings`], with the word list loaded once.
+#[derive(Default)]
+pub struct Linter {
+    enabled: bool,
+    dictionary: Option<HashSet<String>>,
+    custom_words: HashSet<String>,
+    glossary: Vec<GlossaryTerm>,
+}
+
+impl Linter {
+    /// An unreadable word list is reported and skipped rather than failing
+    /// the export it runs in.
+    pub fn new(settings: &LintSettings) -> Self {
+        let dictionary = settings
+            .dictionary_path
+            .as_ref()
+            .filter(|_| settings.enabled)
+            .and_then(|path| match std::fs::read_to_string(path) {
+                Ok(text) => Some(parse_dictionary(&text)),
+                Err(e) => {
+                    eprintln!("Warning: failed to read dictionary {}: {}", path, e);
+                    None
+                }
+            });
+        Self {
+            enabled: settings.enabled,
+            dictionary,
+            custom_words: settings.custom_words.iter().map(|w| w.to_lowercase()).collect(),
+            glossary: settings.glossary.clone(),
+        }
+    }
+
+    pub fn load(conn: &Connection) -> Self {
+        Self::new(&LintSettings::load(conn))
+    }
+
+    pub fn lint(&self, text: &str) -> Vec<LintWarning> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        let mut warnings: Vec<LintWarning> = Vec::new();
+        let mut seen: HashSet<(LintKind, String)> = HashSet::new();
+        let mut push = |warning: LintWarning| {
+            if seen.insert((warning.kind, warning.word.to_lowercase())) {
+                warnings.push(warning);
+            }
+        };
+
+        let mut in_code_block = false;
+        for (index, line) in text.lines().enumerate() {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+            if in_code_block {
+                continue;
+            }
+            let prose = strip_inline_code(line);
+            let lower = prose.to_lowercase();
+
+            for term in &self.glossary {
+                let needle = term.term.trim().to_lowercase();
+                if let Some(start) = find_term(&lower, &needle) {
+                    push(LintWarning {
+                        kind: match term.kind {
+                            TermKind::Banned => LintKind::Banned,
+                            TermKind::Ambiguous => LintKind::Ambiguous,
+                        },
+                        word: prose.get(start..start + needle.len()).unwrap_or(&needle).to_string(),
+                        line: index + 1,
+                        suggestion: term.suggestion.clone(),
+                    });
+                }
+            }
+
+            for word in words(&prose) {
+                if let Some(suggestion) = self.misspelling(word) {
+                    push(LintWarning {
+                        kind: LintKind::Misspelling,
+                        word: word.to_string(),
+                        line: index + 1,
+                        suggestion,
+                    });
+                }
+            }
+        }
+        warnings
+    }
+
+    /// `Some(correction)` when `word` looks misspelled.
+    fn misspelling(&self, word: &str) -> Option<Option<String>> {
+        let lower = word.to_lowercase();
+        if let Some((_, correct)) = COMMON_MISSPELLINGS.iter().find(|(wrong, _)| *wrong == lower) {
+            return Some(Some(correct.to_string()));
+        }
+        let dictionary = self.dictionary.as_ref()?;
+        let stem = lower.strip_suffix("'s").unwrap_or(&lower);
+        let known = |w: &str| dictionary.contains(w) || self.custom_words.contains(w);
+        if known(stem) || stem.strip_suffix('s').is_some_and(known) {
+            return None;
+        }
+        Some(None)
+    }
+}
+
+/// Words of a word list: one per line, hunspell affix flags after `/` and the
+/// leading entry count are ignored.
+fn parse_dictionary(text: &str) -> HashSet<String> {
+    text.lines()
+        .filter_map(|line| line.split('/').next())
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| !word.is_empty() && !word.chars().all(|c| c.is_ascii_digit()))
+        .collect()
+}
+
+/// `line` with `inline code` spans blanked out, keeping byte offsets.
+pub(crate) fn strip_inline_code(line: &str) -> String {
+    let mut in_code = false;
+    line.chars()
+        .map(|c| {
+            if c == '`' {
+                in_code = !in_code;
+                ' '
+            } else if in_code {
+                // Same byte length, so glossary offsets stay valid
+                if c.is_ascii() { ' ' } else { c }
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Byte offset of the first whole-word occurrence of `needle` in `haystack`.
+pub(crate) fn find_term(haystack: &str, needle: &str) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    haystack.match_indices(needle).map(|(start, _)| start).find(|&start| {
+        let end = start + needle.len();
+        !haystack[..start].chars().next_back().is_some_and(is_word)
+            && !haystack[end..].chars().next().is_some_and(is_word)
+    })
+}
+
+/// Prose words of a line worth spell-checking.
+fn words(line: &str) -> impl Iterator<Item = &str> {
+    line.split_whitespace()
+        .filter(|token| !token.contains("://") && !token.starts_with("www.") && !token.contains(['/', '\\', '@', '_']))
+        .flat_map(|token| token.split(|c: char| !(c.is_alphabetic() || c == '\'')))
+        .map(|word| word.trim_matches('\''))
+        .filter(|word| {
+            let mut chars = word.chars();
+            chars.next().is_some()
+                && word.chars().count() > 1
+                // Acronyms and camelCase identifiers
+                && !chars.any(char::is_uppercase)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glossary() -> Vec<GlossaryTerm> {
+        vec![
+            GlossaryTerm { term: "crashes".to_string(), kind: TermKind::Ambiguous, suggestion: Some("Describe what happens: freeze, error dialog or exit".to_string()) },
+            GlossaryTerm { term: "user error".to_string(), kind: TermKind::Banned, suggestion: None },
+        ]
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        assert_eq!(LintSettings::load(&conn), LintSettings::default());
+        assert!(Linter::load(&conn).lint("Teh app recieved nothing").is_empty());
+
+        let settings = LintSettings { enabled: true, glossary: glossary(), ..Default::default() };
+        settings.save(&conn).unwrap();
+        assert_eq!(LintSettings::load(&conn), settings);
+        assert!(LintSettings { dictionary_path: Some("/nonexistent/words".to_string()), ..Default::default() }
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_flags_misspellings_and_glossary_terms() {
+        let linter = Linter::new(&LintSettings { enabled: true, glossary: glossary(), ..Default::default() });
+        let text = "## Steps\nOpen the editor, teh app Crashes.\nLooks like User Error?\n\n```\nteh crashes\n```\nRun `recieve --user error` and the App crashes again";
+
+        let warnings = linter.lint(text);
+        assert_eq!(
+            warnings,
+            vec![
+                LintWarning {
+                    kind: LintKind::Ambiguous,
+                    word: "Crashes".to_string(),
+                    line: 2,
+                    suggestion: Some("Describe what happens: freeze, error dialog or exit".to_string()),
+                },
+                LintWarning { kind: LintKind::Misspelling, word: "teh".to_string(), line: 2, suggestion: Some("the".to_string()) },
+                LintWarning { kind: LintKind::Banned, word: "User Error".to_string(), line: 3, suggestion: None },
+            ],
+            "code is skipped and each term is reported once"
+        );
+        // Whole words only
+        assert!(linter.lint("It never crashesx").is_empty());
+    }
+
+    #[test]
+    fn test_dictionary_words() {
+        let dir = tempfile::tempdir().unwrap();
+        let dic = dir.path().join("en.dic");
+        std::fs::write(&dic, "6\nthe/S\nbutton\nis\nhidden\nby\ntoolbar/MS\n").unwrap();
+        let linter = Linter::new(&LintSettings {
+            enabled: true,
+            dictionary_path: Some(dic.to_string_lossy().to_string()),
+            custom_words: vec!["Unbroken".to_string()],
+            ..Default::default()
+        });
+
+        let warnings = linter.lint("The buton is hidden by the toolbars in Unbroken's onSave handler (see QA_LOG, https://x.io/a).");
+        let flagged: Vec<&str> = warnings.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(flagged, vec!["buton", "in", "handler", "see"]);
+        assert!(warnings.iter().all(|w| w.kind == LintKind::Misspelling && w.suggestion.is_none()));
+    }
+}
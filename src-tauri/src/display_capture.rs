@@ -0,0 +1,138 @@
+//! Screenshots of every monitor in one action.
+//!
+//! Bugs that span windows on several displays need all of them in the
+//! report. `capture_all_displays` grabs each monitor of the current
+//! [`DisplaySnapshot`](crate::display_info::DisplaySnapshot) and writes either
+//! one PNG per monitor or a single composite laid out like the desktop. The
+//! files go to the session's `_captures/` folder, so the capture watcher
+//! routes them to the active bug like any other capture. The cursor overlay
+//! is burned into each monitor at that monitor's scaling. Grabbing the screen
+//! is Windows-only; the layout is platform independent.
+
+use std::path::{Path, PathBuf};
+
+use image::{GenericImage, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::cursor_overlay::CursorOverlay;
+use crate::display_info::MonitorInfo;
+use crate::window_capture::grab_screen_area;
+
+/// Fill for the parts of the composite no monitor covers.
+const BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// What `capture_all_displays` writes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DisplayCaptureMode {
+    /// One PNG per monitor
+    #[default]
+    PerMonitor,
+    /// One PNG with all monitors at their desktop positions
+    Composite,
+}
+
+/// Bounds of the desktop spanned by `monitors`: `(left, top, width, height)`.
+pub fn desktop_bounds(monitors: &[MonitorInfo]) -> Option<(i32, i32, u32, u32)> {
+    let left = monitors.iter().map(|m| m.x).min()?;
+    let top = monitors.iter().map(|m| m.y).min()?;
+    let right = monitors.iter().map(|m| m.x + m.width as i32).max()?;
+    let bottom = monitors.iter().map(|m| m.y + m.height as i32).max()?;
+    Some((left, top, (right - left) as u32, (bottom - top) as u32))
+}
+
+/// Place each monitor's image at its desktop position.
+pub fn stitch(shots: &[(MonitorInfo, RgbaImage)]) -> Result<RgbaImage, String> {
+    let monitors: Vec<MonitorInfo> = shots.iter().map(|(m, _)| m.clone()).collect();
+    let (left, top, width, height) = desktop_bounds(&monitors).ok_or("No monitors to stitch")?;
+    let mut composite = RgbaImage::from_pixel(width, height, BACKGROUND);
+    for (monitor, image) in shots {
+        composite
+            .copy_from(image, (monitor.x - left) as u32, (monitor.y - top) as u32)
+            .map_err(|e| format!("Failed to place monitor image: {}", e))?;
+    }
+    Ok(composite)
+}
+
+fn save_png(image: &RgbaImage, path: &Path) -> Result<(), String> {
+    image
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Grab every monitor, burn in `overlay` and write the PNGs into `dest_dir`.
+/// Returns the paths in monitor order (a single path in composite mode).
+pub fn capture_all_displays(
+    monitors: &[MonitorInfo],
+    mode: DisplayCaptureMode,
+    overlay: &CursorOverlay,
+    dest_dir: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    if monitors.is_empty() {
+        return Err("No monitors found".to_string());
+    }
+    // Grab everything before writing, so the files show the same moment
+    let shots = monitors
+        .iter()
+        .map(|m| {
+            let mut image = grab_screen_area(m.x, m.y, m.width as i32, m.height as i32)?;
+            CursorOverlay { scale: m.scale_factor, ..overlay.clone() }.burn_in(&mut image, (m.x, m.y));
+            Ok((m.clone(), image))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    std::fs::create_dir_all(dest_dir).map_err(|e| format!("Cannot create {:?}: {}", dest_dir, e))?;
+    let id = Uuid::new_v4();
+    match mode {
+        DisplayCaptureMode::Composite => {
+            let path = dest_dir.join(format!("displays-{}.png", id));
+            save_png(&stitch(&shots)?, &path)?;
+            Ok(vec![path])
+        }
+        DisplayCaptureMode::PerMonitor => shots
+            .iter()
+            .enumerate()
+            .map(|(i, (_, image))| {
+                let path = dest_dir.join(format!("display-{}-{}.png", i + 1, id));
+                save_png(image, &path).map(|_| path)
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(x: i32, y: i32, width: u32, height: u32) -> MonitorInfo {
+        MonitorInfo { name: None, x, y, width, height, scale_factor: 1.0, primary: x == 0 && y == 0 }
+    }
+
+    #[test]
+    fn test_desktop_bounds_include_negative_positions() {
+        // Secondary monitor left of and slightly above the primary
+        let monitors = [monitor(0, 0, 1920, 1080), monitor(-1280, -200, 1280, 1024)];
+        assert_eq!(desktop_bounds(&monitors), Some((-1280, -200, 3200, 1280)));
+        assert_eq!(desktop_bounds(&[]), None);
+    }
+
+    #[test]
+    fn test_stitch_places_monitors_and_fills_gaps() {
+        let red = Rgba([255, 0, 0, 255]);
+        let blue = Rgba([0, 0, 255, 255]);
+        let shots = [
+            (monitor(0, 0, 4, 2), RgbaImage::from_pixel(4, 2, red)),
+            (monitor(-2, 1, 2, 3), RgbaImage::from_pixel(2, 3, blue)),
+        ];
+
+        let composite = stitch(&shots).unwrap();
+        assert_eq!(composite.dimensions(), (6, 4));
+        assert_eq!(*composite.get_pixel(2, 0), red);
+        assert_eq!(*composite.get_pixel(0, 1), blue);
+        assert_eq!(*composite.get_pixel(1, 3), blue);
+        // Below the primary, right of the secondary
+        assert_eq!(*composite.get_pixel(4, 3), BACKGROUND);
+        assert_eq!(*composite.get_pixel(0, 0), BACKGROUND);
+    }
+}
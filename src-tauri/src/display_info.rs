@@ -0,0 +1,194 @@
+//! Monitor layout and DPI scaling recorded with each capture.
+//!
+//! Many reported bugs are scaling issues, so the capture watcher takes a
+//! [`DisplaySnapshot`] when a new file is detected and stores it in the
+//! capture's `source_metadata`. The monitor under the cursor at that moment
+//! is taken as the one the capture was made on. Bug reports fill the
+//! template's display resolution and DPI scaling from these snapshots when
+//! the session environment does not provide them.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+use crate::capture_routing::CaptureSource;
+use crate::database::Capture;
+
+/// One monitor, in physical pixels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+impl MonitorInfo {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x as f64
+            && y >= self.y as f64
+            && x < self.x as f64 + self.width as f64
+            && y < self.y as f64 + self.height as f64
+    }
+
+    /// e.g. "2560x1440"
+    pub fn resolution(&self) -> String {
+        format!("{}x{}", self.width, self.height)
+    }
+
+    /// e.g. "150%"
+    pub fn scaling(&self) -> String {
+        format!("{}%", (self.scale_factor * 100.0).round() as i64)
+    }
+}
+
+/// Monitor layout when a capture was detected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplaySnapshot {
+    pub monitors: Vec<MonitorInfo>,
+    /// Index into `monitors` of the monitor under the cursor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_monitor: Option<usize>,
+}
+
+impl DisplaySnapshot {
+    pub fn new(monitors: Vec<MonitorInfo>, cursor: Option<(f64, f64)>) -> Self {
+        let active_monitor = cursor.and_then(|(x, y)| monitors.iter().position(|m| m.contains(x, y)));
+        Self { monitors, active_monitor }
+    }
+
+    /// The monitor the capture was made on: the one under the cursor, else
+    /// the primary, else the first.
+    pub fn capture_monitor(&self) -> Option<&MonitorInfo> {
+        self.active_monitor
+            .and_then(|i| self.monitors.get(i))
+            .or_else(|| self.monitors.iter().find(|m| m.primary))
+            .or_else(|| self.monitors.first())
+    }
+}
+
+/// Current monitor layout of the running app. None when no monitor can be
+/// queried (e.g. a headless session).
+pub fn snapshot<R: Runtime>(app: &AppHandle<R>) -> Option<DisplaySnapshot> {
+    let primary = app.primary_monitor().ok().flatten().map(|m| *m.position());
+    let monitors: Vec<MonitorInfo> = app
+        .available_monitors()
+        .ok()?
+        .iter()
+        .map(|m| MonitorInfo {
+            name: m.name().cloned(),
+            x: m.position().x,
+            y: m.position().y,
+            width: m.size().width,
+            height: m.size().height,
+            scale_factor: m.scale_factor(),
+            primary: primary == Some(*m.position()),
+        })
+        .collect();
+    if monitors.is_empty() {
+        return None;
+    }
+    let cursor = app.cursor_position().ok().map(|p| (p.x, p.y));
+    Some(DisplaySnapshot::new(monitors, cursor))
+}
+
+/// The display snapshot stored with a capture, if any.
+pub fn for_capture(capture: &Capture) -> Option<DisplaySnapshot> {
+    let json = capture.source_metadata.as_deref()?;
+    serde_json::from_str::<CaptureSource>(json).ok()?.display
+}
+
+/// Display resolution and DPI scaling of the monitors `captures` were made
+/// on, each as a comma-separated list of distinct values in capture order.
+/// None when no capture has a snapshot.
+pub fn describe_captures(captures: &[Capture]) -> Option<(String, String)> {
+    let mut resolutions: Vec<String> = Vec::new();
+    let mut scalings: Vec<String> = Vec::new();
+    for snapshot in captures.iter().filter_map(for_capture) {
+        let Some(monitor) = snapshot.capture_monitor() else {
+            continue;
+        };
+        let (resolution, scaling) = (monitor.resolution(), monitor.scaling());
+        if !resolutions.contains(&resolution) {
+            resolutions.push(resolution);
+        }
+        if !scalings.contains(&scaling) {
+            scalings.push(scaling);
+        }
+    }
+    if resolutions.is_empty() {
+        return None;
+    }
+    Some((resolutions.join(", "), scalings.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_routing::Routing;
+    use crate::database::CaptureType;
+
+    fn monitor(x: i32, width: u32, height: u32, scale_factor: f64, primary: bool) -> MonitorInfo {
+        MonitorInfo { name: None, x, y: 0, width, height, scale_factor, primary }
+    }
+
+    fn capture_with(display: Option<DisplaySnapshot>) -> Capture {
+        let source = CaptureSource {
+            source: "capture_watcher".to_string(),
+            routing: Routing::ActiveBug,
+            ms_after_bug_end: None,
+            trigger: None,
+            ms_after_trigger: None,
+            trigger_bug_id: None,
+            display,
+            foreground: None,
+        };
+        Capture {
+            id: "c-1".to_string(),
+            bug_id: Some("b-1".to_string()),
+            session_id: "s-1".to_string(),
+            file_name: "capture-001.png".to_string(),
+            file_path: "/qa/capture-001.png".to_string(),
+            file_type: CaptureType::Screenshot,
+            created_at: "2024-01-01T10:00:00Z".to_string(),
+            source_metadata: Some(source.to_json()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_capture_monitor_follows_cursor() {
+        let monitors = vec![monitor(0, 1920, 1080, 1.0, true), monitor(1920, 2560, 1440, 1.5, false)];
+        let on_second = DisplaySnapshot::new(monitors.clone(), Some((2000.0, 500.0)));
+        assert_eq!(on_second.active_monitor, Some(1));
+        assert_eq!(on_second.capture_monitor().unwrap().scaling(), "150%");
+
+        // Cursor unknown or off screen: fall back to the primary monitor
+        let unknown = DisplaySnapshot::new(monitors, Some((-50.0, 0.0)));
+        assert_eq!(unknown.active_monitor, None);
+        assert_eq!(unknown.capture_monitor().unwrap().resolution(), "1920x1080");
+    }
+
+    #[test]
+    fn test_describe_captures_lists_distinct_values() {
+        let laptop = DisplaySnapshot::new(vec![monitor(0, 2880, 1800, 2.0, true)], None);
+        let external = DisplaySnapshot::new(vec![monitor(0, 1920, 1080, 1.25, true)], None);
+        let captures = vec![
+            capture_with(Some(laptop.clone())),
+            capture_with(None),
+            capture_with(Some(external)),
+            capture_with(Some(laptop)),
+        ];
+        assert_eq!(
+            describe_captures(&captures),
+            Some(("2880x1800, 1920x1080".to_string(), "200%, 125%".to_string()))
+        );
+        assert_eq!(describe_captures(&captures[1..2]), None);
+    }
+}
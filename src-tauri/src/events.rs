@@ -0,0 +1,908 @@
+//! Typed payloads for events emitted to the frontend.
+//!
+//! Each event has a payload struct here, and emitting code builds that struct
+//! rather than an ad-hoc `json!` value, so a renamed or retyped field shows up
+//! as a compile error or a failing round-trip test instead of a frontend
+//! listener silently reading `undefined`. Payloads serialize with camelCase
+//! keys.
+//!
+//! The schema is versioned by [`EVENT_SCHEMA_VERSION`], which the frontend
+//! can read with `get_event_schema`. Bump it whenever a payload changes in a
+//! way existing listeners cannot handle: removing or renaming a field,
+//! changing a field's type, or making an optional field required. Adding an
+//! optional field does not need a bump.
+//!
+//! | Event | Payload |
+//! |---|---|
+//! | `session:started` | [`SessionStarted`] |
+//! | `session:ended` | [`SessionEnded`] |
+//! | `session:resumed` | [`SessionResumed`] |
+//! | `session:environment-changed` | [`SessionEnvironmentChanged`] |
+//! | `bug:capture-started` | [`BugCaptureStarted`] |
+//! | `bug:capture-ended` | [`BugCaptureEnded`] |
+//! | `bug-status-changed` | [`BugStatusChanged`] |
+//! | `bug:auto-stopped` | [`BugAutoStopped`] |
+//! | `bug:split` | [`BugSplit`] |
+//! | `bug:promoted` | [`BugPromoted`] |
+//! | `bug:folded` | [`BugFolded`] |
+//! | `screenshot:captured` | [`ScreenshotCaptured`] |
+//! | `capture:edited` | [`CaptureEdited`] |
+//! | `capture:moved` | [`CaptureMoved`] |
+//! | `capture:unassigned` | [`CaptureUnassigned`] |
+//! | `capture:write-lost` | [`CaptureWriteLost`] |
+//! | `capture:file-detected` | [`CaptureFileDetected`] |
+//! | `capture:quota-exceeded` | [`CaptureQuotaExceeded`] |
+//! | `crash:dump-collected` | [`CrashDumpCollected`] |
+//! | `recording:started` | [`RecordingStarted`] |
+//! | `recording:progress` | [`RecordingProgress`] |
+//! | `recording:stopped` | [`RecordingStopped`] |
+//! | `ticketing:queued` | [`TicketingQueued`] |
+//! | `ticketing:sent` | [`TicketingSent`] |
+//! | `ticketing:failed` | [`TicketingFailed`] |
+//! | `deep-link:open-bug` | [`DeepLinkOpenBug`] |
+//! | `deep-link:open-session` | [`DeepLinkOpenSession`] |
+//! | `command:deprecated` | [`CommandDeprecated`] |
+//! | `settings:quick-toggle-changed` | [`QuickToggleChanged`] |
+//! | `staging:file-queued` | [`StagedFileQueued`] |
+//! | `staging:import-prompt` | [`StagedImportPrompt`] |
+//! | `session:post-processing` | [`PostProcessingEvent`] |
+//! | `viewer:opened` | [`ViewerOpened`] |
+//! | `tray-state-changed` | [`TrayStateChanged`] |
+//! | `session:export-progress` | [`ArchiveProgress`] |
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Runtime};
+
+use crate::post_session::PostProcessingEvent;
+use crate::session_archive::ArchiveProgress;
+
+/// Version of the event payload schema described in this module.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// A frontend event: its name and payload type.
+pub trait AppEvent: Serialize + DeserializeOwned {
+    const NAME: &'static str;
+}
+
+/// Emit a typed event to all windows.
+pub fn emit<R: Runtime, E: AppEvent>(emitter: &impl Emitter<R>, event: &E) -> tauri::Result<()> {
+    emitter.emit(E::NAME, event)
+}
+
+/// Event names and the schema version, as returned by `get_event_schema`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSchema {
+    pub version: u32,
+    pub events: Vec<String>,
+}
+
+impl EventSchema {
+    pub fn current() -> Self {
+        Self {
+            version: EVENT_SCHEMA_VERSION,
+            events: [
+                SessionStarted::NAME,
+                SessionEnded::NAME,
+                SessionResumed::NAME,
+                SessionEnvironmentChanged::NAME,
+                BugCaptureStarted::NAME,
+                BugCaptureEnded::NAME,
+                BugStatusChanged::NAME,
+                BugAutoStopped::NAME,
+                BugSplit::NAME,
+                BugPromoted::NAME,
+                BugFolded::NAME,
+                ScreenshotCaptured::NAME,
+                CaptureEdited::NAME,
+                CaptureMoved::NAME,
+                CaptureUnassigned::NAME,
+                CaptureWriteLost::NAME,
+                CaptureFileDetected::NAME,
+                CrashDumpCollected::NAME,
+                RecordingStarted::NAME,
+                RecordingProgress::NAME,
+                RecordingStopped::NAME,
+                TicketingQueued::NAME,
+                TicketingSent::NAME,
+                TicketingFailed::NAME,
+                DeepLinkOpenBug::NAME,
+                DeepLinkOpenSession::NAME,
+                CommandDeprecated::NAME,
+                QuickToggleChanged::NAME,
+                CaptureQuotaExceeded::NAME,
+                StagedFileQueued::NAME,
+                StagedImportPrompt::NAME,
+                PostProcessingEvent::NAME,
+                ViewerOpened::NAME,
+                TrayStateChanged::NAME,
+                ArchiveProgress::NAME,
+            ]
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        }
+    }
+}
+
+macro_rules! app_event {
+    ($name:literal, $ty:ident) => {
+        impl AppEvent for $ty {
+            const NAME: &'static str = $name;
+        }
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStarted {
+    pub session_id: String,
+    pub folder_path: String,
+    pub started_at: String,
+}
+app_event!("session:started", SessionStarted);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEnded {
+    pub session_id: String,
+    pub ended_at: String,
+}
+app_event!("session:ended", SessionEnded);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionResumed {
+    pub session_id: String,
+    pub folder_path: String,
+}
+app_event!("session:resumed", SessionResumed);
+
+/// The session's build, OS version or GPU driver differs from the previous
+/// session of the same profile (see `session_environment`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEnvironmentChanged {
+    pub session_id: String,
+    pub previous_session_id: String,
+    pub changes: Vec<crate::session_environment::EnvironmentChange>,
+}
+app_event!("session:environment-changed", SessionEnvironmentChanged);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BugCaptureStarted {
+    pub bug_id: String,
+    pub session_id: String,
+    pub bug_number: i32,
+    pub display_id: String,
+    pub folder_path: String,
+}
+app_event!("bug:capture-started", BugCaptureStarted);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BugCaptureEnded {
+    pub bug_id: String,
+    pub session_id: String,
+}
+app_event!("bug:capture-ended", BugCaptureEnded);
+
+/// Uses `id` rather than `bugId`, matching the bug store's other
+/// `bug-*` events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BugStatusChanged {
+    pub id: String,
+    pub status: String,
+}
+app_event!("bug-status-changed", BugStatusChanged);
+
+/// The active bug was ended after a period without activity (see
+/// `bug_auto_stop`). `undo_auto_stop` resumes it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BugAutoStopped {
+    pub bug_id: String,
+    pub session_id: String,
+    pub display_id: String,
+    pub idle_minutes: u32,
+}
+app_event!("bug:auto-stopped", BugAutoStopped);
+
+/// Captures were split off `source_bug_id` into the new bug `bug_id`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BugSplit {
+    pub source_bug_id: String,
+    pub bug_id: String,
+    pub session_id: String,
+    pub display_id: String,
+    pub capture_ids: Vec<String>,
+}
+app_event!("bug:split", BugSplit);
+
+/// A scratch bug was given a bug number (and moved to its `bug_NNN` folder).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BugPromoted {
+    pub bug_id: String,
+    pub session_id: String,
+    pub bug_number: i32,
+    pub display_id: String,
+    pub folder_path: String,
+}
+app_event!("bug:promoted", BugPromoted);
+
+/// A scratch bug was deleted after its captures moved to `_unsorted/`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BugFolded {
+    pub bug_id: String,
+    pub session_id: String,
+}
+app_event!("bug:folded", BugFolded);
+
+/// A new capture file. Captures taken outside a session (manual screenshot
+/// trigger) only carry the file path and timestamp.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotCaptured {
+    pub file_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_id: Option<String>,
+    /// Explicit `null` for unsorted captures
+    #[serde(default)]
+    pub bug_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Unix time in milliseconds
+    pub timestamp: i64,
+}
+app_event!("screenshot:captured", ScreenshotCaptured);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureEdited {
+    pub capture_id: String,
+    pub bug_id: Option<String>,
+    pub file_path: String,
+    pub edited_at: Option<String>,
+}
+app_event!("capture:edited", CaptureEdited);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureMoved {
+    pub capture_id: String,
+    pub bug_id: String,
+    pub file_path: String,
+}
+app_event!("capture:moved", CaptureMoved);
+
+/// A capture was moved out of its bug back to `_unsorted/`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureUnassigned {
+    pub capture_id: String,
+    pub previous_bug_id: String,
+    pub file_path: String,
+}
+app_event!("capture:unassigned", CaptureUnassigned);
+
+/// A capture file was saved but its database record could not be written,
+/// even after retries (see `capture_journal`). The frontend keeps a warning
+/// banner up until the user dismisses it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureWriteLost {
+    pub session_id: String,
+    pub capture_id: String,
+    pub file_path: String,
+    pub error: String,
+}
+app_event!("capture:write-lost", CaptureWriteLost);
+
+/// A capture file was detected and recorded. `replay_capture_events` sends
+/// the same event, rebuilt from the DB with `replayed` set, so a window that
+/// was hidden or reloaded can catch up without reloading every capture.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureFileDetected {
+    pub capture_id: String,
+    pub session_id: String,
+    /// Explicit `null` for unsorted captures
+    pub bug_id: Option<String>,
+    pub file_path: String,
+    pub file_type: crate::database::CaptureType,
+    pub created_at: String,
+    #[serde(default)]
+    pub replayed: bool,
+}
+app_event!("capture:file-detected", CaptureFileDetected);
+
+impl CaptureFileDetected {
+    pub fn from_capture(capture: &crate::database::Capture, replayed: bool) -> Self {
+        Self {
+            capture_id: capture.id.clone(),
+            session_id: capture.session_id.clone(),
+            bug_id: capture.bug_id.clone(),
+            file_path: capture.file_path.clone(),
+            file_type: capture.file_type.clone(),
+            created_at: capture.created_at.clone(),
+            replayed,
+        }
+    }
+}
+
+/// A crash dump of the watched process was copied into the session (see
+/// `crash_dumps`). `bug_id` is `null` when it went to `_unsorted/`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashDumpCollected {
+    pub session_id: String,
+    pub bug_id: Option<String>,
+    pub dump_path: String,
+    pub process: String,
+    pub faulting_module: Option<String>,
+    pub exception_code: Option<String>,
+}
+app_event!("crash:dump-collected", CrashDumpCollected);
+
+/// A screen recording started writing `file_path`. `bug_id` is `null` when it
+/// goes to `_unsorted/`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingStarted {
+    pub recording_id: String,
+    pub session_id: String,
+    pub bug_id: Option<String>,
+    pub file_path: String,
+}
+app_event!("recording:started", RecordingStarted);
+
+/// Emitted about once a second while recording, for the recording timer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingProgress {
+    pub recording_id: String,
+    pub duration_ms: i64,
+    /// Bytes written so far, when the file can be read
+    pub file_size_bytes: Option<u64>,
+}
+app_event!("recording:progress", RecordingProgress);
+
+/// A screen recording ended. `capture_id` is the new capture, or `null` with
+/// `error` set when the file could not be finished or recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingStopped {
+    pub recording_id: String,
+    pub file_path: String,
+    pub duration_ms: i64,
+    pub capture_id: Option<String>,
+    pub error: Option<String>,
+}
+app_event!("recording:stopped", RecordingStopped);
+
+/// A ticket could not be created and waits in the retry queue until
+/// `next_attempt_at`. Emitted when it is queued and after each failed retry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketingQueued {
+    pub queue_id: String,
+    pub bug_id: Option<String>,
+    pub title: String,
+    pub attempts: i64,
+    pub next_attempt_at: String,
+    pub error: Option<String>,
+}
+app_event!("ticketing:queued", TicketingQueued);
+
+/// A queued ticket was created on retry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketingSent {
+    pub queue_id: String,
+    pub bug_id: Option<String>,
+    pub identifier: String,
+    pub url: String,
+}
+app_event!("ticketing:sent", TicketingSent);
+
+/// The queue gave up on a ticket; it stays listed until retried or discarded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketingFailed {
+    pub queue_id: String,
+    pub bug_id: Option<String>,
+    pub title: String,
+    pub attempts: i64,
+    pub error: String,
+}
+app_event!("ticketing:failed", TicketingFailed);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkOpenBug {
+    pub bug_id: String,
+    pub session_id: String,
+}
+app_event!("deep-link:open-bug", DeepLinkOpenBug);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkOpenSession {
+    pub session_id: String,
+}
+app_event!("deep-link:open-session", DeepLinkOpenSession);
+
+/// A deprecated command name was invoked (see `command_registry`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandDeprecated {
+    pub command: String,
+    pub replacement: Option<String>,
+    pub since: String,
+}
+app_event!("command:deprecated", CommandDeprecated);
+
+/// A setting was switched from the tray menu (see `quick_toggles`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickToggleChanged {
+    pub key: String,
+    pub enabled: bool,
+}
+app_event!("settings:quick-toggle-changed", QuickToggleChanged);
+
+/// A new capture took its bug (`bug_id` set) or session over a soft limit
+/// (see `capture_quota`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureQuotaExceeded {
+    pub session_id: String,
+    pub bug_id: Option<String>,
+    pub captures: u64,
+    pub total_bytes: u64,
+    pub message: String,
+}
+app_event!("capture:quota-exceeded", CaptureQuotaExceeded);
+
+/// A media file landed in the staging folder (see `staging_watcher`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedFileQueued {
+    #[serde(flatten)]
+    pub file: crate::staging_watcher::StagedFile,
+}
+app_event!("staging:file-queued", StagedFileQueued);
+
+/// A session started while files wait in the staging folder; the frontend
+/// asks whether to import them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedImportPrompt {
+    pub session_id: String,
+    pub files: Vec<crate::staging_watcher::StagedFile>,
+}
+app_event!("staging:import-prompt", StagedImportPrompt);
+
+// Progress of the post-session pipeline, defined in `post_session`
+app_event!("session:post-processing", PostProcessingEvent);
+
+/// An archive was opened in the read-only viewer; `get_viewer_session`
+/// returns its contents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewerOpened {
+    pub archive_path: String,
+    pub session_id: String,
+    pub bug_count: usize,
+}
+app_event!("viewer:opened", ViewerOpened);
+
+/// The tray switched state: `idle`, `active`, `bug` or `review`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayStateChanged {
+    pub state: String,
+}
+app_event!("tray-state-changed", TrayStateChanged);
+
+// Progress of a `.qacap` export, defined in `session_archive`
+app_event!("session:export-progress", ArchiveProgress);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Serialize `event`, compare with the wire format the frontend expects
+    /// and parse it back.
+    fn assert_round_trip<E: AppEvent + PartialEq + std::fmt::Debug>(event: E, expected: serde_json::Value) {
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value, expected, "{} payload changed", E::NAME);
+        assert_eq!(serde_json::from_value::<E>(value).unwrap(), event);
+    }
+
+    #[test]
+    fn test_session_and_bug_payloads() {
+        assert_round_trip(
+            SessionStarted {
+                session_id: "s-1".to_string(),
+                folder_path: "/qa/s-1".to_string(),
+                started_at: "2024-01-01T10:00:00Z".to_string(),
+            },
+            json!({ "sessionId": "s-1", "folderPath": "/qa/s-1", "startedAt": "2024-01-01T10:00:00Z" }),
+        );
+        assert_round_trip(
+            SessionEnded { session_id: "s-1".to_string(), ended_at: "2024-01-01T11:00:00Z".to_string() },
+            json!({ "sessionId": "s-1", "endedAt": "2024-01-01T11:00:00Z" }),
+        );
+        assert_round_trip(
+            SessionResumed { session_id: "s-1".to_string(), folder_path: "/qa/s-1".to_string() },
+            json!({ "sessionId": "s-1", "folderPath": "/qa/s-1" }),
+        );
+        assert_round_trip(
+            SessionEnvironmentChanged {
+                session_id: "s-2".to_string(),
+                previous_session_id: "s-1".to_string(),
+                changes: vec![crate::session_environment::EnvironmentChange {
+                    key: "gpu_driver".to_string(),
+                    label: "GPU driver".to_string(),
+                    previous: "30.0".to_string(),
+                    current: "31.0".to_string(),
+                }],
+            },
+            json!({
+                "sessionId": "s-2",
+                "previousSessionId": "s-1",
+                "changes": [{ "key": "gpu_driver", "label": "GPU driver", "previous": "30.0", "current": "31.0" }]
+            }),
+        );
+        assert_round_trip(
+            BugCaptureStarted {
+                bug_id: "b-1".to_string(),
+                session_id: "s-1".to_string(),
+                bug_number: 3,
+                display_id: "Bug-03".to_string(),
+                folder_path: "/qa/s-1/bug_003".to_string(),
+            },
+            json!({
+                "bugId": "b-1",
+                "sessionId": "s-1",
+                "bugNumber": 3,
+                "displayId": "Bug-03",
+                "folderPath": "/qa/s-1/bug_003"
+            }),
+        );
+        assert_round_trip(
+            BugCaptureEnded { bug_id: "b-1".to_string(), session_id: "s-1".to_string() },
+            json!({ "bugId": "b-1", "sessionId": "s-1" }),
+        );
+        assert_round_trip(
+            BugStatusChanged { id: "b-1".to_string(), status: "capturing".to_string() },
+            json!({ "id": "b-1", "status": "capturing" }),
+        );
+        assert_round_trip(
+            BugAutoStopped {
+                bug_id: "b-1".to_string(),
+                session_id: "s-1".to_string(),
+                display_id: "Bug-01".to_string(),
+                idle_minutes: 15,
+            },
+            json!({ "bugId": "b-1", "sessionId": "s-1", "displayId": "Bug-01", "idleMinutes": 15 }),
+        );
+        assert_round_trip(
+            BugSplit {
+                source_bug_id: "b-1".to_string(),
+                bug_id: "b-2".to_string(),
+                session_id: "s-1".to_string(),
+                display_id: "BUG-002".to_string(),
+                capture_ids: vec!["c-3".to_string()],
+            },
+            json!({ "sourceBugId": "b-1", "bugId": "b-2", "sessionId": "s-1", "displayId": "BUG-002", "captureIds": ["c-3"] }),
+        );
+        assert_round_trip(
+            BugPromoted {
+                bug_id: "b-3".to_string(),
+                session_id: "s-1".to_string(),
+                bug_number: 4,
+                display_id: "BUG-004".to_string(),
+                folder_path: "/qa/s-1/bug_004".to_string(),
+            },
+            json!({ "bugId": "b-3", "sessionId": "s-1", "bugNumber": 4, "displayId": "BUG-004", "folderPath": "/qa/s-1/bug_004" }),
+        );
+        assert_round_trip(
+            BugFolded { bug_id: "b-3".to_string(), session_id: "s-1".to_string() },
+            json!({ "bugId": "b-3", "sessionId": "s-1" }),
+        );
+    }
+
+    #[test]
+    fn test_capture_and_deep_link_payloads() {
+        assert_round_trip(
+            ScreenshotCaptured {
+                file_path: "/qa/s-1/_unsorted/capture-001.png".to_string(),
+                capture_id: Some("c-1".to_string()),
+                bug_id: None,
+                session_id: Some("s-1".to_string()),
+                timestamp: 1_704_103_200_000,
+            },
+            json!({
+                "filePath": "/qa/s-1/_unsorted/capture-001.png",
+                "captureId": "c-1",
+                "bugId": null,
+                "sessionId": "s-1",
+                "timestamp": 1_704_103_200_000i64
+            }),
+        );
+        // Manual screenshot trigger: no session context at all
+        let manual: ScreenshotCaptured =
+            serde_json::from_value(json!({ "filePath": "/tmp/shot.png", "timestamp": 1 })).unwrap();
+        assert_eq!(manual.capture_id, None);
+
+        assert_round_trip(
+            CaptureEdited {
+                capture_id: "c-1".to_string(),
+                bug_id: Some("b-1".to_string()),
+                file_path: "/qa/shot.png".to_string(),
+                edited_at: Some("2024-01-01T10:05:00Z".to_string()),
+            },
+            json!({ "captureId": "c-1", "bugId": "b-1", "filePath": "/qa/shot.png", "editedAt": "2024-01-01T10:05:00Z" }),
+        );
+        assert_round_trip(
+            CaptureMoved { capture_id: "c-1".to_string(), bug_id: "b-2".to_string(), file_path: "/qa/b-2/shot.png".to_string() },
+            json!({ "captureId": "c-1", "bugId": "b-2", "filePath": "/qa/b-2/shot.png" }),
+        );
+        assert_round_trip(
+            CaptureUnassigned {
+                capture_id: "c-1".to_string(),
+                previous_bug_id: "b-2".to_string(),
+                file_path: "/qa/_unsorted/capture-004.png".to_string(),
+            },
+            json!({ "captureId": "c-1", "previousBugId": "b-2", "filePath": "/qa/_unsorted/capture-004.png" }),
+        );
+        assert_round_trip(
+            CaptureWriteLost {
+                session_id: "s-1".to_string(),
+                capture_id: "c-1".to_string(),
+                file_path: "/qa/_unsorted/capture-004.png".to_string(),
+                error: "database is locked".to_string(),
+            },
+            json!({
+                "sessionId": "s-1",
+                "captureId": "c-1",
+                "filePath": "/qa/_unsorted/capture-004.png",
+                "error": "database is locked"
+            }),
+        );
+        assert_round_trip(
+            CaptureFileDetected {
+                capture_id: "c-1".to_string(),
+                session_id: "s-1".to_string(),
+                bug_id: None,
+                file_path: "/qa/_unsorted/capture-004.png".to_string(),
+                file_type: crate::database::CaptureType::Screenshot,
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                replayed: true,
+            },
+            json!({
+                "captureId": "c-1",
+                "sessionId": "s-1",
+                "bugId": null,
+                "filePath": "/qa/_unsorted/capture-004.png",
+                "fileType": "screenshot",
+                "createdAt": "2024-01-01T10:00:00Z",
+                "replayed": true
+            }),
+        );
+        assert_round_trip(
+            CrashDumpCollected {
+                session_id: "s-1".to_string(),
+                bug_id: Some("b-1".to_string()),
+                dump_path: "/qa/bug_001/Shop.exe.77.dmp".to_string(),
+                process: "Shop.exe".to_string(),
+                faulting_module: Some("render.dll".to_string()),
+                exception_code: Some("0xC0000005".to_string()),
+            },
+            json!({
+                "sessionId": "s-1",
+                "bugId": "b-1",
+                "dumpPath": "/qa/bug_001/Shop.exe.77.dmp",
+                "process": "Shop.exe",
+                "faultingModule": "render.dll",
+                "exceptionCode": "0xC0000005"
+            }),
+        );
+        assert_round_trip(
+            RecordingStarted {
+                recording_id: "r-1".to_string(),
+                session_id: "s-1".to_string(),
+                bug_id: Some("b-1".to_string()),
+                file_path: "/qa/bug_001/recording-001.mp4".to_string(),
+            },
+            json!({
+                "recordingId": "r-1",
+                "sessionId": "s-1",
+                "bugId": "b-1",
+                "filePath": "/qa/bug_001/recording-001.mp4"
+            }),
+        );
+        assert_round_trip(
+            RecordingProgress { recording_id: "r-1".to_string(), duration_ms: 3000, file_size_bytes: Some(81920) },
+            json!({ "recordingId": "r-1", "durationMs": 3000, "fileSizeBytes": 81920 }),
+        );
+        assert_round_trip(
+            RecordingStopped {
+                recording_id: "r-1".to_string(),
+                file_path: "/qa/bug_001/recording-001.mp4".to_string(),
+                duration_ms: 12500,
+                capture_id: Some("c-1".to_string()),
+                error: None,
+            },
+            json!({
+                "recordingId": "r-1",
+                "filePath": "/qa/bug_001/recording-001.mp4",
+                "durationMs": 12500,
+                "captureId": "c-1",
+                "error": null
+            }),
+        );
+        assert_round_trip(
+            TicketingQueued {
+                queue_id: "q-1".to_string(),
+                bug_id: Some("b-1".to_string()),
+                title: "Crash on save".to_string(),
+                attempts: 2,
+                next_attempt_at: "2024-01-01T10:01:00+00:00".to_string(),
+                error: Some("Network error: timed out".to_string()),
+            },
+            json!({
+                "queueId": "q-1",
+                "bugId": "b-1",
+                "title": "Crash on save",
+                "attempts": 2,
+                "nextAttemptAt": "2024-01-01T10:01:00+00:00",
+                "error": "Network error: timed out"
+            }),
+        );
+        assert_round_trip(
+            TicketingSent {
+                queue_id: "q-1".to_string(),
+                bug_id: None,
+                identifier: "ENG-7".to_string(),
+                url: "https://linear.app/acme/issue/ENG-7".to_string(),
+            },
+            json!({
+                "queueId": "q-1",
+                "bugId": null,
+                "identifier": "ENG-7",
+                "url": "https://linear.app/acme/issue/ENG-7"
+            }),
+        );
+        assert_round_trip(
+            TicketingFailed {
+                queue_id: "q-1".to_string(),
+                bug_id: Some("b-1".to_string()),
+                title: "Crash on save".to_string(),
+                attempts: 8,
+                error: "Network error: timed out".to_string(),
+            },
+            json!({
+                "queueId": "q-1",
+                "bugId": "b-1",
+                "title": "Crash on save",
+                "attempts": 8,
+                "error": "Network error: timed out"
+            }),
+        );
+        assert_round_trip(
+            DeepLinkOpenBug { bug_id: "b-1".to_string(), session_id: "s-1".to_string() },
+            json!({ "bugId": "b-1", "sessionId": "s-1" }),
+        );
+        assert_round_trip(
+            DeepLinkOpenSession { session_id: "s-1".to_string() },
+            json!({ "sessionId": "s-1" }),
+        );
+        assert_round_trip(
+            CommandDeprecated {
+                command: "get_bugs".to_string(),
+                replacement: Some("list_bugs".to_string()),
+                since: "0.2.0".to_string(),
+            },
+            json!({ "command": "get_bugs", "replacement": "list_bugs", "since": "0.2.0" }),
+        );
+        assert_round_trip(
+            QuickToggleChanged { key: "ai_enabled".to_string(), enabled: false },
+            json!({ "key": "ai_enabled", "enabled": false }),
+        );
+        assert_round_trip(
+            CaptureQuotaExceeded {
+                session_id: "s-1".to_string(),
+                bug_id: Some("b-4".to_string()),
+                captures: 26,
+                total_bytes: 5_242_880,
+                message: "BUG-004 has 26 captures — consider splitting it".to_string(),
+            },
+            json!({
+                "sessionId": "s-1",
+                "bugId": "b-4",
+                "captures": 26,
+                "totalBytes": 5_242_880,
+                "message": "BUG-004 has 26 captures — consider splitting it"
+            }),
+        );
+        let file = crate::staging_watcher::StagedFile {
+            path: "/staging/IMG_0042.JPG".to_string(),
+            file_name: "IMG_0042.JPG".to_string(),
+            size_bytes: 2048,
+            captured_at: "2024-01-15T09:00:00+00:00".to_string(),
+        };
+        let file_json = json!({
+            "path": "/staging/IMG_0042.JPG",
+            "fileName": "IMG_0042.JPG",
+            "sizeBytes": 2048,
+            "capturedAt": "2024-01-15T09:00:00+00:00"
+        });
+        assert_round_trip(StagedFileQueued { file: file.clone() }, file_json.clone());
+        assert_round_trip(
+            StagedImportPrompt { session_id: "s-1".to_string(), files: vec![file] },
+            json!({ "sessionId": "s-1", "files": [file_json] }),
+        );
+        assert_round_trip(
+            PostProcessingEvent {
+                session_id: "s-1".to_string(),
+                total_steps: 2,
+                steps: vec![crate::post_session::StepResult {
+                    action: "zip_export".to_string(),
+                    success: true,
+                    output: Some("/qa/s-1.zip".to_string()),
+                    error: None,
+                }],
+                complete: false,
+            },
+            json!({
+                "sessionId": "s-1",
+                "totalSteps": 2,
+                "steps": [{ "action": "zip_export", "success": true, "output": "/qa/s-1.zip", "error": null }],
+                "complete": false
+            }),
+        );
+        assert_round_trip(
+            ViewerOpened { archive_path: "/exports/s-1.qacap".to_string(), session_id: "s-1".to_string(), bug_count: 3 },
+            json!({ "archivePath": "/exports/s-1.qacap", "sessionId": "s-1", "bugCount": 3 }),
+        );
+        assert_round_trip(TrayStateChanged { state: "bug".to_string() }, json!({ "state": "bug" }));
+        assert_round_trip(
+            ArchiveProgress {
+                session_id: "s-1".to_string(),
+                current: "bug_001/capture-001.png".to_string(),
+                files_done: 1,
+                files_total: 4,
+                bytes_done: 1024,
+                bytes_total: 8192,
+            },
+            json!({
+                "sessionId": "s-1",
+                "current": "bug_001/capture-001.png",
+                "filesDone": 1,
+                "filesTotal": 4,
+                "bytesDone": 1024,
+                "bytesTotal": 8192
+            }),
+        );
+    }
+
+    #[test]
+    fn test_schema_lists_each_event_once() {
+        let schema = EventSchema::current();
+        assert_eq!(schema.version, EVENT_SCHEMA_VERSION);
+        let mut names = schema.events.clone();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), schema.events.len());
+    }
+}
@@ -0,0 +1,294 @@
+//! User-defined command run after an export.
+//!
+//! Teams plug their own steps into the export pipeline (a script that
+//! post-processes the ZIP, a copy to a network share, an uploader) by
+//! configuring a command line in the `export.post_hook` setting. After a
+//! session ZIP, HTML report or CSV is written, the backend runs the command
+//! with the export path as an argument on a background thread. Its exit
+//! code, stdout and stderr go to the log and to the audit table
+//! (`export.post_hook`), so a failing upload is visible after the fact.
+//!
+//! The setting can only be changed with `set_post_export_hook`, which is
+//! restricted to the main window, never through `set_setting`.
+
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{record_audit, SettingsOps, SettingsRepository};
+
+/// Settings key holding [`PostExportHook`] as JSON.
+pub const EXPORT_HOOK_KEY: &str = "export.post_hook";
+
+/// Placeholder in `args` replaced by the export path.
+pub const PATH_PLACEHOLDER: &str = "{path}";
+
+/// Accepted range for `timeout_secs`.
+pub const MAX_TIMEOUT_SECS: u64 = 3600;
+
+/// Output kept per stream in the audit entry.
+const MAX_AUDIT_OUTPUT: usize = 4000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostExportHook {
+    /// Executable, e.g. `C:\Tools\upload.exe` or `powershell`
+    pub command: String,
+    /// Arguments; [`PATH_PLACEHOLDER`] is replaced by the export path. When no
+    /// argument contains it, the path is passed as the last argument.
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    300
+}
+
+impl PostExportHook {
+    /// The configured hook; None when unset or unreadable.
+    pub fn load(conn: &Connection) -> Option<Self> {
+        SettingsRepository::new(conn)
+            .get(EXPORT_HOOK_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(EXPORT_HOOK_KEY, &json)
+            .map_err(|e| format!("Failed to save post-export hook: {}", e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.command.trim().is_empty() {
+            return Err("The post-export hook needs a command".to_string());
+        }
+        if !(1..=MAX_TIMEOUT_SECS).contains(&self.timeout_secs) {
+            return Err(format!("Timeout must be between 1 and {} seconds", MAX_TIMEOUT_SECS));
+        }
+        Ok(())
+    }
+
+    /// Arguments with the export path filled in.
+    pub fn args_for(&self, export_path: &Path) -> Vec<String> {
+        let path = export_path.to_string_lossy();
+        let mut args: Vec<String> = self.args.iter().map(|arg| arg.replace(PATH_PLACEHOLDER, &path)).collect();
+        if !self.args.iter().any(|arg| arg.contains(PATH_PLACEHOLDER)) {
+            args.push(path.to_string());
+        }
+        args
+    }
+}
+
+/// Result of one hook run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookOutcome {
+    /// None when the process was killed or ended by a signal
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+}
+
+fn read_stream<R: Read + Send + 'static>(stream: Option<R>) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut stream) = stream {
+            let _ = stream.read_to_end(&mut buffer);
+        }
+        String::from_utf8_lossy(&buffer).into_owned()
+    })
+}
+
+/// Run `hook` for `export_path`. `kind` (`zip`, `html`, `csv`) and the
+/// session id are passed in the `QA_EXPORT_KIND` and `QA_SESSION_ID`
+/// environment variables. The process is killed after the hook's timeout.
+pub fn run(hook: &PostExportHook, export_path: &Path, kind: &str, session_id: &str) -> Result<HookOutcome, String> {
+    let started = Instant::now();
+    let mut child = Command::new(&hook.command)
+        .args(hook.args_for(export_path))
+        .env("QA_EXPORT_KIND", kind)
+        .env("QA_SESSION_ID", session_id)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start post-export hook '{}': {}", hook.command, e))?;
+
+    // Drain both pipes while waiting so a chatty hook cannot block on a full pipe
+    let stdout = read_stream(child.stdout.take());
+    let stderr = read_stream(child.stderr.take());
+
+    let timeout = Duration::from_secs(hook.timeout_secs);
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait().map_err(|e| format!("Failed to wait for post-export hook: {}", e))? {
+            Some(status) => break Some(status),
+            None if started.elapsed() >= timeout => {
+                timed_out = true;
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            None => std::thread::sleep(Duration::from_millis(50)),
+        }
+    };
+
+    let exit_code = status.and_then(|s| s.code());
+    Ok(HookOutcome {
+        exit_code,
+        success: status.is_some_and(|s| s.success()),
+        timed_out,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// The last `max` characters of `text`.
+fn tail(text: &str, max: usize) -> &str {
+    let count = text.chars().count();
+    if count <= max {
+        return text;
+    }
+    let start = text.char_indices().nth(count - max).map(|(i, _)| i).unwrap_or(0);
+    &text[start..]
+}
+
+/// Run the configured hook, if any, and record the outcome in the log and
+/// the audit table. Returns None when no hook is configured.
+pub fn run_and_record(
+    db: &Mutex<Connection>,
+    session_id: &str,
+    export_path: &Path,
+    kind: &str,
+) -> Option<Result<HookOutcome, String>> {
+    let hook = PostExportHook::load(&db.lock().unwrap())?;
+    let result = run(&hook, export_path, kind, session_id);
+
+    let details = match &result {
+        Ok(outcome) => {
+            let label = format!("Post-export hook '{}' for {}", hook.command, export_path.display());
+            for line in outcome.stdout.lines() {
+                println!("[{}] {}", hook.command, line);
+            }
+            for line in outcome.stderr.lines() {
+                eprintln!("[{}] {}", hook.command, line);
+            }
+            if outcome.timed_out {
+                eprintln!("{} timed out after {}s", label, hook.timeout_secs);
+            } else if !outcome.success {
+                eprintln!("{} failed with exit code {:?}", label, outcome.exit_code);
+            }
+            serde_json::json!({
+                "command": hook.command,
+                "exportPath": export_path.to_string_lossy(),
+                "kind": kind,
+                "exitCode": outcome.exit_code,
+                "success": outcome.success,
+                "timedOut": outcome.timed_out,
+                "durationMs": outcome.duration_ms,
+                "stdout": tail(&outcome.stdout, MAX_AUDIT_OUTPUT),
+                "stderr": tail(&outcome.stderr, MAX_AUDIT_OUTPUT),
+            })
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            serde_json::json!({
+                "command": hook.command,
+                "exportPath": export_path.to_string_lossy(),
+                "kind": kind,
+                "success": false,
+                "error": e,
+            })
+        }
+    };
+    if let Err(e) = record_audit(&db.lock().unwrap(), "export.post_hook", "session", session_id, Some(details)) {
+        eprintln!("Warning: {}", e);
+    }
+    Some(result)
+}
+
+/// Run the configured hook on a background thread so the export returns
+/// immediately.
+pub fn spawn(db: Arc<Mutex<Connection>>, session_id: &str, export_path: &Path, kind: &'static str) {
+    let session_id = session_id.to_string();
+    let export_path = export_path.to_path_buf();
+    std::thread::spawn(move || {
+        run_and_record(&db, &session_id, &export_path, kind);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{AuditOps, AuditRepository};
+
+    fn hook(command: &str, args: &[&str]) -> PostExportHook {
+        PostExportHook {
+            command: command.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            timeout_secs: 10,
+        }
+    }
+
+    #[test]
+    fn test_args_and_validation() {
+        let path = Path::new("/exports/session.zip");
+        assert_eq!(hook("upload", &["--quiet"]).args_for(path), vec!["--quiet", "/exports/session.zip"]);
+        assert_eq!(
+            hook("upload", &["--file={path}", "--share"]).args_for(path),
+            vec!["--file=/exports/session.zip", "--share"]
+        );
+        assert!(hook(" ", &[]).validate().is_err());
+        assert!(PostExportHook { timeout_secs: 0, ..hook("upload", &[]) }.validate().is_err());
+        assert_eq!(tail("abcdef", 3), "def");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_records_output_in_audit_log() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        hook("sh", &["-c", "echo \"$QA_EXPORT_KIND $1\"; echo oops >&2; exit 3", "hook", "{path}"])
+            .save(&conn)
+            .unwrap();
+        let db = Mutex::new(conn);
+
+        let outcome = run_and_record(&db, "s-1", Path::new("/exports/s-1.zip"), "zip").unwrap().unwrap();
+        assert_eq!(outcome.exit_code, Some(3));
+        assert!(!outcome.success);
+        assert_eq!(outcome.stdout, "zip /exports/s-1.zip\n");
+        assert_eq!(outcome.stderr, "oops\n");
+
+        let conn = db.lock().unwrap();
+        let entries = AuditRepository::new(&conn).list_for_entity("session", "s-1").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, "export.post_hook");
+        let details: serde_json::Value = serde_json::from_str(entries[0].details.as_deref().unwrap()).unwrap();
+        assert_eq!(details["exitCode"], 3);
+        assert_eq!(details["stderr"], "oops\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_kills_hook_after_timeout() {
+        // The export path is appended: `sleep 30 1`
+        let hook = PostExportHook { timeout_secs: 1, ..hook("sleep", &["30"]) };
+        let outcome = run(&hook, Path::new("1"), "zip", "s-1").unwrap();
+        assert!(outcome.timed_out);
+        assert!(!outcome.success);
+    }
+}
@@ -0,0 +1,212 @@
+//! Watches captures opened in an external editor.
+//!
+//! `open_capture_in_editor` hands the capture file to the system default
+//! application. While it is open there, an [`ExternalEditWatcher`] watches the
+//! file's folder; when the editor saves the file the capture's `edited_at`
+//! timestamp and size are updated and a `capture:edited` event is emitted so
+//! the frontend can reload the image and its thumbnail.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use chrono::Utc;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rusqlite::Connection;
+use tauri::AppHandle;
+
+use crate::database::{Capture, CaptureOps, CaptureRepository};
+use crate::events;
+
+type SharedConn = Arc<Mutex<Connection>>;
+
+/// The file that should be opened for a capture: the annotated copy when one
+/// exists (that is what the user sees), otherwise the original.
+pub fn editable_path(capture: &Capture) -> PathBuf {
+    capture
+        .annotated_path
+        .as_ref()
+        .map(PathBuf::from)
+        .filter(|p| p.exists())
+        .unwrap_or_else(|| PathBuf::from(&capture.file_path))
+}
+
+/// Record that `path` (belonging to `capture_id`) was changed externally.
+/// Updates `edited_at`, and `file_size_bytes` when the original was edited.
+pub fn record_external_edit(
+    conn: &Connection,
+    capture_id: &str,
+    path: &Path,
+) -> Result<Option<Capture>, String> {
+    let repo = CaptureRepository::new(conn);
+    let Some(mut capture) = repo
+        .get(capture_id)
+        .map_err(|e| format!("Failed to get capture: {}", e))?
+    else {
+        return Ok(None);
+    };
+
+    if Path::new(&capture.file_path) == path {
+        if let Ok(metadata) = std::fs::metadata(path) {
+            capture.file_size_bytes = Some(metadata.len() as i64);
+        }
+    }
+    capture.edited_at = Some(Utc::now().to_rfc3339());
+
+    repo.update(&capture)
+        .map_err(|e| format!("Failed to update capture: {}", e))?;
+    Ok(Some(capture))
+}
+
+/// Watches a single capture file for saves from an external editor.
+///
+/// Dropping the struct stops the watcher.
+pub struct ExternalEditWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl ExternalEditWatcher {
+    pub fn start(
+        capture_id: String,
+        path: PathBuf,
+        db_conn: SharedConn,
+        app_handle: AppHandle,
+    ) -> Result<Self, String> {
+        let folder = path
+            .parent()
+            .ok_or_else(|| format!("Capture file has no parent folder: {}", path.display()))?
+            .to_path_buf();
+
+        // Many editors save by writing a temp file and renaming it over the
+        // original, so watch the folder and match on the file path.
+        let last_modified = Mutex::new(modified_time(&path));
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<Event, notify::Error>| {
+                let Ok(event) = res else { return };
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    return;
+                }
+                if !event.paths.iter().any(|p| p == &path) {
+                    return;
+                }
+
+                // Editors often emit several events per save; only react once
+                // per change of modification time.
+                let modified = modified_time(&path);
+                {
+                    let mut last = last_modified.lock().unwrap();
+                    if modified.is_none() || *last == modified {
+                        return;
+                    }
+                    *last = modified;
+                }
+
+                let conn = db_conn.lock().unwrap();
+                match record_external_edit(&conn, &capture_id, &path) {
+                    Ok(Some(capture)) => {
+                        let _ = events::emit(
+                            &app_handle,
+                            &events::CaptureEdited {
+                                capture_id: capture.id,
+                                bug_id: capture.bug_id,
+                                file_path: path.to_string_lossy().to_string(),
+                                edited_at: capture.edited_at,
+                            },
+                        );
+                    }
+                    Ok(None) => {}
+                    Err(e) => eprintln!("Warning: failed to record external edit: {}", e),
+                }
+            },
+            notify::Config::default(),
+        )
+        .map_err(|e| format!("Failed to create file watcher: {e}"))?;
+
+        watcher
+            .watch(&folder, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch capture folder: {e}"))?;
+
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{CaptureType, Database, Session, SessionOps, SessionRepository, SessionStatus};
+
+    fn seed(conn: &Connection, file_path: &Path, annotated_path: Option<&Path>) {
+        SessionRepository::new(conn)
+            .create(&Session {
+                id: "s-1".to_string(),
+                started_at: "2024-01-01T10:00:00Z".to_string(),
+                ended_at: None,
+                status: SessionStatus::Active,
+                folder_path: "/tmp/s-1".to_string(),
+                session_notes: None,
+                environment_json: None,
+                original_snip_path: None,
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                profile_id: None,
+                unlocked_at: None,
+                timezone: None,
+            })
+            .unwrap();
+        CaptureRepository::new(conn)
+            .create(&Capture {
+                id: "c-1".to_string(),
+                session_id: "s-1".to_string(),
+                file_name: "capture-001.png".to_string(),
+                file_path: file_path.to_string_lossy().to_string(),
+                file_type: CaptureType::Screenshot,
+                annotated_path: annotated_path.map(|p| p.to_string_lossy().to_string()),
+                file_size_bytes: Some(1),
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_editable_path_prefers_existing_annotated_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("capture-001.png");
+        let annotated = dir.path().join("capture-001_annotated.png");
+        let db = Database::in_memory().unwrap();
+        seed(db.connection(), &original, Some(&annotated));
+
+        let capture = CaptureRepository::new(db.connection()).get("c-1").unwrap().unwrap();
+        assert_eq!(editable_path(&capture), original);
+
+        std::fs::write(&annotated, b"png").unwrap();
+        assert_eq!(editable_path(&capture), annotated);
+    }
+
+    #[test]
+    fn test_record_external_edit_updates_timestamp_and_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("capture-001.png");
+        std::fs::write(&original, b"edited image bytes").unwrap();
+        let db = Database::in_memory().unwrap();
+        seed(db.connection(), &original, None);
+
+        let capture = record_external_edit(db.connection(), "c-1", &original).unwrap().unwrap();
+        assert!(capture.edited_at.is_some());
+        assert_eq!(capture.file_size_bytes, Some(18));
+
+        let stored = CaptureRepository::new(db.connection()).get("c-1").unwrap().unwrap();
+        assert_eq!(stored.edited_at, capture.edited_at);
+    }
+
+    #[test]
+    fn test_record_external_edit_unknown_capture() {
+        let db = Database::in_memory().unwrap();
+        assert!(record_external_edit(db.connection(), "missing", Path::new("/tmp/x.png"))
+            .unwrap()
+            .is_none());
+    }
+}
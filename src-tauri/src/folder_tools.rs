@@ -0,0 +1,189 @@
+//! Copying session paths and opening a terminal in a session folder.
+//!
+//! Power users run their own scripts against session folders. These helpers
+//! back the `copy_path_to_clipboard` and `open_in_terminal` commands: the
+//! first resolves the on-disk path of a session, bug or capture, the second
+//! starts the platform's terminal with the folder as working directory
+//! (Windows Terminal or PowerShell, Terminal.app, or the desktop's terminal
+//! on Linux).
+
+use std::path::Path;
+use std::process::Command;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{BugOps, BugRepository, CaptureOps, CaptureRepository, SessionOps, SessionRepository};
+
+/// What `copy_path_to_clipboard` copies the path of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PathKind {
+    /// The session folder
+    Session,
+    /// The bug folder
+    Bug,
+    /// The capture file
+    Capture,
+    /// The capture's annotated copy, else the capture file
+    AnnotatedCapture,
+}
+
+/// On-disk path of the session, bug or capture `id`.
+pub fn resolve_path(conn: &Connection, kind: PathKind, id: &str) -> Result<String, String> {
+    let not_found = |what: &str| format!("{} not found: {}", what, id);
+    match kind {
+        PathKind::Session => SessionRepository::new(conn)
+            .get(id)
+            .map_err(|e| e.to_string())?
+            .map(|session| session.folder_path)
+            .ok_or_else(|| not_found("Session")),
+        PathKind::Bug => BugRepository::new(conn)
+            .get(id)
+            .map_err(|e| e.to_string())?
+            .map(|bug| bug.folder_path)
+            .ok_or_else(|| not_found("Bug")),
+        PathKind::Capture | PathKind::AnnotatedCapture => {
+            let capture = CaptureRepository::new(conn)
+                .get(id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| not_found("Capture"))?;
+            Ok(match (kind, capture.annotated_path) {
+                (PathKind::AnnotatedCapture, Some(annotated)) => annotated,
+                _ => capture.file_path,
+            })
+        }
+    }
+}
+
+/// Terminal launch commands for `folder`, in the order they are tried:
+/// `(program, arguments)`. Each is started with `folder` as working
+/// directory as well, for terminals without a directory argument.
+pub fn terminal_candidates(folder: &str) -> Vec<(&'static str, Vec<String>)> {
+    if cfg!(target_os = "windows") {
+        let mut candidates = Vec::new();
+        // wt.exe splits its command line into several commands at `;`, even
+        // inside the -d argument, so such folders go straight to PowerShell
+        if !folder.contains(';') {
+            candidates.push(("wt.exe", vec!["-d".to_string(), folder.to_string()]));
+        }
+        candidates.push((
+            "powershell.exe",
+            vec![
+                "-NoExit".to_string(),
+                "-Command".to_string(),
+                // Single quotes are escaped by doubling inside a PowerShell literal
+                format!("Set-Location -LiteralPath '{}'", folder.replace('\'', "''")),
+            ],
+        ));
+        candidates
+    } else if cfg!(target_os = "macos") {
+        vec![("open", vec!["-a".to_string(), "Terminal".to_string(), folder.to_string()])]
+    } else {
+        vec![
+            ("x-terminal-emulator", vec![]),
+            ("gnome-terminal", vec![format!("--working-directory={}", folder)]),
+            ("konsole", vec!["--workdir".to_string(), folder.to_string()]),
+            ("xfce4-terminal", vec![format!("--working-directory={}", folder)]),
+            ("xterm", vec![]),
+        ]
+    }
+}
+
+/// Open a terminal in `folder`, trying each of [`terminal_candidates`].
+pub fn open_in_terminal(folder: &str) -> Result<(), String> {
+    let path = Path::new(folder);
+    if !path.is_dir() {
+        return Err(format!("Folder does not exist: {}", folder));
+    }
+
+    let mut errors = Vec::new();
+    for (program, args) in terminal_candidates(folder) {
+        let mut command = Command::new(program);
+        command.args(&args).current_dir(path);
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            // PowerShell would otherwise attach to the app's (absent) console
+            const CREATE_NEW_CONSOLE: u32 = 0x0000_0010;
+            command.creation_flags(CREATE_NEW_CONSOLE);
+        }
+        match command.spawn() {
+            Ok(_) => return Ok(()),
+            Err(e) => errors.push(format!("{}: {}", program, e)),
+        }
+    }
+    Err(format!("Failed to open a terminal ({})", errors.join("; ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{init_database, Capture, CaptureType, Session, SessionStatus};
+
+    #[test]
+    fn test_resolve_path() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        SessionRepository::new(&conn)
+            .create(&Session {
+                id: "s-1".to_string(),
+                started_at: "2024-01-01T10:00:00Z".to_string(),
+                ended_at: None,
+                status: SessionStatus::Active,
+                folder_path: "/qa/s-1".to_string(),
+                session_notes: None,
+                environment_json: None,
+                original_snip_path: None,
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                profile_id: None,
+                unlocked_at: None,
+                timezone: None,
+            })
+            .unwrap();
+        let mut capture = Capture {
+            id: "c-1".to_string(),
+            session_id: "s-1".to_string(),
+            file_name: "capture-001.png".to_string(),
+            file_path: "/qa/s-1/_unsorted/capture-001.png".to_string(),
+            file_type: CaptureType::Screenshot,
+            created_at: "2024-01-01T10:01:00Z".to_string(),
+            ..Default::default()
+        };
+        CaptureRepository::new(&conn).create(&capture).unwrap();
+
+        assert_eq!(resolve_path(&conn, PathKind::Session, "s-1").unwrap(), "/qa/s-1");
+        // No annotated copy yet: the capture itself
+        assert_eq!(
+            resolve_path(&conn, PathKind::AnnotatedCapture, "c-1").unwrap(),
+            "/qa/s-1/_unsorted/capture-001.png"
+        );
+        capture.annotated_path = Some("/qa/s-1/_unsorted/capture-001_annotated.png".to_string());
+        CaptureRepository::new(&conn).update(&capture).unwrap();
+        assert_eq!(
+            resolve_path(&conn, PathKind::AnnotatedCapture, "c-1").unwrap(),
+            "/qa/s-1/_unsorted/capture-001_annotated.png"
+        );
+        assert_eq!(resolve_path(&conn, PathKind::Bug, "b-9").unwrap_err(), "Bug not found: b-9");
+    }
+
+    #[test]
+    fn test_terminal_candidates_target_folder() {
+        let folder = "/qa/it's here";
+        let candidates = terminal_candidates(folder);
+        assert!(!candidates.is_empty());
+        #[cfg(target_os = "windows")]
+        {
+            assert_eq!(candidates[1].1[2], "Set-Location -LiteralPath '/qa/it''s here'");
+            let candidates = terminal_candidates("C:\\qa\\a;calc.exe");
+            assert!(candidates.iter().all(|(program, _)| *program != "wt.exe"));
+        }
+        #[cfg(target_os = "linux")]
+        assert!(candidates.iter().any(|(_, args)| args.contains(&"--working-directory=/qa/it's here".to_string())));
+    }
+
+    #[test]
+    fn test_open_in_terminal_rejects_missing_folder() {
+        assert!(open_in_terminal("/definitely/not/a/qa/folder").unwrap_err().starts_with("Folder does not exist"));
+    }
+}
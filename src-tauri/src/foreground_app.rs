@@ -0,0 +1,123 @@
+//! The application a bug occurred in.
+//!
+//! Every capture records the foreground window (see
+//! `platform::foreground_window`) in its `source_metadata`. When a capture is
+//! filed to a bug, the window is also stored under `foreground` in the bug's
+//! `metadata_json`, so the bug keeps the one of its latest capture. Bug
+//! reports show it as the template's `foregroundApp`.
+
+use rusqlite::Connection;
+use serde_json::{Map, Value};
+
+use crate::capture_routing::CaptureSource;
+use crate::database::{Bug, BugOps, BugRepository, Capture};
+use crate::platform::ForegroundWindow;
+
+/// Key of the window in a bug's `metadata_json`.
+pub const FOREGROUND_KEY: &str = "foreground";
+
+/// The foreground window recorded with `capture`, if any.
+pub fn from_capture(capture: &Capture) -> Option<ForegroundWindow> {
+    capture
+        .source_metadata
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<CaptureSource>(json).ok())
+        .and_then(|source| source.foreground)
+}
+
+/// The foreground window stored on `bug`, if any.
+pub fn from_bug(bug: &Bug) -> Option<ForegroundWindow> {
+    bug.metadata_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<Map<String, Value>>(json).ok())
+        .and_then(|mut metadata| metadata.remove(FOREGROUND_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+}
+
+/// Store `window` under `foreground` in the bug's `metadata_json`.
+pub fn attach_to_bug(conn: &Connection, bug_id: &str, window: &ForegroundWindow) -> Result<(), String> {
+    let value = serde_json::to_value(window).map_err(|e| e.to_string())?;
+    BugRepository::new(conn).merge_metadata(bug_id, FOREGROUND_KEY, value).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{
+        init_database, BugStatus, BugType, CaptureType, Session, SessionOps, SessionRepository, SessionStatus,
+    };
+
+    fn shop() -> ForegroundWindow {
+        ForegroundWindow {
+            title: "Checkout".to_string(),
+            process_name: "Shop.exe".to_string(),
+            executable_path: Some("C:\\Program Files\\Shop\\Shop.exe".to_string()),
+            version: Some("2.3.1.0".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_from_capture() {
+        let mut capture = Capture {
+            id: "c-1".to_string(),
+            bug_id: Some("b-1".to_string()),
+            session_id: "s-1".to_string(),
+            file_name: "capture-001.png".to_string(),
+            file_path: "/qa/s-1/bug_001/capture-001.png".to_string(),
+            file_type: CaptureType::Screenshot,
+            created_at: "2024-01-01T10:01:00Z".to_string(),
+            source_metadata: Some(r#"{"source":"capture_watcher","routing":"active_bug"}"#.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(from_capture(&capture), None);
+
+        capture.source_metadata = Some(format!(
+            r#"{{"source":"native_capture","routing":"active_bug","foreground":{}}}"#,
+            serde_json::to_string(&shop()).unwrap()
+        ));
+        assert_eq!(from_capture(&capture), Some(shop()));
+    }
+
+    #[test]
+    fn test_attach_keeps_other_metadata() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        SessionRepository::new(&conn)
+            .create(&Session {
+                id: "s-1".to_string(),
+                started_at: "2024-01-01T10:00:00Z".to_string(),
+                ended_at: None,
+                status: SessionStatus::Active,
+                folder_path: "/qa/s-1".to_string(),
+                session_notes: None,
+                environment_json: None,
+                original_snip_path: None,
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                profile_id: None,
+                unlocked_at: None,
+                timezone: None,
+            })
+            .unwrap();
+        BugRepository::new(&conn)
+            .create(&Bug {
+                id: "b-1".to_string(),
+                session_id: "s-1".to_string(),
+                bug_number: 1,
+                display_id: "BUG-001".to_string(),
+                bug_type: BugType::Bug,
+                status: BugStatus::Capturing,
+                metadata_json: Some(r#"{"locale":{"displayLanguage":"de-DE"}}"#.to_string()),
+                folder_path: "/qa/s-1/bug_001".to_string(),
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                updated_at: "2024-01-01T10:00:00Z".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        attach_to_bug(&conn, "b-1", &shop()).unwrap();
+
+        let bug = BugRepository::new(&conn).get("b-1").unwrap().unwrap();
+        assert_eq!(from_bug(&bug), Some(shop()));
+        assert!(bug.metadata_json.unwrap().contains(r#""displayLanguage":"de-DE""#));
+    }
+}
@@ -0,0 +1,221 @@
+//! Explaining project abbreviations to readers outside the team.
+//!
+//! A profile's glossary maps abbreviations such as "CPQ" to their full names.
+//! Rendered tickets either expand the first use of each abbreviation in place
+//! or list the ones used in a glossary section at the end, depending on the
+//! profile's [`GlossaryStyle`]. AI prompts get the list so generated
+//! descriptions spell them out too. Abbreviations inside code blocks, inline
+//! code, URLs and file paths are left alone.
+
+use rusqlite::Connection;
+
+use crate::database::{SessionOps, SessionRepository, SettingsOps, SettingsRepository};
+use crate::profile::{GlossaryEntry, GlossaryStyle, ProfileRepository, QaProfile, SqliteProfileRepository, ACTIVE_PROFILE_KEY};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Glossary {
+    pub entries: Vec<GlossaryEntry>,
+    pub style: GlossaryStyle,
+}
+
+impl Glossary {
+    pub fn from_profile(profile: &QaProfile) -> Self {
+        Self {
+            entries: profile
+                .glossary
+                .iter()
+                .filter(|e| !e.abbreviation.trim().is_empty() && !e.expansion.trim().is_empty())
+                .cloned()
+                .collect(),
+            style: profile.glossary_style,
+        }
+    }
+
+    /// The glossary of the session's profile, or of the active profile when
+    /// the session has none (or no session is given).
+    pub fn for_session(conn: &Connection, session_id: Option<&str>) -> Self {
+        let session_profile = session_id
+            .and_then(|id| SessionRepository::new(conn).get(id).ok().flatten())
+            .and_then(|session| session.profile_id);
+        let profile_id = session_profile
+            .or_else(|| SettingsRepository::new(conn).get(ACTIVE_PROFILE_KEY).ok().flatten());
+        profile_id
+            .and_then(|id| SqliteProfileRepository::new(conn).get(&id).ok().flatten())
+            .map(|profile| Self::from_profile(&profile))
+            .unwrap_or_default()
+    }
+
+    /// `text` with the abbreviations it uses explained.
+    pub fn apply(&self, text: &str) -> String {
+        match self.style {
+            GlossaryStyle::Expand => {
+                let mut output = text.to_string();
+                for entry in &self.entries {
+                    let Some(start) = first_prose_use(&output, &entry.abbreviation) else {
+                        continue;
+                    };
+                    let end = start + entry.abbreviation.len();
+                    // Already explained by the author
+                    if output[end..].starts_with(" (")
+                        || output.to_lowercase().contains(&entry.expansion.to_lowercase())
+                    {
+                        continue;
+                    }
+                    output.insert_str(end, &format!(" ({})", entry.expansion));
+                }
+                output
+            }
+            GlossaryStyle::Footnote => {
+                let used: Vec<&GlossaryEntry> = self
+                    .entries
+                    .iter()
+                    .filter(|e| first_prose_use(text, &e.abbreviation).is_some())
+                    .collect();
+                if used.is_empty() {
+                    return text.to_string();
+                }
+                let mut output = format!("{}\n\n## Glossary\n\n", text.trim_end());
+                for entry in used {
+                    output.push_str(&format!("- **{}**: {}\n", entry.abbreviation, entry.expansion));
+                }
+                output
+            }
+        }
+    }
+
+    /// The glossary as "ABBR: expansion" lines for an AI prompt, if not empty.
+    pub fn prompt_list(&self) -> Option<String> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        Some(
+            self.entries
+                .iter()
+                .map(|e| format!("{}: {}", e.abbreviation, e.expansion))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+}
+
+/// Whether the whitespace-separated word around `line[start..end]` is a URL
+/// or a file path.
+fn in_url_or_path(line: &str, start: usize, end: usize) -> bool {
+    let before = line[..start].rsplit(char::is_whitespace).next().unwrap_or_default();
+    let after = line[end..].split(char::is_whitespace).next().unwrap_or_default();
+    let word = format!("{}{}{}", before, &line[start..end], after);
+    word.contains("://") || word.starts_with("www.") || word.contains('/') || word.contains('\\')
+}
+
+/// Byte offset of the first whole-word, case-sensitive use of `abbreviation`
+/// outside code blocks, inline code, URLs and paths.
+fn first_prose_use(text: &str, abbreviation: &str) -> Option<usize> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut in_code_block = false;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        for (start, _) in line.match_indices(abbreviation) {
+            let end = start + abbreviation.len();
+            let in_inline_code = line[..start].matches('`').count() % 2 == 1;
+            if !in_inline_code
+                && !line[..start].chars().next_back().is_some_and(is_word)
+                && !line[end..].chars().next().is_some_and(is_word)
+                && !in_url_or_path(line, start, end)
+            {
+                return Some(line_start + start);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glossary(style: GlossaryStyle) -> Glossary {
+        Glossary {
+            entries: vec![
+                GlossaryEntry { abbreviation: "CPQ".to_string(), expansion: "Configure, Price, Quote".to_string() },
+                GlossaryEntry { abbreviation: "SSO".to_string(), expansion: "Single Sign-On".to_string() },
+                GlossaryEntry { abbreviation: "ERP".to_string(), expansion: "Enterprise Resource Planning".to_string() },
+            ],
+            style,
+        }
+    }
+
+    const TEXT: &str = "Run `CPQ --sync` first.\n```\nCPQ error\n```\nThe CPQS list and the CPQ editor crash; CPQ again. SSO (Okta) fails.";
+
+    #[test]
+    fn test_expand_first_prose_use() {
+        assert_eq!(
+            glossary(GlossaryStyle::Expand).apply(TEXT),
+            "Run `CPQ --sync` first.\n```\nCPQ error\n```\nThe CPQS list and the CPQ (Configure, Price, Quote) editor crash; CPQ again. SSO (Okta) fails."
+        );
+        // Spelled out by the author already
+        let text = "CPQ means Configure, Price, Quote here";
+        assert_eq!(glossary(GlossaryStyle::Expand).apply(text), text);
+    }
+
+    #[test]
+    fn test_urls_and_paths_are_left_alone() {
+        let text = "See https://wiki.example.com/CPQ and C:\\apps\\CPQ\\log.txt or /opt/SSO/conf.";
+        assert_eq!(glossary(GlossaryStyle::Expand).apply(text), text);
+        assert_eq!(glossary(GlossaryStyle::Footnote).apply(text), text);
+        assert_eq!(
+            glossary(GlossaryStyle::Expand).apply("Open https://example.com/CPQ, then CPQ."),
+            "Open https://example.com/CPQ, then CPQ (Configure, Price, Quote)."
+        );
+    }
+
+    #[test]
+    fn test_footnote_lists_used_abbreviations() {
+        assert_eq!(
+            glossary(GlossaryStyle::Footnote).apply(TEXT),
+            format!("{}\n\n## Glossary\n\n- **CPQ**: Configure, Price, Quote\n- **SSO**: Single Sign-On\n", TEXT)
+        );
+        assert_eq!(glossary(GlossaryStyle::Footnote).apply("No abbreviations"), "No abbreviations");
+        assert_eq!(Glossary::default().prompt_list(), None);
+    }
+
+    #[test]
+    fn test_for_session_falls_back_to_active_profile() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        let repo = SqliteProfileRepository::new(&conn);
+        for (id, abbreviation) in [("p-session", "CPQ"), ("p-active", "ERP")] {
+            repo.create(&QaProfile {
+                id: id.to_string(),
+                name: id.to_string(),
+                linear_config: None,
+                area_categories: vec![],
+                custom_fields: vec![],
+                title_conventions: None,
+                post_session_actions: vec![],
+                glossary: vec![GlossaryEntry { abbreviation: abbreviation.to_string(), expansion: "x".to_string() }],
+                glossary_style: GlossaryStyle::Expand,
+                created_at: "2024-01-01T00:00:00Z".to_string(),
+                updated_at: "2024-01-01T00:00:00Z".to_string(),
+            })
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO sessions (id, started_at, folder_path, profile_id) VALUES ('s-1', '2024-01-01T10:00:00Z', '/qa/s-1', 'p-session')",
+            [],
+        )
+        .unwrap();
+        SettingsRepository::new(&conn).set(ACTIVE_PROFILE_KEY, "p-active").unwrap();
+
+        assert_eq!(Glossary::for_session(&conn, Some("s-1")).prompt_list().unwrap(), "CPQ: x");
+        assert_eq!(Glossary::for_session(&conn, None).prompt_list().unwrap(), "ERP: x");
+    }
+}
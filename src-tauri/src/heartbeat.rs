@@ -0,0 +1,189 @@
+//! Heartbeat file for external monitoring.
+//!
+//! While a session is active, a small JSON [`Heartbeat`] is written to
+//! `heartbeat.json` in the storage root every [`HEARTBEAT_INTERVAL`]. It names
+//! the active session and bug and the time of the last capture, so dashboards
+//! and scripts can flag a session that has stopped producing captures. A
+//! heartbeat whose `writtenAt` is older than a few intervals means the app has
+//! stopped or hung. The file is removed when the session ends.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::annotation_windows::write_atomically;
+use crate::database::{CaptureOps, CaptureRepository};
+
+/// File name of the heartbeat in the storage root.
+pub const HEARTBEAT_FILE_NAME: &str = "heartbeat.json";
+
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the writer thread checks whether it should stop.
+const STOP_POLL: Duration = Duration::from_millis(250);
+
+/// Contents of `heartbeat.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Heartbeat {
+    pub session_id: String,
+    pub bug_id: Option<String>,
+    /// `created_at` of the session's newest capture
+    pub last_capture_at: Option<String>,
+    pub capture_count: usize,
+    pub written_at: String,
+    pub interval_secs: u64,
+    pub pid: u32,
+}
+
+/// Returned by `get_heartbeat_status`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartbeatStatus {
+    pub path: String,
+    /// Whether a heartbeat writer is running
+    pub active: bool,
+    pub last: Option<Heartbeat>,
+}
+
+pub fn heartbeat_path(storage_root: &Path) -> PathBuf {
+    storage_root.join(HEARTBEAT_FILE_NAME)
+}
+
+/// Current heartbeat for a session, stamped now.
+pub fn build(conn: &Connection, session_id: &str, bug_id: Option<String>) -> Result<Heartbeat, String> {
+    let captures = CaptureRepository::new(conn)
+        .list_by_session(session_id)
+        .map_err(|e| format!("Failed to list captures: {}", e))?;
+    Ok(Heartbeat {
+        session_id: session_id.to_string(),
+        bug_id,
+        last_capture_at: captures.iter().map(|c| c.created_at.clone()).max(),
+        capture_count: captures.len(),
+        written_at: Utc::now().to_rfc3339(),
+        interval_secs: HEARTBEAT_INTERVAL.as_secs(),
+        pid: std::process::id(),
+    })
+}
+
+pub fn write(path: &Path, heartbeat: &Heartbeat) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(heartbeat).map_err(|e| e.to_string())?;
+    write_atomically(path, json.as_bytes()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Writes the heartbeat on a background thread.
+///
+/// Dropping the struct stops the thread and removes the file.
+pub struct HeartbeatWriter {
+    path: PathBuf,
+    stop_flag: Arc<AtomicBool>,
+    last: Arc<Mutex<Option<Heartbeat>>>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl HeartbeatWriter {
+    /// Write `next()` to `path` now and then every `interval`. A tick where
+    /// `next` returns None (e.g. the session is gone) is skipped.
+    pub fn start<F>(path: PathBuf, interval: Duration, next: F) -> Self
+    where
+        F: Fn() -> Option<Heartbeat> + Send + 'static,
+    {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let last = Arc::new(Mutex::new(None));
+        let (flag, thread_last, thread_path) = (Arc::clone(&stop_flag), Arc::clone(&last), path.clone());
+
+        let worker = thread::spawn(move || {
+            while !flag.load(Ordering::Relaxed) {
+                if let Some(heartbeat) = next() {
+                    if let Err(e) = write(&thread_path, &heartbeat) {
+                        eprintln!("Warning: {}", e);
+                    }
+                    *thread_last.lock().unwrap() = Some(heartbeat);
+                }
+                let mut waited = Duration::ZERO;
+                while waited < interval && !flag.load(Ordering::Relaxed) {
+                    thread::sleep(STOP_POLL.min(interval - waited));
+                    waited += STOP_POLL;
+                }
+            }
+        });
+
+        Self { path, stop_flag, last, worker: Some(worker) }
+    }
+
+    pub fn status(&self) -> HeartbeatStatus {
+        HeartbeatStatus {
+            path: self.path.to_string_lossy().to_string(),
+            active: true,
+            last: self.last.lock().unwrap().clone(),
+        }
+    }
+}
+
+impl Drop for HeartbeatWriter {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_reports_last_capture() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/qa/s-1');
+             INSERT INTO captures (id, session_id, file_name, file_path, file_type, created_at) VALUES
+               ('c-1', 's-1', 'capture-001.png', '/qa/s-1/capture-001.png', 'screenshot', '2024-01-01T10:05:00Z'),
+               ('c-2', 's-1', 'capture-002.png', '/qa/s-1/capture-002.png', 'screenshot', '2024-01-01T10:09:00Z');",
+        )
+        .unwrap();
+
+        let heartbeat = build(&conn, "s-1", Some("b-1".to_string())).unwrap();
+        assert_eq!(heartbeat.last_capture_at.as_deref(), Some("2024-01-01T10:09:00Z"));
+        assert_eq!(heartbeat.capture_count, 2);
+        assert_eq!(heartbeat.interval_secs, 30);
+    }
+
+    #[test]
+    fn test_writer_writes_and_removes_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = heartbeat_path(dir.path());
+        let heartbeat = Heartbeat {
+            session_id: "s-1".to_string(),
+            bug_id: None,
+            last_capture_at: None,
+            capture_count: 0,
+            written_at: "2024-01-01T10:00:00Z".to_string(),
+            interval_secs: 30,
+            pid: 1,
+        };
+        let expected = heartbeat.clone();
+
+        let writer = HeartbeatWriter::start(path.clone(), Duration::from_secs(30), move || Some(heartbeat.clone()));
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while writer.status().last.is_none() && std::time::Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(writer.status().last, Some(expected.clone()));
+        let written: Heartbeat = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written, expected);
+
+        // Stops promptly even mid-interval
+        drop(writer);
+        assert!(!path.exists());
+    }
+}
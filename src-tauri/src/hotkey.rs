@@ -2,7 +2,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter};
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::capture_trigger::TriggerSource;
 
 /// Represents a hotkey action that can be triggered
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -13,6 +15,7 @@ pub enum HotkeyAction {
     EndBugCapture,
     OpenQuickNotepad,
     OpenSessionNotepad,
+    TakeScreenshot,
 }
 
 impl HotkeyAction {
@@ -24,6 +27,7 @@ impl HotkeyAction {
             HotkeyAction::EndBugCapture => "hotkey-end-bug-capture",
             HotkeyAction::OpenQuickNotepad => "hotkey-open-quick-notepad",
             HotkeyAction::OpenSessionNotepad => "hotkey-open-session-notepad",
+            HotkeyAction::TakeScreenshot => "hotkey-take-screenshot",
         }
     }
 
@@ -35,6 +39,7 @@ impl HotkeyAction {
             HotkeyAction::EndBugCapture => "End Bug Capture",
             HotkeyAction::OpenQuickNotepad => "Open Quick Notepad",
             HotkeyAction::OpenSessionNotepad => "Open Session Notepad",
+            HotkeyAction::TakeScreenshot => "Take Screenshot",
         }
     }
 
@@ -46,6 +51,7 @@ impl HotkeyAction {
             HotkeyAction::EndBugCapture => "hotkey.end_bug_capture",
             HotkeyAction::OpenQuickNotepad => "hotkey.open_quick_notepad",
             HotkeyAction::OpenSessionNotepad => "hotkey.open_session_notepad",
+            HotkeyAction::TakeScreenshot => "hotkey.take_screenshot",
         }
     }
 }
@@ -79,10 +85,23 @@ impl Default for HotkeyConfig {
             HotkeyAction::OpenSessionNotepad,
             "Ctrl+Alt+P".to_string(),
         );
+        shortcuts.insert(HotkeyAction::TakeScreenshot, "Ctrl+Alt+X".to_string());
         Self { shortcuts }
     }
 }
 
+impl HotkeyConfig {
+    /// This config, with any action it lacks taken from `base`.
+    pub fn with_missing_from(mut self, base: &HotkeyConfig) -> Self {
+        for (action, shortcut) in &base.shortcuts {
+            self.shortcuts
+                .entry(action.clone())
+                .or_insert_with(|| shortcut.clone());
+        }
+        self
+    }
+}
+
 /// Manages global hotkey registration and handling
 pub struct HotkeyManager {
     config: Arc<Mutex<HotkeyConfig>>,
@@ -147,9 +166,17 @@ impl HotkeyManager {
 
         let event_name = action.event_name().to_string();
         let app_clone = app.clone();
+        let takes_screenshot = *action == HotkeyAction::TakeScreenshot;
 
         app.global_shortcut()
-            .on_shortcut(shortcut, move |_app, _shortcut, _event| {
+            .on_shortcut(shortcut, move |_app, _shortcut, event| {
+                // The screenshot hotkey is handled here rather than in the
+                // frontend so the pending-capture marker carries the press time.
+                if takes_screenshot && event.state() == ShortcutState::Pressed {
+                    if let Err(e) = crate::take_screenshot(TriggerSource::Hotkey) {
+                        eprintln!("Failed to trigger screenshot from hotkey: {}", e);
+                    }
+                }
                 app_clone.emit(&event_name, ()).ok();
             })
             .map_err(|e| format!("Failed to register shortcut: {}", e))?;
@@ -173,12 +200,18 @@ impl HotkeyManager {
     }
 
     /// Update the hotkey configuration and re-register
+    ///
+    /// Actions missing from `new_config` keep their current binding, so a
+    /// caller that only knows some actions cannot drop the others.
     pub fn update_config(&self, app: &AppHandle, new_config: HotkeyConfig) -> Vec<Result<(), String>> {
         // Unregister existing hotkeys
         self.unregister_all(app).ok();
 
         // Update config
-        *self.config.lock().unwrap() = new_config;
+        {
+            let mut config = self.config.lock().unwrap();
+            *config = new_config.with_missing_from(&config);
+        }
 
         // Re-register with new config
         self.register_all(app)
@@ -209,6 +242,7 @@ impl HotkeyManager {
             HotkeyAction::EndBugCapture,
             HotkeyAction::OpenQuickNotepad,
             HotkeyAction::OpenSessionNotepad,
+            HotkeyAction::TakeScreenshot,
         ];
 
         for action in &actions {
@@ -272,6 +306,10 @@ mod tests {
             HotkeyAction::OpenSessionNotepad.event_name(),
             "hotkey-open-session-notepad"
         );
+        assert_eq!(
+            HotkeyAction::TakeScreenshot.event_name(),
+            "hotkey-take-screenshot"
+        );
     }
 
     #[test]
@@ -290,6 +328,7 @@ mod tests {
             HotkeyAction::OpenSessionNotepad.description(),
             "Open Session Notepad"
         );
+        assert_eq!(HotkeyAction::TakeScreenshot.description(), "Take Screenshot");
     }
 
     #[test]
@@ -315,13 +354,17 @@ mod tests {
             config.shortcuts.get(&HotkeyAction::OpenSessionNotepad),
             Some(&"Ctrl+Alt+P".to_string())
         );
+        assert_eq!(
+            config.shortcuts.get(&HotkeyAction::TakeScreenshot),
+            Some(&"Ctrl+Alt+X".to_string())
+        );
     }
 
     #[test]
     fn test_hotkey_manager_creation() {
         let manager = HotkeyManager::new();
         let config = manager.get_config();
-        assert_eq!(config.shortcuts.len(), 5);
+        assert_eq!(config.shortcuts.len(), 6);
     }
 
     #[test]
@@ -375,10 +418,11 @@ mod tests {
             HotkeyAction::EndBugCapture,
             HotkeyAction::OpenQuickNotepad,
             HotkeyAction::OpenSessionNotepad,
+            HotkeyAction::TakeScreenshot,
         ];
 
         let event_names: HashSet<_> = actions.iter().map(|a| a.event_name()).collect();
-        assert_eq!(event_names.len(), 5);
+        assert_eq!(event_names.len(), 6);
     }
 
     #[test]
@@ -390,10 +434,11 @@ mod tests {
             HotkeyAction::EndBugCapture,
             HotkeyAction::OpenQuickNotepad,
             HotkeyAction::OpenSessionNotepad,
+            HotkeyAction::TakeScreenshot,
         ];
 
         let descriptions: HashSet<_> = actions.iter().map(|a| a.description()).collect();
-        assert_eq!(descriptions.len(), 5);
+        assert_eq!(descriptions.len(), 6);
     }
 
     #[test]
@@ -406,4 +451,23 @@ mod tests {
             config2.shortcuts.get(&HotkeyAction::ToggleSession)
         );
     }
+
+    #[test]
+    fn test_partial_config_keeps_missing_actions() {
+        let mut shortcuts = HashMap::new();
+        shortcuts.insert(HotkeyAction::ToggleSession, "Ctrl+Alt+T".to_string());
+        let partial = HotkeyConfig { shortcuts };
+
+        let merged = partial.with_missing_from(&HotkeyConfig::default());
+
+        assert_eq!(merged.shortcuts.len(), 6);
+        assert_eq!(
+            merged.shortcuts.get(&HotkeyAction::ToggleSession),
+            Some(&"Ctrl+Alt+T".to_string())
+        );
+        assert_eq!(
+            merged.shortcuts.get(&HotkeyAction::TakeScreenshot),
+            Some(&"Ctrl+Alt+X".to_string())
+        );
+    }
 }
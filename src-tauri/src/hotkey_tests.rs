@@ -14,6 +14,7 @@ mod integration_tests {
         assert!(config.shortcuts.contains_key(&HotkeyAction::EndBugCapture));
         assert!(config.shortcuts.contains_key(&HotkeyAction::OpenQuickNotepad));
         assert!(config.shortcuts.contains_key(&HotkeyAction::OpenSessionNotepad));
+        assert!(config.shortcuts.contains_key(&HotkeyAction::TakeScreenshot));
     }
 
     #[test]
@@ -44,6 +45,7 @@ mod integration_tests {
             (HotkeyAction::EndBugCapture, "hotkey-end-bug-capture"),
             (HotkeyAction::OpenQuickNotepad, "hotkey-open-quick-notepad"),
             (HotkeyAction::OpenSessionNotepad, "hotkey-open-session-notepad"),
+            (HotkeyAction::TakeScreenshot, "hotkey-take-screenshot"),
         ];
 
         for (action, expected_event) in actions {
@@ -59,6 +61,7 @@ mod integration_tests {
             (HotkeyAction::EndBugCapture, "End Bug Capture"),
             (HotkeyAction::OpenQuickNotepad, "Open Quick Notepad"),
             (HotkeyAction::OpenSessionNotepad, "Open Session Notepad"),
+            (HotkeyAction::TakeScreenshot, "Take Screenshot"),
         ];
 
         for (action, expected_desc) in actions {
@@ -122,6 +125,10 @@ mod integration_tests {
             config.shortcuts.get(&HotkeyAction::OpenSessionNotepad).unwrap(),
             "Ctrl+Alt+P"
         );
+        assert_eq!(
+            config.shortcuts.get(&HotkeyAction::TakeScreenshot).unwrap(),
+            "Ctrl+Alt+X"
+        );
     }
 
     #[test]
@@ -137,6 +144,7 @@ mod integration_tests {
             HotkeyAction::EndBugCapture,
             HotkeyAction::OpenQuickNotepad,
             HotkeyAction::OpenSessionNotepad,
+            HotkeyAction::TakeScreenshot,
         ] {
             assert_eq!(
                 original_config.shortcuts.get(&action),
@@ -153,6 +161,7 @@ mod integration_tests {
             (HotkeyAction::EndBugCapture, "\"end_bug_capture\""),
             (HotkeyAction::OpenQuickNotepad, "\"open_quick_notepad\""),
             (HotkeyAction::OpenSessionNotepad, "\"open_session_notepad\""),
+            (HotkeyAction::TakeScreenshot, "\"take_screenshot\""),
         ];
 
         for (action, expected_json) in test_cases {
@@ -170,7 +179,7 @@ mod integration_tests {
         let config = HotkeyConfig::default();
 
         // If a new action is added but not included in the default config, this test will fail
-        let expected_count = 5; // Current number of actions
+        let expected_count = 6; // Current number of actions
         assert_eq!(config.shortcuts.len(), expected_count);
     }
 
@@ -268,6 +277,7 @@ mod integration_tests {
             HotkeyAction::EndBugCapture,
             HotkeyAction::OpenQuickNotepad,
             HotkeyAction::OpenSessionNotepad,
+            HotkeyAction::TakeScreenshot,
         ] {
             assert_eq!(
                 config1.shortcuts.get(&action),
@@ -0,0 +1,177 @@
+//! Re-rendering freehand ink onto a screenshot.
+//!
+//! The annotation window saves pen and eraser strokes as an [`InkLayer`] so
+//! they stay editable, and the annotated copy is drawn from the original
+//! screenshot with [`render`]. Pen strokes widen with pressure, as they do
+//! while inking with a Surface pen or Wacom stylus; eraser strokes uncover the
+//! original screenshot under earlier strokes. Stamps from the team's stamp
+//! library (see [`crate::stamp_library`]) are drawn over the strokes.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::Path;
+
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+
+use crate::database::{InkLayer, InkPoint, InkStroke, InkTool};
+
+/// Share of the stroke width drawn at zero pressure.
+const MIN_WIDTH_SHARE: f32 = 0.25;
+
+/// Radius of `stroke` at `pressure`, never below half a pixel.
+fn radius(stroke: &InkStroke, pressure: f32) -> f32 {
+    (stroke.width * (MIN_WIDTH_SHARE + (1.0 - MIN_WIDTH_SHARE) * pressure) / 2.0).max(0.5)
+}
+
+/// `#RRGGBB` as an opaque colour; black when unreadable.
+fn parse_color(hex: &str) -> Rgba<u8> {
+    let channel = |i: usize| {
+        hex.trim_start_matches('#')
+            .get(i..i + 2)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .unwrap_or(0)
+    };
+    Rgba([channel(0), channel(2), channel(4), 255])
+}
+
+/// Paint a disc of radius `r` around `(cx, cy)`: with `color`, or with the
+/// pixels of `base` when erasing.
+fn stamp(out: &mut RgbaImage, base: &RgbaImage, cx: f32, cy: f32, r: f32, color: Option<Rgba<u8>>) {
+    let (width, height) = out.dimensions();
+    let x0 = (cx - r).floor().max(0.0) as u32;
+    let y0 = (cy - r).floor().max(0.0) as u32;
+    let x1 = ((cx + r).ceil().max(0.0) as u32).min(width);
+    let y1 = ((cy + r).ceil().max(0.0) as u32).min(height);
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let dx = x as f32 + 0.5 - cx;
+            let dy = y as f32 + 0.5 - cy;
+            if dx * dx + dy * dy <= r * r {
+                out.put_pixel(x, y, color.unwrap_or(*base.get_pixel(x, y)));
+            }
+        }
+    }
+}
+
+/// `base` with the strokes of `layer` drawn over it in order, then its
+/// stamps, whose images `stamps` maps by stamp id. Stamps missing from the
+/// map are skipped.
+pub fn render(base: &RgbaImage, layer: &InkLayer, stamps: &HashMap<String, RgbaImage>) -> RgbaImage {
+    let mut out = base.clone();
+    for stroke in &layer.strokes {
+        let color = match stroke.tool {
+            InkTool::Pen => Some(parse_color(&stroke.color)),
+            InkTool::Eraser => None,
+        };
+        let mut paint = |p: InkPoint| stamp(&mut out, base, p.x, p.y, radius(stroke, p.pressure), color);
+
+        let Some(&first) = stroke.points.first() else {
+            continue;
+        };
+        paint(first);
+        for pair in stroke.points.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let length = ((to.x - from.x).powi(2) + (to.y - from.y).powi(2)).sqrt();
+            // Stamps half a radius apart leave no gaps along the line
+            let spacing = (radius(stroke, from.pressure.min(to.pressure)) / 2.0).max(0.5);
+            let steps = (length / spacing).ceil().max(1.0) as u32;
+            for step in 1..=steps {
+                let t = step as f32 / steps as f32;
+                paint(InkPoint {
+                    x: from.x + (to.x - from.x) * t,
+                    y: from.y + (to.y - from.y) * t,
+                    pressure: from.pressure + (to.pressure - from.pressure) * t,
+                });
+            }
+        }
+    }
+    for placement in &layer.stamps {
+        let Some(image) = stamps.get(&placement.stamp_id) else {
+            continue;
+        };
+        let scaled = imageops::resize(
+            image,
+            placement.width.round().max(1.0) as u32,
+            placement.height.round().max(1.0) as u32,
+            FilterType::Triangle,
+        );
+        imageops::overlay(&mut out, &scaled, placement.x.round() as i64, placement.y.round() as i64);
+    }
+    out
+}
+
+/// Encode `image` in the format `path`'s extension names (PNG when unknown).
+pub fn encode_for(path: &Path, image: RgbaImage) -> Result<Vec<u8>, String> {
+    let format = ImageFormat::from_path(path).unwrap_or(ImageFormat::Png);
+    let image = match format {
+        // JPEG has no alpha channel
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(image).to_rgb8()),
+        _ => DynamicImage::ImageRgba8(image),
+    };
+    let mut bytes = Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, format)
+        .map_err(|e| format!("Failed to encode {}: {}", path.display(), e))?;
+    Ok(bytes.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::StampPlacement;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+    fn line(tool: InkTool, y: f32, pressure: f32) -> InkStroke {
+        InkStroke {
+            tool,
+            color: "#FF0000".to_string(),
+            width: 8.0,
+            points: vec![InkPoint { x: 2.0, y, pressure }, InkPoint { x: 30.0, y, pressure }],
+        }
+    }
+
+    #[test]
+    fn test_pressure_widens_pen_and_eraser_uncovers_base() {
+        let base = RgbaImage::from_pixel(32, 32, WHITE);
+
+        let light = render(&base, &InkLayer { strokes: vec![line(InkTool::Pen, 10.0, 0.0)], ..Default::default() }, &HashMap::new());
+        let firm = render(&base, &InkLayer { strokes: vec![line(InkTool::Pen, 10.0, 1.0)], ..Default::default() }, &HashMap::new());
+        assert_eq!(*light.get_pixel(16, 9), RED);
+        assert_eq!(*light.get_pixel(16, 12), WHITE);
+        assert_eq!(*firm.get_pixel(16, 12), RED);
+        assert_eq!(*firm.get_pixel(16, 15), WHITE);
+
+        let erased = InkLayer {
+            strokes: vec![line(InkTool::Pen, 10.0, 1.0), InkStroke {
+                points: vec![InkPoint { x: 16.0, y: 2.0, pressure: 1.0 }, InkPoint { x: 16.0, y: 20.0, pressure: 1.0 }],
+                ..line(InkTool::Eraser, 0.0, 1.0)
+            }],
+            ..Default::default()
+        };
+        let erased = render(&base, &erased, &HashMap::new());
+        assert_eq!(*erased.get_pixel(16, 10), WHITE);
+        assert_eq!(*erased.get_pixel(6, 10), RED);
+    }
+
+    #[test]
+    fn test_stamps_are_scaled_and_blended_over_strokes() {
+        let base = RgbaImage::from_pixel(32, 32, WHITE);
+        let stamp = RgbaImage::from_fn(2, 2, |x, _| if x == 0 { RED } else { Rgba([0, 0, 255, 0]) });
+        let layer = InkLayer {
+            strokes: vec![line(InkTool::Pen, 10.0, 1.0)],
+            stamps: vec![
+                StampPlacement { stamp_id: "s-1".to_string(), x: 20.0, y: 4.0, width: 8.0, height: 8.0 },
+                StampPlacement { stamp_id: "deleted".to_string(), x: 0.0, y: 0.0, width: 8.0, height: 8.0 },
+            ],
+        };
+        let out = render(&base, &layer, &HashMap::from([("s-1".to_string(), stamp)]));
+        assert_eq!(*out.get_pixel(20, 5), RED);
+        // The transparent half leaves the stroke under it visible
+        assert_eq!(*out.get_pixel(27, 10), RED);
+        assert_eq!(*out.get_pixel(27, 5), WHITE);
+        assert_eq!(*out.get_pixel(1, 1), WHITE);
+    }
+}
@@ -0,0 +1,176 @@
+//! Keeping feedback and questions apart from defects in exports and tickets.
+//!
+//! Each bug has a [`BugType`]. `tickets-ready.md` groups its items into one
+//! section per type ("Bugs", "Feature Requests", "Feedback", "Questions")
+//! when a session has more than one type, and tickets filed for a bug carry
+//! the labels configured for its type, so feedback lands in the PM's triage
+//! queue rather than the defect backlog. [`ticket_batches`] groups a
+//! session's unfiled items the same way for filing one batch per type.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Bug, BugType, SettingsOps, SettingsRepository};
+
+/// Settings key holding [`TypeLabelSettings`] as JSON.
+pub const TYPE_LABELS_KEY: &str = "ticketing.type_labels";
+
+/// Types in the order their sections and batches appear.
+pub const SECTION_ORDER: [BugType; 4] = [BugType::Bug, BugType::Feature, BugType::Feedback, BugType::Question];
+
+/// Section heading for items of `bug_type`.
+pub fn section_heading(bug_type: &BugType) -> &'static str {
+    match bug_type {
+        BugType::Bug => "Bugs",
+        BugType::Feature => "Feature Requests",
+        BugType::Feedback => "Feedback",
+        BugType::Question => "Questions",
+    }
+}
+
+/// Ticket label names added for each item type. Linear tickets get the IDs
+/// of the team's labels with these names.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TypeLabelSettings {
+    pub bug: Vec<String>,
+    pub feature: Vec<String>,
+    pub feedback: Vec<String>,
+    pub question: Vec<String>,
+}
+
+impl Default for TypeLabelSettings {
+    fn default() -> Self {
+        Self {
+            bug: vec!["bug".to_string()],
+            feature: vec!["feature-request".to_string()],
+            feedback: vec!["feedback".to_string()],
+            question: vec!["question".to_string()],
+        }
+    }
+}
+
+impl TypeLabelSettings {
+    /// Stored settings; missing or unreadable settings give the defaults.
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(TYPE_LABELS_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(TYPE_LABELS_KEY, &json)
+            .map_err(|e| format!("Failed to save type labels: {}", e))
+    }
+
+    pub fn labels_for(&self, bug_type: &BugType) -> &[String] {
+        match bug_type {
+            BugType::Bug => &self.bug,
+            BugType::Feature => &self.feature,
+            BugType::Feedback => &self.feedback,
+            BugType::Question => &self.question,
+        }
+    }
+
+    /// Add the labels for `bug_type` to `labels`, skipping ones already there.
+    pub fn apply(&self, bug_type: &BugType, labels: &mut Vec<String>) {
+        for label in self.labels_for(bug_type) {
+            if !labels.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+                labels.push(label.clone());
+            }
+        }
+    }
+}
+
+/// Items of one type to file together.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketBatch {
+    pub bug_type: BugType,
+    pub heading: String,
+    pub labels: Vec<String>,
+    pub bug_ids: Vec<String>,
+}
+
+/// `bugs` without a ticket, one batch per type in [`SECTION_ORDER`]. Scratch
+/// bugs are left out until promoted.
+pub fn ticket_batches(bugs: &[Bug], settings: &TypeLabelSettings) -> Vec<TicketBatch> {
+    SECTION_ORDER
+        .iter()
+        .filter_map(|bug_type| {
+            let bug_ids: Vec<String> = bugs
+                .iter()
+                .filter(|bug| bug.bug_type == *bug_type && bug.external_ticket_id.is_none() && !bug.is_scratch())
+                .map(|bug| bug.id.clone())
+                .collect();
+            (!bug_ids.is_empty()).then(|| TicketBatch {
+                bug_type: bug_type.clone(),
+                heading: section_heading(bug_type).to_string(),
+                labels: settings.labels_for(bug_type).to_vec(),
+                bug_ids,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::BugStatus;
+
+    fn bug(id: &str, number: i32, bug_type: BugType) -> Bug {
+        Bug {
+            id: id.to_string(),
+            session_id: "s-1".to_string(),
+            bug_number: number,
+            display_id: format!("BUG-{:03}", number),
+            bug_type,
+            status: BugStatus::Ready,
+            folder_path: format!("/qa/s-1/bug_{:03}", number),
+            created_at: "2024-01-01T10:00:00Z".to_string(),
+            updated_at: "2024-01-01T10:00:00Z".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ticket_batches_per_type() {
+        let mut filed = bug("b-2", 2, BugType::Bug);
+        filed.external_ticket_id = Some("issue-1".to_string());
+        let bugs = vec![
+            bug("b-1", 1, BugType::Question),
+            filed,
+            bug("b-3", 3, BugType::Bug),
+            bug("b-4", 4, BugType::Feedback),
+            bug("b-5", 0, BugType::Bug),
+        ];
+
+        let batches = ticket_batches(&bugs, &TypeLabelSettings::default());
+        let summary: Vec<(&str, Vec<&str>)> = batches
+            .iter()
+            .map(|b| (b.heading.as_str(), b.bug_ids.iter().map(String::as_str).collect()))
+            .collect();
+        assert_eq!(summary, vec![("Bugs", vec!["b-3"]), ("Feedback", vec!["b-4"]), ("Questions", vec!["b-1"])]);
+        assert_eq!(batches[1].labels, vec!["feedback"]);
+    }
+
+    #[test]
+    fn test_apply_labels_and_settings() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        assert_eq!(TypeLabelSettings::load(&conn), TypeLabelSettings::default());
+
+        let settings = TypeLabelSettings { feedback: vec!["pm-triage".to_string(), "UX".to_string()], ..Default::default() };
+        settings.save(&conn).unwrap();
+        let settings = TypeLabelSettings::load(&conn);
+
+        let mut labels = vec!["ux".to_string()];
+        settings.apply(&BugType::Feedback, &mut labels);
+        assert_eq!(labels, vec!["ux", "pm-triage"]);
+    }
+}
@@ -3,6 +3,8 @@ pub mod database;
 pub mod platform;
 pub mod session_manager;
 mod session_summary;
+mod summary_template;
+mod time_format;
 mod session_json;
 mod hotkey;
 mod claude_cli;
@@ -10,6 +12,72 @@ mod ticketing;
 mod profile;
 mod capture_watcher;
 mod clipboard_watcher;
+mod demo_data;
+mod session_lock;
+mod notes_lock;
+mod annotation_windows;
+mod external_editor;
+mod media_offload;
+mod video_metadata;
+mod video_frames;
+mod console_export;
+mod bug_metadata;
+mod metadata_sync;
+mod session_archive;
+mod session_import;
+mod archive_viewer;
+mod deep_link;
+mod post_session;
+mod staging_watcher;
+mod ui_tree;
+mod window_capture;
+mod window_theme;
+mod window_geometry;
+mod events;
+mod command_registry;
+mod command_permissions;
+mod storage_paths;
+mod settings_schema;
+mod bug_auto_stop;
+mod capture_routing;
+mod bug_split;
+mod session_environment;
+mod export_hooks;
+mod capture_naming;
+mod capture_quota;
+mod capture_journal;
+mod capture_trigger;
+mod display_info;
+mod heartbeat;
+mod metrics;
+mod notes_mirror;
+mod description_lint;
+mod glossary;
+mod symbolication;
+mod crash_dumps;
+mod perf_capture;
+mod network_snapshot;
+mod locale_info;
+mod display_capture;
+mod cursor_overlay;
+mod capture_diff;
+mod native_capture;
+mod folder_tools;
+mod screen_recording;
+mod foreground_app;
+mod capture_triage;
+mod item_types;
+mod bug_timing;
+mod tone_filter;
+mod annotation_queue;
+mod ink_render;
+mod pdf_report;
+mod stamp_library;
+mod quick_toggles;
+mod render_target;
+mod template_check;
+mod template_library;
+mod template_variables;
 
 #[cfg(test)]
 mod hotkey_tests;
@@ -18,17 +86,20 @@ use std::sync::{Arc, Mutex};
 use template::TemplateManager;
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri::image::Image;
-use tauri::menu::{Menu, MenuItemBuilder};
+use tauri::menu::{CheckMenuItem, CheckMenuItemBuilder, Menu, MenuItemBuilder, PredefinedMenuItem};
 use tauri::tray::{TrayIcon, TrayIconBuilder, TrayIconEvent};
 use tauri::{Manager, Emitter, AppHandle};
 use session_manager::{SessionManager, EventEmitter, RealFileSystem};
 use hotkey::{HotkeyManager, HotkeyConfig};
-use ticketing::{LinearIntegration, TicketingIntegration};
+use ticketing::TicketingIntegration;
 use database::DbState;
 
 // Global template manager
 static TEMPLATE_MANAGER: Mutex<Option<TemplateManager>> = Mutex::new(None);
 
+// Global library of named bug templates
+static TEMPLATE_LIBRARY: Mutex<Option<template_library::TemplateLibrary>> = Mutex::new(None);
+
 // Global session manager
 static SESSION_MANAGER: Mutex<Option<Arc<SessionManager>>> = Mutex::new(None);
 
@@ -38,6 +109,9 @@ static HOTKEY_MANAGER: Mutex<Option<Arc<HotkeyManager>>> = Mutex::new(None);
 // Global tray icon (must persist for app lifetime or it gets dropped/destroyed)
 static TRAY_ICON: Mutex<Option<TrayIcon>> = Mutex::new(None);
 
+// Checkable quick toggle items of the current tray menu, by settings key
+static TRAY_QUICK_TOGGLES: Mutex<Vec<(&'static str, CheckMenuItem<tauri::Wry>)>> = Mutex::new(Vec::new());
+
 // Global ticketing integration
 static TICKETING_INTEGRATION: Mutex<Option<Arc<dyn TicketingIntegration>>> = Mutex::new(None);
 
@@ -47,9 +121,66 @@ static CAPTURE_BRIDGE: Mutex<Option<Box<dyn platform::CaptureBridge>>> = Mutex::
 // Global capture watcher (monitors _captures/ for new files)
 static CAPTURE_WATCHER: Mutex<Option<capture_watcher::CaptureWatcher>> = Mutex::new(None);
 
+// Global crash dump watcher (WER dumps of the app under test, per session)
+static CRASH_DUMP_WATCHER: Mutex<Option<crash_dumps::CrashDumpWatcher>> = Mutex::new(None);
+
+// Last mouse click, for the click marker in native screenshots (only while enabled)
+static CLICK_TRACKER: Mutex<Option<cursor_overlay::ClickTracker>> = Mutex::new(None);
+
+// Screen recording in progress (see `screen_recording`; one at a time)
+static SCREEN_RECORDING: Mutex<Option<screen_recording::ActiveRecording>> = Mutex::new(None);
+
+// Performance sampler for the bug being captured (see `perf_capture`)
+static PERF_SAMPLER: Mutex<Option<perf_capture::PerfSampler>> = Mutex::new(None);
+
+// Global staging-folder watcher (hot import; runs with or without a session)
+static STAGING_WATCHER: Mutex<Option<staging_watcher::StagingWatcher>> = Mutex::new(None);
+
 // Global clipboard watcher (polls clipboard for new screenshot images)
 static CLIPBOARD_WATCHER: Mutex<Option<clipboard_watcher::ClipboardWatcher>> = Mutex::new(None);
 
+// Opt-in metrics endpoint (see `metrics`)
+static METRICS_SERVER: Mutex<Option<metrics::MetricsServer>> = Mutex::new(None);
+
+// Heartbeat file writer (only active during a session)
+static HEARTBEAT: Mutex<Option<heartbeat::HeartbeatWriter>> = Mutex::new(None);
+
+// Global notes lease registry (one lease per notes document, shared across windows)
+static NOTES_LOCKS: Mutex<Option<notes_lock::NotesLockRegistry>> = Mutex::new(None);
+
+// Global annotation window registry (lets session end close orphaned annotation windows)
+static ANNOTATION_WINDOWS: Mutex<Option<annotation_windows::AnnotationWindowRegistry>> = Mutex::new(None);
+
+// Screenshots a reviewer is annotating one after another (one queue at a time)
+static ANNOTATION_QUEUE: Mutex<Option<annotation_queue::AnnotationQueue>> = Mutex::new(None);
+
+// Last known geometry of open secondary windows, keyed by label (saved when the window closes)
+static WINDOW_GEOMETRY: Mutex<Option<std::collections::HashMap<String, window_geometry::WindowGeometry>>> = Mutex::new(None);
+
+// Global external-editor watchers, keyed by capture ID (dropped when the session ends)
+static EXTERNAL_EDIT_WATCHERS: Mutex<Option<std::collections::HashMap<String, external_editor::ExternalEditWatcher>>> = Mutex::new(None);
+
+// Global metadata.json sync worker (debounced; started in setup)
+static METADATA_SYNC: Mutex<Option<metadata_sync::MetadataSyncer>> = Mutex::new(None);
+
+// Global retry worker for tickets that failed to send (started in setup)
+static TICKET_QUEUE_WORKER: Mutex<Option<ticketing::TicketQueueWorker>> = Mutex::new(None);
+
+// Global notes file mirror (debounced, keyed by notes_mirror::NotesOwner::key; started in setup)
+static NOTES_MIRROR: Mutex<Option<metadata_sync::MetadataSyncer>> = Mutex::new(None);
+
+// Global read-only archive viewer workspace (a `.qacap` opened without importing it)
+static ARCHIVE_VIEWER: Mutex<Option<archive_viewer::ArchiveWorkspace>> = Mutex::new(None);
+
+// Image files the user picked in a native dialog (and their annotated copies), allowed outside session storage
+static PICKED_IMAGE_FILES: Mutex<Option<std::collections::HashSet<std::path::PathBuf>>> = Mutex::new(None);
+
+// Bug most recently ended by the auto-stop check (cleared by undo_auto_stop)
+static LAST_AUTO_STOP: Mutex<Option<bug_auto_stop::AutoStopped>> = Mutex::new(None);
+
+// Global deep link received before the frontend was ready (taken once on load)
+static PENDING_LAUNCH_TARGET: Mutex<Option<deep_link::LaunchTarget>> = Mutex::new(None);
+
 // Tauri event emitter implementation
 struct TauriEventEmitter {
     app_handle: Arc<Mutex<Option<AppHandle>>>,
@@ -99,20 +230,177 @@ fn set_custom_template_path(path: Option<String>) -> Result<(), String> {
 }
 
 #[tauri::command]
-fn render_bug_template(bug_data: serde_json::Value) -> Result<String, String> {
-    let mut manager_guard = TEMPLATE_MANAGER.lock().unwrap();
-
-    if manager_guard.is_none() {
-        *manager_guard = Some(TemplateManager::new());
-    }
-
-    let manager = manager_guard.as_ref().unwrap();
+fn render_bug_template(bug_data: serde_json::Value, db_state: tauri::State<'_, DbState>) -> Result<String, String> {
+    use rusqlite::OptionalExtension;
 
     // Convert JSON to BugData
     let bug: template::BugData = serde_json::from_value(bug_data)
         .map_err(|e| format!("Failed to parse bug data: {}", e))?;
 
-    manager.render(&bug)
+    let render = {
+        let conn = db_state.connection();
+        let session_id: Option<String> = conn
+            .query_row(
+                "SELECT session_id FROM bugs WHERE folder_path = ?1",
+                rusqlite::params![bug.folder_path],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to query bug: {}", e))?;
+        BugRender::load(&conn, bug, session_id.as_deref())
+    };
+
+    render.render()
+}
+
+/// Content of the named template selected for a bug of `bug_type` in
+/// `session_id`, if any.
+fn selected_bug_template(conn: &rusqlite::Connection, bug_type: &str, session_id: Option<&str>) -> Option<String> {
+    let selection = template_library::TemplateSelection::load(conn);
+    let name = selection.resolve_for_session(conn, bug_type, session_id)?;
+    TEMPLATE_LIBRARY.lock().unwrap().as_ref()?.selected_content(name)
+}
+
+/// Run `f` with the named template library.
+fn with_template_library<T>(
+    f: impl FnOnce(&template_library::TemplateLibrary) -> Result<T, String>,
+) -> Result<T, String> {
+    let guard = TEMPLATE_LIBRARY.lock().unwrap();
+    let library = guard.as_ref().ok_or("Template library not initialized")?;
+    f(library)
+}
+
+#[tauri::command]
+fn list_bug_templates(db_state: tauri::State<'_, DbState>) -> Result<Vec<template_library::NamedTemplate>, String> {
+    let selection = template_library::TemplateSelection::load(&db_state.connection());
+    with_template_library(|library| Ok(library.list(&selection)))
+}
+
+#[tauri::command]
+fn get_bug_template(name: String) -> Result<String, String> {
+    with_template_library(|library| library.read(&name))
+}
+
+/// Add a named template, starting from the default template when no content
+/// is given.
+#[tauri::command]
+fn create_bug_template(name: String, content: Option<String>) -> Result<String, String> {
+    let content = content.unwrap_or_else(|| template::DEFAULT_TEMPLATE.to_string());
+    with_template_library(|library| library.create(&name, &content))
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn update_bug_template(name: String, content: String) -> Result<String, String> {
+    with_template_library(|library| library.update(&name, &content))
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn rename_bug_template(name: String, new_name: String, db_state: tauri::State<'_, DbState>) -> Result<String, String> {
+    let conn = db_state.connection();
+    let mut selection = template_library::TemplateSelection::load(&conn);
+    let path = with_template_library(|library| library.rename(&name, &new_name, &mut selection))?;
+    selection.save(&conn)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn delete_bug_template(name: String, db_state: tauri::State<'_, DbState>) -> Result<(), String> {
+    let conn = db_state.connection();
+    let mut selection = template_library::TemplateSelection::load(&conn);
+    with_template_library(|library| library.delete(&name, &mut selection))?;
+    selection.save(&conn)
+}
+
+/// Keys of the active profile's custom fields, which templates may use as
+/// `{key}` placeholders.
+fn active_profile_field_keys(conn: &rusqlite::Connection) -> Vec<String> {
+    use database::{SettingsOps, SettingsRepository};
+    use profile::{ProfileRepository, SqliteProfileRepository};
+
+    SettingsRepository::new(conn)
+        .get(profile::ACTIVE_PROFILE_KEY)
+        .ok()
+        .flatten()
+        .and_then(|id| SqliteProfileRepository::new(conn).get(&id).ok().flatten())
+        .map(|profile| profile.custom_fields.into_iter().map(|field| field.key).collect())
+        .unwrap_or_default()
+}
+
+/// Run `f` with the shared TemplateManager, creating it if needed.
+fn with_template_manager<T>(f: impl FnOnce(&TemplateManager) -> T) -> T {
+    let mut manager_guard = TEMPLATE_MANAGER.lock().unwrap();
+    f(manager_guard.get_or_insert_with(TemplateManager::new))
+}
+
+/// Values of the `{var.<name>}` template variables.
+fn template_variable_values(conn: &rusqlite::Connection) -> template::VariableValues {
+    let registered = with_template_manager(|manager| manager.variables().to_vec());
+    template_variables::values(conn, &registered)
+}
+
+/// Partials, variables and custom fields a template may use.
+fn template_known_names(conn: &rusqlite::Connection) -> template_check::KnownNames {
+    let custom_field_keys = active_profile_field_keys(conn);
+    with_template_manager(|manager| template_variables::known_names(conn, manager, custom_field_keys))
+}
+
+/// Unknown variables and syntax errors in a template being edited.
+#[tauri::command]
+fn validate_template(content: String, db_state: tauri::State<'_, DbState>) -> Vec<template_check::TemplateIssue> {
+    template_check::validate(&content, &template_known_names(&db_state.connection()))
+}
+
+/// Bug fields, variables, custom fields and partials templates can use, for
+/// the template editor.
+#[tauri::command]
+fn list_template_variables(db_state: tauri::State<'_, DbState>) -> Vec<template_variables::AvailableVariable> {
+    let conn = db_state.connection();
+    let custom_field_keys = active_profile_field_keys(&conn);
+    with_template_manager(|manager| template_variables::available(&conn, manager, &custom_field_keys))
+}
+
+#[tauri::command]
+fn get_custom_template_variables(db_state: tauri::State<'_, DbState>) -> std::collections::BTreeMap<String, String> {
+    template_variables::load_custom(&db_state.connection())
+}
+
+/// Replace the user-defined `{var.<name>}` variables.
+#[tauri::command]
+fn set_custom_template_variables(
+    variables: std::collections::BTreeMap<String, String>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let registered = with_template_manager(|manager| manager.variables().to_vec());
+    template_variables::save_custom(&db_state.connection(), &variables, &registered)
+}
+
+/// Render a template being edited against `sample_bug`, or the built-in
+/// sample bug when none is given.
+#[tauri::command]
+fn preview_template(
+    content: String,
+    sample_bug: Option<template::BugData>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<template_check::TemplatePreview, String> {
+    let bug = sample_bug.unwrap_or_else(template_check::sample_bug);
+    let conn = db_state.connection();
+    let (variables, known) = (template_variable_values(&conn), template_known_names(&conn));
+    with_template_manager(|manager| template_check::preview(manager, &content, &bug, &variables, &known))
+}
+
+#[tauri::command]
+fn get_template_selection(db_state: tauri::State<'_, DbState>) -> template_library::TemplateSelection {
+    template_library::TemplateSelection::load(&db_state.connection())
+}
+
+#[tauri::command]
+fn set_template_selection(
+    selection: template_library::TemplateSelection,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    selection.save(&db_state.connection())
 }
 
 #[tauri::command]
@@ -189,6 +477,51 @@ fn reset_template_to_default() -> Result<(), String> {
     manager.set_custom_template_path(None)
 }
 
+#[tauri::command]
+fn get_summary_template_source(db_state: tauri::State<'_, DbState>) -> Result<String, String> {
+    let conn = db_state.connection();
+    Ok(summary_template::load_summary_template(&conn))
+}
+
+#[tauri::command]
+fn save_custom_summary_template(
+    content: String,
+    db_state: tauri::State<'_, DbState>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    use database::{SettingsRepository, SettingsOps};
+
+    let data_dir = app.path().app_data_dir().unwrap_or_else(|_| {
+        std::env::current_dir().unwrap().join("data")
+    });
+
+    let templates_dir = data_dir.join("templates");
+    std::fs::create_dir_all(&templates_dir)
+        .map_err(|e| format!("Failed to create templates directory: {}", e))?;
+
+    let custom_template_path = templates_dir.join("custom_session_summary.md");
+    std::fs::write(&custom_template_path, &content)
+        .map_err(|e| format!("Failed to save custom summary template: {}", e))?;
+
+    let path = custom_template_path.to_string_lossy().to_string();
+    let conn = db_state.connection();
+    SettingsRepository::new(&conn)
+        .set(summary_template::SUMMARY_TEMPLATE_PATH_KEY, &path)
+        .map_err(|e| e.to_string())?;
+
+    Ok(path)
+}
+
+#[tauri::command]
+fn reset_summary_template_to_default(db_state: tauri::State<'_, DbState>) -> Result<(), String> {
+    use database::{SettingsRepository, SettingsOps};
+
+    let conn = db_state.connection();
+    SettingsRepository::new(&conn)
+        .delete(summary_template::SUMMARY_TEMPLATE_PATH_KEY)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn get_template_path(app: tauri::AppHandle) -> Result<Option<String>, String> {
     let manager_guard = TEMPLATE_MANAGER.lock().unwrap();
@@ -234,13 +567,15 @@ async fn open_template_in_editor(app: tauri::AppHandle) -> Result<(), String> {
 }
 
 /// Build a `template::BugData` from database records for rendering.
+/// Dates are formatted for `locale` in the session's time zone.
 fn bug_to_template_data(
     bug: &database::Bug,
     captures: &[database::Capture],
     session: &database::Session,
+    locale: &time_format::ExportLocale,
 ) -> template::BugData {
     // Parse environment from session's environment_json
-    let environment: template::Environment = session
+    let mut environment: template::Environment = session
         .environment_json
         .as_deref()
         .and_then(|s| serde_json::from_str(s).ok())
@@ -251,8 +586,36 @@ fn bug_to_template_data(
             ram: "Unknown".to_string(),
             cpu: "Unknown".to_string(),
             foreground_app: "Unknown".to_string(),
+            display_language: "Unknown".to_string(),
+            keyboard_layout: "Unknown".to_string(),
         });
 
+    // Language and layout are recorded per bug, when its capture started
+    if let Some(locale) = locale_info::LocaleSnapshot::from_bug(bug) {
+        if let Some(language) = locale.display_language.clone() {
+            environment.display_language = language;
+        }
+        if let Some(keyboard) = locale.describe_keyboard() {
+            environment.keyboard_layout = keyboard;
+        }
+    }
+
+    // The application the bug occurred in, from its latest capture
+    if let Some(window) = foreground_app::from_bug(bug) {
+        environment.foreground_app = window.describe();
+    }
+
+    // Display values recorded with the bug's captures fill in what the
+    // session environment does not know
+    if let Some((resolution, scaling)) = display_info::describe_captures(captures) {
+        if environment.display_resolution == "Unknown" {
+            environment.display_resolution = resolution;
+        }
+        if environment.dpi_scaling == "Unknown" {
+            environment.dpi_scaling = scaling;
+        }
+    }
+
     // Parse custom_metadata JSON into custom_fields map
     let custom_fields: std::collections::HashMap<String, String> = bug
         .custom_metadata
@@ -315,12 +678,33 @@ fn bug_to_template_data(
         folder_path: bug.folder_path.clone(),
         captures: capture_names,
         console_output,
+        created_at: locale.format_datetime(&bug.created_at, session.timezone.as_deref()),
+        design_details: Vec::new(),
     }
 }
 
-/// Render a bug report from DB data using the template engine.
-fn render_bug_from_db(bug_id: &str, conn: &rusqlite::Connection) -> Result<String, String> {
-    use database::{BugRepository, BugOps, CaptureRepository, CaptureOps, SessionRepository, SessionOps};
+/// Template lines for a bug's design-QA measurements, naming the capture
+/// each one was taken on.
+fn design_detail_lines(annotations: &[database::Annotation], captures: &[database::Capture]) -> Vec<String> {
+    annotations
+        .iter()
+        .map(|a| {
+            let file = captures
+                .iter()
+                .find(|c| c.id == a.capture_id)
+                .map(|c| c.file_name.as_str())
+                .unwrap_or("unknown capture");
+            match &a.label {
+                Some(label) => format!("{} ({}, {})", a.data.summary(), label, file),
+                None => format!("{} ({})", a.data.summary(), file),
+            }
+        })
+        .collect()
+}
+
+/// Build template data for a bug directly from the DB (bug, captures, session environment).
+fn load_bug_template_data(bug_id: &str, conn: &rusqlite::Connection) -> Result<(database::Bug, template::BugData), String> {
+    use database::{AnnotationOps, AnnotationRepository, BugRepository, BugOps, CaptureRepository, CaptureOps, SessionRepository, SessionOps};
 
     let bug = BugRepository::new(conn)
         .get(bug_id)
@@ -336,34 +720,192 @@ fn render_bug_from_db(bug_id: &str, conn: &rusqlite::Connection) -> Result<Strin
         .map_err(|e| format!("Failed to query session: {}", e))?
         .ok_or_else(|| format!("Session not found: {}", bug.session_id))?;
 
-    let bug_data = bug_to_template_data(&bug, &captures, &session);
+    let annotations = AnnotationRepository::new(conn)
+        .list_by_bug(bug_id)
+        .map_err(|e| format!("Failed to query annotations: {}", e))?;
 
-    // Get TemplateManager and render
-    let mut manager_guard = TEMPLATE_MANAGER.lock().unwrap();
-    if manager_guard.is_none() {
-        *manager_guard = Some(TemplateManager::new());
+    let mut bug_data = bug_to_template_data(&bug, &captures, &session, &time_format::ExportLocale::from_settings(conn));
+    bug_data.design_details = design_detail_lines(&annotations, &captures);
+    Ok((bug, bug_data))
+}
+
+/// The glossary of the profile the bug's session uses.
+fn bug_glossary(conn: &rusqlite::Connection, bug_id: &str) -> glossary::Glossary {
+    use database::{BugOps, BugRepository};
+
+    let session_id = BugRepository::new(conn).get(bug_id).ok().flatten().map(|bug| bug.session_id);
+    glossary::Glossary::for_session(conn, session_id.as_deref())
+}
+
+/// Render template data with the `selected` named template, or the shared
+/// TemplateManager's template when none is selected, filling `{var.<name>}`
+/// from `variables`, explaining the abbreviations of `glossary` and mapping
+/// minified stack frames in the console output back to their sources.
+fn render_template_data(
+    bug_data: &template::BugData,
+    glossary: &glossary::Glossary,
+    symbolicator: &symbolication::Symbolicator,
+    selected: Option<&str>,
+    variables: &template::VariableValues,
+) -> Result<String, String> {
+    use render_target::RenderTarget;
+
+    let mut bug_data = bug_data.clone();
+    bug_data.console_output = bug_data.console_output.map(|output| symbolicator.rewrite(&output));
+
+    with_template_manager(|manager| match selected {
+        Some(template) => manager.render_template(template, &bug_data, variables, RenderTarget::Markdown),
+        None => manager.render(&bug_data, variables, RenderTarget::Markdown),
+    })
+    .map(|rendered| glossary.apply(&rendered))
+}
+
+/// A bug's template data with the settings its report is rendered with, read
+/// from the DB so that rendering (which may fetch source maps over the
+/// network) runs with the database unlocked.
+struct BugRender {
+    bug_data: template::BugData,
+    glossary: glossary::Glossary,
+    symbolicator: symbolication::Symbolicator,
+    selected: Option<String>,
+    variables: template::VariableValues,
+}
+
+impl BugRender {
+    /// Render settings for `bug_data` of a bug in `session_id`.
+    fn load(conn: &rusqlite::Connection, bug_data: template::BugData, session_id: Option<&str>) -> Self {
+        Self {
+            glossary: glossary::Glossary::for_session(conn, session_id),
+            symbolicator: symbolication::Symbolicator::load(conn),
+            selected: selected_bug_template(conn, &bug_data.bug_type, session_id),
+            variables: template_variable_values(conn),
+            bug_data,
+        }
     }
-    let manager = manager_guard.as_ref().unwrap();
 
-    manager.render(&bug_data)
+    /// Render inputs for the bug `bug_id`.
+    fn for_bug(bug_id: &str, conn: &rusqlite::Connection) -> Result<Self, String> {
+        let (bug, bug_data) = load_bug_template_data(bug_id, conn)?;
+        Ok(Self::load(conn, bug_data, Some(&bug.session_id)))
+    }
+
+    /// Render inputs for the bug stored in `folder_path`. When the folder
+    /// belongs to a bug in the DB, its `metadata.json` is regenerated from the
+    /// DB first; otherwise the folder's existing `metadata.json` is used (e.g.
+    /// a folder copied off-machine).
+    fn for_folder(folder_path: &str, conn: &rusqlite::Connection) -> Result<Self, String> {
+        use rusqlite::OptionalExtension;
+
+        let folder = std::path::Path::new(folder_path);
+        let bug_id: Option<String> = conn
+            .query_row(
+                "SELECT id FROM bugs WHERE folder_path = ?1",
+                rusqlite::params![folder_path],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to query bug: {}", e))?;
+
+        match bug_id {
+            Some(bug_id) => {
+                let (bug, bug_data) = load_bug_template_data(&bug_id, conn)?;
+                bug_metadata::write_bug_metadata(folder, &bug.id, &bug.display_id, &bug_data)?;
+                Ok(Self::load(conn, bug_data, Some(&bug.session_id)))
+            }
+            None => Ok(Self::load(conn, bug_metadata::read_bug_metadata(folder)?, None)),
+        }
+    }
+
+    fn render(&self) -> Result<String, String> {
+        render_template_data(
+            &self.bug_data,
+            &self.glossary,
+            &self.symbolicator,
+            self.selected.as_deref(),
+            &self.variables,
+        )
+    }
+}
+
+impl session_archive::ArchiveReport for BugRender {
+    fn load_report(bug_id: &str, conn: &rusqlite::Connection) -> Result<Self, String> {
+        Self::for_bug(bug_id, conn)
+    }
+
+    fn render_report(&self) -> Result<String, String> {
+        self.render()
+    }
+}
+
+/// Render a bug report from DB data using the template engine.
+#[cfg(test)]
+fn render_bug_from_db(bug_id: &str, conn: &rusqlite::Connection) -> Result<String, String> {
+    BugRender::for_bug(bug_id, conn)?.render()
+}
+
+/// Render the bug stored in `folder_path` (see [`BugRender::for_folder`]).
+#[cfg(test)]
+fn render_bug_folder_from_db(folder_path: &str, conn: &rusqlite::Connection) -> Result<String, String> {
+    BugRender::for_folder(folder_path, conn)?.render()
+}
+
+/// Rewrite a bug's `metadata.json` from the DB.
+fn sync_bug_metadata(bug_id: &str, conn: &rusqlite::Connection) -> Result<(), String> {
+    let (bug, bug_data) = load_bug_template_data(bug_id, conn)?;
+    bug_metadata::write_bug_metadata(std::path::Path::new(&bug.folder_path), &bug.id, &bug.display_id, &bug_data)?;
+    Ok(())
+}
+
+/// Schedule a debounced rewrite of the notes file of a session or bug from the DB.
+fn queue_notes_mirror(owner: notes_mirror::NotesOwner) {
+    if let Some(mirror) = NOTES_MIRROR.lock().unwrap().as_ref() {
+        mirror.notify(&owner.key());
+    }
+}
+
+/// Schedule a debounced `metadata.json` rewrite after a bug, its notes or its captures change.
+pub(crate) fn queue_metadata_sync(bug_id: &str) {
+    if let Some(syncer) = METADATA_SYNC.lock().unwrap().as_ref() {
+        syncer.notify(bug_id);
+    }
+}
+
+#[tauri::command]
+fn render_bug_by_id(bug_id: String, db_state: tauri::State<'_, DbState>) -> Result<String, String> {
+    let render = BugRender::for_bug(&bug_id, &db_state.connection())?;
+    render.render()
+}
+
+#[tauri::command]
+fn render_bug_folder(folder_path: String, db_state: tauri::State<'_, DbState>) -> Result<String, String> {
+    let render = BugRender::for_folder(&folder_path, &db_state.connection())?;
+    render.render()
 }
 
+/// Copy a bug report to the clipboard, as Markdown unless another `target`
+/// is given (`jira-wiki`, `html` or `plain`). HTML is copied as rich text
+/// with the plain text version alongside, for apps that only paste text.
 #[tauri::command]
 async fn copy_bug_to_clipboard(
     bug_id: String,
+    target: Option<render_target::RenderTarget>,
     db_state: tauri::State<'_, DbState>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    let rendered_markdown = {
-        let conn = db_state.connection();
-        render_bug_from_db(&bug_id, &conn)?
-    };
+    use render_target::RenderTarget;
+
+    let render = BugRender::for_bug(&bug_id, &db_state.connection())?;
+    let rendered_markdown = render.render()?;
+    let target = target.unwrap_or_default();
+    let rendered = target.convert(&rendered_markdown);
 
     // Copy to clipboard using Tauri clipboard plugin
-    app_handle
-        .clipboard()
-        .write_text(rendered_markdown)
-        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    let clipboard = app_handle.clipboard();
+    match target {
+        RenderTarget::Html => clipboard.write_html(rendered, Some(RenderTarget::Plain.convert(&rendered_markdown))),
+        _ => clipboard.write_text(rendered),
+    }
+    .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
 
     Ok(())
 }
@@ -417,54 +959,272 @@ async fn open_session_folder(
     Ok(())
 }
 
+/// Copy the on-disk path of a session, bug or capture (`kind`) to the
+/// clipboard. Returns the copied path.
 #[tauri::command]
-fn get_capture_folder_path(session_folder_path: String) -> Result<String, String> {
-    use std::path::Path;
-
-    let captures_path = Path::new(&session_folder_path).join("_captures");
-    Ok(captures_path.to_string_lossy().to_string())
+fn copy_path_to_clipboard(
+    kind: folder_tools::PathKind,
+    id: String,
+    db_state: tauri::State<'_, DbState>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let path = folder_tools::resolve_path(&db_state.connection(), kind, &id)?;
+    app_handle
+        .clipboard()
+        .write_text(path.clone())
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    Ok(path)
 }
 
-/// Load the embedded tray icon PNG for the given state.
-///
-/// PRD Section 14 (Iconography) specifies:
-/// - idle:   gray/neutral circle
-/// - active: green indicator
-/// - bug:    red indicator
-/// - review: blue indicator
-///
-/// Icons are 32x32 8-bit RGBA PNGs embedded at compile time.
-/// Decode a PNG byte slice into raw RGBA pixels.
-fn decode_png_rgba(png_bytes: &[u8]) -> Result<(Vec<u8>, u32, u32), String> {
-    use std::io::Cursor;
-    let decoder = png::Decoder::new(Cursor::new(png_bytes));
-    let mut reader = decoder.read_info().map_err(|e| format!("PNG decode error: {}", e))?;
-    let mut buf = vec![0u8; reader.output_buffer_size()];
-    let info = reader.next_frame(&mut buf).map_err(|e| format!("PNG frame error: {}", e))?;
-    // Ensure RGBA output
-    let rgba = match info.color_type {
-        png::ColorType::Rgba => buf[..info.buffer_size()].to_vec(),
-        png::ColorType::Rgb => {
-            // Expand RGB to RGBA by adding full-opacity alpha channel
-            let rgb = &buf[..info.buffer_size()];
-            let mut rgba = Vec::with_capacity(rgb.len() / 3 * 4);
-            for chunk in rgb.chunks(3) {
-                rgba.extend_from_slice(chunk);
-                rgba.push(255);
-            }
-            rgba
-        }
-        ct => return Err(format!("Unsupported PNG color type: {:?}", ct)),
-    };
-    Ok((rgba, info.width, info.height))
+/// Open a terminal (Windows Terminal/PowerShell, Terminal.app, or the
+/// desktop's terminal on Linux) with `folder` as working directory. Only
+/// folders inside the session storage root are accepted.
+#[tauri::command]
+fn open_in_terminal(folder: String) -> Result<(), String> {
+    let storage_root = SESSION_MANAGER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|m| m.storage_root().to_path_buf())
+        .ok_or("Session manager not initialized")?;
+    storage_paths::PathScope::new([storage_root]).check_existing(std::path::Path::new(&folder))?;
+    folder_tools::open_in_terminal(&folder)
 }
 
-/// Load the embedded tray icon PNG for the given state.
-///
-/// PRD Section 14 (Iconography) specifies:
-/// - idle:   gray/neutral circle
-/// - active: green indicator
-/// - bug:    red indicator
+/// Screenshot one window (the active one unless `hwnd` is given) with a box
+/// around `element_rect`, or around the element under the cursor. The PNG goes
+/// to the active session's `_captures/`, so it is routed like any other capture.
+#[tauri::command]
+fn capture_window_with_highlight(
+    hwnd: Option<i64>,
+    element_rect: Option<[i32; 4]>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<String, String> {
+    use database::{SessionOps, SessionRepository};
+
+    let session_id = {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        manager.get_active_session_id().ok_or("No active session")?
+    };
+    let session = SessionRepository::new(&db_state.connection())
+        .get(&session_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+    let captures_dir = std::path::Path::new(&session.folder_path).join("_captures");
+    let overlay = current_cursor_overlay(&db_state.connection());
+    window_capture::capture_window_with_highlight(hwnd.map(|h| h as isize), element_rect, &overlay, &captures_dir)
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+/// Cursor and click marker to burn into a native screenshot taken now.
+fn current_cursor_overlay(conn: &rusqlite::Connection) -> cursor_overlay::CursorOverlay {
+    let settings = cursor_overlay::CursorOverlaySettings::load(conn);
+    cursor_overlay::CursorOverlay::current(&settings, CLICK_TRACKER.lock().unwrap().as_ref())
+}
+
+/// Track clicks only while the click marker is enabled.
+fn apply_click_tracker(settings: &cursor_overlay::CursorOverlaySettings) {
+    let mut tracker = CLICK_TRACKER.lock().unwrap();
+    if !settings.click_marker {
+        *tracker = None;
+    } else if tracker.is_none() {
+        *tracker = Some(cursor_overlay::ClickTracker::start());
+    }
+}
+
+/// Screenshot every monitor in one action, as one PNG per monitor or, with
+/// `mode: "composite"`, one image laid out like the desktop. The files go to
+/// the active session's `_captures/` and are routed to the active bug.
+#[tauri::command]
+fn capture_all_displays(
+    mode: Option<display_capture::DisplayCaptureMode>,
+    app: AppHandle,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<Vec<String>, String> {
+    use database::{SessionOps, SessionRepository};
+
+    let session_id = {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        manager.get_active_session_id().ok_or("No active session")?
+    };
+    let session = SessionRepository::new(&db_state.connection())
+        .get(&session_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+    let displays = display_info::snapshot(&app).ok_or("No monitors found")?;
+    let captures_dir = std::path::Path::new(&session.folder_path).join("_captures");
+    let overlay = current_cursor_overlay(&db_state.connection());
+    let paths = display_capture::capture_all_displays(&displays.monitors, mode.unwrap_or_default(), &overlay, &captures_dir)?;
+    Ok(paths.iter().map(|path| path.to_string_lossy().to_string()).collect())
+}
+
+/// Screenshot the monitor under the cursor, the active window or a region
+/// without an external tool. The PNG and its capture record go straight into
+/// the active bug (or `_unsorted/`); the capture watcher is not involved.
+#[tauri::command]
+async fn capture_screen_region(target: native_capture::CaptureTarget, app: AppHandle) -> Result<database::Capture, String> {
+    use database::{SessionOps, SessionRepository};
+
+    let (session_id, routing) = {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        (manager.get_active_session_id().ok_or("No active session")?, manager.routing_handles())
+    };
+    let db = app.state::<DbState>().arc();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let (session, overlay) = {
+            let conn = db.lock().unwrap();
+            let session = SessionRepository::new(&conn)
+                .get(&session_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            (session, current_cursor_overlay(&conn))
+        };
+        let displays = display_info::snapshot(&app);
+        let screen = platform::get_screen_capture();
+        let image = native_capture::grab(screen.as_ref(), &target, displays.as_ref(), &overlay)?;
+
+        let capture = native_capture::store(&db.lock().unwrap(), &session, &routing, &image, displays)?;
+        capture_watcher::CaptureWatcher::finish_capture(&capture, &db, &app);
+        Ok(capture)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Start recording a monitor or window to `recording-NNN.mp4` in the active
+/// bug (or `_unsorted/`), at `fps` frames per second (default 15). Progress is
+/// reported with `recording:progress` events.
+#[tauri::command]
+async fn start_recording(
+    target: screen_recording::RecordingTarget,
+    fps: Option<u32>,
+    app: AppHandle,
+) -> Result<screen_recording::RecordingInfo, String> {
+    use database::{SessionOps, SessionRepository};
+
+    let (session_id, routing) = {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        (manager.get_active_session_id().ok_or("No active session")?, manager.routing_handles())
+    };
+    let db = app.state::<DbState>().arc();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut active = SCREEN_RECORDING.lock().unwrap();
+        if active.is_some() {
+            return Err("A recording is already running".to_string());
+        }
+        let area = screen_recording::resolve_area(&target, display_info::snapshot(&app).as_ref())?;
+        let conn = db.lock().unwrap();
+        let session = SessionRepository::new(&conn)
+            .get(&session_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        let bridge = platform::get_recording_bridge(&video_frames::ffmpeg_path(&conn));
+        let fps = fps.unwrap_or(screen_recording::DEFAULT_FPS);
+        let recording = screen_recording::start(bridge.as_ref(), &conn, &session, &routing, area, fps, &app)?;
+        let info = recording.info();
+        *active = Some(recording);
+        Ok(info)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Stop the running recording and add it to its bug as a video capture.
+#[tauri::command]
+async fn stop_recording(app: AppHandle) -> Result<database::Capture, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let recording = SCREEN_RECORDING.lock().unwrap().take().ok_or("No recording is running")?;
+        let db = app.state::<DbState>().arc();
+        let capture = recording.stop(&db)?;
+        capture_watcher::CaptureWatcher::finish_capture(&capture, &db, &app);
+        Ok(capture)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// The running recording, if any.
+#[tauri::command]
+fn get_recording() -> Option<screen_recording::RecordingInfo> {
+    SCREEN_RECORDING.lock().unwrap().as_ref().map(|r| r.info())
+}
+
+/// Stop and file a running recording, e.g. when its session ends.
+fn finish_screen_recording(app: &AppHandle) {
+    let Some(recording) = SCREEN_RECORDING.lock().unwrap().take() else {
+        return;
+    };
+    let db = app.state::<DbState>().arc();
+    match recording.stop(&db) {
+        Ok(capture) => capture_watcher::CaptureWatcher::finish_capture(&capture, &db, app),
+        Err(e) => eprintln!("Warning: failed to finish screen recording: {}", e),
+    }
+}
+
+#[tauri::command]
+fn get_capture_folder_path(session_folder_path: String) -> Result<String, String> {
+    use std::path::Path;
+
+    let captures_path = Path::new(&session_folder_path).join("_captures");
+    Ok(captures_path.to_string_lossy().to_string())
+}
+
+/// Load the embedded tray icon PNG for the given state.
+///
+/// PRD Section 14 (Iconography) specifies:
+/// - idle:   gray/neutral circle
+/// - active: green indicator
+/// - bug:    red indicator
+/// - review: blue indicator
+///
+/// Icons are 32x32 8-bit RGBA PNGs embedded at compile time.
+/// Decode a PNG byte slice into raw RGBA pixels.
+fn decode_png_rgba(png_bytes: &[u8]) -> Result<(Vec<u8>, u32, u32), String> {
+    use std::io::Cursor;
+    let decoder = png::Decoder::new(Cursor::new(png_bytes));
+    let mut reader = decoder.read_info().map_err(|e| format!("PNG decode error: {}", e))?;
+    let mut buf = vec![0u8; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(|e| format!("PNG frame error: {}", e))?;
+    // Ensure RGBA output
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buf[..info.buffer_size()].to_vec(),
+        png::ColorType::Rgb => {
+            // Expand RGB to RGBA by adding full-opacity alpha channel
+            let rgb = &buf[..info.buffer_size()];
+            let mut rgba = Vec::with_capacity(rgb.len() / 3 * 4);
+            for chunk in rgb.chunks(3) {
+                rgba.extend_from_slice(chunk);
+                rgba.push(255);
+            }
+            rgba
+        }
+        ct => return Err(format!("Unsupported PNG color type: {:?}", ct)),
+    };
+    Ok((rgba, info.width, info.height))
+}
+
+/// Load the embedded tray icon PNG for the given state.
+///
+/// PRD Section 14 (Iconography) specifies:
+/// - idle:   gray/neutral circle
+/// - active: green indicator
+/// - bug:    red indicator
 /// - review: blue indicator
 ///
 /// Icons are 32x32 8-bit RGBA PNGs embedded at compile time.
@@ -586,6 +1346,7 @@ async fn update_tray_menu(state: String, bug_id: Option<String>, app_handle: tau
         }
     }
 
+    add_quick_toggles(&menu, &app_handle)?;
     tray.set_menu(Some(menu))
         .map_err(|e| format!("Failed to set tray menu: {}", e))?;
 
@@ -595,13 +1356,79 @@ async fn update_tray_menu(state: String, bug_id: Option<String>, app_handle: tau
         .map_err(|e| format!("Failed to set tray icon: {}", e))?;
 
     // Also emit event so frontend can react if needed
-    app_handle
-        .emit("tray-state-changed", &state)
+    events::emit(&app_handle, &events::TrayStateChanged { state })
         .map_err(|e| format!("Failed to emit tray state event: {}", e))?;
 
     Ok(())
 }
 
+/// Add the quick toggle items (see `quick_toggles`) to a tray menu, above
+/// Quit when the menu has one.
+fn add_quick_toggles(menu: &Menu<tauri::Wry>, app_handle: &AppHandle) -> Result<(), String> {
+    let menu_error = |e: tauri::Error| format!("Menu item error: {}", e);
+    // Menu items are built on the main thread; don't hold the DB lock meanwhile
+    let states: Vec<bool> = {
+        let db_state = app_handle.state::<DbState>();
+        let conn = db_state.connection();
+        quick_toggles::QUICK_TOGGLES.iter().map(|toggle| toggle.is_on(&conn)).collect()
+    };
+
+    let mut items = Vec::new();
+    for (toggle, on) in quick_toggles::QUICK_TOGGLES.iter().zip(states) {
+        let item = CheckMenuItemBuilder::new(toggle.label)
+            .id(toggle.menu_id)
+            .checked(on)
+            .build(app_handle)
+            .map_err(menu_error)?;
+        items.push((toggle.key, item));
+    }
+
+    let existing = menu.items().map_err(menu_error)?;
+    let position = match existing.last() {
+        Some(last) if last.id().as_ref() == "quit" => existing.len() - 1,
+        _ => existing.len(),
+    };
+    let separator = PredefinedMenuItem::separator(app_handle).map_err(menu_error)?;
+    let separator_below = PredefinedMenuItem::separator(app_handle).map_err(menu_error)?;
+    let mut new_items: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = vec![&separator];
+    new_items.extend(items.iter().map(|(_, item)| item as &dyn tauri::menu::IsMenuItem<tauri::Wry>));
+    if position < existing.len() {
+        new_items.push(&separator_below);
+    }
+    menu.insert_items(&new_items, position).map_err(menu_error)?;
+
+    *TRAY_QUICK_TOGGLES.lock().unwrap() = items;
+    Ok(())
+}
+
+/// Tick or untick the tray item of `key` after its setting changed.
+fn sync_quick_toggle_item(key: &str, enabled: bool) {
+    for (item_key, item) in TRAY_QUICK_TOGGLES.lock().unwrap().iter() {
+        if *item_key == key {
+            if let Err(e) = item.set_checked(enabled) {
+                eprintln!("Warning: failed to update tray item for {}: {}", key, e);
+            }
+        }
+    }
+}
+
+/// Flip the setting behind a quick toggle tray item.
+fn toggle_quick_setting(app_handle: &AppHandle, toggle: &quick_toggles::QuickToggle) -> Result<(), String> {
+    let enabled = {
+        let db_state = app_handle.state::<DbState>();
+        let conn = db_state.connection();
+        let enabled = toggle.toggle(&conn)?;
+        database::record_audit(&conn, setting_audit_action(toggle.key, false), "setting", toggle.key, None)?;
+        enabled
+    };
+    sync_quick_toggle_item(toggle.key, enabled);
+    events::emit(
+        app_handle,
+        &events::QuickToggleChanged { key: toggle.key.to_string(), enabled },
+    )
+    .map_err(|e| format!("Failed to emit quick toggle event: {}", e))
+}
+
 #[tauri::command]
 async fn update_tray_tooltip(tooltip: String, app_handle: tauri::AppHandle) -> Result<(), String> {
     if let Some(tray) = app_handle.tray_by_id("main-tray") {
@@ -618,27 +1445,11 @@ fn get_bug_notes(bug_id: String, db_state: tauri::State<'_, DbState>) -> Result<
     let conn = db_state.connection();
     let repo = BugRepository::new(&conn);
 
+    // Notes that only existed in notes.md were copied in by notes_mirror::backfill
     let bug = repo.get(&bug_id)
         .map_err(|e: rusqlite::Error| e.to_string())?
         .ok_or_else(|| format!("Bug not found: {}", bug_id))?;
 
-    // If DB notes are empty, attempt one-time migration from notes.md file
-    if bug.notes.as_ref().is_none_or(|n| n.is_empty()) {
-        let notes_file = std::path::Path::new(&bug.folder_path).join("notes.md");
-        if notes_file.exists() {
-            if let Ok(file_notes) = std::fs::read_to_string(&notes_file) {
-                if !file_notes.is_empty() {
-                    let update = database::BugUpdate {
-                        notes: Some(file_notes.clone()),
-                        ..Default::default()
-                    };
-                    let _ = repo.update_partial(&bug_id, &update);
-                    return Ok(file_notes);
-                }
-            }
-        }
-    }
-
     Ok(bug.notes.unwrap_or_default())
 }
 
@@ -651,6 +1462,7 @@ fn update_bug_notes(
     use database::{BugOps, BugRepository};
 
     let conn = db_state.connection();
+    session_lock::ensure_bug_editable(&conn, &bug_id)?;
     let repo = BugRepository::new(&conn);
 
     let update = database::BugUpdate {
@@ -659,7 +1471,19 @@ fn update_bug_notes(
     };
 
     repo.update_partial(&bug_id, &update)
-        .map_err(|e: rusqlite::Error| e.to_string())
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+
+    queue_metadata_sync(&bug_id);
+    queue_notes_mirror(notes_mirror::NotesOwner::Bug(bug_id));
+    Ok(())
+}
+
+/// Reject custom metadata that is not a JSON object. Malformed JSON would
+/// also make `idx_bugs_session_severity` (a `json_extract` index) fail to update.
+fn validate_custom_metadata(metadata_json: &str) -> Result<(), String> {
+    serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(metadata_json)
+        .map(|_| ())
+        .map_err(|e| format!("Custom metadata must be a JSON object: {}", e))
 }
 
 /// Update the custom_metadata JSON blob on a bug.
@@ -672,7 +1496,10 @@ fn update_bug_metadata(
 ) -> Result<(), String> {
     use database::{BugOps, BugRepository};
 
+    validate_custom_metadata(&metadata_json)?;
+
     let conn = db_state.connection();
+    session_lock::ensure_bug_editable(&conn, &bug_id)?;
     let repo = BugRepository::new(&conn);
 
     let update = database::BugUpdate {
@@ -681,45 +1508,142 @@ fn update_bug_metadata(
     };
 
     repo.update_partial(&bug_id, &update)
-        .map_err(|e: rusqlite::Error| e.to_string())
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+
+    queue_metadata_sync(&bug_id);
+    Ok(())
 }
 
+/// Session notes from the DB. For a folder with no session in the DB (e.g.
+/// an archive being viewed), the mirrored `session-notes.md` is read instead.
 #[tauri::command]
-async fn get_session_notes(_session_id: String, folder_path: String) -> Result<String, String> {
-    use std::path::Path;
+async fn get_session_notes(
+    session_id: String,
+    folder_path: String,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<String, String> {
+    use database::{SessionOps, SessionRepository};
+
+    let session = {
+        let conn = db_state.connection();
+        SessionRepository::new(&conn)
+            .get(&session_id)
+            .map_err(|e| format!("Failed to get session: {}", e))?
+    };
+    if let Some(session) = session {
+        return Ok(session.session_notes.unwrap_or_default());
+    }
 
-    // Try to read from session-notes.md file
-    let notes_file = Path::new(&folder_path).join("session-notes.md");
+    let notes_file = std::path::Path::new(&folder_path).join(notes_mirror::SESSION_NOTES_FILE);
     if notes_file.exists() {
         std::fs::read_to_string(&notes_file)
-            .map_err(|e| format!("Failed to read session-notes.md: {}", e))
+            .map_err(|e| format!("Failed to read {}: {}", notes_mirror::SESSION_NOTES_FILE, e))
     } else {
         // Return empty string if file doesn't exist yet
         Ok(String::new())
     }
 }
 
+/// Save session notes.
+///
+/// `lease_token` must match the live notes lease (if any) held via
+/// `acquire_notes_lock`. When `base_notes` is given and the stored notes have
+/// changed since the caller read them, the write is merged with the newer
+/// content instead of overwriting it. The notes are saved to the DB and
+/// mirrored to `session-notes.md` in the session folder shortly after.
 #[tauri::command]
 async fn update_session_notes(
-    _session_id: String,
-    folder_path: String,
+    session_id: String,
+    _folder_path: String,
     notes: String,
-) -> Result<(), String> {
-    use std::path::Path;
+    base_notes: Option<String>,
+    lease_token: Option<String>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<notes_lock::NotesWriteResult, String> {
+    use database::{SessionOps, SessionRepository};
 
-    // Ensure the folder exists
-    let session_folder = Path::new(&folder_path);
-    if !session_folder.exists() {
-        std::fs::create_dir_all(session_folder)
-            .map_err(|e| format!("Failed to create session folder: {}", e))?;
+    {
+        let conn = db_state.connection();
+        session_lock::ensure_session_editable(&conn, &session_id)?;
+    }
+
+    {
+        let mut guard = NOTES_LOCKS.lock().unwrap();
+        let registry = guard.get_or_insert_with(notes_lock::NotesLockRegistry::new);
+        registry.check_write(&session_id, lease_token.as_deref(), std::time::Instant::now())?;
     }
 
-    // Write notes to session-notes.md file
-    let notes_file = session_folder.join("session-notes.md");
-    std::fs::write(&notes_file, notes)
-        .map_err(|e| format!("Failed to write session-notes.md: {}", e))?;
+    let result = {
+        let conn = db_state.connection();
+        let repo = SessionRepository::new(&conn);
+        let current = repo
+            .get(&session_id)
+            .map_err(|e| format!("Failed to get session: {}", e))?
+            .ok_or_else(|| format!("Session not found: {}", session_id))?
+            .session_notes
+            .unwrap_or_default();
+        let result = match base_notes {
+            Some(base) => notes_lock::merge_notes(&base, &current, &notes),
+            None => notes_lock::NotesWriteResult { content: notes, merged: false },
+        };
+        repo.update_notes(&session_id, &result.content)
+            .map_err(|e| format!("Failed to save session notes: {}", e))?;
+        result
+    };
+
+    queue_notes_mirror(notes_mirror::NotesOwner::Session(session_id));
+    Ok(result)
+}
 
-    Ok(())
+/// Acquire (or renew) the editing lease on a notes document for a window.
+/// Leases expire after 30 seconds unless renewed by calling this again.
+#[tauri::command]
+fn acquire_notes_lock(document_id: String, holder: String) -> Result<notes_lock::NotesLease, String> {
+    let mut guard = NOTES_LOCKS.lock().unwrap();
+    let registry = guard.get_or_insert_with(notes_lock::NotesLockRegistry::new);
+    registry.acquire(
+        &document_id,
+        &holder,
+        notes_lock::DEFAULT_LEASE_TTL,
+        std::time::Instant::now(),
+    )
+}
+
+/// Release a notes lease. Returns false if the token does not match the live lease.
+#[tauri::command]
+fn release_notes_lock(document_id: String, token: String) -> Result<bool, String> {
+    let mut guard = NOTES_LOCKS.lock().unwrap();
+    let registry = guard.get_or_insert_with(notes_lock::NotesLockRegistry::new);
+    Ok(registry.release(&document_id, &token))
+}
+
+#[tauri::command]
+fn get_window_appearance(db_state: tauri::State<'_, DbState>) -> window_theme::WindowAppearance {
+    window_theme::WindowAppearance::load(&db_state.connection())
+}
+
+/// Save the secondary-window appearance (`ui.theme`) and apply it to every
+/// open window except the main one.
+#[tauri::command]
+fn set_window_appearance(
+    appearance: window_theme::WindowAppearance,
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    appearance.validate()?;
+    appearance.save(&db_state.connection())?;
+
+    let failures: Vec<String> = app
+        .webview_windows()
+        .values()
+        .filter(|w| w.label() != window_theme::MAIN_WINDOW_LABEL)
+        .filter_map(|w| appearance.apply_to_window(w).err())
+        .collect();
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Saved, but some windows could not be updated: {}", failures.join("; ")))
+    }
 }
 
 #[tauri::command]
@@ -733,7 +1657,12 @@ async fn open_session_notes_window(app: tauri::AppHandle) -> Result<(), String>
         return Ok(());
     }
 
-    tauri::WebviewWindowBuilder::new(
+    let (appearance, saved_geometry) = {
+        let db_state = app.state::<DbState>();
+        let conn = db_state.connection();
+        (window_theme::WindowAppearance::load(&conn), window_geometry::load(&conn, window_label))
+    };
+    let builder = tauri::WebviewWindowBuilder::new(
         &app,
         window_label,
         tauri::WebviewUrl::App("/session-notes".into()),
@@ -743,10 +1672,15 @@ async fn open_session_notes_window(app: tauri::AppHandle) -> Result<(), String>
     .min_inner_size(300.0, 250.0)
     .resizable(true)
     .decorations(true)
-    .always_on_top(true)
-    .focused(true)
-    .build()
-    .map_err(|e| format!("Failed to create session notes window: {}", e))?;
+    .focused(true);
+    let builder = window_geometry::apply_to_builder(builder, &app, saved_geometry);
+    let window = appearance
+        .apply_to_builder(builder, window_label)
+        .build()
+        .map_err(|e| format!("Failed to create session notes window: {}", e))?;
+    if let Err(e) = appearance.apply_opacity(&window) {
+        eprintln!("Failed to apply window opacity: {}", e);
+    }
 
     Ok(())
 }
@@ -762,7 +1696,12 @@ async fn open_session_status_window(app: tauri::AppHandle) -> Result<(), String>
         return Ok(());
     }
 
-    tauri::WebviewWindowBuilder::new(
+    let (appearance, saved_geometry) = {
+        let db_state = app.state::<DbState>();
+        let conn = db_state.connection();
+        (window_theme::WindowAppearance::load(&conn), window_geometry::load(&conn, window_label))
+    };
+    let builder = tauri::WebviewWindowBuilder::new(
         &app,
         window_label,
         tauri::WebviewUrl::App("/session-status".into()),
@@ -773,11 +1712,16 @@ async fn open_session_status_window(app: tauri::AppHandle) -> Result<(), String>
     .max_inner_size(600.0, 48.0)
     .resizable(true)
     .decorations(false)
-    .always_on_top(true)
     .focused(false)
-    .transparent(true)
-    .build()
-    .map_err(|e| format!("Failed to create session status window: {}", e))?;
+    .transparent(true);
+    let builder = window_geometry::apply_to_builder(builder, &app, saved_geometry);
+    let window = appearance
+        .apply_to_builder(builder, window_label)
+        .build()
+        .map_err(|e| format!("Failed to create session status window: {}", e))?;
+    if let Err(e) = appearance.apply_opacity(&window) {
+        eprintln!("Failed to apply window opacity: {}", e);
+    }
 
     Ok(())
 }
@@ -803,12 +1747,12 @@ fn start_capture_watcher_for_session(session: &database::Session, app: &AppHandl
     // Ensure the _captures directory exists.
     let _ = std::fs::create_dir_all(&captures_dir);
 
-    let active_bug = {
+    let routing = {
         let guard = SESSION_MANAGER.lock().unwrap();
         guard
             .as_ref()
-            .map(|m| m.active_bug_arc())
-            .unwrap_or_else(|| std::sync::Arc::new(std::sync::Mutex::new(None)))
+            .map(|m| m.routing_handles())
+            .unwrap_or_default()
     };
 
     // Get the shared DB connection from Tauri managed state.
@@ -817,16 +1761,23 @@ fn start_capture_watcher_for_session(session: &database::Session, app: &AppHandl
         db_state.arc()
     };
 
+    if let Some(bridge) = CAPTURE_BRIDGE.lock().unwrap().as_ref() {
+        if let Err(e) = bridge.set_capture_folder(Some(&captures_dir)) {
+            eprintln!("Warning: Failed to point the capture bridge at the session: {e}");
+        }
+    }
+
     match capture_watcher::CaptureWatcher::start(
         captures_dir,
         session.id.clone(),
         session_folder,
-        active_bug,
+        routing,
         db_conn,
         app.clone(),
     ) {
         Ok(watcher) => {
             *CAPTURE_WATCHER.lock().unwrap() = Some(watcher);
+            metrics::increment(metrics::Counter::WatcherRestarts);
         }
         Err(e) => {
             eprintln!("Warning: Failed to start capture watcher: {e}");
@@ -837,6 +1788,81 @@ fn start_capture_watcher_for_session(session: &database::Session, app: &AppHandl
 /// Stop the capture watcher (drops the file-system watch).
 fn stop_capture_watcher() {
     *CAPTURE_WATCHER.lock().unwrap() = None;
+    if let Some(bridge) = CAPTURE_BRIDGE.lock().unwrap().as_ref() {
+        let _ = bridge.set_capture_folder(None);
+    }
+}
+
+/// Start the crash dump watcher for the given session, if a process is configured.
+fn start_crash_dump_watcher_for_session(session: &database::Session, app: &AppHandle) {
+    let db_conn = app.state::<database::DbState>().arc();
+    let target = crash_dumps::CrashDumpSettings::load(&db_conn.lock().unwrap()).watch_target();
+    let Some((process, folder)) = target else {
+        return;
+    };
+
+    let active_bug = {
+        let guard = SESSION_MANAGER.lock().unwrap();
+        guard
+            .as_ref()
+            .map(|m| m.routing_handles())
+            .unwrap_or_default()
+            .active_bug
+    };
+    let crash_session = crash_dumps::CrashSession {
+        session_id: session.id.clone(),
+        session_folder: std::path::PathBuf::from(&session.folder_path),
+        active_bug,
+    };
+
+    match crash_dumps::CrashDumpWatcher::start(folder, process, crash_session, db_conn, app.clone()) {
+        Ok(watcher) => *CRASH_DUMP_WATCHER.lock().unwrap() = Some(watcher),
+        Err(e) => eprintln!("Warning: Failed to start crash dump watcher: {e}"),
+    }
+}
+
+/// Stop the crash dump watcher.
+fn stop_crash_dump_watcher() {
+    *CRASH_DUMP_WATCHER.lock().unwrap() = None;
+}
+
+/// (Re)start the staging watcher from the `staging.folder` setting; stops it when unset.
+fn restart_staging_watcher(conn: &rusqlite::Connection, app: &AppHandle) {
+    use database::{SettingsOps, SettingsRepository};
+
+    let folder = SettingsRepository::new(conn)
+        .get(staging_watcher::STAGING_FOLDER_KEY)
+        .ok()
+        .flatten()
+        .filter(|f| !f.trim().is_empty());
+
+    let mut guard = STAGING_WATCHER.lock().unwrap();
+    *guard = None;
+    if let Some(folder) = folder {
+        match staging_watcher::StagingWatcher::start(std::path::PathBuf::from(folder), app.clone()) {
+            Ok(watcher) => {
+                *guard = Some(watcher);
+                metrics::increment(metrics::Counter::WatcherRestarts);
+            }
+            Err(e) => eprintln!("Warning: Failed to start staging watcher: {e}"),
+        }
+    }
+}
+
+/// Ask the frontend whether to import files queued in the staging folder.
+fn prompt_staged_import(session: &database::Session, app: &AppHandle) {
+    let queued = STAGING_WATCHER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|w| w.queued())
+        .unwrap_or_default();
+    if !queued.is_empty() {
+        let _ = events::emit(
+            app,
+            &events::StagedImportPrompt { session_id: session.id.clone(), files: queued },
+        );
+    }
 }
 
 /// Start the clipboard watcher for the given session.
@@ -856,12 +1882,54 @@ fn stop_clipboard_watcher() {
     *CLIPBOARD_WATCHER.lock().unwrap() = None;
 }
 
+/// Start writing the heartbeat file for the active session. A writer already
+/// running is stopped first: it removes the file when dropped, and both write
+/// to the same path.
+fn start_heartbeat(app: &AppHandle) {
+    stop_heartbeat();
+    let Some(storage_root) = SESSION_MANAGER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|m| m.storage_root().to_path_buf())
+    else {
+        return;
+    };
+    let db = app.state::<DbState>().arc();
+    let writer = heartbeat::HeartbeatWriter::start(
+        heartbeat::heartbeat_path(&storage_root),
+        heartbeat::HEARTBEAT_INTERVAL,
+        move || {
+            let (session_id, bug_id) = {
+                let guard = SESSION_MANAGER.lock().unwrap();
+                let manager = guard.as_ref()?;
+                (manager.get_active_session_id()?, manager.get_active_bug_id())
+            };
+            let conn = db.lock().unwrap();
+            heartbeat::build(&conn, &session_id, bug_id)
+                .inspect_err(|e| eprintln!("Warning: Failed to build heartbeat: {}", e))
+                .ok()
+        },
+    );
+    *HEARTBEAT.lock().unwrap() = Some(writer);
+}
+
+/// Stop the heartbeat writer and remove the heartbeat file.
+fn stop_heartbeat() {
+    *HEARTBEAT.lock().unwrap() = None;
+}
+
 // ─── Session Manager Commands ────────────────────────────────────────────
 
-/// Determine capture type and generate PRD-compliant file name.
-/// Screenshots: capture-{NNN}.png, Videos: recording-{NNN}.mp4 (or .webm/.mkv).
+/// Determine capture type and generate the file name from the naming pattern
+/// (see `capture_naming`). With the default pattern, screenshots are
+/// capture-{NNN}.png and videos recording-{NNN}.mp4 (or .webm/.mkv).
 #[allow(dead_code)]
-pub(crate) fn make_capture_filename(source_path: &std::path::Path, capture_number: u32) -> (String, database::CaptureType) {
+pub(crate) fn make_capture_filename(
+    source_path: &std::path::Path,
+    capture_number: u32,
+    naming: &capture_naming::NamingContext,
+) -> (String, database::CaptureType) {
     use database::CaptureType;
     let extension = source_path
         .extension()
@@ -870,28 +1938,25 @@ pub(crate) fn make_capture_filename(source_path: &std::path::Path, capture_numbe
         .to_lowercase();
     match extension.as_str() {
         "mp4" | "webm" | "mkv" | "avi" | "mov" => (
-            format!("recording-{:03}.{}", capture_number, extension),
+            format!("{}.{}", naming.stem("recording", capture_number), extension),
             CaptureType::Video,
         ),
         ext => (
-            format!("capture-{:03}.{}", capture_number, ext),
+            format!("{}.{}", naming.stem("capture", capture_number), ext),
             CaptureType::Screenshot,
         ),
     }
 }
 
-/// Count existing captures in a directory to determine the next sequential number.
+/// Count existing captures (files named by `naming`) in a directory to
+/// determine the next sequential number.
 #[allow(dead_code)]
-pub(crate) fn next_capture_number(dir: &std::path::Path) -> u32 {
+pub(crate) fn next_capture_number(dir: &std::path::Path, naming: &capture_naming::NamingContext) -> u32 {
     let count = std::fs::read_dir(dir)
         .map(|entries| {
             entries
                 .filter_map(|e| e.ok())
-                .filter(|e| {
-                    let name = e.file_name();
-                    let s = name.to_string_lossy();
-                    s.starts_with("capture-") || s.starts_with("recording-")
-                })
+                .filter(|e| naming.matches(&e.file_name().to_string_lossy()))
                 .count()
         })
         .unwrap_or(0);
@@ -909,68 +1974,775 @@ fn start_session(profile_id: Option<String>, app: AppHandle) -> Result<database:
     };
 
     start_capture_watcher_for_session(&session, &app);
+    start_crash_dump_watcher_for_session(&session, &app);
     start_clipboard_watcher_for_session(&session, &app);
+    start_heartbeat(&app);
+    record_session_network_snapshot(&session, &app);
+    prompt_staged_import(&session, &app);
     Ok(session)
 }
 
-#[tauri::command]
-async fn end_session(session_id: String) -> Result<(), String> {
-    stop_clipboard_watcher();
-    stop_capture_watcher();
+/// Profile a session started from the tray uses: the active profile, or the
+/// profile of the most recent session when none is active.
+fn tray_session_profile(conn: &rusqlite::Connection) -> Option<String> {
+    use database::{SessionOps, SessionRepository, SettingsOps, SettingsRepository};
+
+    SettingsRepository::new(conn)
+        .get(profile::ACTIVE_PROFILE_KEY)
+        .ok()
+        .flatten()
+        .or_else(|| {
+            SessionRepository::new(conn)
+                .list()
+                .ok()?
+                .into_iter()
+                .find_map(|session| session.profile_id)
+        })
+}
 
-    tauri::async_runtime::spawn_blocking(move || {
-        let manager_guard = SESSION_MANAGER.lock().unwrap();
-        let manager = manager_guard
+/// Start a session from the tray menu without bringing up the main window.
+/// On failure the window is shown and asked to start the session itself, so
+/// the user sees why it did not start.
+async fn start_session_from_tray(app: AppHandle) {
+    let profile_id = tray_session_profile(&app.state::<DbState>().connection());
+    let started = {
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || start_session(profile_id, app))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))
+            .and_then(|result| result)
+    };
+
+    if let Err(e) = started {
+        eprintln!("Warning: failed to start session from tray: {}", e);
+        if let Some(window) = app.get_webview_window("main") {
+            window.show().ok();
+            window.set_focus().ok();
+        }
+        app.emit("tray-menu-start-session", e).ok();
+        return;
+    }
+
+    let tray_updated = match update_tray_menu("active".to_string(), None, app.clone()).await {
+        Ok(()) => update_tray_tooltip("Unbroken QA Capture - Session Active".to_string(), app).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = tray_updated {
+        eprintln!("Warning: failed to update tray for the started session: {}", e);
+    }
+}
+
+#[tauri::command]
+async fn end_session(session_id: String, app: AppHandle) -> Result<(), String> {
+    stop_clipboard_watcher();
+    stop_capture_watcher();
+    stop_crash_dump_watcher();
+    stop_perf_sampler();
+    stop_heartbeat();
+    finish_screen_recording(&app);
+    close_annotation_windows_for_session(&app, &session_id);
+    *EXTERNAL_EDIT_WATCHERS.lock().unwrap() = None;
+
+    let ended_session_id = session_id.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
             .as_ref()
             .ok_or("Session manager not initialized")?;
         manager.end_session(&session_id)
     })
     .await
-    .map_err(|e| format!("Task join error: {}", e))?
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    start_post_session_pipeline(&app, ended_session_id);
+    Ok(())
+}
+
+/// Run the ended session's profile `post_session_actions` in the background,
+/// reporting each step through `session:post-processing` events.
+fn start_post_session_pipeline(app: &AppHandle, session_id: String) {
+    let db = app.state::<DbState>().arc();
+    let actions = post_session::profile_actions(&db.lock().unwrap(), &session_id);
+    if actions.is_empty() {
+        return;
+    }
+
+    let storage_root = SESSION_MANAGER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|m| m.storage_root().to_path_buf());
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let runner = post_session::RealActionRunner::new(db, storage_root);
+        post_session::run_pipeline(&session_id, &actions, &runner, |event| {
+            let _ = events::emit(&app, event);
+        });
+    });
+}
+
+/// Files waiting in the staging folder (empty when hot import is off).
+#[tauri::command]
+fn get_staged_files() -> Vec<staging_watcher::StagedFile> {
+    STAGING_WATCHER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|w| w.queued())
+        .unwrap_or_default()
+}
+
+/// Move queued staging files (all, or those in `paths`) into the session's
+/// `_unsorted/` folder, keeping their original timestamps.
+#[tauri::command]
+fn import_staged_files(
+    session_id: String,
+    paths: Option<Vec<String>>,
+    db_state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<staging_watcher::StagingImportReport, String> {
+    use database::{SessionOps, SessionRepository};
+
+    let conn = db_state.connection();
+    session_lock::ensure_session_editable(&conn, &session_id)?;
+    let session = SessionRepository::new(&conn)
+        .get(&session_id)
+        .map_err(|e: rusqlite::Error| e.to_string())?
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+    let files = STAGING_WATCHER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|w| w.take(paths.as_deref()))
+        .ok_or("Staging folder is not configured")?;
+
+    let report = staging_watcher::import_staged(
+        &conn,
+        &session_id,
+        std::path::Path::new(&session.folder_path),
+        &files,
+    );
+    let failed: Vec<staging_watcher::StagedFile> = files
+        .into_iter()
+        .filter(|file| report.failed.iter().any(|f| f.path == file.path))
+        .collect();
+    if let Some(watcher) = STAGING_WATCHER.lock().unwrap().as_ref() {
+        watcher.requeue(&failed);
+    }
+    for capture in &report.imported {
+        let _ = events::emit(
+            &app,
+            &events::ScreenshotCaptured {
+                file_path: capture.file_path.clone(),
+                capture_id: Some(capture.id.clone()),
+                bug_id: None,
+                session_id: Some(session_id.to_string()),
+                timestamp: chrono::Utc::now().timestamp_millis(),
+            },
+        );
+    }
+    Ok(report)
+}
+
+/// Set (or clear, with `None`) the hot-import staging folder and restart its watcher.
+#[tauri::command]
+fn set_staging_folder(
+    folder: Option<String>,
+    db_state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    use database::{SettingsOps, SettingsRepository};
+
+    let conn = db_state.connection();
+    let repo = SettingsRepository::new(&conn);
+    match folder.as_deref().filter(|f| !f.trim().is_empty()) {
+        Some(folder) => repo.set(staging_watcher::STAGING_FOLDER_KEY, folder),
+        None => repo.delete(staging_watcher::STAGING_FOLDER_KEY),
+    }
+    .map_err(|e: rusqlite::Error| e.to_string())?;
+
+    restart_staging_watcher(&conn, &app);
+    Ok(())
+}
+
+#[tauri::command]
+fn resume_session(session_id: String, app: AppHandle) -> Result<database::Session, String> {
+    let session = {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        manager.resume_session(&session_id)?
+    };
+
+    start_capture_watcher_for_session(&session, &app);
+    start_crash_dump_watcher_for_session(&session, &app);
+    start_clipboard_watcher_for_session(&session, &app);
+    start_heartbeat(&app);
+    Ok(session)
+}
+
+/// Start sampling the app under test into `bug`'s folder when performance
+/// capture is enabled. Any sampler for a previous bug writes its report first.
+fn start_perf_sampler(conn: &rusqlite::Connection, bug: &database::Bug) {
+    let settings = perf_capture::PerfCaptureSettings::load(conn);
+    let mut sampler = PERF_SAMPLER.lock().unwrap();
+    *sampler = None;
+    if settings.enabled && !settings.process.trim().is_empty() {
+        *sampler = Some(perf_capture::PerfSampler::start(
+            settings.process.trim().to_string(),
+            std::path::PathBuf::from(&bug.folder_path),
+        ));
+    }
+}
+
+/// Stop sampling and write the report into the bug folder.
+fn stop_perf_sampler() {
+    *PERF_SAMPLER.lock().unwrap() = None;
+}
+
+/// Start capturing a new bug. With `deferred`, start a scratch bug instead:
+/// it takes no bug number until `promote_scratch_bug`, or is folded into
+/// `_unsorted/` with `fold_scratch_bug`.
+#[tauri::command]
+fn start_bug_capture(
+    session_id: String,
+    deferred: Option<bool>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<database::Bug, String> {
+    let bug = {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        if deferred.unwrap_or(false) {
+            manager.start_scratch_capture(&session_id)?
+        } else {
+            manager.start_bug_capture(&session_id)?
+        }
+    };
+
+    // Localization and IME bugs need the language and layout the tester had at the start
+    let bug = {
+        use database::BugOps;
+        let conn = db_state.connection();
+        if let Err(e) = locale_info::attach_to_bug(&conn, &bug.id, &locale_info::LocaleSnapshot::capture()) {
+            eprintln!("Warning: failed to record locale for bug {}: {}", bug.id, e);
+        }
+        database::BugRepository::new(&conn).get(&bug.id).ok().flatten().unwrap_or(bug)
+    };
+
+    start_perf_sampler(&db_state.connection(), &bug);
+
+    // Optional: record the focused window's accessibility tree before focus moves on
+    if ui_tree::is_enabled(&db_state.connection()) {
+        let folder = std::path::PathBuf::from(&bug.folder_path);
+        std::thread::spawn(move || {
+            if let Err(e) = ui_tree::capture_to_folder(&folder) {
+                eprintln!("UI tree snapshot skipped for {:?}: {}", folder, e);
+            }
+        });
+    }
+    Ok(bug)
+}
+
+/// Give scratch bug `bug_id` the next bug number, a title and a type, moving
+/// it from `_scratch/` to its `bug_NNN` folder.
+#[tauri::command]
+fn promote_scratch_bug(
+    bug_id: String,
+    title: Option<String>,
+    bug_type: Option<database::BugType>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<database::Bug, String> {
+    session_lock::ensure_bug_editable(&db_state.connection(), &bug_id)?;
+    let bug = {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        manager.promote_scratch_bug(&bug_id, title, bug_type.unwrap_or(database::BugType::Bug))?
+    };
+    if SESSION_MANAGER.lock().unwrap().as_ref().and_then(|m| m.get_active_bug_id()).as_deref() == Some(bug_id.as_str()) {
+        if let Some(sampler) = PERF_SAMPLER.lock().unwrap().as_ref() {
+            sampler.retarget(std::path::PathBuf::from(&bug.folder_path));
+        }
+    }
+    queue_metadata_sync(&bug.id);
+    Ok(bug)
+}
+
+/// Move the captures of scratch bug `bug_id` to `_unsorted/` and delete it.
+#[tauri::command]
+fn fold_scratch_bug(bug_id: String, db_state: tauri::State<'_, DbState>, app: AppHandle) -> Result<(), String> {
+    use database::{BugOps, BugRepository, CaptureOps, CaptureRepository};
+
+    let (session_id, captures) = {
+        let conn = db_state.connection();
+        session_lock::ensure_bug_editable(&conn, &bug_id)?;
+        let bug = BugRepository::new(&conn)
+            .get(&bug_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Bug not found: {}", bug_id))?;
+        if !bug.is_scratch() {
+            return Err(format!("{} is not a scratch bug", bug.display_id));
+        }
+        let captures = CaptureRepository::new(&conn).list_by_bug(&bug_id).map_err(|e| e.to_string())?;
+        (bug.session_id, captures)
+    };
+    if SESSION_MANAGER.lock().unwrap().as_ref().and_then(|m| m.get_active_bug_id()).as_deref() == Some(bug_id.as_str()) {
+        stop_perf_sampler();
+    }
+    let folded_captures = captures.len();
+    for capture in captures {
+        unassign_capture(capture.id, db_state.clone(), app.clone())?;
+    }
+
+    {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        manager.discard_scratch_bug(&bug_id, folded_captures)?;
+    }
+    // The scratch bug's notes were appended to the session notes
+    queue_notes_mirror(notes_mirror::NotesOwner::Session(session_id));
+    Ok(())
+}
+
+/// Wrap up a bug that stopped capturing, whether ended by the tester or by
+/// auto-stop: write the perf report, record the network snapshot and sync the
+/// bug's metadata file.
+fn finish_bug_capture(app: &AppHandle, bug_id: &str) {
+    stop_perf_sampler();
+    record_bug_network_snapshot(bug_id, app);
+    queue_metadata_sync(bug_id);
+}
+
+#[tauri::command]
+fn end_bug_capture(bug_id: String, app: AppHandle) -> Result<(), String> {
+    {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        manager.end_bug_capture(&bug_id)?;
+    }
+    finish_bug_capture(&app, &bug_id);
+    Ok(())
+}
+
+/// Resume capturing for an existing bug — sets its status back to 'capturing' and marks it as the active bug.
+/// Used when the user wants to add more screenshots to a bug that was previously ended.
+#[tauri::command]
+fn resume_bug_capture(bug_id: String, db_state: tauri::State<'_, DbState>) -> Result<database::Bug, String> {
+    let bug = {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        manager.resume_bug_capture(&bug_id)?
+    };
+    start_perf_sampler(&db_state.connection(), &bug);
+    Ok(bug)
+}
+
+/// End the active bug if it has been idle longer than the `capture.auto_stop`
+/// setting allows. Runs every `bug_auto_stop::CHECK_INTERVAL` from setup.
+fn check_bug_auto_stop(app: &tauri::AppHandle) -> Result<(), String> {
+    use database::{BugOps, BugRepository};
+
+    let Some(manager) = SESSION_MANAGER.lock().unwrap().clone() else {
+        return Ok(());
+    };
+    let Some(bug_id) = manager.get_active_bug_id() else {
+        return Ok(());
+    };
+
+    let (settings, bug, idle) = {
+        let db_state = app.state::<DbState>();
+        let conn = db_state.connection();
+        let settings = bug_auto_stop::AutoStopSettings::load(&conn);
+        if !settings.enabled {
+            return Ok(());
+        }
+        let Some(bug) = BugRepository::new(&conn).get(&bug_id).map_err(|e| e.to_string())? else {
+            return Ok(());
+        };
+        let idle = bug_auto_stop::idle_time(&conn, &bug_id, chrono::Utc::now())?;
+        (settings, bug, idle)
+    };
+    if !idle.is_some_and(|idle| settings.should_stop(idle)) {
+        return Ok(());
+    }
+
+    manager.end_bug_capture(&bug_id)?;
+    finish_bug_capture(app, &bug_id);
+
+    *LAST_AUTO_STOP.lock().unwrap() = Some(bug_auto_stop::AutoStopped {
+        bug_id: bug_id.clone(),
+        session_id: bug.session_id.clone(),
+        display_id: bug.display_id.clone(),
+        stopped_at: chrono::Utc::now().to_rfc3339(),
+        idle_minutes: settings.idle_minutes,
+    });
+    if let Some(tray) = app.tray_by_id("main-tray") {
+        let _ = tray.set_tooltip(Some(format!(
+            "{} ended after {} min without activity",
+            bug.display_id, settings.idle_minutes
+        )));
+    }
+    events::emit(
+        app,
+        &events::BugAutoStopped {
+            bug_id,
+            session_id: bug.session_id,
+            display_id: bug.display_id,
+            idle_minutes: settings.idle_minutes,
+        },
+    )
+    .map_err(|e| format!("Failed to emit bug:auto-stopped event: {}", e))
+}
+
+/// Resume the bug most recently ended by auto-stop. Fails if its session is
+/// no longer active or another bug has been started since.
+#[tauri::command]
+fn undo_auto_stop(db_state: tauri::State<'_, DbState>) -> Result<database::Bug, String> {
+    let manager = SESSION_MANAGER
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("Session manager not initialized")?;
+    let mut last = LAST_AUTO_STOP.lock().unwrap();
+    let stopped = last.as_ref().ok_or("No bug was auto-stopped")?;
+
+    if manager.get_active_session_id().as_deref() != Some(stopped.session_id.as_str()) {
+        return Err(format!("The session of {} is no longer active", stopped.display_id));
+    }
+    if let Some(active) = manager.get_active_bug_id() {
+        if active != stopped.bug_id {
+            return Err(format!("Another bug is being captured; end it before resuming {}", stopped.display_id));
+        }
+    }
+
+    let bug = manager.resume_bug_capture(&stopped.bug_id)?;
+    *last = None;
+    start_perf_sampler(&db_state.connection(), &bug);
+    Ok(bug)
+}
+
+/// The bug most recently ended by auto-stop, if it can still be undone.
+#[tauri::command]
+fn get_last_auto_stop() -> Option<bug_auto_stop::AutoStopped> {
+    LAST_AUTO_STOP.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_auto_stop_settings(db_state: tauri::State<'_, DbState>) -> bug_auto_stop::AutoStopSettings {
+    let conn = db_state.connection();
+    bug_auto_stop::AutoStopSettings::load(&conn)
+}
+
+#[tauri::command]
+fn set_auto_stop_settings(
+    settings: bug_auto_stop::AutoStopSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    settings.validate()?;
+    let conn = db_state.connection();
+    settings.save(&conn)
+}
+
+#[tauri::command]
+fn get_capture_quota(db_state: tauri::State<'_, DbState>) -> capture_quota::CaptureQuota {
+    let conn = db_state.connection();
+    capture_quota::CaptureQuota::load(&conn)
+}
+
+#[tauri::command]
+fn set_capture_quota(quota: capture_quota::CaptureQuota, db_state: tauri::State<'_, DbState>) -> Result<(), String> {
+    quota.validate()?;
+    let conn = db_state.connection();
+    quota.save(&conn)
+}
+
+/// Warn when `capture` took its bug or session over a soft capture limit:
+/// an event for the frontend and, unless notifications are off, the tray
+/// tooltip.
+fn check_capture_quota(db: &Mutex<rusqlite::Connection>, capture: &database::Capture, app: &AppHandle) {
+    let (warnings, notify) = {
+        let conn = db.lock().unwrap();
+        let warnings = capture_quota::check(&conn, capture);
+        let notify = quick_toggles::by_key(quick_toggles::NOTIFICATIONS_KEY).is_some_and(|t| t.is_on(&conn));
+        (warnings, notify)
+    };
+    let warnings = match warnings {
+        Ok(warnings) => warnings,
+        Err(e) => {
+            eprintln!("Warning: capture quota not checked: {}", e);
+            return;
+        }
+    };
+    for warning in warnings {
+        if notify {
+            if let Some(tray) = app.tray_by_id("main-tray") {
+                let _ = tray.set_tooltip(Some(&warning.message));
+            }
+        }
+        if let Err(e) = events::emit(app, &warning) {
+            eprintln!("Warning: failed to emit capture:quota-exceeded event: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+fn get_description_lint_settings(db_state: tauri::State<'_, DbState>) -> description_lint::LintSettings {
+    let conn = db_state.connection();
+    description_lint::LintSettings::load(&conn)
+}
+
+#[tauri::command]
+fn set_description_lint_settings(
+    settings: description_lint::LintSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    settings.validate()?;
+    let conn = db_state.connection();
+    settings.save(&conn)
+}
+
+#[tauri::command]
+fn get_symbolication_settings(db_state: tauri::State<'_, DbState>) -> symbolication::SymbolicationSettings {
+    let conn = db_state.connection();
+    symbolication::SymbolicationSettings::load(&conn)
+}
+
+#[tauri::command]
+fn set_symbolication_settings(
+    settings: symbolication::SymbolicationSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    settings.validate()?;
+    let conn = db_state.connection();
+    settings.save(&conn)
+}
+
+#[tauri::command]
+fn get_perf_capture_settings(db_state: tauri::State<'_, DbState>) -> perf_capture::PerfCaptureSettings {
+    let conn = db_state.connection();
+    perf_capture::PerfCaptureSettings::load(&conn)
+}
+
+/// Takes effect from the next bug capture.
+#[tauri::command]
+fn set_perf_capture_settings(
+    settings: perf_capture::PerfCaptureSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    settings.validate()?;
+    let conn = db_state.connection();
+    settings.save(&conn)
+}
+
+#[tauri::command]
+fn get_cursor_overlay_settings(db_state: tauri::State<'_, DbState>) -> cursor_overlay::CursorOverlaySettings {
+    let conn = db_state.connection();
+    cursor_overlay::CursorOverlaySettings::load(&conn)
+}
+
+#[tauri::command]
+fn set_cursor_overlay_settings(
+    settings: cursor_overlay::CursorOverlaySettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let conn = db_state.connection();
+    settings.save(&conn)?;
+    apply_click_tracker(&settings);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_type_label_settings(db_state: tauri::State<'_, DbState>) -> item_types::TypeLabelSettings {
+    let conn = db_state.connection();
+    item_types::TypeLabelSettings::load(&conn)
+}
+
+#[tauri::command]
+fn set_type_label_settings(
+    settings: item_types::TypeLabelSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let conn = db_state.connection();
+    settings.save(&conn)
+}
+
+#[tauri::command]
+fn get_translation_settings(db_state: tauri::State<'_, DbState>) -> claude_cli::TranslationSettings {
+    let conn = db_state.connection();
+    claude_cli::TranslationSettings::load(&conn)
+}
+
+#[tauri::command]
+fn set_translation_settings(
+    settings: claude_cli::TranslationSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let conn = db_state.connection();
+    settings.save(&conn)
+}
+
+#[tauri::command]
+fn get_diff_annotation_settings(db_state: tauri::State<'_, DbState>) -> capture_diff::DiffAnnotationSettings {
+    let conn = db_state.connection();
+    capture_diff::DiffAnnotationSettings::load(&conn)
+}
+
+#[tauri::command]
+fn set_diff_annotation_settings(
+    settings: capture_diff::DiffAnnotationSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let conn = db_state.connection();
+    settings.save(&conn)
+}
+
+#[tauri::command]
+fn get_network_snapshot_settings(db_state: tauri::State<'_, DbState>) -> network_snapshot::NetworkSnapshotSettings {
+    let conn = db_state.connection();
+    network_snapshot::NetworkSnapshotSettings::load(&conn)
+}
+
+#[tauri::command]
+fn set_network_snapshot_settings(
+    settings: network_snapshot::NetworkSnapshotSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    settings.validate()?;
+    let conn = db_state.connection();
+    settings.save(&conn)
+}
+
+#[tauri::command]
+fn get_crash_dump_settings(db_state: tauri::State<'_, DbState>) -> crash_dumps::CrashDumpSettings {
+    let conn = db_state.connection();
+    crash_dumps::CrashDumpSettings::load(&conn)
+}
+
+/// Takes effect from the next session start or resume.
+#[tauri::command]
+fn set_crash_dump_settings(
+    settings: crash_dumps::CrashDumpSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    settings.validate()?;
+    let conn = db_state.connection();
+    settings.save(&conn)
 }
 
 #[tauri::command]
-fn resume_session(session_id: String, app: AppHandle) -> Result<database::Session, String> {
-    let session = {
-        let manager_guard = SESSION_MANAGER.lock().unwrap();
-        let manager = manager_guard
-            .as_ref()
-            .ok_or("Session manager not initialized")?;
-        manager.resume_session(&session_id)?
-    };
+fn get_capture_filename_pattern(db_state: tauri::State<'_, DbState>) -> String {
+    let conn = db_state.connection();
+    capture_naming::load_pattern(&conn)
+}
 
-    start_capture_watcher_for_session(&session, &app);
-    start_clipboard_watcher_for_session(&session, &app);
-    Ok(session)
+/// Example file name for `pattern`, e.g. `143025_capture-001.png`, or the
+/// reason the pattern is rejected.
+#[tauri::command]
+fn preview_capture_filename(pattern: String) -> Result<String, String> {
+    let naming = capture_naming::NamingContext::new(&pattern, Some("BUG-001".to_string()))?;
+    Ok(make_capture_filename(std::path::Path::new("capture.png"), 1, &naming).0)
 }
 
+/// Set the capture file name pattern; `None` restores the default.
 #[tauri::command]
-fn start_bug_capture(session_id: String) -> Result<database::Bug, String> {
-    let manager_guard = SESSION_MANAGER.lock().unwrap();
-    let manager = manager_guard
-        .as_ref()
-        .ok_or("Session manager not initialized")?;
-    manager.start_bug_capture(&session_id)
+fn set_capture_filename_pattern(pattern: Option<String>, db_state: tauri::State<'_, DbState>) -> Result<(), String> {
+    use database::{SettingsOps, SettingsRepository};
+
+    let conn = db_state.connection();
+    let repo = SettingsRepository::new(&conn);
+    match pattern {
+        Some(pattern) => {
+            capture_naming::validate(&pattern)?;
+            repo.set(capture_naming::FILENAME_PATTERN_KEY, &pattern)
+        }
+        None => repo.delete(capture_naming::FILENAME_PATTERN_KEY),
+    }
+    .map_err(|e| format!("Failed to save file name pattern: {}", e))
 }
 
 #[tauri::command]
-fn end_bug_capture(bug_id: String) -> Result<(), String> {
-    let manager_guard = SESSION_MANAGER.lock().unwrap();
-    let manager = manager_guard
-        .as_ref()
-        .ok_or("Session manager not initialized")?;
-    manager.end_bug_capture(&bug_id)
+fn get_post_export_hook(db_state: tauri::State<'_, DbState>) -> Option<export_hooks::PostExportHook> {
+    let conn = db_state.connection();
+    export_hooks::PostExportHook::load(&conn)
 }
 
-/// Resume capturing for an existing bug — sets its status back to 'capturing' and marks it as the active bug.
-/// Used when the user wants to add more screenshots to a bug that was previously ended.
+/// Configure the command run after each export, or remove it with `None`.
 #[tauri::command]
-fn resume_bug_capture(bug_id: String) -> Result<database::Bug, String> {
-    let manager_guard = SESSION_MANAGER.lock().unwrap();
-    let manager = manager_guard
-        .as_ref()
-        .ok_or("Session manager not initialized")?;
-    manager.resume_bug_capture(&bug_id)
+fn set_post_export_hook(
+    hook: Option<export_hooks::PostExportHook>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    use database::{SettingsOps, SettingsRepository};
+
+    let conn = db_state.connection();
+    match hook {
+        Some(hook) => {
+            hook.validate()?;
+            hook.save(&conn)?;
+            database::record_audit(
+                &conn,
+                "setting.update",
+                "setting",
+                export_hooks::EXPORT_HOOK_KEY,
+                Some(serde_json::json!({ "command": hook.command })),
+            )
+        }
+        None => {
+            SettingsRepository::new(&conn)
+                .delete(export_hooks::EXPORT_HOOK_KEY)
+                .map_err(|e| format!("Failed to remove post-export hook: {}", e))?;
+            database::record_audit(&conn, "setting.delete", "setting", export_hooks::EXPORT_HOOK_KEY, None)
+        }
+    }
+}
+
+/// Start or stop the metrics endpoint to match `settings`.
+fn apply_metrics_settings(settings: &metrics::MetricsSettings) -> Result<(), String> {
+    let mut guard = METRICS_SERVER.lock().unwrap();
+    // Release the port before binding it again
+    *guard = None;
+    if settings.enabled {
+        let server = metrics::MetricsServer::start(settings.port)?;
+        println!("Metrics endpoint listening on http://127.0.0.1:{}/metrics", server.port());
+        *guard = Some(server);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_metrics_settings(db_state: tauri::State<'_, DbState>) -> metrics::MetricsSettings {
+    let conn = db_state.connection();
+    metrics::MetricsSettings::load(&conn)
+}
+
+/// Save the metrics endpoint settings and restart the endpoint.
+#[tauri::command]
+fn set_metrics_settings(
+    settings: metrics::MetricsSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    settings.validate()?;
+    apply_metrics_settings(&settings)?;
+    let conn = db_state.connection();
+    settings.save(&conn)?;
+    database::record_audit(
+        &conn,
+        "setting.update",
+        "setting",
+        metrics::METRICS_KEY,
+        Some(serde_json::json!({ "enabled": settings.enabled, "port": settings.port })),
+    )
 }
 
 #[tauri::command]
@@ -1039,6 +2811,156 @@ fn update_session_status(session_id: String, status: String, db_state: tauri::St
         .map_err(|e| format!("Failed to update session status: {}", e))
 }
 
+/// Unlock a reviewed/synced session so its notes, captures and descriptions
+/// can be edited again. The unlock is recorded in the audit log.
+#[tauri::command]
+fn unlock_session(
+    session_id: String,
+    reason: Option<String>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<database::Session, String> {
+    let conn = db_state.connection();
+    session_lock::unlock_session(&conn, &session_id, reason.as_deref())
+}
+
+/// Record environment values for a session, e.g. `{"build": "4.2.0"}`; a null
+/// value removes the key. Emits `session:environment-changed` when a tracked
+/// value now differs from the previous session of the profile. Returns the
+/// session's environment.
+#[tauri::command]
+fn set_session_environment(
+    session_id: String,
+    values: serde_json::Map<String, serde_json::Value>,
+    db_state: tauri::State<'_, DbState>,
+    app: tauri::AppHandle,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let (environment, diff) = {
+        let conn = db_state.connection();
+        session_lock::ensure_session_editable(&conn, &session_id)?;
+        let previous = session_environment::environment_diff(&conn, &session_id)?;
+        let environment = session_environment::merge_environment(&conn, &session_id, values)?;
+        let diff = session_environment::environment_diff(&conn, &session_id)?;
+        // Only announce differences that are new with this update
+        (environment, diff.filter(|diff| Some(diff) != previous.as_ref()))
+    };
+    if let Some(diff) = diff {
+        let _ = events::emit(
+            &app,
+            &events::SessionEnvironmentChanged {
+                session_id,
+                previous_session_id: diff.previous_session_id,
+                changes: diff.changes,
+            },
+        );
+    }
+    Ok(environment)
+}
+
+/// How a session's build, OS version and GPU driver differ from the previous
+/// session of its profile; None when nothing changed.
+#[tauri::command]
+fn get_session_environment_diff(
+    session_id: String,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<Option<session_environment::EnvironmentDiff>, String> {
+    let conn = db_state.connection();
+    session_environment::environment_diff(&conn, &session_id)
+}
+
+/// Take a network snapshot in the background and store it under `network`
+/// in the session's environment.
+fn record_session_network_snapshot(session: &database::Session, app: &AppHandle) {
+    let db = app.state::<DbState>().arc();
+    let session_id = session.id.clone();
+    std::thread::spawn(move || {
+        let settings = network_snapshot::NetworkSnapshotSettings::load(&db.lock().unwrap());
+        let snapshot = network_snapshot::capture(&settings);
+        let Ok(value) = serde_json::to_value(&snapshot) else {
+            return;
+        };
+        let values = serde_json::Map::from_iter([(network_snapshot::NETWORK_KEY.to_string(), value)]);
+        if let Err(e) = session_environment::merge_environment(&db.lock().unwrap(), &session_id, values) {
+            eprintln!("Warning: failed to record network snapshot: {}", e);
+        }
+    });
+}
+
+/// Attach a fresh network snapshot to a bug tagged as network-related, in the background.
+fn record_bug_network_snapshot(bug_id: &str, app: &AppHandle) {
+    use database::{BugOps, BugRepository};
+
+    let db = app.state::<DbState>().arc();
+    let bug_id = bug_id.to_string();
+    std::thread::spawn(move || {
+        let settings = {
+            let conn = db.lock().unwrap();
+            match BugRepository::new(&conn).get(&bug_id) {
+                Ok(Some(bug)) if network_snapshot::is_network_related(&bug) => {
+                    network_snapshot::NetworkSnapshotSettings::load(&conn)
+                }
+                _ => return,
+            }
+        };
+        let snapshot = network_snapshot::capture(&settings);
+        if let Err(e) = network_snapshot::attach_to_bug(&db.lock().unwrap(), &bug_id, &snapshot) {
+            eprintln!("Warning: failed to attach network snapshot to {}: {}", bug_id, e);
+        }
+    });
+}
+
+/// Record the network type, VPN state and endpoint latency now. The snapshot
+/// is stored under `network` in the session's environment and, when given,
+/// in the bug's metadata.
+#[tauri::command]
+async fn capture_network_snapshot(
+    session_id: Option<String>,
+    bug_id: Option<String>,
+    app: AppHandle,
+) -> Result<network_snapshot::NetworkSnapshot, String> {
+    let db = app.state::<DbState>().arc();
+    tauri::async_runtime::spawn_blocking(move || {
+        let settings = {
+            let conn = db.lock().unwrap();
+            if let Some(session_id) = &session_id {
+                session_lock::ensure_session_editable(&conn, session_id)?;
+            }
+            network_snapshot::NetworkSnapshotSettings::load(&conn)
+        };
+        let snapshot = network_snapshot::capture(&settings);
+
+        let conn = db.lock().unwrap();
+        if let Some(session_id) = &session_id {
+            let value = serde_json::to_value(&snapshot).map_err(|e| e.to_string())?;
+            let values = serde_json::Map::from_iter([(network_snapshot::NETWORK_KEY.to_string(), value)]);
+            session_environment::merge_environment(&conn, session_id, values)?;
+        }
+        if let Some(bug_id) = &bug_id {
+            network_snapshot::attach_to_bug(&conn, bug_id, &snapshot)?;
+        }
+        Ok(snapshot)
+    })
+    .await
+    .map_err(|e| format!("Network snapshot task failed: {}", e))?
+}
+
+/// Where the heartbeat file is written and the last heartbeat, if a session
+/// is active.
+#[tauri::command]
+fn get_heartbeat_status() -> Result<heartbeat::HeartbeatStatus, String> {
+    if let Some(writer) = HEARTBEAT.lock().unwrap().as_ref() {
+        return Ok(writer.status());
+    }
+    let manager_guard = SESSION_MANAGER.lock().unwrap();
+    let manager = manager_guard
+        .as_ref()
+        .ok_or("Session manager not initialized")?;
+    Ok(heartbeat::HeartbeatStatus {
+        path: heartbeat::heartbeat_path(manager.storage_root()).to_string_lossy().to_string(),
+        active: false,
+        last: None,
+    })
+}
+
 #[tauri::command]
 fn get_bugs_by_session(session_id: String, db_state: tauri::State<'_, DbState>) -> Result<Vec<database::Bug>, String> {
     use database::{BugRepository, BugOps};
@@ -1049,6 +2971,21 @@ fn get_bugs_by_session(session_id: String, db_state: tauri::State<'_, DbState>)
         .map_err(|e| format!("Failed to get bugs for session: {}", e))
 }
 
+/// Bugs of a session matching the review screen's quick filters, by bug number.
+#[tauri::command]
+fn list_bugs_filtered(
+    session_id: String,
+    filter: database::BugFilter,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<Vec<database::Bug>, String> {
+    use database::{BugRepository, BugOps};
+
+    let conn = db_state.connection();
+    BugRepository::new(&conn)
+        .list_filtered(&session_id, &filter)
+        .map_err(|e| format!("Failed to filter bugs: {}", e))
+}
+
 #[tauri::command]
 fn get_bug(bug_id: String, db_state: tauri::State<'_, DbState>) -> Result<Option<database::Bug>, String> {
     use database::{BugRepository, BugOps};
@@ -1071,6 +3008,71 @@ fn generate_session_summary(
     generator.generate_summary(&session_id, include_ai_summary)
 }
 
+/// Write session-bugs.csv for a session, formatted for the export locale.
+#[tauri::command]
+fn export_session_csv(session_id: String, db_state: tauri::State<'_, DbState>) -> Result<String, String> {
+    let path = session_summary::SessionSummaryGenerator::new(db_state.arc()).generate_csv_export(&session_id)?;
+    export_hooks::spawn(db_state.arc(), &session_id, std::path::Path::new(&path), "csv");
+    Ok(path)
+}
+
+/// Write session-report.pdf for a session: the summary, every bug's notes and
+/// descriptions, and its screenshots embedded.
+#[tauri::command]
+async fn generate_session_report_pdf(session_id: String, app: AppHandle) -> Result<String, String> {
+    let db = app.state::<DbState>().arc();
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = session_summary::SessionSummaryGenerator::new(Arc::clone(&db)).generate_pdf_report(&session_id)?;
+        export_hooks::spawn(db, &session_id, std::path::Path::new(&path), "pdf");
+        Ok(path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Locale tags accepted by the `export.locale` setting.
+#[tauri::command]
+fn get_export_locales() -> Vec<&'static str> {
+    time_format::ExportLocale::supported_tags()
+}
+
+// ─── Demo Data Commands ──────────────────────────────────────────────────
+
+/// Seed a fake, fully populated session (bugs, placeholder captures, notes,
+/// summary) for demos and UI profiling. Only available in debug builds or
+/// when the app is launched with `QA_CAPTURE_DEMO=1`.
+#[tauri::command]
+fn seed_demo_data(db_state: tauri::State<'_, DbState>) -> Result<database::Session, String> {
+    use session_summary::SessionSummaryGenerator;
+    use session_json::SessionJsonWriter;
+
+    if !cfg!(debug_assertions) && std::env::var("QA_CAPTURE_DEMO").as_deref() != Ok("1") {
+        return Err("Demo data is only available in development or demo builds".to_string());
+    }
+
+    let storage_root = {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        manager.storage_root().to_path_buf()
+    };
+
+    let session = {
+        let conn = db_state.connection();
+        demo_data::seed_demo_session(&conn, &storage_root)?
+    };
+
+    if let Err(e) = SessionSummaryGenerator::new(db_state.arc()).generate_summary(&session.id, false) {
+        eprintln!("Warning: Failed to generate demo session summary: {}", e);
+    }
+    if let Err(e) = SessionJsonWriter::new(db_state.arc()).write(&session.id) {
+        eprintln!("Warning: Failed to write demo .session.json: {}", e);
+    }
+
+    Ok(session)
+}
+
 // ─── Hotkey Manager Commands ─────────────────────────────────────────────
 
 #[tauri::command]
@@ -1083,69 +3085,307 @@ fn get_hotkey_config() -> Result<HotkeyConfig, String> {
 }
 
 #[tauri::command]
-fn update_hotkey_config(
-    config: HotkeyConfig,
-    app_handle: tauri::AppHandle,
+fn update_hotkey_config(
+    config: HotkeyConfig,
+    app_handle: tauri::AppHandle,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<Vec<String>, String> {
+    use database::{SettingsRepository, SettingsOps};
+
+    let manager_guard = HOTKEY_MANAGER.lock().unwrap();
+    let manager = manager_guard
+        .as_ref()
+        .ok_or("Hotkey manager not initialized")?;
+
+    // Actions the caller left out keep their current binding
+    let config = config.with_missing_from(&manager.get_config());
+
+    manager.save_to_settings(&config, |key, value| {
+        let conn = db_state.connection();
+        let repo = SettingsRepository::new(&conn);
+        repo.set(key, value).map_err(|e: rusqlite::Error| e.to_string())
+    })?;
+
+    // Update the runtime config and re-register hotkeys
+    let results = manager.update_config(&app_handle, config);
+
+    // Collect error messages
+    let errors: Vec<String> = results
+        .into_iter()
+        .filter_map(|r| r.err())
+        .collect();
+
+    Ok(errors)
+}
+
+#[tauri::command]
+fn is_hotkey_registered(shortcut: String) -> Result<bool, String> {
+    let manager_guard = HOTKEY_MANAGER.lock().unwrap();
+    let manager = manager_guard
+        .as_ref()
+        .ok_or("Hotkey manager not initialized")?;
+    Ok(manager.is_registered(&shortcut))
+}
+
+// ─── Ticketing Integration Commands ──────────────────────────────────────
+
+#[tauri::command]
+fn ticketing_authenticate(credentials: ticketing::TicketingCredentials) -> Result<(), String> {
+    let integration_guard = TICKETING_INTEGRATION.lock().unwrap();
+    let integration = integration_guard
+        .as_ref()
+        .ok_or("Ticketing integration not initialized")?;
+
+    integration
+        .authenticate(&credentials)
+        .map_err(|e| e.to_string())
+}
+
+/// Send `request` with the active integration, linking the files in the
+/// description when the service does not take uploads.
+fn send_ticket(request: &ticketing::CreateTicketRequest) -> ticketing::TicketingResult<ticketing::CreateTicketResponse> {
+    let integration = TICKETING_INTEGRATION
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| ticketing::TicketingError::InvalidConfig("Ticketing integration not initialized".to_string()))?;
+
+    let response = if integration.supports_binary_attachments() {
+        integration.create_ticket(request)?
+    } else {
+        let mut linked = request.clone();
+        linked.description.push_str(&request.attachment_links());
+        linked.attachments.clear();
+        linked.captures.clear();
+        integration.create_ticket(&linked)?
+    };
+    metrics::increment(metrics::Counter::TicketCreations);
+    Ok(response)
+}
+
+/// Record a created ticket on its bug and keep the uploaded URLs on the
+/// captures they came from.
+fn record_created_ticket(
+    conn: &rusqlite::Connection,
+    bug_id: &str,
+    request: &ticketing::CreateTicketRequest,
+    response: &ticketing::CreateTicketResponse,
+) -> Result<(), String> {
+    use database::{BugOps, BugRepository, CaptureOps, CaptureRepository};
+
+    BugRepository::new(conn)
+        .set_external_ticket(bug_id, &response.id, &response.identifier, &response.url)
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+
+    let uploaded = |path: &str| {
+        response
+            .attachment_results
+            .iter()
+            .find(|r| r.success && r.file_path == path && !r.message.is_empty())
+            .map(|r| r.message.as_str())
+    };
+    let repo = CaptureRepository::new(conn);
+    for capture in &request.captures {
+        let url = uploaded(&capture.file_path);
+        let annotated_url = capture.annotated_path.as_deref().and_then(uploaded);
+        if url.is_some() || annotated_url.is_some() {
+            repo.set_attachment_urls(&capture.capture_id, url, annotated_url)
+                .map_err(|e: rusqlite::Error| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Create a ticket. When `bug_id` is given the ticket is recorded on that bug
+/// so summaries and exports can link to it, the labels for its type are added
+/// (see `item_types`), the bug's captures are attached (unless
+/// `request.captures` is set) and their upload URLs are stored.
+///
+/// If the service cannot be reached the request is put in the retry queue
+/// (`ticketing:queued`) and an error is still returned.
+#[tauri::command]
+fn ticketing_create_ticket(
+    mut request: ticketing::CreateTicketRequest,
+    bug_id: Option<String>,
+    app: AppHandle,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<ticketing::CreateTicketResponse, String> {
+    use database::{CaptureOps, CaptureRepository};
+
+    // Label the ticket by item type so feedback and questions are triaged apart
+    let mut notes = None;
+    if let Some(bug_id) = bug_id.as_deref() {
+        use database::{BugOps, BugRepository};
+        let conn = db_state.connection();
+        if let Some(bug) = BugRepository::new(&conn).get(bug_id).map_err(|e: rusqlite::Error| e.to_string())? {
+            item_types::TypeLabelSettings::load(&conn).apply(&bug.bug_type, &mut request.labels);
+            notes = bug.notes.filter(|notes| !notes.trim().is_empty());
+        }
+    }
+
+    // Notes written in another language are translated, with the original kept below.
+    // Tester notes the description does not already carry get their own section.
+    if let Some(translator) = ticket_translator(&db_state) {
+        let notes = notes.filter(|notes| !request.description.contains(notes.trim()));
+        request.description = translator.translate_or_keep(&request.description);
+        if let Some(notes) = notes {
+            request.description.push_str("\n\n**Tester notes:**\n\n");
+            request.description.push_str(&translator.translate_or_keep(&notes));
+        }
+    }
+
+    // Attach the bug's captures unless the caller picked them
+    if let Some(bug_id) = bug_id.as_deref().filter(|_| request.captures.is_empty()) {
+        let conn = db_state.connection();
+        let repo = CaptureRepository::new(&conn);
+        request.captures = repo
+            .list_by_bug(bug_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?
+            .into_iter()
+            .map(|capture| ticketing::CaptureAttachment {
+                capture_id: capture.id,
+                alt_text: capture.alt_text,
+                file_path: capture.file_path,
+                annotated_path: capture
+                    .annotated_path
+                    .filter(|path| std::path::Path::new(path).exists()),
+            })
+            .collect();
+    }
+
+    let response = match send_ticket(&request) {
+        Ok(response) => response,
+        Err(e) if ticketing::is_retryable(&e) => {
+            let entry = {
+                let conn = db_state.connection();
+                ticketing::enqueue(&conn, bug_id.as_deref(), &request, &e)?
+            };
+            let _ = events::emit(&app, &ticketing::queued_event(&entry));
+            return Err(format!("{} (queued for retry)", e));
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+
+    if let Some(bug_id) = bug_id {
+        {
+            let conn = db_state.connection();
+            record_created_ticket(&conn, &bug_id, &request, &response)?;
+        }
+        queue_metadata_sync(&bug_id);
+    }
+
+    Ok(response)
+}
+
+/// The session's items without a ticket, one batch per type (bugs, feature
+/// requests, feedback, questions) with the labels its tickets get.
+#[tauri::command]
+fn ticketing_plan_batches(
+    session_id: String,
     db_state: tauri::State<'_, DbState>,
-) -> Result<Vec<String>, String> {
-    use database::{SettingsRepository, SettingsOps};
-
-    let manager_guard = HOTKEY_MANAGER.lock().unwrap();
-    let manager = manager_guard
-        .as_ref()
-        .ok_or("Hotkey manager not initialized")?;
-
-    manager.save_to_settings(&config, |key, value| {
-        let conn = db_state.connection();
-        let repo = SettingsRepository::new(&conn);
-        repo.set(key, value).map_err(|e: rusqlite::Error| e.to_string())
-    })?;
+) -> Result<Vec<item_types::TicketBatch>, String> {
+    use database::{BugOps, BugRepository};
 
-    // Update the runtime config and re-register hotkeys
-    let results = manager.update_config(&app_handle, config);
+    let conn = db_state.connection();
+    let bugs = BugRepository::new(&conn)
+        .list_by_session(&session_id)
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+    Ok(item_types::ticket_batches(&bugs, &item_types::TypeLabelSettings::load(&conn)))
+}
 
-    // Collect error messages
-    let errors: Vec<String> = results
-        .into_iter()
-        .filter_map(|r| r.err())
-        .collect();
+/// Tickets in the retry queue, oldest first, including sent and failed ones.
+#[tauri::command]
+fn ticketing_list_queue(db_state: tauri::State<'_, DbState>) -> Result<Vec<database::QueuedTicket>, String> {
+    use database::{TicketQueueOps, TicketQueueRepository};
 
-    Ok(errors)
+    let conn = db_state.connection();
+    TicketQueueRepository::new(&conn)
+        .list()
+        .map_err(|e: rusqlite::Error| e.to_string())
 }
 
+/// Retry a queued or failed ticket on the worker's next poll.
 #[tauri::command]
-fn is_hotkey_registered(shortcut: String) -> Result<bool, String> {
-    let manager_guard = HOTKEY_MANAGER.lock().unwrap();
-    let manager = manager_guard
-        .as_ref()
-        .ok_or("Hotkey manager not initialized")?;
-    Ok(manager.is_registered(&shortcut))
-}
+fn ticketing_retry_queued(queue_id: String, db_state: tauri::State<'_, DbState>) -> Result<(), String> {
+    use database::{TicketQueueOps, TicketQueueRepository};
 
-// ─── Ticketing Integration Commands ──────────────────────────────────────
+    let conn = db_state.connection();
+    let rescheduled = TicketQueueRepository::new(&conn)
+        .reschedule(&queue_id, &chrono::Utc::now().to_rfc3339())
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+    if !rescheduled {
+        return Err(format!("No unsent queued ticket {}", queue_id));
+    }
+    Ok(())
+}
 
+/// Remove a ticket from the retry queue without sending it.
 #[tauri::command]
-fn ticketing_authenticate(credentials: ticketing::TicketingCredentials) -> Result<(), String> {
-    let integration_guard = TICKETING_INTEGRATION.lock().unwrap();
-    let integration = integration_guard
-        .as_ref()
-        .ok_or("Ticketing integration not initialized")?;
+fn ticketing_discard_queued(queue_id: String, db_state: tauri::State<'_, DbState>) -> Result<(), String> {
+    use database::{TicketQueueOps, TicketQueueRepository};
 
-    integration
-        .authenticate(&credentials)
-        .map_err(|e| e.to_string())
+    let conn = db_state.connection();
+    TicketQueueRepository::new(&conn)
+        .delete(&queue_id)
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+    Ok(())
 }
 
+/// Fetch the provider status of the tickets filed for a session's bugs (or
+/// only `bug_ids`) and store it on the bugs, so the review screen can show
+/// which bugs are already fixed upstream. A bug whose status cannot be
+/// fetched keeps its last known status and reports the error.
 #[tauri::command]
-fn ticketing_create_ticket(request: ticketing::CreateTicketRequest) -> Result<ticketing::CreateTicketResponse, String> {
-    let integration_guard = TICKETING_INTEGRATION.lock().unwrap();
-    let integration = integration_guard
-        .as_ref()
+async fn ticketing_sync_status(
+    session_id: String,
+    bug_ids: Option<Vec<String>>,
+    app: AppHandle,
+) -> Result<Vec<ticketing::TicketStatusSync>, String> {
+    use database::{BugFilter, BugOps, BugRepository};
+
+    let integration = TICKETING_INTEGRATION
+        .lock()
+        .unwrap()
+        .clone()
         .ok_or("Ticketing integration not initialized")?;
+    let db = app.state::<DbState>().arc();
 
-    integration
-        .create_ticket(&request)
-        .map_err(|e| e.to_string())
+    tauri::async_runtime::spawn_blocking(move || {
+        let bugs = {
+            let conn = db.lock().unwrap();
+            let filter = BugFilter { has_ticket: Some(true), ..Default::default() };
+            BugRepository::new(&conn)
+                .list_filtered(&session_id, &filter)
+                .map_err(|e: rusqlite::Error| e.to_string())?
+        };
+
+        let mut results = Vec::new();
+        for bug in bugs {
+            if bug_ids.as_ref().is_some_and(|ids| !ids.contains(&bug.id)) {
+                continue;
+            }
+            let Some(ticket_id) = bug.external_ticket_id.as_deref() else { continue };
+            let (status, error) = match integration.fetch_ticket_status(ticket_id) {
+                Ok(status) => {
+                    let conn = db.lock().unwrap();
+                    BugRepository::new(&conn)
+                        .set_external_status(&bug.id, &status.name, status.category.as_str())
+                        .map_err(|e: rusqlite::Error| e.to_string())?;
+                    (Some(status), None)
+                }
+                Err(e) => (None, Some(e.to_string())),
+            };
+            results.push(ticketing::TicketStatusSync {
+                bug_id: bug.id,
+                ticket_key: bug.external_ticket_key,
+                status,
+                error,
+            });
+        }
+        Ok(results)
+    })
+    .await
+    .map_err(|e| format!("Ticket status sync failed: {}", e))?
 }
 
 #[tauri::command]
@@ -1193,8 +3433,11 @@ fn ticketing_save_credentials(
     let conn = db_state.connection();
     let repo = SettingsRepository::new(&conn);
 
-    // Save credentials
-    repo.set("ticketing.api_key", &credentials.api_key).map_err(|e: rusqlite::Error| e.to_string())?;
+    // Save credentials; a masked key read back from the settings keeps the stored one
+    let api_key_changed = credentials.api_key != settings_schema::MASK;
+    if api_key_changed {
+        repo.set("ticketing.api_key", &credentials.api_key).map_err(|e: rusqlite::Error| e.to_string())?;
+    }
 
     if let Some(team_id) = &credentials.team_id {
         repo.set("ticketing.team_id", team_id).map_err(|e: rusqlite::Error| e.to_string())?;
@@ -1204,9 +3447,85 @@ fn ticketing_save_credentials(
         repo.set("ticketing.workspace_id", workspace_id).map_err(|e: rusqlite::Error| e.to_string())?;
     }
 
+    // Record which fields changed — never the secret values themselves.
+    database::record_audit(
+        &conn,
+        "credentials.update",
+        "ticketing",
+        "linear",
+        Some(serde_json::json!({
+            "api_key": api_key_changed,
+            "team_id": credentials.team_id.is_some(),
+            "workspace_id": credentials.workspace_id.is_some(),
+        })),
+    )
+}
+
+/// Replace the active integration with a fresh one for the configured
+/// provider. The new integration needs to authenticate again.
+fn reload_ticketing_integration(conn: &rusqlite::Connection) {
+    *TICKETING_INTEGRATION.lock().unwrap() = Some(ticketing::build_integration(conn));
+}
+
+#[tauri::command]
+fn ticketing_get_provider(db_state: tauri::State<'_, DbState>) -> ticketing::TicketingProvider {
+    let conn = db_state.connection();
+    ticketing::TicketingProvider::load(&conn)
+}
+
+#[tauri::command]
+fn ticketing_set_provider(
+    provider: ticketing::TicketingProvider,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let conn = db_state.connection();
+    provider.save(&conn)?;
+    reload_ticketing_integration(&conn);
     Ok(())
 }
 
+/// Stored Jira settings with the API token masked, like other secrets.
+#[tauri::command]
+fn ticketing_get_jira_config(db_state: tauri::State<'_, DbState>) -> ticketing::JiraConfig {
+    let conn = db_state.connection();
+    let mut config = ticketing::JiraConfig::load(&conn);
+    if !config.api_token.is_empty() {
+        config.api_token = settings_schema::MASK.to_string();
+    }
+    config
+}
+
+/// Save the Jira settings. A masked token read back from
+/// `ticketing_get_jira_config` keeps the stored one.
+#[tauri::command]
+fn ticketing_save_jira_config(
+    mut config: ticketing::JiraConfig,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let conn = db_state.connection();
+    if config.api_token == settings_schema::MASK {
+        config.api_token = ticketing::JiraConfig::load(&conn).api_token;
+    }
+    config.validate()?;
+    config.save(&conn)?;
+    if ticketing::TicketingProvider::load(&conn) == ticketing::TicketingProvider::Jira {
+        reload_ticketing_integration(&conn);
+    }
+
+    // Record the change — never the token itself.
+    database::record_audit(
+        &conn,
+        "credentials.update",
+        "ticketing",
+        "jira",
+        Some(serde_json::json!({
+            "site_url": config.site_url.trim(),
+            "project_key": config.project_key.trim(),
+            "api_token": true,
+        })),
+    )
+}
+
 #[tauri::command]
 fn ticketing_fetch_teams() -> Result<Vec<ticketing::LinearTeam>, String> {
     let integration_guard = TICKETING_INTEGRATION.lock().unwrap();
@@ -1241,7 +3560,7 @@ fn get_linear_profile_defaults(db_state: tauri::State<'_, DbState>) -> Result<Op
 
     // Get active profile ID from settings
     let active_id = settings_repo
-        .get("active_profile_id")
+        .get(profile::ACTIVE_PROFILE_KEY)
         .map_err(|e: rusqlite::Error| e.to_string())?;
 
     let profile_id = match active_id {
@@ -1258,6 +3577,126 @@ fn get_linear_profile_defaults(db_state: tauri::State<'_, DbState>) -> Result<Op
 
 // Claude API commands — uses Claude Code OAuth (no API key needed)
 
+/// Settings key: longest image edge, in pixels, sent to Claude.
+const CLAUDE_MAX_IMAGE_DIMENSION_KEY: &str = "claude.max_image_dimension";
+
+/// Settings key: seconds a cached Claude response is reused.
+const CLAUDE_CACHE_TTL_KEY: &str = "claude.cache_ttl_secs";
+
+/// Credentials for a Claude request, unless AI suggestions were switched off
+/// (e.g. from the tray while on a metered connection).
+fn claude_credentials(db_state: &DbState) -> Result<claude_cli::ClaudeCredentials, String> {
+    if !quick_toggles::AI.is_on(&db_state.connection()) {
+        return Err("AI suggestions are turned off".to_string());
+    }
+    claude_cli::load_credentials().map_err(|e| format!("Claude not ready: {}", e))
+}
+
+/// API client that downscales attachments to the configured maximum dimension
+/// and answers repeated requests from the response cache.
+///
+/// Takes the database lock briefly; callers must not hold it.
+fn claude_invoker(
+    creds: claude_cli::ClaudeCredentials,
+    db: Arc<Mutex<rusqlite::Connection>>,
+) -> claude_cli::CachedClaudeInvoker {
+    use database::{SettingsRepository, SettingsOps};
+
+    let (max_dimension, ttl) = {
+        let conn = db.lock().unwrap();
+        let repo = SettingsRepository::new(&conn);
+        let number = |key: &str| {
+            repo.get(key)
+                .ok()
+                .flatten()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        (
+            number(CLAUDE_MAX_IMAGE_DIMENSION_KEY)
+                .filter(|&v| v > 0)
+                .map(|v| v.min(u32::MAX as u64) as u32)
+                .unwrap_or(claude_cli::DEFAULT_MAX_IMAGE_DIMENSION),
+            number(CLAUDE_CACHE_TTL_KEY)
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(claude_cli::DEFAULT_CACHE_TTL),
+        )
+    };
+    let real = claude_cli::RealClaudeInvoker::new(creds).with_max_image_dimension(max_dimension);
+    claude_cli::CachedClaudeInvoker::new(Arc::new(real), db).with_ttl(ttl)
+}
+
+/// Translator for ticket text when translation is enabled (see
+/// `claude_cli::TranslationSettings`); None when it is off or Claude is not
+/// available, in which case text goes out as written.
+///
+/// Takes the database lock briefly; callers must not hold it.
+fn ticket_translator(db_state: &DbState) -> Option<claude_cli::Translator> {
+    let settings = claude_cli::TranslationSettings::load(&db_state.connection());
+    if !settings.enabled {
+        return None;
+    }
+    let creds = claude_credentials(db_state)
+        .map_err(|e| eprintln!("Warning: notes not translated: {}", e))
+        .ok()?;
+    let params = ai_params(db_state, claude_cli::ModelTask::Translate, None).ok()?;
+    let invoker = Arc::new(claude_invoker(creds, db_state.arc()));
+    Some(claude_cli::Translator::new(invoker, &settings.target_language, params))
+}
+
+/// Model parameters for a request: validated per-request overrides on top of
+/// the stored settings for `task`.
+fn ai_params(
+    db_state: &DbState,
+    task: claude_cli::ModelTask,
+    overrides: Option<claude_cli::ModelParams>,
+) -> Result<claude_cli::ModelParams, String> {
+    if let Some(overrides) = &overrides {
+        overrides.validate()?;
+    }
+    let conn = db_state.connection();
+    Ok(claude_cli::AiModelSettings::load(&conn).resolve(task, overrides))
+}
+
+/// Apply the `bypass_cache` flag sent by the frontend.
+fn with_cache_flag(request: claude_cli::ClaudeRequest, bypass_cache: Option<bool>) -> claude_cli::ClaudeRequest {
+    if bypass_cache.unwrap_or(false) {
+        request.bypassing_cache()
+    } else {
+        request
+    }
+}
+
+#[tauri::command]
+fn get_ai_model_settings(db_state: tauri::State<'_, DbState>) -> claude_cli::AiModelSettings {
+    claude_cli::AiModelSettings::load(&db_state.connection())
+}
+
+/// Models offered by the provider for the signed-in account.
+#[tauri::command]
+async fn list_claude_models() -> Result<Vec<claude_cli::ModelInfo>, String> {
+    let creds = claude_cli::load_credentials()
+        .map_err(|e| format!("Claude not ready: {}", e))?;
+    claude_cli::RealClaudeInvoker::new(creds)
+        .list_models()
+        .map_err(|e| format!("Failed to list models: {}", e))
+}
+
+/// Save per-task model settings after checking the configured models against
+/// the provider's current model list. Models already saved were checked then,
+/// so the list is only fetched when a new model is chosen.
+#[tauri::command]
+async fn save_ai_model_settings(
+    settings: claude_cli::AiModelSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let mut known = claude_cli::AiModelSettings::load(&db_state.connection()).models();
+    if settings.models().iter().any(|model| !known.contains(model)) {
+        known.extend(list_claude_models().await?.into_iter().map(|m| m.id));
+    }
+    settings.validate(&known)?;
+    settings.save(&db_state.connection())
+}
+
 #[tauri::command]
 fn get_claude_status() -> claude_cli::ClaudeStatus {
     claude_cli::get_claude_status()
@@ -1271,12 +3710,29 @@ fn refresh_claude_status() -> claude_cli::ClaudeStatus {
 #[tauri::command]
 async fn generate_bug_description(
     bug_context: claude_cli::BugContext,
+    bypass_cache: Option<bool>,
+    model_overrides: Option<claude_cli::ModelParams>,
+    db_state: tauri::State<'_, DbState>,
 ) -> Result<claude_cli::ClaudeResponse, String> {
-    use claude_cli::{PromptBuilder, PromptTask, ClaudeRequest, RealClaudeInvoker, ClaudeInvoker};
+    use claude_cli::{PromptBuilder, PromptTask, ClaudeRequest, ClaudeInvoker, ModelTask};
+    use database::{BugOps, BugRepository};
 
     // Load credentials from Claude Code OAuth
-    let creds = claude_cli::load_credentials()
-        .map_err(|e| format!("Claude not ready: {}", e))?;
+    let creds = claude_credentials(&db_state)?;
+
+    // Use the bug's accessibility-tree snapshot, if one was recorded
+    let mut bug_context = bug_context;
+    if bug_context.ui_tree.is_none() {
+        let folder = BugRepository::new(&db_state.connection())
+            .get(&bug_context.bug_id)
+            .ok()
+            .flatten()
+            .map(|bug| std::path::PathBuf::from(bug.folder_path));
+        bug_context.ui_tree = folder.and_then(|f| ui_tree::load(&f)).map(|t| ui_tree::outline(&t));
+    }
+    if bug_context.glossary.is_none() {
+        bug_context.glossary = bug_glossary(&db_state.connection(), &bug_context.bug_id).prompt_list();
+    }
 
     // Build prompt
     let prompt = PromptBuilder::build_prompt(
@@ -1291,25 +3747,28 @@ async fn generate_bug_description(
         bug_context.screenshot_paths.clone(),
         PromptTask::DescribeBug,
     )
-    .with_bug_id(bug_context.bug_id.clone());
+    .with_bug_id(bug_context.bug_id.clone())
+    .with_params(ai_params(&db_state, ModelTask::Describe, model_overrides)?);
 
-    // Invoke Claude API
-    let invoker = RealClaudeInvoker::new(creds);
+    // Invoke Claude API (or reuse a cached response)
+    let invoker = claude_invoker(creds, db_state.arc());
     invoker
-        .invoke(request)
+        .invoke(with_cache_flag(request, bypass_cache))
         .map_err(|e| format!("Failed to generate description: {}", e))
 }
 
 #[tauri::command]
 async fn parse_console_screenshot(
     screenshot_path: String,
+    bypass_cache: Option<bool>,
+    model_overrides: Option<claude_cli::ModelParams>,
+    db_state: tauri::State<'_, DbState>,
 ) -> Result<claude_cli::ClaudeResponse, String> {
-    use claude_cli::{PromptBuilder, PromptTask, ClaudeRequest, RealClaudeInvoker, ClaudeInvoker};
+    use claude_cli::{PromptBuilder, PromptTask, ClaudeRequest, ClaudeInvoker, ModelTask};
     use std::path::PathBuf;
 
     // Load credentials from Claude Code OAuth
-    let creds = claude_cli::load_credentials()
-        .map_err(|e| format!("Claude not ready: {}", e))?;
+    let creds = claude_credentials(&db_state)?;
 
     // Build prompt
     let prompt = PromptBuilder::build_console_parse_prompt();
@@ -1319,12 +3778,13 @@ async fn parse_console_screenshot(
         prompt,
         vec![PathBuf::from(screenshot_path)],
         PromptTask::ParseConsole,
-    );
+    )
+    .with_params(ai_params(&db_state, ModelTask::ParseConsole, model_overrides)?);
 
-    // Invoke Claude API
-    let invoker = RealClaudeInvoker::new(creds);
+    // Invoke Claude API (or reuse a cached response)
+    let invoker = claude_invoker(creds, db_state.arc());
     invoker
-        .invoke(request)
+        .invoke(with_cache_flag(request, bypass_cache))
         .map_err(|e| format!("Failed to parse console: {}", e))
 }
 
@@ -1333,27 +3793,33 @@ async fn refine_bug_description(
     current_description: String,
     refinement_instructions: String,
     bug_id: String,
+    bypass_cache: Option<bool>,
+    model_overrides: Option<claude_cli::ModelParams>,
+    db_state: tauri::State<'_, DbState>,
 ) -> Result<claude_cli::ClaudeResponse, String> {
-    use claude_cli::{PromptBuilder, PromptTask, ClaudeRequest, RealClaudeInvoker, ClaudeInvoker};
+    use claude_cli::{PromptBuilder, PromptTask, ClaudeRequest, ClaudeInvoker, ModelTask};
 
     // Load credentials from Claude Code OAuth
-    let creds = claude_cli::load_credentials()
-        .map_err(|e| format!("Claude not ready: {}", e))?;
+    let creds = claude_credentials(&db_state)?;
 
     // Build refinement prompt
-    let prompt = PromptBuilder::build_refinement_prompt(
+    let mut prompt = PromptBuilder::build_refinement_prompt(
         &current_description,
         &refinement_instructions,
     );
+    if let Some(glossary) = bug_glossary(&db_state.connection(), &bug_id).prompt_list() {
+        prompt.push_str(&PromptBuilder::glossary_section(&glossary));
+    }
 
     // Create request
     let request = ClaudeRequest::new_text(prompt, PromptTask::RefineDescription)
-        .with_bug_id(bug_id);
+        .with_bug_id(bug_id)
+        .with_params(ai_params(&db_state, ModelTask::Refine, model_overrides)?);
 
-    // Invoke Claude API
-    let invoker = RealClaudeInvoker::new(creds);
+    // Invoke Claude API (or reuse a cached response)
+    let invoker = claude_invoker(creds, db_state.arc());
     invoker
-        .invoke(request)
+        .invoke(with_cache_flag(request, bypass_cache))
         .map_err(|e| format!("Failed to refine description: {}", e))
 }
 
@@ -1365,17 +3831,15 @@ async fn suggest_capture_assignment(
 ) -> Result<claude_cli::CaptureAssignmentSuggestion, String> {
     use claude_cli::{
         BugSummary, CaptureAssignmentSuggestion, ClaudeInvoker, ClaudeRequest, PromptBuilder,
-        PromptTask, RealClaudeInvoker,
+        PromptTask,
     };
     use database::{BugOps, BugRepository, CaptureOps, CaptureRepository};
     use std::path::PathBuf;
 
-    const MAX_IMAGE_SIZE: u64 = 1_048_576; // 1 MB
     const MAX_BUGS_WITH_IMAGES: usize = 5;
 
     // 1. Load credentials
-    let creds = claude_cli::load_credentials()
-        .map_err(|e| format!("Claude not ready: {}", e))?;
+    let creds = claude_credentials(&db_state)?;
 
     // 2. Fetch capture + bugs from the shared database connection, then release lock.
     let (capture, bugs) = {
@@ -1409,15 +3873,6 @@ async fn suggest_capture_assignment(
             capture_path.display()
         ));
     }
-    let capture_file_size = std::fs::metadata(&capture_path)
-        .map(|m| m.len())
-        .unwrap_or(0);
-    if capture_file_size > MAX_IMAGE_SIZE {
-        return Err(format!(
-            "Capture file too large ({} bytes, max {}). Cannot send to AI.",
-            capture_file_size, MAX_IMAGE_SIZE
-        ));
-    }
     image_paths.push(capture_path);
 
     // 4. For each bug, build a summary and optionally collect a reference screenshot
@@ -1443,8 +3898,9 @@ async fn suggest_capture_assignment(
                     if !bc_path.exists() {
                         continue;
                     }
+                    // Large images are downscaled by the invoker; only skip empty files
                     let size = std::fs::metadata(&bc_path).map(|m| m.len()).unwrap_or(0);
-                    if size > MAX_IMAGE_SIZE || size == 0 {
+                    if size == 0 {
                         continue;
                     }
                     // Only include images (not videos)
@@ -1473,7 +3929,9 @@ async fn suggest_capture_assignment(
     // 6. Create request with images and call Claude API
     let request = ClaudeRequest::new_with_images(prompt, image_paths, PromptTask::Custom);
 
-    let invoker = RealClaudeInvoker::new(creds);
+    // Release the database before the (possibly long) API call
+    drop(conn_for_loop);
+    let invoker = claude_invoker(creds, db_state.arc());
     let response = invoker
         .invoke(request)
         .map_err(|e| format!("AI suggestion failed: {}", e))?;
@@ -1535,13 +3993,113 @@ async fn suggest_capture_assignment(
     })
 }
 
+/// Ask the model for a severity for the bug, judged by the team's rubric.
+/// The suggestion is stored next to the bug; the bug's own severity is never
+/// changed.
+#[tauri::command]
+async fn classify_bug_severity(
+    bug_id: String,
+    bypass_cache: Option<bool>,
+    model_overrides: Option<claude_cli::ModelParams>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<claude_cli::SeveritySuggestion, String> {
+    use claude_cli::{ClaudeInvoker, ClaudeRequest, ModelTask, PromptBuilder, PromptTask, SeverityContext};
+    use database::{BugOps, BugRepository, CaptureOps, CaptureRepository};
+
+    let creds = claude_credentials(&db_state)?;
+
+    // Gather everything from the database, then release the lock for the API call
+    let (context, rubric, levels) = {
+        let conn = db_state.connection();
+        let bug = BugRepository::new(&conn)
+            .get(&bug_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Bug not found: {}", bug_id))?;
+        let capture_count = CaptureRepository::new(&conn)
+            .list_by_bug(&bug_id)
+            .map_err(|e| e.to_string())?
+            .len();
+        let context = SeverityContext {
+            display_id: bug.display_id,
+            title: bug.title,
+            description: bug.description.or(bug.ai_description),
+            console_parse: bug.console_parse_json,
+            capture_count,
+        };
+        (context, claude_cli::load_rubric(&conn), claude_cli::levels_for_session(&conn, &bug.session_id))
+    };
+
+    let prompt = PromptBuilder::build_severity_prompt(&context, &rubric, &levels);
+    let request = ClaudeRequest::new_text(prompt, PromptTask::Custom)
+        .with_bug_id(bug_id.clone())
+        .with_params(ai_params(&db_state, ModelTask::Classify, model_overrides)?);
+
+    let invoker = claude_invoker(creds, db_state.arc());
+    let response = invoker
+        .invoke(with_cache_flag(request, bypass_cache))
+        .map_err(|e| format!("Failed to classify severity: {}", e))?;
+
+    let suggestion = claude_cli::parse_severity_response(
+        &bug_id,
+        &response.content,
+        &levels,
+        &chrono::Utc::now().to_rfc3339(),
+    )
+    .map_err(|e| format!("Failed to classify severity: {}", e))?;
+
+    let json = serde_json::to_string(&suggestion).map_err(|e| e.to_string())?;
+    BugRepository::new(&db_state.connection())
+        .set_severity_suggestion(&bug_id, &json)
+        .map_err(|e| e.to_string())?;
+
+    Ok(suggestion)
+}
+
+/// The last stored severity suggestion for the bug, if any.
+#[tauri::command]
+fn get_severity_suggestion(
+    bug_id: String,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<Option<claude_cli::SeveritySuggestion>, String> {
+    use database::{BugOps, BugRepository};
+
+    let conn = db_state.connection();
+    let stored = BugRepository::new(&conn)
+        .get_severity_suggestion(&bug_id)
+        .map_err(|e| e.to_string())?;
+    Ok(stored.and_then(|json| serde_json::from_str(&json).ok()))
+}
+
+#[tauri::command]
+fn get_severity_rubric(db_state: tauri::State<'_, DbState>) -> String {
+    let conn = db_state.connection();
+    claude_cli::load_rubric(&conn)
+}
+
+#[tauri::command]
+fn set_severity_rubric(rubric: String, db_state: tauri::State<'_, DbState>) -> Result<(), String> {
+    use database::{SettingsOps, SettingsRepository};
+
+    claude_cli::validate_rubric(&rubric)?;
+    let conn = db_state.connection();
+    SettingsRepository::new(&conn)
+        .set(claude_cli::SEVERITY_RUBRIC_KEY, rubric.trim())
+        .map_err(|e| format!("Failed to save severity rubric: {}", e))
+}
+
 #[tauri::command]
 async fn save_bug_description(
     folder_path: String,
     description: String,
+    db_state: tauri::State<'_, DbState>,
 ) -> Result<(), String> {
     use std::path::Path;
 
+    {
+        let conn = db_state.connection();
+        session_lock::ensure_bug_folder_editable(&conn, &folder_path)?;
+    }
+
     // Ensure the folder exists
     let bug_folder = Path::new(&folder_path);
     if !bug_folder.exists() {
@@ -1565,6 +4123,7 @@ fn update_bug_description(
     use database::{BugOps, BugRepository};
 
     let conn = db_state.connection();
+    session_lock::ensure_bug_editable(&conn, &bug_id)?;
     let repo = BugRepository::new(&conn);
 
     let mut bug = repo.get(&bug_id)
@@ -1574,7 +4133,10 @@ fn update_bug_description(
     bug.description = if description.is_empty() { None } else { Some(description) };
 
     repo.update(&bug)
-        .map_err(|e: rusqlite::Error| e.to_string())
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+
+    queue_metadata_sync(&bug_id);
+    Ok(())
 }
 
 #[tauri::command]
@@ -1586,6 +4148,7 @@ fn update_bug_title(
     use database::{BugOps, BugRepository};
 
     let conn = db_state.connection();
+    session_lock::ensure_bug_editable(&conn, &bug_id)?;
     let repo = BugRepository::new(&conn);
 
     // Use update_partial to only touch the title field.
@@ -1597,7 +4160,10 @@ fn update_bug_title(
     };
 
     repo.update_partial(&bug_id, &update)
-        .map_err(|e: rusqlite::Error| e.to_string())
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+
+    queue_metadata_sync(&bug_id);
+    Ok(())
 }
 
 #[tauri::command]
@@ -1609,6 +4175,7 @@ fn update_bug_type(
     use database::{BugOps, BugRepository, BugType};
 
     let conn = db_state.connection();
+    session_lock::ensure_bug_editable(&conn, &bug_id)?;
     let repo = BugRepository::new(&conn);
 
     let parsed_type = BugType::from_str(&bug_type)
@@ -1620,17 +4187,72 @@ fn update_bug_type(
     };
 
     repo.update_partial(&bug_id, &update)
-        .map_err(|e: rusqlite::Error| e.to_string())
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+
+    queue_metadata_sync(&bug_id);
+    Ok(())
 }
 
+/// Write tickets-ready.md and return the description lint warnings of each
+/// bug that has any. Warnings never stop the export.
 #[tauri::command]
-fn format_session_export(session_folder_path: String) -> Result<(), String> {
+fn format_session_export(
+    session_folder_path: String,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<Vec<description_lint::BugLintWarnings>, String> {
+    let (linter, types) = {
+        let conn = db_state.connection();
+        (description_lint::Linter::load(&conn), bug_types_in_folder(&conn, &session_folder_path)?)
+    };
+    let translator = ticket_translator(&db_state);
+    write_tickets_ready(std::path::Path::new(&session_folder_path), None, &types, &linter, translator.as_ref())
+}
+
+/// Bug number → type for the session stored in `session_folder_path`; empty
+/// for a folder no session is recorded for.
+fn bug_types_in_folder(
+    conn: &rusqlite::Connection,
+    session_folder_path: &str,
+) -> Result<std::collections::HashMap<i32, database::BugType>, String> {
+    use database::{BugOps, BugRepository};
+    use rusqlite::OptionalExtension;
+
+    let session_id: Option<String> = conn
+        .query_row(
+            "SELECT id FROM sessions WHERE folder_path = ?1",
+            rusqlite::params![session_folder_path],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to query session: {}", e))?;
+    let Some(session_id) = session_id else {
+        return Ok(std::collections::HashMap::new());
+    };
+    Ok(BugRepository::new(conn)
+        .list_by_session(&session_id)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|bug| (bug.bug_number, bug.bug_type))
+        .collect())
+}
+
+/// Write tickets-ready.md from the bug folders' description.md files. When
+/// `tickets` (bug number → ticket Markdown) is given, each bug gets a ticket line.
+/// When `types` (bug number → type) holds more than one type, items are grouped
+/// into a section per type; folders without a known type count as bugs. With a
+/// `translator`, descriptions are translated (original kept below) before linting.
+fn write_tickets_ready(
+    session_path: &std::path::Path,
+    tickets: Option<&std::collections::HashMap<i32, String>>,
+    types: &std::collections::HashMap<i32, database::BugType>,
+    linter: &description_lint::Linter,
+    translator: Option<&claude_cli::Translator>,
+) -> Result<Vec<description_lint::BugLintWarnings>, String> {
     use std::path::Path;
     use std::fs;
 
-    let session_path = Path::new(&session_folder_path);
     if !session_path.exists() {
-        return Err(format!("Session folder does not exist: {}", session_folder_path));
+        return Err(format!("Session folder does not exist: {}", session_path.display()));
     }
 
     // Read all entries in the session folder
@@ -1656,40 +4278,142 @@ fn format_session_export(session_folder_path: String) -> Result<(), String> {
         }
     }
 
-    // Sort by bug number
-    bug_folders.sort_by_key(|(num, _)| *num);
+    // Sort by bug number
+    bug_folders.sort_by_key(|(num, _)| *num);
+
+    // One section per item type, in section order, when the session mixes types
+    let type_of = |num: &i32| types.get(num).cloned().unwrap_or(database::BugType::Bug);
+    let grouped = bug_folders.iter().any(|(num, _)| type_of(num) != type_of(&bug_folders[0].0));
+    let sections: Vec<Option<database::BugType>> = if grouped {
+        item_types::SECTION_ORDER.iter().cloned().map(Some).collect()
+    } else {
+        vec![None]
+    };
+    let item_heading = if grouped { "##" } else { "#" };
+
+    // Build the formatted output
+    let mut output = String::new();
+    let mut lint_warnings = Vec::new();
+
+    for bug_type in &sections {
+        let items: Vec<&(i32, String)> = bug_folders
+            .iter()
+            .filter(|(num, _)| bug_type.as_ref().is_none_or(|bug_type| type_of(num) == *bug_type))
+            .collect();
+        if items.is_empty() {
+            continue;
+        }
+        if let Some(bug_type) = bug_type {
+            if !output.is_empty() {
+                output.push_str("\n\n");
+            }
+            output.push_str(&format!("# {}\n\n", item_types::section_heading(bug_type)));
+        }
+        for (i, (bug_num, bug_folder_path)) in items.iter().enumerate() {
+            let bug_path = Path::new(bug_folder_path);
+            let description_file = bug_path.join("description.md");
+
+            // Read description.md if it exists
+            let description = if description_file.exists() {
+                fs::read_to_string(&description_file)
+                    .unwrap_or_else(|_| String::from("No description available."))
+            } else {
+                String::from("No description available.")
+            };
+            let description = match translator.filter(|_| description_file.exists()) {
+                Some(translator) => translator.translate_or_keep(&description),
+                None => description,
+            };
+            let warnings = linter.lint(&description);
+            if !warnings.is_empty() {
+                lint_warnings.push(description_lint::BugLintWarnings { bug_number: *bug_num, warnings });
+            }
+
+            // Add bug header and description
+            output.push_str(&format!("{} Bug {:03}\n\n", item_heading, bug_num));
+            if let Some(tickets) = tickets {
+                let ticket = tickets.get(bug_num).map(String::as_str).unwrap_or("Not yet filed");
+                output.push_str(&format!("**Ticket:** {}\n\n", ticket));
+            }
+            output.push_str(&description);
+
+            // Add divider if not the last item of the section
+            if i < items.len() - 1 {
+                output.push_str("\n\n---\n\n");
+            }
+        }
+    }
+
+    // Write to tickets-ready.md
+    let tickets_ready_file = session_path.join("tickets-ready.md");
+    fs::write(&tickets_ready_file, output)
+        .map_err(|e| format!("Failed to write tickets-ready.md: {}", e))?;
 
-    // Build the formatted output
-    let mut output = String::new();
+    Ok(lint_warnings)
+}
 
-    for (i, (bug_num, bug_folder_path)) in bug_folders.iter().enumerate() {
-        let bug_path = Path::new(bug_folder_path);
-        let description_file = bug_path.join("description.md");
+/// Regenerate session-summary.md and tickets-ready.md so each bug shows its
+/// filed ticket (key and URL) or a "not yet filed" marker.
+#[tauri::command]
+fn refresh_session_exports(session_id: String, db_state: tauri::State<'_, DbState>) -> Result<(), String> {
+    use database::{BugOps, BugRepository, SessionOps, SessionRepository};
+    use session_summary::SessionSummaryGenerator;
 
-        // Read description.md if it exists
-        let description = if description_file.exists() {
-            fs::read_to_string(&description_file)
-                .unwrap_or_else(|_| String::from("No description available."))
-        } else {
-            String::from("No description available.")
-        };
+    let (session, tickets, types) = {
+        let conn = db_state.connection();
+        let session = SessionRepository::new(&conn)
+            .get(&session_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        let bugs = BugRepository::new(&conn)
+            .list_by_session(&session_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?;
+        let tickets: std::collections::HashMap<i32, String> =
+            bugs.iter().map(|bug| (bug.bug_number, session_summary::ticket_link(bug))).collect();
+        let types: std::collections::HashMap<i32, database::BugType> =
+            bugs.into_iter().map(|bug| (bug.bug_number, bug.bug_type)).collect();
+        (session, tickets, types)
+    };
 
-        // Add bug header and description
-        output.push_str(&format!("# Bug {:03}\n\n", bug_num));
-        output.push_str(&description);
+    SessionSummaryGenerator::new(db_state.arc()).refresh_summary(&session_id)?;
+    write_tickets_ready(
+        std::path::Path::new(&session.folder_path),
+        Some(&tickets),
+        &types,
+        &description_lint::Linter::default(),
+        ticket_translator(&db_state).as_ref(),
+    )
+    .map(|_| ())
+}
 
-        // Add divider if not the last bug
-        if i < bug_folders.len() - 1 {
-            output.push_str("\n\n---\n\n");
-        }
-    }
+/// Lint a description shown in the ticket preview. Empty unless description
+/// linting is enabled.
+#[tauri::command]
+fn lint_description(text: String, db_state: tauri::State<'_, DbState>) -> Vec<description_lint::LintWarning> {
+    description_lint::Linter::load(&db_state.connection()).lint(&text)
+}
 
-    // Write to tickets-ready.md
-    let tickets_ready_file = session_path.join("tickets-ready.md");
-    fs::write(&tickets_ready_file, output)
-        .map_err(|e| format!("Failed to write tickets-ready.md: {}", e))?;
+/// Flag venting in a description shown in the ticket preview and propose a
+/// softened version for the tester to approve. Flags nothing unless the tone
+/// filter is enabled.
+#[tauri::command]
+fn review_ticket_tone(text: String, db_state: tauri::State<'_, DbState>) -> tone_filter::ToneReview {
+    tone_filter::review(&text, &tone_filter::ToneFilterSettings::load(&db_state.connection()))
+}
 
-    Ok(())
+#[tauri::command]
+fn get_tone_filter_settings(db_state: tauri::State<'_, DbState>) -> tone_filter::ToneFilterSettings {
+    let conn = db_state.connection();
+    tone_filter::ToneFilterSettings::load(&conn)
+}
+
+#[tauri::command]
+fn set_tone_filter_settings(
+    settings: tone_filter::ToneFilterSettings,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let conn = db_state.connection();
+    settings.save(&conn)
 }
 
 // ─── Settings Commands ───────────────────────────────────────────────────
@@ -1700,16 +4424,71 @@ fn get_setting(key: String, db_state: tauri::State<'_, DbState>) -> Result<Optio
 
     let conn = db_state.connection();
     let repo = SettingsRepository::new(&conn);
-    repo.get(&key).map_err(|e: rusqlite::Error| e.to_string())
+    let value = repo.get(&key).map_err(|e: rusqlite::Error| e.to_string())?;
+    Ok(value.map(|value| {
+        settings_schema::redact(database::Setting { key, value, updated_at: String::new() }).value
+    }))
+}
+
+/// Raw value of a secret setting (masked by `get_setting` and
+/// `get_all_settings`). Only secret-classified keys can be read here.
+#[tauri::command]
+fn get_secret(key: String, db_state: tauri::State<'_, DbState>) -> Result<Option<String>, String> {
+    use database::{SettingsRepository, SettingsOps};
+
+    if !settings_schema::is_secret(&key) {
+        return Err(format!("'{}' is not a secret setting; use get_setting", key));
+    }
+    let conn = db_state.connection();
+    SettingsRepository::new(&conn).get(&key).map_err(|e: rusqlite::Error| e.to_string())
+}
+
+/// Settings keys with their classification.
+#[tauri::command]
+fn get_settings_schema() -> Vec<settings_schema::SettingSpec> {
+    settings_schema::SCHEMA.to_vec()
+}
+
+/// Audit action for a settings change. Ticketing keys hold credentials and are
+/// recorded as credential updates; values are never written to the audit log.
+fn setting_audit_action(key: &str, deleted: bool) -> &'static str {
+    match (key.starts_with("ticketing."), deleted) {
+        (true, false) => "credentials.update",
+        (true, true) => "credentials.delete",
+        (false, false) => "setting.update",
+        (false, true) => "setting.delete",
+    }
 }
 
 #[tauri::command]
 fn set_setting(key: String, value: String, db_state: tauri::State<'_, DbState>) -> Result<(), String> {
     use database::{SettingsRepository, SettingsOps};
 
+    // A masked value read back from get_all_settings must not replace the secret
+    if settings_schema::is_secret(&key) && value == settings_schema::MASK {
+        return Ok(());
+    }
+    if key == export_hooks::EXPORT_HOOK_KEY {
+        return Err("Use set_post_export_hook to configure the post-export hook".to_string());
+    }
+    if key == metrics::METRICS_KEY {
+        return Err("Use set_metrics_settings to configure the metrics endpoint".to_string());
+    }
+    if key == capture_naming::FILENAME_PATTERN_KEY {
+        capture_naming::validate(&value)?;
+    }
+
     let conn = db_state.connection();
     let repo = SettingsRepository::new(&conn);
-    repo.set(&key, &value).map_err(|e: rusqlite::Error| e.to_string())
+    repo.set(&key, &value).map_err(|e: rusqlite::Error| e.to_string())?;
+    database::record_audit(&conn, setting_audit_action(&key, false), "setting", &key, None)?;
+
+    let toggled = quick_toggles::by_key(&key).map(|toggle| toggle.is_on(&conn));
+    drop(conn);
+    if let Some(enabled) = toggled {
+        sync_quick_toggle_item(&key, enabled);
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -1718,7 +4497,8 @@ fn get_all_settings(db_state: tauri::State<'_, DbState>) -> Result<Vec<database:
 
     let conn = db_state.connection();
     let repo = SettingsRepository::new(&conn);
-    repo.get_all().map_err(|e: rusqlite::Error| e.to_string())
+    let settings = repo.get_all().map_err(|e: rusqlite::Error| e.to_string())?;
+    Ok(settings.into_iter().map(settings_schema::redact).collect())
 }
 
 #[tauri::command]
@@ -1727,7 +4507,24 @@ fn delete_setting(key: String, db_state: tauri::State<'_, DbState>) -> Result<()
 
     let conn = db_state.connection();
     let repo = SettingsRepository::new(&conn);
-    repo.delete(&key).map_err(|e: rusqlite::Error| e.to_string())
+    repo.delete(&key).map_err(|e: rusqlite::Error| e.to_string())?;
+    database::record_audit(&conn, setting_audit_action(&key, true), "setting", &key, None)
+}
+
+// ─── Audit Log Commands ──────────────────────────────────────────────────
+
+/// Query the append-only audit log, newest first. Pass `None` to list everything.
+#[tauri::command]
+fn get_audit_log(
+    filter: Option<database::AuditFilter>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<Vec<database::AuditEntry>, String> {
+    use database::{AuditOps, AuditRepository};
+
+    let conn = db_state.connection();
+    AuditRepository::new(&conn)
+        .list(&filter.unwrap_or_default())
+        .map_err(|e| format!("Failed to read audit log: {}", e))
 }
 
 // ─── Setup Commands ──────────────────────────────────────────────────────
@@ -1764,7 +4561,8 @@ fn reset_setup(db_state: tauri::State<'_, DbState>) -> Result<(), String> {
     let conn = db_state.connection();
     let repo = SettingsRepository::new(&conn);
     repo.delete(SETUP_COMPLETE_KEY)
-        .map_err(|e: rusqlite::Error| e.to_string())
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+    database::record_audit(&conn, "setting.delete", "setting", SETUP_COMPLETE_KEY, None)
 }
 
 #[tauri::command]
@@ -1778,6 +4576,57 @@ fn get_bug_captures(bug_id: String, db_state: tauri::State<'_, DbState>) -> Resu
         .map_err(|e: rusqlite::Error| e.to_string())
 }
 
+/// Record a design-QA measurement (picked colour or distance) on a capture.
+#[tauri::command]
+fn add_capture_annotation(
+    capture_id: String,
+    data: database::AnnotationData,
+    label: Option<String>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<database::Annotation, String> {
+    use database::{AnnotationOps, AnnotationRepository, CaptureOps, CaptureRepository};
+
+    let data = data.normalized()?;
+    let conn = db_state.connection();
+    CaptureRepository::new(&conn)
+        .get(&capture_id)
+        .map_err(|e| format!("Failed to get capture: {}", e))?
+        .ok_or_else(|| format!("Capture not found: {}", capture_id))?;
+    session_lock::ensure_capture_editable(&conn, &capture_id)?;
+
+    let label = label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    let repo = AnnotationRepository::new(&conn);
+    let id = repo
+        .add(&capture_id, label.as_deref(), &data)
+        .map_err(|e| format!("Failed to save annotation: {}", e))?;
+    repo.get(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Annotation disappeared after insert".to_string())
+}
+
+#[tauri::command]
+fn get_capture_annotations(capture_id: String, db_state: tauri::State<'_, DbState>) -> Result<Vec<database::Annotation>, String> {
+    use database::{AnnotationOps, AnnotationRepository};
+
+    let conn = db_state.connection();
+    AnnotationRepository::new(&conn)
+        .list_by_capture(&capture_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn delete_capture_annotation(annotation_id: i64, db_state: tauri::State<'_, DbState>) -> Result<(), String> {
+    use database::{AnnotationOps, AnnotationRepository};
+
+    let conn = db_state.connection();
+    let repo = AnnotationRepository::new(&conn);
+    if let Some(annotation) = repo.get(annotation_id).map_err(|e| e.to_string())? {
+        session_lock::ensure_capture_editable(&conn, &annotation.capture_id)?;
+        repo.delete(annotation_id).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 fn get_unsorted_captures(session_id: String, db_state: tauri::State<'_, DbState>) -> Result<Vec<database::Capture>, String> {
     use database::{CaptureOps, CaptureRepository};
@@ -1789,14 +4638,161 @@ fn get_unsorted_captures(session_id: String, db_state: tauri::State<'_, DbState>
         .map_err(|e: rusqlite::Error| e.to_string())
 }
 
+/// Bugs an unsorted capture likely belongs to, best first, ranked by when it
+/// was taken relative to each bug's capture and by file name hints. Works
+/// offline; see `suggest_capture_assignment` for the AI suggestion.
+#[tauri::command]
+fn rank_capture_assignments(
+    capture_id: String,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<Vec<capture_triage::AssignmentCandidate>, String> {
+    capture_triage::suggest(&db_state.connection(), &capture_id)
+}
+
+/// `capture:file-detected` events for a session's captures recorded after
+/// `since` (RFC 3339; every capture when None), oldest first.
+fn capture_events_since(
+    conn: &rusqlite::Connection,
+    session_id: &str,
+    since: Option<&str>,
+) -> Result<Vec<events::CaptureFileDetected>, String> {
+    use database::{CaptureOps, CaptureRepository};
+
+    let since = since
+        .map(|s| time_format::parse_utc(s).ok_or_else(|| format!("Invalid timestamp: {}", s)))
+        .transpose()?;
+    let mut captures: Vec<(chrono::DateTime<chrono::Utc>, database::Capture)> = CaptureRepository::new(conn)
+        .list_by_session(session_id)
+        .map_err(|e| format!("Failed to list captures: {}", e))?
+        .into_iter()
+        .filter(|c| !c.is_console_capture)
+        .filter_map(|c| Some((time_format::parse_utc(&c.created_at)?, c)))
+        .filter(|(created, _)| since.is_none_or(|since| *created > since))
+        .collect();
+    captures.sort_by_key(|(created, _)| *created);
+    Ok(captures
+        .iter()
+        .map(|(_, c)| events::CaptureFileDetected::from_capture(c, true))
+        .collect())
+}
+
+/// Re-emit `capture:file-detected` for captures recorded after `since`, so a
+/// reopened or reloaded window can catch up. Returns how many were sent.
+#[tauri::command]
+fn replay_capture_events(
+    session_id: String,
+    since: Option<String>,
+    app: AppHandle,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<usize, String> {
+    let replay = {
+        let conn = db_state.connection();
+        capture_events_since(&conn, &session_id, since.as_deref())?
+    };
+    for event in &replay {
+        events::emit(&app, event).map_err(|e| format!("Failed to emit capture event: {}", e))?;
+    }
+    Ok(replay.len())
+}
+
+/// Storage root of the session manager, once it is initialized. Look it up
+/// before locking the database.
+fn manager_storage_root() -> Option<std::path::PathBuf> {
+    SESSION_MANAGER.lock().unwrap().as_ref().map(|m| m.storage_root().to_path_buf())
+}
+
+/// Move `from` to `to` as part of `uow`, moving it back if the work is
+/// rolled back.
+fn move_file_in(uow: &mut database::UnitOfWork, from: &std::path::Path, to: &std::path::Path) -> Result<(), String> {
+    fn move_file(from: &std::path::Path, to: &std::path::Path) -> std::io::Result<()> {
+        if std::fs::rename(from, to).is_err() {
+            // Cross-volume fallback: copy then delete.
+            std::fs::copy(from, to)?;
+            let _ = std::fs::remove_file(from);
+        }
+        Ok(())
+    }
+
+    move_file(from, to).map_err(|e| format!("Failed to move {:?} -> {:?}: {}", from, to, e))?;
+    let (from, to) = (from.to_path_buf(), to.to_path_buf());
+    uow.on_rollback(move || {
+        if let Err(e) = move_file(&to, &from) {
+            eprintln!("Warning: Failed to move {} back: {}", to.display(), e);
+        }
+    });
+    Ok(())
+}
+
+/// Move a capture's file (and annotated copy) into `folder` with the next
+/// sequential capture name as part of `uow`, updating `capture` but not the
+/// database. The files move back if the work is rolled back. `storage_root`
+/// locates offloaded recordings.
+fn move_capture_files(
+    capture: &mut database::Capture,
+    folder: &std::path::Path,
+    storage_root: Option<&std::path::Path>,
+    uow: &mut database::UnitOfWork,
+) -> Result<(), String> {
+    // Offloaded recordings stay in the media root, moving to the folder that
+    // mirrors the target folder.
+    let offload = match (&capture.media_link, storage_root) {
+        (Some(_), Some(root)) => media_offload::MediaOffload::from_settings(uow.connection(), root)
+            .map(|o| (o.mirror_dir(root, folder), o)),
+        _ => None,
+    };
+    let primary_dir = offload.as_ref().map(|(dir, _)| dir.clone()).unwrap_or_else(|| folder.to_path_buf());
+    // `{time}` is when the capture was taken, not when it is moved
+    let naming = capture_naming::NamingContext::for_folder(uow.connection(), folder)
+        .with_created_at(&capture.created_at);
+
+    // Ensure the target folder exists.
+    std::fs::create_dir_all(folder)
+        .map_err(|e| format!("Cannot create folder {:?}: {}", folder, e))?;
+    std::fs::create_dir_all(&primary_dir)
+        .map_err(|e| format!("Cannot create media folder {:?}: {}", primary_dir, e))?;
+
+    // Move the primary capture file into the target folder with a sequential name.
+    let old_path = std::path::PathBuf::from(&capture.file_path);
+    if old_path.exists() {
+        let mut capture_number = next_capture_number(folder, &naming);
+        if offload.is_some() {
+            capture_number += next_capture_number(&primary_dir, &naming) - 1;
+        }
+        let (new_file_name, _) = make_capture_filename(&old_path, capture_number, &naming);
+        let new_path = primary_dir.join(&new_file_name);
+        move_file_in(uow, &old_path, &new_path)?;
+
+        if let Some((_, ref o)) = offload {
+            capture.media_link = o.link_for(&new_path);
+        }
+        capture.file_path = new_path.to_string_lossy().to_string();
+        capture.file_name = new_file_name;
+    }
+
+    // Move the annotated file (if any) into the target folder as well.
+    if let Some(ref annotated) = capture.annotated_path.clone() {
+        let old_annotated = std::path::PathBuf::from(annotated);
+        if old_annotated.exists() {
+            let capture_number = next_capture_number(folder, &naming);
+            let (new_annotated_name, _) = make_capture_filename(&old_annotated, capture_number, &naming);
+            let new_annotated = folder.join(&new_annotated_name);
+            move_file_in(uow, &old_annotated, &new_annotated)?;
+
+            capture.annotated_path = Some(new_annotated.to_string_lossy().to_string());
+        }
+    }
+
+    Ok(())
+}
+
 #[tauri::command]
 fn assign_capture_to_bug(capture_id: String, bug_id: String, db_state: tauri::State<'_, DbState>, app: tauri::AppHandle) -> Result<(), String> {
     use database::{BugOps, BugRepository, CaptureOps, CaptureRepository};
-    use tauri::Emitter;
 
-    // Fetch capture and bug from DB, then release the lock before doing file I/O.
     let (mut capture, bug_folder) = {
         let conn = db_state.connection();
+        session_lock::ensure_capture_editable(&conn, &capture_id)?;
+        session_lock::ensure_bug_editable(&conn, &bug_id)?;
         let bug_repo = BugRepository::new(&conn);
         let capture_repo = CaptureRepository::new(&conn);
 
@@ -1812,67 +4808,291 @@ fn assign_capture_to_bug(capture_id: String, bug_id: String, db_state: tauri::St
         (capture, std::path::PathBuf::from(&bug.folder_path))
     };
 
-    // Ensure the bug folder exists.
-    std::fs::create_dir_all(&bug_folder)
-        .map_err(|e| format!("Cannot create bug folder {:?}: {}", bug_folder, e))?;
+    // Move the files and persist the updated capture record together.
+    let storage_root = manager_storage_root();
+    let previous_bug_id = {
+        let mut conn = db_state.connection();
+        let mut uow = database::UnitOfWork::begin(&mut conn)
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        move_capture_files(&mut capture, &bug_folder, storage_root.as_deref(), &mut uow)?;
+        let previous_bug_id = capture.bug_id.replace(bug_id.clone());
+        CaptureRepository::new(uow.connection()).update(&capture)
+            .map_err(|e: rusqlite::Error| e.to_string())?;
+        uow.commit().map_err(|e| format!("Failed to move capture: {}", e))?;
+        previous_bug_id
+    };
 
-    // Move the primary capture file into the bug folder with a sequential name.
-    let old_path = std::path::PathBuf::from(&capture.file_path);
-    if old_path.exists() {
-        let capture_number = next_capture_number(&bug_folder);
-        let (new_file_name, _) = make_capture_filename(&old_path, capture_number);
-        let new_path = bug_folder.join(&new_file_name);
+    queue_metadata_sync(&bug_id);
+    if let Some(previous) = previous_bug_id.filter(|previous| *previous != bug_id) {
+        queue_metadata_sync(&previous);
+    }
 
-        if std::fs::rename(&old_path, &new_path).is_err() {
-            // Cross-volume fallback: copy then delete.
-            std::fs::copy(&old_path, &new_path)
-                .map_err(|e| format!("Failed to copy capture file {:?} -> {:?}: {}", old_path, new_path, e))?;
-            let _ = std::fs::remove_file(&old_path);
-        }
+    // Notify the frontend so it can refresh capture lists.
+    let _ = events::emit(
+        &app,
+        &events::CaptureMoved {
+            capture_id: capture.id.clone(),
+            bug_id: bug_id.clone(),
+            file_path: capture.file_path.clone(),
+        },
+    );
 
-        capture.file_path = new_path.to_string_lossy().to_string();
-        capture.file_name = new_file_name;
-    }
+    Ok(())
+}
 
-    // Move the annotated file (if any) into the bug folder as well.
-    if let Some(ref annotated) = capture.annotated_path.clone() {
-        let old_annotated = std::path::PathBuf::from(annotated);
-        if old_annotated.exists() {
-            let capture_number = next_capture_number(&bug_folder);
-            let (new_annotated_name, _) = make_capture_filename(&old_annotated, capture_number);
-            let new_annotated = bug_folder.join(&new_annotated_name);
-
-            if std::fs::rename(&old_annotated, &new_annotated).is_err() {
-                std::fs::copy(&old_annotated, &new_annotated)
-                    .map_err(|e| format!("Failed to copy annotated file {:?} -> {:?}: {}", old_annotated, new_annotated, e))?;
-                let _ = std::fs::remove_file(&old_annotated);
+/// Split captures (and optionally note lines, zero-based) off `bug_id` into
+/// a new bug in the same session, linked to it as related. The captures
+/// left in `bug_id` are renumbered. Returns the new bug.
+#[tauri::command]
+fn split_bug_from_captures(
+    bug_id: String,
+    capture_ids: Vec<String>,
+    note_lines: Option<Vec<usize>>,
+    db_state: tauri::State<'_, DbState>,
+    app: tauri::AppHandle,
+) -> Result<database::Bug, String> {
+    use database::{BugOps, BugRepository, CaptureOps, CaptureRepository};
+
+    if capture_ids.is_empty() {
+        return Err("Select at least one capture to split off".to_string());
+    }
+    let manager = SESSION_MANAGER.lock().unwrap().clone().ok_or("Session manager not initialized")?;
+    let storage_root = manager_storage_root();
+
+    // The new bug, the moved files and notes, and the renumbering commit or
+    // roll back together
+    let new_bug = {
+        let mut conn = db_state.connection();
+        let mut uow = database::UnitOfWork::begin(&mut conn)
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        session_lock::ensure_bug_editable(uow.connection(), &bug_id)?;
+        let source = BugRepository::new(uow.connection()).get(&bug_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?
+            .ok_or_else(|| format!("Bug not found: {}", bug_id))?;
+        let bug_captures = CaptureRepository::new(uow.connection()).list_by_bug(&bug_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?;
+        if let Some(missing) = capture_ids.iter().find(|id| !bug_captures.iter().any(|c| &c.id == *id)) {
+            return Err(format!("Capture {} does not belong to {}", missing, source.display_id));
+        }
+        let (mut moving, mut remaining): (Vec<database::Capture>, Vec<database::Capture>) =
+            bug_captures.into_iter().partition(|c| capture_ids.contains(&c.id));
+        // Check the note lines before anything changes
+        let notes_split = match &note_lines {
+            Some(lines) if !lines.is_empty() => {
+                Some(bug_split::split_note_lines(source.notes.as_deref().unwrap_or_default(), lines)?)
             }
+            _ => None,
+        };
 
-            capture.annotated_path = Some(new_annotated.to_string_lossy().to_string());
+        let new_bug = manager.split_bug_in(&mut uow, &bug_id)?;
+        let new_folder = std::path::PathBuf::from(&new_bug.folder_path);
+
+        moving.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        for capture in &mut moving {
+            move_capture_files(capture, &new_folder, storage_root.as_deref(), &mut uow)?;
+            capture.bug_id = Some(new_bug.id.clone());
+            CaptureRepository::new(uow.connection()).update(capture)
+                .map_err(|e: rusqlite::Error| e.to_string())?;
+        }
+
+        if let Some((kept, moved)) = notes_split {
+            let bug_repo = BugRepository::new(uow.connection());
+            bug_repo.update_partial(&bug_id, &database::BugUpdate { notes: Some(kept), ..Default::default() })
+                .map_err(|e: rusqlite::Error| e.to_string())?;
+            bug_repo.update_partial(&new_bug.id, &database::BugUpdate { notes: Some(moved), ..Default::default() })
+                .map_err(|e: rusqlite::Error| e.to_string())?;
+        }
+
+        let source_folder = std::path::Path::new(&source.folder_path);
+        let naming = capture_naming::NamingContext::for_folder(uow.connection(), source_folder);
+        let renames = bug_split::renumber_captures(&mut remaining, source_folder, &naming)?;
+        let file_name = |path: &std::path::PathBuf| path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let renamed: Vec<_> = renames
+            .iter()
+            .map(|(from, to)| serde_json::json!({ "from": file_name(from), "to": file_name(to) }))
+            .collect();
+        uow.on_rollback(move || {
+            if let Err(e) = bug_split::undo_renames(&renames) {
+                eprintln!("Warning: Failed to undo capture renumbering: {}", e);
+            }
+        });
+        if !renamed.is_empty() {
+            database::record_audit(
+                uow.connection(),
+                "capture.renumber",
+                "bug",
+                &bug_id,
+                Some(serde_json::json!({ "renamed": renamed })),
+            )?;
+        }
+        let capture_repo = CaptureRepository::new(uow.connection());
+        for capture in &remaining {
+            capture_repo.update(capture).map_err(|e: rusqlite::Error| e.to_string())?;
         }
+
+        uow.commit().map_err(|e| format!("Failed to split bug: {}", e))?;
+        new_bug
+    };
+    if let Err(e) = session_json::SessionJsonWriter::new(db_state.arc()).write(&new_bug.session_id) {
+        eprintln!("Warning: Failed to update .session.json on bug split: {}", e);
     }
 
-    capture.bug_id = Some(bug_id.clone());
+    queue_metadata_sync(&bug_id);
+    queue_metadata_sync(&new_bug.id);
 
-    // Persist the updated capture record.
-    {
+    let _ = events::emit(
+        &app,
+        &events::BugSplit {
+            source_bug_id: bug_id,
+            bug_id: new_bug.id.clone(),
+            session_id: new_bug.session_id.clone(),
+            display_id: new_bug.display_id.clone(),
+            capture_ids,
+        },
+    );
+
+    let conn = db_state.connection();
+    BugRepository::new(&conn).get(&new_bug.id)
+        .map_err(|e: rusqlite::Error| e.to_string())?
+        .ok_or_else(|| format!("Bug not found: {}", new_bug.id))
+}
+
+/// Link two bugs of the same session. Returns the links of `bug_id`.
+#[tauri::command]
+fn create_bug_link(
+    bug_id: String,
+    related_bug_id: String,
+    kind: database::BugLinkKind,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<Vec<database::BugLink>, String> {
+    use database::{BugLinkOps, BugLinkRepository, BugOps, BugRepository};
+
+    if bug_id == related_bug_id {
+        return Err("A bug cannot be linked to itself".to_string());
+    }
+    let conn = db_state.connection();
+    session_lock::ensure_bug_editable(&conn, &bug_id)?;
+    let bug_repo = BugRepository::new(&conn);
+    let get = |id: &str| {
+        bug_repo.get(id)
+            .map_err(|e| format!("Failed to get bug: {}", e))?
+            .ok_or_else(|| format!("Bug not found: {}", id))
+    };
+    let (bug, related) = (get(&bug_id)?, get(&related_bug_id)?);
+    if bug.session_id != related.session_id {
+        return Err(format!("{} and {} belong to different sessions", bug.display_id, related.display_id));
+    }
+
+    let link_repo = BugLinkRepository::new(&conn);
+    link_repo.create(&bug_id, &related_bug_id, kind)
+        .map_err(|e| format!("Failed to link bugs: {}", e))?;
+    link_repo.list_by_bug(&bug_id)
+        .map_err(|e| format!("Failed to list bug links: {}", e))
+}
+
+/// Remove a link created with `create_bug_link`. Returns whether it existed.
+#[tauri::command]
+fn remove_bug_link(
+    bug_id: String,
+    related_bug_id: String,
+    kind: database::BugLinkKind,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<bool, String> {
+    use database::{BugLinkOps, BugLinkRepository};
+
+    let conn = db_state.connection();
+    session_lock::ensure_bug_editable(&conn, &bug_id)?;
+    BugLinkRepository::new(&conn)
+        .remove(&bug_id, &related_bug_id, kind)
+        .map_err(|e| format!("Failed to remove bug link: {}", e))
+}
+
+/// Every bug of a session and the links between them.
+#[tauri::command]
+fn get_bug_link_graph(session_id: String, db_state: tauri::State<'_, DbState>) -> Result<database::BugLinkGraph, String> {
+    use database::{BugLinkOps, BugLinkRepository, BugOps, BugRepository};
+
+    let conn = db_state.connection();
+    let nodes = BugRepository::new(&conn)
+        .list_by_session(&session_id)
+        .map_err(|e| format!("Failed to list bugs: {}", e))?
+        .into_iter()
+        .map(|bug| database::BugLinkNode {
+            bug_id: bug.id,
+            display_id: bug.display_id,
+            title: bug.title,
+            status: bug.status,
+        })
+        .collect();
+    let links = BugLinkRepository::new(&conn)
+        .list_by_session(&session_id)
+        .map_err(|e| format!("Failed to list bug links: {}", e))?;
+    Ok(database::BugLinkGraph { session_id, nodes, links })
+}
+
+/// Undo a routing decision: move a capture out of its bug into the session's
+/// `_unsorted/` folder (with the next unsorted capture number) and clear its
+/// `bug_id`. Returns the updated capture.
+#[tauri::command]
+fn unassign_capture(capture_id: String, db_state: tauri::State<'_, DbState>, app: tauri::AppHandle) -> Result<database::Capture, String> {
+    use database::{CaptureOps, CaptureRepository, SessionOps, SessionRepository};
+
+    let (mut capture, unsorted_folder) = {
         let conn = db_state.connection();
-        let capture_repo = CaptureRepository::new(&conn);
-        capture_repo.update(&capture)
+        session_lock::ensure_capture_editable(&conn, &capture_id)?;
+
+        let capture = CaptureRepository::new(&conn).get(&capture_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?
+            .ok_or_else(|| format!("Capture not found: {}", capture_id))?;
+        if capture.bug_id.is_none() {
+            return Err(format!("Capture {} is not assigned to a bug", capture_id));
+        }
+        let session = SessionRepository::new(&conn).get(&capture.session_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?
+            .ok_or_else(|| format!("Session not found: {}", capture.session_id))?;
+
+        (capture, std::path::PathBuf::from(&session.folder_path).join("_unsorted"))
+    };
+
+    let storage_root = manager_storage_root();
+    let previous_bug_id = {
+        let mut conn = db_state.connection();
+        let mut uow = database::UnitOfWork::begin(&mut conn)
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let previous_path = capture.file_path.clone();
+        move_capture_files(&mut capture, &unsorted_folder, storage_root.as_deref(), &mut uow)?;
+        let previous_bug_id = capture.bug_id.take();
+        CaptureRepository::new(uow.connection()).update(&capture)
             .map_err(|e: rusqlite::Error| e.to_string())?;
+        database::record_audit(
+            uow.connection(),
+            "capture.unassign",
+            "capture",
+            &capture.id,
+            Some(serde_json::json!({
+                "bug_id": previous_bug_id,
+                "from": previous_path,
+                "to": capture.file_path,
+            })),
+        )?;
+        uow.commit().map_err(|e| format!("Failed to move capture: {}", e))?;
+        previous_bug_id
+    };
+
+    if let Some(previous) = &previous_bug_id {
+        queue_metadata_sync(previous);
     }
 
-    // Notify the frontend so it can refresh capture lists.
-    let _ = app.emit(
-        "capture:moved",
-        serde_json::json!({
-            "captureId": capture.id,
-            "bugId": bug_id,
-            "filePath": capture.file_path,
-        }),
+    let _ = events::emit(
+        &app,
+        &events::CaptureUnassigned {
+            capture_id: capture.id.clone(),
+            previous_bug_id: previous_bug_id.unwrap_or_default(),
+            file_path: capture.file_path.clone(),
+        },
     );
 
-    Ok(())
+    Ok(capture)
 }
 
 #[tauri::command]
@@ -1889,9 +5109,15 @@ fn enable_startup() -> Result<(), String> {
         platform.enable_startup().map_err(|e| e.to_string())
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
     {
-        Err("Startup configuration is only supported on Windows".to_string())
+        use platform::{LinuxPlatform, Platform};
+        LinuxPlatform.enable_startup().map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        Err("Startup configuration is only supported on Windows and Linux".to_string())
     }
 }
 
@@ -1904,21 +5130,32 @@ fn disable_startup() -> Result<(), String> {
         platform.disable_startup().map_err(|e| e.to_string())
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
+    {
+        use platform::{LinuxPlatform, Platform};
+        LinuxPlatform.disable_startup().map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     {
-        Err("Startup configuration is only supported on Windows".to_string())
+        Err("Startup configuration is only supported on Windows and Linux".to_string())
     }
 }
 
+/// Store a console parse result on a bug and export it to `console-NNN.md` in
+/// the bug folder. `capture_id` identifies the parsed console screenshot so
+/// the export reuses its capture number.
 #[tauri::command]
 fn update_bug_console_parse(
     bug_id: String,
     console_parsed_json: String,
+    capture_id: Option<String>,
     db_state: tauri::State<'_, DbState>,
 ) -> Result<(), String> {
-    use database::{BugOps, BugRepository};
+    use database::{BugOps, BugRepository, CaptureOps, CaptureRepository};
 
     let conn = db_state.connection();
+    session_lock::ensure_bug_editable(&conn, &bug_id)?;
     let repo = BugRepository::new(&conn);
 
     // Get the bug
@@ -1926,12 +5163,34 @@ fn update_bug_console_parse(
         .map_err(|e: rusqlite::Error| e.to_string())?
         .ok_or_else(|| format!("Bug not found: {}", bug_id))?;
 
+    // Write console-NNN.md so the bug folder is self-contained without the DB
+    match console_export::ConsoleParse::from_json(&console_parsed_json) {
+        Ok(parse) => {
+            let source_file = capture_id
+                .as_deref()
+                .and_then(|id| CaptureRepository::new(&conn).get(id).ok().flatten())
+                .map(|c| c.file_name);
+            if let Err(e) = console_export::write_console_export(
+                std::path::Path::new(&bug.folder_path),
+                &bug.display_id,
+                source_file.as_deref(),
+                &parse,
+            ) {
+                eprintln!("Warning: failed to export console parse for {}: {}", bug.display_id, e);
+            }
+        }
+        Err(e) => eprintln!("Warning: skipping console export for {}: {}", bug.display_id, e),
+    }
+
     // Update the console_parse_json field
     bug.console_parse_json = Some(console_parsed_json);
 
     // Save back to database
     repo.update(&bug)
-        .map_err(|e: rusqlite::Error| e.to_string())
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+
+    queue_metadata_sync(&bug.id);
+    Ok(())
 }
 
 #[tauri::command]
@@ -1943,6 +5202,7 @@ fn update_capture_console_flag(
     use database::{CaptureOps, CaptureRepository};
 
     let conn = db_state.connection();
+    session_lock::ensure_capture_editable(&conn, &capture_id)?;
     let repo = CaptureRepository::new(&conn);
 
     // Get the capture
@@ -1955,15 +5215,186 @@ fn update_capture_console_flag(
 
     // Save back to database
     repo.update(&capture)
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+
+    if let Some(bug_id) = &capture.bug_id {
+        queue_metadata_sync(bug_id);
+    }
+    Ok(())
+}
+
+/// How far into the future a capture timestamp may be set (clock skew allowance).
+const CAPTURE_TIMESTAMP_MAX_SKEW_SECS: i64 = 5 * 60;
+
+/// Validate a user-supplied capture timestamp and normalise it to UTC RFC 3339,
+/// the format capture ordering relies on.
+fn normalize_capture_timestamp(
+    created_at: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<String, String> {
+    let parsed = chrono::DateTime::parse_from_rfc3339(created_at.trim())
+        .map_err(|e| format!("Invalid timestamp '{}': {} (expected RFC 3339)", created_at, e))?
+        .with_timezone(&chrono::Utc);
+    if parsed > now + chrono::Duration::seconds(CAPTURE_TIMESTAMP_MAX_SKEW_SECS) {
+        return Err(format!("Timestamp {} is in the future", parsed.to_rfc3339()));
+    }
+    Ok(parsed.to_rfc3339())
+}
+
+/// Backdate (or otherwise correct) when a capture was taken, e.g. for a phone
+/// photo imported after the fact. Captures, and the repro steps built from
+/// them, are ordered by this timestamp.
+#[tauri::command]
+fn update_capture_timestamp(
+    capture_id: String,
+    created_at: String,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<database::Capture, String> {
+    use database::{CaptureOps, CaptureRepository};
+
+    let created_at = normalize_capture_timestamp(&created_at, chrono::Utc::now())?;
+
+    let conn = db_state.connection();
+    session_lock::ensure_capture_editable(&conn, &capture_id)?;
+    let repo = CaptureRepository::new(&conn);
+    let previous = repo.get(&capture_id)
+        .map_err(|e: rusqlite::Error| e.to_string())?
+        .ok_or_else(|| format!("Capture not found: {}", capture_id))?;
+
+    repo.set_created_at(&capture_id, &created_at)
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+    database::record_audit(
+        &conn,
+        "capture.update_timestamp",
+        "capture",
+        &capture_id,
+        Some(serde_json::json!({ "from": previous.created_at, "to": created_at })),
+    )?;
+
+    if let Some(bug_id) = &previous.bug_id {
+        queue_metadata_sync(bug_id);
+    }
+    Ok(database::Capture { created_at, ..previous })
+}
+
+/// Longest alt text accepted for a capture.
+const MAX_ALT_TEXT_CHARS: usize = 1000;
+
+/// Alt text of a capture, for screen readers; None when not written yet.
+#[tauri::command]
+fn get_capture_alt_text(capture_id: String, db_state: tauri::State<'_, DbState>) -> Result<Option<String>, String> {
+    use database::{CaptureOps, CaptureRepository};
+
+    let conn = db_state.connection();
+    CaptureRepository::new(&conn)
+        .get_alt_text(&capture_id)
         .map_err(|e: rusqlite::Error| e.to_string())
 }
 
+/// Set the alt text shown for a capture in HTML exports and ticket bodies.
+/// Empty text clears it.
+#[tauri::command]
+fn set_capture_alt_text(
+    capture_id: String,
+    alt_text: Option<String>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let conn = db_state.connection();
+    store_alt_text(&conn, &capture_id, alt_text.as_deref())
+}
+
+fn store_alt_text(conn: &rusqlite::Connection, capture_id: &str, alt_text: Option<&str>) -> Result<(), String> {
+    use database::{CaptureOps, CaptureRepository};
+
+    let alt_text = alt_text.map(str::trim).filter(|t| !t.is_empty());
+    if alt_text.is_some_and(|t| t.chars().count() > MAX_ALT_TEXT_CHARS) {
+        return Err(format!("Alt text must be at most {} characters", MAX_ALT_TEXT_CHARS));
+    }
+    session_lock::ensure_capture_editable(conn, capture_id)?;
+    let repo = CaptureRepository::new(conn);
+    let capture = repo
+        .get(capture_id)
+        .map_err(|e: rusqlite::Error| e.to_string())?
+        .ok_or_else(|| format!("Capture not found: {}", capture_id))?;
+    repo.set_alt_text(capture_id, alt_text)
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+    if let Some(bug_id) = &capture.bug_id {
+        queue_metadata_sync(bug_id);
+    }
+    Ok(())
+}
+
+/// Have Claude write alt text for a screenshot (its annotated version when
+/// there is one) and store it on the capture. Returns the alt text.
+#[tauri::command]
+async fn describe_image(
+    capture_id: String,
+    bypass_cache: Option<bool>,
+    model_overrides: Option<claude_cli::ModelParams>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<String, String> {
+    use claude_cli::{ClaudeInvoker, ClaudeRequest, ModelTask, PromptBuilder, PromptTask};
+    use database::{BugOps, BugRepository, CaptureOps, CaptureRepository};
+
+    let (image_path, bug_title) = {
+        let conn = db_state.connection();
+        let capture = CaptureRepository::new(&conn)
+            .get(&capture_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?
+            .ok_or_else(|| format!("Capture not found: {}", capture_id))?;
+        if capture.file_type != database::CaptureType::Screenshot {
+            return Err("Alt text can only be generated for screenshots".to_string());
+        }
+        let bug_title = match &capture.bug_id {
+            Some(bug_id) => BugRepository::new(&conn)
+                .get(bug_id)
+                .map_err(|e: rusqlite::Error| e.to_string())?
+                .and_then(|bug| bug.title),
+            None => None,
+        };
+        let image_path = capture
+            .annotated_path
+            .filter(|path| std::path::Path::new(path).exists())
+            .unwrap_or(capture.file_path);
+        (std::path::PathBuf::from(image_path), bug_title)
+    };
+
+    let creds = claude_credentials(&db_state)?;
+    let request = ClaudeRequest::new_with_images(
+        PromptBuilder::build_alt_text_prompt(bug_title.as_deref()),
+        vec![image_path],
+        PromptTask::Custom,
+    )
+    .with_params(ai_params(&db_state, ModelTask::Describe, model_overrides)?);
+
+    let invoker = claude_invoker(creds, db_state.arc());
+    let response = invoker
+        .invoke(with_cache_flag(request, bypass_cache))
+        .map_err(|e| format!("Failed to describe image: {}", e))?;
+    let alt_text = response.content.trim().trim_matches('"').trim().to_string();
+    if alt_text.is_empty() {
+        return Err("Claude returned no alt text".to_string());
+    }
+
+    store_alt_text(&db_state.connection(), &capture_id, Some(&alt_text))?;
+    Ok(alt_text)
+}
+
 // ─── Capture Bridge Commands ──────────────────────────────────────────
 
 /// Trigger the OS screenshot tool (Snipping Tool on Windows).
 /// Opens the snipping tool so the user can take a screenshot.
 #[tauri::command]
 fn trigger_screenshot() -> Result<(), String> {
+    take_screenshot(capture_trigger::TriggerSource::Command)
+}
+
+/// Record a pending-capture marker, then open the OS screenshot tool. Used
+/// by `trigger_screenshot` and the screenshot hotkey.
+pub(crate) fn take_screenshot(source: capture_trigger::TriggerSource) -> Result<(), String> {
+    if let Some(manager) = SESSION_MANAGER.lock().unwrap().as_ref() {
+        manager.record_capture_trigger(source);
+    }
     let bridge_guard = CAPTURE_BRIDGE.lock().unwrap();
     let bridge = bridge_guard
         .as_ref()
@@ -1979,13 +5410,62 @@ async fn emit_screenshot_captured(
     app: tauri::AppHandle,
 ) -> Result<(), String> {
     // Emit screenshot:captured event to frontend
-    app.emit("screenshot:captured", serde_json::json!({
-        "filePath": file_path,
-        "timestamp": chrono::Utc::now().timestamp_millis(),
-    }))
+    events::emit(
+        &app,
+        &events::ScreenshotCaptured {
+            file_path: file_path.clone(),
+            capture_id: None,
+            bug_id: None,
+            session_id: None,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        },
+    )
     .map_err(|e| format!("Failed to emit screenshot:captured event: {}", e))
 }
 
+/// Queue the bug's screenshots that have no annotated version, oldest first,
+/// so a reviewer can annotate them one after another. `bug_id` defaults to
+/// the active bug. Replaces any queue already open.
+#[tauri::command]
+fn open_annotation_queue(
+    session_id: String,
+    bug_id: Option<String>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<annotation_queue::AnnotationQueueProgress, String> {
+    use database::{BugOps, BugRepository, CaptureOps, CaptureRepository};
+
+    let bug_id = bug_id
+        .or_else(|| SESSION_MANAGER.lock().unwrap().as_ref().and_then(|m| m.get_active_bug_id()))
+        .ok_or("No bug selected and no bug is being captured")?;
+    let queue = {
+        let conn = db_state.connection();
+        let bug = BugRepository::new(&conn)
+            .get(&bug_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?
+            .ok_or_else(|| format!("Bug not found: {}", bug_id))?;
+        if bug.session_id != session_id {
+            return Err(format!("{} is not part of session {}", bug.display_id, session_id));
+        }
+        let captures = CaptureRepository::new(&conn)
+            .list_by_bug(&bug_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?;
+        annotation_queue::AnnotationQueue::new(&session_id, &bug_id, &captures)
+    };
+    let progress = queue.progress();
+    *ANNOTATION_QUEUE.lock().unwrap() = Some(queue);
+    Ok(progress)
+}
+
+/// Record a queued screenshot as annotated or skipped; returns the progress
+/// and the next screenshot.
+#[tauri::command]
+fn mark_annotation_done(capture_id: String) -> Result<annotation_queue::AnnotationQueueProgress, String> {
+    let mut queue = ANNOTATION_QUEUE.lock().unwrap();
+    let queue = queue.as_mut().ok_or("No annotation queue is open")?;
+    queue.mark_done(&capture_id)?;
+    Ok(queue.progress())
+}
+
 #[tauri::command]
 async fn open_annotation_window(
     image_path: String,
@@ -1994,11 +5474,13 @@ async fn open_annotation_window(
 ) -> Result<(), String> {
     use std::path::Path;
 
-    // Validate image path exists
-    let path = Path::new(&image_path);
-    if !path.exists() {
-        return Err(format!("Image file not found: {}", image_path));
+    // Only open images from session storage or picked by the user
+    {
+        let db_state = app.state::<DbState>();
+        let conn = db_state.connection();
+        annotation_path_scope(&conn).check_existing(Path::new(&image_path))?;
     }
+    let path = Path::new(&image_path);
 
     // Get primary monitor dimensions
     let monitor = app.primary_monitor()
@@ -2013,55 +5495,459 @@ async fn open_annotation_window(
     let max_width = monitor_width * 0.9;
     let max_height = monitor_height * 0.9;
 
-    // Use the full 90% of monitor — the canvas sizes itself to the container
-    let window_width = max_width;
-    let window_height = max_height;
+    // Use the full 90% of monitor — the canvas sizes itself to the container
+    let window_width = max_width;
+    let window_height = max_height;
+
+    // Center the window
+    let window_x = (monitor_width - window_width) / 2.0;
+    let window_y = (monitor_height - window_height) / 2.0;
+
+    // Create window ID based on image path to avoid duplicates
+    let window_label = format!("annotation-{}",
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("window")
+            .replace(".", "-"));
+
+    // Check if window already exists
+    if let Some(existing) = app.get_webview_window(&window_label) {
+        existing.set_focus().map_err(|e| format!("Failed to focus existing window: {}", e))?;
+        return Ok(());
+    }
+
+    // Remember which session the window belongs to so it can be closed when the session ends
+    let session_id = match &capture_id {
+        Some(cid) => {
+            use database::{CaptureOps, CaptureRepository};
+            let db_state = app.state::<DbState>();
+            let conn = db_state.connection();
+            CaptureRepository::new(&conn).get(cid).ok().flatten().map(|c| c.session_id)
+        }
+        None => None,
+    }
+    .or_else(|| SESSION_MANAGER.lock().unwrap().as_ref().and_then(|m| m.get_active_session_id()));
+
+    ANNOTATION_WINDOWS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(annotation_windows::AnnotationWindowRegistry::new)
+        .register(annotation_windows::AnnotationWindow {
+            label: window_label.clone(),
+            image_path: image_path.clone(),
+            session_id,
+        });
+
+    // Build URL, optionally including capture_id for DB update after save
+    let url = if let Some(cid) = capture_id {
+        format!("/annotate?image={}&captureId={}", urlencoding::encode(&image_path), urlencoding::encode(&cid))
+    } else {
+        format!("/annotate?image={}", urlencoding::encode(&image_path))
+    };
+
+    let (appearance, saved_geometry) = {
+        let db_state = app.state::<DbState>();
+        let conn = db_state.connection();
+        (window_theme::WindowAppearance::load(&conn), window_geometry::load(&conn, &window_label))
+    };
+    let builder = tauri::WebviewWindowBuilder::new(
+        &app,
+        &window_label,
+        tauri::WebviewUrl::App(url.into())
+    )
+    .title("Annotate Screenshot")
+    .inner_size(window_width, window_height)
+    .position(window_x, window_y)
+    .resizable(true)
+    .decorations(true) // Use native title bar to avoid dark rectangle when image loads slowly
+    .focused(true);
+    let builder = window_geometry::apply_to_builder(builder, &app, saved_geometry);
+    let window = appearance
+        .apply_to_builder(builder, &window_label)
+        .build()
+        .map_err(|e| format!("Failed to create annotation window: {}", e))?;
+    if let Err(e) = appearance.apply_opacity(&window) {
+        eprintln!("Failed to apply window opacity: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Open a capture in the system default application (e.g. an image editor)
+/// and watch the file so saves made there update the capture's `edited_at`
+/// and emit `capture:edited`. Returns the path that was opened.
+#[tauri::command]
+fn open_capture_in_editor(
+    capture_id: String,
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<String, String> {
+    use database::{CaptureOps, CaptureRepository};
+    use tauri_plugin_opener::OpenerExt;
+
+    let path = {
+        let conn = db_state.connection();
+        session_lock::ensure_capture_editable(&conn, &capture_id)?;
+        let capture = CaptureRepository::new(&conn)
+            .get(&capture_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?
+            .ok_or_else(|| format!("Capture not found: {}", capture_id))?;
+        external_editor::editable_path(&capture)
+    };
+
+    if !path.exists() {
+        return Err(format!("Capture file not found: {}", path.display()));
+    }
+
+    let watcher = external_editor::ExternalEditWatcher::start(
+        capture_id.clone(),
+        path.clone(),
+        db_state.arc(),
+        app.clone(),
+    )?;
+    EXTERNAL_EDIT_WATCHERS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(Default::default)
+        .insert(capture_id, watcher);
+
+    let path_str = path.to_string_lossy().to_string();
+    app.opener()
+        .open_path(&path_str, None::<&str>)
+        .map_err(|e| format!("Failed to open capture in editor: {}", e))?;
+
+    Ok(path_str)
+}
+
+/// Extract the frame at `timestamp_ms` from a recording as a PNG screenshot
+/// attached to the same bug (a derived capture), ready to annotate.
+#[tauri::command]
+fn extract_video_frame(
+    capture_id: String,
+    timestamp_ms: i64,
+    db_state: tauri::State<'_, DbState>,
+    app: tauri::AppHandle,
+) -> Result<database::Capture, String> {
+    let ffmpeg = {
+        let conn = db_state.connection();
+        session_lock::ensure_capture_editable(&conn, &capture_id)?;
+        video_frames::ffmpeg_path(&conn)
+    };
+    // ffmpeg runs with the database unlocked
+    let frame = video_frames::create_frame_capture(&db_state.arc(), &capture_id, timestamp_ms, |video, output| {
+        video_frames::extract_frame(&ffmpeg, video, timestamp_ms, output)
+    })?;
+
+    if let Some(bug_id) = &frame.bug_id {
+        queue_metadata_sync(bug_id);
+    }
+
+    let _ = events::emit(
+        &app,
+        &events::ScreenshotCaptured {
+            file_path: frame.file_path.clone(),
+            capture_id: Some(frame.id.clone()),
+            bug_id: frame.bug_id.clone(),
+            session_id: Some(frame.session_id.clone()),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+
+    Ok(frame)
+}
+
+/// Plan how a session's captures would be written to a ZIP/HTML export:
+/// which files are copied and which recordings are only linked. `mode`
+/// defaults to the `export.video_mode` setting.
+#[tauri::command]
+fn plan_session_media_export(
+    session_id: String,
+    mode: Option<media_offload::VideoExportMode>,
+    db_state: tauri::State<'_, DbState>,
+) -> Result<Vec<media_offload::MediaExportEntry>, String> {
+    use database::{CaptureOps, CaptureRepository};
+
+    let storage_root = SESSION_MANAGER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|m| m.storage_root().to_path_buf());
+
+    let conn = db_state.connection();
+    let captures = CaptureRepository::new(&conn)
+        .list_by_session(&session_id)
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+    let mode = mode.unwrap_or_else(|| media_offload::VideoExportMode::from_settings(&conn));
+    let offload = storage_root.and_then(|root| media_offload::MediaOffload::from_settings(&conn, &root));
+
+    Ok(media_offload::plan_export(&captures, mode, offload.as_ref()))
+}
+
+/// Export a session to a ZIP at `dest` with session-summary.md brought up to
+/// date and each bug's rendered report, calling `on_progress` as it goes.
+pub(crate) fn export_session_archive(
+    db: &Arc<Mutex<rusqlite::Connection>>,
+    session_id: &str,
+    dest: &std::path::Path,
+    mode: Option<media_offload::VideoExportMode>,
+    storage_root: Option<&std::path::Path>,
+    on_progress: &mut dyn FnMut(&session_archive::ArchiveProgress),
+) -> Result<session_archive::ArchiveManifest, String> {
+    if let Err(e) = session_summary::SessionSummaryGenerator::new(db.clone()).refresh_summary(session_id) {
+        eprintln!("Warning: session summary not refreshed before export: {}", e);
+    }
+    session_archive::export_session::<BugRender>(db, session_id, dest, mode, storage_root, on_progress)
+}
+
+/// Export a session as a ZIP at `dest_path` with an integrity `manifest.json`:
+/// the session folder with its bug folders, .session.json and a refreshed
+/// session-summary.md, plus each bug's rendered report. Recordings follow
+/// `mode` (defaults to the `export.video_mode` setting); linked ones are
+/// listed in the manifest. Emits `session:export-progress` while writing.
+#[tauri::command]
+async fn export_session_zip(
+    session_id: String,
+    dest_path: String,
+    mode: Option<media_offload::VideoExportMode>,
+    app: AppHandle,
+) -> Result<session_archive::ArchiveManifest, String> {
+    let storage_root = SESSION_MANAGER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|m| m.storage_root().to_path_buf());
+    let db = app.state::<DbState>().arc();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let dest = std::path::Path::new(&dest_path);
+        let manifest = export_session_archive(&db, &session_id, dest, mode, storage_root.as_deref(), &mut |progress| {
+            let _ = events::emit(&app, progress);
+        })?;
+        export_hooks::spawn(db, &session_id, dest, "zip");
+        Ok(manifest)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Check a session ZIP against its `manifest.json` (sizes and SHA-256).
+#[tauri::command]
+fn verify_session_archive(path: String) -> Result<session_archive::ArchiveVerification, String> {
+    session_archive::verify_archive(std::path::Path::new(&path))
+}
+
+/// Import a session ZIP written by `export_session_zip` into the storage root.
+/// The session then appears in `list_sessions`, ended and ready for review.
+#[tauri::command]
+async fn import_session_zip(path: String, app: AppHandle) -> Result<session_import::SessionImport, String> {
+    let storage_root = SESSION_MANAGER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|m| m.storage_root().to_path_buf())
+        .ok_or("Session manager not initialized")?;
+    let db = app.state::<DbState>().arc();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let imported = session_import::import_archive(&db, std::path::Path::new(&path), &storage_root)?;
+        // The unpacked .session.json still carries any replaced IDs
+        if let Err(e) = session_json::SessionJsonWriter::new(db).write(&imported.session.id) {
+            eprintln!("Warning: .session.json not rewritten after import: {}", e);
+        }
+        Ok(imported)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ─── Deep Link Commands ──────────────────────────────────────────────────
+
+fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        window.unminimize().ok();
+        window.show().ok();
+        window.set_focus().ok();
+    }
+}
+
+/// Act on a deep link forwarded from a second launch: focus the app and tell
+/// the frontend what to open.
+fn handle_launch_target(app: &AppHandle, target: deep_link::LaunchTarget) {
+    focus_main_window(app);
+    match &target {
+        deep_link::LaunchTarget::Bug(bug_id) => {
+            use database::{BugOps, BugRepository};
+
+            let db_state = app.state::<DbState>();
+            let conn = db_state.connection();
+            match BugRepository::new(&conn).get(bug_id) {
+                Ok(Some(bug)) => {
+                    let _ = events::emit(
+                        app,
+                        &events::DeepLinkOpenBug { bug_id: bug.id, session_id: bug.session_id },
+                    );
+                }
+                _ => eprintln!("Deep link refers to unknown bug: {}", bug_id),
+            }
+        }
+        deep_link::LaunchTarget::Session(session_id) => {
+            let _ = events::emit(app, &events::DeepLinkOpenSession { session_id: session_id.clone() });
+        }
+        deep_link::LaunchTarget::Archive(archive) => {
+            if let Err(e) = open_archive_viewer(archive.to_string_lossy().to_string(), app.clone()) {
+                eprintln!("Warning: failed to open archive {:?}: {}", archive, e);
+            }
+        }
+    }
+}
+
+/// Version and names of the typed frontend events.
+#[tauri::command]
+fn get_event_schema() -> events::EventSchema {
+    events::EventSchema::current()
+}
+
+/// Maintenance check: rows whose session, bug or capture no longer exists.
+#[tauri::command]
+fn find_orphans(db_state: tauri::State<'_, DbState>) -> Result<Vec<database::Orphan>, String> {
+    let conn = db_state.connection();
+    database::find_orphans(&conn).map_err(|e| format!("Failed to check for orphaned rows: {}", e))
+}
+
+/// The deep link or archive the app was launched with, if the frontend has not taken it yet.
+#[tauri::command]
+fn take_pending_launch_target() -> Option<deep_link::LaunchTarget> {
+    PENDING_LAUNCH_TARGET.lock().unwrap().take()
+}
+
+/// `qacapture://bug/<id>` link for embedding in ticket descriptions.
+#[tauri::command]
+fn get_bug_deep_link(bug_id: String) -> String {
+    deep_link::bug_url(&bug_id)
+}
+
+// ─── Archive Viewer Commands ─────────────────────────────────────────────
+
+/// Open a `.qacap` archive in the read-only viewer, replacing any archive
+/// already open. Nothing is written to the local database.
+#[tauri::command]
+fn open_archive_viewer(path: String, app: AppHandle) -> Result<archive_viewer::ViewerSession, String> {
+    let workspace = archive_viewer::open_workspace(std::path::Path::new(&path))?;
+    let session = workspace.session.clone();
+    *ARCHIVE_VIEWER.lock().unwrap() = Some(workspace);
+
+    let _ = events::emit(
+        &app,
+        &events::ViewerOpened {
+            archive_path: session.archive_path.clone(),
+            session_id: session.session.id.clone(),
+            bug_count: session.bugs.len(),
+        },
+    );
+    Ok(session)
+}
+
+/// The archive currently open in the viewer, if any.
+#[tauri::command]
+fn get_viewer_session() -> Option<archive_viewer::ViewerSession> {
+    ARCHIVE_VIEWER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|workspace| workspace.session.clone())
+}
 
-    // Center the window
-    let window_x = (monitor_width - window_width) / 2.0;
-    let window_y = (monitor_height - window_height) / 2.0;
+/// Close the viewer and delete its temporary workspace.
+#[tauri::command]
+fn close_archive_viewer() {
+    *ARCHIVE_VIEWER.lock().unwrap() = None;
+}
 
-    // Create window ID based on image path to avoid duplicates
-    let window_label = format!("annotation-{}",
-        path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("window")
-            .replace(".", "-"));
+/// Close any annotation windows still open for `session_id` (or opened
+/// without a session) and drop them from the registry.
+fn close_annotation_windows_for_session(app: &AppHandle, session_id: &str) {
+    let labels = ANNOTATION_WINDOWS
+        .lock()
+        .unwrap()
+        .as_mut()
+        .map(|registry| registry.take_for_session(session_id))
+        .unwrap_or_default();
 
-    // Check if window already exists
-    if let Some(existing) = app.get_webview_window(&window_label) {
-        existing.set_focus().map_err(|e| format!("Failed to focus existing window: {}", e))?;
-        return Ok(());
+    for label in labels {
+        if let Some(window) = app.get_webview_window(&label) {
+            if let Err(e) = window.close() {
+                eprintln!("Warning: failed to close annotation window {}: {}", label, e);
+            }
+        }
     }
+}
 
-    // Build URL, optionally including capture_id for DB update after save
-    let url = if let Some(cid) = capture_id {
-        format!("/annotate?image={}&captureId={}", urlencoding::encode(&image_path), urlencoding::encode(&cid))
-    } else {
-        format!("/annotate?image={}", urlencoding::encode(&image_path))
+/// Delete abandoned temporary annotation files (older than a day) under the
+/// session storage root. Returns the number of files removed.
+#[tauri::command]
+fn reap_annotation_temp_files() -> Result<usize, String> {
+    let storage_root = {
+        let manager_guard = SESSION_MANAGER.lock().unwrap();
+        let manager = manager_guard
+            .as_ref()
+            .ok_or("Session manager not initialized")?;
+        manager.storage_root().to_path_buf()
     };
 
-    tauri::WebviewWindowBuilder::new(
-        &app,
-        window_label,
-        tauri::WebviewUrl::App(url.into())
-    )
-    .title("Annotate Screenshot")
-    .inner_size(window_width, window_height)
-    .position(window_x, window_y)
-    .resizable(true)
-    .decorations(true) // Use native title bar to avoid dark rectangle when image loads slowly
-    .always_on_top(true)
-    .focused(true)
-    .build()
-    .map_err(|e| format!("Failed to create annotation window: {}", e))?;
+    let removed = annotation_windows::reap_stale_temp_files(
+        &storage_root,
+        annotation_windows::STALE_TEMP_MAX_AGE,
+        std::time::SystemTime::now(),
+    );
+    Ok(removed.len())
+}
 
-    Ok(())
+/// Paths the annotation commands may read and write: the session storage
+/// root, the offloaded media root, and images picked with
+/// `pick_annotation_image`.
+fn annotation_path_scope(conn: &rusqlite::Connection) -> storage_paths::PathScope {
+    let storage_root = SESSION_MANAGER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|m| m.storage_root().to_path_buf());
+    let media_root = storage_root
+        .as_ref()
+        .and_then(|root| media_offload::MediaOffload::from_settings(conn, root))
+        .map(|o| o.media_root);
+    let picked = PICKED_IMAGE_FILES.lock().unwrap().clone().unwrap_or_default();
+    storage_paths::PathScope::new(storage_root.into_iter().chain(media_root)).with_picked(picked)
+}
+
+/// Let the user pick an image outside session storage to annotate. The
+/// picked file (and its annotated copy) can then be passed to
+/// `open_annotation_window` and `save_annotated_image`. Returns None when
+/// the dialog is cancelled.
+#[tauri::command]
+async fn pick_annotation_image(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let Some(picked) = app
+        .dialog()
+        .file()
+        .add_filter("Images", &["png", "jpg", "jpeg", "gif", "bmp", "webp"])
+        .blocking_pick_file()
+    else {
+        return Ok(None);
+    };
+    let path = picked.into_path().map_err(|e| format!("Invalid picked file: {}", e))?;
+    PICKED_IMAGE_FILES
+        .lock()
+        .unwrap()
+        .get_or_insert_with(Default::default)
+        .extend([storage_paths::annotated_path_for(&path), path.clone()]);
+    Ok(Some(path.to_string_lossy().to_string()))
 }
 
 /// Save an annotated screenshot from a base64-encoded PNG data URL.
 ///
 /// `image_path` is the original screenshot path (used to derive the save path).
+/// Both it and the save path must be inside session storage (see `annotation_path_scope`).
 /// `data_url` is a data URL string like "data:image/png;base64,<base64data>".
 /// `save_mode` is either "alongside" (default, saves as filename_annotated.png) or "overwrite".
 /// `capture_id` is the optional DB capture ID — if provided, the annotated_path is stored in the DB.
@@ -2074,9 +5960,32 @@ fn save_annotated_image(
     save_mode: String,
     capture_id: Option<String>,
     db_state: tauri::State<'_, DbState>,
+    app: AppHandle,
 ) -> Result<String, String> {
     use std::path::Path;
 
+    if let Some(ref id) = capture_id {
+        let conn = db_state.connection();
+        session_lock::ensure_capture_editable(&conn, id)?;
+    }
+
+    // Determine save path
+    let original = Path::new(&image_path);
+    let mut save_path = if save_mode == "overwrite" {
+        image_path.clone()
+    } else {
+        // Save alongside original: e.g. screenshot.png -> screenshot_annotated.png
+        storage_paths::annotated_path_for(original).to_string_lossy().to_string()
+    };
+
+    // Refuse to write outside session storage
+    {
+        let conn = db_state.connection();
+        let scope = annotation_path_scope(&conn);
+        scope.check_existing(original)?;
+        scope.check_writable(Path::new(&save_path))?;
+    }
+
     // Decode the data URL: strip the "data:image/png;base64," prefix
     let base64_data = data_url
         .split_once(',')
@@ -2089,31 +5998,33 @@ fn save_annotated_image(
     )
     .map_err(|e| format!("Failed to decode base64 image data: {}", e))?;
 
-    // Determine save path
-    let original = Path::new(&image_path);
-    let save_path = if save_mode == "overwrite" {
-        image_path.clone()
-    } else {
-        // Save alongside original: e.g. screenshot.png -> screenshot_annotated.png
-        let stem = original.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("screenshot");
-        let ext = original.extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("png");
-        let parent = original.parent().unwrap_or(Path::new("."));
-        parent.join(format!("{}_annotated.{}", stem, ext))
-            .to_string_lossy()
-            .to_string()
-    };
-
-    // Write the PNG bytes to disk
-    std::fs::write(&save_path, &image_bytes)
+    // Write the PNG bytes to disk (via a temp file, so a forcibly closed window
+    // never leaves a truncated image behind)
+    annotation_windows::write_atomically(Path::new(&save_path), &image_bytes)
         .map_err(|e| format!("Failed to write annotated image to {}: {}", save_path, e))?;
 
     // If a capture_id was provided, update the DB record
     if let Some(id) = capture_id {
-        use database::{CaptureOps, CaptureRepository};
+        use database::{AnnotationOps, AnnotationRepository, CaptureOps, CaptureRepository};
+
+        // Ink saved for the screenshot is drawn again over the new shapes
+        let ink = AnnotationRepository::new(&db_state.connection())
+            .get_ink(&id)
+            .map_err(|e: rusqlite::Error| e.to_string())?;
+        let shapes = ink_shapes_path(&app, &id, original);
+        match ink {
+            Some(layer) => {
+                if save_mode == "overwrite" {
+                    let _ = std::fs::remove_file(&shapes);
+                } else {
+                    keep_ink_shapes(Path::new(&save_path), &shapes)?;
+                }
+                save_path = render_ink_copy(&app, &db_state, original, &shapes, &layer)?.to_string_lossy().to_string();
+            }
+            None => {
+                let _ = std::fs::remove_file(&shapes);
+            }
+        }
 
         let conn = db_state.connection();
         let repo = CaptureRepository::new(&conn);
@@ -2121,12 +6032,193 @@ fn save_annotated_image(
         if let Ok(Some(mut capture)) = repo.get(&id) {
             capture.annotated_path = Some(save_path.clone());
             repo.update(&capture).map_err(|e: rusqlite::Error| e.to_string())?;
+            if let Some(bug_id) = &capture.bug_id {
+                queue_metadata_sync(bug_id);
+            }
         }
     }
 
     Ok(save_path)
 }
 
+/// The pen and eraser strokes saved for a screenshot, to edit them again.
+/// Empty when none were saved.
+#[tauri::command]
+fn get_annotation_data(capture_id: String, db_state: tauri::State<'_, DbState>) -> Result<database::InkLayer, String> {
+    use database::{AnnotationOps, AnnotationRepository};
+
+    let conn = db_state.connection();
+    AnnotationRepository::new(&conn)
+        .get_ink(&capture_id)
+        .map(Option::unwrap_or_default)
+        .map_err(|e: rusqlite::Error| e.to_string())
+}
+
+/// Copy of the shapes and text saved for `capture_id` from the annotation
+/// window, without ink. It is kept in the app data folder because the
+/// annotated copy is replaced whenever the ink is drawn again.
+fn ink_shapes_path(app: &AppHandle, capture_id: &str, original: &std::path::Path) -> std::path::PathBuf {
+    let data_dir = app.path().app_data_dir().unwrap_or_else(|_| {
+        std::env::current_dir().unwrap().join("data")
+    });
+    let ext = original.extension().and_then(|e| e.to_str()).unwrap_or("png");
+    // Capture ids are UUIDs; anything else must not name a path
+    let name: String = capture_id.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+    data_dir.join("ink_shapes").join(format!("{}.{}", name, ext))
+}
+
+/// Set `annotated` (shapes and text, no ink yet) aside as the ink's base.
+fn keep_ink_shapes(annotated: &std::path::Path, shapes: &std::path::Path) -> Result<(), String> {
+    if let Some(folder) = shapes.parent() {
+        std::fs::create_dir_all(folder).map_err(|e| format!("Cannot create {}: {}", folder.display(), e))?;
+    }
+    std::fs::copy(annotated, shapes)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to keep annotations of {}: {}", annotated.display(), e))
+}
+
+/// Draw `layer` over the saved shapes image (or the original screenshot when
+/// there is none) and write it to the annotated copy of `original`, rendering
+/// without holding the DB lock. Returns the annotated copy's path.
+fn render_ink_copy(
+    app: &AppHandle,
+    db_state: &DbState,
+    original: &std::path::Path,
+    shapes: &std::path::Path,
+    layer: &database::InkLayer,
+) -> Result<std::path::PathBuf, String> {
+    let save_path = storage_paths::annotated_path_for(original);
+    let stamps = {
+        let conn = db_state.connection();
+        annotation_path_scope(&conn).check_writable(&save_path)?;
+        let stamp_ids: Vec<&str> = layer.stamps.iter().map(|s| s.stamp_id.as_str()).collect();
+        stamp_store(app, &conn).images(&stamp_ids)?
+    };
+
+    let base_path = if shapes.exists() { shapes } else { original };
+    let base = image::open(base_path)
+        .map_err(|e| format!("Failed to read {}: {}", base_path.display(), e))?
+        .to_rgba8();
+    let bytes = ink_render::encode_for(&save_path, ink_render::render(&base, layer, &stamps))?;
+    annotation_windows::write_atomically(&save_path, &bytes)
+        .map_err(|e| format!("Failed to write annotated image to {}: {}", save_path.display(), e))?;
+    Ok(save_path)
+}
+
+/// Save the pen and eraser strokes drawn on a screenshot, with their
+/// pressure, and re-render its annotated copy (`<name>_annotated.<ext>`)
+/// over the shapes and text saved from the annotation window, or over the
+/// original when there are none. Returns the path of the annotated copy.
+#[tauri::command]
+fn save_annotation_data(
+    capture_id: String,
+    layer: database::InkLayer,
+    db_state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<String, String> {
+    use database::{AnnotationOps, AnnotationRepository, CaptureOps, CaptureRepository};
+    use std::path::PathBuf;
+
+    let (original, had_ink) = {
+        let conn = db_state.connection();
+        session_lock::ensure_capture_editable(&conn, &capture_id)?;
+        let capture = CaptureRepository::new(&conn)
+            .get(&capture_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?
+            .ok_or_else(|| format!("Capture not found: {}", capture_id))?;
+        if capture.file_type != database::CaptureType::Screenshot {
+            return Err("Only screenshots can be drawn on".to_string());
+        }
+        annotation_path_scope(&conn).check_existing(std::path::Path::new(&capture.file_path))?;
+        let had_ink = AnnotationRepository::new(&conn)
+            .get_ink(&capture_id)
+            .map_err(|e: rusqlite::Error| e.to_string())?
+            .is_some();
+        (PathBuf::from(capture.file_path), had_ink)
+    };
+    let (width, height) = image::image_dimensions(&original)
+        .map_err(|e| format!("Failed to read {}: {}", original.display(), e))?;
+    let layer = layer.normalized(width, height)?;
+
+    // Before the first ink, an existing annotated copy holds only shapes and text
+    let shapes = ink_shapes_path(&app, &capture_id, &original);
+    let annotated = storage_paths::annotated_path_for(&original);
+    if !had_ink && !shapes.exists() && annotated.exists() {
+        keep_ink_shapes(&annotated, &shapes)?;
+    }
+    let save_path = render_ink_copy(&app, &db_state, &original, &shapes, &layer)?;
+
+    let conn = db_state.connection();
+    AnnotationRepository::new(&conn)
+        .set_ink(&capture_id, &layer)
+        .map_err(|e: rusqlite::Error| e.to_string())?;
+    let repo = CaptureRepository::new(&conn);
+    if let Some(mut capture) = repo.get(&capture_id).map_err(|e: rusqlite::Error| e.to_string())? {
+        capture.annotated_path = Some(save_path.to_string_lossy().to_string());
+        repo.update(&capture).map_err(|e: rusqlite::Error| e.to_string())?;
+        if let Some(bug_id) = &capture.bug_id {
+            queue_metadata_sync(bug_id);
+        }
+    }
+    Ok(save_path.to_string_lossy().to_string())
+}
+
+/// The stamp library in use: the shared folder from the settings, or the
+/// one in the app data directory.
+fn stamp_store(app: &AppHandle, conn: &rusqlite::Connection) -> stamp_library::StampStore {
+    let data_dir = app.path().app_data_dir().unwrap_or_else(|_| {
+        std::env::current_dir().unwrap().join("data")
+    });
+    stamp_library::StampStore::from_settings(conn, &data_dir)
+}
+
+/// Custom stamps and labelled shape presets available to the annotation tools.
+#[tauri::command]
+fn get_stamp_library(db_state: tauri::State<'_, DbState>, app: AppHandle) -> Result<stamp_library::StampLibrary, String> {
+    stamp_store(&app, &db_state.connection()).load()
+}
+
+/// Copy the image at `source_path` into the stamp library as a new stamp.
+#[tauri::command]
+fn add_stamp(
+    label: String,
+    source_path: String,
+    db_state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<stamp_library::Stamp, String> {
+    stamp_store(&app, &db_state.connection()).add_stamp(&label, std::path::Path::new(&source_path))
+}
+
+#[tauri::command]
+fn rename_stamp(
+    stamp_id: String,
+    label: String,
+    db_state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<stamp_library::Stamp, String> {
+    stamp_store(&app, &db_state.connection()).rename_stamp(&stamp_id, &label)
+}
+
+#[tauri::command]
+fn delete_stamp(stamp_id: String, db_state: tauri::State<'_, DbState>, app: AppHandle) -> Result<(), String> {
+    stamp_store(&app, &db_state.connection()).delete_stamp(&stamp_id)
+}
+
+/// Create a shape preset (empty id) or update an existing one.
+#[tauri::command]
+fn save_shape_preset(
+    preset: stamp_library::ShapePreset,
+    db_state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<stamp_library::ShapePreset, String> {
+    stamp_store(&app, &db_state.connection()).save_shape(preset)
+}
+
+#[tauri::command]
+fn delete_shape_preset(preset_id: String, db_state: tauri::State<'_, DbState>, app: AppHandle) -> Result<(), String> {
+    stamp_store(&app, &db_state.connection()).delete_shape(&preset_id)
+}
+
 // ─── Swarm Ticket Commands ───────────────────────────────────────────────
 
 /// Create a ticket in the local swarm ticket database via the ticket.py CLI.
@@ -2222,7 +6314,9 @@ fn profile_delete(id: String, db_state: tauri::State<'_, DbState>) -> Result<(),
 
     let conn = db_state.connection();
     let repo = SqliteProfileRepository::new(&conn);
-    repo.delete(&id)
+    let name = repo.get(&id)?.map(|p| p.name);
+    repo.delete(&id)?;
+    database::record_audit(&conn, "profile.delete", "profile", &id, Some(serde_json::json!({ "name": name })))
 }
 
 #[tauri::command]
@@ -2231,7 +6325,7 @@ fn get_active_profile_id(db_state: tauri::State<'_, DbState>) -> Result<Option<S
 
     let conn = db_state.connection();
     let repo = SettingsRepository::new(&conn);
-    repo.get("active_profile_id").map_err(|e: rusqlite::Error| e.to_string())
+    repo.get(profile::ACTIVE_PROFILE_KEY).map_err(|e: rusqlite::Error| e.to_string())
 }
 
 #[tauri::command]
@@ -2240,10 +6334,258 @@ fn set_active_profile_id(profile_id: String, db_state: tauri::State<'_, DbState>
 
     let conn = db_state.connection();
     let repo = SettingsRepository::new(&conn);
-    repo.set("active_profile_id", &profile_id)
+    repo.set(profile::ACTIVE_PROFILE_KEY, &profile_id)
         .map_err(|e: rusqlite::Error| e.to_string())
 }
 
+// Every command exposed to the frontend, grouped by domain. See
+// `command_registry` for how to rename a command without breaking callers.
+command_registry::command_registry! {
+    Session => [
+        open_session_folder,
+        open_in_terminal,
+        copy_path_to_clipboard,
+        get_session_notes,
+        update_session_notes,
+        acquire_notes_lock,
+        release_notes_lock,
+        start_session,
+        get_staged_files,
+        import_staged_files,
+        set_staging_folder,
+        end_session,
+        resume_session,
+        get_active_session_id,
+        get_active_session,
+        list_sessions,
+        update_session_status,
+        unlock_session,
+        set_session_environment,
+        get_session_environment_diff,
+        capture_network_snapshot,
+        get_heartbeat_status,
+        get_session_summaries,
+        generate_session_summary,
+        export_session_csv,
+        generate_session_report_pdf,
+        get_export_locales,
+        seed_demo_data,
+        format_session_export,
+        refresh_session_exports,
+        plan_session_media_export,
+        export_session_zip,
+        verify_session_archive,
+        import_session_zip,
+        open_archive_viewer,
+        get_viewer_session,
+        close_archive_viewer,
+    ],
+    Bug => [
+        copy_bug_to_clipboard,
+        render_bug_by_id,
+        render_bug_folder,
+        open_bug_folder,
+        get_bug_notes,
+        update_bug_notes,
+        update_bug_metadata,
+        start_bug_capture,
+        promote_scratch_bug,
+        fold_scratch_bug,
+        end_bug_capture,
+        resume_bug_capture,
+        split_bug_from_captures,
+        create_bug_link,
+        remove_bug_link,
+        get_bug_link_graph,
+        undo_auto_stop,
+        get_last_auto_stop,
+        get_active_bug_id,
+        get_bugs_by_session,
+        list_bugs_filtered,
+        get_bug,
+        save_bug_description,
+        update_bug_console_parse,
+        update_bug_description,
+        update_bug_title,
+        update_bug_type,
+        get_bug_deep_link,
+    ],
+    Capture => [
+        get_capture_folder_path,
+        replay_capture_events,
+        capture_window_with_highlight,
+        capture_all_displays,
+        capture_screen_region,
+        start_recording,
+        stop_recording,
+        get_recording,
+        get_bug_captures,
+        get_unsorted_captures,
+        rank_capture_assignments,
+        add_capture_annotation,
+        get_capture_annotations,
+        delete_capture_annotation,
+        assign_capture_to_bug,
+        unassign_capture,
+        update_capture_console_flag,
+        update_capture_timestamp,
+        get_capture_alt_text,
+        set_capture_alt_text,
+        describe_image,
+        emit_screenshot_captured,
+        open_annotation_window,
+        open_annotation_queue,
+        mark_annotation_done,
+        pick_annotation_image,
+        save_annotated_image,
+        get_annotation_data,
+        save_annotation_data,
+        get_stamp_library,
+        add_stamp,
+        rename_stamp,
+        delete_stamp,
+        save_shape_preset,
+        delete_shape_preset,
+        reap_annotation_temp_files,
+        open_capture_in_editor,
+        extract_video_frame,
+        trigger_screenshot,
+    ],
+    Ticketing => [
+        ticketing_authenticate,
+        ticketing_create_ticket,
+        ticketing_plan_batches,
+        ticketing_list_queue,
+        ticketing_retry_queued,
+        ticketing_discard_queued,
+        ticketing_sync_status,
+        ticketing_check_connection,
+        ticketing_get_credentials,
+        ticketing_save_credentials,
+        ticketing_fetch_teams,
+        ticketing_fetch_templates,
+        ticketing_get_provider,
+        ticketing_set_provider,
+        ticketing_get_jira_config,
+        ticketing_save_jira_config,
+        get_linear_profile_defaults,
+        create_swarm_ticket,
+        lint_description,
+        review_ticket_tone,
+        get_tone_filter_settings,
+        set_tone_filter_settings,
+    ],
+    Ai => [
+        get_claude_status,
+        get_ai_model_settings,
+        list_claude_models,
+        save_ai_model_settings,
+        refresh_claude_status,
+        generate_bug_description,
+        parse_console_screenshot,
+        refine_bug_description,
+        suggest_capture_assignment,
+        classify_bug_severity,
+        get_severity_suggestion,
+        get_severity_rubric,
+        set_severity_rubric,
+    ],
+    Settings => [
+        get_auto_stop_settings,
+        set_auto_stop_settings,
+        get_capture_quota,
+        set_capture_quota,
+        get_post_export_hook,
+        set_post_export_hook,
+        get_metrics_settings,
+        set_metrics_settings,
+        get_description_lint_settings,
+        set_description_lint_settings,
+        get_symbolication_settings,
+        set_symbolication_settings,
+        get_crash_dump_settings,
+        set_crash_dump_settings,
+        get_perf_capture_settings,
+        set_perf_capture_settings,
+        get_cursor_overlay_settings,
+        set_cursor_overlay_settings,
+        get_diff_annotation_settings,
+        get_type_label_settings,
+        set_type_label_settings,
+        get_translation_settings,
+        set_translation_settings,
+        set_diff_annotation_settings,
+        get_network_snapshot_settings,
+        set_network_snapshot_settings,
+        get_capture_filename_pattern,
+        preview_capture_filename,
+        set_capture_filename_pattern,
+        get_window_appearance,
+        set_window_appearance,
+        get_hotkey_config,
+        update_hotkey_config,
+        is_hotkey_registered,
+        get_setting,
+        get_secret,
+        get_settings_schema,
+        set_setting,
+        get_all_settings,
+        delete_setting,
+        get_audit_log,
+        has_completed_setup,
+        mark_setup_complete,
+        reset_setup,
+        profile_list,
+        profile_get,
+        profile_create,
+        profile_update,
+        profile_delete,
+        get_active_profile_id,
+        set_active_profile_id,
+    ],
+    Templates => [
+        set_custom_template_path,
+        render_bug_template,
+        list_bug_templates,
+        get_bug_template,
+        create_bug_template,
+        update_bug_template,
+        rename_bug_template,
+        delete_bug_template,
+        get_template_selection,
+        set_template_selection,
+        validate_template,
+        preview_template,
+        list_template_variables,
+        get_custom_template_variables,
+        set_custom_template_variables,
+        reload_template,
+        get_template_source,
+        save_custom_template,
+        reset_template_to_default,
+        get_summary_template_source,
+        save_custom_summary_template,
+        reset_summary_template_to_default,
+        get_template_path,
+        open_template_in_editor,
+    ],
+    App => [
+        greet,
+        update_tray_icon,
+        update_tray_menu,
+        update_tray_tooltip,
+        open_session_notes_window,
+        open_session_status_window,
+        close_session_status_window,
+        get_app_version,
+        enable_startup,
+        disable_startup,
+        take_pending_launch_target,
+        get_event_schema,
+        find_orphans,
+    ],
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -2252,6 +6594,14 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            // A second launch (e.g. a clicked qacapture:// link) forwards its
+            // arguments here instead of starting another instance
+            match deep_link::from_args(args) {
+                Some(target) => handle_launch_target(app, target),
+                None => focus_main_window(app),
+            }
+        }))
         .setup(|app| {
             // Initialize session manager
             let app_handle = app.handle().clone();
@@ -2260,6 +6610,13 @@ pub fn run() {
             });
             let db_path = data_dir.join("qa_capture.db");
             let storage_root = data_dir.join("sessions");
+            *TEMPLATE_LIBRARY.lock().unwrap() =
+                Some(template_library::TemplateLibrary::new(data_dir.join("templates").join("named")));
+            TEMPLATE_MANAGER
+                .lock()
+                .unwrap()
+                .get_or_insert_with(TemplateManager::new)
+                .set_partials_dir(Some(data_dir.join("templates").join("partials")));
 
             // Create data directory if it doesn't exist
             std::fs::create_dir_all(&data_dir).ok();
@@ -2286,6 +6643,90 @@ pub fn run() {
             let emitter = Arc::new(TauriEventEmitter::new());
             emitter.set_app_handle(app_handle);
 
+            // Reap temp files left behind by annotation windows that were closed mid-save
+            let reap_root = storage_root.clone();
+            std::thread::spawn(move || {
+                let removed = annotation_windows::reap_stale_temp_files(
+                    &reap_root,
+                    annotation_windows::STALE_TEMP_MAX_AGE,
+                    std::time::SystemTime::now(),
+                );
+                if !removed.is_empty() {
+                    eprintln!("Removed {} stale annotation temp file(s)", removed.len());
+                }
+            });
+
+            // Register qacapture:// and .qacap with the OS (per-user, refreshed each
+            // launch so the handler follows the installed executable)
+            #[cfg(target_os = "windows")]
+            {
+                use platform::{Platform, WindowsPlatform};
+                if let Err(e) = WindowsPlatform.register_url_handlers() {
+                    eprintln!("Warning: failed to register URL handlers: {}", e);
+                }
+            }
+            #[cfg(target_os = "linux")]
+            {
+                use platform::{LinuxPlatform, Platform};
+                if let Err(e) = LinuxPlatform.register_url_handlers() {
+                    eprintln!("Warning: failed to register URL handlers: {}", e);
+                }
+            }
+
+            // Launched with a deep link or .qacap argument. The frontend takes it
+            // with take_pending_launch_target once it has loaded.
+            if let Some(target) = deep_link::from_args(std::env::args()) {
+                if let deep_link::LaunchTarget::Archive(archive) = &target {
+                    match archive_viewer::open_workspace(archive) {
+                        Ok(workspace) => *ARCHIVE_VIEWER.lock().unwrap() = Some(workspace),
+                        Err(e) => eprintln!("Warning: failed to open archive {:?}: {}", archive, e),
+                    }
+                }
+                *PENDING_LAUNCH_TARGET.lock().unwrap() = Some(target);
+            }
+
+            // Lab deployments can expose counters for scraping
+            if let Err(e) = apply_metrics_settings(&metrics::MetricsSettings::load(&db_arc.lock().unwrap())) {
+                eprintln!("Warning: Failed to start metrics endpoint: {}", e);
+            }
+
+            // Hot import: watch the staging folder even before a session starts
+            restart_staging_watcher(&db_arc.lock().unwrap(), app.handle());
+
+            // Notes are stored in the DB; copy in any that only exist as files
+            match notes_mirror::backfill(&db_arc.lock().unwrap()) {
+                Ok(0) => {}
+                Ok(filled) => println!("Copied notes of {} session(s)/bug(s) from disk into the database", filled),
+                Err(e) => eprintln!("Warning: Failed to backfill notes: {}", e),
+            }
+            let mirror_conn = Arc::clone(&db_arc);
+            *NOTES_MIRROR.lock().unwrap() = Some(metadata_sync::MetadataSyncer::spawn(
+                metadata_sync::DEFAULT_DEBOUNCE,
+                move |key| {
+                    let Some(owner) = notes_mirror::NotesOwner::from_key(key) else {
+                        return;
+                    };
+                    if let Err(e) = notes_mirror::mirror(&mirror_conn.lock().unwrap(), &owner) {
+                        eprintln!("Warning: failed to mirror notes for {}: {}", key, e);
+                    }
+                },
+            ));
+
+            // Keep each bug folder's metadata.json mirroring the DB
+            let sync_conn = Arc::clone(&db_arc);
+            *METADATA_SYNC.lock().unwrap() = Some(metadata_sync::MetadataSyncer::spawn(
+                metadata_sync::DEFAULT_DEBOUNCE,
+                move |bug_id| {
+                    let conn = sync_conn.lock().unwrap();
+                    // Bugs deleted before the write settles have nothing to mirror
+                    if let Err(e) = sync_bug_metadata(bug_id, &conn) {
+                        if !e.starts_with("Bug not found") {
+                            eprintln!("Warning: failed to sync metadata.json for {}: {}", bug_id, e);
+                        }
+                    }
+                },
+            ));
+
             let manager = Arc::new(SessionManager::new(
                 Arc::clone(&db_arc),
                 storage_root,
@@ -2295,6 +6736,15 @@ pub fn run() {
 
             *SESSION_MANAGER.lock().unwrap() = Some(manager);
 
+            // End bug captures left running without activity
+            let auto_stop_handle = app.handle().clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(bug_auto_stop::CHECK_INTERVAL);
+                if let Err(e) = check_bug_auto_stop(&auto_stop_handle) {
+                    eprintln!("Warning: bug auto-stop check failed: {}", e);
+                }
+            });
+
             // Initialize capture bridge (platform-specific screenshot/file-watcher)
             *CAPTURE_BRIDGE.lock().unwrap() = Some(platform::get_capture_bridge());
 
@@ -2335,10 +6785,26 @@ pub fn run() {
 
             *HOTKEY_MANAGER.lock().unwrap() = Some(hotkey_manager);
 
-            // Initialize ticketing integration (Linear by default)
-            let ticketing_integration: Arc<dyn TicketingIntegration> = Arc::new(LinearIntegration::new());
+            apply_click_tracker(&cursor_overlay::CursorOverlaySettings::load(&app.state::<DbState>().connection()));
+
+            // Initialize ticketing integration for the configured provider (Linear by default)
+            let ticketing_integration = ticketing::build_integration(&app.state::<DbState>().connection());
             *TICKETING_INTEGRATION.lock().unwrap() = Some(ticketing_integration);
 
+            // Retry tickets that could not be sent, including ones queued before a restart
+            *TICKET_QUEUE_WORKER.lock().unwrap() = Some(ticketing::TicketQueueWorker::start(
+                app.state::<DbState>().arc(),
+                app.handle().clone(),
+                send_ticket,
+                |conn, entry, request, response| {
+                    let Some(bug_id) = entry.bug_id.as_deref() else { return };
+                    if let Err(e) = record_created_ticket(conn, bug_id, request, response) {
+                        eprintln!("Warning: failed to record queued ticket on bug {}: {}", bug_id, e);
+                    }
+                    queue_metadata_sync(bug_id);
+                },
+            ));
+
             // Build tray menu
             let menu = Menu::new(app)?;
             let toggle_item = MenuItemBuilder::new("Start Session")
@@ -2372,6 +6838,7 @@ pub fn run() {
             menu.append(&settings_item)?;
             menu.append(&help_item)?;
             menu.append(&quit_item)?;
+            add_quick_toggles(&menu, app.handle())?;
 
             // Build tray icon and store in static to prevent it from being dropped
             let tray = TrayIconBuilder::with_id("main-tray")
@@ -2381,11 +6848,10 @@ pub fn run() {
                 .on_menu_event(|app_handle, event| {
                     match event.id().as_ref() {
                         "start-session" => {
-                            if let Some(window) = app_handle.get_webview_window("main") {
-                                window.show().ok();
-                                window.set_focus().ok();
-                            }
-                            app_handle.emit("tray-menu-start-session", ()).ok();
+                            let app_handle = app_handle.clone();
+                            tauri::async_runtime::spawn(async move {
+                                start_session_from_tray(app_handle).await;
+                            });
                         }
                         "new-bug-capture" => {
                             if let Some(window) = app_handle.get_webview_window("main") {
@@ -2437,9 +6903,17 @@ pub fn run() {
                             app_handle.emit("tray-menu-help", ()).ok();
                         }
                         "quit" => {
+                            // Statics are not dropped on exit; remove the viewer workspace now
+                            close_archive_viewer();
                             app_handle.exit(0);
                         }
-                        _ => {}
+                        id => {
+                            if let Some(toggle) = quick_toggles::by_menu_id(id) {
+                                if let Err(e) = toggle_quick_setting(app_handle, toggle) {
+                                    eprintln!("Warning: {}", e);
+                                }
+                            }
+                        }
                     }
                 })
                 .on_tray_icon_event(|tray, event| {
@@ -2458,97 +6932,39 @@ pub fn run() {
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            greet,
-            set_custom_template_path,
-            render_bug_template,
-            reload_template,
-            get_template_source,
-            save_custom_template,
-            reset_template_to_default,
-            get_template_path,
-            open_template_in_editor,
-            copy_bug_to_clipboard,
-            open_bug_folder,
-            open_session_folder,
-            get_capture_folder_path,
-            update_tray_icon,
-            update_tray_menu,
-            update_tray_tooltip,
-            get_bug_notes,
-            update_bug_notes,
-            update_bug_metadata,
-            get_session_notes,
-            update_session_notes,
-            open_session_notes_window,
-            open_session_status_window,
-            close_session_status_window,
-            start_session,
-            end_session,
-            resume_session,
-            start_bug_capture,
-            end_bug_capture,
-            resume_bug_capture,
-            get_active_session_id,
-            get_active_bug_id,
-            get_active_session,
-            list_sessions,
-            update_session_status,
-            get_bugs_by_session,
-            get_bug,
-            get_session_summaries,
-            generate_session_summary,
-            get_hotkey_config,
-            update_hotkey_config,
-            is_hotkey_registered,
-            ticketing_authenticate,
-            ticketing_create_ticket,
-            ticketing_check_connection,
-            ticketing_get_credentials,
-            ticketing_save_credentials,
-            ticketing_fetch_teams,
-            ticketing_fetch_templates,
-            get_linear_profile_defaults,
-            get_claude_status,
-            refresh_claude_status,
-            generate_bug_description,
-            parse_console_screenshot,
-            refine_bug_description,
-            suggest_capture_assignment,
-            save_bug_description,
-            format_session_export,
-            get_setting,
-            set_setting,
-            get_all_settings,
-            delete_setting,
-            has_completed_setup,
-            mark_setup_complete,
-            reset_setup,
-            get_bug_captures,
-            get_unsorted_captures,
-            assign_capture_to_bug,
-            update_bug_console_parse,
-            update_bug_description,
-            update_bug_title,
-            update_bug_type,
-            update_capture_console_flag,
-            get_app_version,
-            enable_startup,
-            disable_startup,
-            emit_screenshot_captured,
-            open_annotation_window,
-            save_annotated_image,
-            trigger_screenshot,
-            profile_list,
-            profile_get,
-            profile_create,
-            profile_update,
-            profile_delete,
-            get_active_profile_id,
-            set_active_profile_id,
-            create_swarm_ticket
-        ])
+        .invoke_handler(command_registry().into_handler())
         .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) = event {
+                // Track secondary window geometry; it is saved once the window closes
+                if window.label() != "main" && !window.is_minimized().unwrap_or(false) {
+                    if let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) {
+                        if size.width > 0 && size.height > 0 {
+                            WINDOW_GEOMETRY.lock().unwrap().get_or_insert_with(std::collections::HashMap::new).insert(
+                                window.label().to_string(),
+                                window_geometry::WindowGeometry {
+                                    x: position.x,
+                                    y: position.y,
+                                    width: size.width,
+                                    height: size.height,
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+            if let tauri::WindowEvent::Destroyed = event {
+                if let Some(registry) = ANNOTATION_WINDOWS.lock().unwrap().as_mut() {
+                    registry.unregister(window.label());
+                }
+                let geometry = WINDOW_GEOMETRY.lock().unwrap().as_mut().and_then(|m| m.remove(window.label()));
+                if let Some(geometry) = geometry {
+                    let db_state = window.state::<DbState>();
+                    let conn = db_state.connection();
+                    if let Err(e) = window_geometry::save(&conn, window.label(), &geometry) {
+                        eprintln!("{}", e);
+                    }
+                }
+            }
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 // Only intercept the main window — other windows (session notes, annotation)
                 // should close normally.
@@ -2568,6 +6984,29 @@ pub fn run() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_capture_events_since() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        database::init_database(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', '/qa/s-1');
+             INSERT INTO captures (id, session_id, file_name, file_path, file_type, is_console_capture, created_at) VALUES
+               ('c-2', 's-1', 'capture-002.png', '/qa/s-1/capture-002.png', 'screenshot', 0, '2024-01-01T10:09:00+00:00'),
+               ('c-1', 's-1', 'capture-001.png', '/qa/s-1/capture-001.png', 'screenshot', 0, '2024-01-01 10:05:00'),
+               ('c-3', 's-1', 'console.txt', '/qa/s-1/console.txt', 'console', 1, '2024-01-01T10:10:00Z');",
+        )
+        .unwrap();
+
+        let ids = |since: Option<&str>| -> Vec<String> {
+            capture_events_since(&conn, "s-1", since).unwrap().into_iter().map(|e| e.capture_id).collect()
+        };
+        assert_eq!(ids(None), vec!["c-1", "c-2"]);
+        assert_eq!(ids(Some("2024-01-01T10:05:00Z")), vec!["c-2"]);
+        assert!(ids(Some("2024-01-01T11:10:00+01:00")).is_empty());
+        assert!(capture_events_since(&conn, "s-1", Some("yesterday")).is_err());
+        assert!(capture_events_since(&conn, "s-1", None).unwrap()[0].replayed);
+    }
+
     /// Helper: seed a session + bug + captures into an in-memory DB and return
     /// the DB file path (on-disk temp file so `render_bug_from_db` can open it).
     fn setup_test_db(temp_dir: &std::path::Path) -> (std::path::PathBuf, String) {
@@ -2589,6 +7028,8 @@ mod tests {
             original_snip_path: None,
             created_at: "2024-01-01T10:00:00Z".to_string(),
             profile_id: None,
+            unlocked_at: None,
+            timezone: None,
         };
         SessionRepository::new(conn).create(&session).unwrap();
 
@@ -2602,16 +7043,13 @@ mod tests {
             title: Some("Test Bug".to_string()),
             notes: Some("1. Click button\n2. Observe error".to_string()),
             description: Some("1. Click button\n2. Observe error".to_string()),
-            ai_description: None,
             status: BugStatus::Captured,
             meeting_id: Some("MTG-123".to_string()),
             software_version: Some("1.0.0".to_string()),
-            console_parse_json: None,
-            metadata_json: None,
-            custom_metadata: None,
             folder_path: "/test/bugs/bug-1".to_string(),
             created_at: "2024-01-01T10:00:00Z".to_string(),
             updated_at: "2024-01-01T10:00:00Z".to_string(),
+            ..Default::default()
         };
         BugRepository::new(conn).create(&bug).unwrap();
 
@@ -2623,11 +7061,9 @@ mod tests {
             file_name: "screenshot1.png".to_string(),
             file_path: "/test/bugs/bug-1/screenshot1.png".to_string(),
             file_type: CaptureType::Screenshot,
-            annotated_path: None,
             file_size_bytes: Some(1024),
-            is_console_capture: false,
-            parsed_content: None,
             created_at: "2024-01-01T10:01:00Z".to_string(),
+            ..Default::default()
         };
         CaptureRepository::new(conn).create(&capture).unwrap();
 
@@ -2671,6 +7107,41 @@ mod tests {
         std::fs::remove_dir_all(&temp_dir).ok();
     }
 
+    #[test]
+    fn test_render_bug_folder_regenerates_metadata() {
+        let temp_dir = std::env::temp_dir().join(format!("test_render_bug_folder_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let (db_path, bug_id) = setup_test_db(&temp_dir);
+        let db = database::Database::open(&db_path).unwrap();
+        let bug_folder = temp_dir.join("bug-1");
+        let bug_folder_str = bug_folder.to_string_lossy().to_string();
+        db.connection()
+            .execute(
+                "UPDATE bugs SET folder_path = ?1 WHERE id = ?2",
+                rusqlite::params![bug_folder_str, bug_id],
+            )
+            .unwrap();
+
+        let rendered = render_bug_folder_from_db(&bug_folder_str, db.connection()).unwrap();
+        assert!(rendered.contains("Test Bug"));
+
+        let metadata = bug_metadata::read_bug_metadata(&bug_folder).unwrap();
+        assert_eq!(metadata.title, "Test Bug");
+        assert_eq!(metadata.metadata.meeting_id.as_deref(), Some("MTG-123"));
+
+        // Folders unknown to the DB fall back to their metadata.json
+        let copied = temp_dir.join("copied");
+        std::fs::create_dir_all(&copied).unwrap();
+        std::fs::copy(bug_folder.join(bug_metadata::METADATA_FILE), copied.join(bug_metadata::METADATA_FILE)).unwrap();
+        let rendered_copy = render_bug_folder_from_db(&copied.to_string_lossy(), db.connection()).unwrap();
+        assert!(rendered_copy.contains("Test Bug"));
+
+        assert!(render_bug_folder_from_db(&temp_dir.join("missing").to_string_lossy(), db.connection()).is_err());
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
     #[test]
     fn test_bug_to_template_data_defaults() {
         // Bug with no title, no description, no environment — should use defaults
@@ -2680,19 +7151,11 @@ mod tests {
             bug_number: 2,
             display_id: "BUG-002".to_string(),
             bug_type: database::BugType::Feature,
-            title: None,
-            notes: None,
-            description: None,
-            ai_description: None,
             status: database::BugStatus::Captured,
-            meeting_id: None,
-            software_version: None,
-            console_parse_json: None,
-            metadata_json: None,
-            custom_metadata: None,
             folder_path: "/test/bugs/bug-2".to_string(),
             created_at: "2024-01-01T10:00:00Z".to_string(),
             updated_at: "2024-01-01T10:00:00Z".to_string(),
+            ..Default::default()
         };
         let session = database::Session {
             id: "session-1".to_string(),
@@ -2705,9 +7168,11 @@ mod tests {
             original_snip_path: None,
             created_at: "2024-01-01T10:00:00Z".to_string(),
             profile_id: None,
+            unlocked_at: None,
+            timezone: None,
         };
 
-        let data = bug_to_template_data(&bug, &[], &session);
+        let data = bug_to_template_data(&bug, &[], &session, &time_format::ExportLocale::default());
 
         assert_eq!(data.title, "Untitled Bug");
         assert_eq!(data.bug_type, "feature");
@@ -2716,6 +7181,15 @@ mod tests {
         assert!(data.captures.is_empty());
     }
 
+    #[test]
+    fn test_custom_metadata_must_be_a_json_object() {
+        assert!(validate_custom_metadata(r#"{"severity":"high","tags":["ui"]}"#).is_ok());
+        assert!(validate_custom_metadata("{}").is_ok());
+        assert!(validate_custom_metadata(r#"{"severity":"#).is_err());
+        assert!(validate_custom_metadata(r#"["severity"]"#).is_err());
+        assert!(validate_custom_metadata("").is_err());
+    }
+
     #[test]
     fn test_bug_to_template_data_custom_metadata() {
         let bug = database::Bug {
@@ -2725,18 +7199,13 @@ mod tests {
             display_id: "BUG-003".to_string(),
             bug_type: database::BugType::Bug,
             title: Some("Custom Fields Bug".to_string()),
-            notes: None,
             description: Some("Steps here".to_string()),
-            ai_description: None,
             status: database::BugStatus::Captured,
-            meeting_id: None,
-            software_version: None,
-            console_parse_json: None,
-            metadata_json: None,
             custom_metadata: Some(r#"{"sprint":"Sprint 5","buildNumber":"42"}"#.to_string()),
             folder_path: "/test/bugs/bug-3".to_string(),
             created_at: "2024-01-01T10:00:00Z".to_string(),
             updated_at: "2024-01-01T10:00:00Z".to_string(),
+            ..Default::default()
         };
         let session = database::Session {
             id: "session-1".to_string(),
@@ -2749,9 +7218,11 @@ mod tests {
             original_snip_path: None,
             created_at: "2024-01-01T10:00:00Z".to_string(),
             profile_id: None,
+            unlocked_at: None,
+            timezone: None,
         };
 
-        let data = bug_to_template_data(&bug, &[], &session);
+        let data = bug_to_template_data(&bug, &[], &session, &time_format::ExportLocale::default());
 
         assert_eq!(data.metadata.custom_fields.get("sprint").unwrap(), "Sprint 5");
         assert_eq!(data.metadata.custom_fields.get("buildNumber").unwrap(), "42");
@@ -2887,7 +7358,7 @@ mod tests {
         ).unwrap();
 
         // Call format_session_export
-        let result = format_session_export(temp_dir.to_string_lossy().to_string());
+        let result = write_tickets_ready(&temp_dir, None, &std::collections::HashMap::new(), &description_lint::Linter::default(), None);
         assert!(result.is_ok());
 
         // Read and verify tickets-ready.md
@@ -2917,7 +7388,7 @@ mod tests {
         std::fs::create_dir_all(&temp_dir).unwrap();
 
         // Call format_session_export on empty session folder
-        let result = format_session_export(temp_dir.to_string_lossy().to_string());
+        let result = write_tickets_ready(&temp_dir, None, &std::collections::HashMap::new(), &description_lint::Linter::default(), None);
         assert!(result.is_ok());
 
         // Read and verify tickets-ready.md exists but is empty
@@ -2941,7 +7412,7 @@ mod tests {
         std::fs::create_dir_all(&bug1_folder).unwrap();
 
         // Call format_session_export
-        let result = format_session_export(temp_dir.to_string_lossy().to_string());
+        let result = write_tickets_ready(&temp_dir, None, &std::collections::HashMap::new(), &description_lint::Linter::default(), None);
         assert!(result.is_ok());
 
         // Read and verify tickets-ready.md
@@ -2958,9 +7429,57 @@ mod tests {
         std::fs::remove_dir_all(&temp_dir).ok();
     }
 
+    #[test]
+    fn test_write_tickets_ready_with_ticket_links() {
+        let temp_dir = std::env::temp_dir().join(format!("test_tickets_ready_links_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(temp_dir.join("bug_001")).unwrap();
+        std::fs::create_dir_all(temp_dir.join("bug_002")).unwrap();
+
+        let tickets = std::collections::HashMap::from([(1, "[QA-7](https://linear.app/qa/issue/QA-7)".to_string())]);
+        write_tickets_ready(&temp_dir, Some(&tickets), &std::collections::HashMap::new(), &description_lint::Linter::default(), None).unwrap();
+
+        let content = std::fs::read_to_string(temp_dir.join("tickets-ready.md")).unwrap();
+        assert!(content.contains("# Bug 001\n\n**Ticket:** [QA-7](https://linear.app/qa/issue/QA-7)"));
+        assert!(content.contains("# Bug 002\n\n**Ticket:** Not yet filed"));
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_write_tickets_ready_groups_items_by_type() {
+        let temp_dir = std::env::temp_dir().join(format!("test_tickets_ready_types_{}", uuid::Uuid::new_v4()));
+        for (number, text) in [(1, "Login fails"), (2, "Why is export slow?"), (3, "Menu feels cramped"), (4, "Crash on save")] {
+            let folder = temp_dir.join(format!("bug_{:03}", number));
+            std::fs::create_dir_all(&folder).unwrap();
+            std::fs::write(folder.join("description.md"), text).unwrap();
+        }
+        let types = std::collections::HashMap::from([
+            (2, database::BugType::Question),
+            (3, database::BugType::Feedback),
+            (4, database::BugType::Bug),
+        ]);
+        write_tickets_ready(&temp_dir, None, &types, &description_lint::Linter::default(), None).unwrap();
+
+        let content = std::fs::read_to_string(temp_dir.join("tickets-ready.md")).unwrap();
+        assert_eq!(
+            content,
+            "# Bugs\n\n## Bug 001\n\nLogin fails\n\n---\n\n## Bug 004\n\nCrash on save\n\n\
+             # Feedback\n\n## Bug 003\n\nMenu feels cramped\n\n\
+             # Questions\n\n## Bug 002\n\nWhy is export slow?"
+        );
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
     #[test]
     fn test_format_session_export_nonexistent_folder() {
-        let result = format_session_export("/nonexistent/folder/path".to_string());
+        let result = write_tickets_ready(
+            std::path::Path::new("/nonexistent/folder/path"),
+            None,
+            &std::collections::HashMap::new(),
+            &description_lint::Linter::default(),
+            None,
+        );
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Session folder does not exist"));
     }
@@ -2984,7 +7503,7 @@ mod tests {
         std::fs::write(bug2_folder.join("description.md"), "Bug 2").unwrap();
 
         // Call format_session_export
-        let result = format_session_export(temp_dir.to_string_lossy().to_string());
+        let result = write_tickets_ready(&temp_dir, None, &std::collections::HashMap::new(), &description_lint::Linter::default(), None);
         assert!(result.is_ok());
 
         // Read tickets-ready.md
@@ -3025,7 +7544,7 @@ mod tests {
         std::fs::write(temp_dir.join("session-notes.md"), "Session notes").unwrap();
 
         // Call format_session_export
-        let result = format_session_export(temp_dir.to_string_lossy().to_string());
+        let result = write_tickets_ready(&temp_dir, None, &std::collections::HashMap::new(), &description_lint::Linter::default(), None);
         assert!(result.is_ok());
 
         // Read tickets-ready.md
@@ -3131,11 +7650,11 @@ mod tests {
     fn test_make_capture_filename_screenshot() {
         use database::CaptureType;
         let path = std::path::Path::new("screenshot_20240217_143025.png");
-        let (name, ctype) = make_capture_filename(path, 1);
+        let (name, ctype) = make_capture_filename(path, 1, &capture_naming::NamingContext::default());
         assert_eq!(name, "capture-001.png");
         assert_eq!(ctype, CaptureType::Screenshot);
 
-        let (name2, _) = make_capture_filename(path, 42);
+        let (name2, _) = make_capture_filename(path, 42, &capture_naming::NamingContext::default());
         assert_eq!(name2, "capture-042.png");
     }
 
@@ -3143,7 +7662,7 @@ mod tests {
     fn test_make_capture_filename_video_mp4() {
         use database::CaptureType;
         let path = std::path::Path::new("recording.mp4");
-        let (name, ctype) = make_capture_filename(path, 1);
+        let (name, ctype) = make_capture_filename(path, 1, &capture_naming::NamingContext::default());
         assert_eq!(name, "recording-001.mp4");
         assert_eq!(ctype, CaptureType::Video);
     }
@@ -3152,7 +7671,7 @@ mod tests {
     fn test_make_capture_filename_video_webm() {
         use database::CaptureType;
         let path = std::path::Path::new("clip.webm");
-        let (name, ctype) = make_capture_filename(path, 5);
+        let (name, ctype) = make_capture_filename(path, 5, &capture_naming::NamingContext::default());
         assert_eq!(name, "recording-005.webm");
         assert_eq!(ctype, CaptureType::Video);
     }
@@ -3161,7 +7680,7 @@ mod tests {
     fn test_make_capture_filename_jpg() {
         use database::CaptureType;
         let path = std::path::Path::new("image.jpg");
-        let (name, ctype) = make_capture_filename(path, 99);
+        let (name, ctype) = make_capture_filename(path, 99, &capture_naming::NamingContext::default());
         assert_eq!(name, "capture-099.jpg");
         assert_eq!(ctype, CaptureType::Screenshot);
     }
@@ -3170,7 +7689,7 @@ mod tests {
     fn test_make_capture_filename_video_avi() {
         use database::CaptureType;
         let path = std::path::Path::new("screen_recording.avi");
-        let (name, ctype) = make_capture_filename(path, 3);
+        let (name, ctype) = make_capture_filename(path, 3, &capture_naming::NamingContext::default());
         assert_eq!(name, "recording-003.avi");
         assert_eq!(ctype, CaptureType::Video);
     }
@@ -3179,18 +7698,43 @@ mod tests {
     fn test_make_capture_filename_video_mov() {
         use database::CaptureType;
         let path = std::path::Path::new("iphone_clip.mov");
-        let (name, ctype) = make_capture_filename(path, 7);
+        let (name, ctype) = make_capture_filename(path, 7, &capture_naming::NamingContext::default());
         assert_eq!(name, "recording-007.mov");
         assert_eq!(ctype, CaptureType::Video);
     }
 
+    #[test]
+    fn test_make_capture_filename_timestamp_pattern() {
+        use database::CaptureType;
+        let time = chrono::NaiveDateTime::parse_from_str("2024-02-17 14:30:25", "%Y-%m-%d %H:%M:%S").unwrap();
+        let naming = capture_naming::NamingContext::new("{time}_{kind}-{seq}", None).unwrap().with_time(time);
+        let (name, ctype) = make_capture_filename(std::path::Path::new("Screenshot.PNG"), 4, &naming);
+        assert_eq!(name, "143025_capture-004.png");
+        assert_eq!(ctype, CaptureType::Screenshot);
+
+        let (name, _) = make_capture_filename(std::path::Path::new("clip.mp4"), 5, &naming);
+        assert_eq!(name, "143025_recording-005.mp4");
+    }
+
+    #[test]
+    fn test_next_capture_number_follows_pattern() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let naming = capture_naming::NamingContext::new("{time}_{kind}-{seq}", None).unwrap();
+        std::fs::write(temp_dir.path().join("091500_capture-001.png"), "").unwrap();
+        std::fs::write(temp_dir.path().join("091502_recording-002.mp4"), "").unwrap();
+        // Named by another pattern
+        std::fs::write(temp_dir.path().join("capture-003.png"), "").unwrap();
+
+        assert_eq!(next_capture_number(temp_dir.path(), &naming), 3);
+    }
+
     #[test]
     fn test_next_capture_number_empty_dir() {
         let temp_dir = std::env::temp_dir().join(format!("test_capture_num_{}", uuid::Uuid::new_v4()));
         std::fs::create_dir_all(&temp_dir).unwrap();
 
         // Empty dir — next number should be 1
-        assert_eq!(next_capture_number(&temp_dir), 1);
+        assert_eq!(next_capture_number(&temp_dir, &capture_naming::NamingContext::default()), 1);
 
         std::fs::remove_dir_all(&temp_dir).ok();
     }
@@ -3207,8 +7751,37 @@ mod tests {
         // Non-capture file should not count
         std::fs::write(temp_dir.join("notes.md"), "").unwrap();
 
-        assert_eq!(next_capture_number(&temp_dir), 4);
+        assert_eq!(next_capture_number(&temp_dir, &capture_naming::NamingContext::default()), 4);
 
         std::fs::remove_dir_all(&temp_dir).ok();
     }
+
+    #[test]
+    fn test_normalize_capture_timestamp() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z").unwrap().with_timezone(&chrono::Utc);
+
+        assert_eq!(
+            normalize_capture_timestamp("2024-06-01T07:30:00-04:00", now).unwrap(),
+            "2024-06-01T11:30:00+00:00"
+        );
+        assert_eq!(normalize_capture_timestamp(" 2024-05-31T09:00:00Z ", now).unwrap(), "2024-05-31T09:00:00+00:00");
+        assert!(normalize_capture_timestamp("2024-06-01T12:04:00Z", now).is_ok());
+        assert!(normalize_capture_timestamp("2024-06-01T13:00:00Z", now).unwrap_err().contains("future"));
+        assert!(normalize_capture_timestamp("yesterday", now).unwrap_err().contains("RFC 3339"));
+    }
+
+    #[test]
+    fn test_command_registry_is_consistent() {
+        use command_registry::CommandDomain;
+
+        let catalog = command_catalog();
+        assert!(catalog.duplicates().is_empty(), "duplicate commands: {:?}", catalog.duplicates());
+        assert!(catalog.invalid_deprecations().is_empty());
+        assert_eq!(catalog.domain_of("start_session"), Some(CommandDomain::Session));
+        assert_eq!(catalog.domain_of("generate_bug_description"), Some(CommandDomain::Ai));
+        assert_eq!(catalog.domain_of("no_such_command"), None);
+        for command in command_permissions::SENSITIVE_COMMANDS {
+            assert!(catalog.domain_of(command).is_some(), "unknown sensitive command {}", command);
+        }
+    }
 }
@@ -0,0 +1,222 @@
+//! OS display language and active keyboard layout recorded per bug.
+//!
+//! Many reported bugs are localization or IME issues, and triage always asks
+//! which language the OS was in and which layout was typing. A
+//! [`LocaleSnapshot`] is taken when a bug capture starts and stored under
+//! `locale` in the bug's `metadata_json`. Bug reports show it as the
+//! template's `displayLanguage` and `keyboardLayout`.
+//!
+//! On Windows the layout is the one of the foreground window's thread, which
+//! is the window the tester was typing in. On Linux it comes from
+//! `setxkbmap -query` (X11) or `XKB_DEFAULT_LAYOUT`, and the language from the
+//! `LC_ALL`/`LC_MESSAGES`/`LANG` variables.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::database::{Bug, BugOps, BugRepository};
+
+/// Key of the snapshot in a bug's `metadata_json`.
+pub const LOCALE_KEY: &str = "locale";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleSnapshot {
+    /// BCP 47 tag of the OS display language, e.g. "de-DE"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_language: Option<String>,
+    /// Language the active layout types, e.g. "ja-JP" (Windows only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_language: Option<String>,
+    /// Layout identifier, e.g. "00000411" on Windows or "us,de" on X11
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyboard_layout: Option<String>,
+}
+
+impl LocaleSnapshot {
+    /// Read the current display language and keyboard layout.
+    pub fn capture() -> Self {
+        platform_snapshot()
+    }
+
+    /// The snapshot stored on `bug`, if any.
+    pub fn from_bug(bug: &Bug) -> Option<Self> {
+        bug.metadata_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<Map<String, Value>>(json).ok())
+            .and_then(|mut metadata| metadata.remove(LOCALE_KEY))
+            .and_then(|value| serde_json::from_value(value).ok())
+    }
+
+    /// e.g. "ja-JP (00000411)", or whichever of the two is known.
+    pub fn describe_keyboard(&self) -> Option<String> {
+        match (&self.input_language, &self.keyboard_layout) {
+            (Some(language), Some(layout)) => Some(format!("{} ({})", language, layout)),
+            (Some(value), None) | (None, Some(value)) => Some(value.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Turn a POSIX locale such as "de_DE.UTF-8" or "sr_RS@latin" into a BCP 47
+/// tag ("de-DE"). "C" and "POSIX" carry no language and give None.
+pub fn posix_to_bcp47(locale: &str) -> Option<String> {
+    let base = locale.split(['.', '@']).next().unwrap_or("").trim();
+    if base.is_empty() || base == "C" || base == "POSIX" {
+        return None;
+    }
+    Some(base.replace('_', "-"))
+}
+
+/// The layouts from `setxkbmap -query` output, with the variant when set,
+/// e.g. "us,de" or "de (nodeadkeys)".
+pub fn parse_setxkbmap(output: &str) -> Option<String> {
+    let field = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == name).then(|| value.trim().to_string()).filter(|v| !v.is_empty())
+        })
+    };
+    let layout = field("layout")?;
+    Some(match field("variant") {
+        Some(variant) => format!("{} ({})", layout, variant),
+        None => layout,
+    })
+}
+
+/// Store `snapshot` under `locale` in the bug's `metadata_json`.
+pub fn attach_to_bug(conn: &Connection, bug_id: &str, snapshot: &LocaleSnapshot) -> Result<(), String> {
+    let value = serde_json::to_value(snapshot).map_err(|e| e.to_string())?;
+    BugRepository::new(conn).merge_metadata(bug_id, LOCALE_KEY, value).map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn platform_snapshot() -> LocaleSnapshot {
+    use windows::Win32::Globalization::{GetUserDefaultUILanguage, LCIDToLocaleName};
+    use windows::Win32::UI::Input::KeyboardAndMouse::GetKeyboardLayout;
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    fn locale_name(lcid: u32) -> Option<String> {
+        let mut buffer = [0u16; 85];
+        let len = unsafe { LCIDToLocaleName(lcid, Some(&mut buffer), 0) };
+        (len > 1).then(|| String::from_utf16_lossy(&buffer[..len as usize - 1]))
+    }
+
+    let display_language = locale_name(unsafe { GetUserDefaultUILanguage() } as u32);
+
+    // Thread 0 is this app; the tester was typing in the foreground window
+    let thread = unsafe { GetWindowThreadProcessId(GetForegroundWindow(), None) };
+    let hkl = unsafe { GetKeyboardLayout(thread) }.0 as usize as u32;
+    let (input_language, keyboard_layout) = if hkl == 0 {
+        (None, None)
+    } else {
+        (locale_name(hkl & 0xFFFF), Some(format!("{:08X}", hkl)))
+    };
+
+    LocaleSnapshot { display_language, input_language, keyboard_layout }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn platform_snapshot() -> LocaleSnapshot {
+    let display_language = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| posix_to_bcp47(&value));
+
+    let keyboard_layout = std::process::Command::new("setxkbmap")
+        .arg("-query")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| parse_setxkbmap(&String::from_utf8_lossy(&output.stdout)))
+        .or_else(|| std::env::var("XKB_DEFAULT_LAYOUT").ok().filter(|v| !v.is_empty()));
+
+    LocaleSnapshot { display_language, input_language: None, keyboard_layout }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{init_database, BugStatus, BugType, Session, SessionOps, SessionRepository, SessionStatus};
+
+    #[test]
+    fn test_posix_to_bcp47() {
+        assert_eq!(posix_to_bcp47("de_DE.UTF-8").as_deref(), Some("de-DE"));
+        assert_eq!(posix_to_bcp47("sr_RS@latin").as_deref(), Some("sr-RS"));
+        assert_eq!(posix_to_bcp47("ja").as_deref(), Some("ja"));
+        assert_eq!(posix_to_bcp47("C.UTF-8"), None);
+        assert_eq!(posix_to_bcp47("POSIX"), None);
+    }
+
+    #[test]
+    fn test_parse_setxkbmap() {
+        let output = "rules:      evdev\nmodel:      pc105\nlayout:     us,de\noptions:    grp:alt_shift_toggle\n";
+        assert_eq!(parse_setxkbmap(output).as_deref(), Some("us,de"));
+
+        let output = "layout:     de\nvariant:    nodeadkeys\n";
+        assert_eq!(parse_setxkbmap(output).as_deref(), Some("de (nodeadkeys)"));
+
+        assert_eq!(parse_setxkbmap("rules: evdev\n"), None);
+    }
+
+    #[test]
+    fn test_describe_keyboard() {
+        let snapshot = LocaleSnapshot {
+            display_language: Some("en-US".to_string()),
+            input_language: Some("ja-JP".to_string()),
+            keyboard_layout: Some("04110411".to_string()),
+        };
+        assert_eq!(snapshot.describe_keyboard().as_deref(), Some("ja-JP (04110411)"));
+        assert_eq!(LocaleSnapshot::default().describe_keyboard(), None);
+    }
+
+    #[test]
+    fn test_attach_keeps_other_metadata() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        SessionRepository::new(&conn)
+            .create(&Session {
+                id: "s-1".to_string(),
+                started_at: "2024-01-01T10:00:00Z".to_string(),
+                ended_at: None,
+                status: SessionStatus::Active,
+                folder_path: "/qa/s-1".to_string(),
+                session_notes: None,
+                environment_json: None,
+                original_snip_path: None,
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                profile_id: None,
+                unlocked_at: None,
+                timezone: None,
+            })
+            .unwrap();
+        BugRepository::new(&conn)
+            .create(&Bug {
+                id: "b-1".to_string(),
+                session_id: "s-1".to_string(),
+                bug_number: 1,
+                display_id: "BUG-001".to_string(),
+                bug_type: BugType::Bug,
+                status: BugStatus::Capturing,
+                metadata_json: Some(r#"{"build":"1.2"}"#.to_string()),
+                folder_path: "/qa/s-1/bug_001".to_string(),
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                updated_at: "2024-01-01T10:00:00Z".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let snapshot = LocaleSnapshot {
+            display_language: Some("de-DE".to_string()),
+            input_language: None,
+            keyboard_layout: Some("de (nodeadkeys)".to_string()),
+        };
+        attach_to_bug(&conn, "b-1", &snapshot).unwrap();
+
+        let bug = BugRepository::new(&conn).get("b-1").unwrap().unwrap();
+        assert_eq!(LocaleSnapshot::from_bug(&bug), Some(snapshot));
+        assert!(bug.metadata_json.unwrap().contains(r#""build":"1.2""#));
+    }
+}
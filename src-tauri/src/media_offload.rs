@@ -0,0 +1,346 @@
+//! Offloading of large video recordings to a separate media root.
+//!
+//! When `media.offload_videos` is enabled, recordings are stored under the
+//! media root (`media.root`, defaulting to a `media/` folder next to the
+//! session storage root) in a folder tree that mirrors the session folders.
+//! The capture keeps its resolved `file_path` plus a `media_link` relative to
+//! the media root, so the media root can be moved and re-pointed later.
+//!
+//! Exports decide what to do with videos via [`VideoExportMode`].
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Capture, CaptureType, SettingsOps, SettingsRepository};
+
+/// Settings key: `"true"` to store recordings outside the session folder.
+pub const OFFLOAD_VIDEOS_KEY: &str = "media.offload_videos";
+/// Settings key: absolute path of the media root.
+pub const MEDIA_ROOT_KEY: &str = "media.root";
+/// Settings key: default [`VideoExportMode`] for ZIP/HTML exports.
+pub const VIDEO_EXPORT_MODE_KEY: &str = "export.video_mode";
+
+/// Where offloaded media is stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaOffload {
+    pub media_root: PathBuf,
+}
+
+impl MediaOffload {
+    /// Load the offload configuration. Returns `None` when offloading is disabled.
+    pub fn from_settings(conn: &Connection, storage_root: &Path) -> Option<Self> {
+        let repo = SettingsRepository::new(conn);
+        let enabled = repo
+            .get(OFFLOAD_VIDEOS_KEY)
+            .ok()
+            .flatten()
+            .is_some_and(|v| v == "true");
+        if !enabled {
+            return None;
+        }
+
+        let media_root = repo
+            .get(MEDIA_ROOT_KEY)
+            .ok()
+            .flatten()
+            .filter(|root| !root.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| default_media_root(storage_root));
+        Some(Self { media_root })
+    }
+
+    /// Folder in the media root mirroring `folder` (a session, bug or
+    /// `_unsorted` folder under `storage_root`).
+    pub fn mirror_dir(&self, storage_root: &Path, folder: &Path) -> PathBuf {
+        match folder.strip_prefix(storage_root) {
+            Ok(relative) => self.media_root.join(relative),
+            Err(_) => self.media_root.join(folder.file_name().unwrap_or_default()),
+        }
+    }
+
+    /// Link for `path` relative to the media root, using `/` separators.
+    pub fn link_for(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.media_root).ok()?;
+        let parts: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        Some(parts.join("/"))
+    }
+
+    /// Resolve a link stored in the DB against the current media root.
+    pub fn resolve(&self, link: &str) -> PathBuf {
+        link.split('/')
+            .fold(self.media_root.clone(), |path, part| path.join(part))
+    }
+}
+
+/// Default media root: a `media/` folder beside the session storage root.
+pub fn default_media_root(storage_root: &Path) -> PathBuf {
+    storage_root
+        .parent()
+        .unwrap_or(storage_root)
+        .join("media")
+}
+
+/// How videos are handled when a session is exported.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VideoExportMode {
+    /// Reference videos by link; no video bytes are copied.
+    LinksOnly,
+    /// Copy trimmed recordings (`*_trimmed.<ext>`) where they exist, link the rest.
+    IncludeTrimmed,
+    /// Copy every recording.
+    #[default]
+    IncludeAll,
+}
+
+impl VideoExportMode {
+    #[allow(dead_code)]
+    pub fn as_str(&self) -> &str {
+        match self {
+            VideoExportMode::LinksOnly => "links_only",
+            VideoExportMode::IncludeTrimmed => "include_trimmed",
+            VideoExportMode::IncludeAll => "include_all",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "links_only" => Some(VideoExportMode::LinksOnly),
+            "include_trimmed" => Some(VideoExportMode::IncludeTrimmed),
+            "include_all" => Some(VideoExportMode::IncludeAll),
+            _ => None,
+        }
+    }
+
+    /// Configured default export mode, falling back to `IncludeAll`.
+    pub fn from_settings(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(VIDEO_EXPORT_MODE_KEY)
+            .ok()
+            .flatten()
+            .and_then(|v| Self::from_str(&v))
+            .unwrap_or_default()
+    }
+}
+
+/// What an export should do with a capture's file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "action", content = "target", rename_all = "snake_case")]
+pub enum ExportMediaAction {
+    /// Copy this file into the export.
+    Include(PathBuf),
+    /// Reference the file by this link instead of copying it.
+    Link(String),
+}
+
+/// Path of the trimmed variant of a recording (`recording-001_trimmed.mp4`).
+pub fn trimmed_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    let name = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}_trimmed.{}", stem, ext),
+        None => format!("{}_trimmed", stem),
+    };
+    path.with_file_name(name)
+}
+
+/// Decide how a capture is exported. Screenshots are always included; videos
+/// follow `mode`. Offloaded videos are resolved against `offload` when given.
+pub fn plan_capture_export(
+    capture: &Capture,
+    mode: VideoExportMode,
+    offload: Option<&MediaOffload>,
+) -> ExportMediaAction {
+    let path = match (&capture.media_link, offload) {
+        (Some(link), Some(offload)) => offload.resolve(link),
+        _ => PathBuf::from(&capture.file_path),
+    };
+
+    if capture.file_type != CaptureType::Video {
+        return ExportMediaAction::Include(path);
+    }
+
+    let link = capture
+        .media_link
+        .clone()
+        .unwrap_or_else(|| path.to_string_lossy().to_string());
+
+    match mode {
+        VideoExportMode::IncludeAll => ExportMediaAction::Include(path),
+        VideoExportMode::LinksOnly => ExportMediaAction::Link(link),
+        VideoExportMode::IncludeTrimmed => {
+            let trimmed = trimmed_path(&path);
+            if trimmed.exists() {
+                ExportMediaAction::Include(trimmed)
+            } else {
+                ExportMediaAction::Link(link)
+            }
+        }
+    }
+}
+
+/// Export plan for one capture, as returned to the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaExportEntry {
+    pub capture_id: String,
+    pub bug_id: Option<String>,
+    #[serde(flatten)]
+    pub action: ExportMediaAction,
+    /// Bytes that would be copied into the export (0 for links).
+    pub bytes: u64,
+    /// Set when an included video uses a codec ticketing systems often reject.
+    pub warning: Option<String>,
+}
+
+/// Plan how every capture in `captures` would be exported under `mode`.
+pub fn plan_export(
+    captures: &[Capture],
+    mode: VideoExportMode,
+    offload: Option<&MediaOffload>,
+) -> Vec<MediaExportEntry> {
+    captures
+        .iter()
+        .map(|capture| {
+            let action = plan_capture_export(capture, mode, offload);
+            let bytes = match &action {
+                ExportMediaAction::Include(path) => std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+                ExportMediaAction::Link(_) => 0,
+            };
+            let warning = match (&action, &capture.video_codec) {
+                (ExportMediaAction::Include(_), Some(codec)) => crate::video_metadata::codec_warning(codec),
+                _ => None,
+            };
+            MediaExportEntry {
+                capture_id: capture.id.clone(),
+                bug_id: capture.bug_id.clone(),
+                action,
+                bytes,
+                warning,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    fn video(file_path: &Path, media_link: Option<&str>) -> Capture {
+        Capture {
+            id: "c-1".to_string(),
+            session_id: "s-1".to_string(),
+            file_name: "recording-001.mp4".to_string(),
+            file_path: file_path.to_string_lossy().to_string(),
+            file_type: CaptureType::Video,
+            created_at: "2024-01-01T10:00:00Z".to_string(),
+            media_link: media_link.map(|l| l.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_from_settings_disabled_by_default() {
+        let db = Database::in_memory().unwrap();
+        assert!(MediaOffload::from_settings(db.connection(), Path::new("/data/sessions")).is_none());
+    }
+
+    #[test]
+    fn test_from_settings_uses_default_root() {
+        let db = Database::in_memory().unwrap();
+        SettingsRepository::new(db.connection()).set(OFFLOAD_VIDEOS_KEY, "true").unwrap();
+
+        let offload = MediaOffload::from_settings(db.connection(), Path::new("/data/sessions")).unwrap();
+        assert_eq!(offload.media_root, PathBuf::from("/data/media"));
+
+        SettingsRepository::new(db.connection()).set(MEDIA_ROOT_KEY, "/mnt/big/qa-media").unwrap();
+        let offload = MediaOffload::from_settings(db.connection(), Path::new("/data/sessions")).unwrap();
+        assert_eq!(offload.media_root, PathBuf::from("/mnt/big/qa-media"));
+    }
+
+    #[test]
+    fn test_mirror_dir_and_links_round_trip() {
+        let offload = MediaOffload { media_root: PathBuf::from("/mnt/media") };
+        let storage_root = Path::new("/data/sessions");
+
+        let dir = offload.mirror_dir(storage_root, Path::new("/data/sessions/2024-01-01_abcd1234/bug_002"));
+        assert_eq!(dir, PathBuf::from("/mnt/media/2024-01-01_abcd1234/bug_002"));
+
+        let file = dir.join("recording-001.mp4");
+        let link = offload.link_for(&file).unwrap();
+        assert_eq!(link, "2024-01-01_abcd1234/bug_002/recording-001.mp4");
+        assert_eq!(offload.resolve(&link), file);
+
+        assert!(offload.link_for(Path::new("/elsewhere/file.mp4")).is_none());
+    }
+
+    #[test]
+    fn test_export_mode_round_trip() {
+        for mode in [VideoExportMode::LinksOnly, VideoExportMode::IncludeTrimmed, VideoExportMode::IncludeAll] {
+            assert_eq!(VideoExportMode::from_str(mode.as_str()), Some(mode));
+        }
+        assert_eq!(VideoExportMode::from_str("bogus"), None);
+
+        let db = Database::in_memory().unwrap();
+        assert_eq!(VideoExportMode::from_settings(db.connection()), VideoExportMode::IncludeAll);
+        SettingsRepository::new(db.connection()).set(VIDEO_EXPORT_MODE_KEY, "links_only").unwrap();
+        assert_eq!(VideoExportMode::from_settings(db.connection()), VideoExportMode::LinksOnly);
+    }
+
+    #[test]
+    fn test_plan_capture_export_modes() {
+        let dir = tempfile::tempdir().unwrap();
+        let offload = MediaOffload { media_root: dir.path().to_path_buf() };
+        let path = dir.path().join("s").join("recording-001.mp4");
+        let capture = video(&path, Some("s/recording-001.mp4"));
+
+        assert_eq!(
+            plan_capture_export(&capture, VideoExportMode::IncludeAll, Some(&offload)),
+            ExportMediaAction::Include(path.clone())
+        );
+        assert_eq!(
+            plan_capture_export(&capture, VideoExportMode::LinksOnly, Some(&offload)),
+            ExportMediaAction::Link("s/recording-001.mp4".to_string())
+        );
+        // No trimmed copy yet: fall back to a link
+        assert_eq!(
+            plan_capture_export(&capture, VideoExportMode::IncludeTrimmed, Some(&offload)),
+            ExportMediaAction::Link("s/recording-001.mp4".to_string())
+        );
+
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(trimmed_path(&path), b"trimmed").unwrap();
+        assert_eq!(
+            plan_capture_export(&capture, VideoExportMode::IncludeTrimmed, Some(&offload)),
+            ExportMediaAction::Include(dir.path().join("s").join("recording-001_trimmed.mp4"))
+        );
+    }
+
+    #[test]
+    fn test_plan_export_warns_about_unsupported_codecs() {
+        let mut capture = video(Path::new("/s/recording-001.mov"), None);
+        capture.video_codec = Some("prores".to_string());
+
+        let plan = plan_export(std::slice::from_ref(&capture), VideoExportMode::IncludeAll, None);
+        assert!(plan[0].warning.as_deref().unwrap().contains("prores"));
+
+        // Linked videos are not uploaded, so no warning
+        let plan = plan_export(&[capture], VideoExportMode::LinksOnly, None);
+        assert!(plan[0].warning.is_none());
+    }
+
+    #[test]
+    fn test_screenshots_are_always_included() {
+        let mut capture = video(Path::new("/s/capture-001.png"), None);
+        capture.file_type = CaptureType::Screenshot;
+        assert_eq!(
+            plan_capture_export(&capture, VideoExportMode::LinksOnly, None),
+            ExportMediaAction::Include(PathBuf::from("/s/capture-001.png"))
+        );
+    }
+}
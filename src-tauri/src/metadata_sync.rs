@@ -0,0 +1,133 @@
+//! Debounced background writer that keeps each bug folder's `metadata.json`
+//! in step with the database.
+//!
+//! Mutating commands call [`MetadataSyncer::notify`] with the affected bug id.
+//! Notifications for the same bug are coalesced until it has been quiet for the
+//! debounce interval, then the write callback runs once on the worker thread.
+//! Dropping the syncer flushes anything still pending.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Quiet period after the last change to a bug before its metadata is written.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// How long the worker sleeps when nothing is pending.
+const IDLE_WAIT: Duration = Duration::from_secs(3600);
+
+pub struct MetadataSyncer {
+    sender: Option<Sender<String>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl MetadataSyncer {
+    /// Start the worker thread. `write` is called with a bug id once changes
+    /// to that bug have settled; errors are its own to report.
+    pub fn spawn<F>(debounce: Duration, write: F) -> Self
+    where
+        F: Fn(&str) + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel::<String>();
+
+        let worker = std::thread::spawn(move || {
+            let mut pending: HashMap<String, Instant> = HashMap::new();
+            loop {
+                let wait = pending
+                    .values()
+                    .min()
+                    .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+                    .unwrap_or(IDLE_WAIT);
+
+                match receiver.recv_timeout(wait) {
+                    Ok(bug_id) => {
+                        pending.insert(bug_id, Instant::now() + debounce);
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => {
+                        for bug_id in pending.keys() {
+                            write(bug_id);
+                        }
+                        break;
+                    }
+                }
+
+                let now = Instant::now();
+                let due: Vec<String> = pending
+                    .iter()
+                    .filter(|(_, deadline)| **deadline <= now)
+                    .map(|(bug_id, _)| bug_id.clone())
+                    .collect();
+                for bug_id in due {
+                    pending.remove(&bug_id);
+                    write(&bug_id);
+                }
+            }
+        });
+
+        Self {
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+
+    /// Record that `bug_id` changed.
+    pub fn notify(&self, bug_id: &str) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(bug_id.to_string());
+        }
+    }
+}
+
+impl Drop for MetadataSyncer {
+    fn drop(&mut self) {
+        // Closing the channel makes the worker flush and exit
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn recorder() -> (Arc<Mutex<Vec<String>>>, impl Fn(&str) + Send + 'static) {
+        let writes = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&writes);
+        (writes, move |bug_id: &str| sink.lock().unwrap().push(bug_id.to_string()))
+    }
+
+    #[test]
+    fn test_notifications_are_coalesced_per_bug() {
+        let (writes, write) = recorder();
+        let syncer = MetadataSyncer::spawn(Duration::from_millis(50), write);
+
+        for _ in 0..5 {
+            syncer.notify("b-1");
+        }
+        syncer.notify("b-2");
+        std::thread::sleep(Duration::from_millis(300));
+
+        let mut written = writes.lock().unwrap().clone();
+        written.sort();
+        assert_eq!(written, vec!["b-1", "b-2"]);
+    }
+
+    #[test]
+    fn test_write_waits_for_debounce() {
+        let (writes, write) = recorder();
+        let syncer = MetadataSyncer::spawn(Duration::from_secs(60), write);
+
+        syncer.notify("b-1");
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(writes.lock().unwrap().is_empty());
+
+        // Dropping flushes pending writes
+        drop(syncer);
+        assert_eq!(*writes.lock().unwrap(), vec!["b-1"]);
+    }
+}
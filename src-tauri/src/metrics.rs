@@ -0,0 +1,260 @@
+//! Opt-in Prometheus metrics endpoint for lab deployments.
+//!
+//! The backend keeps a few process-wide counters (captures routed, routing
+//! errors, AI requests, ticket creations, watcher restarts). When enabled in
+//! the `metrics.endpoint` setting, they are served in the Prometheus text
+//! format at `http://127.0.0.1:<port>/metrics` so fleet health can be scraped
+//! centrally. The endpoint only listens on localhost and is off by default;
+//! it is configured with `set_metrics_settings`, which restarts the server.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Settings key holding [`MetricsSettings`] as JSON.
+pub const METRICS_KEY: &str = "metrics.endpoint";
+
+/// Default port, the one commonly used by Prometheus exporters.
+pub const DEFAULT_PORT: u16 = 9464;
+
+/// How often the server thread checks whether it should stop.
+const ACCEPT_POLL: Duration = Duration::from_millis(200);
+
+/// A process-wide counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+    CapturesRouted,
+    RoutingErrors,
+    AiRequests,
+    TicketCreations,
+    WatcherRestarts,
+}
+
+impl Counter {
+    pub const ALL: [Counter; 5] = [
+        Counter::CapturesRouted,
+        Counter::RoutingErrors,
+        Counter::AiRequests,
+        Counter::TicketCreations,
+        Counter::WatcherRestarts,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Counter::CapturesRouted => "qa_capture_captures_routed_total",
+            Counter::RoutingErrors => "qa_capture_routing_errors_total",
+            Counter::AiRequests => "qa_capture_ai_requests_total",
+            Counter::TicketCreations => "qa_capture_ticket_creations_total",
+            Counter::WatcherRestarts => "qa_capture_watcher_restarts_total",
+        }
+    }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            Counter::CapturesRouted => "Capture files moved into a bug or _unsorted folder and recorded",
+            Counter::RoutingErrors => "Capture files that could not be moved or recorded",
+            Counter::AiRequests => "Requests sent to the AI API",
+            Counter::TicketCreations => "Tickets created in the ticketing integration",
+            Counter::WatcherRestarts => "Capture and staging watcher (re)starts",
+        }
+    }
+
+    fn cell(&self) -> &'static AtomicU64 {
+        &COUNTERS[*self as usize]
+    }
+}
+
+static COUNTERS: [AtomicU64; 5] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+
+pub fn increment(counter: Counter) {
+    counter.cell().fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn value(counter: Counter) -> u64 {
+    counter.cell().load(Ordering::Relaxed)
+}
+
+/// All counters in the Prometheus text exposition format.
+pub fn render() -> String {
+    Counter::ALL
+        .iter()
+        .map(|c| format!("# HELP {0} {1}\n# TYPE {0} counter\n{0} {2}\n", c.name(), c.help(), value(*c)))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsSettings {
+    pub enabled: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+impl Default for MetricsSettings {
+    fn default() -> Self {
+        Self { enabled: false, port: DEFAULT_PORT }
+    }
+}
+
+impl MetricsSettings {
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(METRICS_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(METRICS_KEY, &json)
+            .map_err(|e| format!("Failed to save metrics settings: {}", e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port < 1024 {
+            return Err("The metrics port must be 1024 or higher".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Serves `/metrics` on localhost.
+///
+/// Dropping the struct stops the server and releases the port.
+pub struct MetricsServer {
+    port: u16,
+    stop_flag: Arc<AtomicBool>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl MetricsServer {
+    /// Bind `127.0.0.1:port` (0 picks a free port) and start serving.
+    pub fn start(port: u16) -> Result<Self, String> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop_flag);
+        let worker = thread::spawn(move || {
+            while !flag.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        if let Err(e) = respond(stream) {
+                            eprintln!("Metrics endpoint: {}", e);
+                        }
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL),
+                    Err(e) => {
+                        eprintln!("Metrics endpoint: accept failed: {}", e);
+                        thread::sleep(ACCEPT_POLL);
+                    }
+                }
+            }
+        });
+
+        Ok(Self { port, stop_flag, worker: Some(worker) })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Answer one request: the metrics for `GET /metrics`, 404 otherwise.
+fn respond(mut stream: TcpStream) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut buffer = [0u8; 1024];
+    let read = stream.read(&mut buffer)?;
+    let request = String::from_utf8_lossy(&buffer[..read]);
+    let mut parts = request.split_whitespace();
+
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", "text/plain; version=0.0.4", render()),
+        _ => ("404 Not Found", "text/plain", "Not found\n".to_string()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(port: u16, path: &str) -> String {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_server_exposes_counters() {
+        increment(Counter::TicketCreations);
+        let server = MetricsServer::start(0).unwrap();
+
+        let response = get(server.port(), "/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("# TYPE qa_capture_ticket_creations_total counter\n"));
+        // Other tests may bump counters concurrently, so only check it is counted
+        let line = response
+            .lines()
+            .find(|l| l.starts_with("qa_capture_ticket_creations_total "))
+            .unwrap();
+        assert!(line.split(' ').nth(1).unwrap().parse::<u64>().unwrap() >= 1);
+
+        assert!(get(server.port(), "/").starts_with("HTTP/1.1 404"));
+    }
+
+    #[test]
+    fn test_settings_default_off() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        assert_eq!(MetricsSettings::load(&conn), MetricsSettings::default());
+
+        let settings = MetricsSettings { enabled: true, port: 9100 };
+        settings.save(&conn).unwrap();
+        assert_eq!(MetricsSettings::load(&conn), settings);
+        assert!(MetricsSettings { enabled: true, port: 80 }.validate().is_err());
+    }
+}
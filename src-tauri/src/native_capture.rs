@@ -0,0 +1,214 @@
+//! Screenshots taken by the app itself, straight into the active bug.
+//!
+//! Captures through the Snipping Tool depend on it saving into the session's
+//! `_captures/` folder and on the capture watcher noticing the file.
+//! `capture_screen_region` reads the screen through the platform's
+//! [`ScreenCapture`] instead and writes the PNG and its `Capture` record
+//! directly: into the active bug (or a bug that ended within the grace window,
+//! see `capture_routing`), else into `_unsorted/`. The cursor overlay is burned
+//! in as for the other native captures.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use image::RgbaImage;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::capture_routing::{self, CaptureSource, RoutingHandles};
+use crate::cursor_overlay::CursorOverlay;
+use crate::database::{BugOps, BugRepository, Capture, CaptureOps, CaptureRepository, Session};
+use crate::display_info::{DisplaySnapshot, MonitorInfo};
+use crate::platform::{ScreenCapture, ScreenGrab};
+
+/// Value of `CaptureSource::source` for these captures.
+pub const SOURCE: &str = "native_capture";
+
+/// What `capture_screen_region` captures.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CaptureTarget {
+    /// The monitor under the cursor
+    FullScreen,
+    /// The foreground window
+    ActiveWindow,
+    /// A rectangle of the desktop, in physical pixels
+    Region { x: i32, y: i32, width: u32, height: u32 },
+}
+
+/// The monitor a full-screen capture covers: the one under the cursor, else
+/// the primary one.
+pub fn full_screen_monitor(displays: &DisplaySnapshot) -> Option<&MonitorInfo> {
+    displays
+        .active_monitor
+        .and_then(|i| displays.monitors.get(i))
+        .or_else(|| displays.monitors.iter().find(|m| m.primary))
+        .or_else(|| displays.monitors.first())
+}
+
+/// Monitor scaling at `point`, for sizing the cursor overlay.
+fn scale_at(displays: Option<&DisplaySnapshot>, point: (i32, i32)) -> f64 {
+    displays
+        .and_then(|d| {
+            d.monitors.iter().find(|m| {
+                point.0 >= m.x && point.1 >= m.y && point.0 < m.x + m.width as i32 && point.1 < m.y + m.height as i32
+            })
+        })
+        .map(|m| m.scale_factor)
+        .unwrap_or(1.0)
+}
+
+/// Read `target` from the screen and burn in `overlay`.
+pub fn grab(
+    screen: &dyn ScreenCapture,
+    target: &CaptureTarget,
+    displays: Option<&DisplaySnapshot>,
+    overlay: &CursorOverlay,
+) -> Result<RgbaImage, String> {
+    let ScreenGrab { mut image, origin } = match target {
+        CaptureTarget::FullScreen => {
+            let monitor = displays.and_then(full_screen_monitor).ok_or("No monitors found")?;
+            screen.grab_area(monitor.x, monitor.y, monitor.width, monitor.height)
+        }
+        CaptureTarget::ActiveWindow => screen.grab_active_window(),
+        CaptureTarget::Region { x, y, width, height } => screen.grab_area(*x, *y, *width, *height),
+    }
+    .map_err(|e| e.to_string())?;
+
+    CursorOverlay { scale: scale_at(displays, origin), ..overlay.clone() }.burn_in(&mut image, origin);
+    Ok(image)
+}
+
+fn bug_folder(conn: &Connection, bug_id: &str) -> Option<PathBuf> {
+    BugRepository::new(conn).get(bug_id).ok()?.map(|bug| PathBuf::from(bug.folder_path))
+}
+
+/// Write `image` into the bug the routing picks (else `_unsorted/`) with the
+/// next capture file name, and insert its `Capture` record.
+pub fn store(
+    conn: &Connection,
+    session: &Session,
+    routing: &RoutingHandles,
+    image: &RgbaImage,
+    display: Option<DisplaySnapshot>,
+) -> Result<Capture, String> {
+    let now = Utc::now();
+    let (bug_id, routed, ms_after_bug_end) = capture_routing::route(
+        routing.active_bug.lock().unwrap().clone(),
+        routing.recently_ended.lock().unwrap().as_ref(),
+        capture_routing::load_grace_window(conn),
+        now,
+    );
+    let session_folder = Path::new(&session.folder_path);
+    let dest_dir = bug_id
+        .as_deref()
+        .and_then(|id| bug_folder(conn, id))
+        .unwrap_or_else(|| session_folder.join("_unsorted"));
+    std::fs::create_dir_all(&dest_dir).map_err(|e| format!("Cannot create {:?}: {}", dest_dir, e))?;
+
+    let naming = crate::capture_naming::NamingContext::for_folder(conn, &dest_dir);
+    let number = crate::next_capture_number(&dest_dir, &naming);
+    let (file_name, file_type) = crate::make_capture_filename(Path::new("capture.png"), number, &naming);
+    let path = dest_dir.join(&file_name);
+    image
+        .save_with_format(&path, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+
+    let source = CaptureSource {
+        source: SOURCE.to_string(),
+        routing: routed,
+        ms_after_bug_end,
+        trigger: None,
+        ms_after_trigger: None,
+        trigger_bug_id: None,
+        display,
+        foreground: crate::platform::foreground_window(),
+    };
+    let capture = Capture {
+        id: Uuid::new_v4().to_string(),
+        bug_id,
+        session_id: session.id.clone(),
+        file_name,
+        file_path: path.to_string_lossy().to_string(),
+        file_type,
+        annotated_path: None,
+        file_size_bytes: std::fs::metadata(&path).ok().map(|m| m.len() as i64),
+        is_console_capture: false,
+        parsed_content: None,
+        created_at: now.to_rfc3339(),
+        edited_at: None,
+        media_link: None,
+        video_duration_ms: None,
+        video_width: None,
+        video_height: None,
+        video_codec: None,
+        derived_from: None,
+        frame_timestamp_ms: None,
+        source_metadata: Some(source.to_json()),
+        alt_text: None,
+    };
+    if let Err(e) = CaptureRepository::new(conn).create(&capture) {
+        // Don't leave an untracked file behind in the bug folder
+        let _ = std::fs::remove_file(&path);
+        return Err(format!("Failed to save capture record: {}", e));
+    }
+    Ok(capture)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{PlatformError, Result as PlatformResult};
+    use image::Rgba;
+
+    struct FakeScreen;
+
+    impl ScreenCapture for FakeScreen {
+        fn grab_area(&self, x: i32, y: i32, width: u32, height: u32) -> PlatformResult<ScreenGrab> {
+            Ok(ScreenGrab { image: RgbaImage::from_pixel(width, height, Rgba([9, 9, 9, 255])), origin: (x, y) })
+        }
+
+        fn grab_active_window(&self) -> PlatformResult<ScreenGrab> {
+            Err(PlatformError::Other { message: "no window".to_string() })
+        }
+    }
+
+    fn monitor(x: i32, primary: bool) -> MonitorInfo {
+        MonitorInfo { name: None, x, y: 0, width: 40, height: 30, scale_factor: 2.0, primary }
+    }
+
+    #[test]
+    fn test_full_screen_uses_monitor_under_cursor() {
+        let mut displays = DisplaySnapshot { monitors: vec![monitor(-40, false), monitor(0, true)], active_monitor: Some(0) };
+        assert_eq!(full_screen_monitor(&displays).map(|m| m.x), Some(-40));
+        displays.active_monitor = None;
+        assert_eq!(full_screen_monitor(&displays).map(|m| m.x), Some(0));
+        let displays = DisplaySnapshot { monitors: vec![monitor(-40, false)], active_monitor: None };
+        assert_eq!(full_screen_monitor(&displays).map(|m| m.x), Some(-40));
+    }
+
+    #[test]
+    fn test_grab_burns_in_cursor_at_screen_position() {
+        let displays = DisplaySnapshot { monitors: vec![monitor(0, true)], active_monitor: None };
+        let overlay = CursorOverlay { cursor: Some((15, 12)), click: None, scale: 1.0 };
+        let target = CaptureTarget::Region { x: 10, y: 10, width: 20, height: 10 };
+        let image = grab(&FakeScreen, &target, Some(&displays), &overlay).unwrap();
+        assert_eq!(image.dimensions(), (20, 10));
+        assert_eq!(*image.get_pixel(5, 2), Rgba([0, 0, 0, 255]));
+
+        assert!(grab(&FakeScreen, &CaptureTarget::FullScreen, None, &overlay).is_err());
+        assert_eq!(
+            grab(&FakeScreen, &CaptureTarget::ActiveWindow, None, &overlay).unwrap_err(),
+            "Platform error: no window"
+        );
+    }
+
+    #[test]
+    fn test_target_serialization() {
+        let target: CaptureTarget =
+            serde_json::from_str(r#"{"kind":"region","x":-5,"y":0,"width":10,"height":20}"#).unwrap();
+        assert_eq!(target, CaptureTarget::Region { x: -5, y: 0, width: 10, height: 20 });
+        assert_eq!(serde_json::to_string(&CaptureTarget::ActiveWindow).unwrap(), r#"{"kind":"activeWindow"}"#);
+    }
+}
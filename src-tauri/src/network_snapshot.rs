@@ -0,0 +1,426 @@
+//! Network conditions a session or bug was recorded under.
+//!
+//! A [`NetworkSnapshot`] records the active connection type (Ethernet, Wi-Fi,
+//! cellular), whether a VPN adapter is up, and the TCP connect time to each
+//! endpoint in the `environment.network` setting. One is taken in the
+//! background when a session starts and stored under `network` in the
+//! session's environment; `capture_network_snapshot` takes one on demand.
+//! Bugs tagged as network-related (see [`is_network_related`]) get a fresh
+//! snapshot under `network` in their `metadata_json` when their capture ends,
+//! so "works on VPN only" reports carry the context.
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::database::{Bug, BugOps, BugRepository, SettingsOps, SettingsRepository};
+
+/// Settings key holding [`NetworkSnapshotSettings`] as JSON.
+pub const NETWORK_SNAPSHOT_KEY: &str = "environment.network";
+
+/// Key the snapshot is stored under in session environments and bug metadata.
+pub const NETWORK_KEY: &str = "network";
+
+/// Accepted number of latency endpoints.
+pub const MAX_ENDPOINTS: usize = 10;
+
+/// How long to wait for each endpoint to accept a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Adapter names and descriptions that identify VPN clients.
+const VPN_MARKERS: &[&str] = &[
+    "vpn", "wireguard", "tap-windows", "openvpn", "anyconnect", "globalprotect", "pangp", "fortinet",
+    "forticlient", "zscaler", "tailscale", "nordlynx", "juniper",
+];
+
+/// Interface name prefixes of tunnels (`tun0`, `wg0`, `utun3`).
+const TUNNEL_PREFIXES: &[&str] = &["tun", "tap", "wg", "utun", "ipsec"];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkSnapshotSettings {
+    /// `host`, `host:port` or an http(s) URL; the port defaults to 443
+    pub endpoints: Vec<String>,
+}
+
+impl NetworkSnapshotSettings {
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(NETWORK_SNAPSHOT_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(NETWORK_SNAPSHOT_KEY, &json)
+            .map_err(|e| format!("Failed to save network snapshot settings: {}", e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.endpoints.len() > MAX_ENDPOINTS {
+            return Err(format!("At most {} latency endpoints are supported", MAX_ENDPOINTS));
+        }
+        for endpoint in &self.endpoints {
+            parse_endpoint(endpoint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Host and port to connect to for `endpoint`.
+pub fn parse_endpoint(endpoint: &str) -> Result<(String, u16), String> {
+    let trimmed = endpoint.trim();
+    let (rest, default_port) = if let Some(rest) = trimmed.strip_prefix("https://") {
+        (rest, 443)
+    } else if let Some(rest) = trimmed.strip_prefix("http://") {
+        (rest, 80)
+    } else {
+        (trimmed, 443)
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+        // [IPv6]:port
+        let (host, rest) = bracketed
+            .split_once(']')
+            .ok_or_else(|| format!("Invalid endpoint: {}", endpoint))?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.split_once(':') {
+            Some((host, port)) if !port.contains(':') => (host, Some(port)),
+            _ => (authority, None),
+        }
+    };
+    let port = match port {
+        Some(port) => port
+            .parse()
+            .map_err(|_| format!("Invalid port in endpoint: {}", endpoint))?,
+        None => default_port,
+    };
+    if host.is_empty() {
+        return Err(format!("Invalid endpoint: {}", endpoint));
+    }
+    Ok((host.to_string(), port))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkType {
+    Ethernet,
+    Wifi,
+    Cellular,
+    Other,
+    Offline,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AdapterKind {
+    Ethernet,
+    Wifi,
+    Cellular,
+    Tunnel,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Adapter {
+    name: String,
+    description: String,
+    kind: AdapterKind,
+    up: bool,
+}
+
+impl Adapter {
+    fn is_vpn(&self) -> bool {
+        if self.kind == AdapterKind::Tunnel {
+            return true;
+        }
+        let name = self.name.to_lowercase();
+        let description = self.description.to_lowercase();
+        TUNNEL_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+            || VPN_MARKERS.iter().any(|marker| name.contains(marker) || description.contains(marker))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointLatency {
+    pub endpoint: String,
+    pub latency_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSnapshot {
+    pub captured_at: String,
+    pub network_type: NetworkType,
+    pub vpn: bool,
+    /// Names of the VPN adapters that are up
+    #[serde(default)]
+    pub vpn_adapters: Vec<String>,
+    #[serde(default)]
+    pub latency: Vec<EndpointLatency>,
+}
+
+/// Primary connection type and the VPN adapters among the adapters that are up.
+fn classify(adapters: &[Adapter]) -> (NetworkType, Vec<String>) {
+    let up: Vec<&Adapter> = adapters.iter().filter(|a| a.up).collect();
+    let vpn_adapters: Vec<String> = up.iter().filter(|a| a.is_vpn()).map(|a| a.name.clone()).collect();
+    let physical = |kind| up.iter().any(|a| a.kind == kind && !a.is_vpn());
+    let network_type = if physical(AdapterKind::Ethernet) {
+        NetworkType::Ethernet
+    } else if physical(AdapterKind::Wifi) {
+        NetworkType::Wifi
+    } else if physical(AdapterKind::Cellular) {
+        NetworkType::Cellular
+    } else if up.is_empty() {
+        NetworkType::Offline
+    } else {
+        NetworkType::Other
+    };
+    (network_type, vpn_adapters)
+}
+
+/// Network interfaces from sysfs, without the loopback.
+#[cfg(target_os = "linux")]
+fn adapters() -> Vec<Adapter> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/net") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            let read = |file: &str| std::fs::read_to_string(path.join(file)).map(|s| s.trim().to_string()).unwrap_or_default();
+            let link_type = read("type");
+            if name == "lo" || link_type == "772" {
+                return None;
+            }
+            let kind = if path.join("wireless").exists() || path.join("phy80211").exists() {
+                AdapterKind::Wifi
+            } else if name.starts_with("ww") {
+                AdapterKind::Cellular
+            } else if link_type == "65534" || link_type == "512" {
+                // ARPHRD_NONE (tun, wireguard) and ARPHRD_PPP
+                AdapterKind::Tunnel
+            } else if link_type == "1" && path.join("device").exists() {
+                AdapterKind::Ethernet
+            } else {
+                // Bridges, veth pairs, docker networks
+                AdapterKind::Other
+            };
+            // Tunnels report `unknown` while passing traffic
+            let operstate = read("operstate");
+            let up = operstate == "up" || (operstate == "unknown" && read("carrier") == "1");
+            Some(Adapter { name, description: String::new(), kind, up })
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+fn adapters() -> Vec<Adapter> {
+    use windows::Win32::NetworkManagement::IpHelper::{
+        GetAdaptersAddresses, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER, GAA_FLAG_SKIP_MULTICAST,
+        IP_ADAPTER_ADDRESSES_LH,
+    };
+    use windows::Win32::Networking::WinSock::AF_UNSPEC;
+
+    const ERROR_BUFFER_OVERFLOW: u32 = 111;
+    let flags = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+    let mut size: u32 = 16 * 1024;
+    // u64 backing keeps the adapter structs aligned
+    let mut buffer: Vec<u64> = Vec::new();
+    let mut result = ERROR_BUFFER_OVERFLOW;
+    // The list can grow between calls; retry with the size asked for
+    for _ in 0..3 {
+        buffer = vec![0u64; (size as usize).div_ceil(8)];
+        result = unsafe {
+            GetAdaptersAddresses(
+                AF_UNSPEC.0 as u32,
+                flags,
+                None,
+                Some(buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH),
+                &mut size,
+            )
+        };
+        if result != ERROR_BUFFER_OVERFLOW {
+            break;
+        }
+    }
+    if result != 0 {
+        return Vec::new();
+    }
+
+    let mut adapters = Vec::new();
+    let mut current = buffer.as_ptr() as *const IP_ADAPTER_ADDRESSES_LH;
+    while let Some(adapter) = unsafe { current.as_ref() } {
+        let kind = match adapter.IfType {
+            6 => AdapterKind::Ethernet,
+            71 => AdapterKind::Wifi,
+            243 | 244 => AdapterKind::Cellular,
+            23 | 131 => AdapterKind::Tunnel,
+            _ => AdapterKind::Other,
+        };
+        // Loopback (24) is not a connection
+        if adapter.IfType != 24 {
+            adapters.push(Adapter {
+                name: unsafe { adapter.FriendlyName.to_string() }.unwrap_or_default(),
+                description: unsafe { adapter.Description.to_string() }.unwrap_or_default(),
+                kind,
+                // IfOperStatusUp
+                up: adapter.OperStatus.0 == 1,
+            });
+        }
+        current = adapter.Next;
+    }
+    adapters
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn adapters() -> Vec<Adapter> {
+    Vec::new()
+}
+
+/// TCP connect time to `endpoint`.
+fn measure(endpoint: &str) -> EndpointLatency {
+    let result = parse_endpoint(endpoint).and_then(|(host, port)| {
+        let address = (host.as_str(), port)
+            .to_socket_addrs()
+            .map_err(|e| format!("Cannot resolve {}: {}", host, e))?
+            .next()
+            .ok_or_else(|| format!("No address for {}", host))?;
+        let started = Instant::now();
+        TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| e.to_string())?;
+        Ok(started.elapsed().as_millis() as u64)
+    });
+    EndpointLatency {
+        endpoint: endpoint.to_string(),
+        latency_ms: result.as_ref().ok().copied(),
+        error: result.err(),
+    }
+}
+
+/// Take a snapshot now. Endpoints are checked in parallel, so this takes at
+/// most about [`CONNECT_TIMEOUT`] plus DNS resolution.
+pub fn capture(settings: &NetworkSnapshotSettings) -> NetworkSnapshot {
+    let (network_type, vpn_adapters) = classify(&adapters());
+    let latency = std::thread::scope(|scope| {
+        let checks: Vec<_> = settings
+            .endpoints
+            .iter()
+            .map(|endpoint| scope.spawn(move || measure(endpoint)))
+            .collect();
+        checks.into_iter().filter_map(|check| check.join().ok()).collect()
+    });
+    NetworkSnapshot {
+        captured_at: Utc::now().to_rfc3339(),
+        network_type,
+        vpn: !vpn_adapters.is_empty(),
+        vpn_adapters,
+        latency,
+    }
+}
+
+/// Whether a bug is tagged as network-related: one of its custom field values
+/// (e.g. an area or category) is or lists "network".
+pub fn is_network_related(bug: &Bug) -> bool {
+    fn mentions_network(value: &Value) -> bool {
+        match value {
+            Value::String(s) => s
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| word.eq_ignore_ascii_case("network")),
+            Value::Array(items) => items.iter().any(mentions_network),
+            _ => false,
+        }
+    }
+    bug.custom_metadata
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<Map<String, Value>>(json).ok())
+        .is_some_and(|fields| fields.values().any(mentions_network))
+}
+
+/// Store `snapshot` under `network` in the bug's `metadata_json`.
+pub fn attach_to_bug(conn: &Connection, bug_id: &str, snapshot: &NetworkSnapshot) -> Result<(), String> {
+    let value = serde_json::to_value(snapshot).map_err(|e| e.to_string())?;
+    BugRepository::new(conn).merge_metadata(bug_id, NETWORK_KEY, value).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adapter(name: &str, description: &str, kind: AdapterKind, up: bool) -> Adapter {
+        Adapter { name: name.to_string(), description: description.to_string(), kind, up }
+    }
+
+    #[test]
+    fn test_parse_endpoint() {
+        assert_eq!(parse_endpoint("https://api.example.com/health").unwrap(), ("api.example.com".to_string(), 443));
+        assert_eq!(parse_endpoint("http://intranet.local").unwrap(), ("intranet.local".to_string(), 80));
+        assert_eq!(parse_endpoint("db.internal:5432").unwrap(), ("db.internal".to_string(), 5432));
+        assert_eq!(parse_endpoint("[::1]:8080").unwrap(), ("::1".to_string(), 8080));
+        assert_eq!(parse_endpoint("example.com").unwrap(), ("example.com".to_string(), 443));
+        assert!(parse_endpoint("example.com:https").is_err());
+        assert!(parse_endpoint("https://").is_err());
+    }
+
+    #[test]
+    fn test_classify_prefers_wired_and_detects_vpn() {
+        let adapters = vec![
+            adapter("Wi-Fi", "Intel(R) Wi-Fi 6 AX201", AdapterKind::Wifi, true),
+            adapter("Ethernet", "Realtek PCIe GbE", AdapterKind::Ethernet, false),
+            adapter("Ethernet 3", "PANGP Virtual Ethernet Adapter", AdapterKind::Ethernet, true),
+        ];
+        assert_eq!(classify(&adapters), (NetworkType::Wifi, vec!["Ethernet 3".to_string()]));
+
+        let adapters = vec![
+            adapter("eth0", "", AdapterKind::Ethernet, true),
+            adapter("wg0", "", AdapterKind::Tunnel, true),
+            adapter("tunnel-down", "", AdapterKind::Tunnel, false),
+        ];
+        assert_eq!(classify(&adapters), (NetworkType::Ethernet, vec!["wg0".to_string()]));
+
+        assert_eq!(classify(&[]), (NetworkType::Offline, vec![]));
+        // "Tunnel" in a description is not a VPN; tunnel prefixes only match names
+        assert!(!adapter("Ethernet", "Teredo Tunneling", AdapterKind::Other, true).is_vpn());
+        assert!(adapter("Ethernet 2", "NordVPN Virtual Adapter", AdapterKind::Ethernet, true).is_vpn());
+    }
+
+    #[test]
+    fn test_is_network_related_reads_custom_fields() {
+        let mut bug: Bug = serde_json::from_value(serde_json::json!({
+            "id": "b-1", "session_id": "s-1", "bug_number": 1, "display_id": "BUG-001", "type": "bug",
+            "title": null, "notes": null, "description": null, "ai_description": null, "status": "capturing",
+            "meeting_id": null, "software_version": null, "console_parse_json": null, "metadata_json": null,
+            "custom_metadata": r#"{"area":"Network / Sync","severity":"high"}"#,
+            "folder_path": "/qa/bug_001", "created_at": "", "updated_at": ""
+        }))
+        .unwrap();
+        assert!(is_network_related(&bug));
+
+        bug.custom_metadata = Some(r#"{"tags":["ui","network"]}"#.to_string());
+        assert!(is_network_related(&bug));
+
+        bug.custom_metadata = Some(r#"{"area":"Networking page"}"#.to_string());
+        assert!(!is_network_related(&bug));
+        bug.custom_metadata = None;
+        assert!(!is_network_related(&bug));
+    }
+
+    #[test]
+    fn test_snapshot_without_endpoints_has_no_latency() {
+        let snapshot = capture(&NetworkSnapshotSettings::default());
+        assert!(snapshot.latency.is_empty());
+        assert_eq!(snapshot.vpn, !snapshot.vpn_adapters.is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+//! Leases and merging for notes documents shared between windows.
+//!
+//! The main window and the session-notes window can both edit the same notes.
+//! A window acquires a short-lived lease before editing; writes from other
+//! windows are rejected while the lease is live. Writes that were based on an
+//! out-of-date copy of the document are merged instead of overwriting the
+//! newer content.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// How long a lease stays valid without being renewed.
+pub const DEFAULT_LEASE_TTL: Duration = Duration::from_secs(30);
+
+/// A lease on a notes document, returned to the window that holds it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NotesLease {
+    pub document_id: String,
+    pub holder: String,
+    pub token: String,
+    pub expires_at: String,
+}
+
+/// Result of writing a notes document.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NotesWriteResult {
+    /// The content that was actually written.
+    pub content: String,
+    /// True when the write was stale and had to be merged with newer content.
+    pub merged: bool,
+}
+
+struct LeaseEntry {
+    lease: NotesLease,
+    expires: Instant,
+}
+
+/// In-memory registry of live notes leases, keyed by document ID.
+#[derive(Default)]
+pub struct NotesLockRegistry {
+    leases: HashMap<String, LeaseEntry>,
+}
+
+impl NotesLockRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Acquire (or renew) the lease on `document_id` for `holder`.
+    ///
+    /// Re-acquiring by the current holder extends the lease and keeps its token.
+    /// Fails if another holder has a live lease.
+    pub fn acquire(
+        &mut self,
+        document_id: &str,
+        holder: &str,
+        ttl: Duration,
+        now: Instant,
+    ) -> Result<NotesLease, String> {
+        let token = match self.live_lease(document_id, now) {
+            Some(existing) if existing.holder != holder => {
+                return Err(format!(
+                    "Notes are currently being edited in the '{}' window",
+                    existing.holder
+                ));
+            }
+            Some(existing) => existing.token.clone(),
+            None => Uuid::new_v4().to_string(),
+        };
+
+        let expires_at = Utc::now()
+            + chrono::Duration::from_std(ttl).unwrap_or_else(|_| chrono::Duration::seconds(30));
+        let lease = NotesLease {
+            document_id: document_id.to_string(),
+            holder: holder.to_string(),
+            token,
+            expires_at: expires_at.to_rfc3339(),
+        };
+
+        self.leases.insert(
+            document_id.to_string(),
+            LeaseEntry {
+                lease: lease.clone(),
+                expires: now + ttl,
+            },
+        );
+        Ok(lease)
+    }
+
+    /// Release the lease on `document_id` if `token` matches.
+    /// Returns true if a lease was released.
+    pub fn release(&mut self, document_id: &str, token: &str) -> bool {
+        match self.leases.get(document_id) {
+            Some(entry) if entry.lease.token == token => {
+                self.leases.remove(document_id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Check that a write with `token` is allowed. Writes are always allowed
+    /// when no live lease exists.
+    pub fn check_write(
+        &self,
+        document_id: &str,
+        token: Option<&str>,
+        now: Instant,
+    ) -> Result<(), String> {
+        match self.live_lease(document_id, now) {
+            Some(lease) if Some(lease.token.as_str()) != token => Err(format!(
+                "Notes are locked by the '{}' window. Acquire the notes lock before saving.",
+                lease.holder
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn live_lease(&self, document_id: &str, now: Instant) -> Option<&NotesLease> {
+        self.leases
+            .get(document_id)
+            .filter(|entry| entry.expires > now)
+            .map(|entry| &entry.lease)
+    }
+}
+
+/// Merge a stale write into the current document.
+///
+/// `base` is the content the writer started from, `current` is what is stored
+/// now and `ours` is what the writer wants to save. When only one side changed
+/// that side wins. When both changed, the writer's content is kept and any
+/// lines the other window added (present in `current` but in neither `base`
+/// nor `ours`) are appended, so no one's notes are lost.
+pub fn merge_notes(base: &str, current: &str, ours: &str) -> NotesWriteResult {
+    if current == base || current == ours {
+        return NotesWriteResult { content: ours.to_string(), merged: false };
+    }
+    if ours == base {
+        return NotesWriteResult { content: current.to_string(), merged: true };
+    }
+
+    let base_lines: Vec<&str> = base.lines().collect();
+    let our_lines: Vec<&str> = ours.lines().collect();
+    let theirs_added: Vec<&str> = current
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter(|line| !base_lines.contains(line) && !our_lines.contains(line))
+        .collect();
+
+    let mut content = ours.to_string();
+    if !theirs_added.is_empty() {
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        for line in theirs_added {
+            content.push_str(line);
+            content.push('\n');
+        }
+    }
+
+    NotesWriteResult { content, merged: true }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire_blocks_other_holders_until_expiry() {
+        let mut registry = NotesLockRegistry::new();
+        let now = Instant::now();
+
+        let lease = registry.acquire("session-1", "main", DEFAULT_LEASE_TTL, now).unwrap();
+        let err = registry.acquire("session-1", "session-notes", DEFAULT_LEASE_TTL, now).unwrap_err();
+        assert!(err.contains("main"));
+
+        // Other documents are independent
+        assert!(registry.acquire("session-2", "session-notes", DEFAULT_LEASE_TTL, now).is_ok());
+
+        // Once expired, another window can take over
+        let later = now + DEFAULT_LEASE_TTL + Duration::from_secs(1);
+        let taken = registry.acquire("session-1", "session-notes", DEFAULT_LEASE_TTL, later).unwrap();
+        assert_ne!(taken.token, lease.token);
+    }
+
+    #[test]
+    fn test_renew_keeps_token() {
+        let mut registry = NotesLockRegistry::new();
+        let now = Instant::now();
+
+        let first = registry.acquire("session-1", "main", DEFAULT_LEASE_TTL, now).unwrap();
+        let renewed = registry
+            .acquire("session-1", "main", DEFAULT_LEASE_TTL, now + Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(first.token, renewed.token);
+    }
+
+    #[test]
+    fn test_release_requires_matching_token() {
+        let mut registry = NotesLockRegistry::new();
+        let now = Instant::now();
+
+        let lease = registry.acquire("session-1", "main", DEFAULT_LEASE_TTL, now).unwrap();
+        assert!(!registry.release("session-1", "wrong-token"));
+        assert!(registry.check_write("session-1", None, now).is_err());
+
+        assert!(registry.release("session-1", &lease.token));
+        assert!(registry.check_write("session-1", None, now).is_ok());
+    }
+
+    #[test]
+    fn test_check_write_accepts_holder_token() {
+        let mut registry = NotesLockRegistry::new();
+        let now = Instant::now();
+
+        let lease = registry.acquire("session-1", "main", DEFAULT_LEASE_TTL, now).unwrap();
+        assert!(registry.check_write("session-1", Some(&lease.token), now).is_ok());
+        assert!(registry.check_write("session-1", Some("other"), now).is_err());
+        assert!(registry.check_write("session-9", None, now).is_ok());
+    }
+
+    #[test]
+    fn test_merge_when_only_one_side_changed() {
+        let result = merge_notes("a\n", "a\n", "a\nb\n");
+        assert_eq!(result, NotesWriteResult { content: "a\nb\n".to_string(), merged: false });
+
+        let result = merge_notes("a\n", "a\nc\n", "a\n");
+        assert_eq!(result, NotesWriteResult { content: "a\nc\n".to_string(), merged: true });
+    }
+
+    #[test]
+    fn test_merge_keeps_both_sides_additions() {
+        let base = "# Notes\n- login works\n";
+        let current = "# Notes\n- login works\n- search is slow\n";
+        let ours = "# Notes\n- login works\n- export fails";
+
+        let result = merge_notes(base, current, ours);
+        assert!(result.merged);
+        assert_eq!(result.content, "# Notes\n- login works\n- export fails\n- search is slow\n");
+    }
+}
@@ -0,0 +1,175 @@
+//! Session and bug notes, stored in the database and mirrored to disk.
+//!
+//! The `sessions.session_notes` and `bugs.notes` columns are the source of
+//! truth, so search, summaries and exports all see the same text. Each save
+//! schedules a debounced rewrite of `session-notes.md` in the session folder or
+//! `notes.md` in the bug folder (through a [`crate::metadata_sync::MetadataSyncer`]
+//! keyed by [`NotesOwner::key`]), so the folders stay readable without the app.
+//! Notes that exist only as files, from before the database held them, are
+//! copied in by [`backfill`] at startup.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+
+use crate::annotation_windows::write_atomically;
+use crate::database::{BugOps, BugRepository, SessionOps, SessionRepository};
+
+/// Notes file in a session folder.
+pub const SESSION_NOTES_FILE: &str = "session-notes.md";
+
+/// Notes file in a bug folder.
+pub const BUG_NOTES_FILE: &str = "notes.md";
+
+/// Whose notes changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotesOwner {
+    Session(String),
+    Bug(String),
+}
+
+impl NotesOwner {
+    /// Key used to coalesce mirror writes, e.g. "bug:<id>".
+    pub fn key(&self) -> String {
+        match self {
+            NotesOwner::Session(id) => format!("session:{}", id),
+            NotesOwner::Bug(id) => format!("bug:{}", id),
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key.split_once(':')? {
+            ("session", id) => Some(NotesOwner::Session(id.to_string())),
+            ("bug", id) => Some(NotesOwner::Bug(id.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// Notes file for `owner` and the notes stored in the DB. None when the
+/// session or bug no longer exists.
+fn load(conn: &Connection, owner: &NotesOwner) -> Result<Option<(PathBuf, String)>, String> {
+    Ok(match owner {
+        NotesOwner::Session(id) => SessionRepository::new(conn)
+            .get(id)
+            .map_err(|e| format!("Failed to get session: {}", e))?
+            .map(|s| (Path::new(&s.folder_path).join(SESSION_NOTES_FILE), s.session_notes.unwrap_or_default())),
+        NotesOwner::Bug(id) => BugRepository::new(conn)
+            .get(id)
+            .map_err(|e| format!("Failed to get bug: {}", e))?
+            .map(|b| (Path::new(&b.folder_path).join(BUG_NOTES_FILE), b.notes.unwrap_or_default())),
+    })
+}
+
+/// Rewrite the notes file of `owner` from the DB. Empty notes only create a
+/// file when one already exists.
+pub fn mirror(conn: &Connection, owner: &NotesOwner) -> Result<(), String> {
+    let Some((path, notes)) = load(conn, owner)? else {
+        return Ok(());
+    };
+    if notes.is_empty() && !path.exists() {
+        return Ok(());
+    }
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder).map_err(|e| format!("Failed to create {}: {}", folder.display(), e))?;
+    }
+    write_atomically(&path, notes.as_bytes()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Copy notes that only exist on disk into the DB. Returns how many sessions
+/// and bugs were filled in.
+pub fn backfill(conn: &Connection) -> Result<usize, String> {
+    let read = |path: PathBuf| std::fs::read_to_string(path).ok().filter(|notes| !notes.trim().is_empty());
+    let mut filled = 0;
+
+    let sessions: Vec<(String, String)> = conn
+        .prepare("SELECT id, folder_path FROM sessions WHERE COALESCE(session_notes, '') = ''")
+        .and_then(|mut stmt| stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect())
+        .map_err(|e| format!("Failed to list sessions: {}", e))?;
+    for (id, folder) in sessions {
+        if let Some(notes) = read(Path::new(&folder).join(SESSION_NOTES_FILE)) {
+            conn.execute("UPDATE sessions SET session_notes = ?1 WHERE id = ?2", [&notes, &id])
+                .map_err(|e| format!("Failed to save session notes: {}", e))?;
+            filled += 1;
+        }
+    }
+
+    let bugs: Vec<(String, String)> = conn
+        .prepare("SELECT id, folder_path FROM bugs WHERE COALESCE(notes, '') = ''")
+        .and_then(|mut stmt| stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect())
+        .map_err(|e| format!("Failed to list bugs: {}", e))?;
+    for (id, folder) in bugs {
+        if let Some(notes) = read(Path::new(&folder).join(BUG_NOTES_FILE)) {
+            conn.execute("UPDATE bugs SET notes = ?1 WHERE id = ?2", [&notes, &id])
+                .map_err(|e| format!("Failed to save bug notes: {}", e))?;
+            filled += 1;
+        }
+    }
+
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db(folder: &Path) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO sessions (id, started_at, folder_path) VALUES ('s-1', '2024-01-01T10:00:00Z', ?1)",
+            [folder.to_string_lossy()],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO bugs (id, session_id, bug_number, display_id, folder_path) VALUES ('b-1', 's-1', 1, 'BUG-001', ?1)",
+            [folder.join("bug_001").to_string_lossy()],
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_backfill_copies_file_only_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = test_db(dir.path());
+        std::fs::write(dir.path().join(SESSION_NOTES_FILE), "Login flow").unwrap();
+        std::fs::create_dir_all(dir.path().join("bug_001")).unwrap();
+        std::fs::write(dir.path().join("bug_001").join(BUG_NOTES_FILE), "Button overlaps").unwrap();
+
+        assert_eq!(backfill(&conn).unwrap(), 2);
+        let session_notes: String =
+            conn.query_row("SELECT session_notes FROM sessions WHERE id = 's-1'", [], |row| row.get(0)).unwrap();
+        assert_eq!(session_notes, "Login flow");
+
+        // Notes already in the DB win over a stale file
+        conn.execute("UPDATE bugs SET notes = 'Edited in app' WHERE id = 'b-1'", []).unwrap();
+        std::fs::write(dir.path().join("bug_001").join(BUG_NOTES_FILE), "Old text").unwrap();
+        conn.execute("UPDATE sessions SET session_notes = NULL", []).unwrap();
+        assert_eq!(backfill(&conn).unwrap(), 1);
+        let bug_notes: String = conn.query_row("SELECT notes FROM bugs WHERE id = 'b-1'", [], |row| row.get(0)).unwrap();
+        assert_eq!(bug_notes, "Edited in app");
+    }
+
+    #[test]
+    fn test_mirror_writes_db_notes_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = test_db(dir.path());
+        let bug = NotesOwner::from_key(&NotesOwner::Bug("b-1".to_string()).key()).unwrap();
+        let bug_file = dir.path().join("bug_001").join(BUG_NOTES_FILE);
+
+        // Nothing to mirror yet
+        mirror(&conn, &bug).unwrap();
+        assert!(!bug_file.exists());
+
+        conn.execute("UPDATE bugs SET notes = 'Steps: 1. open' WHERE id = 'b-1'", []).unwrap();
+        mirror(&conn, &bug).unwrap();
+        assert_eq!(std::fs::read_to_string(&bug_file).unwrap(), "Steps: 1. open");
+
+        // Clearing the notes empties the existing file; a deleted bug is skipped
+        conn.execute("UPDATE bugs SET notes = '' WHERE id = 'b-1'", []).unwrap();
+        mirror(&conn, &bug).unwrap();
+        assert_eq!(std::fs::read_to_string(&bug_file).unwrap(), "");
+        mirror(&conn, &NotesOwner::Bug("b-gone".to_string())).unwrap();
+    }
+}
@@ -0,0 +1,836 @@
+//! A small PDF writer for session reports.
+//!
+//! [`PdfReport`] lays out wrapped text and screenshots top to bottom on A4
+//! pages, starting a new page when one is full, and numbers the pages. Text
+//! uses the standard Helvetica fonts every PDF reader has, so nothing is
+//! embedded but the screenshots, which are re-encoded as JPEG and scaled down
+//! to keep the file small enough to mail. A line with characters outside the
+//! Windows-1252 set those fonts cover is set in a TrueType font from the
+//! system instead, embedded with Identity-H encoding and cut down to the
+//! glyphs the report uses. Without such a font those characters print as `?`.
+
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+/// Line height as a multiple of the font size.
+const LINE_SPACING: f32 = 1.35;
+
+/// Tallest a screenshot is drawn, in points, so a caption fits below it.
+const MAX_IMAGE_HEIGHT: f32 = 560.0;
+
+/// Longest side, in pixels, screenshots are scaled down to before embedding.
+const MAX_IMAGE_PIXELS: u32 = 1600;
+
+const JPEG_QUALITY: u8 = 85;
+
+/// Helvetica advance widths for ' ' to '~', in 1/1000 of the font size.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556, 556, 556, 556, 556,
+    556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833,
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556,
+    556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334,
+    260, 334, 584,
+];
+
+/// TrueType tables an embedded font needs (PDF 32000-1, 9.9), in tag order.
+const EMBEDDED_TABLES: [&[u8; 4]; 9] =
+    [b"cvt ", b"fpgm", b"glyf", b"head", b"hhea", b"hmtx", b"loca", b"maxp", b"prep"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+/// Width of `text` in Helvetica, in points. Bold is measured a little wider
+/// than regular, which errs on the side of wrapping early.
+fn helvetica_width(text: &str, font: Font, size: f32) -> f32 {
+    let units: u32 = text
+        .chars()
+        .map(|c| match c {
+            ' '..='~' => HELVETICA_WIDTHS[c as usize - 32] as u32,
+            _ => 556,
+        })
+        .sum();
+    let scale = if font == Font::Bold { 1.07 } else { 1.0 };
+    units as f32 * scale * size / 1000.0
+}
+
+/// Split `line` into pieces no wider than `width` as measured by `measure`,
+/// breaking between words and inside words too long for a line.
+fn wrap(line: &str, width: f32, measure: &dyn Fn(&str) -> f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in line.split(' ') {
+        let candidate = if current.is_empty() { word.to_string() } else { format!("{} {}", current, word) };
+        if measure(&candidate) <= width {
+            current = candidate;
+            continue;
+        }
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        for c in word.chars() {
+            current.push(c);
+            if measure(&current) > width && current.chars().count() > 1 {
+                current.pop();
+                lines.push(std::mem::replace(&mut current, c.to_string()));
+            }
+        }
+    }
+    lines.push(current);
+    lines
+}
+
+/// The Windows-1252 byte for `c`, if the standard fonts can show it.
+fn win_ansi_byte(c: char) -> Option<u8> {
+    let byte = match c {
+        ' '..='~' => c as u8,
+        '\t' => b' ',
+        '\u{a0}'..='\u{ff}' => c as u32 as u8,
+        '€' => 0x80,
+        '…' => 0x85,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        _ => return None,
+    };
+    Some(byte)
+}
+
+/// Whether `text` has characters the standard fonts can't show.
+fn needs_unicode(text: &str) -> bool {
+    text.chars().any(|c| win_ansi_byte(c).is_none())
+}
+
+/// `text` as a PDF string literal body in Windows-1252, the encoding of the
+/// standard fonts, with bytes outside ASCII written as octal escapes.
+fn pdf_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '(' | ')' | '\\') {
+            out.push('\\');
+            out.push(c);
+            continue;
+        }
+        let byte = win_ansi_byte(c).unwrap_or(b'?');
+        if byte.is_ascii() {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("\\{:03o}", byte));
+        }
+    }
+    out
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+fn table_checksum(table: &[u8]) -> u32 {
+    table.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// The TrueType font `data` reduced to the tables a PDF needs, with the
+/// outlines of every glyph outside `keep` (and the glyphs they are built
+/// from) left empty. Glyph ids stay the same. `None` if the tables can't be
+/// read.
+fn subset_font(data: &[u8], keep: impl IntoIterator<Item = u16>) -> Option<Vec<u8>> {
+    let mut tables = HashMap::new();
+    for i in 0..read_u16(data, 4)? as usize {
+        let record = 12 + 16 * i;
+        let tag: [u8; 4] = data.get(record..record + 4)?.try_into().ok()?;
+        let offset = read_u32(data, record + 8)? as usize;
+        let length = read_u32(data, record + 12)? as usize;
+        tables.insert(tag, data.get(offset..offset.checked_add(length)?)?);
+    }
+    let head = *tables.get(b"head")?;
+    let loca = *tables.get(b"loca")?;
+    let glyf = *tables.get(b"glyf")?;
+    let long_loca = read_u16(head, 50)? == 1;
+    let num_glyphs = read_u16(tables.get(b"maxp")?, 4)? as usize;
+    let glyph = |id: usize| -> Option<&[u8]> {
+        let (start, end) = if long_loca {
+            (read_u32(loca, id * 4)? as usize, read_u32(loca, id * 4 + 4)? as usize)
+        } else {
+            (read_u16(loca, id * 2)? as usize * 2, read_u16(loca, id * 2 + 2)? as usize * 2)
+        };
+        glyf.get(start..end)
+    };
+
+    // Composite glyphs are drawn from other glyphs, which are kept as well
+    let mut kept = BTreeSet::new();
+    let mut pending: Vec<u16> = keep.into_iter().chain([0]).collect();
+    while let Some(id) = pending.pop() {
+        if id as usize >= num_glyphs || !kept.insert(id) {
+            continue;
+        }
+        let outline = glyph(id as usize)?;
+        if outline.len() < 10 || (read_u16(outline, 0)? as i16) >= 0 {
+            continue;
+        }
+        let mut pos = 10;
+        loop {
+            let flags = read_u16(outline, pos)?;
+            pending.push(read_u16(outline, pos + 2)?);
+            pos += if flags & 0x0001 != 0 { 8 } else { 6 };
+            pos += match flags {
+                f if f & 0x0008 != 0 => 2,
+                f if f & 0x0040 != 0 => 4,
+                f if f & 0x0080 != 0 => 8,
+                _ => 0,
+            };
+            if flags & 0x0020 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((num_glyphs + 1) * 4);
+    for id in 0..num_glyphs {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        if kept.contains(&(id as u16)) {
+            new_glyf.extend_from_slice(glyph(id)?);
+            new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+    // Long offsets for the new loca; the whole-font checksum is not kept
+    let mut new_head = head.to_vec();
+    new_head.get_mut(8..12)?.fill(0);
+    new_head.get_mut(50..52)?.copy_from_slice(&1u16.to_be_bytes());
+
+    let out_tables: Vec<(&[u8; 4], &[u8])> = EMBEDDED_TABLES
+        .iter()
+        .filter_map(|&tag| match tag {
+            b"glyf" => Some((tag, new_glyf.as_slice())),
+            b"loca" => Some((tag, new_loca.as_slice())),
+            b"head" => Some((tag, new_head.as_slice())),
+            _ => tables.get(tag).map(|table| (tag, *table)),
+        })
+        .collect();
+    let count = out_tables.len() as u16;
+    let entry_selector = 15 - count.leading_zeros() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+    let mut font = 0x0001_0000u32.to_be_bytes().to_vec();
+    for value in [count, search_range, entry_selector, count * 16 - search_range] {
+        font.extend_from_slice(&value.to_be_bytes());
+    }
+    let mut offset = 12 + 16 * out_tables.len();
+    for (tag, table) in &out_tables {
+        font.extend_from_slice(*tag);
+        font.extend_from_slice(&table_checksum(table).to_be_bytes());
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        offset += table.len().next_multiple_of(4);
+    }
+    for (_, table) in &out_tables {
+        font.extend_from_slice(table);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    Some(font)
+}
+
+/// Regular and bold TrueType fonts to try, in order.
+fn system_font_candidates() -> Vec<(PathBuf, PathBuf)> {
+    let windows = std::env::var_os("WINDIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(r"C:\Windows"))
+        .join("Fonts");
+    let dejavu = |dir: &str| (Path::new(dir).join("DejaVuSans.ttf"), Path::new(dir).join("DejaVuSans-Bold.ttf"));
+    vec![
+        (windows.join("arial.ttf"), windows.join("arialbd.ttf")),
+        (windows.join("segoeui.ttf"), windows.join("segoeuib.ttf")),
+        (
+            PathBuf::from("/System/Library/Fonts/Supplemental/Arial.ttf"),
+            PathBuf::from("/System/Library/Fonts/Supplemental/Arial Bold.ttf"),
+        ),
+        dejavu("/usr/share/fonts/truetype/dejavu"),
+        dejavu("/usr/share/fonts/TTF"),
+        dejavu("/usr/share/fonts/dejavu-sans-fonts"),
+    ]
+}
+
+/// A TrueType font embedded as a Type 0 font with Identity-H encoding, so
+/// text is written as glyph ids.
+struct EmbeddedFont {
+    resource: &'static str,
+    data: Vec<u8>,
+    name: String,
+    units_per_em: f32,
+    ascent: i16,
+    descent: i16,
+    cap_height: i16,
+    italic_angle: f32,
+    bbox: [i16; 4],
+    /// Glyph id and advance width per character
+    glyphs: HashMap<char, (u16, u16)>,
+    missing_advance: u16,
+    /// Glyphs written so far, with their advance and the character they show
+    used: BTreeMap<u16, (u16, char)>,
+}
+
+impl EmbeddedFont {
+    /// `None` unless `data` is a TrueType font with glyph outlines (not CFF).
+    fn parse(data: Vec<u8>, resource: &'static str) -> Option<Self> {
+        let face = ttf_parser::Face::parse(&data, 0).ok()?;
+        face.tables().glyf?;
+        let mut glyphs = HashMap::new();
+        for subtable in face.tables().cmap?.subtables.into_iter().filter(|s| s.is_unicode()) {
+            subtable.codepoints(|code| {
+                let glyph = char::from_u32(code).zip(subtable.glyph_index(code));
+                if let Some((c, id)) = glyph {
+                    glyphs.entry(c).or_insert((id.0, face.glyph_hor_advance(id).unwrap_or(0)));
+                }
+            });
+        }
+        let name = face
+            .names()
+            .into_iter()
+            .filter(|n| n.name_id == ttf_parser::name_id::POST_SCRIPT_NAME)
+            .find_map(|n| n.to_string())
+            .unwrap_or_else(|| "Unicode".to_string())
+            .replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "");
+        let bbox = face.global_bounding_box();
+        Some(Self {
+            resource,
+            name,
+            units_per_em: face.units_per_em() as f32,
+            ascent: face.ascender(),
+            descent: face.descender(),
+            cap_height: face.capital_height().unwrap_or(face.ascender()),
+            italic_angle: face.italic_angle(),
+            bbox: [bbox.x_min, bbox.y_min, bbox.x_max, bbox.y_max],
+            missing_advance: face.glyph_hor_advance(ttf_parser::GlyphId(0)).unwrap_or(0),
+            glyphs,
+            used: BTreeMap::new(),
+            data,
+        })
+    }
+
+    /// Glyph id and advance for `c`; characters the font lacks get its
+    /// missing-glyph box.
+    fn glyph(&self, c: char) -> (u16, u16) {
+        let c = if c == '\t' { ' ' } else { c };
+        self.glyphs.get(&c).copied().unwrap_or((0, self.missing_advance))
+    }
+
+    /// `units` of the font in 1/1000 of the font size.
+    fn scaled(&self, units: f32) -> f32 {
+        units * 1000.0 / self.units_per_em
+    }
+
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let units: u32 = text.chars().map(|c| self.glyph(c).1 as u32).sum();
+        self.scaled(units as f32) * size / 1000.0
+    }
+
+    /// `text` as a hex string of glyph ids, remembering the glyphs for
+    /// embedding.
+    fn encode(&mut self, text: &str) -> String {
+        let mut out = String::from("<");
+        for c in text.chars() {
+            let (id, advance) = self.glyph(c);
+            self.used.entry(id).or_insert((advance, c));
+            out.push_str(&format!("{:04X}", id));
+        }
+        out.push('>');
+        out
+    }
+
+    /// The Type 0 font dictionary followed by its descendant CID font, font
+    /// descriptor, font file and ToUnicode map; `id` is the number of the
+    /// first of these five objects.
+    fn objects(&self, id: usize) -> Vec<Vec<u8>> {
+        // A subset is named with a tag of six capital letters (9.6.4), here
+        // derived from the glyphs it holds
+        let mut seed = self
+            .used
+            .keys()
+            .fold(self.resource.as_bytes()[1] as u32, |seed, glyph| seed.wrapping_mul(31).wrapping_add(*glyph as u32));
+        let tag: String = (0..6)
+            .map(|_| {
+                let letter = (b'A' + (seed % 26) as u8) as char;
+                seed /= 26;
+                letter
+            })
+            .collect();
+        let name = format!("{}+{}", tag, self.name);
+        let widths: String = self
+            .used
+            .iter()
+            .map(|(glyph, (advance, _))| format!("{} [{}] ", glyph, self.scaled(*advance as f32).round()))
+            .collect();
+        let font_file = subset_font(&self.data, self.used.keys().copied()).unwrap_or_else(|| self.data.clone());
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        let compressed = encoder.write_all(&font_file).and_then(|_| encoder.finish());
+
+        let mut objects = vec![
+            format!(
+                "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /Identity-H \
+                 /DescendantFonts [{} 0 R] /ToUnicode {} 0 R >>",
+                name,
+                id + 1,
+                id + 4
+            )
+            .into_bytes(),
+            format!(
+                "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /{} \
+                 /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> \
+                 /FontDescriptor {} 0 R /DW {} /W [{}] /CIDToGIDMap /Identity >>",
+                name,
+                id + 2,
+                self.scaled(self.missing_advance as f32).round(),
+                widths.trim_end()
+            )
+            .into_bytes(),
+            format!(
+                "<< /Type /FontDescriptor /FontName /{} /Flags 32 /FontBBox [{}] /ItalicAngle {} \
+                 /Ascent {} /Descent {} /CapHeight {} /StemV 80 /FontFile2 {} 0 R >>",
+                name,
+                self.bbox.map(|v| self.scaled(v as f32).round().to_string()).join(" "),
+                self.italic_angle,
+                self.scaled(self.ascent as f32).round(),
+                self.scaled(self.descent as f32).round(),
+                self.scaled(self.cap_height as f32).round(),
+                id + 3
+            )
+            .into_bytes(),
+        ];
+        let mut file = match compressed {
+            Ok(compressed) => {
+                let mut file = format!(
+                    "<< /Length {} /Length1 {} /Filter /FlateDecode >>\nstream\n",
+                    compressed.len(),
+                    font_file.len()
+                )
+                .into_bytes();
+                file.extend_from_slice(&compressed);
+                file
+            }
+            Err(_) => {
+                let mut file =
+                    format!("<< /Length {} /Length1 {} >>\nstream\n", font_file.len(), font_file.len()).into_bytes();
+                file.extend_from_slice(&font_file);
+                file
+            }
+        };
+        file.extend_from_slice(b"\nendstream");
+        objects.push(file);
+
+        // Maps glyph ids back to text, so the PDF can be searched and copied from
+        let mut cmap = String::from(
+            "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+             /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+             /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+             1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+        );
+        let entries: Vec<_> = self.used.iter().collect();
+        for chunk in entries.chunks(100) {
+            cmap.push_str(&format!("{} beginbfchar\n", chunk.len()));
+            for (glyph, (_, c)) in chunk {
+                let utf16: String = c.encode_utf16(&mut [0; 2]).iter().map(|u| format!("{:04X}", u)).collect();
+                cmap.push_str(&format!("<{:04X}> <{}>\n", glyph, utf16));
+            }
+            cmap.push_str("endbfchar\n");
+        }
+        cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend");
+        objects.push(format!("<< /Length {} >>\nstream\n{}\nendstream", cmap.len(), cmap).into_bytes());
+        objects
+    }
+}
+
+/// Fonts for lines the standard fonts can't show.
+struct UnicodeFonts {
+    regular: EmbeddedFont,
+    bold: EmbeddedFont,
+}
+
+impl UnicodeFonts {
+    /// The first system font pair that can be read. A missing bold file is
+    /// replaced by the regular one.
+    fn load() -> Option<Self> {
+        system_font_candidates().into_iter().find_map(|(regular, bold)| {
+            let regular = std::fs::read(regular).ok()?;
+            let bold = std::fs::read(bold).unwrap_or_else(|_| regular.clone());
+            Some(Self { regular: EmbeddedFont::parse(regular, "F3")?, bold: EmbeddedFont::parse(bold, "F4")? })
+        })
+    }
+
+    fn get(&self, font: Font) -> &EmbeddedFont {
+        match font {
+            Font::Regular => &self.regular,
+            Font::Bold => &self.bold,
+        }
+    }
+
+    fn get_mut(&mut self, font: Font) -> &mut EmbeddedFont {
+        match font {
+            Font::Regular => &mut self.regular,
+            Font::Bold => &mut self.bold,
+        }
+    }
+}
+
+struct EmbeddedImage {
+    width: u32,
+    height: u32,
+    jpeg: Vec<u8>,
+}
+
+#[derive(Default)]
+struct Page {
+    content: String,
+    /// Indices into `PdfReport::images`
+    images: Vec<usize>,
+}
+
+/// A document being laid out from the top of the first page down.
+pub struct PdfReport {
+    title: String,
+    pages: Vec<Page>,
+    images: Vec<EmbeddedImage>,
+    /// Loaded the first time a line needs them; `None` inside if the system
+    /// has no usable font
+    unicode_fonts: OnceCell<Option<UnicodeFonts>>,
+    /// Distance of the layout cursor from the top of the current page
+    y: f32,
+}
+
+impl PdfReport {
+    /// An empty document whose metadata title is `title`.
+    pub fn new(title: &str) -> Self {
+        let mut report = Self {
+            title: title.to_string(),
+            pages: Vec::new(),
+            images: Vec::new(),
+            unicode_fonts: OnceCell::new(),
+            y: 0.0,
+        };
+        report.page_break();
+        report
+    }
+
+    pub fn page_break(&mut self) {
+        self.pages.push(Page::default());
+        self.y = MARGIN;
+    }
+
+    /// Start a new page unless `height` more points fit on this one. A fresh
+    /// page always takes the content, however tall.
+    fn ensure_room(&mut self, height: f32) {
+        if self.y + height > PAGE_HEIGHT - MARGIN && self.y > MARGIN {
+            self.page_break();
+        }
+    }
+
+    fn page(&mut self) -> &mut Page {
+        self.pages.last_mut().expect("a report always has a page")
+    }
+
+    /// The embedded font `text` is set in, if it needs one and one exists.
+    fn unicode_font(&self, text: &str, font: Font) -> Option<&EmbeddedFont> {
+        if !needs_unicode(text) {
+            return None;
+        }
+        self.unicode_fonts.get_or_init(UnicodeFonts::load).as_ref().map(|fonts| fonts.get(font))
+    }
+
+    /// Width of `text` in points, in the font it will be set in.
+    fn text_width(&self, text: &str, font: Font, size: f32) -> f32 {
+        match self.unicode_font(text, font) {
+            Some(embedded) => embedded.width(text, size),
+            None => helvetica_width(text, font, size),
+        }
+    }
+
+    fn line(&mut self, text: &str, font: Font, size: f32) {
+        self.ensure_room(size * LINE_SPACING);
+        let baseline = PAGE_HEIGHT - self.y - size;
+        if !text.is_empty() {
+            let embedded = match self.unicode_font(text, font) {
+                Some(_) => self.unicode_fonts.get_mut().and_then(Option::as_mut).map(|fonts| fonts.get_mut(font)),
+                None => None,
+            };
+            let (resource, shown) = match embedded {
+                Some(embedded) => (embedded.resource, embedded.encode(text)),
+                None => (font.resource(), format!("({})", pdf_string(text))),
+            };
+            let op = format!(
+                "BT /{} {} Tf {:.2} {:.2} Td {} Tj ET\n",
+                resource, size, MARGIN, baseline, shown
+            );
+            self.page().content.push_str(&op);
+        }
+        self.y += size * LINE_SPACING;
+    }
+
+    /// Add `text`, keeping its line breaks and wrapping long lines.
+    pub fn text(&mut self, text: &str, font: Font, size: f32) {
+        for line in text.trim_end().lines() {
+            let pieces = wrap(line.trim_end(), CONTENT_WIDTH, &|piece: &str| self.text_width(piece, font, size));
+            for piece in pieces {
+                self.line(&piece, font, size);
+            }
+        }
+    }
+
+    /// Vertical gap of `height` points.
+    pub fn space(&mut self, height: f32) {
+        self.y += height;
+    }
+
+    /// Add the image at `path` scaled to the page width, on the next page if
+    /// it doesn't fit on this one.
+    pub fn image(&mut self, path: &Path) -> Result<(), String> {
+        let mut image = image::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if image.width().max(image.height()) > MAX_IMAGE_PIXELS {
+            image = image.resize(MAX_IMAGE_PIXELS, MAX_IMAGE_PIXELS, FilterType::Triangle);
+        }
+        let rgb = image.to_rgb8();
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+            .encode_image(&rgb)
+            .map_err(|e| format!("Failed to encode {}: {}", path.display(), e))?;
+
+        // 96 dpi screens: a pixel is 0.75 pt at most
+        let (width_px, height_px) = rgb.dimensions();
+        let scale = (CONTENT_WIDTH / width_px as f32)
+            .min(MAX_IMAGE_HEIGHT / height_px as f32)
+            .min(0.75);
+        let (width, height) = (width_px as f32 * scale, height_px as f32 * scale);
+
+        self.ensure_room(height);
+        let index = self.images.len();
+        self.images.push(EmbeddedImage { width: width_px, height: height_px, jpeg });
+        let bottom = PAGE_HEIGHT - self.y - height;
+        let page = self.page();
+        page.images.push(index);
+        page.content.push_str(&format!(
+            "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im{} Do Q\n",
+            width, height, MARGIN, bottom, index
+        ));
+        self.y += height + 4.0;
+        Ok(())
+    }
+
+    /// The finished PDF file, with "Page n of m" at the foot of each page.
+    pub fn finish(mut self) -> Vec<u8> {
+        let page_count = self.pages.len();
+        for (i, page) in self.pages.iter_mut().enumerate() {
+            let footer = format!("Page {} of {}", i + 1, page_count);
+            let x = PAGE_WIDTH - MARGIN - helvetica_width(&footer, Font::Regular, 8.0);
+            page.content
+                .push_str(&format!("BT /F1 8 Tf {:.2} {:.2} Td ({}) Tj ET\n", x, MARGIN / 2.0, footer));
+        }
+
+        // Objects in number order: catalog, page tree, info, two fonts,
+        // images, each page followed by its content stream, then the
+        // embedded fonts that were used
+        let first_image = 6;
+        let first_page = first_image + self.images.len();
+        let page_ids: Vec<usize> = (0..page_count).map(|i| first_page + 2 * i).collect();
+        let embedded: Vec<&EmbeddedFont> = match self.unicode_fonts.get() {
+            Some(Some(fonts)) => [&fonts.regular, &fonts.bold].into_iter().filter(|f| !f.used.is_empty()).collect(),
+            _ => Vec::new(),
+        };
+        let first_font = first_page + 2 * page_count;
+        let font_ids: Vec<usize> = (0..embedded.len()).map(|i| first_font + 5 * i).collect();
+        let embedded_resources: String = embedded
+            .iter()
+            .zip(&font_ids)
+            .map(|(font, id)| format!(" /{} {} 0 R", font.resource, id))
+            .collect();
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<_>>().join(" "),
+                page_count
+            )
+            .into_bytes(),
+            format!("<< /Title ({}) /Producer (Unbroken QA Capture) >>", pdf_string(&self.title)).into_bytes(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec(),
+        ];
+        for image in &self.images {
+            let mut object = format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB \
+                 /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+                image.width,
+                image.height,
+                image.jpeg.len()
+            )
+            .into_bytes();
+            object.extend_from_slice(&image.jpeg);
+            object.extend_from_slice(b"\nendstream");
+            objects.push(object);
+        }
+        for (page, id) in self.pages.iter().zip(&page_ids) {
+            let images: String = page
+                .images
+                .iter()
+                .map(|i| format!(" /Im{} {} 0 R", i, first_image + i))
+                .collect();
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents {} 0 R \
+                     /Resources << /Font << /F1 4 0 R /F2 5 0 R{} >> /XObject <<{} >> >> >>",
+                    PAGE_WIDTH,
+                    PAGE_HEIGHT,
+                    id + 1,
+                    embedded_resources,
+                    images
+                )
+                .into_bytes(),
+            );
+            objects.push(
+                format!("<< /Length {} >>\nstream\n{}\nendstream", page.content.len(), page.content).into_bytes(),
+            );
+        }
+
+        for (font, id) in embedded.iter().zip(&font_ids) {
+            objects.extend(font.objects(*id));
+        }
+
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref
+            )
+            .as_bytes(),
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_and_encode_text() {
+        let regular = |text: &str| helvetica_width(text, Font::Regular, 10.0);
+        let lines = wrap("the quick brown fox jumps over the lazy dog", 80.0, &regular);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| regular(l) <= 80.0));
+        assert_eq!(lines.join(" "), "the quick brown fox jumps over the lazy dog");
+        // A word wider than the line is broken inside
+        assert!(wrap(&"W".repeat(40), 80.0, &|text| helvetica_width(text, Font::Bold, 10.0)).len() > 1);
+
+        assert_eq!(pdf_string("a (b) \\ café – ✓"), "a \\(b\\) \\\\ caf\\351 \\226 ?");
+    }
+
+    #[test]
+    fn test_report_pages_images_and_xref() {
+        let dir = tempfile::tempdir().unwrap();
+        let shot = dir.path().join("shot.png");
+        image::RgbaImage::from_pixel(900, 1200, image::Rgba([200, 20, 20, 255])).save(&shot).unwrap();
+
+        let mut report = PdfReport::new("QA Session Report");
+        report.text("QA Session Report", Font::Bold, 20.0);
+        report.text(&"Steps to reproduce the problem. ".repeat(200), Font::Regular, 10.0);
+        report.page_break();
+        report.image(&shot).unwrap();
+        report.image(&shot).unwrap();
+        assert!(report.image(&dir.path().join("missing.png")).is_err());
+        let pdf = report.finish();
+
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.ends_with("%%EOF\n"));
+        // Long text spills onto a second page; two tall screenshots take a page each
+        assert!(text.contains("/Count 4"));
+        assert!(text.contains("(Page 4 of 4)"));
+        assert_eq!(text.matches("/Subtype /Image").count(), 2);
+
+        // Every xref entry points at its object
+        let xref = text.rfind("xref\n").unwrap();
+        let entries: Vec<usize> = text[xref..]
+            .lines()
+            .skip(3)
+            .take_while(|l| l.ends_with(" n "))
+            .map(|l| l[..10].parse().unwrap())
+            .collect();
+        for (i, offset) in entries.iter().enumerate() {
+            assert!(pdf[*offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_non_latin_text_uses_an_embedded_font() {
+        let Some(fonts) = UnicodeFonts::load() else {
+            eprintln!("no system TrueType font, skipping");
+            return;
+        };
+        let a = fonts.regular.glyph('a').0;
+        let subset = subset_font(&fonts.regular.data, [a]).unwrap();
+        assert!(subset.len() < fonts.regular.data.len());
+        let face = ttf_parser::Face::parse(&subset, 0).unwrap();
+        assert!(face.glyph_bounding_box(ttf_parser::GlyphId(a)).is_some());
+        assert!(face.glyph_bounding_box(ttf_parser::GlyphId(fonts.regular.glyph('b').0)).is_none());
+
+        let mut report = PdfReport::new("Report");
+        report.text("Ошибка входа – ✓", Font::Bold, 12.0);
+        report.text("Plain Latin text", Font::Regular, 10.0);
+        let pdf = report.finish();
+        let text = String::from_utf8_lossy(&pdf);
+
+        // Only the line Helvetica can't show is set in the embedded font
+        assert!(text.contains("(Plain Latin text)"));
+        assert!(!text.contains("/F3 "));
+        assert_eq!(text.matches("/Subtype /Type0").count(), 1);
+        assert!(text.contains("/Encoding /Identity-H"));
+        assert!(text.contains("/FontFile2"));
+        let cyrillic = fonts.bold.glyph('О').0;
+        assert!(text.contains(&format!("<{:04X}> <041E>", cyrillic)));
+    }
+}
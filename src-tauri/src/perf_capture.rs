@@ -0,0 +1,473 @@
+//! Performance sampling of the app under test while a bug is capturing.
+//!
+//! When enabled in the `capture.perf` setting, starting (or resuming) a bug
+//! capture starts a [`PerfSampler`] that reads the named process's CPU and
+//! memory use once a second. When the capture ends the samples are written
+//! into the bug folder as `perf.csv`, with `perf.png` showing each series as a
+//! sparkline. GPU load is recorded where the OS exposes it (the device-wide
+//! `gpu_busy_percent` on Linux); frame rates need a hook into the app's
+//! renderer and are not sampled.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use image::{Rgb, RgbImage};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Settings key holding [`PerfCaptureSettings`] as JSON.
+pub const PERF_CAPTURE_KEY: &str = "capture.perf";
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+const STOP_POLL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PerfCaptureSettings {
+    pub enabled: bool,
+    /// Executable name of the app under test, e.g. `Shop.exe`
+    pub process: String,
+}
+
+impl PerfCaptureSettings {
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(PERF_CAPTURE_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(PERF_CAPTURE_KEY, &json)
+            .map_err(|e| format!("Failed to save performance capture settings: {}", e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enabled && self.process.trim().is_empty() {
+            return Err("A process name is required for performance capture".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// One reading; `None` where the value could not be read (e.g. the process
+/// was not running).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerfSample {
+    pub timestamp: String,
+    pub elapsed_secs: f64,
+    pub cpu_percent: Option<f64>,
+    pub ram_mb: Option<f64>,
+    pub gpu_percent: Option<f64>,
+}
+
+/// Whether an executable name (`Shop.exe`, or `shop` as Linux reports it)
+/// is `process`. Linux truncates process names to 15 characters.
+fn matches_process(exe: &str, process: &str) -> bool {
+    let normalize = |name: &str| {
+        let name = name.trim().to_lowercase();
+        name.strip_suffix(".exe").map(str::to_string).unwrap_or(name)
+    };
+    let (exe, process) = (normalize(exe), normalize(process));
+    exe == process || (exe.len() == 15 && process.starts_with(&exe))
+}
+
+/// Cumulative CPU time and resident memory of a process.
+struct Usage {
+    cpu_time: Duration,
+    rss_bytes: u64,
+}
+
+/// CPU use between two readings as a share of all cores.
+fn cpu_percent(previous: Duration, current: Duration, wall: Duration, cores: usize) -> Option<f64> {
+    if wall.is_zero() || current < previous {
+        return None;
+    }
+    let percent = (current - previous).as_secs_f64() / wall.as_secs_f64() / cores.max(1) as f64 * 100.0;
+    Some(percent.min(100.0))
+}
+
+#[cfg(target_os = "linux")]
+mod probe {
+    use super::Usage;
+    use std::time::Duration;
+
+    /// USER_HZ; fixed at 100 on every mainstream Linux configuration
+    const CLOCK_TICKS: u64 = 100;
+
+    pub fn find_process(process: &str) -> Option<u32> {
+        std::fs::read_dir("/proc").ok()?.flatten().find_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            let comm = std::fs::read_to_string(entry.path().join("comm")).ok()?;
+            super::matches_process(&comm, process).then_some(pid)
+        })
+    }
+
+    pub fn read_usage(pid: u32) -> Option<Usage> {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // Fields after the parenthesised name; utime and stime are the 14th and 15th
+        let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+        let ticks: u64 = fields.get(11)?.parse::<u64>().ok()? + fields.get(12)?.parse::<u64>().ok()?;
+
+        let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+        let rss_kb: u64 = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))?
+            .trim()
+            .trim_end_matches("kB")
+            .trim()
+            .parse()
+            .ok()?;
+
+        Some(Usage {
+            cpu_time: Duration::from_millis(ticks * 1000 / CLOCK_TICKS),
+            rss_bytes: rss_kb * 1024,
+        })
+    }
+
+    /// Device-wide GPU load of the first card reporting it (amdgpu, i915 on newer kernels).
+    pub fn read_gpu_percent() -> Option<f64> {
+        std::fs::read_dir("/sys/class/drm").ok()?.flatten().find_map(|card| {
+            std::fs::read_to_string(card.path().join("device/gpu_busy_percent"))
+                .ok()?
+                .trim()
+                .parse()
+                .ok()
+        })
+    }
+}
+
+#[cfg(windows)]
+mod probe {
+    use super::Usage;
+    use std::time::Duration;
+    use windows::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+    };
+    use windows::Win32::System::ProcessStatus::{K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows::Win32::System::Threading::{GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    pub fn find_process(process: &str) -> Option<u32> {
+        unsafe {
+            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0).ok()?;
+            let mut entry = PROCESSENTRY32W {
+                dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+                ..Default::default()
+            };
+            let mut found = None;
+            let mut more = Process32FirstW(snapshot, &mut entry).is_ok();
+            while more {
+                let len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+                if super::matches_process(&String::from_utf16_lossy(&entry.szExeFile[..len]), process) {
+                    found = Some(entry.th32ProcessID);
+                    break;
+                }
+                more = Process32NextW(snapshot, &mut entry).is_ok();
+            }
+            let _ = CloseHandle(snapshot);
+            found
+        }
+    }
+
+    pub fn read_usage(pid: u32) -> Option<Usage> {
+        let as_duration = |time: FILETIME| {
+            // 100 ns units
+            Duration::from_nanos((((time.dwHighDateTime as u64) << 32) | time.dwLowDateTime as u64) * 100)
+        };
+        unsafe {
+            let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let (mut created, mut exited, mut kernel, mut user) =
+                (FILETIME::default(), FILETIME::default(), FILETIME::default(), FILETIME::default());
+            let times = GetProcessTimes(handle, &mut created, &mut exited, &mut kernel, &mut user);
+            let mut counters = PROCESS_MEMORY_COUNTERS::default();
+            let _ = K32GetProcessMemoryInfo(
+                handle,
+                &mut counters,
+                std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+            );
+            let _ = CloseHandle(handle);
+            times.ok()?;
+            Some(Usage {
+                cpu_time: as_duration(kernel) + as_duration(user),
+                rss_bytes: counters.WorkingSetSize as u64,
+            })
+        }
+    }
+
+    pub fn read_gpu_percent() -> Option<f64> {
+        None
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod probe {
+    use super::Usage;
+
+    pub fn find_process(_process: &str) -> Option<u32> {
+        None
+    }
+
+    pub fn read_usage(_pid: u32) -> Option<Usage> {
+        None
+    }
+
+    pub fn read_gpu_percent() -> Option<f64> {
+        None
+    }
+}
+
+/// `perf.csv`: one row per sample, empty cells for missing values.
+pub fn to_csv(samples: &[PerfSample]) -> String {
+    let cell = |value: Option<f64>| value.map(|v| format!("{:.1}", v)).unwrap_or_default();
+    let mut csv = String::from("timestamp,elapsed_s,cpu_percent,ram_mb,gpu_percent\n");
+    for sample in samples {
+        csv.push_str(&format!(
+            "{},{:.1},{},{},{}\n",
+            sample.timestamp,
+            sample.elapsed_secs,
+            cell(sample.cpu_percent),
+            cell(sample.ram_mb),
+            cell(sample.gpu_percent),
+        ));
+    }
+    csv
+}
+
+const SPARKLINE_WIDTH: u32 = 480;
+const SPARKLINE_ROW_HEIGHT: u32 = 40;
+
+fn draw_line(image: &mut RgbImage, from: (i64, i64), to: (i64, i64), color: Rgb<u8>) {
+    let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).max(1);
+    for step in 0..=steps {
+        let x = from.0 + (to.0 - from.0) * step / steps;
+        let y = from.1 + (to.1 - from.1) * step / steps;
+        if x >= 0 && y >= 0 && (x as u32) < image.width() && (y as u32) < image.height() {
+            image.put_pixel(x as u32, y as u32, color);
+        }
+    }
+}
+
+/// `perf.png`: CPU, RAM and (when sampled) GPU as stacked sparklines, each
+/// scaled to its own range. Gaps where a value is missing break the line.
+pub fn render_sparkline(samples: &[PerfSample]) -> RgbImage {
+    let series: Vec<(Vec<Option<f64>>, Rgb<u8>)> = [
+        (samples.iter().map(|s| s.cpu_percent).collect::<Vec<_>>(), Rgb([37, 99, 235])),
+        (samples.iter().map(|s| s.ram_mb).collect(), Rgb([234, 88, 12])),
+        (samples.iter().map(|s| s.gpu_percent).collect(), Rgb([22, 163, 74])),
+    ]
+    .into_iter()
+    .filter(|(values, _)| values.iter().any(Option::is_some))
+    .collect();
+
+    let rows = series.len().max(1) as u32;
+    let mut image = RgbImage::from_pixel(SPARKLINE_WIDTH, rows * SPARKLINE_ROW_HEIGHT, Rgb([255, 255, 255]));
+    let step = (SPARKLINE_WIDTH - 1) as f64 / samples.len().saturating_sub(1).max(1) as f64;
+
+    for (row, (values, color)) in series.iter().enumerate() {
+        let present = values.iter().flatten();
+        let min = present.clone().cloned().fold(f64::INFINITY, f64::min);
+        let max = present.cloned().fold(f64::NEG_INFINITY, f64::max);
+        let range = if max > min { max - min } else { 1.0 };
+        let top = row as u32 * SPARKLINE_ROW_HEIGHT + 2;
+        let height = (SPARKLINE_ROW_HEIGHT - 4) as f64;
+
+        let mut previous: Option<(i64, i64)> = None;
+        for (i, value) in values.iter().enumerate() {
+            let point = value.map(|v| {
+                let x = (i as f64 * step).round() as i64;
+                let y = top as i64 + (height - (v - min) / range * height).round() as i64;
+                (x, y)
+            });
+            if let Some(point) = point {
+                draw_line(&mut image, previous.unwrap_or(point), point, *color);
+            }
+            previous = point;
+        }
+    }
+    image
+}
+
+/// `perf.csv` and `perf.png` in `folder`, or `perf-2.csv` etc. when an earlier
+/// capture of the bug (before a resume) already wrote a report.
+fn report_paths(folder: &Path) -> (PathBuf, PathBuf) {
+    let stem = |n: u32| if n == 1 { "perf".to_string() } else { format!("perf-{}", n) };
+    let n = (1..)
+        .find(|&n| !folder.join(format!("{}.csv", stem(n))).exists())
+        .unwrap_or(1);
+    (folder.join(format!("{}.csv", stem(n))), folder.join(format!("{}.png", stem(n))))
+}
+
+/// Write the CSV and sparkline into `folder`.
+pub fn write_report(folder: &Path, samples: &[PerfSample]) -> Result<(), String> {
+    let (csv_path, png_path) = report_paths(folder);
+    std::fs::write(&csv_path, to_csv(samples)).map_err(|e| format!("Failed to write {:?}: {}", csv_path, e))?;
+    render_sparkline(samples)
+        .save_with_format(&png_path, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to write {:?}: {}", png_path, e))
+}
+
+/// Samples the process on a background thread.
+///
+/// Dropping the struct stops sampling and writes the report into the bug
+/// folder (nothing is written when no sample was taken).
+pub struct PerfSampler {
+    stop_flag: Arc<AtomicBool>,
+    /// Where the report goes; changes when the bug folder moves
+    bug_folder: Arc<Mutex<PathBuf>>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl PerfSampler {
+    pub fn start(process: String, bug_folder: PathBuf) -> Self {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop_flag);
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        let bug_folder = Arc::new(Mutex::new(bug_folder));
+        let folder = Arc::clone(&bug_folder);
+
+        let worker = thread::spawn(move || {
+            let started = Instant::now();
+            let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+            let mut pid = None;
+            let mut previous: Option<(Duration, Instant)> = None;
+
+            while !flag.load(Ordering::Relaxed) {
+                // Look the process up again whenever it is gone (e.g. restarted)
+                let usage = pid
+                    .and_then(probe::read_usage)
+                    .or_else(|| {
+                        pid = probe::find_process(&process);
+                        previous = None;
+                        pid.and_then(probe::read_usage)
+                    });
+                let now = Instant::now();
+                let cpu = usage.as_ref().and_then(|usage| {
+                    let (cpu_time, at) = previous.replace((usage.cpu_time, now))?;
+                    cpu_percent(cpu_time, usage.cpu_time, now - at, cores)
+                });
+                samples.lock().unwrap().push(PerfSample {
+                    timestamp: Utc::now().to_rfc3339(),
+                    elapsed_secs: started.elapsed().as_secs_f64(),
+                    cpu_percent: cpu,
+                    ram_mb: usage.map(|usage| usage.rss_bytes as f64 / (1024.0 * 1024.0)),
+                    gpu_percent: probe::read_gpu_percent(),
+                });
+
+                let mut waited = Duration::ZERO;
+                while waited < SAMPLE_INTERVAL && !flag.load(Ordering::Relaxed) {
+                    thread::sleep(STOP_POLL);
+                    waited += STOP_POLL;
+                }
+            }
+
+            let samples = samples.lock().unwrap();
+            if !samples.is_empty() {
+                let folder = folder.lock().unwrap().clone();
+                if let Err(e) = write_report(&folder, &samples) {
+                    eprintln!("Warning: {}", e);
+                }
+            }
+        });
+
+        Self { stop_flag, bug_folder, worker: Some(worker) }
+    }
+
+    /// Write the report into `bug_folder` instead, e.g. after a scratch bug
+    /// moved from `_scratch/` to its `bug_NNN` folder.
+    pub fn retarget(&self, bug_folder: PathBuf) {
+        *self.bug_folder.lock().unwrap() = bug_folder;
+    }
+}
+
+impl Drop for PerfSampler {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(elapsed_secs: f64, cpu_percent: Option<f64>, ram_mb: Option<f64>) -> PerfSample {
+        PerfSample {
+            timestamp: "2024-01-01T10:00:00+00:00".to_string(),
+            elapsed_secs,
+            cpu_percent,
+            ram_mb,
+            gpu_percent: None,
+        }
+    }
+
+    #[test]
+    fn test_matches_process() {
+        assert!(matches_process("Shop.exe", "shop.exe"));
+        assert!(matches_process("shop", "Shop.exe"));
+        // Linux truncates comm to 15 characters
+        assert!(matches_process("VeryLongAppName", "VeryLongAppNameEditor.exe"));
+        assert!(!matches_process("ShopHelper.exe", "Shop.exe"));
+    }
+
+    #[test]
+    fn test_cpu_percent_is_share_of_all_cores() {
+        let wall = Duration::from_secs(1);
+        assert_eq!(cpu_percent(Duration::ZERO, Duration::from_millis(500), wall, 1), Some(50.0));
+        assert_eq!(cpu_percent(Duration::ZERO, Duration::from_millis(2000), wall, 4), Some(50.0));
+        assert_eq!(cpu_percent(Duration::from_secs(2), Duration::from_secs(1), wall, 1), None);
+    }
+
+    #[test]
+    fn test_csv_leaves_missing_values_empty() {
+        let csv = to_csv(&[sample(0.0, None, Some(120.0)), sample(1.0, Some(12.34), Some(121.0))]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "timestamp,elapsed_s,cpu_percent,ram_mb,gpu_percent");
+        assert_eq!(lines[1], "2024-01-01T10:00:00+00:00,0.0,,120.0,");
+        assert_eq!(lines[2], "2024-01-01T10:00:00+00:00,1.0,12.3,121.0,");
+    }
+
+    #[test]
+    fn test_sparkline_has_a_row_per_sampled_series() {
+        let samples = vec![sample(0.0, Some(10.0), Some(100.0)), sample(1.0, Some(80.0), Some(140.0))];
+        let image = render_sparkline(&samples);
+        assert_eq!(image.dimensions(), (SPARKLINE_WIDTH, 2 * SPARKLINE_ROW_HEIGHT));
+        assert!(image.pixels().any(|p| *p == Rgb([37, 99, 235])));
+
+        // A resumed capture writes a second report next to the first
+        let dir = tempfile::tempdir().unwrap();
+        write_report(dir.path(), &samples).unwrap();
+        write_report(dir.path(), &samples).unwrap();
+        for name in ["perf.csv", "perf.png", "perf-2.csv", "perf-2.png"] {
+            assert!(dir.path().join(name).exists(), "{} missing", name);
+        }
+    }
+
+    #[test]
+    fn test_retargeted_sampler_writes_into_the_new_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let (scratch, promoted) = (dir.path().join("_scratch"), dir.path().join("bug_001"));
+        std::fs::create_dir(&promoted).unwrap();
+
+        let sampler = PerfSampler::start("no-such-process.exe".to_string(), scratch.clone());
+        // Let the worker take its first sample before stopping it
+        thread::sleep(Duration::from_millis(300));
+        sampler.retarget(promoted.clone());
+        drop(sampler);
+
+        assert!(promoted.join("perf.csv").exists());
+        assert!(!scratch.exists());
+    }
+}
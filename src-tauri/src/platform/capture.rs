@@ -3,6 +3,8 @@
 //! The `CaptureBridge` trait defines the interface for platform-specific
 //! screenshot capture operations.
 
+use std::path::Path;
+
 use super::error::Result;
 
 /// Platform abstraction trait for triggering screenshot capture.
@@ -13,6 +15,7 @@ use super::error::Result;
 /// # Platform Implementations
 ///
 /// - **Windows**: Multiple trigger methods (URI, process launch, key simulation).
+/// - **Linux**: gnome-screenshot, grim + slurp or spectacle, writing into the session
 /// - **macOS**: Uses `screencapture` CLI with output path arguments (v2)
 ///
 /// # Thread Safety
@@ -28,6 +31,9 @@ pub trait CaptureBridge: Send + Sync {
     ///   2. Spawn `SnippingTool.exe` process
     ///   3. Simulate `Win+Shift+S` key combination via Windows API
     ///
+    /// - **Linux**: Runs the first installed of `gnome-screenshot`, `grim` (with
+    ///   `slurp`) and `spectacle` in region mode
+    ///
     /// - **macOS**: Launches `screencapture -i` for interactive screenshot (v2)
     ///
     /// # Returns
@@ -40,4 +46,16 @@ pub trait CaptureBridge: Send + Sync {
     /// - `PlatformError::ScreenshotTriggerError`: All trigger methods failed
     /// - `PlatformError::NotImplemented`: Platform does not support this operation (macOS v1)
     fn trigger_screenshot(&self) -> Result<()>;
+
+    /// Tells the bridge the active session's _captures/ folder (`None` when the
+    /// session ends).
+    ///
+    /// # Platform Behavior
+    ///
+    /// - **Windows / macOS**: Nothing to do; the user saves into _captures/ directly
+    /// - **Linux**: Triggered screenshots are written there, and screenshots saved
+    ///   to `~/Pictures/Screenshots` are moved there while a session is active
+    fn set_capture_folder(&self, _folder: Option<&Path>) -> Result<()> {
+        Ok(())
+    }
 }
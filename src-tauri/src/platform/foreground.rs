@@ -0,0 +1,171 @@
+//! Reading which application is in the foreground.
+//!
+//! `foreground_window` reports the title, process and executable version of
+//! the window the user is working in, so captures can record where a bug
+//! occurred. Windows of this app are skipped: when the tester is looking at
+//! QA Capture, the app under test is not known.
+
+use serde::{Deserialize, Serialize};
+
+/// The foreground window and the program it belongs to.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForegroundWindow {
+    pub title: String,
+    /// Executable file name, e.g. `Shop.exe`
+    pub process_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executable_path: Option<String>,
+    /// File version of the executable, e.g. `2.3.1.0` (Windows only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl ForegroundWindow {
+    /// e.g. `Shop.exe 2.3.1.0 — "Checkout"`.
+    pub fn describe(&self) -> String {
+        let mut text = self.process_name.clone();
+        if let Some(version) = &self.version {
+            text = format!("{} {}", text, version);
+        }
+        if !self.title.is_empty() {
+            text = format!("{} — \"{}\"", text, self.title);
+        }
+        text
+    }
+}
+
+/// The window in the foreground now, unless it belongs to this app.
+///
+/// # Platform Behavior
+///
+/// - **Windows**: `GetForegroundWindow`, the process image path and its
+///   `VS_FIXEDFILEINFO` file version
+/// - **Linux**: `xdotool` for the active window (X11), `/proc` for the process
+/// - **macOS**: Not implemented (returns `None`)
+#[cfg(target_os = "windows")]
+pub fn foreground_window() -> Option<ForegroundWindow> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0.is_null() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == 0 || pid == std::process::id() {
+            return None;
+        }
+
+        let mut title = [0u16; 512];
+        let len = GetWindowTextW(hwnd, &mut title).max(0) as usize;
+        let title = String::from_utf16_lossy(&title[..len]);
+
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let queried = QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut len).is_ok();
+        let _ = CloseHandle(handle);
+        let executable_path = queried.then(|| String::from_utf16_lossy(&buffer[..len as usize]));
+
+        let process_name = executable_path
+            .as_deref()
+            .and_then(|path| std::path::Path::new(path).file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| format!("pid {}", pid));
+        let version = executable_path.as_deref().and_then(file_version);
+        Some(ForegroundWindow { title, process_name, executable_path, version })
+    }
+}
+
+/// `major.minor.build.revision` from the executable's version resource.
+#[cfg(target_os = "windows")]
+fn file_version(path: &str) -> Option<String> {
+    use windows::core::{HSTRING, PCWSTR};
+    use windows::Win32::Storage::FileSystem::{
+        GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW, VS_FIXEDFILEINFO,
+    };
+
+    unsafe {
+        let path = HSTRING::from(path);
+        let size = GetFileVersionInfoSizeW(&path, None);
+        if size == 0 {
+            return None;
+        }
+        let mut data = vec![0u8; size as usize];
+        GetFileVersionInfoW(&path, 0, size, data.as_mut_ptr().cast()).ok()?;
+
+        let mut info: *mut std::ffi::c_void = std::ptr::null_mut();
+        let mut len = 0u32;
+        let root = HSTRING::from("\\");
+        if !VerQueryValueW(data.as_ptr().cast(), PCWSTR(root.as_ptr()), &mut info, &mut len).as_bool()
+            || info.is_null()
+        {
+            return None;
+        }
+        let info = &*(info as *const VS_FIXEDFILEINFO);
+        Some(format!(
+            "{}.{}.{}.{}",
+            info.dwFileVersionMS >> 16,
+            info.dwFileVersionMS & 0xFFFF,
+            info.dwFileVersionLS >> 16,
+            info.dwFileVersionLS & 0xFFFF
+        ))
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn foreground_window() -> Option<ForegroundWindow> {
+    use std::process::Command;
+
+    let xdotool = |args: &[&str]| {
+        Command::new("xdotool")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let title = xdotool(&["getactivewindow", "getwindowname"])?;
+    let pid: u32 = xdotool(&["getactivewindow", "getwindowpid"])?.parse().ok()?;
+    if pid == std::process::id() {
+        return None;
+    }
+
+    let executable_path = std::fs::read_link(format!("/proc/{}/exe", pid))
+        .ok()
+        .map(|path| path.to_string_lossy().to_string());
+    let process_name = std::fs::read_to_string(format!("/proc/{}/comm", pid))
+        .map(|name| name.trim().to_string())
+        .unwrap_or_else(|_| format!("pid {}", pid));
+    Some(ForegroundWindow { title, process_name, executable_path, version: None })
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn foreground_window() -> Option<ForegroundWindow> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let window = ForegroundWindow {
+            title: "Checkout".to_string(),
+            process_name: "Shop.exe".to_string(),
+            executable_path: Some("C:\\Program Files\\Shop\\Shop.exe".to_string()),
+            version: Some("2.3.1.0".to_string()),
+        };
+        assert_eq!(window.describe(), "Shop.exe 2.3.1.0 — \"Checkout\"");
+        let window = ForegroundWindow { process_name: "firefox".to_string(), ..Default::default() };
+        assert_eq!(window.describe(), "firefox");
+    }
+}
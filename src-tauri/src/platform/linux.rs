@@ -0,0 +1,597 @@
+//! Linux platform implementation.
+//!
+//! This module provides Linux implementations of the platform abstraction traits.
+//!
+//! # Implementation Status
+//!
+//! - **CaptureBridge**: Full implementation (gnome-screenshot, grim + slurp, spectacle)
+//! - **ScreenCapture**: The same tools run non-interactively
+//! - **RecordingBridge**: `ffmpeg` with the `x11grab` input (X11 sessions only)
+//! - **Platform**: Startup via XDG autostart, URL handlers via a desktop entry
+//! - **RegistryBridge**: Not applicable; the macOS stub is used
+//!
+//! # Capture Model
+//!
+//! Triggered screenshots are written straight into the session's _captures/
+//! folder. Screenshots the user takes with the desktop's own Print Screen
+//! handling land in `~/Pictures/Screenshots` instead; while a session is
+//! active the bridge watches that folder and moves new images into
+//! _captures/, where the capture watcher picks them up as usual.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use image::RgbaImage;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::capture::CaptureBridge;
+use super::error::{PlatformError, Result};
+use super::recording::{Recording, RecordingArea, RecordingBridge};
+use super::screen::{ScreenCapture, ScreenGrab};
+
+/// Name of the desktop entries written for autostart and URL handling.
+const DESKTOP_FILE: &str = "unbroken-qa-capture.desktop";
+
+/// MIME type registered for `.qacap` session archives.
+const ARCHIVE_MIME_TYPE: &str = "application/x-qacapture-archive";
+
+/// Extensions moved from the screenshot folder into the session.
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp"];
+
+/// How long a new screenshot may take to be written before it is moved anyway.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Screenshot tools, tried in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotTool {
+    /// GNOME
+    GnomeScreenshot,
+    /// wlroots compositors (Sway, Hyprland); `slurp` picks the region
+    Grim,
+    /// KDE Plasma
+    Spectacle,
+}
+
+impl ScreenshotTool {
+    pub const ALL: [ScreenshotTool; 3] = [Self::GnomeScreenshot, Self::Grim, Self::Spectacle];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::GnomeScreenshot => "gnome-screenshot",
+            Self::Grim => "grim",
+            Self::Spectacle => "spectacle",
+        }
+    }
+
+    /// Command for an interactive region screenshot saved to `output`.
+    pub fn command(&self, output: &Path) -> Command {
+        let output = output.to_string_lossy().to_string();
+        match self {
+            Self::GnomeScreenshot => {
+                let mut command = Command::new("gnome-screenshot");
+                command.args(["--area", "--file", &output]);
+                command
+            }
+            Self::Grim => {
+                // grim takes the geometry slurp prints; run both through sh
+                let mut command = Command::new("sh");
+                command.args(["-c", "grim -g \"$(slurp)\" \"$1\"", "sh", &output]);
+                command
+            }
+            Self::Spectacle => {
+                let mut command = Command::new("spectacle");
+                command.args(["--region", "--background", "--nonotify", "--output", &output]);
+                command
+            }
+        }
+    }
+}
+
+/// Whether `program` is an executable on `PATH`.
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// `$XDG_CONFIG_HOME`, defaulting to `~/.config`.
+fn config_home() -> Result<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// `$XDG_DATA_HOME`, defaulting to `~/.local/share`.
+fn data_home() -> Result<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+fn xdg_dir(variable: &str, fallback: &str) -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os(variable).filter(|v| !v.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+    dirs::home_dir()
+        .map(|home| home.join(fallback))
+        .ok_or_else(|| PlatformError::Other { message: "Cannot determine home directory".to_string() })
+}
+
+/// The folder desktop screenshot tools save to: `Screenshots` inside the XDG
+/// pictures directory.
+pub fn screenshot_folder() -> Option<PathBuf> {
+    let pictures = Command::new("xdg-user-dir")
+        .arg("PICTURES")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join("Pictures")))?;
+    Some(pictures.join("Screenshots"))
+}
+
+/// Desktop entry launching `exe`. `extra` lines are appended as-is.
+pub fn desktop_entry(exe: &Path, extra: &[&str]) -> String {
+    let mut entry = format!(
+        "[Desktop Entry]\nType=Application\nName=Unbroken QA Capture\nExec=\"{}\" %u\nTerminal=false\n",
+        exe.display()
+    );
+    for line in extra {
+        entry.push_str(line);
+        entry.push('\n');
+    }
+    entry
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Wait until the file size stops changing (tools write in several steps).
+fn wait_until_written(path: &Path) {
+    let started = Instant::now();
+    let mut last_size = None;
+    while started.elapsed() < WRITE_TIMEOUT {
+        let size = std::fs::metadata(path).map(|m| m.len()).ok();
+        if size.is_some() && size == last_size {
+            return;
+        }
+        last_size = size;
+        thread::sleep(Duration::from_millis(200));
+    }
+}
+
+/// Move `source` into `folder`, keeping its file name.
+fn move_into(source: &Path, folder: &Path) -> std::io::Result<PathBuf> {
+    let name = source.file_name().ok_or_else(|| std::io::Error::other("no file name"))?;
+    let target = folder.join(name);
+    if std::fs::rename(source, &target).is_err() {
+        // Different filesystems
+        std::fs::copy(source, &target)?;
+        std::fs::remove_file(source)?;
+    }
+    Ok(target)
+}
+
+/// Moves new screenshots from the desktop's screenshot folder into the session.
+/// Dropping it stops the watch.
+struct ScreenshotForwarder {
+    _watcher: RecommendedWatcher,
+}
+
+impl ScreenshotForwarder {
+    fn start(watched: &Path, captures_dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(watched)?;
+        let mut watcher = RecommendedWatcher::new(
+            move |res: std::result::Result<Event, notify::Error>| {
+                let Ok(event) = res else { return };
+                if !matches!(event.kind, EventKind::Create(_)) {
+                    return;
+                }
+                for path in event.paths.into_iter().filter(|p| is_image(p)) {
+                    let captures_dir = captures_dir.clone();
+                    thread::spawn(move || {
+                        wait_until_written(&path);
+                        if let Err(e) = move_into(&path, &captures_dir) {
+                            eprintln!("LinuxCaptureBridge: cannot move {:?} into the session: {}", path, e);
+                        }
+                    });
+                }
+            },
+            notify::Config::default(),
+        )
+        .map_err(|e| PlatformError::Other { message: format!("Failed to create file watcher: {}", e) })?;
+
+        watcher
+            .watch(watched, RecursiveMode::NonRecursive)
+            .map_err(|e| PlatformError::FileSystemError {
+                path: watched.to_string_lossy().to_string(),
+                operation: "watch".to_string(),
+                message: e.to_string(),
+            })?;
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+/// Linux implementation of `CaptureBridge`.
+///
+/// This implementation provides:
+/// - Screenshot trigger via the first installed tool of [`ScreenshotTool::ALL`]
+/// - Forwarding of screenshots saved to `~/Pictures/Screenshots` while a
+///   session is active
+pub struct LinuxCaptureBridge {
+    captures_dir: Mutex<Option<PathBuf>>,
+    forwarder: Mutex<Option<ScreenshotForwarder>>,
+}
+
+impl LinuxCaptureBridge {
+    /// Creates a new Linux capture bridge.
+    pub fn new() -> Self {
+        Self { captures_dir: Mutex::new(None), forwarder: Mutex::new(None) }
+    }
+
+    /// Where a triggered screenshot is written: the session's _captures/
+    /// folder, or the screenshot folder when no session is active.
+    fn output_path(&self) -> Result<PathBuf> {
+        let folder = match self.captures_dir.lock().unwrap().clone() {
+            Some(dir) => dir,
+            None => screenshot_folder().ok_or_else(|| PlatformError::Other {
+                message: "Cannot determine the screenshot folder".to_string(),
+            })?,
+        };
+        std::fs::create_dir_all(&folder)?;
+        let stamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S-%3f");
+        Ok(folder.join(format!("Screenshot_{}.png", stamp)))
+    }
+}
+
+impl Default for LinuxCaptureBridge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CaptureBridge for LinuxCaptureBridge {
+    fn trigger_screenshot(&self) -> Result<()> {
+        let tool = ScreenshotTool::ALL
+            .into_iter()
+            .find(|tool| on_path(tool.name()))
+            .ok_or_else(|| PlatformError::ScreenshotTriggerError {
+                method: "linux".to_string(),
+                message: "No screenshot tool found; install gnome-screenshot, grim and slurp, or spectacle"
+                    .to_string(),
+            })?;
+
+        // The tools block until the region is picked, so don't wait for them
+        tool.command(&self.output_path()?)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| PlatformError::ScreenshotTriggerError {
+                method: tool.name().to_string(),
+                message: e.to_string(),
+            })
+    }
+
+    fn set_capture_folder(&self, folder: Option<&Path>) -> Result<()> {
+        *self.captures_dir.lock().unwrap() = folder.map(Path::to_path_buf);
+        let mut forwarder = self.forwarder.lock().unwrap();
+        *forwarder = None;
+        if let (Some(folder), Some(watched)) = (folder, screenshot_folder()) {
+            *forwarder = Some(ScreenshotForwarder::start(&watched, folder.to_path_buf())?);
+        }
+        Ok(())
+    }
+}
+
+/// Linux implementation of `ScreenCapture`.
+///
+/// The first installed screenshot tool writes a temporary PNG. `grim` takes
+/// the area directly; the others shoot the whole desktop, which is cropped.
+/// Window positions are not available, so active-window grabs report an
+/// origin of (0, 0).
+pub struct LinuxScreenCapture;
+
+impl LinuxScreenCapture {
+    fn installed_tool() -> Result<ScreenshotTool> {
+        ScreenshotTool::ALL
+            .into_iter()
+            .find(|tool| on_path(tool.name()))
+            .ok_or_else(|| PlatformError::ScreenshotTriggerError {
+                method: "linux".to_string(),
+                message: "No screenshot tool found; install grim, gnome-screenshot or spectacle".to_string(),
+            })
+    }
+
+    /// Run `command`, which writes `output`, and load the image.
+    fn run(tool: ScreenshotTool, mut command: Command, output: &Path) -> Result<RgbaImage> {
+        let error = |message: String| PlatformError::ScreenshotTriggerError { method: tool.name().to_string(), message };
+        let status = command.status().map_err(|e| error(e.to_string()))?;
+        if !status.success() {
+            let _ = std::fs::remove_file(output);
+            return Err(error(format!("exited with {}", status)));
+        }
+        let image = image::open(output).map(|image| image.to_rgba8()).map_err(|e| error(e.to_string()));
+        let _ = std::fs::remove_file(output);
+        image
+    }
+
+    fn temp_output() -> PathBuf {
+        std::env::temp_dir().join(format!("qa-capture-grab-{}.png", uuid::Uuid::new_v4()))
+    }
+}
+
+impl ScreenCapture for LinuxScreenCapture {
+    fn grab_area(&self, x: i32, y: i32, width: u32, height: u32) -> Result<ScreenGrab> {
+        if width == 0 || height == 0 {
+            return Err(PlatformError::InvalidArgument {
+                parameter: "area".to_string(),
+                message: "Capture area is empty".to_string(),
+            });
+        }
+        let tool = Self::installed_tool()?;
+        let output = Self::temp_output();
+        let out = output.to_string_lossy().to_string();
+        let mut command = Command::new(tool.name());
+        let image = match tool {
+            ScreenshotTool::Grim => {
+                command.args(["-g", &format!("{},{} {}x{}", x, y, width, height), &out]);
+                return Self::run(tool, command, &output).map(|image| ScreenGrab { image, origin: (x, y) });
+            }
+            ScreenshotTool::GnomeScreenshot => {
+                command.args(["--file", &out]);
+                Self::run(tool, command, &output)?
+            }
+            ScreenshotTool::Spectacle => {
+                command.args(["--fullscreen", "--background", "--nonotify", "--output", &out]);
+                Self::run(tool, command, &output)?
+            }
+        };
+        crop(&image, x, y, width, height).map(|image| ScreenGrab { image, origin: (x, y) })
+    }
+
+    fn grab_active_window(&self) -> Result<ScreenGrab> {
+        let tool = Self::installed_tool()?;
+        let output = Self::temp_output();
+        let out = output.to_string_lossy().to_string();
+        let mut command = Command::new(tool.name());
+        match tool {
+            ScreenshotTool::GnomeScreenshot => command.args(["--window", "--file", &out]),
+            ScreenshotTool::Spectacle => {
+                command.args(["--activewindow", "--background", "--nonotify", "--output", &out])
+            }
+            ScreenshotTool::Grim => {
+                return Err(PlatformError::NotImplemented {
+                    operation: "grab_active_window".to_string(),
+                    platform: "Linux (grim)".to_string(),
+                })
+            }
+        };
+        Self::run(tool, command, &output).map(|image| ScreenGrab { image, origin: (0, 0) })
+    }
+}
+
+/// Linux implementation of `RecordingBridge`, running `ffmpeg` with the
+/// `x11grab` input. Wayland sessions do not allow grabbing the screen this way.
+pub struct LinuxRecordingBridge {
+    ffmpeg: String,
+}
+
+impl LinuxRecordingBridge {
+    /// Creates a bridge running the `ffmpeg` executable at `ffmpeg`.
+    pub fn new(ffmpeg: &str) -> Self {
+        Self { ffmpeg: ffmpeg.to_string() }
+    }
+
+    /// ffmpeg arguments recording `area` of X display `display`.
+    pub fn arguments(display: &str, area: RecordingArea, fps: u32, output: &Path) -> Vec<String> {
+        [
+            "-y", "-loglevel", "error", "-f", "x11grab",
+            "-video_size", &format!("{}x{}", area.width, area.height),
+            "-framerate", &fps.to_string(),
+            "-i", &format!("{}+{},{}", display, area.x, area.y),
+            "-c:v", "libx264", "-preset", "veryfast", "-pix_fmt", "yuv420p",
+            "-movflags", "+faststart",
+            &*output.to_string_lossy(),
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect()
+    }
+}
+
+impl RecordingBridge for LinuxRecordingBridge {
+    fn start(&self, area: RecordingArea, fps: u32, output: &Path) -> Result<Box<dyn Recording>> {
+        let area = area.even();
+        if area.width == 0 || area.height == 0 || fps == 0 {
+            return Err(PlatformError::InvalidArgument {
+                parameter: "area".to_string(),
+                message: "Recording area and frame rate must not be empty".to_string(),
+            });
+        }
+        let display = std::env::var("DISPLAY").ok().filter(|d| !d.is_empty()).unwrap_or_else(|| ":0".to_string());
+        let child = Command::new(&self.ffmpeg)
+            .args(Self::arguments(&display, area, fps, output))
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| PlatformError::Other { message: format!("Failed to run ffmpeg: {}", e) })?;
+        Ok(Box::new(FfmpegRecording { child }))
+    }
+}
+
+struct FfmpegRecording {
+    child: std::process::Child,
+}
+
+impl Recording for FfmpegRecording {
+    fn stop(mut self: Box<Self>) -> Result<()> {
+        use std::io::Write;
+
+        // "q" makes ffmpeg finish the file; killing it would leave no moov atom
+        if let Some(mut stdin) = self.child.stdin.take() {
+            let _ = stdin.write_all(b"q");
+        }
+        let output = self
+            .child
+            .wait_with_output()
+            .map_err(|e| PlatformError::Other { message: format!("Failed to stop ffmpeg: {}", e) })?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(PlatformError::Other {
+                message: format!("ffmpeg failed: {}", String::from_utf8_lossy(&output.stderr).trim()),
+            })
+        }
+    }
+}
+
+/// Cut the area out of a desktop screenshot whose top-left is at (0, 0).
+pub fn crop(desktop: &RgbaImage, x: i32, y: i32, width: u32, height: u32) -> Result<RgbaImage> {
+    let fits = x >= 0
+        && y >= 0
+        && x as u64 + width as u64 <= desktop.width() as u64
+        && y as u64 + height as u64 <= desktop.height() as u64;
+    if !fits {
+        return Err(PlatformError::InvalidArgument {
+            parameter: "area".to_string(),
+            message: format!("{}x{} at ({}, {}) is outside the screen", width, height, x, y),
+        });
+    }
+    Ok(image::imageops::crop_imm(desktop, x as u32, y as u32, width, height).to_image())
+}
+
+/// Linux platform implementation for startup and URL handling
+pub struct LinuxPlatform;
+
+impl LinuxPlatform {
+    fn exe_path() -> Result<PathBuf> {
+        std::env::current_exe().map_err(|e| PlatformError::InvalidArgument {
+            parameter: "exe_path".to_string(),
+            message: format!("Failed to get current executable path: {}", e),
+        })
+    }
+
+    fn write_file(path: &Path, contents: &str) -> Result<()> {
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, contents)
+        };
+        write().map_err(|e| PlatformError::FileSystemError {
+            path: path.to_string_lossy().to_string(),
+            operation: "write".to_string(),
+            message: e.to_string(),
+        })
+    }
+}
+
+impl super::Platform for LinuxPlatform {
+    fn enable_startup(&self) -> Result<()> {
+        let entry = desktop_entry(&Self::exe_path()?, &["X-GNOME-Autostart-enabled=true"]);
+        Self::write_file(&config_home()?.join("autostart").join(DESKTOP_FILE), &entry)
+    }
+
+    fn disable_startup(&self) -> Result<()> {
+        let path = config_home()?.join("autostart").join(DESKTOP_FILE);
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(PlatformError::FileSystemError {
+                path: path.to_string_lossy().to_string(),
+                operation: "remove".to_string(),
+                message: e.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn register_url_handlers(&self) -> Result<()> {
+        let data = data_home()?;
+        let mime_types = format!("MimeType=x-scheme-handler/qacapture;{};", ARCHIVE_MIME_TYPE);
+        let entry = desktop_entry(&Self::exe_path()?, &[&mime_types, "NoDisplay=true"]);
+        Self::write_file(&data.join("applications").join(DESKTOP_FILE), &entry)?;
+
+        let package = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <mime-info xmlns=\"http://www.freedesktop.org/standards/shared-mime-info\">\n  \
+             <mime-type type=\"{}\">\n    <comment>QA Capture Session Archive</comment>\n    \
+             <glob pattern=\"*.qacap\"/>\n  </mime-type>\n</mime-info>\n",
+            ARCHIVE_MIME_TYPE
+        );
+        Self::write_file(&data.join("mime/packages/unbroken-qa-capture.xml"), &package)?;
+
+        // Best effort: the files above are picked up on the next desktop
+        // database refresh even if these tools are missing
+        let _ = Command::new("update-mime-database").arg(data.join("mime")).status();
+        let _ = Command::new("update-desktop-database").arg(data.join("applications")).status();
+        for mime in ["x-scheme-handler/qacapture", ARCHIVE_MIME_TYPE] {
+            let _ = Command::new("xdg-mime").args(["default", DESKTOP_FILE, mime]).status();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_commands_write_to_output() {
+        let output = Path::new("/qa/session/_captures/shot.png");
+        for tool in ScreenshotTool::ALL {
+            let command = tool.command(output);
+            let args: Vec<String> = command.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+            assert!(args.iter().any(|a| a == "/qa/session/_captures/shot.png"), "{:?}: {:?}", tool, args);
+        }
+        let grim = ScreenshotTool::Grim.command(output);
+        assert_eq!(grim.get_program(), "sh");
+    }
+
+    #[test]
+    fn test_crop_checks_bounds() {
+        let mut desktop = RgbaImage::new(100, 50);
+        desktop.put_pixel(30, 20, image::Rgba([255, 0, 0, 255]));
+        let area = crop(&desktop, 30, 20, 10, 10).unwrap();
+        assert_eq!(area.dimensions(), (10, 10));
+        assert_eq!(*area.get_pixel(0, 0), image::Rgba([255, 0, 0, 255]));
+
+        assert!(crop(&desktop, 95, 0, 10, 10).is_err());
+        assert!(crop(&desktop, -1, 0, 10, 10).is_err());
+    }
+
+    #[test]
+    fn test_ffmpeg_arguments() {
+        let area = RecordingArea { x: 1920, y: 0, width: 1280, height: 720 };
+        let args = LinuxRecordingBridge::arguments(":1", area, 15, Path::new("/qa/bug_001/recording-001.mp4"));
+        let joined = args.join(" ");
+        assert!(joined.contains("-f x11grab -video_size 1280x720 -framerate 15 -i :1+1920,0"));
+        assert_eq!(args.last().map(String::as_str), Some("/qa/bug_001/recording-001.mp4"));
+    }
+
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry(Path::new("/opt/qa/unbroken-qa-capture"), &["X-GNOME-Autostart-enabled=true"]);
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("Exec=\"/opt/qa/unbroken-qa-capture\" %u\n"));
+        assert!(entry.ends_with("X-GNOME-Autostart-enabled=true\n"));
+    }
+
+    #[test]
+    fn test_forwarding_only_takes_images() {
+        assert!(is_image(Path::new("/home/qa/Pictures/Screenshots/Screenshot.PNG")));
+        assert!(!is_image(Path::new("/home/qa/Pictures/Screenshots/.goutputstream-ABC")));
+
+        let dir = tempfile::tempdir().unwrap();
+        let (source, target) = (dir.path().join("shots"), dir.path().join("_captures"));
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::write(source.join("a.png"), b"png").unwrap();
+        let moved = move_into(&source.join("a.png"), &target).unwrap();
+        assert_eq!(moved, target.join("a.png"));
+        assert!(!source.join("a.png").exists());
+    }
+}
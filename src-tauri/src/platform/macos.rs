@@ -8,17 +8,22 @@
 //!
 //! - **CaptureBridge**: Stub implementation (returns `NotImplemented` for all operations)
 //! - **RegistryBridge**: Stub implementation (returns `NotImplemented` for all operations)
+//! - **ScreenCapture**: Stub implementation (returns `NotImplemented` for all operations)
+//! - **RecordingBridge**: Stub implementation (returns `NotImplemented` for all operations)
 //!
 //! # Future Implementation (v2)
 //!
 //! When macOS support is added, this module will be replaced with actual implementations:
 //! - `CaptureBridge` will use `screencapture -i` CLI for interactive screenshots
 //! - `RegistryBridge` will be a no-op (macOS does not have a Windows-style registry)
+//! - `ScreenCapture` will use `CGDisplayCreateImage` / `CGWindowListCreateImage`
 
 use std::path::{Path, PathBuf};
 
 use super::capture::CaptureBridge;
 use super::registry::RegistryBridge;
+use super::recording::{Recording, RecordingArea, RecordingBridge};
+use super::screen::{ScreenCapture, ScreenGrab};
 use super::error::{PlatformError, Result};
 
 /// macOS stub implementation for `CaptureBridge`.
@@ -26,10 +31,12 @@ use super::error::{PlatformError, Result};
 /// This stub implementation returns `NotImplemented` errors for all operations.
 /// It allows the application to compile on macOS but does not provide actual
 /// screenshot capture or file watching functionality.
+#[allow(dead_code)]
 pub struct MacCaptureBridge {
     // Placeholder for future state
 }
 
+#[allow(dead_code)]
 impl MacCaptureBridge {
     /// Creates a new macOS capture bridge stub.
     pub fn new() -> Self {
@@ -104,6 +111,42 @@ impl RegistryBridge for MacRegistryBridge {
     }
 }
 
+/// macOS stub implementation for `ScreenCapture`.
+///
+/// Reading the screen needs CoreGraphics and the Screen Recording
+/// permission, which are part of the v2 macOS work.
+#[allow(dead_code)]
+pub struct MacScreenCapture;
+
+impl ScreenCapture for MacScreenCapture {
+    fn grab_area(&self, _x: i32, _y: i32, _width: u32, _height: u32) -> Result<ScreenGrab> {
+        Err(PlatformError::NotImplemented {
+            operation: "grab_area".to_string(),
+            platform: "macOS".to_string(),
+        })
+    }
+
+    fn grab_active_window(&self) -> Result<ScreenGrab> {
+        Err(PlatformError::NotImplemented {
+            operation: "grab_active_window".to_string(),
+            platform: "macOS".to_string(),
+        })
+    }
+}
+
+/// macOS stub implementation for `RecordingBridge`.
+#[allow(dead_code)]
+pub struct MacRecordingBridge;
+
+impl RecordingBridge for MacRecordingBridge {
+    fn start(&self, _area: RecordingArea, _fps: u32, _output: &Path) -> Result<Box<dyn Recording>> {
+        Err(PlatformError::NotImplemented {
+            operation: "start_recording".to_string(),
+            platform: "macOS".to_string(),
+        })
+    }
+}
+
 /// macOS platform stub implementation
 #[allow(dead_code)]
 pub struct MacPlatform;
@@ -122,6 +165,14 @@ impl super::Platform for MacPlatform {
             platform: "macOS".to_string(),
         })
     }
+
+    fn register_url_handlers(&self) -> Result<()> {
+        // Declared in the app bundle's Info.plist at build time on macOS
+        Err(PlatformError::NotImplemented {
+            operation: "register_url_handlers".to_string(),
+            platform: "macOS".to_string(),
+        })
+    }
 }
 
 #[cfg(test)]
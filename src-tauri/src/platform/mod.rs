@@ -7,6 +7,7 @@
 //! # Platform Support
 //!
 //! - **Windows 11**: Full implementation (v1)
+//! - **Linux**: Capture bridge and startup/URL handling; no registry
 //! - **macOS**: Stubbed implementations returning `NotImplemented` errors (v2 planned)
 //!
 //! # Architecture
@@ -14,23 +15,36 @@
 //! The platform layer uses Rust traits to define contracts for platform-specific operations:
 //! - `CaptureBridge`: Screenshot capture, file watching, and system integration
 //! - `RegistryBridge`: Windows registry operations with crash-safe restore
+//! - `ScreenCapture`: Copying screen pixels without an external screenshot tool
+//! - `RecordingBridge`: Recording part of the screen to MP4
+//!
+//! `foreground_window` reads the window the user is working in.
 //!
 //! Platform-specific implementations are selected at compile time using `cfg` attributes.
 
 mod capture;
+mod foreground;
+mod recording;
 mod registry;
+mod screen;
 pub(crate) mod registry_cache;
 mod error;
 
 #[cfg(target_os = "windows")]
 mod windows;
 
+#[cfg(target_os = "linux")]
+mod linux;
+
 #[cfg(not(target_os = "windows"))]
 mod macos;
 
 // Re-export public types
 pub use capture::CaptureBridge;
+pub use foreground::{foreground_window, ForegroundWindow};
 pub use registry::RegistryBridge;
+pub use recording::{Recording, RecordingArea, RecordingBridge};
+pub use screen::{ScreenCapture, ScreenGrab};
 pub use error::{PlatformError, Result};
 
 /// Platform trait for OS-specific operations
@@ -40,12 +54,20 @@ pub trait Platform {
 
     /// Disable application startup on system boot
     fn disable_startup(&self) -> Result<()>;
+
+    /// Register the `qacapture://` URL scheme and `.qacap` file association
+    /// so both launch (or are forwarded to) this executable
+    fn register_url_handlers(&self) -> Result<()>;
 }
 
 /// Windows platform implementation
 #[cfg(target_os = "windows")]
 pub use windows::WindowsPlatform;
 
+/// Linux platform implementation
+#[cfg(target_os = "linux")]
+pub use linux::LinuxPlatform;
+
 /// macOS platform implementation (stub)
 #[cfg(target_os = "macos")]
 pub use macos::MacPlatform;
@@ -55,8 +77,9 @@ pub use macos::MacPlatform;
 /// # Platform Selection
 ///
 /// - **Windows**: Returns `WindowsCaptureBridge` with Snipping Tool integration
+/// - **Linux**: Returns `LinuxCaptureBridge` with desktop screenshot tool integration
 /// - **macOS**: Returns `MacCaptureBridge` with stub implementations
-/// - **Other**: Compile error (unsupported platform)
+/// - **Other**: Returns the macOS stub
 ///
 /// # Example
 ///
@@ -76,12 +99,63 @@ pub fn get_capture_bridge() -> Box<dyn CaptureBridge> {
     Box::new(macos::MacCaptureBridge::new())
 }
 
-/// Fallback stub for unsupported platforms (e.g. Linux when building in WSL).
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[cfg(target_os = "linux")]
+pub fn get_capture_bridge() -> Box<dyn CaptureBridge> {
+    Box::new(linux::LinuxCaptureBridge::new())
+}
+
+/// Fallback stub for other platforms (e.g. the BSDs).
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 pub fn get_capture_bridge() -> Box<dyn CaptureBridge> {
     Box::new(macos::MacCaptureBridge::new())
 }
 
+/// Returns the platform-specific `ScreenCapture` implementation for the current OS.
+///
+/// # Platform Selection
+///
+/// - **Windows**: Returns `WindowsScreenCapture` (GDI)
+/// - **Linux**: Returns `LinuxScreenCapture` (desktop screenshot tools)
+/// - **Other**: Returns the macOS stub
+#[cfg(target_os = "windows")]
+pub fn get_screen_capture() -> Box<dyn ScreenCapture> {
+    Box::new(windows::WindowsScreenCapture)
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_screen_capture() -> Box<dyn ScreenCapture> {
+    Box::new(linux::LinuxScreenCapture)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn get_screen_capture() -> Box<dyn ScreenCapture> {
+    Box::new(macos::MacScreenCapture)
+}
+
+/// Returns the platform-specific `RecordingBridge` implementation for the current OS.
+/// `ffmpeg` is the configured ffmpeg executable, used where the OS has no
+/// built-in encoder.
+///
+/// # Platform Selection
+///
+/// - **Windows**: Returns `WindowsRecordingBridge` (Media Foundation)
+/// - **Linux**: Returns `LinuxRecordingBridge` (ffmpeg x11grab)
+/// - **Other**: Returns the macOS stub
+#[cfg(target_os = "windows")]
+pub fn get_recording_bridge(_ffmpeg: &str) -> Box<dyn RecordingBridge> {
+    Box::new(windows::WindowsRecordingBridge)
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_recording_bridge(ffmpeg: &str) -> Box<dyn RecordingBridge> {
+    Box::new(linux::LinuxRecordingBridge::new(ffmpeg))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn get_recording_bridge(_ffmpeg: &str) -> Box<dyn RecordingBridge> {
+    Box::new(macos::MacRecordingBridge)
+}
+
 /// Returns the platform-specific `RegistryBridge` implementation for the current OS.
 ///
 /// # Platform Selection
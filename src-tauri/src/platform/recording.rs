@@ -0,0 +1,57 @@
+//! Platform abstraction for screen recording.
+//!
+//! The `RecordingBridge` trait records a rectangle of the screen to an MP4
+//! file until the returned [`Recording`] is stopped.
+
+use std::path::Path;
+
+use super::error::Result;
+
+/// Screen rectangle to record, in virtual-desktop coordinates (physical
+/// pixels). Encoders need even sizes; see [`RecordingArea::even`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordingArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl RecordingArea {
+    /// The area shrunk to even width and height, as H.264 requires.
+    pub fn even(self) -> Self {
+        Self { width: self.width & !1, height: self.height & !1, ..self }
+    }
+}
+
+/// A recording in progress. Dropping it without `stop` abandons the file.
+pub trait Recording: Send {
+    /// Stops recording and finishes writing the file.
+    ///
+    /// # Errors
+    ///
+    /// - `PlatformError::Other`: The encoder failed while recording or finishing
+    fn stop(self: Box<Self>) -> Result<()>;
+}
+
+/// Platform abstraction trait for recording the screen.
+///
+/// # Platform Implementations
+///
+/// - **Windows**: GDI frames encoded to H.264/MP4 with a Media Foundation sink writer
+/// - **Linux**: `ffmpeg` with the `x11grab` input
+/// - **macOS**: AVFoundation (v2)
+///
+/// # Thread Safety
+///
+/// Implementations should be `Send + Sync` to allow usage across threads.
+pub trait RecordingBridge: Send + Sync {
+    /// Starts recording `area` at `fps` frames per second to `output` (MP4).
+    ///
+    /// # Errors
+    ///
+    /// - `PlatformError::InvalidArgument`: Empty area or zero frame rate
+    /// - `PlatformError::Other`: The encoder could not be started
+    /// - `PlatformError::NotImplemented`: Platform does not support this operation (macOS v1)
+    fn start(&self, area: RecordingArea, fps: u32, output: &Path) -> Result<Box<dyn Recording>>;
+}
@@ -0,0 +1,48 @@
+//! Platform abstraction for grabbing screen pixels directly.
+//!
+//! The `ScreenCapture` trait copies part of the screen into an image without
+//! involving an external screenshot tool, so a capture never depends on
+//! where that tool saves its files.
+
+use image::RgbaImage;
+
+use super::error::Result;
+
+/// Screen pixels and where they came from.
+#[derive(Debug, Clone)]
+pub struct ScreenGrab {
+    pub image: RgbaImage,
+    /// Screen position of the image's top-left pixel (virtual-desktop
+    /// coordinates, physical pixels)
+    pub origin: (i32, i32),
+}
+
+/// Platform abstraction trait for copying screen contents.
+///
+/// # Platform Implementations
+///
+/// - **Windows**: GDI `BitBlt` from the screen device context
+/// - **Linux**: Non-interactive `grim`, `gnome-screenshot` or `spectacle`
+/// - **macOS**: `CGDisplayCreateImage` (v2)
+///
+/// # Thread Safety
+///
+/// Implementations should be `Send + Sync` to allow usage across threads.
+pub trait ScreenCapture: Send + Sync {
+    /// Copies a rectangle of the virtual desktop, in physical pixels.
+    ///
+    /// # Errors
+    ///
+    /// - `PlatformError::InvalidArgument`: Empty rectangle
+    /// - `PlatformError::ScreenshotTriggerError`: The screen could not be read
+    /// - `PlatformError::NotImplemented`: Platform does not support this operation (macOS v1)
+    fn grab_area(&self, x: i32, y: i32, width: u32, height: u32) -> Result<ScreenGrab>;
+
+    /// Copies the foreground window.
+    ///
+    /// # Errors
+    ///
+    /// - `PlatformError::ScreenshotTriggerError`: No foreground window or it could not be read
+    /// - `PlatformError::NotImplemented`: Platform does not support this operation (macOS v1)
+    fn grab_active_window(&self) -> Result<ScreenGrab>;
+}
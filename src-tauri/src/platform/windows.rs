@@ -5,6 +5,8 @@
 //! # Implementation Status
 //!
 //! - **CaptureBridge**: Full implementation (screenshot trigger only)
+//! - **ScreenCapture**: Full implementation (GDI)
+//! - **RecordingBridge**: Full implementation (GDI frames, Media Foundation H.264/MP4)
 //! - **RegistryBridge**: Full implementation with crash recovery via SQLite cache
 //!
 //! # Capture Model
@@ -20,11 +22,20 @@
 //! - Persistent caching of original values in SQLite for crash recovery
 
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 use std::os::windows::process::CommandExt;
 
+use windows::core::HSTRING;
+use windows::Win32::Media::MediaFoundation::*;
+use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+
 use super::capture::CaptureBridge;
 use super::registry::RegistryBridge;
+use super::recording::{Recording, RecordingArea, RecordingBridge};
+use super::screen::{ScreenCapture, ScreenGrab};
 use super::registry_cache::RegistryCache;
 use super::error::{PlatformError, Result};
 
@@ -250,6 +261,207 @@ impl CaptureBridge for WindowsCaptureBridge {
 
 }
 
+/// Windows implementation of `ScreenCapture`, copying from the screen with GDI
+/// (see `window_capture`).
+pub struct WindowsScreenCapture;
+
+impl ScreenCapture for WindowsScreenCapture {
+    fn grab_area(&self, x: i32, y: i32, width: u32, height: u32) -> Result<ScreenGrab> {
+        if width == 0 || height == 0 {
+            return Err(PlatformError::InvalidArgument {
+                parameter: "area".to_string(),
+                message: "Capture area is empty".to_string(),
+            });
+        }
+        crate::window_capture::grab_screen_area(x, y, width as i32, height as i32)
+            .map(|image| ScreenGrab { image, origin: (x, y) })
+            .map_err(|message| PlatformError::ScreenshotTriggerError { method: "gdi".to_string(), message })
+    }
+
+    fn grab_active_window(&self) -> Result<ScreenGrab> {
+        crate::window_capture::grab_window(None)
+            .map(|shot| ScreenGrab { image: shot.image, origin: shot.origin })
+            .map_err(|message| PlatformError::ScreenshotTriggerError { method: "gdi".to_string(), message })
+    }
+}
+
+/// Windows implementation of `RecordingBridge`.
+///
+/// A worker thread grabs the area with GDI at the requested frame rate and
+/// feeds the frames to a Media Foundation sink writer, which encodes H.264
+/// into an MP4 container. Sample times follow the wall clock, so a slow grab
+/// lowers the frame rate rather than shortening the video.
+pub struct WindowsRecordingBridge;
+
+impl RecordingBridge for WindowsRecordingBridge {
+    fn start(&self, area: RecordingArea, fps: u32, output: &Path) -> Result<Box<dyn Recording>> {
+        let area = area.even();
+        if area.width == 0 || area.height == 0 || fps == 0 {
+            return Err(PlatformError::InvalidArgument {
+                parameter: "area".to_string(),
+                message: "Recording area and frame rate must not be empty".to_string(),
+            });
+        }
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop_flag);
+        let output = output.to_path_buf();
+        // The writer is created on the worker; its result tells `start` whether recording began
+        let (started_tx, started_rx) = mpsc::channel::<std::result::Result<(), String>>();
+
+        let worker = thread::spawn(move || -> std::result::Result<(), String> {
+            let _com = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+            let mut writer = match Mp4Writer::create(&output, area.width, area.height, fps) {
+                Ok(writer) => writer,
+                Err(e) => {
+                    let message = format!("Failed to create {:?}: {}", output, e);
+                    let _ = started_tx.send(Err(message.clone()));
+                    return Err(message);
+                }
+            };
+            let _ = started_tx.send(Ok(()));
+
+            let interval = Duration::from_secs(1) / fps;
+            let started = Instant::now();
+            let mut encode_error = None;
+            let mut grab_failing = false;
+            while !flag.load(Ordering::Relaxed) {
+                let frame_started = Instant::now();
+                // A frame that cannot be grabbed (e.g. while the secure desktop is up) is skipped
+                match crate::window_capture::grab_screen_area(area.x, area.y, area.width as i32, area.height as i32) {
+                    Ok(image) => {
+                        grab_failing = false;
+                        if let Err(e) = writer.write_frame(&image, started.elapsed()) {
+                            encode_error = Some(format!("Failed to encode frame: {}", e));
+                            break;
+                        }
+                    }
+                    Err(e) if !grab_failing => {
+                        grab_failing = true;
+                        eprintln!("Warning: skipping recording frames that cannot be captured: {}", e);
+                    }
+                    Err(_) => {}
+                }
+                thread::sleep(interval.saturating_sub(frame_started.elapsed()));
+            }
+            // Finalize even after an encoder error so the frames written so far stay playable
+            let finished = writer.finish().map_err(|e| format!("Failed to finish {:?}: {}", output, e));
+            encode_error.map_or(finished, Err)
+        });
+
+        match started_rx.recv() {
+            Ok(Ok(())) => Ok(Box::new(WindowsRecording { stop_flag, worker: Some(worker) })),
+            Ok(Err(message)) => Err(PlatformError::Other { message }),
+            Err(_) => Err(PlatformError::Other { message: "Recording thread exited unexpectedly".to_string() }),
+        }
+    }
+}
+
+struct WindowsRecording {
+    stop_flag: Arc<AtomicBool>,
+    worker: Option<thread::JoinHandle<std::result::Result<(), String>>>,
+}
+
+impl Recording for WindowsRecording {
+    fn stop(mut self: Box<Self>) -> Result<()> {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        let worker = self.worker.take().expect("recording already stopped");
+        match worker.join() {
+            Ok(result) => result.map_err(|message| PlatformError::Other { message }),
+            Err(_) => Err(PlatformError::Other { message: "Recording thread panicked".to_string() }),
+        }
+    }
+}
+
+impl Drop for WindowsRecording {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// H.264/MP4 encoder on a Media Foundation sink writer, fed with RGB32 frames.
+struct Mp4Writer {
+    writer: IMFSinkWriter,
+    stream: u32,
+    width: u32,
+    height: u32,
+    /// Frame duration in 100 ns units
+    frame_duration: i64,
+}
+
+impl Mp4Writer {
+    fn create(path: &Path, width: u32, height: u32, fps: u32) -> windows::core::Result<Self> {
+        unsafe {
+            MFStartup(MF_VERSION, MFSTARTUP_FULL)?;
+            let writer = MFCreateSinkWriterFromURL(&HSTRING::from(path.as_os_str()), None, None)?;
+
+            let frame_size = ((width as u64) << 32) | height as u64;
+            let frame_rate = ((fps as u64) << 32) | 1;
+            let square_pixels = (1u64 << 32) | 1;
+
+            let encoded = MFCreateMediaType()?;
+            encoded.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Video)?;
+            encoded.SetGUID(&MF_MT_SUBTYPE, &MFVideoFormat_H264)?;
+            // Screen content compresses well; ~4 bits per pixel per second
+            encoded.SetUINT32(&MF_MT_AVG_BITRATE, (width * height * 4).clamp(2_000_000, 16_000_000))?;
+            encoded.SetUINT32(&MF_MT_INTERLACE_MODE, MFVideoInterlace_Progressive.0 as u32)?;
+            encoded.SetUINT64(&MF_MT_FRAME_SIZE, frame_size)?;
+            encoded.SetUINT64(&MF_MT_FRAME_RATE, frame_rate)?;
+            encoded.SetUINT64(&MF_MT_PIXEL_ASPECT_RATIO, square_pixels)?;
+            let stream = writer.AddStream(&encoded)?;
+
+            let input = MFCreateMediaType()?;
+            input.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Video)?;
+            input.SetGUID(&MF_MT_SUBTYPE, &MFVideoFormat_RGB32)?;
+            input.SetUINT32(&MF_MT_INTERLACE_MODE, MFVideoInterlace_Progressive.0 as u32)?;
+            input.SetUINT64(&MF_MT_FRAME_SIZE, frame_size)?;
+            input.SetUINT64(&MF_MT_FRAME_RATE, frame_rate)?;
+            input.SetUINT64(&MF_MT_PIXEL_ASPECT_RATIO, square_pixels)?;
+            writer.SetInputMediaType(stream, &input, None)?;
+
+            writer.BeginWriting()?;
+            Ok(Self { writer, stream, width, height, frame_duration: 10_000_000 / fps as i64 })
+        }
+    }
+
+    /// Encode one frame shown at `at` since the start.
+    fn write_frame(&mut self, image: &image::RgbaImage, at: Duration) -> windows::core::Result<()> {
+        let stride = self.width as usize * 4;
+        let length = stride * self.height as usize;
+        unsafe {
+            let buffer = MFCreateMemoryBuffer(length as u32)?;
+            let mut data = std::ptr::null_mut();
+            buffer.Lock(&mut data, None, None)?;
+            let target = std::slice::from_raw_parts_mut(data, length);
+            // RGB32 is BGRX and bottom-up
+            for (y, row) in image.rows().take(self.height as usize).enumerate() {
+                let line = &mut target[(self.height as usize - 1 - y) * stride..][..stride];
+                for (x, pixel) in row.take(self.width as usize).enumerate() {
+                    line[x * 4..x * 4 + 4].copy_from_slice(&[pixel[2], pixel[1], pixel[0], 255]);
+                }
+            }
+            buffer.Unlock()?;
+            buffer.SetCurrentLength(length as u32)?;
+
+            let sample = MFCreateSample()?;
+            sample.AddBuffer(&buffer)?;
+            sample.SetSampleTime(at.as_nanos() as i64 / 100)?;
+            sample.SetSampleDuration(self.frame_duration)?;
+            self.writer.WriteSample(self.stream, &sample)
+        }
+    }
+
+    fn finish(self) -> windows::core::Result<()> {
+        unsafe {
+            self.writer.Finalize()?;
+            MFShutdown()
+        }
+    }
+}
+
 /// Windows implementation of `RegistryBridge` with crash recovery.
 ///
 /// This implementation provides full registry read/write operations for the
@@ -624,6 +836,54 @@ impl super::Platform for WindowsPlatform {
             platform: "Non-Windows platform".to_string(),
         })
     }
+
+    #[cfg(windows)]
+    fn register_url_handlers(&self) -> Result<()> {
+        let exe_path = std::env::current_exe()
+            .map_err(|e| PlatformError::InvalidArgument {
+                parameter: "exe_path".to_string(),
+                message: format!("Failed to get current executable path: {}", e),
+            })?;
+        let exe = exe_path.to_string_lossy().to_string();
+        let command = format!("\"{}\" \"%1\"", exe);
+        let icon = format!("\"{}\",0", exe);
+
+        // (subkey under HKCU\Software\Classes, default value, extra named values)
+        let keys: [(String, String, &[(&str, &str)]); 7] = [
+            ("qacapture".to_string(), "URL:QA Capture".to_string(), &[("URL Protocol", "")]),
+            ("qacapture\\DefaultIcon".to_string(), icon.clone(), &[]),
+            ("qacapture\\shell\\open\\command".to_string(), command.clone(), &[]),
+            (".qacap".to_string(), "UnbrokenQACapture.Archive".to_string(), &[]),
+            ("UnbrokenQACapture.Archive".to_string(), "QA Capture Session Archive".to_string(), &[]),
+            ("UnbrokenQACapture.Archive\\DefaultIcon".to_string(), icon, &[]),
+            ("UnbrokenQACapture.Archive\\shell\\open\\command".to_string(), command, &[]),
+        ];
+
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        for (subkey, default, values) in keys.iter() {
+            let path = format!("Software\\Classes\\{}", subkey);
+            let registry_error = |operation: &str, e: std::io::Error| PlatformError::RegistryError {
+                key: format!("HKCU\\{}", path),
+                operation: operation.to_string(),
+                message: format!("Failed to register URL handler: {}", e),
+            };
+            let (key, _) = hkcu.create_subkey(&path).map_err(|e| registry_error("open", e))?;
+            key.set_value("", default).map_err(|e| registry_error("write", e))?;
+            for (name, value) in values.iter() {
+                key.set_value(name, value).map_err(|e| registry_error("write", e))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(windows))]
+    fn register_url_handlers(&self) -> Result<()> {
+        Err(PlatformError::NotImplemented {
+            operation: "register_url_handlers".to_string(),
+            platform: "Non-Windows platform".to_string(),
+        })
+    }
 }
 
 #[cfg(test)]
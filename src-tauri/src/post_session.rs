@@ -0,0 +1,293 @@
+//! Post-session pipeline.
+//!
+//! When a session ends, the `post_session_actions` of its profile run in
+//! order on a background thread: summary generation, ZIP and HTML exports,
+//! and webhooks. A failed step is reported and the pipeline moves on to the
+//! next one. Progress goes to the frontend as `session:post-processing`
+//! events (see `events`) carrying every step finished so far.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{BugOps, BugRepository, SessionOps, SessionRepository};
+use crate::export_hooks;
+use crate::profile::{PostSessionAction, ProfileRepository, SqliteProfileRepository};
+use crate::session_summary::SessionSummaryGenerator;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Outcome of one pipeline step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepResult {
+    /// Action kind, e.g. `zip_export`
+    pub action: String,
+    pub success: bool,
+    /// What the step produced (usually a file path)
+    pub output: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Payload of the `session:post-processing` event, sent after each step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostProcessingEvent {
+    pub session_id: String,
+    pub total_steps: usize,
+    pub steps: Vec<StepResult>,
+    pub complete: bool,
+}
+
+/// Executes a single action (abstracted for testing).
+pub trait ActionRunner {
+    /// Run `action` for `session_id`. `completed` holds the results of the
+    /// earlier steps, which webhooks pass along.
+    fn run(&self, session_id: &str, action: &PostSessionAction, completed: &[StepResult]) -> Result<String, String>;
+}
+
+/// Run `actions` in order, reporting progress after every step. Returns the final event.
+pub fn run_pipeline<F>(
+    session_id: &str,
+    actions: &[PostSessionAction],
+    runner: &dyn ActionRunner,
+    mut on_progress: F,
+) -> PostProcessingEvent
+where
+    F: FnMut(&PostProcessingEvent),
+{
+    let mut event = PostProcessingEvent {
+        session_id: session_id.to_string(),
+        total_steps: actions.len(),
+        steps: Vec::with_capacity(actions.len()),
+        complete: actions.is_empty(),
+    };
+
+    for (index, action) in actions.iter().enumerate() {
+        let result = runner.run(session_id, action, &event.steps);
+        if let Err(e) = &result {
+            eprintln!("Post-session step '{}' failed for session {}: {}", action.kind(), session_id, e);
+        }
+        event.steps.push(StepResult {
+            action: action.kind().to_string(),
+            success: result.is_ok(),
+            output: result.as_ref().ok().cloned(),
+            error: result.err(),
+        });
+        event.complete = index + 1 == actions.len();
+        on_progress(&event);
+    }
+
+    event
+}
+
+/// The post-session actions configured on the session's profile (empty when
+/// the session has no profile).
+pub fn profile_actions(conn: &Connection, session_id: &str) -> Vec<PostSessionAction> {
+    SessionRepository::new(conn)
+        .get(session_id)
+        .ok()
+        .flatten()
+        .and_then(|session| session.profile_id)
+        .and_then(|profile_id| SqliteProfileRepository::new(conn).get(&profile_id).ok().flatten())
+        .map(|profile| profile.post_session_actions)
+        .unwrap_or_default()
+}
+
+/// Runs actions against the app database and the file system.
+pub struct RealActionRunner {
+    db: Arc<Mutex<Connection>>,
+    storage_root: Option<PathBuf>,
+}
+
+impl RealActionRunner {
+    pub fn new(db: Arc<Mutex<Connection>>, storage_root: Option<PathBuf>) -> Self {
+        Self { db, storage_root }
+    }
+
+    fn zip_export(
+        &self,
+        session_id: &str,
+        destination: &str,
+        mode: Option<crate::media_offload::VideoExportMode>,
+    ) -> Result<String, String> {
+        let folder_path = {
+            let conn = self.db.lock().unwrap();
+            SessionRepository::new(&conn)
+                .get(session_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Session not found: {}", session_id))?
+                .folder_path
+        };
+        let name = PathBuf::from(&folder_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| session_id.to_string());
+
+        let dest_dir = PathBuf::from(destination);
+        std::fs::create_dir_all(&dest_dir)
+            .map_err(|e| format!("Cannot create export folder {:?}: {}", dest_dir, e))?;
+        let dest = dest_dir.join(format!("{}.zip", name));
+
+        crate::export_session_archive(&self.db, session_id, &dest, mode, self.storage_root.as_deref(), &mut |_| {})?;
+        export_hooks::run_and_record(&self.db, session_id, &dest, "zip");
+        Ok(dest.to_string_lossy().to_string())
+    }
+
+    fn webhook(&self, session_id: &str, url: &str, completed: &[StepResult]) -> Result<String, String> {
+        let payload = {
+            let conn = self.db.lock().unwrap();
+            let session = SessionRepository::new(&conn)
+                .get(session_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            let bug_count = BugRepository::new(&conn)
+                .list_by_session(session_id)
+                .map_err(|e| e.to_string())?
+                .len();
+            serde_json::json!({
+                "event": "session.ended",
+                "sessionId": session.id,
+                "startedAt": session.started_at,
+                "endedAt": session.ended_at,
+                "folderPath": session.folder_path,
+                "bugCount": bug_count,
+                "steps": completed,
+            })
+        };
+
+        let response = reqwest::blocking::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?
+            .post(url)
+            .json(&payload)
+            .send()
+            .map_err(|e| format!("Webhook request failed: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Webhook returned HTTP {}", status));
+        }
+        Ok(status.to_string())
+    }
+}
+
+impl ActionRunner for RealActionRunner {
+    fn run(&self, session_id: &str, action: &PostSessionAction, completed: &[StepResult]) -> Result<String, String> {
+        match action {
+            PostSessionAction::GenerateSummary { include_ai_summary } => {
+                SessionSummaryGenerator::new(Arc::clone(&self.db)).generate_summary(session_id, *include_ai_summary)
+            }
+            PostSessionAction::ZipExport { destination, video_mode } => {
+                self.zip_export(session_id, destination, *video_mode)
+            }
+            PostSessionAction::HtmlExport => {
+                let path = SessionSummaryGenerator::new(Arc::clone(&self.db)).generate_html_report(session_id)?;
+                export_hooks::run_and_record(&self.db, session_id, std::path::Path::new(&path), "html");
+                Ok(path)
+            }
+            PostSessionAction::Webhook { url } => self.webhook(session_id, url, completed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct MockRunner {
+        calls: RefCell<Vec<(String, usize)>>,
+    }
+
+    impl ActionRunner for MockRunner {
+        fn run(&self, _session_id: &str, action: &PostSessionAction, completed: &[StepResult]) -> Result<String, String> {
+            self.calls.borrow_mut().push((action.kind().to_string(), completed.len()));
+            match action {
+                PostSessionAction::ZipExport { .. } => Err("disk full".to_string()),
+                _ => Ok(format!("{} done", action.kind())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_pipeline_continues_after_failure_and_reports_each_step() {
+        let actions = vec![
+            PostSessionAction::GenerateSummary { include_ai_summary: false },
+            PostSessionAction::ZipExport { destination: "/exports".to_string(), video_mode: None },
+            PostSessionAction::Webhook { url: "https://hooks.example.com".to_string() },
+        ];
+        let runner = MockRunner { calls: RefCell::new(Vec::new()) };
+        let mut progress = Vec::new();
+
+        let event = run_pipeline("s-1", &actions, &runner, |e| progress.push((e.steps.len(), e.complete)));
+
+        assert_eq!(progress, vec![(1, false), (2, false), (3, true)]);
+        assert_eq!(
+            *runner.calls.borrow(),
+            vec![("generate_summary".to_string(), 0), ("zip_export".to_string(), 1), ("webhook".to_string(), 2)]
+        );
+        assert!(event.complete);
+        assert!(event.steps[0].success);
+        assert_eq!(
+            event.steps[1],
+            StepResult {
+                action: "zip_export".to_string(),
+                success: false,
+                output: None,
+                error: Some("disk full".to_string()),
+            }
+        );
+        assert_eq!(event.steps[2].output.as_deref(), Some("webhook done"));
+    }
+
+    #[test]
+    fn test_profile_actions_follow_session_profile() {
+        use crate::database::{init_database, Session, SessionStatus};
+        use crate::profile::QaProfile;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        SqliteProfileRepository::new(&conn)
+            .create(&QaProfile {
+                id: "p-1".to_string(),
+                name: "Nightly".to_string(),
+                linear_config: None,
+                area_categories: vec![],
+                custom_fields: vec![],
+                title_conventions: None,
+                post_session_actions: vec![PostSessionAction::HtmlExport],
+                glossary: vec![],
+                glossary_style: Default::default(),
+                created_at: "2024-01-01T00:00:00Z".to_string(),
+                updated_at: "2024-01-01T00:00:00Z".to_string(),
+            })
+            .unwrap();
+        for (id, profile_id) in [("s-1", Some("p-1".to_string())), ("s-2", None)] {
+            SessionRepository::new(&conn)
+                .create(&Session {
+                    id: id.to_string(),
+                    started_at: "2024-01-01T10:00:00Z".to_string(),
+                    ended_at: None,
+                    status: SessionStatus::Ended,
+                    folder_path: format!("/tmp/{}", id),
+                    session_notes: None,
+                    environment_json: None,
+                    original_snip_path: None,
+                    created_at: "2024-01-01T10:00:00Z".to_string(),
+                    profile_id,
+                    unlocked_at: None,
+                    timezone: None,
+                })
+                .unwrap();
+        }
+
+        assert_eq!(profile_actions(&conn, "s-1"), vec![PostSessionAction::HtmlExport]);
+        assert!(profile_actions(&conn, "s-2").is_empty());
+        assert!(profile_actions(&conn, "missing").is_empty());
+    }
+}
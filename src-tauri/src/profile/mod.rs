@@ -8,3 +8,6 @@ pub use types::*;
 pub use repository::*;
 #[allow(unused_imports)]
 pub use seed::seed_default_profile;
+
+/// Settings key holding the id of the profile selected in the app.
+pub const ACTIVE_PROFILE_KEY: &str = "active_profile_id";
@@ -208,6 +208,9 @@ mod tests {
                 options: Some(vec!["low".to_string(), "medium".to_string(), "high".to_string()]),
             }],
             title_conventions: None,
+            post_session_actions: vec![],
+            glossary: vec![],
+            glossary_style: Default::default(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
         }
@@ -236,6 +236,10 @@ fn build_contio_profile() -> QaProfile {
             feature_prefix: "[QA Feature Suggestion] ".to_string(),
         }),
 
+        post_session_actions: vec![],
+        glossary: vec![],
+        glossary_style: Default::default(),
+
         created_at: now.clone(),
         updated_at: now,
     }
@@ -294,6 +298,9 @@ mod tests {
             area_categories: vec![],
             custom_fields: vec![],
             title_conventions: None,
+            post_session_actions: vec![],
+            glossary: vec![],
+            glossary_style: Default::default(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
         };
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::media_offload::VideoExportMode;
+
 /// QA testing profile — captures all configuration needed for a testing engagement
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -10,6 +12,14 @@ pub struct QaProfile {
     pub area_categories: Vec<AreaCategory>,
     pub custom_fields: Vec<CustomMetadataField>,
     pub title_conventions: Option<TitleConventions>,
+    /// Steps run automatically after a session using this profile ends
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_session_actions: Vec<PostSessionAction>,
+    /// Project abbreviations explained in rendered tickets and AI prompts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub glossary: Vec<GlossaryEntry>,
+    #[serde(default)]
+    pub glossary_style: GlossaryStyle,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -96,6 +106,58 @@ pub struct TitleConventions {
     pub feature_prefix: String,
 }
 
+/// A project-specific abbreviation, e.g. "CPQ" → "Configure, Price, Quote"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GlossaryEntry {
+    pub abbreviation: String,
+    pub expansion: String,
+}
+
+/// How abbreviations from the glossary are explained in rendered tickets
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GlossaryStyle {
+    /// Write the expansion after the first use: "CPQ (Configure, Price, Quote)"
+    #[default]
+    Expand,
+    /// List the abbreviations used in a glossary section at the end
+    Footnote,
+}
+
+/// A step of the post-session pipeline (see [`crate::post_session`])
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PostSessionAction {
+    /// Write session-summary.md
+    GenerateSummary {
+        #[serde(default)]
+        include_ai_summary: bool,
+    },
+    /// Write a session ZIP into `destination` (a directory)
+    ZipExport {
+        destination: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        video_mode: Option<VideoExportMode>,
+    },
+    /// Write session-report.html into the session folder
+    HtmlExport,
+    /// POST a JSON notice of the ended session (and the earlier steps' results) to `url`
+    Webhook { url: String },
+}
+
+impl PostSessionAction {
+    #[allow(dead_code)]
+    pub fn kind(&self) -> &str {
+        match self {
+            PostSessionAction::GenerateSummary { .. } => "generate_summary",
+            PostSessionAction::ZipExport { .. } => "zip_export",
+            PostSessionAction::HtmlExport => "html_export",
+            PostSessionAction::Webhook { .. } => "webhook",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,6 +201,18 @@ mod tests {
                 bug_prefix: "[BUG]".to_string(),
                 feature_prefix: "[FEAT]".to_string(),
             }),
+            post_session_actions: vec![
+                PostSessionAction::GenerateSummary { include_ai_summary: false },
+                PostSessionAction::ZipExport {
+                    destination: "D:\\qa-exports".to_string(),
+                    video_mode: Some(VideoExportMode::LinksOnly),
+                },
+            ],
+            glossary: vec![GlossaryEntry {
+                abbreviation: "CPQ".to_string(),
+                expansion: "Configure, Price, Quote".to_string(),
+            }],
+            glossary_style: GlossaryStyle::Footnote,
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
         };
@@ -148,6 +222,24 @@ mod tests {
         assert_eq!(profile, deserialized);
     }
 
+    #[test]
+    fn test_post_session_actions_default_and_tagging() {
+        let json = r#"{"id":"p","name":"P","linear_config":null,"area_categories":[],"custom_fields":[],
+            "title_conventions":null,"created_at":"","updated_at":""}"#;
+        let profile: QaProfile = serde_json::from_str(json).unwrap();
+        assert!(profile.post_session_actions.is_empty());
+        assert!(profile.glossary.is_empty());
+        assert_eq!(profile.glossary_style, GlossaryStyle::Expand);
+
+        let action: PostSessionAction =
+            serde_json::from_str(r#"{"type":"webhook","url":"https://hooks.example.com/qa"}"#).unwrap();
+        assert_eq!(action.kind(), "webhook");
+        assert_eq!(
+            serde_json::to_value(PostSessionAction::HtmlExport).unwrap(),
+            serde_json::json!({"type": "html_export"})
+        );
+    }
+
     #[test]
     fn test_custom_field_type_serde_lowercase() {
         let field_type = CustomFieldType::Select;
@@ -0,0 +1,111 @@
+//! On/off settings that can be flipped from the tray menu.
+//!
+//! Testers on a metered connection or in a screen-shared meeting want to
+//! silence AI calls or notifications without opening Settings. Each toggle is
+//! a `"true"`/`"false"` setting shown as a checkable tray item; flipping it
+//! from the tray saves the setting and emits `settings:quick-toggle-changed`
+//! so open windows pick it up, and saving it from Settings updates the tray
+//! item.
+
+use rusqlite::Connection;
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Whether Claude is called for suggestions (descriptions, severity, capture
+/// assignment, console parsing, alt text, translation).
+pub const AI_ENABLED_KEY: &str = "ai_enabled";
+
+/// Whether a screenshot marked as console output is parsed right away.
+pub const AUTO_CONSOLE_PARSE_KEY: &str = "console.auto_parse";
+
+/// Whether the app shows notifications (toasts, tray messages).
+pub const NOTIFICATIONS_KEY: &str = "notifications.enabled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuickToggle {
+    /// Tray menu item id
+    pub menu_id: &'static str,
+    pub key: &'static str,
+    pub label: &'static str,
+    /// Value while the setting was never saved
+    pub default: bool,
+}
+
+pub const AI: QuickToggle = QuickToggle {
+    menu_id: "toggle-ai",
+    key: AI_ENABLED_KEY,
+    label: "AI Suggestions",
+    // Matches the Settings screen, where AI is opt-in
+    default: false,
+};
+
+pub const QUICK_TOGGLES: &[QuickToggle] = &[
+    AI,
+    QuickToggle {
+        menu_id: "toggle-console-parse",
+        key: AUTO_CONSOLE_PARSE_KEY,
+        label: "Auto Console Parse",
+        default: true,
+    },
+    QuickToggle {
+        menu_id: "toggle-notifications",
+        key: NOTIFICATIONS_KEY,
+        label: "Notifications",
+        default: true,
+    },
+];
+
+pub fn by_menu_id(menu_id: &str) -> Option<&'static QuickToggle> {
+    QUICK_TOGGLES.iter().find(|t| t.menu_id == menu_id)
+}
+
+pub fn by_key(key: &str) -> Option<&'static QuickToggle> {
+    QUICK_TOGGLES.iter().find(|t| t.key == key)
+}
+
+impl QuickToggle {
+    pub fn is_on(&self, conn: &Connection) -> bool {
+        match SettingsRepository::new(conn).get(self.key).ok().flatten() {
+            Some(value) => value.trim().eq_ignore_ascii_case("true"),
+            None => self.default,
+        }
+    }
+
+    pub fn set(&self, conn: &Connection, on: bool) -> Result<(), String> {
+        SettingsRepository::new(conn)
+            .set(self.key, if on { "true" } else { "false" })
+            .map_err(|e| format!("Failed to save {}: {}", self.key, e))
+    }
+
+    /// Flip the setting; returns the new value.
+    pub fn toggle(&self, conn: &Connection) -> Result<bool, String> {
+        let on = !self.is_on(conn);
+        self.set(conn, on)?;
+        Ok(on)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::init_database;
+
+    #[test]
+    fn test_toggles_default_and_flip() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        assert!(!AI.is_on(&conn));
+        let notifications = by_menu_id("toggle-notifications").unwrap();
+        assert!(notifications.is_on(&conn));
+
+        assert!(AI.toggle(&conn).unwrap());
+        assert!(AI.is_on(&conn));
+        assert!(!notifications.toggle(&conn).unwrap());
+        assert_eq!(SettingsRepository::new(&conn).get(NOTIFICATIONS_KEY).unwrap().as_deref(), Some("false"));
+
+        // Values saved by the Settings screen are read the same way
+        SettingsRepository::new(&conn).set(AI_ENABLED_KEY, "false").unwrap();
+        assert!(!by_key(AI_ENABLED_KEY).unwrap().is_on(&conn));
+    }
+}
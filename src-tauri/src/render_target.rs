@@ -0,0 +1,517 @@
+//! Output formats for rendered bug reports.
+//!
+//! Bug templates are Markdown, which GitHub and Linear understand but a Jira
+//! text box or an email client shows as raw asterisks and hashes.
+//! [`RenderTarget::convert`] turns a rendered report into Jira wiki markup,
+//! HTML or plain text. It covers the Markdown templates use: headings, lists,
+//! fenced code, block quotes, rules, bold, italic, inline code, links and
+//! images. Anything else is kept as text.
+
+use serde::{Deserialize, Serialize};
+
+use crate::session_summary::html_escape;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RenderTarget {
+    #[default]
+    Markdown,
+    /// Jira wiki markup (`h2.`, `*bold*`, `{code}`), which the Jira editor
+    /// also converts when pasted
+    JiraWiki,
+    /// An HTML fragment, for email and rich text editors
+    Html,
+    Plain,
+}
+
+impl RenderTarget {
+    /// `markdown` in this format.
+    pub fn convert(self, markdown: &str) -> String {
+        match self {
+            RenderTarget::Markdown => markdown.to_string(),
+            RenderTarget::JiraWiki => to_jira_wiki(markdown),
+            RenderTarget::Html => to_html(markdown),
+            RenderTarget::Plain => to_plain(markdown),
+        }
+    }
+}
+
+enum Block<'a> {
+    Heading(usize, &'a str),
+    /// A list item; `number` is set for ordered lists
+    Item { indent: usize, number: Option<&'a str>, text: &'a str },
+    Quote(&'a str),
+    Code { language: &'a str, lines: Vec<&'a str> },
+    Rule,
+    Blank,
+    Text(&'a str),
+}
+
+fn blocks(markdown: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut lines = markdown.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        let block = if let Some(language) = trimmed.strip_prefix("```") {
+            // An unclosed fence runs to the end
+            let lines = lines.by_ref().take_while(|l| !l.trim_start().starts_with("```")).collect();
+            Block::Code { language: language.trim(), lines }
+        } else if trimmed.is_empty() {
+            Block::Blank
+        } else if let Some((level, text)) = heading(trimmed) {
+            Block::Heading(level, text)
+        } else if is_rule(trimmed) {
+            Block::Rule
+        } else if let Some(block) = item(line) {
+            block
+        } else if let Some(text) = trimmed.strip_prefix('>') {
+            Block::Quote(text.trim())
+        } else {
+            Block::Text(trimmed)
+        };
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// `## Title` → `(2, "Title")`
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let text = line[level..].strip_prefix(' ')?.trim();
+    ((1..=6).contains(&level) && !text.is_empty()).then_some((level, text))
+}
+
+/// `---`, `***` or `___`
+fn is_rule(line: &str) -> bool {
+    line.len() >= 3 && ['-', '*', '_'].iter().any(|&c| line.chars().all(|l| l == c))
+}
+
+/// `  - text` or `1. text`
+fn item(line: &str) -> Option<Block<'_>> {
+    let content = line.trim_start();
+    let indent = line[..line.len() - content.len()].chars().map(|c| if c == '\t' { 4 } else { 1 }).sum();
+    if let Some(text) = ["- ", "* ", "+ "].iter().find_map(|marker| content.strip_prefix(marker)) {
+        return Some(Block::Item { indent, number: None, text: text.trim() });
+    }
+    let digits = content.chars().take_while(char::is_ascii_digit).count();
+    let text = content[digits..].strip_prefix(". ")?;
+    (digits > 0).then(|| Block::Item { indent, number: Some(&content[..digits]), text: text.trim() })
+}
+
+/// Nesting levels of the items of one list, from their indentation.
+#[derive(Default)]
+struct ListLevels {
+    indents: Vec<usize>,
+}
+
+impl ListLevels {
+    /// 0-based level of an item indented by `indent`.
+    fn level(&mut self, indent: usize) -> usize {
+        while self.indents.last().is_some_and(|&last| last > indent) {
+            self.indents.pop();
+        }
+        if self.indents.last().is_none_or(|&last| last < indent) {
+            self.indents.push(indent);
+        }
+        self.indents.len() - 1
+    }
+}
+
+enum Span {
+    Text(String),
+    Strong(String),
+    Emphasis(String),
+    Code(String),
+    Link { text: String, url: String },
+    Image { alt: String, url: String },
+}
+
+fn spans(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut previous = None;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let escaped = rest[c.len_utf8()..].chars().next().filter(|e| c == '\\' && e.is_ascii_punctuation());
+        let length = if let Some(escaped) = escaped {
+            plain.push(escaped);
+            2
+        } else if let Some((span, length)) = span_at(rest, previous) {
+            if !plain.is_empty() {
+                spans.push(Span::Text(std::mem::take(&mut plain)));
+            }
+            spans.push(span);
+            length
+        } else {
+            plain.push(c);
+            c.len_utf8()
+        };
+        previous = rest[..length].chars().last();
+        rest = &rest[length..];
+    }
+    if !plain.is_empty() {
+        spans.push(Span::Text(plain));
+    }
+    spans
+}
+
+/// The span starting at `rest` and its length in bytes. `_` only marks
+/// emphasis at a word boundary, so `capture_001.png` stays as written.
+fn span_at(rest: &str, previous: Option<char>) -> Option<(Span, usize)> {
+    if let Some(inner) = rest.strip_prefix('`') {
+        let end = inner.find('`')?;
+        return (end > 0).then(|| (Span::Code(inner[..end].to_string()), end + 2));
+    }
+    if let Some(inner) = rest.strip_prefix("![") {
+        let (alt, url, length) = link(inner)?;
+        return Some((Span::Image { alt: alt.to_string(), url: url.to_string() }, length + 2));
+    }
+    if let Some(inner) = rest.strip_prefix('[') {
+        let (text, url, length) = link(inner)?;
+        return Some((Span::Link { text: text.to_string(), url: url.to_string() }, length + 1));
+    }
+    if rest.starts_with('_') && previous.is_some_and(char::is_alphanumeric) {
+        return None;
+    }
+    for marker in ["**", "__", "*", "_"] {
+        let Some(inner) = rest.strip_prefix(marker) else {
+            continue;
+        };
+        if inner.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let end = inner.find(marker)?;
+        let content = &inner[..end];
+        let after = &inner[end + marker.len()..];
+        if content.is_empty()
+            || content.ends_with(char::is_whitespace)
+            || (marker.starts_with('_') && after.starts_with(char::is_alphanumeric))
+        {
+            return None;
+        }
+        let span = if marker.len() == 2 {
+            Span::Strong(content.to_string())
+        } else {
+            Span::Emphasis(content.to_string())
+        };
+        return Some((span, end + 2 * marker.len()));
+    }
+    None
+}
+
+/// `text](url)` after the opening bracket → `(text, url, length)`
+fn link(rest: &str) -> Option<(&str, &str, usize)> {
+    let text_end = rest.find("](")?;
+    let url_start = text_end + 2;
+    let url_end = url_start + rest[url_start..].find(')')?;
+    let text = &rest[..text_end];
+    (!text.contains('[')).then(|| (text, rest[url_start..url_end].trim(), url_end + 1))
+}
+
+/// `text` with the characters Jira reads as markup (effects, macros, links,
+/// tables, images and lists) backslash-escaped.
+fn jira_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '*' | '_' | '-' | '+' | '^' | '~' | '?' | '|' | '!' | '#' | '{' | '}' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+fn jira_inline(text: &str) -> String {
+    spans(text)
+        .into_iter()
+        .map(|span| match span {
+            Span::Text(text) => jira_escape(&text),
+            Span::Strong(text) => format!("*{}*", jira_escape(&text)),
+            Span::Emphasis(text) => format!("_{}_", jira_escape(&text)),
+            Span::Code(text) => format!("{{{{{}}}}}", text),
+            Span::Link { text, url } if text.is_empty() || text == url => format!("[{}]", url),
+            Span::Link { text, url } => format!("[{}|{}]", jira_escape(&text), url),
+            Span::Image { url, .. } => format!("!{}!", url),
+        })
+        .collect()
+}
+
+fn html_inline(text: &str) -> String {
+    spans(text)
+        .into_iter()
+        .map(|span| match span {
+            Span::Text(text) => html_escape(&text),
+            Span::Strong(text) => format!("<strong>{}</strong>", html_escape(&text)),
+            Span::Emphasis(text) => format!("<em>{}</em>", html_escape(&text)),
+            Span::Code(text) => format!("<code>{}</code>", html_escape(&text)),
+            Span::Link { text, url } => {
+                let text = if text.is_empty() { &url } else { &text };
+                format!("<a href=\"{}\">{}</a>", html_escape(&url), html_escape(text))
+            }
+            Span::Image { alt, url } => format!("<img src=\"{}\" alt=\"{}\">", html_escape(&url), html_escape(&alt)),
+        })
+        .collect()
+}
+
+fn plain_inline(text: &str) -> String {
+    spans(text)
+        .into_iter()
+        .map(|span| match span {
+            Span::Text(text) | Span::Strong(text) | Span::Emphasis(text) | Span::Code(text) => text,
+            Span::Link { text, url } | Span::Image { alt: text, url } if text.is_empty() || text == url => url,
+            Span::Link { text, url } | Span::Image { alt: text, url } => format!("{} ({})", text, url),
+        })
+        .collect()
+}
+
+fn to_jira_wiki(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut levels = ListLevels::default();
+    for block in blocks(markdown) {
+        if !matches!(block, Block::Item { .. }) {
+            levels = ListLevels::default();
+        }
+        match block {
+            Block::Heading(level, text) => lines.push(format!("h{}. {}", level, jira_inline(text))),
+            Block::Item { indent, number, text } => {
+                let marker = if number.is_some() { "#" } else { "*" };
+                lines.push(format!("{} {}", marker.repeat(levels.level(indent) + 1), jira_inline(text)));
+            }
+            Block::Quote(text) => lines.push(format!("bq. {}", jira_inline(text))),
+            Block::Code { language, lines: code } => {
+                lines.push(if language.is_empty() { "{code}".to_string() } else { format!("{{code:{}}}", language) });
+                // Nothing is escaped inside a code block, so a `{code` tag in the code
+                // would end it; a zero-width space keeps the tag from being read
+                lines.extend(code.iter().map(|l| l.replace("{code", "{\u{200B}code")));
+                lines.push("{code}".to_string());
+            }
+            Block::Rule => lines.push("----".to_string()),
+            Block::Blank => lines.push(String::new()),
+            Block::Text(text) => lines.push(jira_inline(text)),
+        }
+    }
+    lines.join("\n")
+}
+
+fn to_plain(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    for block in blocks(markdown) {
+        match block {
+            Block::Heading(level, text) => {
+                let text = plain_inline(text);
+                let underline = match level {
+                    1 => Some('='),
+                    2 => Some('-'),
+                    _ => None,
+                };
+                let width = text.chars().count();
+                lines.push(text);
+                lines.extend(underline.map(|c| c.to_string().repeat(width)));
+            }
+            Block::Item { indent, number, text } => {
+                let marker = number.map(|n| format!("{}.", n)).unwrap_or_else(|| "•".to_string());
+                lines.push(format!("{}{} {}", " ".repeat(indent), marker, plain_inline(text)));
+            }
+            Block::Quote(text) => lines.push(format!("> {}", plain_inline(text))),
+            Block::Code { lines: code, .. } => lines.extend(code.iter().map(|l| l.to_string())),
+            Block::Rule => lines.push("-".repeat(20)),
+            Block::Blank => lines.push(String::new()),
+            Block::Text(text) => lines.push(plain_inline(text)),
+        }
+    }
+    lines.join("\n")
+}
+
+/// Open lists of an HTML list being written: tag and whether an `<li>` is
+/// open at that level.
+#[derive(Default)]
+struct HtmlList {
+    levels: ListLevels,
+    open: Vec<(&'static str, bool)>,
+}
+
+impl HtmlList {
+    fn item(&mut self, html: &mut String, indent: usize, ordered: bool, text: &str) {
+        let level = self.levels.level(indent);
+        let tag = if ordered { "ol" } else { "ul" };
+        while self.open.len() > level + 1 {
+            self.close_level(html);
+        }
+        if self.open.last().is_some_and(|&(open_tag, _)| self.open.len() == level + 1 && open_tag != tag) {
+            self.close_level(html);
+        }
+        if self.open.len() == level + 1 {
+            if let Some((_, li_open)) = self.open.last_mut() {
+                if *li_open {
+                    html.push_str("</li>\n");
+                }
+                *li_open = false;
+            }
+        }
+        while self.open.len() < level + 1 {
+            html.push_str(&format!("<{}>\n", tag));
+            self.open.push((tag, false));
+        }
+        html.push_str(&format!("<li>{}", html_inline(text)));
+        if let Some((_, li_open)) = self.open.last_mut() {
+            *li_open = true;
+        }
+    }
+
+    fn close_level(&mut self, html: &mut String) {
+        if let Some((tag, li_open)) = self.open.pop() {
+            if li_open {
+                html.push_str("</li>\n");
+            }
+            html.push_str(&format!("</{}>\n", tag));
+        }
+    }
+
+    fn close(&mut self, html: &mut String) {
+        while !self.open.is_empty() {
+            self.close_level(html);
+        }
+        self.levels = ListLevels::default();
+    }
+}
+
+fn to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    let mut list = HtmlList::default();
+    // Lines of the paragraph or quote being written
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut quote: Vec<String> = Vec::new();
+
+    let flush = |html: &mut String, lines: &mut Vec<String>, tag: &str| {
+        if !lines.is_empty() {
+            html.push_str(&format!("<{}>{}</{}>\n", tag, lines.join("<br>\n"), tag));
+            lines.clear();
+        }
+    };
+
+    for block in blocks(markdown) {
+        if !matches!(block, Block::Text(_)) {
+            flush(&mut html, &mut paragraph, "p");
+        }
+        if !matches!(block, Block::Quote(_)) {
+            flush(&mut html, &mut quote, "blockquote");
+        }
+        if !matches!(block, Block::Item { .. }) {
+            list.close(&mut html);
+        }
+        match block {
+            Block::Heading(level, text) => html.push_str(&format!("<h{}>{}</h{}>\n", level, html_inline(text), level)),
+            Block::Item { indent, number, text } => list.item(&mut html, indent, number.is_some(), text),
+            Block::Quote(text) => quote.push(html_inline(text)),
+            Block::Code { language, lines } => {
+                let class = if language.is_empty() {
+                    String::new()
+                } else {
+                    format!(" class=\"language-{}\"", html_escape(language))
+                };
+                html.push_str(&format!("<pre><code{}>{}</code></pre>\n", class, html_escape(&lines.join("\n"))));
+            }
+            Block::Rule => html.push_str("<hr>\n"),
+            Block::Blank => {}
+            Block::Text(text) => paragraph.push(html_inline(text)),
+        }
+    }
+    flush(&mut html, &mut paragraph, "p");
+    flush(&mut html, &mut quote, "blockquote");
+    list.close(&mut html);
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = "# Save fails\n\n\
+        **Type:** bug\n\n\
+        ## Environment\n\n\
+        - **OS:** Windows 11 _Pro_\n  \
+          - see [docs](https://example.com/os)\n\
+        - Folder: sessions/2024-05-14_3f2a/bug_001\n\n\
+        1. Open `editor.exe`\n\
+        2. Click {Save}\n\n\
+        ```js\n\
+        if (a < b) { save(); }\n\
+        ```";
+
+    #[test]
+    fn test_jira_wiki() {
+        assert_eq!(
+            RenderTarget::JiraWiki.convert(REPORT),
+            "h1. Save fails\n\n\
+             *Type:* bug\n\n\
+             h2. Environment\n\n\
+             * *OS:* Windows 11 _Pro_\n\
+             ** see [docs|https://example.com/os]\n\
+             * Folder: sessions/2024\\-05\\-14\\_3f2a/bug\\_001\n\n\
+             # Open {{editor.exe}}\n\
+             # Click \\{Save\\}\n\n\
+             {code:js}\n\
+             if (a < b) { save(); }\n\
+             {code}"
+        );
+    }
+
+    #[test]
+    fn test_jira_wiki_escapes_markup() {
+        assert_eq!(
+            RenderTarget::JiraWiki.convert("a+b ^ c~d -x- ??q?? | !img! #1"),
+            "a\\+b \\^ c\\~d \\-x\\- \\?\\?q\\?\\? \\| \\!img\\! \\#1"
+        );
+        assert_eq!(
+            RenderTarget::JiraWiki.convert("```\nprint(\"{code}\")\n```"),
+            "{code}\nprint(\"{\u{200B}code}\")\n{code}"
+        );
+    }
+
+    #[test]
+    fn test_html() {
+        assert_eq!(
+            RenderTarget::Html.convert(REPORT),
+            "<h1>Save fails</h1>\n\
+             <p><strong>Type:</strong> bug</p>\n\
+             <h2>Environment</h2>\n\
+             <ul>\n\
+             <li><strong>OS:</strong> Windows 11 <em>Pro</em><ul>\n\
+             <li>see <a href=\"https://example.com/os\">docs</a></li>\n\
+             </ul>\n\
+             </li>\n\
+             <li>Folder: sessions/2024-05-14_3f2a/bug_001</li>\n\
+             </ul>\n\
+             <ol>\n\
+             <li>Open <code>editor.exe</code></li>\n\
+             <li>Click {Save}</li>\n\
+             </ol>\n\
+             <pre><code class=\"language-js\">if (a &lt; b) { save(); }</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_plain_and_markdown() {
+        assert_eq!(
+            RenderTarget::Plain.convert(REPORT),
+            "Save fails\n==========\n\n\
+             Type: bug\n\n\
+             Environment\n-----------\n\n\
+             • OS: Windows 11 Pro\n  \
+               • see docs (https://example.com/os)\n\
+             • Folder: sessions/2024-05-14_3f2a/bug_001\n\n\
+             1. Open editor.exe\n\
+             2. Click {Save}\n\n\
+             if (a < b) { save(); }"
+        );
+        assert_eq!(RenderTarget::Markdown.convert(REPORT), REPORT);
+        assert_eq!(RenderTarget::Plain.convert("2 * 3 * 4, \\*literal\\*"), "2 * 3 * 4, *literal*");
+    }
+
+    #[test]
+    fn test_target_names() {
+        let target: RenderTarget = serde_json::from_str("\"jira-wiki\"").unwrap();
+        assert_eq!(target, RenderTarget::JiraWiki);
+        assert_eq!(serde_json::to_string(&RenderTarget::Plain).unwrap(), "\"plain\"");
+    }
+}
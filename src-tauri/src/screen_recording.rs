@@ -0,0 +1,292 @@
+//! Screen recordings of a monitor or window, written into the active bug.
+//!
+//! `start_recording` resolves the target to a screen rectangle and hands it
+//! to the platform's [`RecordingBridge`], which writes `recording-NNN.mp4`
+//! (see `capture_naming`) straight into the active bug folder, or
+//! `_unsorted/` when no bug is active. While it runs, `recording:progress`
+//! events drive the UI's timer. `stop_recording` finishes the file and adds
+//! it as a video capture; the capture watcher is not involved. Only one
+//! recording runs at a time.
+//!
+//! A window is recorded at the bounds it had when recording started.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::capture_routing::{self, CaptureSource, RoutingHandles};
+use crate::database::{BugOps, BugRepository, Capture, CaptureOps, CaptureRepository, Session};
+use crate::display_info::DisplaySnapshot;
+use crate::events;
+use crate::platform::{Recording, RecordingArea, RecordingBridge};
+
+/// Value of `CaptureSource::source` for recordings.
+pub const SOURCE: &str = "screen_recording";
+
+/// Frames per second unless the caller asks otherwise.
+pub const DEFAULT_FPS: u32 = 15;
+
+/// Highest accepted frame rate.
+pub const MAX_FPS: u32 = 60;
+
+/// How often `recording:progress` is emitted.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What `start_recording` records.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum RecordingTarget {
+    /// A monitor by index in the display snapshot; the one under the cursor when omitted
+    Monitor {
+        #[serde(default)]
+        index: Option<usize>,
+    },
+    /// A window by handle; the active window when omitted (Windows only)
+    Window {
+        #[serde(default)]
+        hwnd: Option<i64>,
+    },
+}
+
+/// The screen rectangle `target` covers now.
+pub fn resolve_area(target: &RecordingTarget, displays: Option<&DisplaySnapshot>) -> Result<RecordingArea, String> {
+    match target {
+        RecordingTarget::Monitor { index } => {
+            let displays = displays.ok_or("No monitors found")?;
+            let monitor = match index {
+                Some(i) => displays.monitors.get(*i).ok_or_else(|| format!("No monitor {}", i))?,
+                None => crate::native_capture::full_screen_monitor(displays).ok_or("No monitors found")?,
+            };
+            Ok(RecordingArea { x: monitor.x, y: monitor.y, width: monitor.width, height: monitor.height })
+        }
+        RecordingTarget::Window { hwnd } => {
+            let (x, y, width, height) = crate::window_capture::window_bounds(hwnd.map(|h| h as isize))?;
+            Ok(RecordingArea { x, y, width: width as u32, height: height as u32 })
+        }
+    }
+}
+
+/// The running recording as shown to the frontend.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingInfo {
+    pub recording_id: String,
+    pub session_id: String,
+    pub bug_id: Option<String>,
+    pub file_path: String,
+    pub duration_ms: i64,
+}
+
+/// A recording in progress, with what is needed to file it when it stops.
+pub struct ActiveRecording {
+    pub id: String,
+    pub session_id: String,
+    pub bug_id: Option<String>,
+    pub path: PathBuf,
+    area: RecordingArea,
+    source: CaptureSource,
+    started: Instant,
+    recording: Box<dyn Recording>,
+    app: AppHandle,
+    /// Dropping the sender ends the progress thread
+    _progress: mpsc::Sender<()>,
+}
+
+fn bug_folder(conn: &Connection, bug_id: &str) -> Option<PathBuf> {
+    BugRepository::new(conn).get(bug_id).ok()?.map(|bug| PathBuf::from(bug.folder_path))
+}
+
+fn duration_ms(started: Instant) -> i64 {
+    started.elapsed().as_millis() as i64
+}
+
+/// Start recording `area` into the bug the routing picks (else `_unsorted/`).
+pub fn start(
+    bridge: &dyn RecordingBridge,
+    conn: &Connection,
+    session: &Session,
+    routing: &RoutingHandles,
+    area: RecordingArea,
+    fps: u32,
+    app: &AppHandle,
+) -> Result<ActiveRecording, String> {
+    if fps == 0 || fps > MAX_FPS {
+        return Err(format!("Frame rate must be between 1 and {}", MAX_FPS));
+    }
+    let (bug_id, routed, ms_after_bug_end) = capture_routing::route(
+        routing.active_bug.lock().unwrap().clone(),
+        routing.recently_ended.lock().unwrap().as_ref(),
+        capture_routing::load_grace_window(conn),
+        Utc::now(),
+    );
+    let dest_dir = bug_id
+        .as_deref()
+        .and_then(|id| bug_folder(conn, id))
+        .unwrap_or_else(|| Path::new(&session.folder_path).join("_unsorted"));
+    std::fs::create_dir_all(&dest_dir).map_err(|e| format!("Cannot create {:?}: {}", dest_dir, e))?;
+
+    let naming = crate::capture_naming::NamingContext::for_folder(conn, &dest_dir);
+    let number = crate::next_capture_number(&dest_dir, &naming);
+    let (file_name, _) = crate::make_capture_filename(Path::new("recording.mp4"), number, &naming);
+    let path = dest_dir.join(file_name);
+
+    let recording = bridge.start(area, fps, &path).map_err(|e| e.to_string())?;
+    let id = Uuid::new_v4().to_string();
+    let started = Instant::now();
+
+    let (progress, stopped) = mpsc::channel::<()>();
+    {
+        let (id, path, app) = (id.clone(), path.clone(), app.clone());
+        thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(PROGRESS_INTERVAL) {
+                let _ = events::emit(
+                    &app,
+                    &events::RecordingProgress {
+                        recording_id: id.clone(),
+                        duration_ms: duration_ms(started),
+                        file_size_bytes: std::fs::metadata(&path).ok().map(|m| m.len()),
+                    },
+                );
+            }
+        });
+    }
+
+    let _ = events::emit(
+        app,
+        &events::RecordingStarted {
+            recording_id: id.clone(),
+            session_id: session.id.clone(),
+            bug_id: bug_id.clone(),
+            file_path: path.to_string_lossy().to_string(),
+        },
+    );
+
+    Ok(ActiveRecording {
+        id,
+        session_id: session.id.clone(),
+        bug_id,
+        path,
+        area: area.even(),
+        source: CaptureSource {
+            source: SOURCE.to_string(),
+            routing: routed,
+            ms_after_bug_end,
+            trigger: None,
+            ms_after_trigger: None,
+            trigger_bug_id: None,
+            display: None,
+            foreground: crate::platform::foreground_window(),
+        },
+        started,
+        recording,
+        app: app.clone(),
+        _progress: progress,
+    })
+}
+
+impl ActiveRecording {
+    pub fn info(&self) -> RecordingInfo {
+        RecordingInfo {
+            recording_id: self.id.clone(),
+            session_id: self.session_id.clone(),
+            bug_id: self.bug_id.clone(),
+            file_path: self.path.to_string_lossy().to_string(),
+            duration_ms: duration_ms(self.started),
+        }
+    }
+
+    /// Finish the file and insert its video capture record. `db` is locked
+    /// only for the insert, after the encoder has finished. Emits
+    /// `recording:stopped` either way.
+    pub fn stop(self, db: &Mutex<Connection>) -> Result<Capture, String> {
+        let duration = duration_ms(self.started);
+        let Self { id, session_id, bug_id, path, area, source, recording, app, .. } = self;
+
+        let result = recording.stop().map_err(|e| e.to_string()).and_then(|()| {
+            let capture = Capture {
+                id: Uuid::new_v4().to_string(),
+                bug_id,
+                session_id,
+                file_name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                file_path: path.to_string_lossy().to_string(),
+                file_type: crate::database::CaptureType::Video,
+                annotated_path: None,
+                file_size_bytes: std::fs::metadata(&path).ok().map(|m| m.len() as i64),
+                is_console_capture: false,
+                parsed_content: None,
+                created_at: Utc::now().to_rfc3339(),
+                edited_at: None,
+                media_link: None,
+                video_duration_ms: Some(duration),
+                video_width: Some(area.width as i64),
+                video_height: Some(area.height as i64),
+                video_codec: Some("h264".to_string()),
+                derived_from: None,
+                frame_timestamp_ms: None,
+                source_metadata: Some(source.to_json()),
+                alt_text: None,
+            };
+            CaptureRepository::new(&db.lock().unwrap())
+                .create(&capture)
+                .map_err(|e| format!("Failed to save recording record: {}", e))?;
+            Ok(capture)
+        });
+
+        let _ = events::emit(
+            &app,
+            &events::RecordingStopped {
+                recording_id: id,
+                file_path: path.to_string_lossy().to_string(),
+                duration_ms: duration,
+                capture_id: result.as_ref().ok().map(|c| c.id.clone()),
+                error: result.as_ref().err().cloned(),
+            },
+        );
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display_info::MonitorInfo;
+
+    #[test]
+    fn test_resolve_monitor_area() {
+        let displays = DisplaySnapshot {
+            monitors: vec![
+                MonitorInfo { name: None, x: 0, y: 0, width: 1920, height: 1080, scale_factor: 1.0, primary: true },
+                MonitorInfo { name: None, x: -1280, y: 0, width: 1280, height: 1023, scale_factor: 1.0, primary: false },
+            ],
+            active_monitor: Some(1),
+        };
+        let area = resolve_area(&RecordingTarget::Monitor { index: None }, Some(&displays)).unwrap();
+        assert_eq!(area, RecordingArea { x: -1280, y: 0, width: 1280, height: 1023 });
+        // Encoders need even sizes
+        assert_eq!(area.even().height, 1022);
+
+        let area = resolve_area(&RecordingTarget::Monitor { index: Some(0) }, Some(&displays)).unwrap();
+        assert_eq!(area.width, 1920);
+        assert_eq!(
+            resolve_area(&RecordingTarget::Monitor { index: Some(5) }, Some(&displays)).unwrap_err(),
+            "No monitor 5"
+        );
+        assert!(resolve_area(&RecordingTarget::Monitor { index: None }, None).is_err());
+    }
+
+    #[test]
+    fn test_target_serialization() {
+        let target: RecordingTarget = serde_json::from_str(r#"{"kind":"monitor"}"#).unwrap();
+        assert_eq!(target, RecordingTarget::Monitor { index: None });
+        let target: RecordingTarget = serde_json::from_str(r#"{"kind":"window","hwnd":1234}"#).unwrap();
+        assert_eq!(target, RecordingTarget::Window { hwnd: Some(1234) });
+    }
+}
@@ -0,0 +1,586 @@
+//! Session ZIP archives with an integrity manifest.
+//!
+//! Every exported archive carries a `manifest.json` listing each file with its
+//! size and SHA-256, plus any recordings that were linked rather than copied
+//! (see [`crate::media_offload::VideoExportMode`]). Recipients can run
+//! [`verify_archive`] to confirm nothing was lost or altered in transit.
+//!
+//! [`export_session`] also adds each bug's report rendered with the bug
+//! template as `report.md` in its folder, and reports progress while writing
+//! so large sessions with recordings can show a progress bar.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rusqlite::Connection;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::annotation_windows::TEMP_SUFFIX;
+use crate::database::{
+    BugOps, BugRepository, Capture, CaptureOps, CaptureRepository, CaptureType, SessionOps, SessionRepository,
+};
+use crate::media_offload::{plan_capture_export, ExportMediaAction, MediaOffload, VideoExportMode};
+
+/// Name of the manifest entry at the root of every archive.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Manifest format version, bumped on incompatible changes.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Name of the rendered bug report added to each bug folder in the archive.
+pub const BUG_REPORT_FILE: &str = "report.md";
+
+/// Bytes copied between progress reports within a single file.
+const PROGRESS_STEP: u64 = 8 * 1024 * 1024;
+
+/// A file stored in the archive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Path inside the archive, `/`-separated.
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// A recording referenced by link instead of being copied into the archive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestLink {
+    pub capture_id: String,
+    pub link: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub version: u32,
+    pub session_id: String,
+    pub created_at: String,
+    pub files: Vec<ManifestFile>,
+    #[serde(default)]
+    pub links: Vec<ManifestLink>,
+}
+
+/// A file on disk and where it goes in the archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveSource {
+    pub source: PathBuf,
+    pub archive_path: String,
+}
+
+/// A file generated at export time rather than read from disk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedFile {
+    pub archive_path: String,
+    pub content: Vec<u8>,
+}
+
+/// How far an archive has been written. Reported when each file starts and
+/// every few megabytes within large files, as the `session:export-progress`
+/// event (see `events`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveProgress {
+    pub session_id: String,
+    /// Path inside the archive of the file being added; empty once finished.
+    pub current: String,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+/// Result of checking an archive against its manifest.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ArchiveVerification {
+    /// True when every manifest entry is present and matches, and nothing extra was added.
+    pub valid: bool,
+    pub manifest_found: bool,
+    pub checked_files: usize,
+    /// Listed in the manifest but absent from the archive.
+    pub missing: Vec<String>,
+    /// Present but with a different size or hash.
+    pub mismatched: Vec<String>,
+    /// Present in the archive but not listed in the manifest.
+    pub unexpected: Vec<String>,
+}
+
+/// `/`-separated path of `path` relative to `root`, if it lies inside it.
+fn archive_path_under(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+fn walk_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            walk_files(&path, out);
+        } else if path.is_file() {
+            out.push(path);
+        }
+    }
+}
+
+/// Files to archive for a session: everything under `session_folder` (bug
+/// folders, notes, `.session.json`, summaries) except in-progress temp files,
+/// with recordings handled according to `mode`. Linked recordings are
+/// returned separately for the manifest.
+pub fn collect_session_sources(
+    session_folder: &Path,
+    captures: &[Capture],
+    mode: VideoExportMode,
+    offload: Option<&MediaOffload>,
+) -> (Vec<ArchiveSource>, Vec<ManifestLink>) {
+    let videos: Vec<&Capture> = captures
+        .iter()
+        .filter(|c| c.file_type == CaptureType::Video)
+        .collect();
+    let video_paths: HashSet<PathBuf> = videos.iter().map(|c| PathBuf::from(&c.file_path)).collect();
+
+    let mut files = Vec::new();
+    walk_files(session_folder, &mut files);
+    files.sort();
+
+    let mut sources: Vec<ArchiveSource> = files
+        .into_iter()
+        .filter(|path| !video_paths.contains(path))
+        .filter(|path| !path.to_string_lossy().ends_with(TEMP_SUFFIX))
+        .filter_map(|path| {
+            archive_path_under(session_folder, &path).map(|archive_path| ArchiveSource {
+                source: path,
+                archive_path,
+            })
+        })
+        .collect();
+
+    let mut links = Vec::new();
+    for capture in videos {
+        match plan_capture_export(capture, mode, offload) {
+            ExportMediaAction::Include(path) => {
+                let file_name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| capture.file_name.clone());
+                // Keep the recording next to its bug folder when it lives in the session
+                let archive_path = archive_path_under(session_folder, &PathBuf::from(&capture.file_path))
+                    .map(|p| match p.rsplit_once('/') {
+                        Some((dir, _)) => format!("{}/{}", dir, file_name),
+                        None => file_name.clone(),
+                    })
+                    .unwrap_or_else(|| format!("media/{}", file_name));
+                sources.push(ArchiveSource { source: path, archive_path });
+            }
+            ExportMediaAction::Link(link) => links.push(ManifestLink {
+                capture_id: capture.id.clone(),
+                link,
+            }),
+        }
+    }
+
+    (sources, links)
+}
+
+/// Copy `reader` to `writer`, returning the byte count and SHA-256 hex digest.
+fn copy_hashed<R: Read, W: Write>(reader: &mut R, writer: &mut W) -> std::io::Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        size += read as u64;
+    }
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+/// Calls `on_read` with the running byte count after each read.
+struct CountingReader<'a, R> {
+    inner: R,
+    count: u64,
+    on_read: &'a mut dyn FnMut(u64),
+}
+
+impl<R: Read> Read for CountingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read as u64;
+        (self.on_read)(self.count);
+        Ok(read)
+    }
+}
+
+/// Already-compressed media gains nothing from deflate.
+fn compression_for(path: &str) -> CompressionMethod {
+    let ext = path.rsplit('.').next().unwrap_or_default().to_lowercase();
+    match ext.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "mp4" | "webm" | "mkv" | "mov" | "avi" | "zip" => {
+            CompressionMethod::Stored
+        }
+        _ => CompressionMethod::Deflated,
+    }
+}
+
+/// Add `input` to `zip` as `archive_path`, reporting progress as it is copied.
+fn add_entry<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    archive_path: &str,
+    input: &mut dyn Read,
+    large: bool,
+    progress: &mut ArchiveProgress,
+    on_progress: &mut dyn FnMut(&ArchiveProgress),
+) -> Result<ManifestFile, String> {
+    progress.current = archive_path.to_string();
+    on_progress(progress);
+
+    let options = SimpleFileOptions::default()
+        .compression_method(compression_for(archive_path))
+        .large_file(large);
+    zip.start_file(archive_path, options)
+        .map_err(|e| format!("Failed to add {} to archive: {}", archive_path, e))?;
+    let start = progress.bytes_done;
+    let mut reported = 0;
+    let (size, sha256) = {
+        let mut on_read = |count: u64| {
+            if count - reported >= PROGRESS_STEP {
+                reported = count;
+                progress.bytes_done = start + count;
+                on_progress(progress);
+            }
+        };
+        let mut reader = CountingReader { inner: input, count: 0, on_read: &mut on_read };
+        copy_hashed(&mut reader, zip).map_err(|e| format!("Failed to add {} to archive: {}", archive_path, e))?
+    };
+    progress.bytes_done = start + size;
+    progress.files_done += 1;
+
+    Ok(ManifestFile {
+        path: archive_path.to_string(),
+        size,
+        sha256,
+    })
+}
+
+/// Write `sources` and `generated` to a new ZIP at `dest` followed by
+/// `manifest.json`, calling `on_progress` as files are added.
+pub fn write_archive(
+    dest: &Path,
+    session_id: &str,
+    sources: &[ArchiveSource],
+    generated: &[GeneratedFile],
+    links: Vec<ManifestLink>,
+    on_progress: &mut dyn FnMut(&ArchiveProgress),
+) -> Result<ArchiveManifest, String> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Cannot create folder {:?}: {}", parent, e))?;
+    }
+    let file = File::create(dest).map_err(|e| format!("Failed to create archive {:?}: {}", dest, e))?;
+    let mut zip = ZipWriter::new(file);
+
+    let source_sizes: Vec<u64> = sources
+        .iter()
+        .map(|s| std::fs::metadata(&s.source).map(|m| m.len()).unwrap_or(0))
+        .collect();
+    let mut progress = ArchiveProgress {
+        session_id: session_id.to_string(),
+        current: String::new(),
+        files_done: 0,
+        files_total: sources.len() + generated.len(),
+        bytes_done: 0,
+        bytes_total: source_sizes.iter().sum::<u64>() + generated.iter().map(|g| g.content.len() as u64).sum::<u64>(),
+    };
+
+    let mut files = Vec::with_capacity(progress.files_total);
+    for (source, size) in sources.iter().zip(source_sizes) {
+        let mut input = File::open(&source.source)
+            .map_err(|e| format!("Failed to open {:?}: {}", source.source, e))?;
+        let large = size >= u32::MAX as u64;
+        files.push(add_entry(&mut zip, &source.archive_path, &mut input, large, &mut progress, on_progress)?);
+    }
+    for file in generated {
+        let mut input = file.content.as_slice();
+        files.push(add_entry(&mut zip, &file.archive_path, &mut input, false, &mut progress, on_progress)?);
+    }
+
+    let manifest = ArchiveManifest {
+        version: MANIFEST_VERSION,
+        session_id: session_id.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        files,
+        links,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize {}: {}", MANIFEST_FILE, e))?;
+    zip.start_file(MANIFEST_FILE, SimpleFileOptions::default())
+        .and_then(|_| zip.write_all(&manifest_json).map_err(Into::into))
+        .map_err(|e| format!("Failed to write {}: {}", MANIFEST_FILE, e))?;
+    zip.finish().map_err(|e| format!("Failed to finish archive: {}", e))?;
+
+    progress.current.clear();
+    on_progress(&progress);
+    Ok(manifest)
+}
+
+/// A bug report added to exported archives. Loading reads what the report
+/// needs while the DB is locked; rendering happens after it is released.
+pub trait ArchiveReport: Sized {
+    fn load_report(bug_id: &str, conn: &Connection) -> Result<Self, String>;
+    fn render_report(&self) -> Result<String, String>;
+}
+
+/// Export a session's folder to a ZIP at `dest`, with each bug's report
+/// rendered through `R`. `mode` defaults to the `export.video_mode` setting;
+/// `storage_root` locates offloaded videos.
+pub fn export_session<R: ArchiveReport>(
+    db: &Mutex<Connection>,
+    session_id: &str,
+    dest: &Path,
+    mode: Option<VideoExportMode>,
+    storage_root: Option<&Path>,
+    on_progress: &mut dyn FnMut(&ArchiveProgress),
+) -> Result<ArchiveManifest, String> {
+    // Hold the DB lock only while gathering sources, not while rendering or compressing
+    let (session_folder, mut sources, report_inputs, links) = {
+        let conn = db.lock().unwrap();
+        let session = SessionRepository::new(&conn)
+            .get(session_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        let captures = CaptureRepository::new(&conn)
+            .list_by_session(session_id)
+            .map_err(|e| e.to_string())?;
+        let mode = mode.unwrap_or_else(|| VideoExportMode::from_settings(&conn));
+        let offload = storage_root.and_then(|root| MediaOffload::from_settings(&conn, root));
+
+        let session_folder = PathBuf::from(&session.folder_path);
+        let (sources, links) = collect_session_sources(&session_folder, &captures, mode, offload.as_ref());
+
+        let bugs = BugRepository::new(&conn)
+            .list_by_session(session_id)
+            .map_err(|e| e.to_string())?;
+        let report_inputs: Vec<_> = bugs
+            .into_iter()
+            .filter_map(|bug| match R::load_report(&bug.id, &conn) {
+                Ok(input) => Some((bug, input)),
+                Err(e) => {
+                    eprintln!("Warning: no report for {} in the archive: {}", bug.display_id, e);
+                    None
+                }
+            })
+            .collect();
+        (session_folder, sources, report_inputs, links)
+    };
+
+    let mut reports = Vec::with_capacity(report_inputs.len());
+    for (bug, input) in report_inputs {
+        let content = match input.render_report() {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Warning: no report for {} in the archive: {}", bug.display_id, e);
+                continue;
+            }
+        };
+        let folder = archive_path_under(&session_folder, Path::new(&bug.folder_path))
+            .unwrap_or_else(|| format!("reports/{}", bug.display_id));
+        reports.push(GeneratedFile {
+            archive_path: format!("{}/{}", folder, BUG_REPORT_FILE),
+            content: content.into_bytes(),
+        });
+    }
+    // A file of the same name in a bug folder gives way to the fresh report
+    sources.retain(|s| !reports.iter().any(|r| r.archive_path == s.archive_path));
+
+    if dest.starts_with(&session_folder) {
+        return Err("The archive cannot be written inside the session folder".to_string());
+    }
+
+    write_archive(dest, session_id, &sources, &reports, links, on_progress)
+}
+
+/// Read the manifest from an open archive.
+pub fn read_manifest<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<ArchiveManifest, String> {
+    let mut entry = archive
+        .by_name(MANIFEST_FILE)
+        .map_err(|_| format!("Archive has no {}", MANIFEST_FILE))?;
+    let mut text = String::new();
+    entry
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Failed to read {}: {}", MANIFEST_FILE, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", MANIFEST_FILE, e))
+}
+
+/// Check every file in the archive at `path` against its manifest.
+pub fn verify_archive(path: &Path) -> Result<ArchiveVerification, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open archive {:?}: {}", path, e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Not a valid ZIP archive: {}", e))?;
+
+    let manifest = match read_manifest(&mut archive) {
+        Ok(manifest) => manifest,
+        Err(_) => return Ok(ArchiveVerification::default()),
+    };
+
+    let mut result = ArchiveVerification {
+        manifest_found: true,
+        ..Default::default()
+    };
+    for expected in &manifest.files {
+        let mut entry = match archive.by_name(&expected.path) {
+            Ok(entry) => entry,
+            Err(_) => {
+                result.missing.push(expected.path.clone());
+                continue;
+            }
+        };
+        let (size, sha256) = copy_hashed(&mut entry, &mut std::io::sink())
+            .map_err(|e| format!("Failed to read {} from archive: {}", expected.path, e))?;
+        result.checked_files += 1;
+        if size != expected.size || sha256 != expected.sha256 {
+            result.mismatched.push(expected.path.clone());
+        }
+    }
+
+    let listed: HashSet<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+    result.unexpected = archive
+        .file_names()
+        .filter(|name| *name != MANIFEST_FILE && !name.ends_with('/') && !listed.contains(name))
+        .map(|name| name.to_string())
+        .collect();
+    result.unexpected.sort();
+
+    result.valid = result.missing.is_empty() && result.mismatched.is_empty() && result.unexpected.is_empty();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(id: &str, path: &Path) -> Capture {
+        Capture {
+            id: id.to_string(),
+            bug_id: Some("b-1".to_string()),
+            session_id: "s-1".to_string(),
+            file_name: "recording-002.mp4".to_string(),
+            file_path: path.to_string_lossy().to_string(),
+            file_type: CaptureType::Video,
+            created_at: "2024-01-01T10:00:00Z".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn session_folder(root: &Path) -> PathBuf {
+        let session = root.join("session");
+        let bug = session.join("bug_001");
+        std::fs::create_dir_all(&bug).unwrap();
+        std::fs::write(session.join(".session.json"), b"{}").unwrap();
+        std::fs::write(bug.join("capture-001.png"), b"png bytes").unwrap();
+        std::fs::write(bug.join("capture-001.png.annotating.tmp"), b"partial").unwrap();
+        std::fs::write(bug.join("recording-002.mp4"), b"video bytes").unwrap();
+        session
+    }
+
+    #[test]
+    fn test_collect_session_sources_respects_video_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = session_folder(dir.path());
+        let captures = vec![video("v-1", &session.join("bug_001/recording-002.mp4"))];
+
+        let (sources, links) = collect_session_sources(&session, &captures, VideoExportMode::IncludeAll, None);
+        let paths: Vec<&str> = sources.iter().map(|s| s.archive_path.as_str()).collect();
+        assert_eq!(paths, vec![".session.json", "bug_001/capture-001.png", "bug_001/recording-002.mp4"]);
+        assert!(links.is_empty());
+
+        let (sources, links) = collect_session_sources(&session, &captures, VideoExportMode::LinksOnly, None);
+        assert_eq!(sources.len(), 2);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].capture_id, "v-1");
+    }
+
+    #[test]
+    fn test_write_and_verify_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = session_folder(dir.path());
+        let (sources, links) = collect_session_sources(&session, &[], VideoExportMode::IncludeAll, None);
+        let dest = dir.path().join("out/session.zip");
+
+        let mut reported = Vec::new();
+        let generated = vec![GeneratedFile {
+            archive_path: format!("bug_001/{}", BUG_REPORT_FILE),
+            content: b"# BUG-001".to_vec(),
+        }];
+        let manifest =
+            write_archive(&dest, "s-1", &sources, &generated, links, &mut |p| reported.push(p.clone())).unwrap();
+        assert_eq!(manifest.files.len(), 4);
+        let png = manifest.files.iter().find(|f| f.path == "bug_001/capture-001.png").unwrap();
+        assert_eq!(png.size, 9);
+        assert_eq!(png.sha256.len(), 64);
+
+        // One report as each file starts, then a final one
+        assert_eq!(reported.len(), 5);
+        assert_eq!(reported[3].current, "bug_001/report.md");
+        let finished = reported.last().unwrap();
+        assert_eq!((finished.files_done, finished.files_total), (4, 4));
+        assert_eq!(finished.bytes_done, finished.bytes_total);
+        assert!(finished.current.is_empty());
+
+        let verification = verify_archive(&dest).unwrap();
+        assert!(verification.valid);
+        assert_eq!(verification.checked_files, 4);
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = session_folder(dir.path());
+        let (sources, _) = collect_session_sources(&session, &[], VideoExportMode::IncludeAll, None);
+        let original = dir.path().join("original.zip");
+        let manifest = write_archive(&original, "s-1", &sources, &[], vec![], &mut |_| {}).unwrap();
+
+        // Rebuild the archive with one file altered, one dropped and one added
+        let tampered = dir.path().join("tampered.zip");
+        let mut zip = ZipWriter::new(File::create(&tampered).unwrap());
+        zip.start_file("bug_001/capture-001.png", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"edited").unwrap();
+        zip.start_file("extra.txt", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"extra").unwrap();
+        zip.start_file(MANIFEST_FILE, SimpleFileOptions::default()).unwrap();
+        zip.write_all(&serde_json::to_vec(&manifest).unwrap()).unwrap();
+        zip.finish().unwrap();
+
+        let verification = verify_archive(&tampered).unwrap();
+        assert!(!verification.valid);
+        assert_eq!(verification.mismatched, vec!["bug_001/capture-001.png"]);
+        assert_eq!(verification.missing.len(), 2);
+        assert_eq!(verification.unexpected, vec!["extra.txt"]);
+    }
+
+    #[test]
+    fn test_verify_without_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plain.zip");
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        zip.start_file("a.txt", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"a").unwrap();
+        zip.finish().unwrap();
+
+        let verification = verify_archive(&path).unwrap();
+        assert!(!verification.manifest_found);
+        assert!(!verification.valid);
+    }
+}
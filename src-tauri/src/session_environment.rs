@@ -0,0 +1,274 @@
+//! Environment a session was recorded in, compared with the previous session.
+//!
+//! `environment_json` on a session is a flat JSON object. The OS version and
+//! GPU driver are collected when the session starts (best effort, see
+//! [`collect`]); the build under test and anything else are recorded with
+//! `set_session_environment`. When a tracked value differs from the previous
+//! session of the same profile, a `session:environment-changed` event is
+//! emitted and the session summary shows the differences, so a "regression"
+//! that is really a new driver or OS update is easy to spot.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::database::{Session, SessionOps, SessionRepository};
+
+/// Environment keys compared between sessions, with their display labels.
+pub const TRACKED_KEYS: &[(&str, &str)] = &[
+    ("build", "Build"),
+    ("os_version", "OS version"),
+    ("gpu_driver", "GPU driver"),
+];
+
+/// A tracked value that differs from the previous session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentChange {
+    pub key: String,
+    pub label: String,
+    pub previous: String,
+    pub current: String,
+}
+
+/// How a session's environment differs from the previous session of its profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentDiff {
+    pub previous_session_id: String,
+    pub previous_started_at: String,
+    pub changes: Vec<EnvironmentChange>,
+}
+
+impl EnvironmentDiff {
+    /// Markdown list of the changes, one line per value.
+    pub fn to_markdown(&self) -> String {
+        self.changes
+            .iter()
+            .map(|c| format!("- **{}:** {} → {}", c.label, c.previous, c.current))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Parse `environment_json`; anything but a JSON object counts as empty.
+pub fn parse(environment_json: Option<&str>) -> Map<String, Value> {
+    environment_json
+        .and_then(|json| serde_json::from_str::<Value>(json).ok())
+        .and_then(|value| match value {
+            Value::Object(map) => Some(map),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn text(map: &Map<String, Value>, key: &str) -> Option<String> {
+    match map.get(key)? {
+        Value::Null => None,
+        Value::String(s) if s.trim().is_empty() => None,
+        Value::String(s) => Some(s.trim().to_string()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Tracked values that are recorded in both environments and differ. A value
+/// missing on either side is unknown rather than changed.
+pub fn diff(previous: &Map<String, Value>, current: &Map<String, Value>) -> Vec<EnvironmentChange> {
+    TRACKED_KEYS
+        .iter()
+        .filter_map(|(key, label)| {
+            let (previous, current) = (text(previous, key)?, text(current, key)?);
+            (previous != current).then(|| EnvironmentChange {
+                key: key.to_string(),
+                label: label.to_string(),
+                previous,
+                current,
+            })
+        })
+        .collect()
+}
+
+/// The latest session of the same profile (or of no profile) that started
+/// before `session` and has a recorded environment.
+fn previous_session(conn: &Connection, session: &Session) -> Result<Option<Session>, String> {
+    let sessions = SessionRepository::new(conn)
+        .list()
+        .map_err(|e| format!("Failed to list sessions: {}", e))?;
+    Ok(sessions
+        .into_iter()
+        .filter(|s| {
+            s.id != session.id
+                && s.profile_id == session.profile_id
+                && s.started_at < session.started_at
+                && !parse(s.environment_json.as_deref()).is_empty()
+        })
+        .max_by(|a, b| a.started_at.cmp(&b.started_at)))
+}
+
+/// Compare a session's environment with the previous session of its profile.
+/// None when there is no previous session or nothing tracked changed.
+pub fn environment_diff(conn: &Connection, session_id: &str) -> Result<Option<EnvironmentDiff>, String> {
+    let session = SessionRepository::new(conn)
+        .get(session_id)
+        .map_err(|e| format!("Failed to get session: {}", e))?
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    let Some(previous) = previous_session(conn, &session)? else {
+        return Ok(None);
+    };
+    let changes = diff(
+        &parse(previous.environment_json.as_deref()),
+        &parse(session.environment_json.as_deref()),
+    );
+    if changes.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(EnvironmentDiff {
+        previous_session_id: previous.id,
+        previous_started_at: previous.started_at,
+        changes,
+    }))
+}
+
+/// Merge `values` into a session's environment; a null value removes the key.
+/// Returns the updated environment.
+pub fn merge_environment(
+    conn: &Connection,
+    session_id: &str,
+    values: Map<String, Value>,
+) -> Result<Map<String, Value>, String> {
+    let repo = SessionRepository::new(conn);
+    let mut session = repo
+        .get(session_id)
+        .map_err(|e| format!("Failed to get session: {}", e))?
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    let mut environment = parse(session.environment_json.as_deref());
+    for (key, value) in values {
+        if value.is_null() {
+            environment.remove(&key);
+        } else {
+            environment.insert(key, value);
+        }
+    }
+    session.environment_json = Some(Value::Object(environment.clone()).to_string());
+    repo.update(&session)
+        .map_err(|e| format!("Failed to update session: {}", e))?;
+    Ok(environment)
+}
+
+/// Environment values collected automatically when a session starts. Values
+/// that cannot be read on this machine are left out.
+pub fn collect() -> Map<String, Value> {
+    let mut environment = Map::new();
+    if let Some(os_version) = os_version() {
+        environment.insert("os_version".to_string(), Value::String(os_version));
+    }
+    if let Some(gpu_driver) = gpu_driver() {
+        environment.insert("gpu_driver".to_string(), Value::String(gpu_driver));
+    }
+    environment
+}
+
+/// e.g. "Windows 11 Pro 23H2 (build 22631)"
+#[cfg(windows)]
+fn os_version() -> Option<String> {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+    use winreg::RegKey;
+
+    let key = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(r"SOFTWARE\Microsoft\Windows NT\CurrentVersion")
+        .ok()?;
+    let product: String = key.get_value("ProductName").ok()?;
+    let display_version: Option<String> = key.get_value("DisplayVersion").ok();
+    let build: Option<String> = key.get_value("CurrentBuild").ok();
+    let mut version = product;
+    if let Some(display_version) = display_version {
+        version.push(' ');
+        version.push_str(&display_version);
+    }
+    if let Some(build) = build {
+        version.push_str(&format!(" (build {})", build));
+    }
+    Some(version)
+}
+
+/// `PRETTY_NAME` from os-release, e.g. "Ubuntu 24.04 LTS"
+#[cfg(not(windows))]
+fn os_version() -> Option<String> {
+    let release = std::fs::read_to_string("/etc/os-release").ok()?;
+    release
+        .lines()
+        .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+        .map(|name| name.trim_matches('"').to_string())
+}
+
+/// Description and driver version of the first display adapter, e.g.
+/// "NVIDIA GeForce RTX 3060 31.0.15.5222"
+#[cfg(windows)]
+fn gpu_driver() -> Option<String> {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+    use winreg::RegKey;
+
+    let key = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(r"SYSTEM\CurrentControlSet\Control\Class\{4d36e968-e325-11ce-bfc1-08002be10318}\0000")
+        .ok()?;
+    let description: String = key.get_value("DriverDesc").ok()?;
+    let version: String = key.get_value("DriverVersion").ok()?;
+    Some(format!("{} {}", description, version))
+}
+
+/// Only read on Windows.
+#[cfg(not(windows))]
+fn gpu_driver() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn env(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_diff_only_reports_known_changes() {
+        let previous = env(json!({ "build": "2.3.0", "os_version": "Windows 11 22H2", "ram": "16 GB" }));
+        let current = env(json!({ "build": "2.3.1", "os_version": "Windows 11 22H2", "gpu_driver": "31.0", "ram": "32 GB" }));
+
+        let changes = diff(&previous, &current);
+        assert_eq!(
+            changes,
+            vec![EnvironmentChange {
+                key: "build".to_string(),
+                label: "Build".to_string(),
+                previous: "2.3.0".to_string(),
+                current: "2.3.1".to_string(),
+            }]
+        );
+        assert!(parse(Some("not json")).is_empty());
+    }
+
+    #[test]
+    fn test_environment_diff_uses_previous_session_of_profile() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        conn.execute_batch(
+            r#"INSERT INTO sessions (id, started_at, folder_path, profile_id, environment_json) VALUES
+                 ('s-1', '2024-01-01T10:00:00Z', '/tmp/s-1', 'p-1', '{"gpu_driver":"30.0"}'),
+                 ('s-2', '2024-01-02T10:00:00Z', '/tmp/s-2', 'p-2', '{"gpu_driver":"32.0"}'),
+                 ('s-3', '2024-01-03T10:00:00Z', '/tmp/s-3', 'p-1', NULL);"#,
+        )
+        .unwrap();
+        assert_eq!(environment_diff(&conn, "s-3").unwrap(), None);
+
+        merge_environment(&conn, "s-3", env(json!({ "gpu_driver": "31.0", "build": "1.0" }))).unwrap();
+        let diff = environment_diff(&conn, "s-3").unwrap().unwrap();
+        assert_eq!(diff.previous_session_id, "s-1");
+        assert_eq!(diff.to_markdown(), "- **GPU driver:** 30.0 → 31.0");
+        assert_eq!(environment_diff(&conn, "s-1").unwrap(), None);
+
+        let environment = merge_environment(&conn, "s-3", env(json!({ "build": null }))).unwrap();
+        assert_eq!(environment, env(json!({ "gpu_driver": "31.0" })));
+    }
+}
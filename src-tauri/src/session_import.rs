@@ -0,0 +1,466 @@
+//! Importing a session ZIP written by [`crate::session_archive`].
+//!
+//! The archive is checked against its manifest and unpacked into a new folder
+//! under the storage root. The session, its bugs and their captures are then
+//! re-created from `.session.json` and the bug folders, so the session shows
+//! up in the session list ready for review. Session and bug IDs already used
+//! in this database are replaced by new ones (a bug's `metadata.json` is
+//! updated to match); capture IDs are always new. Recordings exported as links
+//! stay in the manifest and are not imported as captures.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+use zip::ZipArchive;
+
+use crate::archive_viewer::{find_bug_folder, SESSION_JSON_FILE};
+use crate::bug_metadata;
+use crate::capture_watcher::CaptureWatcher;
+use crate::database::{
+    Bug, BugOps, BugRepository, BugStatus, BugType, Capture, CaptureOps, CaptureRepository, CaptureType, Session,
+    SessionOps, SessionRepository, SessionStatus, UnitOfWork,
+};
+use crate::notes_mirror::{BUG_NOTES_FILE, SESSION_NOTES_FILE};
+use crate::session_archive::{verify_archive, MANIFEST_FILE};
+use crate::session_json::{BugJson, SessionJson};
+use crate::storage_paths::annotated_path_for;
+
+/// What an import created.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionImport {
+    pub session: Session,
+    pub bugs: usize,
+    pub captures: usize,
+    /// Session and bug IDs replaced because they were already in use.
+    pub reassigned_ids: usize,
+}
+
+/// Read `.session.json` from an open archive.
+fn read_session_json(archive: &mut ZipArchive<File>) -> Result<SessionJson, String> {
+    let mut entry = archive
+        .by_name(SESSION_JSON_FILE)
+        .map_err(|_| format!("Archive has no {}; is it a session export?", SESSION_JSON_FILE))?;
+    let mut text = String::new();
+    entry
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Failed to read {}: {}", SESSION_JSON_FILE, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", SESSION_JSON_FILE, e))
+}
+
+/// `root/name`, or `root/name_2`, `root/name_3`... when taken.
+fn unique_folder(root: &Path, name: &str) -> PathBuf {
+    let mut folder = root.join(name);
+    let mut n = 2;
+    while folder.exists() {
+        folder = root.join(format!("{}_{}", name, n));
+        n += 1;
+    }
+    folder
+}
+
+/// Name of the folder an archive is unpacked into: the session's start
+/// date and the first characters of its ID. Both come from the archive, so
+/// anything that is not a plain date or ID is replaced rather than used in a
+/// path.
+fn session_folder_name(started_at: &str, session_id: &str) -> String {
+    let date = started_at
+        .get(..10)
+        .filter(|date| {
+            date.char_indices()
+                .all(|(i, c)| if i == 4 || i == 7 { c == '-' } else { c.is_ascii_digit() })
+        })
+        .unwrap_or("imported");
+    let short_id: String = session_id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .take(8)
+        .collect();
+    if short_id.is_empty() {
+        format!("{}_{}", date, &Uuid::new_v4().simple().to_string()[..8])
+    } else {
+        format!("{}_{}", date, short_id)
+    }
+}
+
+/// Whether `name`, taken from the archive, names a file directly in its
+/// folder: no separators, no `..` and no root.
+fn is_plain_file_name(name: &str) -> bool {
+    Path::new(name).file_name().is_some_and(|file_name| file_name == name)
+}
+
+fn read_notes(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok().filter(|notes| !notes.trim().is_empty())
+}
+
+/// The bug row for `json`, with the fields `.session.json` folds into its
+/// metadata split back out.
+fn bug_from_json(json: &BugJson, id: String, number: i32, session: &Session, folder: &Path) -> Bug {
+    let mut metadata = match &json.metadata {
+        Value::Object(map) => map.clone(),
+        _ => serde_json::Map::new(),
+    };
+    let text = |value: Option<Value>| value.and_then(|v| v.as_str().map(String::from));
+    let meeting_id = text(metadata.remove("meetingId"));
+    let software_version = text(metadata.remove("softwareVersion"));
+    let console_parse_json = metadata.remove("consoleParse").map(|v| v.to_string());
+
+    Bug {
+        id,
+        session_id: session.id.clone(),
+        bug_number: number,
+        display_id: json.display_id.clone(),
+        bug_type: BugType::from_str(&json.bug_type).unwrap_or(BugType::Bug),
+        title: json.title.clone(),
+        notes: read_notes(&folder.join(BUG_NOTES_FILE)),
+        description: json.description.clone(),
+        ai_description: None,
+        status: BugStatus::Captured,
+        meeting_id,
+        software_version,
+        console_parse_json,
+        metadata_json: (!metadata.is_empty()).then(|| Value::Object(metadata).to_string()),
+        custom_metadata: None,
+        folder_path: folder.to_string_lossy().to_string(),
+        created_at: session.started_at.clone(),
+        updated_at: session.created_at.clone(),
+        external_ticket_id: None,
+        external_ticket_key: None,
+        external_ticket_url: None,
+        external_status: None,
+        external_status_category: None,
+    }
+}
+
+/// Capture rows for the screenshots and recordings `json` lists in `folder`.
+/// Annotated copies become the `annotated_path` of their original. Names
+/// that are not plain file names are skipped, so nothing outside `folder`
+/// is referenced.
+fn captures_in_folder(json: &BugJson, bug: &Bug, folder: &Path) -> Vec<Capture> {
+    let names: Vec<&String> = json.captures.iter().filter(|name| is_plain_file_name(name)).collect();
+    let annotated: Vec<PathBuf> = names
+        .iter()
+        .map(|name| annotated_path_for(&folder.join(name)))
+        .filter(|path| path.exists())
+        .collect();
+
+    names
+        .iter()
+        .map(|name| folder.join(name))
+        .filter(|path| path.is_file() && CaptureWatcher::is_media_file(path) && !annotated.contains(path))
+        .map(|path| {
+            let annotated_path = annotated_path_for(&path);
+            Capture {
+                id: Uuid::new_v4().to_string(),
+                bug_id: Some(bug.id.clone()),
+                session_id: bug.session_id.clone(),
+                file_name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                file_path: path.to_string_lossy().to_string(),
+                file_type: if CaptureWatcher::is_video_file(&path) {
+                    CaptureType::Video
+                } else {
+                    CaptureType::Screenshot
+                },
+                annotated_path: annotated
+                    .contains(&annotated_path)
+                    .then(|| annotated_path.to_string_lossy().to_string()),
+                file_size_bytes: std::fs::metadata(&path).ok().map(|m| m.len() as i64),
+                is_console_capture: false,
+                parsed_content: None,
+                created_at: bug.created_at.clone(),
+                edited_at: None,
+                media_link: None,
+                video_duration_ms: None,
+                video_width: None,
+                video_height: None,
+                video_codec: None,
+                derived_from: None,
+                frame_timestamp_ms: None,
+                source_metadata: None,
+                alt_text: None,
+            }
+        })
+        .collect()
+}
+
+/// Unpack the session archive at `archive_path` into a new folder under
+/// `storage_root` and re-create its rows. The archive is verified and
+/// unpacked before `db` is locked; the lock is only held to insert the rows.
+/// Nothing is kept if any step fails.
+pub fn import_archive(db: &Mutex<Connection>, archive_path: &Path, storage_root: &Path) -> Result<SessionImport, String> {
+    let verification = verify_archive(archive_path)?;
+    if !verification.manifest_found {
+        return Err(format!("Archive has no {}; is it a session export?", MANIFEST_FILE));
+    }
+    if !verification.valid {
+        return Err(format!(
+            "Archive failed verification: {} missing, {} altered and {} unexpected file(s)",
+            verification.missing.len(),
+            verification.mismatched.len(),
+            verification.unexpected.len()
+        ));
+    }
+
+    let file = File::open(archive_path).map_err(|e| format!("Failed to open archive {:?}: {}", archive_path, e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Not a valid ZIP archive: {}", e))?;
+    let json = read_session_json(&mut archive)?;
+
+    let folder = unique_folder(storage_root, &session_folder_name(&json.started_at, &json.id));
+    std::fs::create_dir_all(&folder).map_err(|e| format!("Cannot create session folder {:?}: {}", folder, e))?;
+    // Entry names are sanitised by the zip crate; nothing escapes `folder`
+    let imported = archive
+        .extract(&folder)
+        .map_err(|e| format!("Failed to extract archive: {}", e))
+        .and_then(|_| {
+            // A fresh manifest is written by the next export
+            let _ = std::fs::remove_file(folder.join(MANIFEST_FILE));
+            let mut conn = db.lock().unwrap();
+            insert_rows(&mut conn, &json, &folder)
+        });
+    if imported.is_err() {
+        let _ = std::fs::remove_dir_all(&folder);
+    }
+    imported
+}
+
+/// Create the rows for the session `json` unpacked into `folder`.
+fn insert_rows(conn: &mut Connection, json: &SessionJson, folder: &Path) -> Result<SessionImport, String> {
+    let mut reassigned_ids = 0;
+    let session_taken = SessionRepository::new(conn)
+        .get(&json.id)
+        .map_err(|e| e.to_string())?
+        .is_some();
+    let session_id = if session_taken || json.id.is_empty() {
+        reassigned_ids += 1;
+        Uuid::new_v4().to_string()
+    } else {
+        json.id.clone()
+    };
+
+    let uow = UnitOfWork::begin(conn).map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    // The session is not running on this machine
+    let status = match SessionStatus::from_str(&json.status) {
+        Ok(SessionStatus::Active) | Err(_) => SessionStatus::Ended,
+        Ok(status) => status,
+    };
+    let session = Session {
+        id: session_id,
+        started_at: json.started_at.clone(),
+        ended_at: json.ended_at.clone().or_else(|| Some(json.started_at.clone())),
+        status,
+        folder_path: folder.to_string_lossy().to_string(),
+        session_notes: read_notes(&folder.join(SESSION_NOTES_FILE)),
+        environment_json: json.environment.as_ref().map(|env| env.to_string()),
+        original_snip_path: None,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        profile_id: None,
+        unlocked_at: None,
+        timezone: json.timezone.clone(),
+    };
+    SessionRepository::new(uow.connection())
+        .create(&session)
+        .map_err(|e| format!("Failed to create session: {}", e))?;
+
+    let mut bugs = 0;
+    let mut captures = 0;
+    let mut numbers = Vec::new();
+    for bug_json in &json.bugs {
+        let bug_repo = BugRepository::new(uow.connection());
+        let id = if bug_json.id.is_empty() || bug_repo.get(&bug_json.id).map_err(|e| e.to_string())?.is_some() {
+            reassigned_ids += 1;
+            Uuid::new_v4().to_string()
+        } else {
+            bug_json.id.clone()
+        };
+        let number = bug_json
+            .display_id
+            .rsplit('-')
+            .next()
+            .and_then(|n| n.parse::<i32>().ok())
+            .filter(|n| !numbers.contains(n))
+            .unwrap_or_else(|| numbers.iter().max().copied().unwrap_or(0) + 1);
+        numbers.push(number);
+
+        let bug_folder = match find_bug_folder(folder, bug_json) {
+            Some(bug_folder) => bug_folder,
+            None => {
+                let bug_folder = folder.join(format!("bug_{:03}", number));
+                std::fs::create_dir_all(&bug_folder)
+                    .map_err(|e| format!("Cannot create bug folder {:?}: {}", bug_folder, e))?;
+                bug_folder
+            }
+        };
+        let bug = bug_from_json(bug_json, id, number, &session, &bug_folder);
+        bug_repo
+            .create(&bug)
+            .map_err(|e| format!("Failed to create {}: {}", bug.display_id, e))?;
+
+        let mut metadata = bug_metadata::read_metadata_object(&bug_folder);
+        if metadata.get("bug_id").and_then(|v| v.as_str()).is_some_and(|old| old != bug.id) {
+            metadata.insert("bug_id".to_string(), Value::String(bug.id.clone()));
+            bug_metadata::write_metadata_object(&bug_folder, metadata)?;
+        }
+
+        let capture_repo = CaptureRepository::new(uow.connection());
+        for capture in captures_in_folder(bug_json, &bug, &bug_folder) {
+            capture_repo
+                .create(&capture)
+                .map_err(|e| format!("Failed to add {}: {}", capture.file_name, e))?;
+            captures += 1;
+        }
+        bugs += 1;
+    }
+
+    // The other machine used these numbers; they stay taken even if an
+    // imported bug is deleted here
+    if let Some(last) = numbers.iter().max() {
+        BugRepository::new(uow.connection())
+            .reserve_bug_numbers(&session.id, 1, *last, Some("import"))
+            .map_err(|e| format!("Failed to reserve bug numbers: {}", e))?;
+    }
+
+    uow.commit().map_err(|e| format!("Failed to commit import: {}", e))?;
+    Ok(SessionImport { session, bugs, captures, reassigned_ids })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::init_database;
+    use crate::media_offload::VideoExportMode;
+    use crate::session_archive::{collect_session_sources, write_archive};
+
+    fn export(root: &Path) -> PathBuf {
+        let session = root.join("export");
+        let bug = session.join("bug_001");
+        std::fs::create_dir_all(&bug).unwrap();
+        std::fs::write(bug.join("capture-001.png"), b"png").unwrap();
+        std::fs::write(bug.join("capture-001_annotated.png"), b"annotated").unwrap();
+        std::fs::write(bug.join("recording-002.mp4"), b"video").unwrap();
+        std::fs::write(bug.join(BUG_NOTES_FILE), "Only on checkout").unwrap();
+        std::fs::write(bug.join(bug_metadata::METADATA_FILE), r#"{"bug_id": "b-1"}"#).unwrap();
+        std::fs::write(
+            session.join(SESSION_JSON_FILE),
+            r#"{
+                "id": "s-1", "startedAt": "2024-01-01T10:00:00Z", "endedAt": null,
+                "status": "active", "environment": {"os": "Windows 11"},
+                "bugs": [{"id": "b-1", "displayId": "BUG-001", "type": "feature", "title": "Crash",
+                          "description": "Steps", "metadata": {"softwareVersion": "2.1", "severity": "high"},
+                          "captures": ["capture-001.png", "capture-001_annotated.png", "metadata.json",
+                                       "notes.md", "recording-002.mp4"]}]
+            }"#,
+        )
+        .unwrap();
+
+        let (sources, links) = collect_session_sources(&session, &[], VideoExportMode::IncludeAll, None);
+        let dest = root.join("session.zip");
+        write_archive(&dest, "s-1", &sources, &[], links, &mut |_| {}).unwrap();
+        dest
+    }
+
+    #[test]
+    fn test_import_recreates_rows_and_replaces_taken_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = export(dir.path());
+        let storage = dir.path().join("storage");
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let db = Mutex::new(conn);
+
+        let first = import_archive(&db, &archive, &storage).unwrap();
+        assert_eq!(first.session.id, "s-1");
+        assert_eq!(first.session.status, SessionStatus::Ended);
+        assert_eq!((first.bugs, first.captures, first.reassigned_ids), (1, 2, 0));
+        assert!(!Path::new(&first.session.folder_path).join(MANIFEST_FILE).exists());
+        assert!(first.session.folder_path.ends_with("2024-01-01_s-1"));
+
+        let conn = db.lock().unwrap();
+        let bug = BugRepository::new(&conn).get("b-1").unwrap().unwrap();
+        assert_eq!(bug.bug_type, BugType::Feature);
+        assert_eq!(bug.software_version.as_deref(), Some("2.1"));
+        assert_eq!(bug.metadata_json.as_deref(), Some(r#"{"severity":"high"}"#));
+        assert_eq!(bug.notes.as_deref(), Some("Only on checkout"));
+        let captures = CaptureRepository::new(&conn).list_by_bug("b-1").unwrap();
+        let screenshot = captures.iter().find(|c| c.file_type == CaptureType::Screenshot).unwrap();
+        assert!(screenshot.annotated_path.as_deref().unwrap().ends_with("capture-001_annotated.png"));
+        assert!(captures.iter().any(|c| c.file_type == CaptureType::Video));
+
+        drop(conn);
+
+        // Importing again keeps both copies apart
+        let second = import_archive(&db, &archive, &storage).unwrap();
+        assert_ne!(second.session.id, "s-1");
+        assert_ne!(second.session.folder_path, first.session.folder_path);
+        assert_eq!(second.reassigned_ids, 2);
+        let conn = db.lock().unwrap();
+        let bugs = BugRepository::new(&conn).list_by_session(&second.session.id).unwrap();
+        assert_eq!(bugs.len(), 1);
+        let metadata = bug_metadata::read_metadata_object(Path::new(&bugs[0].folder_path));
+        assert_eq!(metadata["bug_id"], bugs[0].id.as_str());
+        assert_eq!(SessionRepository::new(&conn).list().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_import_reserves_the_imported_bug_numbers() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = export(dir.path());
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let db = Mutex::new(conn);
+
+        let import = import_archive(&db, &archive, &dir.path().join("storage")).unwrap();
+        let conn = db.lock().unwrap();
+        let repo = BugRepository::new(&conn);
+        repo.delete("b-1").unwrap();
+        assert_eq!(repo.get_next_bug_number(&import.session.id).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_import_rejects_damaged_archive() {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let dir = tempfile::tempdir().unwrap();
+        let archive = export(dir.path());
+        // Keep only the manifest and .session.json, so the captures are missing
+        let damaged = dir.path().join("damaged.zip");
+        let mut source = ZipArchive::new(File::open(&archive).unwrap()).unwrap();
+        let mut zip = zip::ZipWriter::new(File::create(&damaged).unwrap());
+        for name in [MANIFEST_FILE, SESSION_JSON_FILE] {
+            let mut content = Vec::new();
+            source.by_name(name).unwrap().read_to_end(&mut content).unwrap();
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(&content).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let storage = dir.path().join("storage");
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let db = Mutex::new(conn);
+        let err = import_archive(&db, &damaged, &storage).unwrap_err();
+        assert!(err.contains("5 missing"), "{}", err);
+        assert!(SessionRepository::new(&db.lock().unwrap()).list().unwrap().is_empty());
+        assert!(!storage.exists());
+    }
+
+    #[test]
+    fn test_archive_names_never_leave_the_storage_root() {
+        assert_eq!(session_folder_name("2024-01-01T10:00:00Z", "s-1"), "2024-01-01_s-1");
+        assert!(session_folder_name("../../../etc/x", "s-1").starts_with("imported_"));
+        assert_eq!(session_folder_name("2024-01-01", "../../evil"), "2024-01-01_evil");
+        let generated = session_folder_name("/abs/path/x", "/..");
+        assert!(generated.starts_with("imported_") && !generated.contains(['/', '\\', '.']));
+
+        assert!(is_plain_file_name("capture-001.png"));
+        for name in ["../secret.png", "/etc/passwd", "sub/capture.png", "..", ".", ""] {
+            assert!(!is_plain_file_name(name), "{}", name);
+        }
+    }
+}
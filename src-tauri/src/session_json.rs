@@ -24,6 +24,9 @@ pub struct SessionJson {
     pub id: String,
     pub started_at: String,
     pub ended_at: Option<String>,
+    /// IANA time zone the session was recorded in (timestamps remain UTC)
+    #[serde(default)]
+    pub timezone: Option<String>,
     pub status: String,
     pub environment: Option<Value>,
     pub bugs: Vec<BugJson>,
@@ -111,6 +114,7 @@ impl SessionJsonWriter {
             id: session.id.clone(),
             started_at: session.started_at.clone(),
             ended_at: session.ended_at.clone(),
+            timezone: session.timezone.clone(),
             status: session.status.as_str().to_string(),
             environment,
             bugs: bug_jsons,
@@ -238,6 +242,8 @@ mod tests {
             original_snip_path: None,
             created_at: "2024-01-15T10:00:00Z".to_string(),
             profile_id: None,
+            unlocked_at: None,
+            timezone: None,
         };
         SessionRepository::new(conn).create(&session).unwrap();
         session
@@ -252,18 +258,14 @@ mod tests {
             display_id: format!("BUG-{:03}", number),
             bug_type: BugType::Bug,
             title: Some(format!("Bug {}", number)),
-            notes: None,
             description: Some(format!("Description of bug {}", number)),
-            ai_description: None,
             status: BugStatus::Captured,
             meeting_id: Some("meet-123".to_string()),
             software_version: Some("1.0.0".to_string()),
-            console_parse_json: None,
-            metadata_json: None,
-            custom_metadata: None,
             folder_path: format!("/tmp/test-session/bug_{:03}", number),
             created_at: "2024-01-15T10:15:00Z".to_string(),
             updated_at: "2024-01-15T10:15:00Z".to_string(),
+            ..Default::default()
         };
         BugRepository::new(conn).create(&bug).unwrap();
         bug
@@ -370,18 +372,12 @@ mod tests {
             display_id: "BUG-001".to_string(),
             bug_type: BugType::Bug,
             title: Some("AI bug".to_string()),
-            notes: None,
-            description: None,
             ai_description: Some("AI-generated description".to_string()),
             status: BugStatus::Captured,
-            meeting_id: None,
-            software_version: None,
-            console_parse_json: None,
-            metadata_json: None,
-            custom_metadata: None,
             folder_path: "/tmp/test-session/bug_001".to_string(),
             created_at: "2024-01-15T10:15:00Z".to_string(),
             updated_at: "2024-01-15T10:15:00Z".to_string(),
+            ..Default::default()
         };
         BugRepository::new(&db_conn.lock().unwrap()).create(&bug).unwrap();
 
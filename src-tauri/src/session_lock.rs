@@ -0,0 +1,246 @@
+//! Review lock for sessions.
+//!
+//! Once a session reaches `Reviewed` or `Synced` its evidence has usually been
+//! exported or filed, so commands that mutate notes, captures or descriptions
+//! are rejected until the session is explicitly unlocked with
+//! [`unlock_session`]. Every unlock is written to the audit log.
+
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::database::{
+    record_audit, BugOps, BugRepository, CaptureOps, CaptureRepository, Session, SessionOps,
+    SessionRepository,
+};
+
+/// Return an error if the session is locked. Unknown sessions are treated as
+/// editable so the calling command can report its own "not found" error.
+pub fn ensure_session_editable(conn: &Connection, session_id: &str) -> Result<(), String> {
+    let session = SessionRepository::new(conn)
+        .get(session_id)
+        .map_err(|e| format!("Failed to get session: {}", e))?;
+
+    match session {
+        Some(session) if session.is_locked() => Err(locked_error(&session)),
+        _ => Ok(()),
+    }
+}
+
+/// Return an error if the session owning `bug_id` is locked.
+pub fn ensure_bug_editable(conn: &Connection, bug_id: &str) -> Result<(), String> {
+    let bug = BugRepository::new(conn)
+        .get(bug_id)
+        .map_err(|e| format!("Failed to get bug: {}", e))?;
+
+    match bug {
+        Some(bug) => ensure_session_editable(conn, &bug.session_id),
+        None => Ok(()),
+    }
+}
+
+/// Return an error if the session owning `capture_id` is locked.
+pub fn ensure_capture_editable(conn: &Connection, capture_id: &str) -> Result<(), String> {
+    let capture = CaptureRepository::new(conn)
+        .get(capture_id)
+        .map_err(|e| format!("Failed to get capture: {}", e))?;
+
+    match capture {
+        Some(capture) => ensure_session_editable(conn, &capture.session_id),
+        None => Ok(()),
+    }
+}
+
+/// Return an error if the bug stored at `folder_path` belongs to a locked session.
+/// Used by commands that only receive the bug folder rather than its ID.
+pub fn ensure_bug_folder_editable(conn: &Connection, folder_path: &str) -> Result<(), String> {
+    let session_id: Option<String> = conn
+        .query_row(
+            "SELECT session_id FROM bugs WHERE folder_path = ?1",
+            params![folder_path],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to look up bug folder: {}", e))?;
+
+    match session_id {
+        Some(id) => ensure_session_editable(conn, &id),
+        None => Ok(()),
+    }
+}
+
+/// Unlock a reviewed/synced session for editing and record an audit entry.
+///
+/// Unlocking a session that is not locked is a no-op (no audit entry is written).
+pub fn unlock_session(
+    conn: &Connection,
+    session_id: &str,
+    reason: Option<&str>,
+) -> Result<Session, String> {
+    let repo = SessionRepository::new(conn);
+    let mut session = repo
+        .get(session_id)
+        .map_err(|e| format!("Failed to get session: {}", e))?
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+    if !session.is_locked() {
+        return Ok(session);
+    }
+
+    let now = Utc::now().to_rfc3339();
+    repo.unlock(session_id, &now)
+        .map_err(|e| format!("Failed to unlock session: {}", e))?;
+
+    record_audit(
+        conn,
+        "session.unlock",
+        "session",
+        session_id,
+        Some(serde_json::json!({
+            "status": session.status.as_str(),
+            "reason": reason,
+        })),
+    )?;
+
+    session.unlocked_at = Some(now);
+    Ok(session)
+}
+
+fn locked_error(session: &Session) -> String {
+    format!(
+        "Session {} is locked because it has been {}. Unlock it before making changes.",
+        session.id,
+        session.status.as_str()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{
+        AuditOps, AuditRepository, Bug, BugStatus, BugType, Capture, CaptureType, Database,
+        SessionStatus,
+    };
+
+    fn seed(conn: &Connection, status: SessionStatus) {
+        SessionRepository::new(conn)
+            .create(&Session {
+                id: "s-1".to_string(),
+                started_at: "2024-01-01T10:00:00Z".to_string(),
+                ended_at: None,
+                status,
+                folder_path: "/tmp/s-1".to_string(),
+                session_notes: None,
+                environment_json: None,
+                original_snip_path: None,
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                profile_id: None,
+                unlocked_at: None,
+                timezone: None,
+            })
+            .unwrap();
+        BugRepository::new(conn)
+            .create(&Bug {
+                id: "b-1".to_string(),
+                session_id: "s-1".to_string(),
+                bug_number: 1,
+                display_id: "BUG-001".to_string(),
+                bug_type: BugType::Bug,
+                status: BugStatus::Captured,
+                folder_path: "/tmp/s-1/bug_001".to_string(),
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                updated_at: "2024-01-01T10:00:00Z".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        CaptureRepository::new(conn)
+            .create(&Capture {
+                id: "c-1".to_string(),
+                bug_id: Some("b-1".to_string()),
+                session_id: "s-1".to_string(),
+                file_name: "capture-001.png".to_string(),
+                file_path: "/tmp/s-1/bug_001/capture-001.png".to_string(),
+                file_type: CaptureType::Screenshot,
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_ended_session_is_editable() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        seed(conn, SessionStatus::Ended);
+
+        assert!(ensure_session_editable(conn, "s-1").is_ok());
+        assert!(ensure_bug_editable(conn, "b-1").is_ok());
+        assert!(ensure_capture_editable(conn, "c-1").is_ok());
+        assert!(ensure_bug_folder_editable(conn, "/tmp/s-1/bug_001").is_ok());
+    }
+
+    #[test]
+    fn test_reviewed_session_rejects_edits() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        seed(conn, SessionStatus::Reviewed);
+
+        let err = ensure_session_editable(conn, "s-1").unwrap_err();
+        assert!(err.contains("locked"));
+        assert!(ensure_bug_editable(conn, "b-1").is_err());
+        assert!(ensure_capture_editable(conn, "c-1").is_err());
+        assert!(ensure_bug_folder_editable(conn, "/tmp/s-1/bug_001").is_err());
+    }
+
+    #[test]
+    fn test_synced_session_rejects_edits() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        seed(conn, SessionStatus::Synced);
+
+        assert!(ensure_bug_editable(conn, "b-1").is_err());
+    }
+
+    #[test]
+    fn test_unknown_ids_are_not_locked() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+
+        assert!(ensure_session_editable(conn, "missing").is_ok());
+        assert!(ensure_bug_editable(conn, "missing").is_ok());
+        assert!(ensure_capture_editable(conn, "missing").is_ok());
+        assert!(ensure_bug_folder_editable(conn, "/nowhere").is_ok());
+    }
+
+    #[test]
+    fn test_unlock_session_allows_edits_and_is_audited() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        seed(conn, SessionStatus::Reviewed);
+
+        let session = unlock_session(conn, "s-1", Some("fix typo")).unwrap();
+        assert!(session.unlocked_at.is_some());
+        assert!(ensure_bug_editable(conn, "b-1").is_ok());
+
+        let entries = AuditRepository::new(conn).list_for_entity("session", "s-1").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, "session.unlock");
+        assert!(entries[0].details.as_deref().unwrap().contains("fix typo"));
+    }
+
+    #[test]
+    fn test_unlock_unlocked_session_is_noop() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        seed(conn, SessionStatus::Ended);
+
+        let session = unlock_session(conn, "s-1", None).unwrap();
+        assert!(session.unlocked_at.is_none());
+        assert!(AuditRepository::new(conn).list_for_entity("session", "s-1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unlock_missing_session_errors() {
+        let db = Database::in_memory().unwrap();
+        assert!(unlock_session(db.connection(), "missing", None).is_err());
+    }
+}
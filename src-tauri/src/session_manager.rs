@@ -1,18 +1,32 @@
 use chrono::Utc;
 use rusqlite::Connection;
-use serde_json::json;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
-use crate::database::{Bug, BugStatus, BugType, Session, SessionStatus};
-use crate::database::{BugOps, BugRepository, SessionOps, SessionRepository};
+use crate::bug_timing;
+use crate::capture_routing::{EndedBug, RoutingHandles, SharedEndedBug};
+use crate::capture_trigger::{self, PendingCapture, SharedPendingCaptures, TriggerSource};
+use crate::events;
+use crate::database::{Bug, BugLinkKind, BugStatus, BugType, Session, SessionStatus};
+use crate::database::{
+    record_audit, BugLinkOps, BugLinkRepository, BugOps, BugRepository, CaptureOps, CaptureRepository, SessionOps,
+    SessionRepository, UnitOfWork,
+};
+use crate::session_environment;
 use crate::session_json::SessionJsonWriter;
 use crate::session_summary::SessionSummaryGenerator;
 
 // Type alias for the shared connection handle
 type SharedConn = Arc<Mutex<Connection>>;
 
+/// Display ID of scratch bugs; without a `-NNN` suffix so bug numbering
+/// ignores them.
+pub const SCRATCH_DISPLAY_ID: &str = "SCRATCH";
+
+/// Session subfolder holding scratch bug folders.
+pub const SCRATCH_FOLDER: &str = "_scratch";
+
 /// Trait for emitting Tauri events
 pub trait EventEmitter: Send + Sync {
     fn emit(&self, event: &str, payload: serde_json::Value) -> Result<(), String>;
@@ -21,6 +35,9 @@ pub trait EventEmitter: Send + Sync {
 /// Trait for filesystem operations
 pub trait FileSystem: Send + Sync {
     fn create_dir_all(&self, path: &Path) -> Result<(), String>;
+    /// Remove an empty directory (used to undo a failed operation)
+    fn remove_dir(&self, path: &Path) -> Result<(), String>;
+    fn rename(&self, from: &Path, to: &Path) -> Result<(), String>;
 }
 
 /// Real filesystem implementation
@@ -30,6 +47,14 @@ impl FileSystem for RealFileSystem {
     fn create_dir_all(&self, path: &Path) -> Result<(), String> {
         std::fs::create_dir_all(path).map_err(|e| format!("Failed to create directory: {}", e))
     }
+
+    fn remove_dir(&self, path: &Path) -> Result<(), String> {
+        std::fs::remove_dir(path).map_err(|e| format!("Failed to remove directory: {}", e))
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<(), String> {
+        std::fs::rename(from, to).map_err(|e| format!("Failed to rename directory: {}", e))
+    }
 }
 
 /// Session Manager handles session lifecycle and bug capture operations
@@ -40,6 +65,8 @@ pub struct SessionManager {
     filesystem: Arc<dyn FileSystem>,
     active_session: Arc<Mutex<Option<String>>>,
     active_bug: Arc<Mutex<Option<String>>>,
+    recently_ended: SharedEndedBug,
+    pending_captures: SharedPendingCaptures,
 }
 
 impl SessionManager {
@@ -56,9 +83,28 @@ impl SessionManager {
             filesystem,
             active_session: Arc::new(Mutex::new(None)),
             active_bug: Arc::new(Mutex::new(None)),
+            recently_ended: Arc::new(Mutex::new(None)),
+            pending_captures: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
+    /// Create `path` as part of `uow`, removing it again if the work is rolled back.
+    fn create_dir_in(&self, uow: &mut UnitOfWork, path: &Path) -> Result<(), String> {
+        self.filesystem.create_dir_all(path)?;
+        let (filesystem, path) = (Arc::clone(&self.filesystem), path.to_path_buf());
+        uow.on_rollback(move || {
+            if let Err(e) = filesystem.remove_dir(&path) {
+                eprintln!("Warning: Failed to clean up {}: {}", path.display(), e);
+            }
+        });
+        Ok(())
+    }
+
+    fn emit_event<E: events::AppEvent>(&self, event: &E) -> Result<(), String> {
+        let payload = serde_json::to_value(event).map_err(|e| format!("Failed to serialize {}: {}", E::NAME, e))?;
+        self.event_emitter.emit(E::NAME, payload)
+    }
+
     /// Start a new QA session.
     ///
     /// `profile_id` is the ID of the QA profile that was active when the session
@@ -80,16 +126,7 @@ impl SessionManager {
         let folder_name = format!("{}_{}", date_str, short_id);
         let folder_path = self.storage_root.join(&folder_name);
 
-        // Create session folder
-        self.filesystem.create_dir_all(&folder_path)?;
-
-        // Create _captures/ subdirectory as temporary landing zone for Snipping Tool output
-        let captures_path = folder_path.join("_captures");
-        self.filesystem.create_dir_all(&captures_path)?;
-
-        // Create _unsorted/ subdirectory for captures made when no bug is active
-        let unsorted_path = folder_path.join("_unsorted");
-        self.filesystem.create_dir_all(&unsorted_path)?;
+        let environment = session_environment::collect();
 
         // Create session record
         let session = Session {
@@ -99,32 +136,56 @@ impl SessionManager {
             status: SessionStatus::Active,
             folder_path: folder_path.to_string_lossy().to_string(),
             session_notes: None,
-            environment_json: None,
+            environment_json: (!environment.is_empty())
+                .then(|| serde_json::Value::Object(environment).to_string()),
             original_snip_path: None,
             created_at: now.to_rfc3339(),
             profile_id,
+            unlocked_at: None,
+            timezone: crate::time_format::local_timezone(),
         };
 
-        // Save to database
-        {
-            let conn = self.db_conn.lock().unwrap();
-            let repo = SessionRepository::new(&conn);
-            repo.create(&session)
+        // Create the folders and save to database; a failure removes the folders again
+        let environment_diff = {
+            let mut conn = self.db_conn.lock().unwrap();
+            let mut uow = UnitOfWork::begin(&mut conn).map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+            // Create session folder
+            self.create_dir_in(&mut uow, &folder_path)?;
+
+            // Create _captures/ subdirectory as temporary landing zone for Snipping Tool output
+            self.create_dir_in(&mut uow, &folder_path.join("_captures"))?;
+
+            // Create _unsorted/ subdirectory for captures made when no bug is active
+            self.create_dir_in(&mut uow, &folder_path.join("_unsorted"))?;
+
+            SessionRepository::new(uow.connection())
+                .create(&session)
                 .map_err(|e| format!("Failed to create session: {}", e))?;
-        }
+            uow.commit().map_err(|e| format!("Failed to create session: {}", e))?;
+
+            session_environment::environment_diff(&conn, &session_id).unwrap_or_else(|e| {
+                eprintln!("Warning: Failed to compare session environment: {}", e);
+                None
+            })
+        };
 
         // Update active session pointer
         *self.active_session.lock().unwrap() = Some(session_id.clone());
 
         // Emit event
-        self.event_emitter.emit(
-            "session:started",
-            json!({
-                "sessionId": session_id,
-                "folderPath": session.folder_path,
-                "startedAt": session.started_at
-            }),
-        )?;
+        self.emit_event(&events::SessionStarted {
+            session_id: session_id.clone(),
+            folder_path: session.folder_path.clone(),
+            started_at: session.started_at.clone(),
+        })?;
+        if let Some(diff) = environment_diff {
+            self.emit_event(&events::SessionEnvironmentChanged {
+                session_id: session_id.clone(),
+                previous_session_id: diff.previous_session_id,
+                changes: diff.changes,
+            })?;
+        }
 
         // Write initial .session.json (don't fail session start if this fails)
         if let Err(e) = SessionJsonWriter::new(Arc::clone(&self.db_conn)).write(&session_id) {
@@ -176,15 +237,14 @@ impl SessionManager {
 
         // Clear active bug
         *self.active_bug.lock().unwrap() = None;
+        *self.recently_ended.lock().unwrap() = None;
+        self.pending_captures.lock().unwrap().clear();
 
         // Emit event
-        self.event_emitter.emit(
-            "session:ended",
-            json!({
-                "sessionId": session_id,
-                "endedAt": ended_at
-            }),
-        )?;
+        self.emit_event(&events::SessionEnded {
+            session_id: session_id.to_string(),
+            ended_at,
+        })?;
 
         Ok(())
     }
@@ -201,6 +261,14 @@ impl SessionManager {
                 .map_err(|e| format!("Failed to get session: {}", e))?
                 .ok_or_else(|| format!("Session not found: {}", session_id))?;
 
+            // Open capture periods are counted up to the end of the session
+            let previous_end = session
+                .ended_at
+                .as_deref()
+                .and_then(|ended| chrono::DateTime::parse_from_rfc3339(ended).ok())
+                .map(|ended| ended.with_timezone(&Utc))
+                .unwrap_or_else(Utc::now);
+
             // Update status to active
             session.status = SessionStatus::Active;
             session.ended_at = None;
@@ -226,6 +294,7 @@ impl SessionManager {
                 for stale in capturing_bugs.iter().skip(1) {
                     let mut fixed = stale.clone();
                     fixed.status = BugStatus::Captured;
+                    bug_timing::end_period(&mut fixed, previous_end);
                     if let Err(e) = bug_repo.update(&fixed) {
                         eprintln!("Warning: Failed to auto-complete stale bug {}: {}", stale.id, e);
                     }
@@ -238,13 +307,10 @@ impl SessionManager {
         };
 
         // Emit event
-        self.event_emitter.emit(
-            "session:resumed",
-            json!({
-                "sessionId": session_id,
-                "folderPath": session.folder_path
-            }),
-        )?;
+        self.emit_event(&events::SessionResumed {
+            session_id: session_id.to_string(),
+            folder_path: session.folder_path.clone(),
+        })?;
 
         // Update .session.json to reflect resumed status (don't fail if this fails)
         if let Err(e) = SessionJsonWriter::new(Arc::clone(&self.db_conn)).write(session_id) {
@@ -256,13 +322,24 @@ impl SessionManager {
 
     /// Start capturing a new bug
     pub fn start_bug_capture(&self, session_id: &str) -> Result<Bug, String> {
+        self.start_capture(session_id, false)
+    }
+
+    /// Start capturing into a scratch bug: a placeholder for evidence grabbed
+    /// before the tester knows what it shows. It takes no bug number and
+    /// lives under `_scratch/` until promoted with [`Self::promote_scratch_bug`]
+    /// or folded into `_unsorted/` with [`Self::discard_scratch_bug`].
+    pub fn start_scratch_capture(&self, session_id: &str) -> Result<Bug, String> {
+        self.start_capture(session_id, true)
+    }
+
+    fn start_capture(&self, session_id: &str, scratch: bool) -> Result<Bug, String> {
         let bug = {
-            let conn = self.db_conn.lock().unwrap();
-            let session_repo = SessionRepository::new(&conn);
-            let bug_repo = BugRepository::new(&conn);
+            let mut conn = self.db_conn.lock().unwrap();
+            let mut uow = UnitOfWork::begin(&mut conn).map_err(|e| format!("Failed to start transaction: {}", e))?;
 
             // Verify session exists and is active
-            let session = session_repo
+            let session = SessionRepository::new(uow.connection())
                 .get(session_id)
                 .map_err(|e| format!("Failed to get session: {}", e))?
                 .ok_or_else(|| format!("Session not found: {}", session_id))?;
@@ -271,66 +348,31 @@ impl SessionManager {
                 return Err("Session is not active".to_string());
             }
 
-            // Get next bug number
-            let bug_number = bug_repo
-                .get_next_bug_number(session_id)
-                .map_err(|e| format!("Failed to get next bug number: {}", e))?;
-
-            // Create bug folder
-            let session_folder = PathBuf::from(&session.folder_path);
-            let bug_folder_name = format!("bug_{:03}", bug_number);
-            let bug_folder_path = session_folder.join(&bug_folder_name);
-
-            self.filesystem.create_dir_all(&bug_folder_path)?;
-
-            // Create bug record
-            let bug_id = Uuid::new_v4().to_string();
-            let now = Utc::now();
-            let display_id = format!("BUG-{:03}", bug_number);
-
-            let bug = Bug {
-                id: bug_id.clone(),
-                session_id: session_id.to_string(),
-                bug_number,
-                display_id: display_id.clone(),
-                bug_type: BugType::Bug,
-                title: None,
-                notes: None,
-                description: None,
-                ai_description: None,
-                status: BugStatus::Capturing,
-                meeting_id: None,
-                software_version: None,
-                console_parse_json: None,
-                metadata_json: None,
-                custom_metadata: None,
-                folder_path: bug_folder_path.to_string_lossy().to_string(),
-                created_at: now.to_rfc3339(),
-                updated_at: now.to_rfc3339(),
+            let mut bug = if scratch {
+                self.create_scratch_record(&mut uow, &session)?
+            } else {
+                self.create_bug_record(&mut uow, &session, BugType::Bug, BugStatus::Capturing)?
             };
-
-            // Save to database
-            bug_repo
-                .create(&bug)
-                .map_err(|e| format!("Failed to create bug: {}", e))?;
+            bug_timing::start_period(&mut bug, Utc::now());
+            BugRepository::new(uow.connection())
+                .update(&bug)
+                .map_err(|e| format!("Failed to update bug: {}", e))?;
+            uow.commit().map_err(|e| format!("Failed to create bug: {}", e))?;
 
             // Update active bug pointer
-            *self.active_bug.lock().unwrap() = Some(bug_id.clone());
+            *self.active_bug.lock().unwrap() = Some(bug.id.clone());
 
             bug
         };
 
         // Emit event
-        self.event_emitter.emit(
-            "bug:capture-started",
-            json!({
-                "bugId": bug.id,
-                "sessionId": session_id,
-                "bugNumber": bug.bug_number,
-                "displayId": bug.display_id,
-                "folderPath": bug.folder_path
-            }),
-        )?;
+        self.emit_event(&events::BugCaptureStarted {
+            bug_id: bug.id.clone(),
+            session_id: session_id.to_string(),
+            bug_number: bug.bug_number,
+            display_id: bug.display_id.clone(),
+            folder_path: bug.folder_path.clone(),
+        })?;
 
         // Update .session.json to include new bug (don't fail if this fails)
         if let Err(e) = SessionJsonWriter::new(Arc::clone(&self.db_conn)).write(session_id) {
@@ -340,6 +382,303 @@ impl SessionManager {
         Ok(bug)
     }
 
+    /// Number a new bug in `session`, create its folder and insert it, as
+    /// part of `uow`.
+    fn create_bug_record(
+        &self,
+        uow: &mut UnitOfWork,
+        session: &Session,
+        bug_type: BugType,
+        status: BugStatus,
+    ) -> Result<Bug, String> {
+        // Get next bug number
+        let bug_number = BugRepository::new(uow.connection())
+            .get_next_bug_number(&session.id)
+            .map_err(|e| format!("Failed to get next bug number: {}", e))?;
+
+        // Create bug folder
+        let session_folder = PathBuf::from(&session.folder_path);
+        let bug_folder_name = format!("bug_{:03}", bug_number);
+        let bug_folder_path = session_folder.join(&bug_folder_name);
+
+        self.create_dir_in(uow, &bug_folder_path)?;
+
+        // Create bug record
+        let bug_id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let display_id = format!("BUG-{:03}", bug_number);
+
+        let bug = Bug {
+            id: bug_id,
+            session_id: session.id.clone(),
+            bug_number,
+            display_id,
+            bug_type,
+            title: None,
+            notes: None,
+            description: None,
+            ai_description: None,
+            status,
+            meeting_id: None,
+            software_version: None,
+            console_parse_json: None,
+            metadata_json: None,
+            custom_metadata: None,
+            folder_path: bug_folder_path.to_string_lossy().to_string(),
+            created_at: now.to_rfc3339(),
+            updated_at: now.to_rfc3339(),
+            external_ticket_id: None,
+            external_ticket_key: None,
+            external_ticket_url: None,
+            external_status: None,
+            external_status_category: None,
+        };
+
+        // Save to database
+        BugRepository::new(uow.connection())
+            .create(&bug)
+            .map_err(|e| format!("Failed to create bug: {}", e))?;
+
+        Ok(bug)
+    }
+
+    /// Insert a capturing scratch bug in `session`, as part of `uow`.
+    fn create_scratch_record(&self, uow: &mut UnitOfWork, session: &Session) -> Result<Bug, String> {
+        let bug_id = Uuid::new_v4().to_string();
+        let folder = PathBuf::from(&session.folder_path)
+            .join(SCRATCH_FOLDER)
+            .join(&bug_id[..8]);
+        self.create_dir_in(uow, &folder)?;
+
+        let now = Utc::now().to_rfc3339();
+        let bug = Bug {
+            id: bug_id,
+            session_id: session.id.clone(),
+            bug_number: 0,
+            display_id: SCRATCH_DISPLAY_ID.to_string(),
+            bug_type: BugType::Bug,
+            title: None,
+            notes: None,
+            description: None,
+            ai_description: None,
+            status: BugStatus::Capturing,
+            meeting_id: None,
+            software_version: None,
+            console_parse_json: None,
+            metadata_json: None,
+            custom_metadata: None,
+            folder_path: folder.to_string_lossy().to_string(),
+            created_at: now.clone(),
+            updated_at: now,
+            external_ticket_id: None,
+            external_ticket_key: None,
+            external_ticket_url: None,
+            external_status: None,
+            external_status_category: None,
+        };
+        BugRepository::new(uow.connection())
+            .create(&bug)
+            .map_err(|e| format!("Failed to create bug: {}", e))?;
+        Ok(bug)
+    }
+
+    fn get_scratch_bug(conn: &Connection, bug_id: &str) -> Result<Bug, String> {
+        let bug = BugRepository::new(conn)
+            .get(bug_id)
+            .map_err(|e| format!("Failed to get bug: {}", e))?
+            .ok_or_else(|| format!("Bug not found: {}", bug_id))?;
+        if !bug.is_scratch() {
+            return Err(format!("{} is not a scratch bug", bug.display_id));
+        }
+        Ok(bug)
+    }
+
+    /// Turn scratch bug `bug_id` into a regular bug: give it the next bug
+    /// number, `title` and `bug_type`, and move its folder to `bug_NNN`
+    /// (capture paths follow). Capturing into it continues if it is active.
+    pub fn promote_scratch_bug(&self, bug_id: &str, title: Option<String>, bug_type: BugType) -> Result<Bug, String> {
+        let bug = {
+            let mut conn = self.db_conn.lock().unwrap();
+            let mut uow = UnitOfWork::begin(&mut conn).map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+            let mut bug = Self::get_scratch_bug(uow.connection(), bug_id)?;
+            let session = SessionRepository::new(uow.connection())
+                .get(&bug.session_id)
+                .map_err(|e| format!("Failed to get session: {}", e))?
+                .ok_or_else(|| format!("Session not found: {}", bug.session_id))?;
+            let bug_number = BugRepository::new(uow.connection())
+                .get_next_bug_number(&session.id)
+                .map_err(|e| format!("Failed to get next bug number: {}", e))?;
+
+            let old_folder = PathBuf::from(&bug.folder_path);
+            let new_folder = PathBuf::from(&session.folder_path).join(format!("bug_{:03}", bug_number));
+            self.filesystem.rename(&old_folder, &new_folder)?;
+            {
+                let (filesystem, from, to) = (Arc::clone(&self.filesystem), new_folder.clone(), old_folder.clone());
+                uow.on_rollback(move || {
+                    if let Err(e) = filesystem.rename(&from, &to) {
+                        eprintln!("Warning: Failed to move {} back: {}", from.display(), e);
+                    }
+                });
+            }
+
+            // Offloaded recordings live outside the bug folder and keep their paths
+            let moved = |path: &str| -> Option<String> {
+                Path::new(path)
+                    .strip_prefix(&old_folder)
+                    .ok()
+                    .map(|rest| new_folder.join(rest).to_string_lossy().to_string())
+            };
+            let capture_repo = CaptureRepository::new(uow.connection());
+            let captures = capture_repo
+                .list_by_bug(&bug.id)
+                .map_err(|e| format!("Failed to list captures: {}", e))?;
+            for mut capture in captures {
+                if let Some(path) = moved(&capture.file_path) {
+                    capture.file_path = path;
+                }
+                capture.annotated_path = capture.annotated_path.map(|path| moved(&path).unwrap_or(path));
+                capture_repo
+                    .update(&capture)
+                    .map_err(|e| format!("Failed to update capture: {}", e))?;
+            }
+
+            bug.bug_number = bug_number;
+            bug.display_id = format!("BUG-{:03}", bug_number);
+            bug.bug_type = bug_type;
+            if title.is_some() {
+                bug.title = title;
+            }
+            bug.folder_path = new_folder.to_string_lossy().to_string();
+            bug.updated_at = Utc::now().to_rfc3339();
+            BugRepository::new(uow.connection())
+                .update(&bug)
+                .map_err(|e| format!("Failed to update bug: {}", e))?;
+            uow.commit().map_err(|e| format!("Failed to promote bug: {}", e))?;
+            bug
+        };
+
+        self.emit_event(&events::BugPromoted {
+            bug_id: bug.id.clone(),
+            session_id: bug.session_id.clone(),
+            bug_number: bug.bug_number,
+            display_id: bug.display_id.clone(),
+            folder_path: bug.folder_path.clone(),
+        })?;
+
+        if let Err(e) = SessionJsonWriter::new(Arc::clone(&self.db_conn)).write(&bug.session_id) {
+            eprintln!("Warning: Failed to update .session.json on bug promotion: {}", e);
+        }
+
+        Ok(bug)
+    }
+
+    /// Delete scratch bug `bug_id` once its `folded_captures` captures have
+    /// been moved out (see the `fold_scratch_bug` command). Its notes are
+    /// appended to the session notes; its folder is removed if nothing else
+    /// was left in it. The deletion is recorded in the audit log.
+    pub fn discard_scratch_bug(&self, bug_id: &str, folded_captures: usize) -> Result<(), String> {
+        let bug = {
+            let mut conn = self.db_conn.lock().unwrap();
+            let uow = UnitOfWork::begin(&mut conn).map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+            let bug = Self::get_scratch_bug(uow.connection(), bug_id)?;
+            let remaining = CaptureRepository::new(uow.connection())
+                .list_by_bug(&bug.id)
+                .map_err(|e| format!("Failed to list captures: {}", e))?;
+            if !remaining.is_empty() {
+                return Err(format!("Scratch bug still has {} capture(s)", remaining.len()));
+            }
+
+            if let Some(notes) = bug.notes.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+                let session_repo = SessionRepository::new(uow.connection());
+                let mut session = session_repo
+                    .get(&bug.session_id)
+                    .map_err(|e| format!("Failed to get session: {}", e))?
+                    .ok_or_else(|| format!("Session not found: {}", bug.session_id))?;
+                session.session_notes = Some(match session.session_notes.as_deref().map(str::trim_end) {
+                    Some(existing) if !existing.is_empty() => format!("{}\n\n{}", existing, notes),
+                    _ => notes.to_string(),
+                });
+                session_repo
+                    .update(&session)
+                    .map_err(|e| format!("Failed to update session: {}", e))?;
+            }
+
+            BugRepository::new(uow.connection())
+                .delete(&bug.id)
+                .map_err(|e| format!("Failed to delete bug: {}", e))?;
+            record_audit(
+                uow.connection(),
+                "bug.delete",
+                "bug",
+                &bug.id,
+                Some(serde_json::json!({ "display_id": bug.display_id, "folded_captures": folded_captures })),
+            )?;
+            uow.commit().map_err(|e| format!("Failed to discard bug: {}", e))?;
+
+            let mut active = self.active_bug.lock().unwrap();
+            if active.as_deref() == Some(bug_id) {
+                *active = None;
+            }
+            bug
+        };
+
+        if let Err(e) = self.filesystem.remove_dir(Path::new(&bug.folder_path)) {
+            eprintln!("Warning: Kept scratch folder {}: {}", bug.folder_path, e);
+        }
+
+        self.emit_event(&events::BugFolded {
+            bug_id: bug.id.clone(),
+            session_id: bug.session_id.clone(),
+        })?;
+
+        if let Err(e) = SessionJsonWriter::new(Arc::clone(&self.db_conn)).write(&bug.session_id) {
+            eprintln!("Warning: Failed to update .session.json on bug fold: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Create a bug next to `source_bug_id` (same session and type, not
+    /// capturing) and link it as related. Moving captures and notes into it
+    /// is up to the caller.
+    pub fn split_bug(&self, source_bug_id: &str) -> Result<Bug, String> {
+        let bug = {
+            let mut conn = self.db_conn.lock().unwrap();
+            let mut uow = UnitOfWork::begin(&mut conn).map_err(|e| format!("Failed to start transaction: {}", e))?;
+            let bug = self.split_bug_in(&mut uow, source_bug_id)?;
+            uow.commit().map_err(|e| format!("Failed to create bug: {}", e))?;
+            bug
+        };
+
+        if let Err(e) = SessionJsonWriter::new(Arc::clone(&self.db_conn)).write(&bug.session_id) {
+            eprintln!("Warning: Failed to update .session.json on bug split: {}", e);
+        }
+
+        Ok(bug)
+    }
+
+    /// [`split_bug`](Self::split_bug) as part of the caller's `uow`, so the
+    /// captures and notes can move in the same transaction. The caller
+    /// commits and refreshes `.session.json`.
+    pub fn split_bug_in(&self, uow: &mut UnitOfWork, source_bug_id: &str) -> Result<Bug, String> {
+        let source = BugRepository::new(uow.connection())
+            .get(source_bug_id)
+            .map_err(|e| format!("Failed to get bug: {}", e))?
+            .ok_or_else(|| format!("Bug not found: {}", source_bug_id))?;
+        let session = SessionRepository::new(uow.connection())
+            .get(&source.session_id)
+            .map_err(|e| format!("Failed to get session: {}", e))?
+            .ok_or_else(|| format!("Session not found: {}", source.session_id))?;
+
+        let bug = self.create_bug_record(uow, &session, source.bug_type, BugStatus::Captured)?;
+        BugLinkRepository::new(uow.connection())
+            .create(&bug.id, source_bug_id, BugLinkKind::RelatesTo)
+            .map_err(|e| format!("Failed to link bugs: {}", e))?;
+        Ok(bug)
+    }
+
     /// End bug capture
     pub fn end_bug_capture(&self, bug_id: &str) -> Result<(), String> {
         let session_id = {
@@ -355,6 +694,7 @@ impl SessionManager {
             // Update bug status
             bug.status = BugStatus::Captured;
             bug.updated_at = Utc::now().to_rfc3339();
+            bug_timing::end_period(&mut bug, Utc::now());
 
             bug_repo
                 .update(&bug)
@@ -366,17 +706,20 @@ impl SessionManager {
                 *active = None;
             }
 
+            // Late-arriving captures may still be routed here (grace window)
+            *self.recently_ended.lock().unwrap() = Some(EndedBug {
+                bug_id: bug_id.to_string(),
+                ended_at: Utc::now(),
+            });
+
             bug.session_id
         };
 
         // Emit event
-        self.event_emitter.emit(
-            "bug:capture-ended",
-            json!({
-                "bugId": bug_id,
-                "sessionId": session_id
-            }),
-        )?;
+        self.emit_event(&events::BugCaptureEnded {
+            bug_id: bug_id.to_string(),
+            session_id: session_id.clone(),
+        })?;
 
         // Update .session.json to reflect bug status change (don't fail if this fails)
         if let Err(e) = SessionJsonWriter::new(Arc::clone(&self.db_conn)).write(&session_id) {
@@ -400,6 +743,7 @@ impl SessionManager {
             // Set bug status back to capturing
             bug.status = BugStatus::Capturing;
             bug.updated_at = Utc::now().to_rfc3339();
+            bug_timing::start_period(&mut bug, Utc::now());
 
             bug_repo
                 .update(&bug)
@@ -415,13 +759,10 @@ impl SessionManager {
         };
 
         // Emit event so the frontend knows
-        self.event_emitter.emit(
-            "bug-status-changed",
-            json!({
-                "id": bug_id,
-                "status": "capturing"
-            }),
-        )?;
+        self.emit_event(&events::BugStatusChanged {
+            id: bug_id.to_string(),
+            status: BugStatus::Capturing.as_str().to_string(),
+        })?;
 
         // Update .session.json
         if let Err(e) = SessionJsonWriter::new(Arc::clone(&self.db_conn)).write(&bug.session_id) {
@@ -441,17 +782,52 @@ impl SessionManager {
         self.active_bug.lock().unwrap().clone()
     }
 
+    /// Root folder under which session folders are created.
+    pub fn storage_root(&self) -> &Path {
+        &self.storage_root
+    }
+
     /// Return a shared reference to the active-bug Arc so callers (e.g. the
     /// capture watcher) can observe live changes without going through the
     /// SessionManager lock.
     pub fn active_bug_arc(&self) -> Arc<Mutex<Option<String>>> {
         Arc::clone(&self.active_bug)
     }
+
+    /// Shared reference to the most recently ended bug, for grace-window
+    /// routing in the capture watcher.
+    pub fn recently_ended_arc(&self) -> SharedEndedBug {
+        Arc::clone(&self.recently_ended)
+    }
+
+    /// Shared handles the capture watcher routes new files with.
+    pub fn routing_handles(&self) -> RoutingHandles {
+        RoutingHandles {
+            active_bug: self.active_bug_arc(),
+            recently_ended: self.recently_ended_arc(),
+            pending_captures: Arc::clone(&self.pending_captures),
+        }
+    }
+
+    /// Record that a screenshot was just triggered, so the capture watcher can
+    /// attach the trigger to the file when it appears. Does nothing without an
+    /// active session.
+    pub fn record_capture_trigger(&self, source: TriggerSource) -> Option<PendingCapture> {
+        self.get_active_session_id()?;
+        let marker = PendingCapture {
+            triggered_at: Utc::now(),
+            bug_id: self.get_active_bug_id(),
+            source,
+        };
+        capture_trigger::record(&self.pending_captures, marker.clone());
+        Some(marker)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::database::{AuditOps, AuditRepository};
     use std::collections::HashMap;
     use std::sync::Mutex as StdMutex;
 
@@ -500,9 +876,26 @@ mod tests {
             self.dirs.lock().unwrap().insert(path.to_path_buf(), true);
             Ok(())
         }
+
+        fn remove_dir(&self, path: &Path) -> Result<(), String> {
+            self.dirs.lock().unwrap().remove(path);
+            Ok(())
+        }
+
+        fn rename(&self, from: &Path, to: &Path) -> Result<(), String> {
+            let mut dirs = self.dirs.lock().unwrap();
+            let value = dirs.remove(from).ok_or_else(|| format!("No such directory: {}", from.display()))?;
+            dirs.insert(to.to_path_buf(), value);
+            Ok(())
+        }
     }
 
     fn create_test_manager() -> (SessionManager, Arc<MockEventEmitter>) {
+        let (manager, emitter, _filesystem) = create_test_manager_with_fs();
+        (manager, emitter)
+    }
+
+    fn create_test_manager_with_fs() -> (SessionManager, Arc<MockEventEmitter>, Arc<MockFileSystem>) {
         let temp_dir = std::env::temp_dir().join(format!("test_session_manager_{}", Uuid::new_v4()));
         let db_path = temp_dir.join("test.db");
         let storage_root = temp_dir.join("storage");
@@ -521,10 +914,63 @@ mod tests {
             db_conn,
             storage_root,
             emitter.clone() as Arc<dyn EventEmitter>,
-            filesystem as Arc<dyn FileSystem>,
+            filesystem.clone() as Arc<dyn FileSystem>,
         );
 
-        (manager, emitter)
+        (manager, emitter, filesystem)
+    }
+
+    /// Make every insert into `table` fail.
+    fn inject_insert_failure(manager: &SessionManager, table: &str) {
+        manager
+            .db_conn
+            .lock()
+            .unwrap()
+            .execute_batch(&format!(
+                "CREATE TRIGGER fail_{0}_insert BEFORE INSERT ON {0} BEGIN SELECT RAISE(ABORT, 'injected failure'); END;",
+                table
+            ))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_failed_session_insert_removes_folders() {
+        let (manager, emitter, filesystem) = create_test_manager_with_fs();
+        inject_insert_failure(&manager, "sessions");
+
+        assert!(manager.start_session(None).is_err());
+        assert!(filesystem.dirs.lock().unwrap().is_empty());
+        assert_eq!(manager.get_active_session_id(), None);
+        assert!(emitter.get_events().is_empty());
+    }
+
+    #[test]
+    fn test_failed_bug_insert_removes_folder_and_keeps_state() {
+        let (manager, emitter, filesystem) = create_test_manager_with_fs();
+        let session = manager.start_session(None).unwrap();
+        let dirs_before = filesystem.dirs.lock().unwrap().clone();
+        inject_insert_failure(&manager, "bugs");
+
+        let err = manager.start_bug_capture(&session.id).unwrap_err();
+        assert!(err.contains("injected failure"), "{}", err);
+        assert_eq!(*filesystem.dirs.lock().unwrap(), dirs_before);
+        assert_eq!(manager.get_active_bug_id(), None);
+        assert_eq!(emitter.get_events().len(), 1, "only session:started");
+    }
+
+    #[test]
+    fn test_failed_split_link_rolls_back_new_bug() {
+        let (manager, _emitter, filesystem) = create_test_manager_with_fs();
+        let session = manager.start_session(None).unwrap();
+        let source = manager.start_bug_capture(&session.id).unwrap();
+        let dirs_before = filesystem.dirs.lock().unwrap().clone();
+        inject_insert_failure(&manager, "bug_links");
+
+        assert!(manager.split_bug(&source.id).is_err());
+        assert_eq!(*filesystem.dirs.lock().unwrap(), dirs_before);
+        let conn = manager.db_conn.lock().unwrap();
+        let bugs = BugRepository::new(&conn).list_by_session(&session.id).unwrap();
+        assert_eq!(bugs.len(), 1, "the half-created split bug must not remain");
     }
 
     #[test]
@@ -705,6 +1151,35 @@ mod tests {
         assert_eq!(manager.get_active_bug_id(), Some(bug.id.clone()));
     }
 
+    #[test]
+    fn test_resume_session_closes_periods_of_stale_capturing_bugs() {
+        let (manager, _emitter) = create_test_manager();
+        let session_id = manager.start_session(None).unwrap().id;
+
+        let first = manager.start_bug_capture(&session_id).unwrap();
+        manager.end_bug_capture(&first.id).unwrap();
+        let second = manager.start_bug_capture(&session_id).unwrap();
+
+        // A crash left both bugs capturing
+        {
+            let conn = manager.db_conn.lock().unwrap();
+            let repo = BugRepository::new(&conn);
+            let mut bug = repo.get(&first.id).unwrap().unwrap();
+            bug.status = BugStatus::Capturing;
+            bug_timing::start_period(&mut bug, Utc::now());
+            repo.update(&bug).unwrap();
+        }
+        *manager.active_session.lock().unwrap() = None;
+        *manager.active_bug.lock().unwrap() = None;
+
+        manager.resume_session(&session_id).unwrap();
+        assert_eq!(manager.get_active_bug_id(), Some(first.id.clone()));
+        let conn = manager.db_conn.lock().unwrap();
+        let stale = BugRepository::new(&conn).get(&second.id).unwrap().unwrap();
+        assert_eq!(stale.status, BugStatus::Captured);
+        assert!(bug_timing::periods(&stale).iter().all(|p| p.ended_at.is_some()));
+    }
+
     #[test]
     fn test_resume_session_no_capturing_bug_leaves_active_bug_none() {
         let (manager, _emitter) = create_test_manager();
@@ -742,6 +1217,125 @@ mod tests {
         assert_eq!(manager.get_active_bug_id(), None);
     }
 
+    #[test]
+    fn test_end_bug_capture_records_recently_ended_bug() {
+        let (manager, _emitter) = create_test_manager();
+
+        let session = manager.start_session(None).unwrap();
+        let bug = manager.start_bug_capture(&session.id).unwrap();
+        assert_eq!(*manager.recently_ended_arc().lock().unwrap(), None);
+
+        manager.end_bug_capture(&bug.id).unwrap();
+        let ended = manager.recently_ended_arc().lock().unwrap().clone().unwrap();
+        assert_eq!(ended.bug_id, bug.id);
+
+        // Ending the session stops grace-window routing
+        manager.end_session(&session.id).unwrap();
+        assert_eq!(*manager.recently_ended_arc().lock().unwrap(), None);
+    }
+
+    #[test]
+    fn test_record_capture_trigger_notes_active_bug() {
+        let (manager, _emitter) = create_test_manager();
+        assert_eq!(manager.record_capture_trigger(TriggerSource::Hotkey), None);
+
+        let session = manager.start_session(None).unwrap();
+        let bug = manager.start_bug_capture(&session.id).unwrap();
+        let marker = manager.record_capture_trigger(TriggerSource::Hotkey).unwrap();
+        assert_eq!(marker.bug_id, Some(bug.id));
+
+        let pending = manager.routing_handles().pending_captures;
+        assert_eq!(capture_trigger::claim(&pending, Utc::now()), Some(marker));
+
+        // Markers do not outlive the session
+        manager.record_capture_trigger(TriggerSource::Command).unwrap();
+        manager.end_session(&session.id).unwrap();
+        assert!(pending.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_split_bug_creates_linked_bug() {
+        let (manager, _emitter) = create_test_manager();
+
+        let session = manager.start_session(None).unwrap();
+        let source = manager.start_bug_capture(&session.id).unwrap();
+
+        let split = manager.split_bug(&source.id).unwrap();
+        assert_eq!(split.bug_number, source.bug_number + 1);
+        assert_eq!(split.status, BugStatus::Captured);
+        // The source bug stays the one being captured
+        assert_eq!(manager.get_active_bug_id(), Some(source.id.clone()));
+
+        let conn = manager.db_conn.lock().unwrap();
+        let links = BugLinkRepository::new(&conn).list_by_bug(&source.id).unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].bug_id, split.id);
+        assert_eq!(links[0].kind, BugLinkKind::RelatesTo);
+    }
+
+    #[test]
+    fn test_scratch_bug_takes_no_number_until_promoted() {
+        let (manager, emitter, filesystem) = create_test_manager_with_fs();
+        let session = manager.start_session(None).unwrap();
+
+        let scratch = manager.start_scratch_capture(&session.id).unwrap();
+        assert!(scratch.is_scratch());
+        assert_eq!(scratch.display_id, SCRATCH_DISPLAY_ID);
+        assert!(scratch.folder_path.contains(SCRATCH_FOLDER));
+        assert_eq!(manager.get_active_bug_id(), Some(scratch.id.clone()));
+        manager.end_bug_capture(&scratch.id).unwrap();
+
+        // Bug numbering is unaffected
+        let bug = manager.start_bug_capture(&session.id).unwrap();
+        assert_eq!(bug.bug_number, 1);
+        manager.end_bug_capture(&bug.id).unwrap();
+
+        let promoted = manager
+            .promote_scratch_bug(&scratch.id, Some("Checkout hangs".to_string()), BugType::Feature)
+            .unwrap();
+        assert_eq!(promoted.bug_number, 2);
+        assert_eq!(promoted.display_id, "BUG-002");
+        assert_eq!(promoted.title.as_deref(), Some("Checkout hangs"));
+        assert_eq!(promoted.bug_type, BugType::Feature);
+        assert!(promoted.folder_path.ends_with("bug_002"));
+        let dirs = filesystem.dirs.lock().unwrap();
+        assert!(dirs.contains_key(Path::new(&promoted.folder_path)));
+        assert!(!dirs.contains_key(Path::new(&scratch.folder_path)));
+        drop(dirs);
+        assert!(emitter.get_events().iter().any(|(name, _)| name == "bug:promoted"));
+
+        assert_eq!(
+            manager.promote_scratch_bug(&promoted.id, None, BugType::Bug).unwrap_err(),
+            "BUG-002 is not a scratch bug"
+        );
+    }
+
+    #[test]
+    fn test_discard_scratch_bug_keeps_notes_in_session() {
+        let (manager, _emitter) = create_test_manager();
+        let session = manager.start_session(None).unwrap();
+        let scratch = manager.start_scratch_capture(&session.id).unwrap();
+        {
+            let conn = manager.db_conn.lock().unwrap();
+            let repo = BugRepository::new(&conn);
+            let mut bug = repo.get(&scratch.id).unwrap().unwrap();
+            bug.notes = Some("spinner never stops".to_string());
+            repo.update(&bug).unwrap();
+        }
+
+        manager.discard_scratch_bug(&scratch.id, 2).unwrap();
+        assert_eq!(manager.get_active_bug_id(), None);
+
+        let conn = manager.db_conn.lock().unwrap();
+        assert!(BugRepository::new(&conn).get(&scratch.id).unwrap().is_none());
+        let audit = AuditRepository::new(&conn).list_for_entity("bug", &scratch.id).unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].action, "bug.delete");
+        assert!(audit[0].details.as_deref().unwrap().contains("\"folded_captures\":2"));
+        let session = SessionRepository::new(&conn).get(&session.id).unwrap().unwrap();
+        assert_eq!(session.session_notes.as_deref(), Some("spinner never stops"));
+    }
+
     #[test]
     fn test_captures_and_unsorted_folders_created_on_session_start() {
         let (manager, _emitter) = create_test_manager();
@@ -831,6 +1425,27 @@ mod tests {
         assert_eq!(bug_folder_name, "bug_001");
     }
 
+    #[test]
+    fn test_start_bug_capture_continues_after_imported_numbering() {
+        let (manager, _emitter) = create_test_manager();
+
+        let session = manager.start_session(None).unwrap();
+        let session_id = session.id.clone();
+
+        // Simulate bugs 1-4 having been captured on another machine and imported
+        {
+            let conn = manager.db_conn.lock().unwrap();
+            BugRepository::new(&conn)
+                .reserve_bug_numbers(&session_id, 1, 4, Some("import"))
+                .unwrap();
+        }
+
+        let bug = manager.start_bug_capture(&session_id).unwrap();
+        assert_eq!(bug.bug_number, 5);
+        assert_eq!(bug.display_id, "BUG-005");
+        assert!(bug.folder_path.ends_with("bug_005"));
+    }
+
     #[test]
     fn test_start_session_with_profile_id() {
         let (manager, _emitter) = create_test_manager();
@@ -4,14 +4,32 @@
 //! - Session metadata (date, duration, bug count)
 //! - List of all bugs with titles/IDs
 //! - Optionally: AI-generated high-level summary from bug descriptions (using Claude CLI)
+//!
+//! A self-contained HTML variant (session-report.html), a PDF report with the
+//! screenshots embedded (session-report.pdf) and a bug list spreadsheet
+//! (session-bugs.csv) can be written alongside it. Dates and numbers follow the
+//! `export.locale` setting (see [`crate::time_format`]).
+//!
+//! The layout comes from the session-summary template (see [`crate::summary_template`]).
 
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use rusqlite::Connection;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
-use crate::claude_cli::{ClaudeInvoker, ClaudeRequest, PromptTask, RealClaudeInvoker, load_credentials};
-use crate::database::{Bug, BugOps, BugRepository, Session, SessionOps, SessionRepository};
+use crate::bug_timing;
+use crate::claude_cli::{
+    load_credentials, AiModelSettings, ClaudeInvoker, ClaudeRequest, ModelTask, PromptTask, RealClaudeInvoker,
+};
+use crate::console_export::ConsoleParse;
+use crate::database::{
+    Bug, BugLink, BugLinkOps, BugLinkRepository, BugOps, BugRepository, Capture, CaptureOps, CaptureRepository,
+    CaptureType, Session, SessionOps, SessionRepository,
+};
+use crate::pdf_report::{Font, PdfReport};
+use crate::session_environment::environment_diff;
+use crate::time_format::ExportLocale;
+use crate::summary_template::{load_summary_template, render_summary, SummaryBugData, SummaryData};
 
 /// Trait for file system operations (enables testing)
 pub trait FileWriter: Send + Sync {
@@ -28,6 +46,9 @@ impl FileWriter for RealFileWriter {
     }
 }
 
+/// A session with its bugs, bug links and captures, and the export locale.
+type ReportData = (Session, Vec<Bug>, Vec<BugLink>, Vec<Capture>, ExportLocale);
+
 /// Session summary generator
 pub struct SessionSummaryGenerator {
     db_conn: Arc<Mutex<Connection>>,
@@ -89,9 +110,15 @@ impl SessionSummaryGenerator {
             (session, bugs)
         };
 
-        // Generate summary content (may call Claude — lock is released above)
+        // AI-generated overview (optional; may call Claude — lock is released above)
+        let overview = if include_ai_summary && !bugs.is_empty() {
+            self.generate_ai_overview(&bugs).ok()
+        } else {
+            None
+        };
+
         let summary_path = PathBuf::from(&session.folder_path).join("session-summary.md");
-        let content = self.build_summary_content(&session, &bugs, include_ai_summary)?;
+        let content = self.build_summary_content(&session, &bugs, overview)?;
 
         // Write to file
         self.file_writer.write_file(&summary_path, &content)?;
@@ -99,119 +126,336 @@ impl SessionSummaryGenerator {
         Ok(summary_path.to_string_lossy().to_string())
     }
 
-    /// Build summary markdown content
-    fn build_summary_content(
-        &self,
-        session: &Session,
-        bugs: &[Bug],
-        include_ai_summary: bool,
-    ) -> Result<String, String> {
-        let mut content = String::new();
-
-        // Title
-        content.push_str("# QA Session Summary\n\n");
-
-        // Metadata section
-        content.push_str("## Session Information\n\n");
-        content.push_str(&format!("- **Session ID:** {}\n", session.id));
-
-        // Parse and format dates
-        let started_at = DateTime::parse_from_rfc3339(&session.started_at)
-            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
-            .unwrap_or_else(|_| session.started_at.clone());
-        content.push_str(&format!("- **Started:** {}\n", started_at));
-
-        if let Some(ended) = &session.ended_at {
-            let ended_at = DateTime::parse_from_rfc3339(ended)
-                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
-                .unwrap_or_else(|_| ended.clone());
-            content.push_str(&format!("- **Ended:** {}\n", ended_at));
-
-            // Calculate duration
-            if let (Ok(start), Ok(end)) = (
-                DateTime::parse_from_rfc3339(&session.started_at),
-                DateTime::parse_from_rfc3339(ended),
-            ) {
-                let duration = end.signed_duration_since(start);
-                let hours = duration.num_hours();
-                let minutes = duration.num_minutes() % 60;
-                content.push_str(&format!("- **Duration:** {}h {}m\n", hours, minutes));
-            }
-        } else {
-            content.push_str("- **Ended:** In Progress\n");
-        }
-
-        content.push_str(&format!("- **Bug Count:** {}\n", bugs.len()));
-        content.push_str(&format!("- **Status:** {}\n", session.status.as_str()));
+    /// Rewrite session-summary.md from the current DB state (e.g. after tickets
+    /// were filed) without calling Claude again: an AI overview already present
+    /// in the existing file is carried over.
+    pub fn refresh_summary(&self, session_id: &str) -> Result<String, String> {
+        let (session, bugs) = {
+            let conn = self.db_conn.lock().unwrap();
+            let session = SessionRepository::new(&conn)
+                .get(session_id)
+                .map_err(|e| format!("Failed to get session: {}", e))?
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            let bugs = BugRepository::new(&conn)
+                .list_by_session(session_id)
+                .map_err(|e| format!("Failed to list bugs: {}", e))?;
+            (session, bugs)
+        };
 
-        if let Some(notes) = &session.session_notes {
-            if !notes.trim().is_empty() {
-                content.push_str(&format!("\n### Session Notes\n\n{}\n", notes));
-            }
-        }
+        let summary_path = PathBuf::from(&session.folder_path).join("session-summary.md");
+        let overview = std::fs::read_to_string(&summary_path)
+            .ok()
+            .and_then(|existing| existing_overview(&existing))
+            .map(|section| section.trim_start_matches("## Overview").trim().to_string());
+        let content = self.build_summary_content(&session, &bugs, overview)?;
 
-        content.push('\n');
+        self.file_writer.write_file(&summary_path, &content)?;
+        Ok(summary_path.to_string_lossy().to_string())
+    }
 
-        // AI-generated overview (optional)
-        if include_ai_summary && !bugs.is_empty() {
-            if let Ok(ai_summary) = self.generate_ai_overview(bugs) {
-                content.push_str("## Overview\n\n");
-                content.push_str(&ai_summary);
-                content.push_str("\n\n");
+    /// Generate session-report.html: the session information and every bug with
+    /// its notes, console output and captures, for readers without the app.
+    /// Screenshots are inlined as base64 so the file opens in any browser on its
+    /// own; videos (and screenshots that can't be read) are linked relative to
+    /// the session folder instead. Each bug is a collapsible section.
+    pub fn generate_html_report(&self, session_id: &str) -> Result<String, String> {
+        let (session, bugs, links, captures, locale) = self.load_report(session_id)?;
+
+        let session_folder = PathBuf::from(&session.folder_path);
+        let data = summary_data(&session, &bugs, &links, None, &locale);
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<title>QA Session Report</title>\n");
+        html.push_str(HTML_REPORT_STYLE);
+        html.push_str("</head>\n<body>\n<h1>QA Session Report</h1>\n<ul>\n");
+        for (label, value) in [
+            ("Session ID", Some(data.session_id.as_str())),
+            ("Started", Some(data.started.as_str())),
+            ("Ended", Some(data.ended.as_deref().unwrap_or("In Progress"))),
+            ("Duration", data.duration.as_deref()),
+            ("Status", Some(data.status.as_str())),
+        ] {
+            if let Some(value) = value {
+                html.push_str(&format!("<li><strong>{}:</strong> {}</li>\n", label, html_escape(value)));
             }
         }
+        html.push_str(&format!("<li><strong>Bug Count:</strong> {}</li>\n</ul>\n", data.bug_count));
+        if let Some(notes) = data.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+            html.push_str(&format!("<h2>Session Notes</h2>\n<pre>{}</pre>\n", html_escape(notes)));
+        }
 
-        // Bug list section
-        if bugs.is_empty() {
-            content.push_str("## Bugs Captured\n\nNo bugs captured in this session.\n");
-        } else {
-            content.push_str("## Bugs Captured\n\n");
+        for (bug, bug_data) in bugs.iter().zip(&data.bugs) {
+            html.push_str(&format!(
+                "<details open>\n<summary><h2>{} - {}</h2></summary>\n<p>Type: {} &middot; Status: {} &middot; Ticket: {}</p>\n",
+                html_escape(&bug_data.display_id),
+                html_escape(&bug_data.title),
+                html_escape(&bug_data.bug_type),
+                html_escape(&bug_data.status),
+                html_escape(&bug_data.ticket),
+            ));
+            if let Some(related) = &bug_data.related {
+                html.push_str(&format!("<p>Related: {}</p>\n", html_escape(related)));
+            }
+            for (label, text) in [
+                ("Notes", &bug_data.notes),
+                ("Description", &bug_data.description),
+                ("AI Description", &bug_data.ai_description),
+            ] {
+                if let Some(text) = text.as_deref().filter(|t| !t.trim().is_empty()) {
+                    html.push_str(&format!("<h3>{}</h3>\n<pre>{}</pre>\n", label, html_escape(text)));
+                }
+            }
 
-            for bug in bugs {
-                content.push_str(&format!("### {} - ", bug.display_id));
+            let bug_captures: Vec<&Capture> =
+                captures.iter().filter(|c| c.bug_id.as_deref() == Some(bug.id.as_str())).collect();
+            // Same source as rendered tickets: parses stored on console captures,
+            // else the bug's own console parse
+            let mut console: Vec<&str> = bug_captures
+                .iter()
+                .filter(|c| c.is_console_capture)
+                .filter_map(|c| c.parsed_content.as_deref())
+                .collect();
+            if console.is_empty() {
+                console.extend(bug.console_parse_json.as_deref());
+            }
+            if !console.is_empty() {
+                html.push_str("<h3>Console Output</h3>\n");
+                for parsed in console {
+                    html.push_str(&console_table_html(parsed));
+                }
+            }
 
-                if let Some(title) = &bug.title {
-                    content.push_str(title);
-                } else {
-                    content.push_str("(No title)");
+            for capture in bug_captures {
+                let path = capture.annotated_path.as_deref().unwrap_or(&capture.file_path);
+                let href = Path::new(path)
+                    .strip_prefix(&session_folder)
+                    .map(|p| p.to_string_lossy().replace('\\', "/"))
+                    .unwrap_or_else(|_| path.to_string());
+                let href = html_escape(&href);
+                let name = html_escape(&capture.file_name);
+                // Screen readers get the capture's alt text when one was written
+                let alt = capture.alt_text.as_deref().map(html_escape).unwrap_or_else(|| name.clone());
+                if capture.file_type == CaptureType::Video {
+                    html.push_str(&format!("<p><a href=\"{}\" title=\"{}\">{}</a></p>\n", href, alt, name));
+                    continue;
                 }
+                let src = match std::fs::read(path) {
+                    Ok(bytes) => format!(
+                        "data:{};base64,{}",
+                        crate::ticketing::attachment_content_type(Path::new(path)),
+                        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes)
+                    ),
+                    Err(_) => href,
+                };
+                html.push_str(&format!(
+                    "<figure><img src=\"{}\" alt=\"{}\"><figcaption>{}</figcaption></figure>\n",
+                    src, alt, name
+                ));
+            }
+            html.push_str("</details>\n");
+        }
+        html.push_str("</body>\n</html>\n");
 
-                content.push_str("\n\n");
+        let report_path = session_folder.join("session-report.html");
+        self.file_writer.write_file(&report_path, &html)?;
+        Ok(report_path.to_string_lossy().to_string())
+    }
 
-                // Bug metadata
-                content.push_str(&format!("- **Type:** {}\n", bug.bug_type.as_str()));
-                content.push_str(&format!("- **Status:** {}\n", bug.status.as_str()));
+    /// Generate session-report.pdf: the session summary, then each bug on its own
+    /// page with its notes and descriptions and its screenshots embedded (the
+    /// annotated copy where there is one). Unlike the HTML report it is a single
+    /// self-contained file, ready to attach to an email or upload to a
+    /// document system. Videos are listed by name only.
+    pub fn generate_pdf_report(&self, session_id: &str) -> Result<String, String> {
+        let (session, bugs, links, captures, locale) = self.load_report(session_id)?;
 
-                if let Some(version) = &bug.software_version {
-                    content.push_str(&format!("- **Software Version:** {}\n", version));
-                }
+        let session_folder = PathBuf::from(&session.folder_path);
+        let overview = std::fs::read_to_string(session_folder.join("session-summary.md"))
+            .ok()
+            .and_then(|existing| existing_overview(&existing))
+            .map(|section| section.trim_start_matches("## Overview").trim().to_string());
+        let data = summary_data(&session, &bugs, &links, overview, &locale);
+
+        let mut pdf = PdfReport::new("QA Session Report");
+        pdf.text("QA Session Report", Font::Bold, 22.0);
+        pdf.space(8.0);
+        for (label, value) in [
+            ("Session ID", Some(data.session_id.as_str())),
+            ("Started", Some(data.started.as_str())),
+            ("Ended", Some(data.ended.as_deref().unwrap_or("In Progress"))),
+            ("Duration", data.duration.as_deref()),
+            ("Status", Some(data.status.as_str())),
+            ("Bug Count", Some(data.bug_count.as_str())),
+        ] {
+            if let Some(value) = value {
+                pdf.text(&format!("{}: {}", label, value), Font::Regular, 11.0);
+            }
+        }
+        for (label, text) in [("Overview", &data.overview), ("Session Notes", &data.notes)] {
+            if let Some(text) = text.as_deref().filter(|t| !t.trim().is_empty()) {
+                pdf.space(12.0);
+                pdf.text(label, Font::Bold, 14.0);
+                pdf.space(4.0);
+                pdf.text(text, Font::Regular, 10.0);
+            }
+        }
+        if !data.bugs.is_empty() {
+            pdf.space(12.0);
+            pdf.text("Bugs", Font::Bold, 14.0);
+            pdf.space(4.0);
+            for bug_data in &data.bugs {
+                pdf.text(
+                    &format!("{} - {} ({}, {})", bug_data.display_id, bug_data.title, bug_data.bug_type, bug_data.status),
+                    Font::Regular,
+                    10.0,
+                );
+            }
+        }
 
-                // Notes
-                if let Some(notes) = &bug.notes {
-                    if !notes.trim().is_empty() {
-                        content.push_str(&format!("\n**Notes:**\n{}\n", notes));
-                    }
+        for (bug, bug_data) in bugs.iter().zip(&data.bugs) {
+            pdf.page_break();
+            pdf.text(&format!("{} - {}", bug_data.display_id, bug_data.title), Font::Bold, 16.0);
+            pdf.space(4.0);
+            pdf.text(
+                &format!(
+                    "Type: {} | Status: {} | Ticket: {} | Created: {}",
+                    bug_data.bug_type, bug_data.status, bug_data.ticket, bug_data.created_at
+                ),
+                Font::Regular,
+                9.0,
+            );
+            if let Some(related) = &bug_data.related {
+                pdf.text(&format!("Related: {}", related), Font::Regular, 9.0);
+            }
+            if let Some(time_spent) = &bug_data.time_spent {
+                pdf.text(&format!("Time spent: {}", time_spent), Font::Regular, 9.0);
+            }
+            for (label, text) in [
+                ("Notes", &bug_data.notes),
+                ("Description", &bug_data.description),
+                ("AI Description", &bug_data.ai_description),
+            ] {
+                if let Some(text) = text.as_deref().filter(|t| !t.trim().is_empty()) {
+                    pdf.space(10.0);
+                    pdf.text(label, Font::Bold, 12.0);
+                    pdf.space(2.0);
+                    pdf.text(text, Font::Regular, 10.0);
                 }
-
-                // Description
-                if let Some(desc) = &bug.description {
-                    if !desc.trim().is_empty() {
-                        content.push_str(&format!("\n**Description:**\n{}\n", desc));
-                    }
+            }
+            for capture in captures.iter().filter(|c| c.bug_id.as_deref() == Some(bug.id.as_str())) {
+                pdf.space(10.0);
+                if capture.file_type == CaptureType::Video {
+                    pdf.text(&format!("Video: {}", capture.file_name), Font::Regular, 9.0);
+                    continue;
                 }
-
-                // AI Description
-                if let Some(ai_desc) = &bug.ai_description {
-                    if !ai_desc.trim().is_empty() {
-                        content.push_str(&format!("\n**AI Description:**\n{}\n", ai_desc));
+                let path = capture.annotated_path.as_deref().unwrap_or(&capture.file_path);
+                match pdf.image(Path::new(path)) {
+                    Ok(()) => pdf.text(&capture.file_name, Font::Regular, 8.0),
+                    Err(e) => {
+                        eprintln!("Warning: skipping screenshot in PDF report: {}", e);
+                        pdf.text(&format!("{} (could not be embedded)", capture.file_name), Font::Regular, 9.0);
                     }
                 }
-
-                content.push('\n');
             }
         }
 
-        Ok(content)
+        let report_path = session_folder.join("session-report.pdf");
+        std::fs::write(&report_path, pdf.finish())
+            .map_err(|e| format!("Failed to write file {}: {}", report_path.display(), e))?;
+        Ok(report_path.to_string_lossy().to_string())
+    }
+
+    /// Generate session-bugs.csv: one row per bug. The delimiter and date order
+    /// follow the export locale so the file opens cleanly in regional spreadsheets.
+    pub fn generate_csv_export(&self, session_id: &str) -> Result<String, String> {
+        let (session, bugs, captures, locale) = {
+            let conn = self.db_conn.lock().unwrap();
+            let session = SessionRepository::new(&conn)
+                .get(session_id)
+                .map_err(|e| format!("Failed to get session: {}", e))?
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            let bugs = BugRepository::new(&conn)
+                .list_by_session(session_id)
+                .map_err(|e| format!("Failed to list bugs: {}", e))?;
+            let captures = CaptureRepository::new(&conn)
+                .list_by_session(session_id)
+                .map_err(|e| format!("Failed to list captures: {}", e))?;
+            (session, bugs, captures, ExportLocale::from_settings(&conn))
+        };
+
+        let delimiter = locale.csv_delimiter();
+        let timezone = session.timezone.as_deref();
+        let until = session_end(&session);
+        let mut rows = vec![
+            ["Bug ID", "Title", "Type", "Status", "Ticket", "Created", "Captures", "Time Spent (min)", "Software Version"]
+                .map(String::from)
+                .to_vec(),
+        ];
+        for bug in &bugs {
+            let capture_count = captures.iter().filter(|c| c.bug_id.as_deref() == Some(bug.id.as_str())).count();
+            rows.push(vec![
+                bug.display_id.clone(),
+                bug.title.clone().unwrap_or_default(),
+                bug.bug_type.as_str().to_string(),
+                bug.status.as_str().to_string(),
+                bug.external_ticket_key.clone().unwrap_or_default(),
+                locale.format_datetime(&bug.created_at, timezone),
+                locale.format_number(capture_count as f64, 0),
+                bug_timing::time_spent(bug, until)
+                    .map(|spent| locale.format_number(spent.num_seconds() as f64 / 60.0, 1))
+                    .unwrap_or_default(),
+                bug.software_version.clone().unwrap_or_default(),
+            ]);
+        }
+        let csv: String = rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<String> = row.iter().map(|field| csv_field(field, delimiter)).collect();
+                fields.join(&delimiter.to_string()) + "\r\n"
+            })
+            .collect();
+
+        let csv_path = PathBuf::from(&session.folder_path).join("session-bugs.csv");
+        self.file_writer.write_file(&csv_path, &csv)?;
+        Ok(csv_path.to_string_lossy().to_string())
+    }
+
+    fn load_report(&self, session_id: &str) -> Result<ReportData, String> {
+        let conn = self.db_conn.lock().unwrap();
+        let session = SessionRepository::new(&conn)
+            .get(session_id)
+            .map_err(|e| format!("Failed to get session: {}", e))?
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        let bugs = BugRepository::new(&conn)
+            .list_by_session(session_id)
+            .map_err(|e| format!("Failed to list bugs: {}", e))?;
+        let links = BugLinkRepository::new(&conn)
+            .list_by_session(session_id)
+            .map_err(|e| format!("Failed to list bug links: {}", e))?;
+        let captures = CaptureRepository::new(&conn)
+            .list_by_session(session_id)
+            .map_err(|e| format!("Failed to list captures: {}", e))?;
+        Ok((session, bugs, links, captures, ExportLocale::from_settings(&conn)))
+    }
+
+    /// Build summary markdown content from the session-summary template
+    fn build_summary_content(
+        &self,
+        session: &Session,
+        bugs: &[Bug],
+        overview: Option<String>,
+    ) -> Result<String, String> {
+        let (template, locale, links, environment_diff) = {
+            let conn = self.db_conn.lock().unwrap();
+            let links = BugLinkRepository::new(&conn)
+                .list_by_session(&session.id)
+                .map_err(|e| format!("Failed to list bug links: {}", e))?;
+            let environment_diff = environment_diff(&conn, &session.id)?;
+            (load_summary_template(&conn), ExportLocale::from_settings(&conn), links, environment_diff)
+        };
+        let mut data = summary_data(session, bugs, &links, overview, &locale);
+        data.environment_changes = environment_diff.map(|diff| diff.to_markdown());
+        Ok(render_summary(&template, &data))
     }
 
     /// Generate AI overview of all bugs using Claude CLI
@@ -251,8 +495,14 @@ impl SessionSummaryGenerator {
 
         prompt.push_str("\nProvide a high-level summary of this testing session's findings.\n");
 
+        let params = {
+            let conn = self.db_conn.lock().unwrap();
+            AiModelSettings::load(&conn).resolve(ModelTask::Summarize, None)
+        };
+
         // Create request
         let request = ClaudeRequest::new_text(prompt, PromptTask::Custom)
+            .with_params(params)
             .with_timeout(120); // 2 minute timeout for summaries
 
         // Invoke Claude
@@ -264,10 +514,167 @@ impl SessionSummaryGenerator {
     }
 }
 
+/// Markdown for a bug's ticket: a link once filed, otherwise a marker.
+pub fn ticket_link(bug: &Bug) -> String {
+    match (&bug.external_ticket_key, &bug.external_ticket_url) {
+        (Some(key), Some(url)) => format!("[{}]({})", key, url),
+        (Some(key), None) => key.clone(),
+        (None, Some(url)) => format!("<{}>", url),
+        (None, None) => "Not yet filed".to_string(),
+    }
+}
+
+/// Stylesheet of session-report.html, kept inline so the file stands alone.
+const HTML_REPORT_STYLE: &str = "<style>
+body { font-family: system-ui, sans-serif; max-width: 960px; margin: 2em auto; padding: 0 1em; color: #222; }
+details { border: 1px solid #ddd; border-radius: 6px; margin: 1em 0; padding: 0 1em 1em; }
+summary { cursor: pointer; }
+summary h2 { display: inline; font-size: 1.2em; }
+pre { white-space: pre-wrap; background: #f6f6f6; padding: 0.5em; }
+img { max-width: 100%; border: 1px solid #ccc; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1em; }
+th, td { border: 1px solid #ddd; padding: 4px 8px; text-align: left; vertical-align: top; }
+td pre { margin: 0; padding: 0; background: none; }
+</style>
+";
+
+/// A console parse as a table of entries by category; output that isn't a
+/// parse result is shown as preformatted text.
+fn console_table_html(parsed: &str) -> String {
+    let Ok(parse) = ConsoleParse::from_json(parsed) else {
+        return format!("<pre>{}</pre>\n", html_escape(parsed));
+    };
+    let mut rows = String::new();
+    for (category, items) in [
+        ("Error", &parse.errors),
+        ("Warning", &parse.warnings),
+        ("Stack Trace", &parse.stack_traces),
+        ("Log", &parse.logs),
+    ] {
+        for item in items {
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td><pre>{}</pre></td></tr>\n",
+                category,
+                html_escape(item.trim_end())
+            ));
+        }
+    }
+    if rows.is_empty() {
+        return "<p>No console entries.</p>\n".to_string();
+    }
+    format!("<table>\n<thead><tr><th>Type</th><th>Entry</th></tr></thead>\n<tbody>\n{}</tbody>\n</table>\n", rows)
+}
+
+pub(crate) fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Quote a CSV field when it contains the delimiter, quotes or line breaks.
+fn csv_field(value: &str, delimiter: char) -> String {
+    if value.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// A bug's links as read from that bug, e.g. "BUG-004 (duplicate), BUG-007 (blocked by)".
+/// None when the bug has no links to other bugs in `bugs`.
+pub fn related_bugs_text(bug: &Bug, bugs: &[Bug], links: &[BugLink]) -> Option<String> {
+    let display_id = |id: &str| bugs.iter().find(|b| b.id == id).map(|b| b.display_id.as_str());
+    let related: Vec<String> = links
+        .iter()
+        .filter_map(|link| {
+            let (other, outgoing) = if link.bug_id == bug.id {
+                (&link.related_bug_id, true)
+            } else if link.related_bug_id == bug.id {
+                (&link.bug_id, false)
+            } else {
+                return None;
+            };
+            Some(format!("{} ({})", display_id(other)?, link.kind.label(outgoing)))
+        })
+        .collect();
+    if related.is_empty() {
+        None
+    } else {
+        Some(related.join(", "))
+    }
+}
+
+/// Where a bug still capturing stops counting: the session's end, or now
+/// while it runs.
+fn session_end(session: &Session) -> DateTime<Utc> {
+    session
+        .ended_at
+        .as_deref()
+        .and_then(|ended| DateTime::parse_from_rfc3339(ended).ok())
+        .map_or_else(Utc::now, |ended| ended.with_timezone(&Utc))
+}
+
+/// Template values for a session and its bugs, formatted for `locale`.
+/// Times are shown in the session's recorded time zone.
+fn summary_data(
+    session: &Session,
+    bugs: &[Bug],
+    links: &[BugLink],
+    overview: Option<String>,
+    locale: &ExportLocale,
+) -> SummaryData {
+    let timezone = session.timezone.as_deref();
+    let until = session_end(session);
+    let duration = session.ended_at.as_ref().and_then(|ended| {
+        let start = DateTime::parse_from_rfc3339(&session.started_at).ok()?;
+        let end = DateTime::parse_from_rfc3339(ended).ok()?;
+        let duration = end.signed_duration_since(start);
+        Some(format!("{}h {}m", duration.num_hours(), duration.num_minutes() % 60))
+    });
+
+    SummaryData {
+        session_id: session.id.clone(),
+        started: locale.format_datetime(&session.started_at, timezone),
+        ended: session.ended_at.as_deref().map(|ended| locale.format_datetime(ended, timezone)),
+        duration,
+        bug_count: locale.format_number(bugs.len() as f64, 0),
+        status: session.status.as_str().to_string(),
+        notes: session.session_notes.clone(),
+        environment_changes: None,
+        overview,
+        bugs: bugs
+            .iter()
+            .map(|bug| SummaryBugData {
+                display_id: bug.display_id.clone(),
+                title: bug.title.clone().unwrap_or_else(|| "(No title)".to_string()),
+                bug_type: bug.bug_type.as_str().to_string(),
+                status: bug.status.as_str().to_string(),
+                ticket: ticket_link(bug),
+                created_at: locale.format_datetime(&bug.created_at, timezone),
+                software_version: bug.software_version.clone(),
+                notes: bug.notes.clone(),
+                description: bug.description.clone(),
+                ai_description: bug.ai_description.clone(),
+                related: related_bugs_text(bug, bugs, links),
+                time_spent: bug_timing::time_spent(bug, until).map(bug_timing::format_time_spent),
+            })
+            .collect(),
+    }
+}
+
+/// The `## Overview` section (heading included) of an existing summary.
+fn existing_overview(content: &str) -> Option<String> {
+    let start = content.find("## Overview\n")?;
+    let rest = &content[start..];
+    let end = rest[1..].find("\n## ").map(|i| i + 2).unwrap_or(rest.len());
+    Some(rest[..end].to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::database::{init_database, BugStatus, BugType, SessionStatus};
+    use crate::database::{init_database, BugStatus, BugType, SessionStatus, SettingsOps, SettingsRepository};
     use std::collections::HashMap;
     use std::sync::Mutex as StdMutex;
 
@@ -284,6 +691,7 @@ mod tests {
                     content: self.response_content.clone(),
                     task: request.task,
                     bug_id: request.bug_id,
+                    cached: false,
                 })
             } else {
                 Err(crate::claude_cli::ClaudeError::InvocationFailed("Mock failure".to_string()))
@@ -330,6 +738,8 @@ mod tests {
             original_snip_path: None,
             created_at: "2024-01-15T10:00:00Z".to_string(),
             profile_id: None,
+            unlocked_at: None,
+            timezone: None,
         };
 
         SessionRepository::new(conn).create(&session).unwrap();
@@ -346,17 +756,13 @@ mod tests {
                 bug_type: BugType::Bug,
                 title: Some("Login button not responding".to_string()),
                 notes: Some("Clicked multiple times, no response".to_string()),
-                description: None,
                 ai_description: Some("The login button does not respond to clicks.".to_string()),
                 status: BugStatus::Captured,
-                meeting_id: None,
                 software_version: Some("1.2.3".to_string()),
-                console_parse_json: None,
-                metadata_json: None,
-                custom_metadata: None,
                 folder_path: "/tmp/test-session/bug_001".to_string(),
                 created_at: "2024-01-15T10:15:00Z".to_string(),
                 updated_at: "2024-01-15T10:15:00Z".to_string(),
+                ..Default::default()
             },
             Bug {
                 id: "bug-2".to_string(),
@@ -366,17 +772,11 @@ mod tests {
                 bug_type: BugType::Feedback,
                 title: Some("Is this behavior expected?".to_string()),
                 notes: Some("Form submits without validation".to_string()),
-                description: None,
-                ai_description: None,
                 status: BugStatus::Captured,
-                meeting_id: None,
-                software_version: None,
-                console_parse_json: None,
-                metadata_json: None,
-                custom_metadata: None,
                 folder_path: "/tmp/test-session/bug_002".to_string(),
                 created_at: "2024-01-15T11:00:00Z".to_string(),
                 updated_at: "2024-01-15T11:00:00Z".to_string(),
+                ..Default::default()
             },
         ];
 
@@ -449,6 +849,262 @@ mod tests {
         assert!(content.contains("This session found 2 critical issues"));
     }
 
+    #[test]
+    fn test_refresh_summary_includes_ticket_links() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let session = create_test_session(&conn);
+        let _bugs = create_test_bugs(&conn, &session.id);
+        BugRepository::new(&conn)
+            .set_external_ticket("bug-1", "issue-1", "QA-7", "https://linear.app/qa/issue/QA-7")
+            .unwrap();
+
+        let db_conn = Arc::new(std::sync::Mutex::new(conn));
+        let file_writer = Arc::new(MockFileWriter::new());
+        let generator = SessionSummaryGenerator::with_deps(db_conn, file_writer.clone(), None);
+
+        generator.refresh_summary(&session.id).unwrap();
+
+        let files = file_writer.get_written_files();
+        let content = files.values().next().unwrap();
+        assert!(content.contains("- **Ticket:** [QA-7](https://linear.app/qa/issue/QA-7)"));
+        assert!(content.contains("- **Ticket:** Not yet filed"));
+    }
+
+    #[test]
+    fn test_summary_lists_related_bugs() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let session = create_test_session(&conn);
+        let _bugs = create_test_bugs(&conn, &session.id);
+        BugLinkRepository::new(&conn)
+            .create("bug-2", "bug-1", crate::database::BugLinkKind::Duplicates)
+            .unwrap();
+
+        let db_conn = Arc::new(std::sync::Mutex::new(conn));
+        let file_writer = Arc::new(MockFileWriter::new());
+        let generator = SessionSummaryGenerator::with_deps(db_conn, file_writer.clone(), None);
+
+        generator.generate_summary(&session.id, false).unwrap();
+
+        let files = file_writer.get_written_files();
+        let content = files.values().next().unwrap();
+        assert!(content.contains("- **Related:** BUG-002 (duplicated by)"));
+        assert!(content.contains("- **Related:** BUG-001 (duplicate)"));
+    }
+
+    #[test]
+    fn test_summary_shows_environment_changes() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let session = create_test_session(&conn);
+        conn.execute_batch(
+            r#"INSERT INTO sessions (id, started_at, folder_path, environment_json)
+               VALUES ('session-prev', '2024-01-14T10:00:00Z', '/tmp/prev', '{"build":"4.1.0"}');
+               UPDATE sessions SET environment_json = '{"build":"4.2.0"}' WHERE id = 'session-123';"#,
+        )
+        .unwrap();
+
+        let db_conn = Arc::new(std::sync::Mutex::new(conn));
+        let file_writer = Arc::new(MockFileWriter::new());
+        let generator = SessionSummaryGenerator::with_deps(db_conn, file_writer.clone(), None);
+
+        generator.generate_summary(&session.id, false).unwrap();
+
+        let files = file_writer.get_written_files();
+        let content = files.values().next().unwrap();
+        assert!(content.contains("### Environment Changes\n\nSince the previous session of this profile:\n\n- **Build:** 4.1.0 → 4.2.0\n"));
+    }
+
+    #[test]
+    fn test_generate_html_report_escapes_and_links_captures() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let session = create_test_session(&conn);
+        let _bugs = create_test_bugs(&conn, &session.id);
+        conn.execute("UPDATE bugs SET notes = ?1 WHERE id = 'bug-1'", ["<script> & friends"])
+            .unwrap();
+        CaptureRepository::new(&conn)
+            .create(&crate::database::Capture {
+                id: "cap-1".to_string(),
+                bug_id: Some("bug-1".to_string()),
+                session_id: session.id.clone(),
+                file_name: "capture-001.png".to_string(),
+                file_path: "/tmp/test-session/bug_001/capture-001.png".to_string(),
+                file_type: CaptureType::Screenshot,
+                created_at: "2024-01-15T10:16:00Z".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let db_conn = Arc::new(std::sync::Mutex::new(conn));
+        let file_writer = Arc::new(MockFileWriter::new());
+        let generator = SessionSummaryGenerator::with_deps(db_conn, file_writer.clone(), None);
+
+        let path = generator.generate_html_report(&session.id).unwrap();
+        assert!(path.ends_with("session-report.html"));
+
+        let files = file_writer.get_written_files();
+        let html = files.values().next().unwrap();
+        assert!(html.contains("<h2>BUG-001 - Login button not responding</h2>"));
+        assert!(html.contains("&lt;script&gt; &amp; friends"));
+        assert!(html.contains("<img src=\"bug_001/capture-001.png\" alt=\"capture-001.png\">"));
+        assert!(html.contains("BUG-002"));
+    }
+
+    #[test]
+    fn test_html_report_inlines_screenshots_with_alt_text_and_console_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let session = create_test_session(&conn);
+        conn.execute("UPDATE sessions SET folder_path = ?1", [dir.path().to_string_lossy()]).unwrap();
+        let _bugs = create_test_bugs(&conn, &session.id);
+        conn.execute(
+            "UPDATE bugs SET console_parse_json = ?1 WHERE id = 'bug-1'",
+            [r#"{"errors": ["TypeError: x is <undefined>"], "stackTraces": ["at login.js:10"]}"#],
+        )
+        .unwrap();
+        let shot = dir.path().join("capture-001.png");
+        std::fs::write(&shot, b"\x89PNG fake").unwrap();
+        CaptureRepository::new(&conn)
+            .create(&crate::database::Capture {
+                id: "cap-1".to_string(),
+                bug_id: Some("bug-1".to_string()),
+                session_id: session.id.clone(),
+                file_name: "capture-001.png".to_string(),
+                file_path: shot.to_string_lossy().to_string(),
+                file_type: CaptureType::Screenshot,
+                created_at: "2024-01-15T10:16:00Z".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        CaptureRepository::new(&conn).set_alt_text("cap-1", Some("Login form showing \"Invalid token\"")).unwrap();
+
+        let file_writer = Arc::new(MockFileWriter::new());
+        let generator = SessionSummaryGenerator::with_deps(Arc::new(StdMutex::new(conn)), file_writer.clone(), None);
+        generator.generate_html_report(&session.id).unwrap();
+
+        let files = file_writer.get_written_files();
+        let html = files.values().next().unwrap();
+        assert!(html.contains("<style>"));
+        assert!(html.contains("<details open>\n<summary><h2>BUG-001 - Login button not responding</h2></summary>"));
+        assert!(html.contains(
+            "<img src=\"data:image/png;base64,iVBORyBmYWtl\" alt=\"Login form showing &quot;Invalid token&quot;\"><figcaption>capture-001.png</figcaption>"
+        ));
+        assert!(html.contains("<tr><td>Error</td><td><pre>TypeError: x is &lt;undefined&gt;</pre></td></tr>"));
+        assert!(html.contains("<tr><td>Stack Trace</td><td><pre>at login.js:10</pre></td></tr>"));
+    }
+
+    #[test]
+    fn test_generate_pdf_report_embeds_screenshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let session = create_test_session(&conn);
+        conn.execute("UPDATE sessions SET folder_path = ?1", [dir.path().to_string_lossy()]).unwrap();
+        let _bugs = create_test_bugs(&conn, &session.id);
+        let shot = dir.path().join("capture-001.png");
+        image::RgbaImage::from_pixel(64, 48, image::Rgba([0, 120, 255, 255])).save(&shot).unwrap();
+        for (id, path) in [("cap-1", shot.clone()), ("cap-2", dir.path().join("missing.png"))] {
+            CaptureRepository::new(&conn)
+                .create(&crate::database::Capture {
+                    id: id.to_string(),
+                    bug_id: Some("bug-1".to_string()),
+                    session_id: session.id.clone(),
+                    file_name: format!("{}.png", id),
+                    file_path: path.to_string_lossy().to_string(),
+                    file_type: CaptureType::Screenshot,
+                    created_at: "2024-01-15T10:16:00Z".to_string(),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let generator = SessionSummaryGenerator::with_deps(Arc::new(StdMutex::new(conn)), Arc::new(MockFileWriter::new()), None);
+        let path = generator.generate_pdf_report(&session.id).unwrap();
+        assert!(path.ends_with("session-report.pdf"));
+
+        let pdf = std::fs::read(&path).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-"));
+        assert!(text.contains("(BUG-001 - Login button not responding)"));
+        assert_eq!(text.matches("/Subtype /Image").count(), 1);
+        assert!(text.contains("(cap-2.png \\(could not be embedded\\))"));
+    }
+
+    #[test]
+    fn test_summary_renders_times_in_session_timezone() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let mut session = create_test_session(&conn);
+        session.timezone = Some("America/New_York".to_string());
+        SessionRepository::new(&conn).update(&session).unwrap();
+
+        let db_conn = Arc::new(std::sync::Mutex::new(conn));
+        let file_writer = Arc::new(MockFileWriter::new());
+        let generator = SessionSummaryGenerator::with_deps(db_conn, file_writer.clone(), None);
+        generator.generate_summary(&session.id, false).unwrap();
+
+        let files = file_writer.get_written_files();
+        let content = files.values().next().unwrap();
+        assert!(content.contains("- **Started:** 2024-01-15 05:00:00 EST"));
+        assert!(content.contains("- **Ended:** 2024-01-15 07:30:00 EST"));
+        assert!(content.contains("- **Duration:** 2h 30m"));
+    }
+
+    #[test]
+    fn test_csv_export_follows_locale() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let session = create_test_session(&conn);
+        create_test_bugs(&conn, &session.id);
+        conn.execute("UPDATE bugs SET title = ?1 WHERE id = 'bug-2'", ["Totals; \"odd\""])
+            .unwrap();
+        conn.execute(
+            "UPDATE bugs SET metadata_json = ?1 WHERE id = 'bug-1'",
+            [r#"{"capture_periods":[{"startedAt":"2024-01-15T10:15:00Z","endedAt":"2024-01-15T10:25:00Z"},{"startedAt":"2024-01-15T10:40:00Z","endedAt":"2024-01-15T10:42:30Z"}]}"#],
+        )
+        .unwrap();
+        SettingsRepository::new(&conn)
+            .set(crate::time_format::LOCALE_KEY, "de-DE")
+            .unwrap();
+
+        let db_conn = Arc::new(std::sync::Mutex::new(conn));
+        let file_writer = Arc::new(MockFileWriter::new());
+        let generator = SessionSummaryGenerator::with_deps(db_conn, file_writer.clone(), None);
+        let path = generator.generate_csv_export(&session.id).unwrap();
+        assert!(path.ends_with("session-bugs.csv"));
+
+        let files = file_writer.get_written_files();
+        let lines: Vec<&str> = files.values().next().unwrap().lines().collect();
+        assert_eq!(lines[0], "Bug ID;Title;Type;Status;Ticket;Created;Captures;Time Spent (min);Software Version");
+        assert_eq!(
+            lines[1],
+            "BUG-001;Login button not responding;bug;captured;;15.01.2024 10:15:00 UTC;0;12,5;1.2.3"
+        );
+        assert!(lines[2].starts_with("BUG-002;\"Totals; \"\"odd\"\"\";feedback;"));
+    }
+
+    #[test]
+    fn test_existing_overview_is_extracted() {
+        let content = "# QA Session Summary\n\n## Overview\n\nMostly login issues.\n\n## Bugs Captured\n\n### BUG-001\n";
+        assert_eq!(
+            existing_overview(content).as_deref(),
+            Some("## Overview\n\nMostly login issues.\n\n")
+        );
+        assert_eq!(existing_overview("# QA Session Summary\n\n## Bugs Captured\n"), None);
+    }
+
     #[test]
     fn test_generate_summary_no_bugs() {
         let conn = Connection::open_in_memory().unwrap();
@@ -0,0 +1,159 @@
+//! Known settings keys and how sensitive their values are.
+//!
+//! Bulk reads (`get_all_settings`, `get_setting`) go through [`redact`], so
+//! values of secret keys never reach a webview that only wanted to list
+//! settings. The raw value of a secret is only available from `get_secret`,
+//! which is restricted to the main window. Keys missing from [`SCHEMA`] are
+//! classified by name, so a new `*.api_key` or `*.token` setting is masked
+//! even before it is listed here.
+
+use serde::Serialize;
+
+use crate::database::Setting;
+
+/// Value returned in place of a stored secret.
+pub const MASK: &str = "********";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingClass {
+    Public,
+    /// Credentials; masked in bulk reads
+    Secret,
+}
+
+/// A documented settings key. A key ending in `.` describes every key with
+/// that prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SettingSpec {
+    pub key: &'static str,
+    pub class: SettingClass,
+    pub description: &'static str,
+}
+
+const fn public(key: &'static str, description: &'static str) -> SettingSpec {
+    SettingSpec { key, class: SettingClass::Public, description }
+}
+
+const fn secret(key: &'static str, description: &'static str) -> SettingSpec {
+    SettingSpec { key, class: SettingClass::Secret, description }
+}
+
+pub const SCHEMA: &[SettingSpec] = &[
+    secret("ticketing.api_key", "Linear API key"),
+    public("ticketing.team_id", "Linear team for new tickets"),
+    public("ticketing.workspace_id", "Linear workspace"),
+    public(crate::ticketing::PROVIDER_KEY, "Ticketing service for new tickets (linear or jira)"),
+    public(crate::ticketing::JIRA_SITE_URL_KEY, "Jira Cloud site URL"),
+    public(crate::ticketing::JIRA_EMAIL_KEY, "Jira account email"),
+    secret(crate::ticketing::JIRA_API_TOKEN_KEY, "Jira API token"),
+    public(crate::ticketing::JIRA_PROJECT_KEY_KEY, "Jira project for new tickets"),
+    public(crate::database::TESTER_NAME_KEY, "Name recorded in the audit log"),
+    public(crate::staging_watcher::STAGING_FOLDER_KEY, "Folder watched for captures from other tools"),
+    public(crate::bug_auto_stop::AUTO_STOP_KEY, "End idle bug captures automatically"),
+    public(crate::capture_quota::CAPTURE_QUOTA_KEY, "Capture count and size per bug and session that triggers a warning"),
+    public(crate::capture_naming::FILENAME_PATTERN_KEY, "File name pattern for new captures"),
+    public(crate::capture_routing::GRACE_WINDOW_KEY, "Seconds after a bug ends that new captures still go to it"),
+    public(crate::window_theme::THEME_KEY, "Appearance of secondary windows"),
+    public(crate::window_geometry::GEOMETRY_KEY_PREFIX, "Last position and size of a secondary window"),
+    public(crate::summary_template::SUMMARY_TEMPLATE_PATH_KEY, "Custom session summary template"),
+    public(crate::ui_tree::UI_TREE_SNAPSHOT_KEY, "Capture the UI tree with screenshots"),
+    public(crate::claude_cli::MODEL_SETTINGS_KEY, "Claude model per task"),
+    public(crate::time_format::LOCALE_KEY, "Locale for exported dates"),
+    public(crate::video_metadata::FFPROBE_PATH_KEY, "ffprobe executable"),
+    public(crate::video_frames::FFMPEG_PATH_KEY, "ffmpeg executable"),
+    public(crate::media_offload::OFFLOAD_VIDEOS_KEY, "Store recordings outside the session folder"),
+    public(crate::media_offload::MEDIA_ROOT_KEY, "Folder for offloaded recordings"),
+    public(crate::media_offload::VIDEO_EXPORT_MODE_KEY, "How recordings are exported"),
+    public(crate::export_hooks::EXPORT_HOOK_KEY, "Command run after each export"),
+    public(crate::metrics::METRICS_KEY, "Localhost metrics endpoint (opt-in)"),
+    public(crate::description_lint::LINT_KEY, "Spelling and glossary checks for bug descriptions"),
+    public(crate::claude_cli::SEVERITY_RUBRIC_KEY, "Rubric for AI severity suggestions"),
+    public(crate::symbolication::SYMBOLICATION_KEY, "Source maps for symbolicating console stack traces"),
+    public(crate::crash_dumps::CRASH_PROCESS_KEY, "Executable whose crash dumps are collected during sessions"),
+    public(crate::network_snapshot::NETWORK_SNAPSHOT_KEY, "Endpoints timed in network snapshots"),
+    public(crate::perf_capture::PERF_CAPTURE_KEY, "CPU/RAM sampling of the app under test while a bug is capturing"),
+    public(crate::cursor_overlay::CURSOR_OVERLAY_KEY, "Draw the cursor and last click into native screenshots"),
+    public(crate::capture_diff::DIFF_ANNOTATION_KEY, "Box what changed between consecutive screenshots of a bug"),
+    public(crate::item_types::TYPE_LABELS_KEY, "Ticket labels per item type (bug, feature, feedback, question)"),
+    public(crate::claude_cli::TRANSLATION_KEY, "Translate notes to the ticket language before export"),
+    public(crate::tone_filter::TONE_FILTER_KEY, "Flag and soften venting in ticket descriptions"),
+    public(crate::stamp_library::STAMP_LIBRARY_KEY, "Shared folder holding the team's stamps and shape presets"),
+    public(crate::template_library::TEMPLATE_SELECTION_KEY, "Named bug template per bug type and per profile"),
+    public(crate::template_variables::TEMPLATE_VARIABLES_KEY, "User-defined {var.name} values for bug templates"),
+    public(crate::quick_toggles::AI_ENABLED_KEY, "Call Claude for suggestions (switchable from the tray)"),
+    public(crate::quick_toggles::AUTO_CONSOLE_PARSE_KEY, "Parse screenshots marked as console output right away"),
+    public(crate::quick_toggles::NOTIFICATIONS_KEY, "Show notifications (switchable from the tray)"),
+    public(crate::crash_dumps::CRASH_DUMP_FOLDER_KEY, "Folder Windows Error Reporting writes crash dumps to"),
+];
+
+/// Name fragments that mark an unlisted key as secret.
+const SECRET_NAME_HINTS: &[&str] = &["api_key", "apikey", "token", "password", "secret"];
+
+pub fn spec(key: &str) -> Option<&'static SettingSpec> {
+    SCHEMA
+        .iter()
+        .find(|s| s.key == key || (s.key.ends_with('.') && key.starts_with(s.key)))
+}
+
+pub fn classify(key: &str) -> SettingClass {
+    match spec(key) {
+        Some(spec) => spec.class,
+        None => {
+            let lower = key.to_ascii_lowercase();
+            if SECRET_NAME_HINTS.iter().any(|hint| lower.contains(hint)) {
+                SettingClass::Secret
+            } else {
+                SettingClass::Public
+            }
+        }
+    }
+}
+
+pub fn is_secret(key: &str) -> bool {
+    classify(key) == SettingClass::Secret
+}
+
+/// Mask secret values. Empty secrets stay empty, so the frontend can still
+/// tell whether one is configured.
+pub fn redact(mut setting: Setting) -> Setting {
+    if is_secret(&setting.key) && !setting.value.is_empty() {
+        setting.value = MASK.to_string();
+    }
+    setting
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setting(key: &str, value: &str) -> Setting {
+        Setting { key: key.to_string(), value: value.to_string(), updated_at: String::new() }
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("ticketing.api_key"), SettingClass::Secret);
+        assert_eq!(classify("ticketing.team_id"), SettingClass::Public);
+        assert_eq!(classify("window.geometry.session-notes"), SettingClass::Public);
+        // Unlisted keys fall back to their name
+        assert_eq!(classify("jira.api_token"), SettingClass::Secret);
+        assert_eq!(classify("export.password"), SettingClass::Secret);
+        assert_eq!(classify("ui.compact"), SettingClass::Public);
+    }
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(redact(setting("ticketing.api_key", "lin_api_123")).value, MASK);
+        assert_eq!(redact(setting("ticketing.api_key", "")).value, "");
+        assert_eq!(redact(setting("ticketing.team_id", "team-1")).value, "team-1");
+    }
+
+    #[test]
+    fn test_schema_keys_are_unique() {
+        let mut keys: Vec<&str> = SCHEMA.iter().map(|s| s.key).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), SCHEMA.len());
+    }
+}
@@ -0,0 +1,342 @@
+//! Always-on "hot import" watcher for a staging folder.
+//!
+//! Testers sometimes capture evidence before starting a session. When the
+//! `staging.folder` setting names a folder, it is watched even with no session
+//! active, and media files saved there are queued. When a session starts and
+//! the queue is not empty, the frontend is asked (`staging:import-prompt`)
+//! whether to import them; imported files are moved into the session's
+//! `_unsorted/` folder with their original timestamps kept. Files that fail to
+//! import go back in the queue and are offered again.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::capture_watcher::CaptureWatcher;
+use crate::database::{self, Capture, CaptureOps, CaptureRepository};
+use crate::events;
+
+/// Settings key: folder to watch; unset or empty disables hot import.
+pub const STAGING_FOLDER_KEY: &str = "staging.folder";
+
+/// A media file waiting in the staging folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedFile {
+    pub path: String,
+    pub file_name: String,
+    pub size_bytes: u64,
+    /// When the file was written (its modification time), RFC 3339
+    pub captured_at: String,
+}
+
+/// A staged file that could not be imported.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagingImportReport {
+    pub imported: Vec<Capture>,
+    pub failed: Vec<ImportFailure>,
+}
+
+/// Describe `path` if it is a non-empty media file.
+pub fn staged_file(path: &Path) -> Option<StagedFile> {
+    if !CaptureWatcher::is_media_file(path) {
+        return None;
+    }
+    let metadata = std::fs::metadata(path).ok().filter(|m| m.is_file() && m.len() > 0)?;
+    let modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+    Some(StagedFile {
+        path: path.to_string_lossy().to_string(),
+        file_name: path.file_name()?.to_string_lossy().to_string(),
+        size_bytes: metadata.len(),
+        captured_at: DateTime::<Utc>::from(modified).to_rfc3339(),
+    })
+}
+
+/// Media files currently in `folder`, oldest first.
+pub fn scan_folder(folder: &Path) -> Vec<StagedFile> {
+    let mut files: Vec<StagedFile> = std::fs::read_dir(folder)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| staged_file(&e.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort_by(|a, b| a.captured_at.cmp(&b.captured_at));
+    files
+}
+
+/// Move staged files into `{session_folder}/_unsorted/` and record them as
+/// unsorted captures dated by their original modification time.
+pub fn import_staged(
+    conn: &Connection,
+    session_id: &str,
+    session_folder: &Path,
+    files: &[StagedFile],
+) -> StagingImportReport {
+    let dest_dir = session_folder.join("_unsorted");
+    let mut report = StagingImportReport::default();
+
+    for file in files {
+        match import_one(conn, session_id, &dest_dir, file) {
+            Ok(capture) => report.imported.push(capture),
+            Err(error) => report.failed.push(ImportFailure {
+                path: file.path.clone(),
+                error,
+            }),
+        }
+    }
+    report
+}
+
+fn import_one(conn: &Connection, session_id: &str, dest_dir: &Path, file: &StagedFile) -> Result<Capture, String> {
+    let source = Path::new(&file.path);
+    let modified = std::fs::metadata(source)
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("Staged file is no longer readable: {}", e))?;
+    std::fs::create_dir_all(dest_dir).map_err(|e| format!("Cannot create {:?}: {}", dest_dir, e))?;
+
+    let naming = crate::capture_naming::NamingContext::for_folder(conn, dest_dir).with_created_at(&file.captured_at);
+    let (file_name, capture_type) =
+        crate::make_capture_filename(source, crate::next_capture_number(dest_dir, &naming), &naming);
+    let dest_path = dest_dir.join(&file_name);
+
+    // Move (rename) the file; fall back to copy+delete for cross-volume
+    if std::fs::rename(source, &dest_path).is_err() {
+        std::fs::copy(source, &dest_path).map_err(|e| format!("Failed to copy {:?}: {}", source, e))?;
+        let _ = std::fs::remove_file(source);
+    }
+    // A cross-volume copy gets a fresh mtime; put the original back
+    if let Ok(dest) = std::fs::File::options().write(true).open(&dest_path) {
+        let _ = dest.set_modified(modified);
+    }
+
+    let capture = Capture {
+        id: Uuid::new_v4().to_string(),
+        bug_id: None,
+        session_id: session_id.to_string(),
+        file_name,
+        file_path: dest_path.to_string_lossy().to_string(),
+        file_type: capture_type,
+        annotated_path: None,
+        file_size_bytes: Some(file.size_bytes as i64),
+        is_console_capture: false,
+        parsed_content: None,
+        created_at: DateTime::<Utc>::from(modified).to_rfc3339(),
+        edited_at: None,
+        media_link: None,
+        video_duration_ms: None,
+        video_width: None,
+        video_height: None,
+        video_codec: None,
+        derived_from: None,
+        frame_timestamp_ms: None,
+        source_metadata: None,
+        alt_text: None,
+    };
+    CaptureRepository::new(conn)
+        .create(&capture)
+        .map_err(|e| format!("Failed to record capture: {}", e))?;
+    database::record_audit(
+        conn,
+        "capture.import_staged",
+        "capture",
+        &capture.id,
+        Some(serde_json::json!({ "source": file.path })),
+    )?;
+
+    Ok(capture)
+}
+
+/// Watches the staging folder and keeps the queue of files found there.
+///
+/// Dropping the struct stops the watcher.
+pub struct StagingWatcher {
+    queue: Arc<Mutex<Vec<StagedFile>>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl StagingWatcher {
+    /// Start watching `folder` (created if missing), queueing files already in it.
+    pub fn start(folder: PathBuf, app_handle: AppHandle) -> Result<Self, String> {
+        std::fs::create_dir_all(&folder)
+            .map_err(|e| format!("Cannot create staging folder {:?}: {}", folder, e))?;
+        let queue = Arc::new(Mutex::new(scan_folder(&folder)));
+
+        let q = Arc::clone(&queue);
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<Event, notify::Error>| {
+                let Ok(event) = res else { return };
+                // Writes and moves count too: a file created empty is queued once it has content
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    return;
+                }
+                for path in &event.paths {
+                    let Some(file) = staged_file(path) else { continue };
+                    let mut queue = q.lock().unwrap();
+                    if queue.iter().any(|f| f.path == file.path) {
+                        continue;
+                    }
+                    queue.push(file.clone());
+                    drop(queue);
+                    let _ = events::emit(&app_handle, &events::StagedFileQueued { file });
+                }
+            },
+            notify::Config::default(),
+        )
+        .map_err(|e| format!("Failed to create staging watcher: {e}"))?;
+
+        watcher
+            .watch(&folder, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch staging folder: {e}"))?;
+
+        Ok(Self {
+            queue,
+            _watcher: watcher,
+        })
+    }
+
+    /// Queued files that are still present, refreshed from disk.
+    pub fn queued(&self) -> Vec<StagedFile> {
+        let mut queue = self.queue.lock().unwrap();
+        *queue = queue.iter().filter_map(|f| staged_file(Path::new(&f.path))).collect();
+        queue.clone()
+    }
+
+    /// Remove and return the queued files with the given paths (all when `None`).
+    pub fn take(&self, paths: Option<&[String]>) -> Vec<StagedFile> {
+        let current = self.queued();
+        let mut queue = self.queue.lock().unwrap();
+        let (taken, kept): (Vec<_>, Vec<_>) = current
+            .into_iter()
+            .partition(|f| paths.is_none_or(|paths| paths.contains(&f.path)));
+        *queue = kept;
+        taken
+    }
+
+    /// Put taken files back in the queue, e.g. after a failed import, so they
+    /// are offered again. Files no longer in the folder are dropped.
+    pub fn requeue(&self, files: &[StagedFile]) {
+        let mut queue = self.queue.lock().unwrap();
+        for file in files.iter().filter_map(|f| staged_file(Path::new(&f.path))) {
+            if !queue.iter().any(|f| f.path == file.path) {
+                queue.push(file);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{init_database, CaptureOps, Session, SessionOps, SessionRepository, SessionStatus};
+    use std::time::Duration;
+
+    #[test]
+    fn test_scan_folder_skips_non_media_and_empty_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("phone.jpg"), b"jpg").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"text").unwrap();
+        std::fs::write(dir.path().join("empty.png"), b"").unwrap();
+
+        let files = scan_folder(dir.path());
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].file_name, "phone.jpg");
+        assert_eq!(files[0].size_bytes, 3);
+    }
+
+    #[test]
+    fn test_requeue_offers_failed_files_again() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("phone.jpg"), b"jpg").unwrap();
+        let watcher = StagingWatcher {
+            queue: Arc::new(Mutex::new(scan_folder(dir.path()))),
+            _watcher: RecommendedWatcher::new(|_: Result<Event, notify::Error>| {}, notify::Config::default()).unwrap(),
+        };
+
+        let taken = watcher.take(None);
+        assert_eq!(taken.len(), 1);
+        assert!(watcher.queued().is_empty());
+
+        let mut failed = taken.clone();
+        failed.push(StagedFile {
+            path: dir.path().join("gone.png").to_string_lossy().to_string(),
+            file_name: "gone.png".to_string(),
+            size_bytes: 1,
+            captured_at: Utc::now().to_rfc3339(),
+        });
+        watcher.requeue(&failed);
+        assert_eq!(watcher.queued(), taken);
+    }
+
+    #[test]
+    fn test_import_staged_moves_files_and_keeps_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let staging = dir.path().join("staging");
+        let session_folder = dir.path().join("session");
+        std::fs::create_dir_all(&staging).unwrap();
+
+        let photo = staging.join("IMG_0042.JPG");
+        std::fs::write(&photo, b"jpg").unwrap();
+        let taken = SystemTime::now() - Duration::from_secs(3 * 3600);
+        std::fs::File::options().write(true).open(&photo).unwrap().set_modified(taken).unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        SessionRepository::new(&conn)
+            .create(&Session {
+                id: "s-1".to_string(),
+                started_at: Utc::now().to_rfc3339(),
+                ended_at: None,
+                status: SessionStatus::Active,
+                folder_path: session_folder.to_string_lossy().to_string(),
+                session_notes: None,
+                environment_json: None,
+                original_snip_path: None,
+                created_at: Utc::now().to_rfc3339(),
+                profile_id: None,
+                unlocked_at: None,
+                timezone: None,
+            })
+            .unwrap();
+
+        let mut files = scan_folder(&staging);
+        files.push(StagedFile {
+            path: staging.join("gone.png").to_string_lossy().to_string(),
+            file_name: "gone.png".to_string(),
+            size_bytes: 1,
+            captured_at: Utc::now().to_rfc3339(),
+        });
+        let report = import_staged(&conn, "s-1", &session_folder, &files);
+
+        assert_eq!(report.imported.len(), 1);
+        assert_eq!(report.failed.len(), 1);
+        let capture = &report.imported[0];
+        assert_eq!(capture.file_name, "capture-001.jpg");
+        assert_eq!(capture.bug_id, None);
+        assert!(!photo.exists());
+
+        let dest = session_folder.join("_unsorted").join("capture-001.jpg");
+        assert_eq!(std::fs::metadata(&dest).unwrap().modified().unwrap(), taken);
+        assert_eq!(capture.created_at, DateTime::<Utc>::from(taken).to_rfc3339());
+
+        let stored = CaptureRepository::new(&conn).list_by_session("s-1").unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].created_at, capture.created_at);
+    }
+}
@@ -0,0 +1,368 @@
+//! Team stamps and labelled shape presets for the annotation tools.
+//!
+//! The library holds custom stamp images (a logo, a "fixed" seal) and shape
+//! presets such as a red "P1" badge or an "expected"/"actual" box pair, so
+//! everyone on a team marks screenshots the same way. It lives in a
+//! `stamps` folder in the app data directory, or in the folder named by the
+//! `annotation.stamp_library` setting so a team can share one library from a
+//! network drive. `library.json` lists the entries; stamp images are copied
+//! next to it as PNG, scaled down to [`MAX_STAMP_PIXELS`].
+//!
+//! Stamps placed on a screenshot are saved with its ink (see
+//! [`crate::database::StampPlacement`]) and drawn into the annotated copy by
+//! [`crate::ink_render`], so exports show them as they looked in the editor.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use image::imageops::FilterType;
+use image::RgbaImage;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::annotation_windows::write_atomically;
+use crate::database::{AnnotationData, SettingsOps, SettingsRepository};
+
+/// Settings key: folder of a shared stamp library, instead of the app data one.
+pub const STAMP_LIBRARY_KEY: &str = "annotation.stamp_library";
+
+/// Folder in the app data directory holding the default library.
+const STAMPS_DIR: &str = "stamps";
+
+const LIBRARY_FILE: &str = "library.json";
+
+/// Longest side, in pixels, of a stored stamp image.
+pub const MAX_STAMP_PIXELS: u32 = 512;
+
+const MAX_LABEL_LEN: usize = 40;
+
+/// Outline drawn by a shape preset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShapeKind {
+    Rectangle,
+    Ellipse,
+    Arrow,
+    /// Rounded label such as "P1"
+    Badge,
+}
+
+/// A custom stamp image.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stamp {
+    pub id: String,
+    pub label: String,
+    /// PNG file in the library folder
+    pub file_name: String,
+    pub width: u32,
+    pub height: u32,
+    pub created_at: String,
+    /// Absolute path of the image; filled in when the library is loaded
+    #[serde(default, skip_deserializing)]
+    pub path: String,
+}
+
+/// A labelled shape the annotation tools offer ready-made.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShapePreset {
+    /// Empty when creating a preset
+    #[serde(default)]
+    pub id: String,
+    pub label: String,
+    pub shape: ShapeKind,
+    /// `#RRGGBB`
+    pub color: String,
+    #[serde(default = "default_stroke_width")]
+    pub stroke_width: f32,
+    #[serde(default)]
+    pub filled: bool,
+}
+
+fn default_stroke_width() -> f32 {
+    3.0
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StampLibrary {
+    pub stamps: Vec<Stamp>,
+    pub shapes: Vec<ShapePreset>,
+}
+
+/// Trimmed `label`, checked for length and against `taken` labels (case-insensitive).
+fn checked_label<'a>(label: &str, mut taken: impl Iterator<Item = &'a str>) -> Result<String, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("Label cannot be empty".to_string());
+    }
+    if label.chars().count() > MAX_LABEL_LEN {
+        return Err(format!("Label must be at most {} characters", MAX_LABEL_LEN));
+    }
+    if taken.any(|other| other.eq_ignore_ascii_case(label)) {
+        return Err(format!("A library entry is already labelled '{}'", label));
+    }
+    Ok(label.to_string())
+}
+
+/// `name` when it is a bare file name. Entries of a shared `library.json`
+/// are not trusted to stay inside the library folder.
+fn plain_file_name(name: &str) -> Result<&str, String> {
+    if Path::new(name).file_name().is_some_and(|f| f == name) {
+        Ok(name)
+    } else {
+        Err(format!("Invalid stamp file name: {}", name))
+    }
+}
+
+/// The library folder on disk.
+pub struct StampStore {
+    dir: PathBuf,
+}
+
+impl StampStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The shared library from the settings, or the one in `app_data_dir`.
+    pub fn from_settings(conn: &Connection, app_data_dir: &Path) -> Self {
+        let dir = SettingsRepository::new(conn)
+            .get(STAMP_LIBRARY_KEY)
+            .ok()
+            .flatten()
+            .filter(|dir| !dir.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| app_data_dir.join(STAMPS_DIR));
+        Self::new(dir)
+    }
+
+    /// The library; empty when none was saved yet.
+    pub fn load(&self) -> Result<StampLibrary, String> {
+        let path = self.dir.join(LIBRARY_FILE);
+        let mut library: StampLibrary = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|e| format!("Failed to read stamp library {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => StampLibrary::default(),
+            Err(e) => return Err(format!("Failed to read stamp library {}: {}", path.display(), e)),
+        };
+        for stamp in &mut library.stamps {
+            stamp.path = match plain_file_name(&stamp.file_name) {
+                Ok(name) => self.dir.join(name).to_string_lossy().to_string(),
+                Err(_) => String::new(),
+            };
+        }
+        Ok(library)
+    }
+
+    fn save(&self, library: &StampLibrary) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create stamp library folder {}: {}", self.dir.display(), e))?;
+        let json = serde_json::to_string_pretty(library).map_err(|e| e.to_string())?;
+        write_atomically(&self.dir.join(LIBRARY_FILE), json.as_bytes())
+            .map_err(|e| format!("Failed to save stamp library: {}", e))
+    }
+
+    /// Add the image at `source` as a stamp called `label`.
+    pub fn add_stamp(&self, label: &str, source: &Path) -> Result<Stamp, String> {
+        let mut library = self.load()?;
+        let label = checked_label(label, library.stamps.iter().map(|s| s.label.as_str()))?;
+        let mut image = image::open(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        if image.width().max(image.height()) > MAX_STAMP_PIXELS {
+            image = image.resize(MAX_STAMP_PIXELS, MAX_STAMP_PIXELS, FilterType::Lanczos3);
+        }
+        let image = image.to_rgba8();
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let file_name = format!("{}.png", id);
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create stamp library folder {}: {}", self.dir.display(), e))?;
+        let path = self.dir.join(&file_name);
+        image.save(&path).map_err(|e| format!("Failed to save stamp image: {}", e))?;
+
+        let stamp = Stamp {
+            id,
+            label,
+            file_name,
+            width: image.width(),
+            height: image.height(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            path: path.to_string_lossy().to_string(),
+        };
+        library.stamps.push(stamp.clone());
+        if let Err(e) = self.save(&library) {
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+        Ok(stamp)
+    }
+
+    pub fn rename_stamp(&self, id: &str, label: &str) -> Result<Stamp, String> {
+        let mut library = self.load()?;
+        let label = checked_label(label, library.stamps.iter().filter(|s| s.id != id).map(|s| s.label.as_str()))?;
+        let stamp = library
+            .stamps
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| format!("Stamp not found: {}", id))?;
+        stamp.label = label;
+        let stamp = stamp.clone();
+        self.save(&library)?;
+        Ok(stamp)
+    }
+
+    /// Remove a stamp and its image. Annotated copies already drawn with it keep it.
+    pub fn delete_stamp(&self, id: &str) -> Result<(), String> {
+        let mut library = self.load()?;
+        let index = library
+            .stamps
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or_else(|| format!("Stamp not found: {}", id))?;
+        let stamp = library.stamps.remove(index);
+        self.save(&library)?;
+        if let Ok(name) = plain_file_name(&stamp.file_name) {
+            let _ = std::fs::remove_file(self.dir.join(name));
+        }
+        Ok(())
+    }
+
+    /// Create `preset`, or update the preset with its id.
+    pub fn save_shape(&self, mut preset: ShapePreset) -> Result<ShapePreset, String> {
+        let mut library = self.load()?;
+        preset.label = checked_label(
+            &preset.label,
+            library.shapes.iter().filter(|p| p.id != preset.id).map(|p| p.label.as_str()),
+        )?;
+        preset.color = match (AnnotationData::Color { x: 0, y: 0, hex: preset.color }).normalized()? {
+            AnnotationData::Color { hex, .. } => hex,
+            _ => unreachable!("a colour normalises to a colour"),
+        };
+        if !(preset.stroke_width > 0.0 && preset.stroke_width <= 50.0) {
+            return Err(format!("Invalid stroke width: {}", preset.stroke_width));
+        }
+        if preset.id.is_empty() {
+            preset.id = uuid::Uuid::new_v4().to_string();
+            library.shapes.push(preset.clone());
+        } else {
+            let existing = library
+                .shapes
+                .iter_mut()
+                .find(|p| p.id == preset.id)
+                .ok_or_else(|| format!("Shape preset not found: {}", preset.id))?;
+            *existing = preset.clone();
+        }
+        self.save(&library)?;
+        Ok(preset)
+    }
+
+    pub fn delete_shape(&self, id: &str) -> Result<(), String> {
+        let mut library = self.load()?;
+        let before = library.shapes.len();
+        library.shapes.retain(|p| p.id != id);
+        if library.shapes.len() == before {
+            return Err(format!("Shape preset not found: {}", id));
+        }
+        self.save(&library)
+    }
+
+    /// Images of the stamps with the given ids, for rendering placements.
+    pub fn images(&self, ids: &[&str]) -> Result<HashMap<String, RgbaImage>, String> {
+        let library = self.load()?;
+        let mut images = HashMap::new();
+        for id in ids {
+            if images.contains_key(*id) {
+                continue;
+            }
+            let stamp = library
+                .stamps
+                .iter()
+                .find(|s| s.id == *id)
+                .ok_or_else(|| format!("Stamp not found: {}", id))?;
+            let path = self.dir.join(plain_file_name(&stamp.file_name)?);
+            let image = image::open(path)
+                .map_err(|e| format!("Failed to read stamp '{}': {}", stamp.label, e))?
+                .to_rgba8();
+            images.insert(id.to_string(), image);
+        }
+        Ok(images)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(label: &str) -> ShapePreset {
+        ShapePreset {
+            id: String::new(),
+            label: label.to_string(),
+            shape: ShapeKind::Badge,
+            color: "#e11".to_string(),
+            stroke_width: 3.0,
+            filled: true,
+        }
+    }
+
+    #[test]
+    fn test_stamp_crud_scales_and_removes_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("logo.png");
+        RgbaImage::from_pixel(1024, 256, image::Rgba([0, 0, 0, 128])).save(&source).unwrap();
+        let store = StampStore::new(dir.path().join("stamps"));
+        assert_eq!(store.load().unwrap(), StampLibrary::default());
+
+        let stamp = store.add_stamp(" Approved ", &source).unwrap();
+        assert_eq!((stamp.label.as_str(), stamp.width, stamp.height), ("Approved", 512, 128));
+        assert!(Path::new(&stamp.path).exists());
+        assert!(store.add_stamp("approved", &source).is_err());
+
+        let renamed = store.rename_stamp(&stamp.id, "Fixed").unwrap();
+        assert_eq!(store.load().unwrap().stamps, vec![renamed]);
+        assert_eq!(store.images(&[&stamp.id, &stamp.id]).unwrap()[&stamp.id].width(), 512);
+        assert!(store.images(&["missing"]).is_err());
+
+        store.delete_stamp(&stamp.id).unwrap();
+        assert!(store.load().unwrap().stamps.is_empty());
+        assert!(!Path::new(&stamp.path).exists());
+    }
+
+    #[test]
+    fn test_library_file_names_stay_in_the_library_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().join("keep.png");
+        RgbaImage::from_pixel(4, 4, image::Rgba([0, 0, 0, 255])).save(&outside).unwrap();
+        let library = dir.path().join("stamps");
+        std::fs::create_dir_all(&library).unwrap();
+        std::fs::write(
+            library.join(LIBRARY_FILE),
+            r#"{"stamps": [{"id": "s-1", "label": "Evil", "fileName": "../keep.png", "width": 4, "height": 4, "createdAt": ""}]}"#,
+        )
+        .unwrap();
+        let store = StampStore::new(library);
+
+        assert_eq!(store.load().unwrap().stamps[0].path, "");
+        assert!(store.images(&["s-1"]).unwrap_err().contains("Invalid stamp file name"));
+        store.delete_stamp("s-1").unwrap();
+        assert!(outside.exists());
+    }
+
+    #[test]
+    fn test_shape_presets_are_validated_and_updated() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = StampStore::new(dir.path().to_path_buf());
+
+        let p1 = store.save_shape(preset("P1")).unwrap();
+        assert_eq!(p1.color, "#EE1111");
+        assert!(store.save_shape(preset("p1")).is_err());
+        assert!(store.save_shape(ShapePreset { stroke_width: 0.0, ..preset("Expected") }).is_err());
+
+        let updated = store.save_shape(ShapePreset { shape: ShapeKind::Rectangle, ..p1.clone() }).unwrap();
+        assert_eq!(updated.id, p1.id);
+        assert_eq!(store.load().unwrap().shapes, vec![updated]);
+
+        store.delete_shape(&p1.id).unwrap();
+        assert!(store.delete_shape(&p1.id).is_err());
+    }
+}
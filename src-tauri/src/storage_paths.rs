@@ -0,0 +1,142 @@
+//! Confinement of file paths supplied by the webview.
+//!
+//! Commands such as `open_annotation_window` and `save_annotated_image` take
+//! paths from the frontend. A [`PathScope`] only accepts paths inside the
+//! session storage root (and the media root when recordings are offloaded),
+//! plus individual files the user picked in a native dialog opened from
+//! Rust. Paths are canonicalized before the check, so `..` components and
+//! symlinks cannot escape the scope.
+
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+/// Roots and individually picked files a command may touch.
+#[derive(Debug, Clone, Default)]
+pub struct PathScope {
+    roots: Vec<PathBuf>,
+    picked: HashSet<PathBuf>,
+}
+
+impl PathScope {
+    pub fn new(roots: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            roots: roots.into_iter().map(|root| canonical_or_raw(&root)).collect(),
+            picked: HashSet::new(),
+        }
+    }
+
+    /// Also allow exactly these files, wherever they are.
+    pub fn with_picked(mut self, picked: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.picked.extend(picked.into_iter().map(|path| canonical_or_raw(&path)));
+        self
+    }
+
+    /// Resolve an existing file and check it is in scope.
+    pub fn check_existing(&self, path: &Path) -> Result<PathBuf, String> {
+        reject_traversal(path)?;
+        let resolved = path
+            .canonicalize()
+            .map_err(|_| format!("File not found: {}", path.display()))?;
+        self.check_resolved(path, resolved)
+    }
+
+    /// Resolve a file that is about to be written (it may not exist yet, but
+    /// its folder must) and check it is in scope.
+    pub fn check_writable(&self, path: &Path) -> Result<PathBuf, String> {
+        reject_traversal(path)?;
+        let resolved = match path.canonicalize() {
+            Ok(resolved) => resolved,
+            Err(_) => {
+                let file_name = path.file_name().ok_or_else(|| format!("Invalid file path: {}", path.display()))?;
+                let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+                parent
+                    .canonicalize()
+                    .map_err(|_| format!("Folder not found: {}", parent.display()))?
+                    .join(file_name)
+            }
+        };
+        self.check_resolved(path, resolved)
+    }
+
+    fn check_resolved(&self, original: &Path, resolved: PathBuf) -> Result<PathBuf, String> {
+        if self.picked.contains(&resolved) || self.roots.iter().any(|root| resolved.starts_with(root)) {
+            Ok(resolved)
+        } else {
+            Err(format!("Path is outside session storage: {}", original.display()))
+        }
+    }
+}
+
+/// Path an annotated copy of `original` is saved to in "alongside" mode,
+/// e.g. `shot.png` -> `shot_annotated.png`.
+pub fn annotated_path_for(original: &Path) -> PathBuf {
+    let stem = original.file_stem().and_then(|s| s.to_str()).unwrap_or("screenshot");
+    let ext = original.extension().and_then(|e| e.to_str()).unwrap_or("png");
+    let parent = original.parent().unwrap_or(Path::new("."));
+    parent.join(format!("{}_annotated.{}", stem, ext))
+}
+
+/// `..` is never needed by the frontend, so refuse it before touching disk.
+fn reject_traversal(path: &Path) -> Result<(), String> {
+    if path.components().any(|c| c == Component::ParentDir) {
+        return Err(format!("Path must not contain '..': {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Roots that do not exist yet (no session started) cannot be
+/// canonicalized; they are compared as given.
+fn canonical_or_raw(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_accepts_only_paths_under_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("sessions");
+        let outside = dir.path().join("elsewhere");
+        std::fs::create_dir_all(root.join("s-1")).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(root.join("s-1/shot.png"), b"png").unwrap();
+        std::fs::write(outside.join("other.png"), b"png").unwrap();
+
+        let scope = PathScope::new([root.clone()]);
+        assert!(scope.check_existing(&root.join("s-1/shot.png")).is_ok());
+        assert!(scope.check_existing(&outside.join("other.png")).is_err());
+        assert!(scope.check_existing(&root.join("s-1/../../elsewhere/other.png")).is_err());
+        assert!(scope.check_existing(&root.join("s-1/missing.png")).is_err());
+
+        // Writable: new files inside the root, but not in missing folders or outside
+        assert!(scope.check_writable(&root.join("s-1/shot_annotated.png")).is_ok());
+        assert!(scope.check_writable(&root.join("nope/shot.png")).is_err());
+        assert!(scope.check_writable(&outside.join("shot.png")).is_err());
+
+        // A file picked in a dialog is allowed on its own, not its siblings
+        let picked = PathScope::new([root]).with_picked([outside.join("other.png")]);
+        assert!(picked.check_existing(&outside.join("other.png")).is_ok());
+        assert!(picked.check_writable(&outside.join("sibling.png")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_out_of_root_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("sessions");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(dir.path().join("secret.png"), b"png").unwrap();
+        std::os::unix::fs::symlink(dir.path().join("secret.png"), root.join("link.png")).unwrap();
+
+        let scope = PathScope::new([root.clone()]);
+        assert!(scope.check_existing(&root.join("link.png")).is_err());
+    }
+
+    #[test]
+    fn test_annotated_path_for() {
+        assert_eq!(annotated_path_for(Path::new("/qa/shot.png")), PathBuf::from("/qa/shot_annotated.png"));
+        assert_eq!(annotated_path_for(Path::new("/qa/shot")), PathBuf::from("/qa/shot_annotated.png"));
+    }
+}
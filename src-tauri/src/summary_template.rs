@@ -0,0 +1,282 @@
+//! Template rendering for session-summary.md.
+//!
+//! Works like the bug ticket template: a Markdown file with `{placeholder}`
+//! substitutions, which teams can replace with their own (the path is stored
+//! in the `template.session_summary_path` setting). Optional values use the
+//! bug template's conditionals, `{name:text with {value}}`, whose text may
+//! span lines. The bug list is a section:
+//!
+//! - `{#bugs}...{/bugs}` repeats its content for every bug
+//! - `{^bugs}...{/bugs}` renders only when the session has no bugs
+//!
+//! A line holding nothing but a section tag is dropped from the output.
+//!
+//! Session placeholders: `{session.id}`, `{session.started}`, `{session.ended}`
+//! (`In Progress` while the session runs),
+//! `{session.duration}`, `{session.bugCount}`, `{session.status}`,
+//! `{session.notes}`, `{session.environmentChanges}` (build, OS or driver
+//! changes since the previous session of the profile), `{overview}` (AI
+//! overview, when generated).
+//!
+//! Bug placeholders (inside `{#bugs}`): `{bug.displayId}`, `{bug.title}`,
+//! `{bug.type}`, `{bug.status}`, `{bug.ticket}`, `{bug.createdAt}`,
+//! `{bug.softwareVersion}`, `{bug.notes}`, `{bug.description}`, `{bug.aiDescription}`,
+//! `{bug.related}` (linked bugs, e.g. `BUG-004 (duplicate)`), `{bug.timeSpent}`
+//! (time spent capturing, see [`crate::bug_timing`]).
+//!
+//! Dates and numbers arrive pre-formatted for the `export.locale` setting and
+//! the session's time zone (see [`crate::time_format`]).
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::database::{SettingsOps, SettingsRepository};
+use crate::template::replace_conditional;
+
+pub const DEFAULT_SUMMARY_TEMPLATE: &str = include_str!("../templates/default_session_summary.md");
+
+/// Settings key: path to a custom session-summary template.
+pub const SUMMARY_TEMPLATE_PATH_KEY: &str = "template.session_summary_path";
+
+/// Per-bug values for the `{#bugs}` section.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SummaryBugData {
+    pub display_id: String,
+    pub title: String,
+    pub bug_type: String,
+    pub status: String,
+    pub ticket: String,
+    pub created_at: String,
+    pub software_version: Option<String>,
+    pub notes: Option<String>,
+    pub description: Option<String>,
+    pub ai_description: Option<String>,
+    pub related: Option<String>,
+    pub time_spent: Option<String>,
+}
+
+/// Values available to a session-summary template.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SummaryData {
+    pub session_id: String,
+    pub started: String,
+    pub ended: Option<String>,
+    pub duration: Option<String>,
+    pub bug_count: String,
+    pub status: String,
+    pub notes: Option<String>,
+    pub environment_changes: Option<String>,
+    pub overview: Option<String>,
+    pub bugs: Vec<SummaryBugData>,
+}
+
+/// A value only counts as present when it has non-whitespace content.
+fn present(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|v| !v.trim().is_empty())
+}
+
+/// Drop lines that hold nothing but a section tag, keeping the tag itself.
+fn strip_standalone_tags(template: &str) -> String {
+    let mut out = String::with_capacity(template.len());
+    for line in template.split_inclusive('\n') {
+        let trimmed = line.trim();
+        let is_tag = trimmed.len() > 3
+            && trimmed.starts_with('{')
+            && trimmed.ends_with('}')
+            && matches!(trimmed.as_bytes()[1], b'#' | b'^' | b'/')
+            && !trimmed[1..].contains('{');
+        if is_tag {
+            out.push_str(trimmed);
+        } else {
+            out.push_str(line);
+        }
+    }
+    out
+}
+
+/// Expand every `{#name}`/`{^name}` ... `{/name}` section. `render` receives
+/// the section body and returns its expansion for a `#` section; `^` sections
+/// are kept verbatim when `empty` is true.
+fn expand_sections<F>(template: &str, name: &str, empty: bool, mut render: F) -> String
+where
+    F: FnMut(&str) -> String,
+{
+    let open = format!("{{#{}}}", name);
+    let inverted = format!("{{^{}}}", name);
+    let close = format!("{{/{}}}", name);
+
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    loop {
+        let next = [(rest.find(&open), false), (rest.find(&inverted), true)]
+            .into_iter()
+            .filter_map(|(pos, inv)| pos.map(|p| (p, inv)))
+            .min_by_key(|(p, _)| *p);
+        let Some((start, is_inverted)) = next else {
+            out.push_str(rest);
+            break;
+        };
+        let body_start = start + open.len();
+        let Some(body_len) = rest[body_start..].find(&close) else {
+            // Unterminated section: leave the remainder untouched
+            out.push_str(rest);
+            break;
+        };
+
+        out.push_str(&rest[..start]);
+        let body = &rest[body_start..body_start + body_len];
+        if is_inverted {
+            if empty {
+                out.push_str(body);
+            }
+        } else if !empty {
+            out.push_str(&render(body));
+        }
+        rest = &rest[body_start + body_len + close.len()..];
+    }
+    out
+}
+
+/// Render conditionals and placeholders for a set of named values.
+fn render_values(template: &str, values: &[(&str, Option<&str>)]) -> String {
+    let mut output = template.to_string();
+    for (name, value) in values {
+        output = replace_conditional(&output, name, *value);
+    }
+    for (name, value) in values {
+        output = output.replace(&format!("{{{}}}", name), value.unwrap_or(""));
+    }
+    output
+}
+
+fn render_bug(body: &str, bug: &SummaryBugData) -> String {
+    render_values(
+        body,
+        &[
+            ("bug.displayId", Some(bug.display_id.as_str())),
+            ("bug.title", Some(bug.title.as_str())),
+            ("bug.type", Some(bug.bug_type.as_str())),
+            ("bug.status", Some(bug.status.as_str())),
+            ("bug.ticket", Some(bug.ticket.as_str())),
+            ("bug.createdAt", Some(bug.created_at.as_str())),
+            ("bug.softwareVersion", present(&bug.software_version)),
+            ("bug.notes", present(&bug.notes)),
+            ("bug.description", present(&bug.description)),
+            ("bug.aiDescription", present(&bug.ai_description)),
+            ("bug.related", present(&bug.related)),
+            ("bug.timeSpent", present(&bug.time_spent)),
+        ],
+    )
+}
+
+/// Render a session-summary template.
+pub fn render_summary(template: &str, data: &SummaryData) -> String {
+    let template = strip_standalone_tags(template);
+
+    // Session-level values first so bug content is never re-substituted
+    let output = render_values(
+        &template,
+        &[
+            ("session.id", Some(data.session_id.as_str())),
+            ("session.started", Some(data.started.as_str())),
+            ("session.ended", present(&data.ended).or(Some("In Progress"))),
+            ("session.duration", present(&data.duration)),
+            ("session.bugCount", Some(data.bug_count.as_str())),
+            ("session.status", Some(data.status.as_str())),
+            ("session.notes", present(&data.notes)),
+            ("session.environmentChanges", present(&data.environment_changes)),
+            ("overview", present(&data.overview)),
+        ],
+    );
+
+    expand_sections(&output, "bugs", data.bugs.is_empty(), |body| {
+        data.bugs.iter().map(|bug| render_bug(body, bug)).collect()
+    })
+}
+
+/// The active session-summary template: the custom file named in settings
+/// when it is readable, otherwise the built-in default.
+pub fn load_summary_template(conn: &Connection) -> String {
+    SettingsRepository::new(conn)
+        .get(SUMMARY_TEMPLATE_PATH_KEY)
+        .ok()
+        .flatten()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_else(|| DEFAULT_SUMMARY_TEMPLATE.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> SummaryData {
+        SummaryData {
+            session_id: "s-1".to_string(),
+            started: "2024-01-15 10:00:00 UTC".to_string(),
+            ended: None,
+            duration: None,
+            bug_count: "2".to_string(),
+            status: "active".to_string(),
+            notes: Some("  ".to_string()),
+            environment_changes: None,
+            overview: None,
+            bugs: vec![
+                SummaryBugData {
+                    display_id: "BUG-001".to_string(),
+                    title: "Login fails".to_string(),
+                    bug_type: "bug".to_string(),
+                    status: "captured".to_string(),
+                    ticket: "Not yet filed".to_string(),
+                    notes: Some("Clicked twice".to_string()),
+                    related: Some("BUG-002 (blocks)".to_string()),
+                    ..Default::default()
+                },
+                SummaryBugData {
+                    display_id: "BUG-002".to_string(),
+                    title: "(No title)".to_string(),
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_default_template_renders_sessions_and_bugs() {
+        let output = render_summary(DEFAULT_SUMMARY_TEMPLATE, &sample());
+        assert!(output.starts_with("# QA Session Summary\n\n## Session Information\n\n- **Session ID:** s-1\n"));
+        assert!(output.contains("- **Ended:** In Progress\n- **Bug Count:** 2\n"));
+        assert!(!output.contains("Session Notes"));
+        assert!(!output.contains("Environment Changes"));
+        assert!(!output.contains("## Overview"));
+        assert!(output.contains("### BUG-001 - Login fails\n\n- **Type:** bug\n"));
+        assert!(output.contains("- **Related:** BUG-002 (blocks)\n"));
+        assert_eq!(output.matches("**Related:**").count(), 1);
+        assert!(output.contains("**Notes:**\nClicked twice\n"));
+        assert!(output.contains("### BUG-002 - (No title)"));
+        assert!(!output.contains("No bugs captured"));
+        assert!(!output.contains('{'));
+    }
+
+    #[test]
+    fn test_inverted_bugs_section_when_empty() {
+        let data = SummaryData {
+            bugs: vec![],
+            ..sample()
+        };
+        let output = render_summary(DEFAULT_SUMMARY_TEMPLATE, &data);
+        assert!(output.ends_with("## Bugs Captured\n\nNo bugs captured in this session.\n"));
+    }
+
+    #[test]
+    fn test_custom_template() {
+        let template = "Session {session.id}{overview: — {value}}\n{#bugs}\n* {bug.displayId}: {bug.title} [{bug.ticket}]\n{/bugs}\n";
+        let data = SummaryData {
+            overview: Some("Mostly login".to_string()),
+            ..sample()
+        };
+        assert_eq!(
+            render_summary(template, &data),
+            "Session s-1 — Mostly login\n* BUG-001: Login fails [Not yet filed]\n* BUG-002: (No title) []\n"
+        );
+    }
+}
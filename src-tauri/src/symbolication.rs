@@ -0,0 +1,443 @@
+//! Symbolication of minified stack frames in console output.
+//!
+//! Electron and web builds ship minified bundles, so parsed stack traces read
+//! `at e (https://app.example.com/main.3f9a.js:1:48213)`. When enabled, frames
+//! whose file has a source map (`<file>.map`) in the configured folder, or
+//! under the configured base URL, are rewritten to the original source
+//! position, e.g. `at e (src/cart/Checkout.tsx:42:7)`, before the console
+//! output is rendered into a ticket. Frames without a map are left alone.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Settings key holding [`SymbolicationSettings`] as JSON.
+pub const SYMBOLICATION_KEY: &str = "console.symbolication";
+
+/// How long to wait for a source map served over HTTP.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SymbolicationSettings {
+    pub enabled: bool,
+    /// Folder holding the `.map` files, or the base URL they are served from
+    pub sourcemap_location: String,
+}
+
+impl SymbolicationSettings {
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(SYMBOLICATION_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(SYMBOLICATION_KEY, &json)
+            .map_err(|e| format!("Failed to save symbolication settings: {}", e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let location = self.sourcemap_location.trim();
+        if location.is_empty() {
+            return Err("A source map folder or URL is required".to_string());
+        }
+        if !is_url(location) && !Path::new(location).is_dir() {
+            return Err(format!("Source map folder not found: {}", location));
+        }
+        Ok(())
+    }
+}
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("https://") || location.starts_with("http://")
+}
+
+/// Where `.map` files are read from.
+#[derive(Debug, Clone)]
+enum MapLocation {
+    Folder(PathBuf),
+    BaseUrl(String),
+}
+
+/// Rewrites stack frames using source maps. Maps are loaded once per
+/// instance, so build one per rendering pass.
+pub struct Symbolicator {
+    location: Option<MapLocation>,
+    /// Parsed maps by file name; `None` when there is no usable map
+    maps: RefCell<HashMap<String, Option<SourceMap>>>,
+}
+
+impl Symbolicator {
+    pub fn new(settings: &SymbolicationSettings) -> Self {
+        let location = settings.sourcemap_location.trim();
+        let location = (settings.enabled && !location.is_empty()).then(|| {
+            if is_url(location) {
+                MapLocation::BaseUrl(location.trim_end_matches('/').to_string())
+            } else {
+                MapLocation::Folder(PathBuf::from(location))
+            }
+        });
+        Self { location, maps: RefCell::new(HashMap::new()) }
+    }
+
+    /// A symbolicator for the stored settings (a no-op when disabled).
+    pub fn load(conn: &Connection) -> Self {
+        Self::new(&SymbolicationSettings::load(conn))
+    }
+
+    /// `text` with every `file:line:column` location that has a source map
+    /// replaced by the original position.
+    pub fn rewrite(&self, text: &str) -> String {
+        if self.location.is_none() {
+            return text.to_string();
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut copied = 0;
+        for frame in find_locations(text) {
+            let Some(original) = self.original_location(&frame) else {
+                continue;
+            };
+            out.push_str(&text[copied..frame.start]);
+            out.push_str(&original);
+            copied = frame.end;
+        }
+        out.push_str(&text[copied..]);
+        out
+    }
+
+    fn original_location(&self, frame: &FrameLocation) -> Option<String> {
+        let map_name = format!("{}.map", file_name(frame.file)?);
+        let mut maps = self.maps.borrow_mut();
+        let map = maps
+            .entry(map_name.clone())
+            .or_insert_with(|| self.fetch_map(&map_name))
+            .as_ref()?;
+
+        // Stack frames count lines and columns from 1, source maps from 0
+        let position = map.lookup(frame.line.checked_sub(1)?, frame.column.saturating_sub(1))?;
+        Some(format!("{}:{}:{}", position.source, position.line + 1, position.column + 1))
+    }
+
+    fn fetch_map(&self, map_name: &str) -> Option<SourceMap> {
+        let json = match self.location.as_ref()? {
+            MapLocation::Folder(folder) => std::fs::read_to_string(folder.join(map_name)).ok()?,
+            MapLocation::BaseUrl(base) => {
+                let client = reqwest::blocking::Client::builder().timeout(FETCH_TIMEOUT).build().ok()?;
+                let response = client.get(format!("{}/{}", base, map_name)).send().ok()?;
+                if !response.status().is_success() {
+                    return None;
+                }
+                response.text().ok()?
+            }
+        };
+        match SourceMap::parse(&json) {
+            Ok(map) => Some(map),
+            Err(e) => {
+                eprintln!("Warning: ignoring source map {}: {}", map_name, e);
+                None
+            }
+        }
+    }
+}
+
+/// A `file:line:column` reference found in console text.
+#[derive(Debug, PartialEq)]
+struct FrameLocation<'a> {
+    /// Byte range of the whole reference
+    start: usize,
+    end: usize,
+    file: &'a str,
+    line: u32,
+    column: u32,
+}
+
+/// Digits starting at `i`, and the index after them.
+fn number_at(bytes: &[u8], i: usize) -> Option<(u32, usize)> {
+    let end = i + bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+    let number = std::str::from_utf8(&bytes[i..end]).ok()?.parse().ok()?;
+    Some((number, end))
+}
+
+/// Start of the file reference ending at `end`: after the nearest space,
+/// bracket, quote, `@`, or escaped newline (console parses are often JSON).
+fn file_start(bytes: &[u8], end: usize) -> usize {
+    let mut i = end;
+    while i > 0 {
+        let b = bytes[i - 1];
+        if b.is_ascii_whitespace() || matches!(b, b'(' | b'[' | b'"' | b'\'' | b'@' | b',') {
+            break;
+        }
+        if b == b'n' && i >= 2 && bytes[i - 2] == b'\\' {
+            break;
+        }
+        i -= 1;
+    }
+    i
+}
+
+fn find_locations(text: &str) -> Vec<FrameLocation<'_>> {
+    let bytes = text.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b':' {
+            let line_col = number_at(bytes, i + 1).and_then(|(line, after_line)| {
+                (bytes.get(after_line) == Some(&b':'))
+                    .then(|| number_at(bytes, after_line + 1))
+                    .flatten()
+                    .map(|(column, end)| (line, column, end))
+            });
+            if let Some((line, column, end)) = line_col {
+                let start = file_start(bytes, i);
+                let file = &text[start..i];
+                if file.contains('.') {
+                    found.push(FrameLocation { start, end, file, line, column });
+                    i = end;
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+    found
+}
+
+/// Last path segment of a file path or URL, without query or fragment.
+fn file_name(file: &str) -> Option<&str> {
+    let path = file.split(['?', '#']).next()?;
+    let name = path.rsplit(['/', '\\']).next()?;
+    (!name.is_empty()).then_some(name)
+}
+
+/// Position in an original source file (0-based line and column).
+#[derive(Debug, PartialEq)]
+pub struct OriginalPosition<'a> {
+    pub source: &'a str,
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    generated_column: u32,
+    /// (source index, line, column)
+    original: Option<(u32, u32, u32)>,
+}
+
+/// A decoded source map (revision 3).
+#[derive(Debug)]
+pub struct SourceMap {
+    sources: Vec<String>,
+    /// Segments of each generated line, sorted by column
+    lines: Vec<Vec<Segment>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSourceMap {
+    version: u32,
+    #[serde(default)]
+    source_root: Option<String>,
+    #[serde(default)]
+    sources: Vec<Option<String>>,
+    mappings: String,
+}
+
+impl SourceMap {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let raw: RawSourceMap = serde_json::from_str(json).map_err(|e| format!("Invalid source map: {}", e))?;
+        if raw.version != 3 {
+            return Err(format!("Unsupported source map version {}", raw.version));
+        }
+
+        let root = raw.source_root.unwrap_or_default();
+        let sources = raw
+            .sources
+            .iter()
+            .map(|source| display_source(&root, source.as_deref().unwrap_or("")))
+            .collect();
+
+        // Every field but the generated column is relative to the previous segment
+        let (mut source, mut line, mut column) = (0i64, 0i64, 0i64);
+        let mut lines = Vec::new();
+        for mapping_line in raw.mappings.split(';') {
+            let mut generated_column = 0i64;
+            let mut segments = Vec::new();
+            for encoded in mapping_line.split(',').filter(|s| !s.is_empty()) {
+                let fields = decode_vlq(encoded)?;
+                if !matches!(fields.len(), 1 | 4 | 5) {
+                    return Err(format!("Invalid mapping segment '{}'", encoded));
+                }
+                generated_column += fields[0];
+                let original = if fields.len() >= 4 {
+                    source += fields[1];
+                    line += fields[2];
+                    column += fields[3];
+                    Some((to_u32(source)?, to_u32(line)?, to_u32(column)?))
+                } else {
+                    None
+                };
+                segments.push(Segment { generated_column: to_u32(generated_column)?, original });
+            }
+            segments.sort_by_key(|s| s.generated_column);
+            lines.push(segments);
+        }
+
+        Ok(Self { sources, lines })
+    }
+
+    /// Original position of the generated `line` and `column` (0-based).
+    pub fn lookup(&self, line: u32, column: u32) -> Option<OriginalPosition<'_>> {
+        let segments = self.lines.get(line as usize)?;
+        let index = segments.partition_point(|s| s.generated_column <= column);
+        let (source, line, column) = segments.get(index.checked_sub(1)?)?.original?;
+        Some(OriginalPosition {
+            source: self.sources.get(source as usize)?,
+            line,
+            column,
+        })
+    }
+}
+
+fn to_u32(value: i64) -> Result<u32, String> {
+    u32::try_from(value).map_err(|_| format!("Mapping value out of range: {}", value))
+}
+
+/// Source path as shown in tickets: `webpack://app/./src/a.ts` → `app/src/a.ts`.
+fn display_source(root: &str, source: &str) -> String {
+    let joined = if root.is_empty() || source.contains("://") {
+        source.to_string()
+    } else {
+        format!("{}/{}", root.trim_end_matches('/'), source)
+    };
+    let path = joined.strip_prefix("webpack://").unwrap_or(&joined);
+    path.trim_start_matches('/').trim_start_matches("./").replace("/./", "/")
+}
+
+/// Decode one Base64 VLQ mapping segment.
+fn decode_vlq(segment: &str) -> Result<Vec<i64>, String> {
+    let mut values = Vec::new();
+    let mut value = 0i64;
+    let mut shift = 0;
+    for c in segment.bytes() {
+        let digit = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return Err(format!("Invalid mapping character '{}'", c as char)),
+        } as i64;
+        if shift > 55 {
+            return Err("Mapping value too large".to_string());
+        }
+        value += (digit & 0b11111) << shift;
+        if digit & 0b100000 != 0 {
+            shift += 5;
+        } else {
+            let magnitude = value >> 1;
+            values.push(if value & 1 == 1 { -magnitude } else { magnitude });
+            value = 0;
+            shift = 0;
+        }
+    }
+    if shift != 0 {
+        return Err(format!("Truncated mapping segment '{}'", segment));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Line 1: col 0 → src/app.ts 1:1; col 10 → 5:3. Line 2: col 0 → 6:1.
+    const MAP: &str = r#"{
+        "version": 3,
+        "sourceRoot": "",
+        "sources": ["webpack://shop/./src/app.ts"],
+        "names": ["render"],
+        "mappings": "AAAA,UAIEA;AACF"
+    }"#;
+
+    #[test]
+    fn test_decode_vlq() {
+        assert_eq!(decode_vlq("AAAA").unwrap(), vec![0, 0, 0, 0]);
+        assert_eq!(decode_vlq("UAIEA").unwrap(), vec![10, 0, 4, 2, 0]);
+        assert_eq!(decode_vlq("F").unwrap(), vec![-2]);
+        assert_eq!(decode_vlq("2HwB").unwrap(), vec![123, 24]);
+        assert!(decode_vlq("g").is_err());
+        assert!(decode_vlq("A!").is_err());
+    }
+
+    #[test]
+    fn test_source_map_lookup() {
+        let map = SourceMap::parse(MAP).unwrap();
+        let at = |line, column| map.lookup(line, column).map(|p| (p.source.to_string(), p.line, p.column));
+
+        assert_eq!(at(0, 5), Some(("shop/src/app.ts".to_string(), 0, 0)));
+        assert_eq!(at(0, 12), Some(("shop/src/app.ts".to_string(), 4, 2)));
+        assert_eq!(at(1, 0), Some(("shop/src/app.ts".to_string(), 5, 0)));
+        assert_eq!(at(7, 0), None);
+        assert!(SourceMap::parse(r#"{"version": 2, "mappings": ""}"#).is_err());
+    }
+
+    #[test]
+    fn test_find_locations() {
+        let text = r#"{"stackTraces":["at e (https://cdn.example.com:8443/app.min.js?v=3:1:13)\nat app.min.js:2:1"]}"#;
+        let frames: Vec<_> = find_locations(text).into_iter().map(|f| (f.file, f.line, f.column)).collect();
+        assert_eq!(
+            frames,
+            vec![("https://cdn.example.com:8443/app.min.js?v=3", 1, 13), ("app.min.js", 2, 1)]
+        );
+        assert_eq!(file_name("https://cdn.example.com/assets/app.min.js?v=3"), Some("app.min.js"));
+        assert!(find_locations("at 12:30:45 the request failed").is_empty());
+    }
+
+    #[test]
+    fn test_rewrite_with_map_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.min.js.map"), MAP).unwrap();
+        let settings = SymbolicationSettings {
+            enabled: true,
+            sourcemap_location: dir.path().to_string_lossy().to_string(),
+        };
+        assert!(settings.validate().is_ok());
+
+        let trace = "TypeError: x is undefined\n    at e (https://cdn.example.com/app.min.js:1:13)\n    at vendor.js:4:2";
+        let rewritten = Symbolicator::new(&settings).rewrite(trace);
+        assert_eq!(
+            rewritten,
+            "TypeError: x is undefined\n    at e (shop/src/app.ts:5:3)\n    at vendor.js:4:2"
+        );
+
+        // Disabled settings leave the text alone
+        let disabled = SymbolicationSettings { enabled: false, ..settings };
+        assert_eq!(Symbolicator::new(&disabled).rewrite(trace), trace);
+    }
+
+    #[test]
+    fn test_settings_validation() {
+        assert!(SymbolicationSettings::default().validate().is_ok());
+        let missing = SymbolicationSettings { enabled: true, sourcemap_location: "  ".to_string() };
+        assert!(missing.validate().is_err());
+        let url = SymbolicationSettings { enabled: true, sourcemap_location: "https://cdn.example.com/maps".to_string() };
+        assert!(url.validate().is_ok());
+    }
+}
@@ -4,8 +4,87 @@ use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
+use crate::render_target::RenderTarget;
+
 pub const DEFAULT_TEMPLATE: &str = include_str!("../templates/default_template.md");
 
+/// Placeholders [`TemplateManager::render_template`] replaces, without braces.
+pub const TEMPLATE_VARIABLES: &[&str] = &[
+    "bug.title",
+    "bug.type",
+    "bug.description.steps",
+    "bug.description.expected",
+    "bug.description.actual",
+    "bug.folderPath",
+    "bug.createdAt",
+    "bug.metadata.environment.os",
+    "bug.metadata.environment.displayResolution",
+    "bug.metadata.environment.dpiScaling",
+    "bug.metadata.environment.foregroundApp",
+    "bug.metadata.environment.displayLanguage",
+    "bug.metadata.environment.keyboardLayout",
+    "bug.metadata.softwareVersion",
+    "bug.captures.count",
+    "bug.captures.list",
+    "bug.designDetails",
+    "bug.consoleOutput",
+];
+
+/// Fields usable as `{field:text with {value}}`, a line kept only when the
+/// field has a value.
+pub const CONDITIONAL_VARIABLES: &[&str] = &["bug.metadata.meetingId"];
+
+/// Prefix of variables filled from settings, e.g. `{var.testerName}`.
+pub const VARIABLE_PREFIX: &str = "var.";
+
+/// Partials available to every template as `{{> name}}`. A file
+/// `<name>.md` in the partials folder replaces the built-in one.
+const BUILTIN_PARTIALS: &[(&str, &str)] = &[
+    (
+        "description",
+        "### Steps to Reproduce\n\n{bug.description.steps}\n\n\
+         ### Expected Behavior\n\n{bug.description.expected}\n\n\
+         ### Actual Behavior\n\n{bug.description.actual}",
+    ),
+    (
+        "environment",
+        "- **OS:** {bug.metadata.environment.os}\n\
+         - **Display:** {bug.metadata.environment.displayResolution} @ {bug.metadata.environment.dpiScaling}\n\
+         - **Application:** {bug.metadata.environment.foregroundApp}\n\
+         - **Language:** {bug.metadata.environment.displayLanguage} (keyboard: {bug.metadata.environment.keyboardLayout})\n\
+         - **Version:** {bug.metadata.softwareVersion}\n\
+         {bug.metadata.meetingId:- **Meeting ID:** {value}}",
+    ),
+    (
+        "evidence",
+        "**Folder:** {bug.folderPath}\n\n\
+         **Screenshots:** {bug.captures.count} file(s)\n\
+         {bug.captures.list}",
+    ),
+];
+
+/// Partials may include partials, up to this depth; deeper (or circular)
+/// includes are left in the output as written.
+const MAX_PARTIAL_DEPTH: usize = 8;
+
+/// A `{var.<name>}` placeholder filled from a setting.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateVariable {
+    /// Name without the `var.` prefix
+    pub name: String,
+    pub description: String,
+    pub setting_key: String,
+}
+
+/// Values of `{var.<name>}` placeholders, by name.
+pub type VariableValues = HashMap<String, String>;
+
+/// Whether `name` can name a partial (and its file).
+pub fn is_partial_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BugMetadata {
     pub meeting_id: Option<String>,
@@ -23,6 +102,16 @@ pub struct Environment {
     pub ram: String,
     pub cpu: String,
     pub foreground_app: String,
+    /// OS display language when the bug was started (see `locale_info`)
+    #[serde(default = "unknown")]
+    pub display_language: String,
+    /// Active keyboard layout when the bug was started
+    #[serde(default = "unknown")]
+    pub keyboard_layout: String,
+}
+
+fn unknown() -> String {
+    "Unknown".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +125,12 @@ pub struct BugData {
     pub folder_path: String,
     pub captures: Vec<String>,
     pub console_output: Option<String>,
+    /// When the bug was logged, already formatted for the export locale
+    #[serde(default)]
+    pub created_at: String,
+    /// Design-QA measurements (picked colours, distances), one line each
+    #[serde(default)]
+    pub design_details: Vec<String>,
 }
 
 /// Template manager handles loading, caching, and hot-reloading of ticket templates
@@ -43,15 +138,83 @@ pub struct TemplateManager {
     pub custom_template_path: Option<PathBuf>,
     cached_template: Arc<Mutex<String>>,
     watcher: Option<notify::RecommendedWatcher>,
+    partials: HashMap<String, String>,
+    /// Folder of user partials, read at render time so edits apply at once
+    partials_dir: Option<PathBuf>,
+    variables: Vec<TemplateVariable>,
 }
 
 impl TemplateManager {
     pub fn new() -> Self {
-        Self {
+        let mut manager = Self {
             custom_template_path: None,
             cached_template: Arc::new(Mutex::new(DEFAULT_TEMPLATE.to_string())),
             watcher: None,
+            partials: HashMap::new(),
+            partials_dir: None,
+            variables: Vec::new(),
+        };
+        for (name, content) in BUILTIN_PARTIALS {
+            manager.register_partial(name, content);
         }
+        manager.register_variable(TemplateVariable {
+            name: "testerName".to_string(),
+            description: "Tester name from Settings".to_string(),
+            setting_key: crate::database::TESTER_NAME_KEY.to_string(),
+        });
+        manager
+    }
+
+    pub fn register_partial(&mut self, name: &str, content: &str) {
+        self.partials.insert(name.to_string(), content.to_string());
+    }
+
+    /// Register a settings-backed variable, replacing one of the same name.
+    pub fn register_variable(&mut self, variable: TemplateVariable) {
+        self.variables.retain(|v| v.name != variable.name);
+        self.variables.push(variable);
+    }
+
+    pub fn variables(&self) -> &[TemplateVariable] {
+        &self.variables
+    }
+
+    pub fn set_partials_dir(&mut self, dir: Option<PathBuf>) {
+        self.partials_dir = dir;
+    }
+
+    /// Content of partial `name`: the user's file, else the built-in one.
+    pub fn partial(&self, name: &str) -> Option<String> {
+        if !is_partial_name(name) {
+            return None;
+        }
+        self.partials_dir
+            .as_ref()
+            .and_then(|dir| std::fs::read_to_string(dir.join(format!("{}.md", name))).ok())
+            .or_else(|| self.partials.get(name).cloned())
+    }
+
+    /// Names of every partial, registered or in the partials folder.
+    pub fn partial_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.partials.keys().cloned().collect();
+        if let Some(entries) = self.partials_dir.as_ref().and_then(|dir| std::fs::read_dir(dir).ok()) {
+            names.extend(
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("md"))
+                    .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+                    .filter(|name| is_partial_name(name)),
+            );
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// The current template (custom or default).
+    pub fn template_source(&self) -> String {
+        self.cached_template.lock().unwrap().clone()
     }
 
     /// Set the path to a custom template file
@@ -118,10 +281,55 @@ impl TemplateManager {
         self.watcher = None;
     }
 
-    /// Render a bug using the current template
-    pub fn render(&self, bug: &BugData) -> Result<String, String> {
-        let template = self.cached_template.lock().unwrap().clone();
-        let mut output = template;
+    /// Render a bug using the current template, converted to `target`
+    pub fn render(&self, bug: &BugData, variables: &VariableValues, target: RenderTarget) -> Result<String, String> {
+        self.render_template(&self.template_source(), bug, variables, target)
+    }
+
+    /// Render a bug using `template` (e.g. a named template from the
+    /// library), including partials and filling `{var.<name>}` from
+    /// `variables`. Unknown partials and variables are left as written.
+    /// Templates are Markdown; other targets are converted from it.
+    pub fn render_template(
+        &self,
+        template: &str,
+        bug: &BugData,
+        variables: &VariableValues,
+        target: RenderTarget,
+    ) -> Result<String, String> {
+        let mut output = self.expand_partials(template, 0);
+        for (name, value) in variables {
+            output = output.replace(&format!("{{{}{}}}", VARIABLE_PREFIX, name), value);
+        }
+        Self::fill(&output, bug).map(|markdown| target.convert(&markdown))
+    }
+
+    /// `template` with each `{{> name}}` replaced by the partial's content.
+    fn expand_partials(&self, template: &str, depth: usize) -> String {
+        let mut output = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("{{>") {
+            let Some(length) = rest[start..].find("}}").map(|end| end + 2) else {
+                break;
+            };
+            output.push_str(&rest[..start]);
+            let include = &rest[start..start + length];
+            match self.partial(include[3..length - 2].trim()) {
+                Some(content) if depth < MAX_PARTIAL_DEPTH => {
+                    let content = content.strip_suffix('\n').unwrap_or(&content);
+                    output.push_str(&self.expand_partials(content, depth + 1));
+                }
+                _ => output.push_str(include),
+            }
+            rest = &rest[start + length..];
+        }
+        output.push_str(rest);
+        output
+    }
+
+    /// Replace the bug placeholders in `template`.
+    fn fill(template: &str, bug: &BugData) -> Result<String, String> {
+        let mut output = template.to_string();
 
         // Simple placeholder replacement
         output = output.replace("{bug.title}", &bug.title);
@@ -130,12 +338,15 @@ impl TemplateManager {
         output = output.replace("{bug.description.expected}", &bug.description_expected);
         output = output.replace("{bug.description.actual}", &bug.description_actual);
         output = output.replace("{bug.folderPath}", &bug.folder_path);
+        output = output.replace("{bug.createdAt}", &bug.created_at);
 
         // Metadata fields
         output = output.replace("{bug.metadata.environment.os}", &bug.metadata.environment.os);
         output = output.replace("{bug.metadata.environment.displayResolution}", &bug.metadata.environment.display_resolution);
         output = output.replace("{bug.metadata.environment.dpiScaling}", &bug.metadata.environment.dpi_scaling);
         output = output.replace("{bug.metadata.environment.foregroundApp}", &bug.metadata.environment.foreground_app);
+        output = output.replace("{bug.metadata.environment.displayLanguage}", &bug.metadata.environment.display_language);
+        output = output.replace("{bug.metadata.environment.keyboardLayout}", &bug.metadata.environment.keyboard_layout);
 
         // Backwards-compatible softwareVersion: use explicit field first, then fall back to
         // custom_fields["softwareVersion"] or custom_fields["software_version"]
@@ -153,7 +364,7 @@ impl TemplateManager {
         let effective_meeting_id = bug.metadata.meeting_id.clone().or(meeting_id_from_custom);
 
         // Conditional fields (meeting ID)
-        output = Self::replace_conditional(&output, "bug.metadata.meetingId", &effective_meeting_id);
+        output = replace_conditional(&output, "bug.metadata.meetingId", effective_meeting_id.as_deref());
 
         // Generic custom field placeholders: replace both {key} and {{key}} for each
         // entry in custom_fields. This allows templates to use either brace style.
@@ -180,60 +391,67 @@ impl TemplateManager {
             .join("\n");
         output = output.replace("{bug.captures.list}", &captures_list);
 
+        // Design details: the whole section is omitted when nothing was measured
+        let design_details = if bug.design_details.is_empty() {
+            String::new()
+        } else {
+            let lines = bug.design_details.iter()
+                .map(|d| format!("- {}", d))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("## Design Details\n\n{}\n", lines)
+        };
+        output = output.replace("{bug.designDetails}", &design_details);
+
         // Console output
         let console_output = bug.console_output.as_deref().unwrap_or("No console output captured");
         output = output.replace("{bug.consoleOutput}", console_output);
 
         Ok(output)
     }
+}
 
-    /// Replace conditional placeholders (lines that should only appear if value exists)
-    fn replace_conditional(template: &str, field: &str, value: &Option<String>) -> String {
-        let pattern = format!("{{{}:", field);
-        let mut result = String::new();
-
-        for line in template.lines() {
-            if line.contains(&pattern) {
-                if let Some(val) = value {
-                    // Find the conditional pattern and extract the template inside
-                    // Format: {field:- **Label:** {value}}
-                    // We need to find the matching closing brace for the outer pattern
-                    if let Some(start_pos) = line.find(&pattern) {
-                        let prefix = &line[..start_pos];
-                        let rest = &line[start_pos + pattern.len()..];
-
-                        // Find the matching closing brace (need to handle nested braces)
-                        let mut depth = 0;
-                        let mut end_pos = 0;
-                        for (i, ch) in rest.chars().enumerate() {
-                            match ch {
-                                '{' => depth += 1,
-                                '}' => {
-                                    if depth == 0 {
-                                        end_pos = i;
-                                        break;
-                                    }
-                                    depth -= 1;
-                                }
-                                _ => {}
-                            }
-                        }
-
-                        let inner_template = &rest[..end_pos];
-                        let processed_line = format!("{}{}", prefix, inner_template.replace("{value}", val));
-                        result.push_str(&processed_line);
-                        result.push('\n');
-                    }
-                }
-                // If no value, skip the entire line
-            } else {
-                result.push_str(line);
-                result.push('\n');
+/// Expand `{field:text with {value}}` conditionals: the text, with `{value}`
+/// filled in, when the field has a value. Without one, the lines holding the
+/// conditional are dropped. The text may span lines and hold placeholders.
+pub(crate) fn replace_conditional(template: &str, field: &str, value: Option<&str>) -> String {
+    let pattern = format!("{{{}:", field);
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(&pattern) {
+        let text_start = start + pattern.len();
+        // The closing brace of the conditional, past any nested placeholders
+        let mut depth = 0;
+        let text_len = rest[text_start..].char_indices().find_map(|(i, ch)| match ch {
+            '{' => {
+                depth += 1;
+                None
+            }
+            '}' if depth == 0 => Some(i),
+            '}' => {
+                depth -= 1;
+                None
+            }
+            _ => None,
+        });
+        let Some(text_len) = text_len else { break };
+        let end = text_start + text_len + 1;
+
+        match value {
+            Some(value) => {
+                output.push_str(&rest[..start]);
+                output.push_str(&rest[text_start..end - 1].replace("{value}", value));
+                rest = &rest[end..];
+            }
+            None => {
+                let line_start = rest[..start].rfind('\n').map_or(0, |i| i + 1);
+                output.push_str(&rest[..line_start]);
+                rest = rest[end..].find('\n').map_or("", |i| &rest[end + i + 1..]);
             }
         }
-
-        result
     }
+    output.push_str(rest);
+    output
 }
 
 impl Drop for TemplateManager {
@@ -263,6 +481,8 @@ mod tests {
                     ram: "16GB".to_string(),
                     cpu: "Intel i7".to_string(),
                     foreground_app: "TestApp".to_string(),
+                    display_language: "en-US".to_string(),
+                    keyboard_layout: "ja-JP (04110411)".to_string(),
                 },
                 console_captures: vec![],
                 custom_fields: HashMap::new(),
@@ -270,9 +490,42 @@ mod tests {
             folder_path: "/path/to/bug".to_string(),
             captures: vec!["screenshot1.png".to_string(), "screenshot2.png".to_string()],
             console_output: Some("Error: Something went wrong".to_string()),
+            created_at: "15.01.2024 10:15:00 UTC".to_string(),
+            design_details: vec![],
         }
     }
 
+    #[test]
+    fn test_partials_and_variables() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("header.md"), "# {bug.title} ({var.projectCode})\n{{> footer}}\n").unwrap();
+        std::fs::write(dir.path().join("loop.md"), "{{> loop}}").unwrap();
+        let mut manager = TemplateManager::new();
+        manager.set_partials_dir(Some(dir.path().to_path_buf()));
+        manager.register_partial("footer", "Reported by {var.testerName}");
+
+        let variables = VariableValues::from([
+            ("projectCode".to_string(), "WEB".to_string()),
+            ("testerName".to_string(), "Jordan".to_string()),
+        ]);
+        let output = manager
+            .render_template(
+                "{{> header}}\n{{>environment}}\n{{> missing}} {var.unknown}",
+                &create_test_bug(),
+                &variables,
+                RenderTarget::Markdown,
+            )
+            .unwrap();
+        assert!(output.starts_with("# Test Bug (WEB)\nReported by Jordan\n- **OS:** Windows 11\n"));
+        assert!(output.contains("- **Meeting ID:** MTG-123"));
+        assert!(output.contains("{{> missing}} {var.unknown}"));
+
+        // Circular includes stop instead of recursing forever
+        let output = manager.render_template("{{> loop}}", &create_test_bug(), &variables, RenderTarget::Markdown).unwrap();
+        assert!(output.contains("{{> loop}}"));
+        assert_eq!(manager.partial_names(), vec!["description", "environment", "evidence", "footer", "header", "loop"]);
+    }
+
     #[test]
     fn test_template_manager_new() {
         let manager = TemplateManager::new();
@@ -283,7 +536,7 @@ mod tests {
     fn test_render_with_default_template() {
         let manager = TemplateManager::new();
         let bug = create_test_bug();
-        let result = manager.render(&bug);
+        let result = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown);
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -296,13 +549,31 @@ mod tests {
         assert!(output.contains("Windows 11"));
         assert!(output.contains("MTG-123"));
         assert!(output.contains("2 file(s)"));
+        assert!(output.contains("en-US (keyboard: ja-JP (04110411))"));
+    }
+
+    #[test]
+    fn test_render_for_target() {
+        let manager = TemplateManager::new();
+        let bug = create_test_bug();
+
+        let jira = manager.render(&bug, &VariableValues::new(), RenderTarget::JiraWiki).unwrap();
+        assert!(jira.starts_with("h1. Test Bug\n\n*Type:* UI\n"));
+        assert!(jira.contains("\n* *OS:* Windows 11\n"));
+
+        let html = manager.render(&bug, &VariableValues::new(), RenderTarget::Html).unwrap();
+        assert!(html.starts_with("<h1>Test Bug</h1>\n<p><strong>Type:</strong> UI</p>\n"));
+
+        let plain = manager.render(&bug, &VariableValues::new(), RenderTarget::Plain).unwrap();
+        assert!(plain.contains("\n• OS: Windows 11\n"));
+        assert!(!plain.contains("**"));
     }
 
     #[test]
     fn test_conditional_field_with_value() {
         let bug = create_test_bug();
         let manager = TemplateManager::new();
-        let result = manager.render(&bug).unwrap();
+        let result = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap();
 
         // Meeting ID should appear
         assert!(result.contains("MTG-123"));
@@ -314,7 +585,7 @@ mod tests {
         bug.metadata.meeting_id = None;
 
         let manager = TemplateManager::new();
-        let result = manager.render(&bug).unwrap();
+        let result = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap();
 
         // Line with meeting ID should not appear
         assert!(!result.contains("Meeting ID:"));
@@ -324,13 +595,33 @@ mod tests {
     fn test_captures_list() {
         let bug = create_test_bug();
         let manager = TemplateManager::new();
-        let result = manager.render(&bug).unwrap();
+        let result = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap();
 
         assert!(result.contains("screenshot1.png"));
         assert!(result.contains("screenshot2.png"));
         assert!(result.contains("2 file(s)"));
     }
 
+    #[test]
+    fn test_created_at_placeholder() {
+        let bug = create_test_bug();
+        let manager = TemplateManager::new();
+        *manager.cached_template.lock().unwrap() = "Logged: {bug.createdAt}".to_string();
+
+        assert_eq!(manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap().trim_end(), "Logged: 15.01.2024 10:15:00 UTC");
+    }
+
+    #[test]
+    fn test_design_details_section() {
+        let manager = TemplateManager::new();
+        let mut bug = create_test_bug();
+        assert!(!manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap().contains("Design Details"));
+
+        bug.design_details = vec!["#1A2B3C at (4, 8) (Header, screenshot1.png)".to_string()];
+        let rendered = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap();
+        assert!(rendered.contains("## Design Details\n\n- #1A2B3C at (4, 8) (Header, screenshot1.png)"));
+    }
+
     #[test]
     fn test_custom_fields_single_brace_replacement() {
         let mut bug = create_test_bug();
@@ -341,7 +632,7 @@ mod tests {
         let custom_template = "Build: {buildNumber}".to_string();
         *manager.cached_template.lock().unwrap() = custom_template;
 
-        let result = manager.render(&bug).unwrap();
+        let result = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap();
         assert!(result.contains("Build: 42"));
     }
 
@@ -354,7 +645,7 @@ mod tests {
         let custom_template = "Sprint: {{sprint}}".to_string();
         *manager.cached_template.lock().unwrap() = custom_template;
 
-        let result = manager.render(&bug).unwrap();
+        let result = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap();
         assert!(result.contains("Sprint: Sprint 5"));
     }
 
@@ -368,7 +659,7 @@ mod tests {
         let custom_template = "Env: {env} | Region: {{region}}".to_string();
         *manager.cached_template.lock().unwrap() = custom_template;
 
-        let result = manager.render(&bug).unwrap();
+        let result = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap();
         assert!(result.contains("Env: staging"));
         assert!(result.contains("Region: us-west-2"));
     }
@@ -381,7 +672,7 @@ mod tests {
         bug.metadata.custom_fields.insert("softwareVersion".to_string(), "2.0.0-custom".to_string());
 
         let manager = TemplateManager::new();
-        let result = manager.render(&bug).unwrap();
+        let result = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap();
 
         assert!(result.contains("2.0.0-custom"));
     }
@@ -394,7 +685,7 @@ mod tests {
         bug.metadata.custom_fields.insert("meetingId".to_string(), "MTG-from-custom".to_string());
 
         let manager = TemplateManager::new();
-        let result = manager.render(&bug).unwrap();
+        let result = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap();
 
         assert!(result.contains("MTG-from-custom"));
     }
@@ -407,7 +698,7 @@ mod tests {
         bug.metadata.custom_fields.insert("softwareVersion".to_string(), "2.0.0-custom".to_string());
 
         let manager = TemplateManager::new();
-        let result = manager.render(&bug).unwrap();
+        let result = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap();
 
         assert!(result.contains("1.0.0-legacy"));
         assert!(!result.contains("2.0.0-custom"));
@@ -425,7 +716,7 @@ mod tests {
         let custom_template = "Meeting: {bug.metadata.meetingId:- ID: {value}}".to_string();
         *manager.cached_template.lock().unwrap() = custom_template;
 
-        let result = manager.render(&bug).unwrap();
+        let result = manager.render(&bug, &VariableValues::new(), RenderTarget::Markdown).unwrap();
         assert!(result.contains("MTG-123"));
         assert!(!result.contains("SHOULD-NOT-APPEAR"));
     }
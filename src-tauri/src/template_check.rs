@@ -0,0 +1,314 @@
+//! Checking bug templates before they are saved.
+//!
+//! The template engine (see [`crate::template`]) replaces placeholders it
+//! knows and leaves everything else alone, so a typo such as
+//! `{bug.descripton.steps}` ends up verbatim in every ticket. [`validate`]
+//! finds such placeholders and reports them with their line and column;
+//! [`preview`] also renders the template against a bug, by default the
+//! built-in [`sample_bug`].
+//!
+//! Markdown templates may contain braces of their own (JSON in a code block),
+//! so only brace pairs that look like placeholders are checked: `{bug.…}`,
+//! `{var.…}`, `{value}`, `{{> partial}}` and `{key}`/`{{key}}` for custom
+//! fields.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::render_target::RenderTarget;
+use crate::template::{
+    BugData, BugMetadata, Environment, TemplateManager, VariableValues, CONDITIONAL_VARIABLES, TEMPLATE_VARIABLES,
+    VARIABLE_PREFIX,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// The placeholder will not be replaced
+    Error,
+    /// The placeholder is only replaced for some bugs
+    Warning,
+}
+
+/// A problem found in a template, located by 1-based line and column
+/// (in characters) so the editor can underline it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateIssue {
+    pub severity: IssueSeverity,
+    pub line: usize,
+    pub column: usize,
+    /// Length of the offending text in characters
+    pub length: usize,
+    /// Variable name, without braces
+    pub variable: Option<String>,
+    pub message: String,
+}
+
+/// Names a template may use besides the bug variables.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KnownNames {
+    /// Custom field keys of the active profile
+    pub custom_fields: Vec<String>,
+    /// `{var.<name>}` variables, without the prefix
+    pub variables: Vec<String>,
+    pub partials: Vec<String>,
+}
+
+/// A template rendered against a bug, with the issues [`validate`] found.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePreview {
+    pub rendered: String,
+    pub issues: Vec<TemplateIssue>,
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Byte offset of the `}` closing the `{` just before `rest`, counting
+/// nested braces.
+fn closing_brace(rest: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Issues in `content`. Custom field placeholders, variables and partials
+/// are fine when `known` lists them.
+pub fn validate(content: &str, known: &KnownNames) -> Vec<TemplateIssue> {
+    let custom_field_keys = &known.custom_fields;
+    let mut issues = Vec::new();
+    for (line_index, line) in content.lines().enumerate() {
+        let issue = |start: usize, text: &str, variable: Option<&str>, severity, message: String| TemplateIssue {
+            severity,
+            line: line_index + 1,
+            column: line[..start].chars().count() + 1,
+            length: text.chars().count(),
+            variable: variable.map(str::to_string),
+            message,
+        };
+        // End of the conditional being scanned, where `{value}` is allowed
+        let mut conditional_end = 0;
+        let mut pos = 0;
+        while let Some(offset) = line[pos..].find('{') {
+            let start = pos + offset;
+            let rest = &line[start + 1..];
+
+            if let Some(inner) = rest.strip_prefix('{') {
+                // {{> partial}} or {{key}} for a custom field
+                if let Some(end) = inner.find("}}") {
+                    let name = &inner[..end];
+                    let text = &line[start..start + end + 4];
+                    if let Some(partial) = name.strip_prefix('>').map(str::trim) {
+                        if !known.partials.iter().any(|p| p == partial) {
+                            issues.push(issue(
+                                start,
+                                text,
+                                Some(partial),
+                                IssueSeverity::Error,
+                                format!("Unknown partial {}", partial),
+                            ));
+                        }
+                    } else if is_identifier(name) && !custom_field_keys.iter().any(|k| k == name) {
+                        issues.push(issue(start, text, Some(name), IssueSeverity::Warning, unknown_field_message(name)));
+                    }
+                    pos = start + text.len();
+                } else {
+                    pos = start + 2;
+                }
+                continue;
+            }
+
+            let Some(end) = closing_brace(rest) else {
+                if rest.starts_with("bug.") {
+                    let name: String = rest.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '.').collect();
+                    issues.push(issue(
+                        start,
+                        &line[start..],
+                        Some(&name),
+                        IssueSeverity::Error,
+                        format!("Placeholder {{{}}} is missing its closing '}}'", name),
+                    ));
+                }
+                break;
+            };
+            let inner = &rest[..end];
+            let text = &line[start..start + end + 2];
+
+            if let Some((field, _)) = inner.split_once(':').filter(|(field, _)| field.starts_with("bug.")) {
+                // {bug.field:text with {value}}: scan the text too
+                if !CONDITIONAL_VARIABLES.contains(&field) {
+                    issues.push(issue(
+                        start,
+                        text,
+                        Some(field),
+                        IssueSeverity::Error,
+                        format!("{} cannot be used as a conditional; only {} can", field, CONDITIONAL_VARIABLES.join(", ")),
+                    ));
+                }
+                conditional_end = start + text.len();
+                pos = start + 1 + field.len() + 1;
+                continue;
+            }
+
+            if let Some(name) = inner.strip_prefix(VARIABLE_PREFIX) {
+                if !known.variables.iter().any(|v| v == name) {
+                    issues.push(issue(
+                        start,
+                        text,
+                        Some(inner),
+                        IssueSeverity::Error,
+                        format!("Unknown variable {}; define it under template variables", inner),
+                    ));
+                }
+            } else if inner.starts_with("bug.") {
+                if CONDITIONAL_VARIABLES.contains(&inner) {
+                    issues.push(issue(
+                        start,
+                        text,
+                        Some(inner),
+                        IssueSeverity::Error,
+                        format!("{} is only replaced in a conditional, e.g. {{{}:- **Label:** {{value}}}}", inner, inner),
+                    ));
+                } else if !TEMPLATE_VARIABLES.contains(&inner) {
+                    issues.push(issue(start, text, Some(inner), IssueSeverity::Error, format!("Unknown variable {}", inner)));
+                }
+            } else if inner == "value" {
+                if start >= conditional_end {
+                    issues.push(issue(
+                        start,
+                        text,
+                        Some(inner),
+                        IssueSeverity::Error,
+                        "{value} is only replaced inside a conditional".to_string(),
+                    ));
+                }
+            } else if is_identifier(inner) && !custom_field_keys.iter().any(|k| k == inner) {
+                issues.push(issue(start, text, Some(inner), IssueSeverity::Warning, unknown_field_message(inner)));
+            }
+            pos = start + text.len();
+        }
+    }
+    issues
+}
+
+fn unknown_field_message(key: &str) -> String {
+    format!("{} is not a built-in variable; it is only replaced for bugs with a custom field '{}'", key, key)
+}
+
+/// A made-up bug with every field filled in, for previews.
+pub fn sample_bug() -> BugData {
+    BugData {
+        title: "Save button does nothing after renaming a file".to_string(),
+        bug_type: "bug".to_string(),
+        description_steps: "1. Open a document\n2. Rename it from the title bar\n3. Click Save".to_string(),
+        description_expected: "The document is saved under its new name".to_string(),
+        description_actual: "Nothing happens; the unsaved marker stays".to_string(),
+        metadata: BugMetadata {
+            meeting_id: Some("MTG-1042".to_string()),
+            software_version: Some("2.4.1".to_string()),
+            environment: Environment {
+                os: "Windows 11 Pro 23H2".to_string(),
+                display_resolution: "2560x1440".to_string(),
+                dpi_scaling: "125%".to_string(),
+                ram: "32 GB".to_string(),
+                cpu: "Intel Core i7-1365U".to_string(),
+                foreground_app: "Editor.exe".to_string(),
+                display_language: "en-US".to_string(),
+                keyboard_layout: "en-US (00000409)".to_string(),
+            },
+            console_captures: vec![],
+            custom_fields: HashMap::from([("impact".to_string(), "High".to_string())]),
+        },
+        folder_path: "sessions/2024-05-14_3f2a9c1e/bug_001".to_string(),
+        captures: vec!["capture-001.png".to_string(), "capture-002.png".to_string()],
+        console_output: Some("TypeError: Cannot read properties of undefined (reading 'path')\n    at save (editor.js:812:17)".to_string()),
+        created_at: "14.05.2024 14:32:07 UTC".to_string(),
+        design_details: vec!["Button colour #1A73E8, expected #1967D2".to_string()],
+    }
+}
+
+/// `content` rendered against `bug`, with the issues found in it.
+pub fn preview(
+    manager: &TemplateManager,
+    content: &str,
+    bug: &BugData,
+    variables: &VariableValues,
+    known: &KnownNames,
+) -> Result<TemplatePreview, String> {
+    let mut known = known.clone();
+    known.custom_fields.extend(bug.metadata.custom_fields.keys().cloned());
+    Ok(TemplatePreview {
+        rendered: manager.render_template(content, bug, variables, RenderTarget::Markdown)?,
+        issues: validate(content, &known),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::DEFAULT_TEMPLATE;
+
+    #[test]
+    fn test_default_template_is_valid() {
+        assert_eq!(validate(DEFAULT_TEMPLATE, &KnownNames::default()), vec![]);
+    }
+
+    #[test]
+    fn test_reports_issue_locations() {
+        let content = "# {bug.title}\n\
+            Steps: {bug.descripton.steps} {value}\n\
+            {bug.metadata.meetingId:- Meeting {value}}\n\
+            {bug.type:- Type {value}}\n\
+            Impact {impact}, area {{area}}, json {\"a\": 1}\n\
+            Broken {bug.consoleOutput";
+        let known = KnownNames { custom_fields: vec!["impact".to_string()], ..Default::default() };
+        let issues = validate(content, &known);
+        let found: Vec<_> = issues
+            .iter()
+            .map(|i| (i.severity, i.line, i.column, i.length, i.variable.as_deref()))
+            .collect();
+        assert_eq!(found, vec![
+            (IssueSeverity::Error, 2, 8, 22, Some("bug.descripton.steps")),
+            (IssueSeverity::Error, 2, 31, 7, Some("value")),
+            (IssueSeverity::Error, 4, 1, 25, Some("bug.type")),
+            (IssueSeverity::Warning, 5, 23, 8, Some("area")),
+            (IssueSeverity::Error, 6, 8, 18, Some("bug.consoleOutput")),
+        ]);
+
+        let known = KnownNames {
+            variables: vec!["testerName".to_string()],
+            partials: vec!["environment".to_string()],
+            ..Default::default()
+        };
+        let issues = validate("{{> environment}} {{>footer}} {var.testerName} {var.build}", &known);
+        let found: Vec<_> = issues.iter().map(|i| (i.column, i.variable.as_deref())).collect();
+        assert_eq!(found, vec![(19, Some("footer")), (48, Some("var.build"))]);
+    }
+
+    #[test]
+    fn test_preview_renders_sample_bug() {
+        let manager = TemplateManager::new();
+        let variables = VariableValues::from([("testerName".to_string(), "Jordan".to_string())]);
+        let known = KnownNames { variables: vec!["testerName".to_string()], ..Default::default() };
+        let content = "{bug.title} by {var.testerName} ({impact}) {bug.metadata.meetingId:in {value}}";
+        let preview = preview(&manager, content, &sample_bug(), &variables, &known).unwrap();
+        assert_eq!(
+            preview.rendered.trim_end(),
+            "Save button does nothing after renaming a file by Jordan (High) in MTG-1042"
+        );
+        assert!(preview.issues.is_empty());
+    }
+}
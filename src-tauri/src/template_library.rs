@@ -0,0 +1,291 @@
+//! Named bug report templates.
+//!
+//! Besides the single custom template of [`crate::template::TemplateManager`],
+//! teams keep a library of named templates ("Crash report", "UX feedback")
+//! as `<name>.md` files in the `templates/named` folder of the app data
+//! directory. The `template.selection` setting maps bug types and profiles to
+//! a template by name; [`TemplateSelection::resolve`] picks the one a bug is
+//! rendered with. A bug type mapping wins over a profile mapping, since types
+//! need a different structure (a question has no repro steps) while a
+//! profile's template is its default for the rest. Bugs matching neither use
+//! the custom or default template as before.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{SessionOps, SessionRepository, SettingsOps, SettingsRepository};
+use crate::profile::ACTIVE_PROFILE_KEY;
+
+/// Settings key holding [`TemplateSelection`] as JSON.
+pub const TEMPLATE_SELECTION_KEY: &str = "template.selection";
+
+const TEMPLATE_EXTENSION: &str = "md";
+
+const MAX_NAME_LEN: usize = 60;
+
+/// A template in the library.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedTemplate {
+    pub name: String,
+    pub path: String,
+    /// Bug types and profile ids rendered with this template
+    pub bug_types: Vec<String>,
+    pub profile_ids: Vec<String>,
+}
+
+/// Which named template bugs are rendered with.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TemplateSelection {
+    /// Bug type (`bug`, `feature`, `feedback`, `question`) to template name
+    pub by_bug_type: HashMap<String, String>,
+    /// Profile id to template name
+    pub by_profile: HashMap<String, String>,
+}
+
+impl TemplateSelection {
+    /// Stored selection; missing or unreadable settings select nothing.
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(TEMPLATE_SELECTION_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        if let Some(bug_type) = self
+            .by_bug_type
+            .keys()
+            .find(|t| crate::database::BugType::from_str(t).is_err())
+        {
+            return Err(format!("Unknown bug type: {}", bug_type));
+        }
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(TEMPLATE_SELECTION_KEY, &json)
+            .map_err(|e| format!("Failed to save template selection: {}", e))
+    }
+
+    /// Template name for a bug of `bug_type` under `profile_id`.
+    pub fn resolve(&self, bug_type: &str, profile_id: Option<&str>) -> Option<&str> {
+        self.by_bug_type
+            .get(bug_type)
+            .or_else(|| profile_id.and_then(|id| self.by_profile.get(id)))
+            .map(String::as_str)
+    }
+
+    /// Template name for a bug of `bug_type` in `session_id`: the session's
+    /// profile, or the active profile when the session has none.
+    pub fn resolve_for_session(&self, conn: &Connection, bug_type: &str, session_id: Option<&str>) -> Option<&str> {
+        let profile_id = session_id
+            .and_then(|id| SessionRepository::new(conn).get(id).ok().flatten())
+            .and_then(|session| session.profile_id)
+            .or_else(|| SettingsRepository::new(conn).get(ACTIVE_PROFILE_KEY).ok().flatten());
+        self.resolve(bug_type, profile_id.as_deref())
+    }
+
+    fn rename(&mut self, from: &str, to: &str) {
+        for name in self.by_bug_type.values_mut().chain(self.by_profile.values_mut()) {
+            if name == from {
+                *name = to.to_string();
+            }
+        }
+    }
+
+    fn remove(&mut self, name: &str) {
+        self.by_bug_type.retain(|_, n| n != name);
+        self.by_profile.retain(|_, n| n != name);
+    }
+}
+
+/// Trimmed `name`, checked to be usable as a file name on every platform.
+fn checked_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("Template name must be at most {} characters", MAX_NAME_LEN));
+    }
+    if !name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.')) || name.starts_with('.') {
+        return Err(format!("Template name '{}' may only contain letters, digits, spaces, '-', '_' and '.'", name));
+    }
+    Ok(name.to_string())
+}
+
+/// The folder of named templates.
+pub struct TemplateLibrary {
+    dir: PathBuf,
+}
+
+impl TemplateLibrary {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", name, TEMPLATE_EXTENSION))
+    }
+
+    /// Path of an existing template, matched case-insensitively.
+    fn existing_path(&self, name: &str) -> Option<(String, PathBuf)> {
+        self.names().into_iter().find(|n| n.eq_ignore_ascii_case(name.trim())).map(|n| {
+            let path = self.path(&n);
+            (n, path)
+        })
+    }
+
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| p.is_file() && p.extension().and_then(|e| e.to_str()) == Some(TEMPLATE_EXTENSION))
+                    .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort_by_key(|n| n.to_lowercase());
+        names
+    }
+
+    /// Every template, by name, with what `selection` maps to it.
+    pub fn list(&self, selection: &TemplateSelection) -> Vec<NamedTemplate> {
+        self.names()
+            .into_iter()
+            .map(|name| {
+                let mut bug_types: Vec<String> =
+                    selection.by_bug_type.iter().filter(|(_, n)| **n == name).map(|(t, _)| t.clone()).collect();
+                let mut profile_ids: Vec<String> =
+                    selection.by_profile.iter().filter(|(_, n)| **n == name).map(|(p, _)| p.clone()).collect();
+                bug_types.sort();
+                profile_ids.sort();
+                NamedTemplate { path: self.path(&name).to_string_lossy().to_string(), name, bug_types, profile_ids }
+            })
+            .collect()
+    }
+
+    pub fn read(&self, name: &str) -> Result<String, String> {
+        let (_, path) = self.existing_path(name).ok_or_else(|| format!("Template not found: {}", name))?;
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read template {}: {}", path.display(), e))
+    }
+
+    /// Add a template called `name` with `content`.
+    pub fn create(&self, name: &str, content: &str) -> Result<PathBuf, String> {
+        let name = checked_name(name)?;
+        if self.existing_path(&name).is_some() {
+            return Err(format!("A template named '{}' already exists", name));
+        }
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create templates directory: {}", e))?;
+        let path = self.path(&name);
+        std::fs::write(&path, content).map_err(|e| format!("Failed to save template: {}", e))?;
+        Ok(path)
+    }
+
+    /// Replace the content of an existing template.
+    pub fn update(&self, name: &str, content: &str) -> Result<PathBuf, String> {
+        let (_, path) = self.existing_path(name).ok_or_else(|| format!("Template not found: {}", name))?;
+        std::fs::write(&path, content).map_err(|e| format!("Failed to save template: {}", e))?;
+        Ok(path)
+    }
+
+    /// Rename a template and the mappings in `selection` that use it.
+    pub fn rename(&self, name: &str, new_name: &str, selection: &mut TemplateSelection) -> Result<PathBuf, String> {
+        let new_name = checked_name(new_name)?;
+        let (name, path) = self.existing_path(name).ok_or_else(|| format!("Template not found: {}", name))?;
+        // A change of case only is allowed; anything else must be free
+        if !name.eq_ignore_ascii_case(&new_name) && self.existing_path(&new_name).is_some() {
+            return Err(format!("A template named '{}' already exists", new_name));
+        }
+        let new_path = self.path(&new_name);
+        std::fs::rename(&path, &new_path).map_err(|e| format!("Failed to rename template: {}", e))?;
+        selection.rename(&name, &new_name);
+        Ok(new_path)
+    }
+
+    /// Delete a template and the mappings in `selection` that use it.
+    pub fn delete(&self, name: &str, selection: &mut TemplateSelection) -> Result<(), String> {
+        let (name, path) = self.existing_path(name).ok_or_else(|| format!("Template not found: {}", name))?;
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to delete template: {}", e))?;
+        selection.remove(&name);
+        Ok(())
+    }
+
+    /// Content of the template a bug was mapped to. A mapping to a template
+    /// that no longer exists is ignored.
+    pub fn selected_content(&self, name: &str) -> Option<String> {
+        self.read(name)
+            .inspect_err(|e| eprintln!("Warning: using the default bug template: {}", e))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::init_database;
+
+    #[test]
+    fn test_library_crud_keeps_selection_in_step() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = TemplateLibrary::new(dir.path().join("named"));
+        let mut selection = TemplateSelection::default();
+        assert!(library.list(&selection).is_empty());
+
+        library.create("Crash report", "# {bug.title}").unwrap();
+        library.create("UX feedback", "Feedback: {bug.title}").unwrap();
+        assert!(library.create("crash REPORT", "").is_err());
+        assert!(library.create("../escape", "").is_err());
+        selection.by_bug_type.insert("feedback".to_string(), "UX feedback".to_string());
+        selection.by_profile.insert("p-1".to_string(), "UX feedback".to_string());
+
+        library.update("crash report", "## {bug.title}").unwrap();
+        assert_eq!(library.read("Crash report").unwrap(), "## {bug.title}");
+
+        library.rename("UX feedback", "Feedback", &mut selection).unwrap();
+        assert_eq!(selection.resolve("feedback", None), Some("Feedback"));
+        let listed = library.list(&selection);
+        assert_eq!(listed.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["Crash report", "Feedback"]);
+        assert_eq!(listed[1].bug_types, vec!["feedback"]);
+        assert_eq!(listed[1].profile_ids, vec!["p-1"]);
+
+        library.delete("feedback", &mut selection).unwrap();
+        assert_eq!(selection, TemplateSelection::default());
+        assert!(library.read("Feedback").is_err());
+        assert_eq!(library.selected_content("Feedback"), None);
+    }
+
+    #[test]
+    fn test_bug_type_mapping_wins_over_profile() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let selection = TemplateSelection {
+            by_bug_type: HashMap::from([("question".to_string(), "Question".to_string())]),
+            by_profile: HashMap::from([("p-1".to_string(), "Team A".to_string())]),
+        };
+        selection.save(&conn).unwrap();
+        let selection = TemplateSelection::load(&conn);
+
+        assert_eq!(selection.resolve("question", Some("p-1")), Some("Question"));
+        assert_eq!(selection.resolve("bug", Some("p-1")), Some("Team A"));
+        assert_eq!(selection.resolve("bug", None), None);
+
+        // Without a session the active profile applies
+        SettingsRepository::new(&conn).set(ACTIVE_PROFILE_KEY, "p-1").unwrap();
+        assert_eq!(selection.resolve_for_session(&conn, "bug", None), Some("Team A"));
+
+        let invalid = TemplateSelection {
+            by_bug_type: HashMap::from([("incident".to_string(), "Question".to_string())]),
+            ..Default::default()
+        };
+        assert!(invalid.save(&conn).is_err());
+    }
+}
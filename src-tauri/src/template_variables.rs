@@ -0,0 +1,210 @@
+//! Template variables filled from settings.
+//!
+//! Templates can use `{var.<name>}` for values that are the same for every
+//! bug of a team but not part of the bug itself: the tester name, a project
+//! code, the build channel under test. Variables registered with the
+//! [`TemplateManager`] read an existing setting (`testerName` reads
+//! `tester_name`); user-defined ones are kept in the `template.variables`
+//! setting as a name to value map. [`available`] lists everything a template
+//! can use, for the editor.
+
+use std::collections::BTreeMap;
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::database::{SettingsOps, SettingsRepository};
+use crate::template::{
+    TemplateManager, TemplateVariable, VariableValues, CONDITIONAL_VARIABLES, TEMPLATE_VARIABLES, VARIABLE_PREFIX,
+};
+use crate::template_check::KnownNames;
+
+/// Settings key holding the user-defined variables as a JSON object.
+pub const TEMPLATE_VARIABLES_KEY: &str = "template.variables";
+
+const MAX_VARIABLES: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VariableKind {
+    /// A field of the bug, e.g. `{bug.title}`
+    Bug,
+    /// A bug field only usable as `{field:text with {value}}`
+    Conditional,
+    /// Registered with the template manager, read from a setting
+    Setting,
+    /// Defined by the user in `template.variables`
+    Custom,
+    /// A custom metadata field of the active profile
+    CustomField,
+    Partial,
+}
+
+/// Something a template can use, as the editor offers it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableVariable {
+    /// Text to insert, e.g. `{var.projectCode}` or `{{> environment}}`
+    pub placeholder: String,
+    pub kind: VariableKind,
+    pub description: Option<String>,
+    /// Current value, for variables filled from settings
+    pub value: Option<String>,
+}
+
+/// User-defined variables; missing or unreadable settings define none.
+pub fn load_custom(conn: &Connection) -> BTreeMap<String, String> {
+    SettingsRepository::new(conn)
+        .get(TEMPLATE_VARIABLES_KEY)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Save the user-defined variables. Names must be identifiers and must not
+/// shadow a `registered` variable.
+pub fn save_custom(
+    conn: &Connection,
+    variables: &BTreeMap<String, String>,
+    registered: &[TemplateVariable],
+) -> Result<(), String> {
+    if variables.len() > MAX_VARIABLES {
+        return Err(format!("At most {} template variables can be defined", MAX_VARIABLES));
+    }
+    for name in variables.keys() {
+        let mut chars = name.chars();
+        let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!("Variable name '{}' must start with a letter and contain only letters, digits and '_'", name));
+        }
+        if registered.iter().any(|v| &v.name == name) {
+            return Err(format!("{}{} is built in and is set in Settings", VARIABLE_PREFIX, name));
+        }
+    }
+    let json = serde_json::to_string(variables).map_err(|e| e.to_string())?;
+    SettingsRepository::new(conn)
+        .set(TEMPLATE_VARIABLES_KEY, &json)
+        .map_err(|e| format!("Failed to save template variables: {}", e))
+}
+
+/// Value of a registered variable; unset settings give no value.
+fn registered_value(conn: &Connection, variable: &TemplateVariable) -> Option<String> {
+    SettingsRepository::new(conn)
+        .get(&variable.setting_key)
+        .ok()
+        .flatten()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Values for rendering: registered variables, then the user-defined ones.
+/// A registered variable whose setting is unset renders as empty text rather
+/// than staying in the output as `{var.<name>}`.
+pub fn values(conn: &Connection, registered: &[TemplateVariable]) -> VariableValues {
+    let mut values: VariableValues = registered
+        .iter()
+        .map(|v| (v.name.clone(), registered_value(conn, v).unwrap_or_default()))
+        .collect();
+    values.extend(load_custom(conn));
+    values
+}
+
+/// Partials and variable names templates may use, for validation.
+pub fn known_names(conn: &Connection, manager: &TemplateManager, custom_field_keys: Vec<String>) -> KnownNames {
+    let mut variables: Vec<String> = manager.variables().iter().map(|v| v.name.clone()).collect();
+    variables.extend(load_custom(conn).into_keys());
+    KnownNames { custom_fields: custom_field_keys, variables, partials: manager.partial_names() }
+}
+
+/// Everything a template can use, in the order the editor lists it.
+pub fn available(conn: &Connection, manager: &TemplateManager, custom_field_keys: &[String]) -> Vec<AvailableVariable> {
+    let entry = |placeholder: String, kind, description: Option<String>, value: Option<String>| AvailableVariable {
+        placeholder,
+        kind,
+        description,
+        value,
+    };
+    let mut list: Vec<AvailableVariable> = TEMPLATE_VARIABLES
+        .iter()
+        .map(|name| entry(format!("{{{}}}", name), VariableKind::Bug, None, None))
+        .collect();
+    list.extend(CONDITIONAL_VARIABLES.iter().map(|name| {
+        entry(
+            format!("{{{}:{{value}}}}", name),
+            VariableKind::Conditional,
+            Some("Line kept only when the field has a value".to_string()),
+            None,
+        )
+    }));
+    list.extend(manager.variables().iter().map(|v| {
+        entry(
+            format!("{{{}{}}}", VARIABLE_PREFIX, v.name),
+            VariableKind::Setting,
+            Some(v.description.clone()),
+            registered_value(conn, v),
+        )
+    }));
+    list.extend(
+        load_custom(conn)
+            .into_iter()
+            .map(|(name, value)| entry(format!("{{{}{}}}", VARIABLE_PREFIX, name), VariableKind::Custom, None, Some(value))),
+    );
+    list.extend(
+        custom_field_keys
+            .iter()
+            .map(|key| entry(format!("{{{}}}", key), VariableKind::CustomField, None, None)),
+    );
+    list.extend(
+        manager
+            .partial_names()
+            .into_iter()
+            .map(|name| entry(format!("{{{{> {}}}}}", name), VariableKind::Partial, None, None)),
+    );
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{init_database, TESTER_NAME_KEY};
+
+    #[test]
+    fn test_values_and_listing() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let manager = TemplateManager::new();
+        SettingsRepository::new(&conn).set(TESTER_NAME_KEY, "Jordan").unwrap();
+
+        let custom = BTreeMap::from([
+            ("projectCode".to_string(), "WEB".to_string()),
+            ("buildChannel".to_string(), "beta".to_string()),
+        ]);
+        save_custom(&conn, &custom, manager.variables()).unwrap();
+        let shadowing = BTreeMap::from([("testerName".to_string(), "x".to_string())]);
+        assert!(save_custom(&conn, &shadowing, manager.variables()).is_err());
+        let invalid = BTreeMap::from([("build channel".to_string(), "x".to_string())]);
+        assert!(save_custom(&conn, &invalid, manager.variables()).is_err());
+
+        let values = values(&conn, manager.variables());
+        assert_eq!(values.get("testerName").map(String::as_str), Some("Jordan"));
+        assert_eq!(values.get("buildChannel").map(String::as_str), Some("beta"));
+
+        let listed = available(&conn, &manager, &["impact".to_string()]);
+        let find = |placeholder: &str| listed.iter().find(|v| v.placeholder == placeholder).unwrap();
+        assert_eq!(find("{bug.title}").kind, VariableKind::Bug);
+        assert_eq!(find("{var.testerName}").value.as_deref(), Some("Jordan"));
+        assert_eq!(find("{var.projectCode}").kind, VariableKind::Custom);
+        assert_eq!(find("{impact}").kind, VariableKind::CustomField);
+        assert_eq!(find("{{> environment}}").kind, VariableKind::Partial);
+
+        let known = known_names(&conn, &manager, vec![]);
+        assert!(known.variables.contains(&"projectCode".to_string()));
+        assert!(known.partials.contains(&"evidence".to_string()));
+
+        // An unset setting still replaces its placeholder
+        SettingsRepository::new(&conn).set(TESTER_NAME_KEY, " ").unwrap();
+        let unset = super::values(&conn, manager.variables());
+        assert_eq!(unset.get("testerName").map(String::as_str), Some(""));
+    }
+}
@@ -0,0 +1,458 @@
+use super::trait_def::TicketingIntegration;
+use super::types::*;
+use crate::database::{SettingsOps, SettingsRepository};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::{Arc, RwLock};
+
+/// Settings key: Jira Cloud site, e.g. `https://acme.atlassian.net`
+pub const JIRA_SITE_URL_KEY: &str = "ticketing.jira.site_url";
+/// Settings key: Atlassian account email used with the API token
+pub const JIRA_EMAIL_KEY: &str = "ticketing.jira.email";
+/// Settings key: Atlassian API token
+pub const JIRA_API_TOKEN_KEY: &str = "ticketing.jira.api_token";
+/// Settings key: key of the project new issues are filed in, e.g. `QA`
+pub const JIRA_PROJECT_KEY_KEY: &str = "ticketing.jira.project_key";
+
+/// Issue type used for new tickets
+const ISSUE_TYPE: &str = "Bug";
+
+/// Jira Cloud connection settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JiraConfig {
+    pub site_url: String,
+    pub email: String,
+    pub api_token: String,
+    pub project_key: String,
+}
+
+impl JiraConfig {
+    /// Stored settings; missing keys are left empty.
+    pub fn load(conn: &Connection) -> Self {
+        let repo = SettingsRepository::new(conn);
+        let get = |key: &str| repo.get(key).ok().flatten().unwrap_or_default();
+        Self {
+            site_url: get(JIRA_SITE_URL_KEY),
+            email: get(JIRA_EMAIL_KEY),
+            api_token: get(JIRA_API_TOKEN_KEY),
+            project_key: get(JIRA_PROJECT_KEY_KEY),
+        }
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let repo = SettingsRepository::new(conn);
+        for (key, value) in [
+            (JIRA_SITE_URL_KEY, self.site_url.trim().trim_end_matches('/')),
+            (JIRA_EMAIL_KEY, self.email.trim()),
+            (JIRA_API_TOKEN_KEY, self.api_token.trim()),
+            (JIRA_PROJECT_KEY_KEY, self.project_key.trim()),
+        ] {
+            repo.set(key, value)
+                .map_err(|e| format!("Failed to save Jira settings: {}", e))?;
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let site = self.site_url.trim();
+        if !site.starts_with("https://") {
+            return Err("Jira site URL must start with https://".to_string());
+        }
+        if !self.email.contains('@') {
+            return Err("Jira email must be an email address".to_string());
+        }
+        if self.api_token.trim().is_empty() {
+            return Err("Jira API token cannot be empty".to_string());
+        }
+        if self.project_key.trim().is_empty() {
+            return Err("Jira project key cannot be empty".to_string());
+        }
+        Ok(())
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{}/rest/api/3/{}", self.site_url, path)
+    }
+}
+
+/// Jira Cloud integration for creating issues via REST API v3
+///
+/// Authenticates with an Atlassian account email and API token (basic auth).
+/// Tokens can be created at: https://id.atlassian.com/manage-profile/security/api-tokens
+pub struct JiraIntegration {
+    /// Stored settings, the only source of the site, account and token
+    config: JiraConfig,
+    /// Settings in use after a successful authentication
+    credentials: Arc<RwLock<Option<JiraConfig>>>,
+}
+
+impl JiraIntegration {
+    /// Create a new Jira integration using the stored settings
+    pub fn new(config: JiraConfig) -> Self {
+        Self {
+            config,
+            credentials: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Set credentials directly without network validation (for testing only)
+    #[cfg(test)]
+    pub(crate) fn set_credentials_for_test(&self, config: JiraConfig) {
+        *self.credentials.write().unwrap() = Some(config);
+    }
+
+    fn authenticated_config(&self) -> TicketingResult<JiraConfig> {
+        self.credentials
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| TicketingError::AuthenticationFailed("Not authenticated".to_string()))
+    }
+
+    /// Fetch the account the token belongs to (read-only)
+    fn get_myself(config: &JiraConfig) -> TicketingResult<serde_json::Value> {
+        let client = reqwest::blocking::Client::new();
+        let response = client
+            .get(config.api_url("myself"))
+            .basic_auth(&config.email, Some(&config.api_token))
+            .header("Accept", "application/json")
+            .send()
+            .map_err(TicketingError::from_send)?;
+
+        if !response.status().is_success() {
+            return Err(TicketingError::AuthenticationFailed(format!(
+                "HTTP {}: Invalid email or API token",
+                response.status()
+            )));
+        }
+
+        response
+            .json()
+            .map_err(TicketingError::from_parse)
+    }
+
+    /// Attach a file to an existing issue, returning its content URL
+    fn upload_attachment(config: &JiraConfig, issue_key: &str, file_path: &str) -> TicketingResult<String> {
+        use std::path::Path;
+
+        let path = Path::new(file_path);
+        let file_bytes = std::fs::read(path).map_err(|e| {
+            TicketingError::NetworkError(format!("Cannot read file {}: {}", file_path, e))
+        })?;
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("attachment")
+            .replace('"', "");
+
+        // multipart/form-data with a single "file" part
+        let boundary = format!("----qa-capture-{}", uuid::Uuid::new_v4().simple());
+        let mut body = Vec::with_capacity(file_bytes.len() + 256);
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                boundary,
+                file_name,
+                attachment_content_type(path)
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&file_bytes);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+        let client = reqwest::blocking::Client::new();
+        let response = client
+            .post(config.api_url(&format!("issue/{}/attachments", issue_key)))
+            .basic_auth(&config.email, Some(&config.api_token))
+            .header("X-Atlassian-Token", "no-check")
+            .header("Content-Type", format!("multipart/form-data; boundary={}", boundary))
+            .body(body)
+            .send()
+            .map_err(TicketingError::from_send)?;
+
+        if !response.status().is_success() {
+            return Err(TicketingError::from_status(response));
+        }
+
+        // The response lists the created attachment(s)
+        let attachments: serde_json::Value = response
+            .json()
+            .map_err(TicketingError::from_parse)?;
+        Ok(attachments
+            .get(0)
+            .and_then(|a| a.get("content"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string())
+    }
+}
+
+/// Jira priority name for a request priority. Numeric values follow Linear's
+/// scale (1 = urgent … 4 = low, 0 = none); anything else is used as a name.
+fn priority_name(priority: &str) -> Option<String> {
+    match priority.trim() {
+        "" | "0" => None,
+        "1" => Some("Highest".to_string()),
+        "2" => Some("High".to_string()),
+        "3" => Some("Medium".to_string()),
+        "4" => Some("Low".to_string()),
+        name => Some(name.to_string()),
+    }
+}
+
+/// Atlassian Document Format text node
+fn adf_text(text: &str) -> serde_json::Value {
+    json!({ "type": "text", "text": text })
+}
+
+/// Lines of a paragraph, separated by hard breaks
+fn adf_paragraph(lines: &[&str]) -> serde_json::Value {
+    let mut content = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            content.push(json!({ "type": "hardBreak" }));
+        }
+        if !line.is_empty() {
+            content.push(adf_text(line));
+        }
+    }
+    json!({ "type": "paragraph", "content": content })
+}
+
+/// Convert a markdown ticket description to an Atlassian Document Format
+/// document. Headings, fenced code blocks and paragraphs are kept; other
+/// markdown is passed through as text.
+pub fn description_to_adf(markdown: &str) -> serde_json::Value {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<(String, Vec<&str>)> = None;
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<serde_json::Value>| {
+        if !paragraph.is_empty() {
+            blocks.push(adf_paragraph(paragraph));
+            paragraph.clear();
+        }
+    };
+
+    for line in markdown.lines() {
+        if let Some((language, code_lines)) = code.as_mut() {
+            if line.trim_start().starts_with("```") {
+                let mut block = json!({ "type": "codeBlock", "content": [] });
+                if !language.is_empty() {
+                    block["attrs"] = json!({ "language": language });
+                }
+                if !code_lines.is_empty() {
+                    block["content"] = json!([adf_text(&code_lines.join("\n"))]);
+                }
+                blocks.push(block);
+                code = None;
+            } else {
+                code_lines.push(line);
+            }
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if let Some(language) = trimmed.strip_prefix("```") {
+            flush(&mut paragraph, &mut blocks);
+            code = Some((language.trim().to_string(), Vec::new()));
+        } else if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some((level, text)) = heading(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(json!({
+                "type": "heading",
+                "attrs": { "level": level },
+                "content": [adf_text(text)],
+            }));
+        } else {
+            paragraph.push(line.trim_end());
+        }
+    }
+
+    // An unclosed code fence keeps its lines as a code block
+    if let Some((_, code_lines)) = code {
+        if !code_lines.is_empty() {
+            blocks.push(json!({ "type": "codeBlock", "content": [adf_text(&code_lines.join("\n"))] }));
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+
+    json!({ "type": "doc", "version": 1, "content": blocks })
+}
+
+/// `## Title` → `(2, "Title")`
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let text = line[level..].strip_prefix(' ')?.trim();
+    ((1..=6).contains(&level) && !text.is_empty()).then_some((level, text))
+}
+
+impl TicketingIntegration for JiraIntegration {
+    /// Jira uses its own stored settings; the shared `credentials` hold the
+    /// Linear API key and team and are ignored.
+    fn authenticate(&self, _credentials: &TicketingCredentials) -> TicketingResult<()> {
+        let mut config = self.config.clone();
+        config.site_url = config.site_url.trim().trim_end_matches('/').to_string();
+        config.validate().map_err(TicketingError::InvalidConfig)?;
+
+        // Read-only check that the email and token are accepted
+        Self::get_myself(&config).map_err(|e| match e {
+            TicketingError::NetworkError(msg) => TicketingError::AuthenticationFailed(msg),
+            other => other,
+        })?;
+
+        *self.credentials.write().unwrap() = Some(config);
+        Ok(())
+    }
+
+    fn create_ticket(&self, request: &CreateTicketRequest) -> TicketingResult<CreateTicketResponse> {
+        let config = self.authenticated_config()?;
+        if config.project_key.is_empty() {
+            return Err(TicketingError::InvalidConfig("project_key is required".to_string()));
+        }
+
+        let mut fields = json!({
+            "project": { "key": config.project_key },
+            "issuetype": { "name": ISSUE_TYPE },
+            "summary": request.title,
+            // Screenshots are attached, not embedded, so their alt texts are listed
+            "description": description_to_adf(&(request.description.clone() + &request.alt_text_section())),
+        });
+
+        if let Some(priority) = request.priority.as_deref().and_then(priority_name) {
+            fields["priority"] = json!({ "name": priority });
+        }
+
+        // Jira labels cannot contain spaces
+        if !request.labels.is_empty() {
+            let labels: Vec<String> = request
+                .labels
+                .iter()
+                .map(|l| l.split_whitespace().collect::<Vec<_>>().join("-"))
+                .filter(|l| !l.is_empty())
+                .collect();
+            fields["labels"] = json!(labels);
+        }
+
+        if let Some(assignee_id) = &request.assignee_id {
+            fields["assignee"] = json!({ "accountId": assignee_id });
+        }
+
+        let client = reqwest::blocking::Client::new();
+        let response = client
+            .post(config.api_url("issue"))
+            .basic_auth(&config.email, Some(&config.api_token))
+            .header("Accept", "application/json")
+            .json(&json!({ "fields": fields }))
+            .send()
+            .map_err(TicketingError::from_send)?;
+
+        if !response.status().is_success() {
+            return Err(TicketingError::from_status(response));
+        }
+
+        let issue: serde_json::Value = response
+            .json()
+            .map_err(TicketingError::from_parse)?;
+
+        let id = issue
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| TicketingError::CreationFailed("Missing issue ID".to_string()))?
+            .to_string();
+
+        let identifier = issue
+            .get("key")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| TicketingError::CreationFailed("Missing issue key".to_string()))?
+            .to_string();
+
+        // Attachments need the issue, so they are uploaded after it exists;
+        // failures are reported per file without failing the ticket
+        let attachment_results = request
+            .upload_paths()
+            .into_iter()
+            .map(|path| match Self::upload_attachment(&config, &identifier, &path) {
+                Ok(url) => AttachmentUploadResult {
+                    file_path: path,
+                    success: true,
+                    message: url,
+                },
+                Err(e) => AttachmentUploadResult {
+                    file_path: path,
+                    success: false,
+                    message: e.to_string(),
+                },
+            })
+            .collect();
+
+        Ok(CreateTicketResponse {
+            id,
+            url: format!("{}/browse/{}", config.site_url, identifier),
+            identifier,
+            attachment_results,
+        })
+    }
+
+    fn check_connection(&self) -> TicketingResult<ConnectionStatus> {
+        let Ok(config) = self.authenticated_config() else {
+            return Ok(ConnectionStatus {
+                connected: false,
+                message: Some("Not authenticated".to_string()),
+                integration_name: "Jira".to_string(),
+            });
+        };
+
+        match Self::get_myself(&config) {
+            Ok(_) => Ok(ConnectionStatus {
+                connected: true,
+                message: None,
+                integration_name: "Jira".to_string(),
+            }),
+            Err(e) => Ok(ConnectionStatus {
+                connected: false,
+                message: Some(e.to_string()),
+                integration_name: "Jira".to_string(),
+            }),
+        }
+    }
+
+    fn fetch_ticket_status(&self, ticket_id: &str) -> TicketingResult<TicketStatus> {
+        let config = self.authenticated_config()?;
+
+        let client = reqwest::blocking::Client::new();
+        let response = client
+            .get(config.api_url(&format!("issue/{}?fields=status", ticket_id)))
+            .basic_auth(&config.email, Some(&config.api_token))
+            .header("Accept", "application/json")
+            .send()
+            .map_err(TicketingError::from_send)?;
+
+        if !response.status().is_success() {
+            return Err(TicketingError::from_status(response));
+        }
+
+        let issue: serde_json::Value = response
+            .json()
+            .map_err(TicketingError::from_parse)?;
+        let status = issue
+            .pointer("/fields/status")
+            .ok_or_else(|| TicketingError::InvalidResponse("Issue response has no status".to_string()))?;
+        let name = status.get("name").and_then(|v| v.as_str()).unwrap_or("Unknown");
+        let category = status
+            .pointer("/statusCategory/key")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+
+        Ok(TicketStatus {
+            name: name.to_string(),
+            category: TicketStatusCategory::from_jira_category_key(category),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "Jira"
+    }
+}
@@ -37,6 +37,37 @@ impl LinearIntegration {
         *self.credentials.write().unwrap() = Some(credentials);
     }
 
+    /// Labels usable on `team_id`'s issues (its own and workspace-wide), as
+    /// (id, name) pairs
+    fn fetch_labels(&self, team_id: &str) -> TicketingResult<Vec<(String, String)>> {
+        let query = r#"
+            query IssueLabels($teamId: ID!) {
+                issueLabels(first: 250, filter: { or: [{ team: { id: { eq: $teamId } } }, { team: { null: true } }] }) {
+                    nodes {
+                        id
+                        name
+                    }
+                }
+            }
+        "#;
+
+        let response = self.send_graphql_query(query, json!({ "teamId": team_id }))?;
+
+        let nodes = response
+            .pointer("/data/issueLabels/nodes")
+            .and_then(|n| n.as_array())
+            .ok_or_else(|| TicketingError::InvalidResponse("Failed to parse labels response".to_string()))?;
+
+        Ok(nodes
+            .iter()
+            .filter_map(|node| {
+                let id = node.get("id")?.as_str()?.to_string();
+                let name = node.get("name")?.as_str()?.to_string();
+                Some((id, name))
+            })
+            .collect())
+    }
+
     /// Send a GraphQL query to Linear API
     fn send_graphql_query(
         &self,
@@ -58,19 +89,15 @@ impl LinearIntegration {
                 "variables": variables
             }))
             .send()
-            .map_err(|e| TicketingError::NetworkError(e.to_string()))?;
+            .map_err(TicketingError::from_send)?;
 
         if !response.status().is_success() {
-            return Err(TicketingError::NetworkError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().unwrap_or_default()
-            )));
+            return Err(TicketingError::from_status(response));
         }
 
         let json_response: serde_json::Value = response
             .json()
-            .map_err(|e| TicketingError::NetworkError(format!("Failed to parse response: {}", e)))?;
+            .map_err(TicketingError::from_parse)?;
 
         // Check for GraphQL errors
         if let Some(errors) = json_response.get("errors") {
@@ -104,16 +131,7 @@ impl LinearIntegration {
         })?;
 
         // Determine MIME type from extension
-        let content_type = match path.extension().and_then(|e| e.to_str()) {
-            Some("png") => "image/png",
-            Some("jpg") | Some("jpeg") => "image/jpeg",
-            Some("gif") => "image/gif",
-            Some("webp") => "image/webp",
-            Some("mp4") => "video/mp4",
-            Some("mov") => "video/quicktime",
-            Some("webm") => "video/webm",
-            _ => "application/octet-stream",
-        };
+        let content_type = attachment_content_type(path);
 
         let file_name = path
             .file_name()
@@ -159,22 +177,16 @@ impl LinearIntegration {
                 "variables": upload_variables
             }))
             .send()
-            .map_err(|e| TicketingError::NetworkError(format!("fileUpload mutation failed: {}", e)))?;
+            .map_err(TicketingError::from_send)?;
 
         if !graphql_response.status().is_success() {
-            return Err(TicketingError::NetworkError(format!(
-                "fileUpload mutation HTTP {}: {}",
-                graphql_response.status(),
-                graphql_response.text().unwrap_or_default()
-            )));
+            return Err(TicketingError::from_status(graphql_response));
         }
 
-        let graphql_json: serde_json::Value = graphql_response.json().map_err(|e| {
-            TicketingError::NetworkError(format!("Failed to parse fileUpload response: {}", e))
-        })?;
+        let graphql_json: serde_json::Value = graphql_response.json().map_err(TicketingError::from_parse)?;
 
         if let Some(errors) = graphql_json.get("errors") {
-            return Err(TicketingError::NetworkError(format!(
+            return Err(TicketingError::InvalidResponse(format!(
                 "fileUpload GraphQL errors: {}",
                 errors
             )));
@@ -185,23 +197,21 @@ impl LinearIntegration {
             .and_then(|d| d.get("fileUpload"))
             .and_then(|fu| fu.get("uploadFile"))
             .ok_or_else(|| {
-                TicketingError::NetworkError(
-                    "fileUpload mutation returned no uploadFile".to_string(),
-                )
+                TicketingError::InvalidResponse("fileUpload mutation returned no uploadFile".to_string())
             })?;
 
         let upload_url = upload_file
             .get("uploadUrl")
             .and_then(|v| v.as_str())
             .ok_or_else(|| {
-                TicketingError::NetworkError("fileUpload returned no uploadUrl".to_string())
+                TicketingError::InvalidResponse("fileUpload returned no uploadUrl".to_string())
             })?;
 
         let asset_url = upload_file
             .get("assetUrl")
             .and_then(|v| v.as_str())
             .ok_or_else(|| {
-                TicketingError::NetworkError("fileUpload returned no assetUrl".to_string())
+                TicketingError::InvalidResponse("fileUpload returned no assetUrl".to_string())
             })?
             .to_string();
 
@@ -225,14 +235,10 @@ impl LinearIntegration {
         let put_response = put_request
             .body(file_bytes)
             .send()
-            .map_err(|e| TicketingError::NetworkError(format!("S3 PUT upload failed: {}", e)))?;
+            .map_err(TicketingError::from_send)?;
 
         if !put_response.status().is_success() {
-            return Err(TicketingError::NetworkError(format!(
-                "S3 PUT upload HTTP {}: {}",
-                put_response.status(),
-                put_response.text().unwrap_or_default()
-            )));
+            return Err(TicketingError::from_status(put_response));
         }
 
         // Step 3: Return the permanent asset URL for embedding in the description
@@ -246,6 +252,34 @@ impl Default for LinearIntegration {
     }
 }
 
+/// Whether `label` is a Linear label ID rather than a label name
+fn is_label_id(label: &str) -> bool {
+    uuid::Uuid::parse_str(label).is_ok()
+}
+
+/// Linear label IDs for `labels`. IDs are kept as they are; names are matched
+/// case-insensitively against `known` (id, name) pairs and dropped with a
+/// warning when the team has no such label.
+pub(crate) fn resolve_label_ids(labels: &[String], known: &[(String, String)]) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for label in labels {
+        let id = if is_label_id(label) {
+            Some(label.clone())
+        } else {
+            known
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(label))
+                .map(|(id, _)| id.clone())
+        };
+        match id {
+            Some(id) if !ids.contains(&id) => ids.push(id),
+            Some(_) => {}
+            None => eprintln!("Warning: Linear has no label named '{}'; leaving it off the ticket", label),
+        }
+    }
+    ids
+}
+
 impl TicketingIntegration for LinearIntegration {
     fn authenticate(&self, credentials: &TicketingCredentials) -> TicketingResult<()> {
         // Test authentication by querying viewer info
@@ -309,8 +343,8 @@ impl TicketingIntegration for LinearIntegration {
 
         // Upload attachments and collect asset URLs; log failures but continue
         let mut attachment_results: Vec<AttachmentUploadResult> = Vec::new();
-        let mut asset_urls: Vec<String> = Vec::new();
-        for attachment_path in &request.attachments {
+        let mut asset_urls: Vec<(String, String)> = Vec::new();
+        for attachment_path in &request.upload_paths() {
             match self.upload_attachment(attachment_path) {
                 Ok(url) if !url.is_empty() => {
                     attachment_results.push(AttachmentUploadResult {
@@ -318,7 +352,7 @@ impl TicketingIntegration for LinearIntegration {
                         success: true,
                         message: url.clone(),
                     });
-                    asset_urls.push(url);
+                    asset_urls.push((attachment_path.clone(), url));
                 }
                 Ok(_) => {
                     attachment_results.push(AttachmentUploadResult {
@@ -341,8 +375,15 @@ impl TicketingIntegration for LinearIntegration {
         let mut full_description = request.description.clone();
         if !asset_urls.is_empty() {
             full_description.push_str("\n\n## Screenshots\n\n");
-            for (i, url) in asset_urls.iter().enumerate() {
-                full_description.push_str(&format!("![Screenshot {}]({})\n\n", i + 1, url));
+            let annotated: Vec<&str> = request.captures.iter().filter_map(|c| c.annotated_path.as_deref()).collect();
+            for (i, (path, url)) in asset_urls.iter().enumerate() {
+                let label = if annotated.contains(&path.as_str()) { " (annotated)" } else { "" };
+                // Markdown alt text can't hold brackets or line breaks
+                let alt = match request.alt_text_for(path) {
+                    Some(alt) => alt.replace(['[', ']'], "").split_whitespace().collect::<Vec<_>>().join(" "),
+                    None => format!("Screenshot {}{}", i + 1, label),
+                };
+                full_description.push_str(&format!("![{}]({})\n\n", alt, url));
             }
         }
         let upload_failures: Vec<&str> = attachment_results
@@ -384,9 +425,21 @@ impl TicketingIntegration for LinearIntegration {
             variables["input"]["priority"] = json!(priority.parse::<i32>().unwrap_or(0));
         }
 
-        // Add labels if specified
+        // Add labels if specified; names (such as the item-type labels) are
+        // looked up since Linear only takes label IDs
         if !request.labels.is_empty() {
-            variables["input"]["labelIds"] = json!(request.labels);
+            let known = if request.labels.iter().all(|label| is_label_id(label)) {
+                Vec::new()
+            } else {
+                self.fetch_labels(team_id).unwrap_or_else(|e| {
+                    eprintln!("Warning: Could not look up Linear labels: {}", e);
+                    Vec::new()
+                })
+            };
+            let label_ids = resolve_label_ids(&request.labels, &known);
+            if !label_ids.is_empty() {
+                variables["input"]["labelIds"] = json!(label_ids);
+            }
         }
 
         // Add assignee if specified (from profile defaults)
@@ -492,7 +545,7 @@ impl TicketingIntegration for LinearIntegration {
             .and_then(|t| t.get("nodes"))
             .and_then(|n| n.as_array())
             .ok_or_else(|| {
-                TicketingError::NetworkError("Failed to parse teams response".to_string())
+                TicketingError::InvalidResponse("Failed to parse teams response".to_string())
             })?;
 
         let teams: Vec<LinearTeam> = nodes
@@ -530,7 +583,7 @@ impl TicketingIntegration for LinearIntegration {
             .and_then(|t| t.get("nodes"))
             .and_then(|n| n.as_array())
             .ok_or_else(|| {
-                TicketingError::NetworkError("Failed to parse templates response".to_string())
+                TicketingError::InvalidResponse("Failed to parse templates response".to_string())
             })?;
 
         let templates: Vec<LinearTemplate> = nodes
@@ -557,6 +610,36 @@ impl TicketingIntegration for LinearIntegration {
         Ok(templates)
     }
 
+    fn fetch_ticket_status(&self, ticket_id: &str) -> TicketingResult<TicketStatus> {
+        let query = r#"
+            query IssueState($id: String!) {
+                issue(id: $id) {
+                    state {
+                        name
+                        type
+                    }
+                }
+            }
+        "#;
+
+        let response = self.send_graphql_query(query, json!({ "id": ticket_id }))?;
+
+        let state = response
+            .get("data")
+            .and_then(|d| d.get("issue"))
+            .and_then(|i| i.get("state"))
+            .ok_or_else(|| {
+                TicketingError::InvalidResponse("Failed to parse issue state response".to_string())
+            })?;
+        let name = state.get("name").and_then(|v| v.as_str()).unwrap_or("Unknown");
+        let state_type = state.get("type").and_then(|v| v.as_str()).unwrap_or("");
+
+        Ok(TicketStatus {
+            name: name.to_string(),
+            category: TicketStatusCategory::from_linear_state_type(state_type),
+        })
+    }
+
     fn name(&self) -> &str {
         "Linear"
     }
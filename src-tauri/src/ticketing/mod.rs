@@ -1,14 +1,26 @@
 /// Ticketing integration module for creating issues in external systems
 ///
 /// Supports pluggable integrations via the TicketingIntegration trait.
-/// Currently implements Linear, with planned support for Jira and GitHub.
+/// Implements Linear and Jira Cloud, selected by the `ticketing.provider`
+/// setting, with planned support for GitHub.
 mod types;
 mod trait_def;
 mod linear;
+mod jira;
+mod provider;
+mod queue;
 
 pub use types::*;
 pub use trait_def::TicketingIntegration;
+#[allow(unused_imports)]
 pub use linear::LinearIntegration;
+#[allow(unused_imports)]
+pub use jira::{
+    JiraConfig, JiraIntegration, JIRA_API_TOKEN_KEY, JIRA_EMAIL_KEY, JIRA_PROJECT_KEY_KEY,
+    JIRA_SITE_URL_KEY,
+};
+pub use provider::{build_integration, TicketingProvider, PROVIDER_KEY};
+pub use queue::{enqueue, is_retryable, queued_event, TicketQueueWorker};
 
 #[cfg(test)]
 mod tests;
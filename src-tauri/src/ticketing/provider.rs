@@ -0,0 +1,64 @@
+use super::jira::{JiraConfig, JiraIntegration};
+use super::linear::LinearIntegration;
+use super::trait_def::TicketingIntegration;
+use crate::database::{SettingsOps, SettingsRepository};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Settings key: which ticketing service new tickets go to
+pub const PROVIDER_KEY: &str = "ticketing.provider";
+
+/// Supported ticketing services
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TicketingProvider {
+    #[default]
+    Linear,
+    Jira,
+}
+
+impl TicketingProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::Jira => "jira",
+        }
+    }
+
+    /// The stored provider; unset or unknown values mean Linear.
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(PROVIDER_KEY)
+            .ok()
+            .flatten()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        SettingsRepository::new(conn)
+            .set(PROVIDER_KEY, self.as_str())
+            .map_err(|e| format!("Failed to save ticketing provider: {}", e))
+    }
+}
+
+impl std::str::FromStr for TicketingProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "linear" => Ok(Self::Linear),
+            "jira" => Ok(Self::Jira),
+            other => Err(format!("Unknown ticketing provider: {}", other)),
+        }
+    }
+}
+
+/// A fresh, unauthenticated integration for the configured provider
+pub fn build_integration(conn: &Connection) -> Arc<dyn TicketingIntegration> {
+    match TicketingProvider::load(conn) {
+        TicketingProvider::Linear => Arc::new(LinearIntegration::new()),
+        TicketingProvider::Jira => Arc::new(JiraIntegration::new(JiraConfig::load(conn))),
+    }
+}
@@ -0,0 +1,347 @@
+//! Persistent retry queue for tickets that could not be created.
+//!
+//! When `create_ticket` fails because the service is unreachable, the request
+//! is stored in the `ticket_queue` table and [`TicketQueueWorker`] retries it
+//! in the background with exponential backoff ([`backoff`]). Progress is
+//! reported through the `ticketing:queued`, `ticketing:sent` and
+//! `ticketing:failed` events. An entry is given up after [`MAX_ATTEMPTS`] or
+//! on an error that retrying cannot fix (bad configuration, rejected ticket).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use tauri::AppHandle;
+
+use super::types::{CreateTicketRequest, CreateTicketResponse, TicketingError, TicketingResult};
+use crate::database::{QueuedTicket, QueuedTicketStatus, TicketQueueOps, TicketQueueRepository};
+use crate::events::{self, TicketingFailed, TicketingQueued, TicketingSent};
+
+/// Attempts (including the original one) before an entry is marked failed.
+pub const MAX_ATTEMPTS: i64 = 8;
+
+/// Delay before the first retry; doubled for every further attempt.
+const BASE_DELAY: Duration = Duration::from_secs(30);
+
+/// Longest delay between two attempts.
+const MAX_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// How often the worker looks for due entries.
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How often the worker thread checks whether it should stop.
+const STOP_POLL: Duration = Duration::from_millis(250);
+
+/// Whether a failed `create_ticket` call should be queued for retry. Only
+/// failures to reach the service qualify: after an HTTP error status or an
+/// unreadable response the ticket may already exist, so resending it could
+/// create a duplicate.
+pub fn is_retryable(error: &TicketingError) -> bool {
+    matches!(error, TicketingError::NetworkError(_) | TicketingError::ConnectionFailed(_))
+}
+
+/// Whether the worker keeps retrying after `error`. Unlike [`is_retryable`]
+/// this includes authentication failures: after a restart the integration is
+/// unauthenticated until the frontend signs in again.
+fn keeps_retrying(error: &TicketingError) -> bool {
+    is_retryable(error) || matches!(error, TicketingError::AuthenticationFailed(_))
+}
+
+/// Delay before the next attempt once `attempts` attempts have failed.
+pub fn backoff(attempts: i64) -> Duration {
+    let doublings = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    BASE_DELAY.saturating_mul(1 << doublings).min(MAX_DELAY)
+}
+
+fn next_attempt_at(now: DateTime<Utc>, attempts: i64) -> String {
+    let delay = chrono::Duration::from_std(backoff(attempts)).unwrap_or(chrono::Duration::zero());
+    (now + delay).to_rfc3339()
+}
+
+/// Store a request whose first attempt failed with `error`.
+pub fn enqueue(
+    conn: &Connection,
+    bug_id: Option<&str>,
+    request: &CreateTicketRequest,
+    error: &TicketingError,
+) -> Result<QueuedTicket, String> {
+    let now = Utc::now();
+    let entry = QueuedTicket {
+        id: uuid::Uuid::new_v4().to_string(),
+        bug_id: bug_id.map(str::to_string),
+        title: request.title.clone(),
+        request_json: serde_json::to_string(request).map_err(|e| e.to_string())?,
+        status: QueuedTicketStatus::Pending,
+        attempts: 1,
+        next_attempt_at: next_attempt_at(now, 1),
+        last_error: Some(error.to_string()),
+        ticket_identifier: None,
+        ticket_url: None,
+        created_at: now.to_rfc3339(),
+        updated_at: now.to_rfc3339(),
+    };
+    TicketQueueRepository::new(conn)
+        .create(&entry)
+        .map_err(|e| format!("Failed to queue ticket: {}", e))?;
+    Ok(entry)
+}
+
+/// Result of one retry of a queued entry. `entry` reflects the updated row.
+#[derive(Debug)]
+pub enum QueueOutcome {
+    Sent {
+        entry: QueuedTicket,
+        request: Box<CreateTicketRequest>,
+        response: CreateTicketResponse,
+    },
+    Retrying(QueuedTicket),
+    Failed(QueuedTicket),
+}
+
+/// Retry every entry due at `now` with `send`. The database lock is not held
+/// while `send` runs.
+pub fn process_due<S>(db: &Mutex<Connection>, now: DateTime<Utc>, send: S) -> Result<Vec<QueueOutcome>, String>
+where
+    S: Fn(&CreateTicketRequest) -> TicketingResult<CreateTicketResponse>,
+{
+    let due = {
+        let conn = db.lock().unwrap();
+        TicketQueueRepository::new(&conn)
+            .list_due(&now.to_rfc3339())
+            .map_err(|e| e.to_string())?
+    };
+
+    let mut outcomes = Vec::new();
+    for entry in due {
+        let result = serde_json::from_str::<CreateTicketRequest>(&entry.request_json)
+            .map_err(|e| TicketingError::InvalidConfig(format!("Unreadable queued request: {}", e)))
+            .and_then(|request| send(&request).map(|response| (request, response)));
+
+        let conn = db.lock().unwrap();
+        let repo = TicketQueueRepository::new(&conn);
+        let outcome = match result {
+            Ok((request, response)) => {
+                repo.mark_sent(&entry.id, &response.identifier, &response.url)
+                    .map_err(|e| e.to_string())?;
+                let entry = repo.get(&entry.id).map_err(|e| e.to_string())?.unwrap_or(entry);
+                QueueOutcome::Sent { entry, request: Box::new(request), response }
+            }
+            Err(error) if keeps_retrying(&error) && entry.attempts + 1 < MAX_ATTEMPTS => {
+                let attempts = entry.attempts + 1;
+                repo.record_retry(&entry.id, &error.to_string(), &next_attempt_at(now, attempts))
+                    .map_err(|e| e.to_string())?;
+                QueueOutcome::Retrying(repo.get(&entry.id).map_err(|e| e.to_string())?.unwrap_or(entry))
+            }
+            Err(error) => {
+                repo.mark_failed(&entry.id, &error.to_string()).map_err(|e| e.to_string())?;
+                QueueOutcome::Failed(repo.get(&entry.id).map_err(|e| e.to_string())?.unwrap_or(entry))
+            }
+        };
+        outcomes.push(outcome);
+    }
+    Ok(outcomes)
+}
+
+/// Event announcing that `entry` is waiting for its next attempt.
+pub fn queued_event(entry: &QueuedTicket) -> TicketingQueued {
+    TicketingQueued {
+        queue_id: entry.id.clone(),
+        bug_id: entry.bug_id.clone(),
+        title: entry.title.clone(),
+        attempts: entry.attempts,
+        next_attempt_at: entry.next_attempt_at.clone(),
+        error: entry.last_error.clone(),
+    }
+}
+
+/// Retries queued tickets on a background thread.
+///
+/// Dropping the struct stops the thread.
+pub struct TicketQueueWorker {
+    stop_flag: Arc<AtomicBool>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl TicketQueueWorker {
+    /// Poll the queue every [`POLL_INTERVAL`], sending due entries with
+    /// `send`. `on_sent` runs with the database lock held for every ticket
+    /// that went through, to record it on its bug.
+    pub fn start<S, F>(db: Arc<Mutex<Connection>>, app_handle: AppHandle, send: S, on_sent: F) -> Self
+    where
+        S: Fn(&CreateTicketRequest) -> TicketingResult<CreateTicketResponse> + Send + 'static,
+        F: Fn(&Connection, &QueuedTicket, &CreateTicketRequest, &CreateTicketResponse) + Send + 'static,
+    {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop_flag);
+
+        let worker = thread::spawn(move || {
+            while !flag.load(Ordering::Relaxed) {
+                let outcomes = process_due(&db, Utc::now(), &send).unwrap_or_else(|e| {
+                    eprintln!("Warning: ticket queue retry failed: {}", e);
+                    Vec::new()
+                });
+                for outcome in outcomes {
+                    match outcome {
+                        QueueOutcome::Sent { entry, request, response } => {
+                            on_sent(&db.lock().unwrap(), &entry, &request, &response);
+                            let _ = events::emit(
+                                &app_handle,
+                                &TicketingSent {
+                                    queue_id: entry.id,
+                                    bug_id: entry.bug_id,
+                                    identifier: response.identifier,
+                                    url: response.url,
+                                },
+                            );
+                        }
+                        QueueOutcome::Retrying(entry) => {
+                            let _ = events::emit(&app_handle, &queued_event(&entry));
+                        }
+                        QueueOutcome::Failed(entry) => {
+                            let _ = events::emit(
+                                &app_handle,
+                                &TicketingFailed {
+                                    queue_id: entry.id,
+                                    bug_id: entry.bug_id,
+                                    title: entry.title,
+                                    attempts: entry.attempts,
+                                    error: entry.last_error.unwrap_or_default(),
+                                },
+                            );
+                        }
+                    }
+                }
+
+                let mut waited = Duration::ZERO;
+                while waited < POLL_INTERVAL && !flag.load(Ordering::Relaxed) {
+                    thread::sleep(STOP_POLL);
+                    waited += STOP_POLL;
+                }
+            }
+        });
+
+        Self { stop_flag, worker: Some(worker) }
+    }
+}
+
+impl Drop for TicketQueueWorker {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::init_database;
+
+    fn request() -> CreateTicketRequest {
+        CreateTicketRequest {
+            title: "Crash on save".to_string(),
+            description: "Steps".to_string(),
+            attachments: vec![],
+            priority: None,
+            labels: vec![],
+            assignee_id: None,
+            state_id: None,
+            template_id: None,
+            captures: vec![],
+        }
+    }
+
+    fn db_with_entry(attempts: i64) -> (Mutex<Connection>, QueuedTicket) {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let entry = enqueue(&conn, None, &request(), &TicketingError::NetworkError("offline".into())).unwrap();
+        conn.execute("UPDATE ticket_queue SET attempts = ?1", [attempts]).unwrap();
+        (Mutex::new(conn), entry)
+    }
+
+    fn later() -> DateTime<Utc> {
+        Utc::now() + chrono::Duration::hours(2)
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        assert_eq!(backoff(1), Duration::from_secs(30));
+        assert_eq!(backoff(2), Duration::from_secs(60));
+        assert_eq!(backoff(4), Duration::from_secs(240));
+        assert_eq!(backoff(20), MAX_DELAY);
+    }
+
+    #[test]
+    fn test_only_transport_errors_are_queued() {
+        assert!(is_retryable(&TicketingError::NetworkError("timeout".into())));
+        assert!(is_retryable(&TicketingError::ConnectionFailed("dns".into())));
+        assert!(!is_retryable(&TicketingError::InvalidConfig("team_id".into())));
+        assert!(!is_retryable(&TicketingError::CreationFailed("rejected".into())));
+        assert!(!is_retryable(&TicketingError::HttpStatus(400, "bad input".into())));
+        assert!(!is_retryable(&TicketingError::InvalidResponse("truncated body".into())));
+    }
+
+    #[test]
+    fn test_entry_is_not_due_before_its_backoff() {
+        let (db, _) = db_with_entry(1);
+        let outcomes = process_due(&db, Utc::now(), |_| panic!("sent too early")).unwrap();
+        assert!(outcomes.is_empty());
+    }
+
+    #[test]
+    fn test_successful_retry_marks_entry_sent() {
+        let (db, entry) = db_with_entry(1);
+        let outcomes = process_due(&db, later(), |req| {
+            assert_eq!(req.title, "Crash on save");
+            Ok(CreateTicketResponse {
+                id: "issue-1".into(),
+                url: "https://linear.app/acme/issue/ENG-7".into(),
+                identifier: "ENG-7".into(),
+                attachment_results: vec![],
+            })
+        })
+        .unwrap();
+
+        assert!(matches!(&outcomes[..], [QueueOutcome::Sent { entry: e, .. }] if e.id == entry.id));
+        let conn = db.lock().unwrap();
+        let stored = TicketQueueRepository::new(&conn).get(&entry.id).unwrap().unwrap();
+        assert_eq!(stored.status, QueuedTicketStatus::Sent);
+        assert_eq!(stored.ticket_identifier.as_deref(), Some("ENG-7"));
+    }
+
+    #[test]
+    fn test_network_errors_back_off_until_max_attempts() {
+        let (db, _) = db_with_entry(1);
+        let offline = |_: &CreateTicketRequest| Err(TicketingError::NetworkError("offline".into()));
+        match &process_due(&db, later(), offline).unwrap()[..] {
+            [QueueOutcome::Retrying(entry)] => assert_eq!(entry.attempts, 2),
+            other => panic!("unexpected outcome {:?}", other),
+        }
+
+        let (db, _) = db_with_entry(MAX_ATTEMPTS - 1);
+        match &process_due(&db, later(), offline).unwrap()[..] {
+            [QueueOutcome::Failed(entry)] => assert_eq!(entry.attempts, MAX_ATTEMPTS),
+            other => panic!("unexpected outcome {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rejected_ticket_fails_immediately() {
+        let (db, _) = db_with_entry(1);
+        let rejected = |_: &CreateTicketRequest| Err(TicketingError::CreationFailed("bad team".into()));
+        let outcomes = process_due(&db, later(), rejected).unwrap();
+        assert!(matches!(&outcomes[..], [QueueOutcome::Failed(e)] if e.last_error.as_deref() == Some("Ticket creation failed: bad team")));
+    }
+
+    #[test]
+    fn test_unreadable_response_is_not_resent() {
+        let (db, _) = db_with_entry(1);
+        let unreadable = |_: &CreateTicketRequest| Err(TicketingError::InvalidResponse("truncated body".into()));
+        let outcomes = process_due(&db, later(), unreadable).unwrap();
+        assert!(matches!(&outcomes[..], [QueueOutcome::Failed(e)] if e.attempts == 2));
+    }
+}
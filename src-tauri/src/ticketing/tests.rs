@@ -155,6 +155,7 @@ fn test_mock_integration_create_ticket_success() {
         assignee_id: None,
         state_id: None,
         template_id: None,
+        captures: vec![],
     };
 
     let result = integration.create_ticket(&request);
@@ -179,6 +180,7 @@ fn test_mock_integration_create_ticket_not_authenticated() {
         assignee_id: None,
         state_id: None,
         template_id: None,
+        captures: vec![],
     };
 
     let result = integration.create_ticket(&request);
@@ -214,6 +216,7 @@ fn test_mock_integration_create_ticket_with_attachments() {
         assignee_id: None,
         state_id: None,
         template_id: None,
+        captures: vec![],
     };
 
     let result = integration.create_ticket(&request);
@@ -265,6 +268,12 @@ fn test_ticketing_error_display() {
     let err = TicketingError::NetworkError("Timeout".to_string());
     assert_eq!(err.to_string(), "Network error: Timeout");
 
+    let err = TicketingError::HttpStatus(422, "Invalid team".to_string());
+    assert_eq!(err.to_string(), "HTTP 422: Invalid team");
+
+    let err = TicketingError::InvalidResponse("missing data".to_string());
+    assert_eq!(err.to_string(), "Invalid response: missing data");
+
     let err = TicketingError::InvalidConfig("Missing team ID".to_string());
     assert_eq!(err.to_string(), "Invalid configuration: Missing team ID");
 
@@ -314,6 +323,7 @@ fn test_batch_push_multiple_bugs() {
             assignee_id: None,
             state_id: None,
             template_id: None,
+            captures: vec![],
         },
         CreateTicketRequest {
             title: "Bug 2: Performance Issue".to_string(),
@@ -324,6 +334,7 @@ fn test_batch_push_multiple_bugs() {
             assignee_id: None,
             state_id: None,
             template_id: None,
+            captures: vec![],
         },
         CreateTicketRequest {
             title: "Feature Request".to_string(),
@@ -334,6 +345,7 @@ fn test_batch_push_multiple_bugs() {
             assignee_id: None,
             state_id: None,
             template_id: None,
+            captures: vec![],
         },
     ];
 
@@ -379,6 +391,7 @@ fn test_batch_push_with_failures() {
         assignee_id: None,
         state_id: None,
         template_id: None,
+        captures: vec![],
     };
     let result1 = integration.create_ticket(&bug1);
     assert!(result1.is_ok());
@@ -395,6 +408,7 @@ fn test_batch_push_with_failures() {
         assignee_id: None,
         state_id: None,
         template_id: None,
+        captures: vec![],
     };
     let result2 = integration.create_ticket(&bug2);
     assert!(result2.is_err());
@@ -418,6 +432,7 @@ fn test_authentication_error_handling() {
         assignee_id: None,
         state_id: None,
         template_id: None,
+        captures: vec![],
     };
 
     let result = integration.create_ticket(&request);
@@ -533,6 +548,7 @@ fn test_mock_integration_create_ticket_returns_attachment_results() {
         assignee_id: None,
         state_id: None,
         template_id: None,
+        captures: vec![],
     };
 
     let result = integration.create_ticket(&request).unwrap();
@@ -565,6 +581,7 @@ fn test_mock_integration_create_ticket_no_attachments_returns_empty_results() {
         assignee_id: None,
         state_id: None,
         template_id: None,
+        captures: vec![],
     };
 
     let result = integration.create_ticket(&request).unwrap();
@@ -595,6 +612,7 @@ fn test_linear_upload_attachment_fails_for_missing_file() {
         assignee_id: None,
         state_id: None,
         template_id: None,
+        captures: vec![],
     };
 
     // create_ticket should fail because the missing file triggers a NetworkError
@@ -766,6 +784,7 @@ fn test_create_ticket_request_includes_template_id() {
         assignee_id: None,
         state_id: None,
         template_id: Some("tpl-uuid-123".to_string()),
+        captures: vec![],
     };
     assert_eq!(request.template_id, Some("tpl-uuid-123".to_string()));
 
@@ -779,6 +798,245 @@ fn test_create_ticket_request_includes_template_id() {
         assignee_id: None,
         state_id: None,
         template_id: None,
+        captures: vec![],
     };
     assert!(request_no_template.template_id.is_none());
 }
+
+// Jira Cloud integration tests
+
+fn jira_test_config() -> JiraConfig {
+    JiraConfig {
+        site_url: "https://127.0.0.1:1".to_string(), // unreachable
+        email: "qa@example.com".to_string(),
+        api_token: "jira-token".to_string(),
+        project_key: "QA".to_string(),
+    }
+}
+
+#[test]
+fn test_upload_paths_include_captures_and_annotated_versions() {
+    let request = CreateTicketRequest {
+        title: "Bug".to_string(),
+        description: "Description".to_string(),
+        attachments: vec!["/qa/bug_001/console.txt".to_string(), "/qa/bug_001/capture-001.png".to_string()],
+        priority: None,
+        labels: vec![],
+        assignee_id: None,
+        state_id: None,
+        template_id: None,
+        captures: vec![
+            CaptureAttachment {
+                capture_id: "c-1".to_string(),
+                file_path: "/qa/bug_001/capture-001.png".to_string(),
+                annotated_path: Some("/qa/bug_001/capture-001_annotated.png".to_string()),
+                alt_text: Some("Checkout page with the Pay button greyed out".to_string()),
+            },
+            CaptureAttachment {
+                capture_id: "c-2".to_string(),
+                file_path: "/qa/bug_001/capture 002.png".to_string(),
+                annotated_path: None,
+                alt_text: None,
+            },
+        ],
+    };
+
+    assert_eq!(
+        request.upload_paths(),
+        vec![
+            "/qa/bug_001/console.txt",
+            "/qa/bug_001/capture-001.png",
+            "/qa/bug_001/capture-001_annotated.png",
+            "/qa/bug_001/capture 002.png",
+        ]
+    );
+
+    let links = request.attachment_links();
+    assert!(links.contains("## Attachments"));
+    assert!(links.contains("- [capture-001_annotated.png](file:///qa/bug_001/capture-001_annotated.png)"));
+    assert!(links.contains("(file:///qa/bug_001/capture%20002.png)\n"));
+    assert!(links.contains(
+        "- [capture-001_annotated.png](file:///qa/bug_001/capture-001_annotated.png): Checkout page with the Pay button greyed out\n"
+    ));
+
+    assert_eq!(request.alt_text_for("/qa/bug_001/capture 002.png"), None);
+    assert_eq!(
+        request.alt_text_section(),
+        "\n\n## Screenshot Descriptions\n\n- capture-001.png: Checkout page with the Pay button greyed out\n"
+    );
+}
+
+#[test]
+fn test_attachment_links_empty_without_files() {
+    let request: CreateTicketRequest = serde_json::from_value(serde_json::json!({
+        "title": "Bug",
+        "description": "Description",
+        "attachments": [],
+        "priority": null,
+        "labels": [],
+        "assignee_id": null,
+        "state_id": null,
+        "template_id": null
+    }))
+    .unwrap();
+    assert!(request.captures.is_empty());
+    assert_eq!(request.attachment_links(), "");
+}
+
+#[test]
+fn test_jira_check_connection_not_authenticated() {
+    let integration = JiraIntegration::new(jira_test_config());
+    assert_eq!(integration.name(), "Jira");
+
+    let status = integration.check_connection().unwrap();
+    assert!(!status.connected);
+    assert_eq!(status.integration_name, "Jira");
+    assert_eq!(status.message.as_deref(), Some("Not authenticated"));
+}
+
+#[test]
+fn test_jira_authenticate_uses_only_stored_config() {
+    // The shared credentials are Linear's and never fill in the Jira config
+    let integration = JiraIntegration::new(JiraConfig::default());
+    let credentials = TicketingCredentials {
+        api_key: "lin_api_123".to_string(),
+        workspace_id: Some("https://acme.atlassian.net".to_string()),
+        team_id: Some("team-1".to_string()),
+    };
+    assert!(matches!(
+        integration.authenticate(&credentials),
+        Err(TicketingError::InvalidConfig(_))
+    ));
+
+    // With the stored config complete, the read-only /myself check is attempted
+    let integration = JiraIntegration::new(jira_test_config());
+    assert!(matches!(
+        integration.authenticate(&credentials),
+        Err(TicketingError::AuthenticationFailed(_))
+    ));
+    assert!(!integration.check_connection().unwrap().connected);
+}
+
+#[test]
+fn test_jira_create_ticket_requires_authentication() {
+    let integration = JiraIntegration::new(jira_test_config());
+    let request = CreateTicketRequest {
+        title: "Bug".to_string(),
+        description: "Description".to_string(),
+        attachments: vec![],
+        priority: None,
+        labels: vec![],
+        assignee_id: None,
+        state_id: None,
+        template_id: None,
+        captures: vec![],
+    };
+
+    assert!(matches!(
+        integration.create_ticket(&request),
+        Err(TicketingError::AuthenticationFailed(_))
+    ));
+
+    integration.set_credentials_for_test(jira_test_config());
+    assert!(matches!(
+        integration.create_ticket(&request),
+        Err(TicketingError::NetworkError(_))
+    ));
+}
+
+#[test]
+fn test_jira_description_to_adf() {
+    let doc = jira::description_to_adf(
+        "## Steps\n\n1. Open cart\n2. Click checkout\n\n```js\nTypeError: cart is undefined\n```\nDone",
+    );
+
+    assert_eq!(doc["type"], "doc");
+    assert_eq!(doc["version"], 1);
+    let blocks = doc["content"].as_array().unwrap();
+    assert_eq!(blocks.len(), 4);
+    assert_eq!(blocks[0]["type"], "heading");
+    assert_eq!(blocks[0]["attrs"]["level"], 2);
+    assert_eq!(blocks[0]["content"][0]["text"], "Steps");
+    assert_eq!(blocks[1]["type"], "paragraph");
+    assert_eq!(blocks[1]["content"][0]["text"], "1. Open cart");
+    assert_eq!(blocks[1]["content"][1]["type"], "hardBreak");
+    assert_eq!(blocks[2]["type"], "codeBlock");
+    assert_eq!(blocks[2]["attrs"]["language"], "js");
+    assert_eq!(blocks[2]["content"][0]["text"], "TypeError: cart is undefined");
+    assert_eq!(blocks[3]["content"][0]["text"], "Done");
+
+    // Empty descriptions give an empty document
+    assert_eq!(jira::description_to_adf("")["content"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_jira_config_validation() {
+    assert!(jira_test_config().validate().is_ok());
+
+    let mut config = jira_test_config();
+    config.site_url = "acme.atlassian.net".to_string();
+    assert!(config.validate().is_err());
+    config.site_url = "http://acme.atlassian.net".to_string();
+    assert!(config.validate().is_err());
+
+    let mut config = jira_test_config();
+    config.project_key = "  ".to_string();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_ticketing_provider_selection() {
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    crate::database::init_database(&conn).unwrap();
+    assert_eq!(TicketingProvider::load(&conn), TicketingProvider::Linear);
+    assert_eq!(build_integration(&conn).name(), "Linear");
+
+    TicketingProvider::Jira.save(&conn).unwrap();
+    jira_test_config().save(&conn).unwrap();
+    assert_eq!(TicketingProvider::load(&conn), TicketingProvider::Jira);
+    assert_eq!(JiraConfig::load(&conn), jira_test_config());
+    assert_eq!(build_integration(&conn).name(), "Jira");
+
+    assert_eq!("JIRA".parse::<TicketingProvider>().unwrap(), TicketingProvider::Jira);
+    assert!("github".parse::<TicketingProvider>().is_err());
+}
+
+#[test]
+fn test_mock_integration_fetch_ticket_status_default_unsupported() {
+    let integration = MockTicketingIntegration::new();
+    match integration.fetch_ticket_status("issue-1") {
+        Err(TicketingError::InvalidConfig(msg)) => assert!(msg.contains("status sync")),
+        other => panic!("Expected InvalidConfig, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_linear_fetch_ticket_status_requires_authentication() {
+    let integration = LinearIntegration::new();
+    match integration.fetch_ticket_status("issue-1") {
+        Err(TicketingError::AuthenticationFailed(_)) => {}
+        other => panic!("Expected AuthenticationFailed, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_ticket_status_categories() {
+    assert_eq!(TicketStatusCategory::from_linear_state_type("triage"), TicketStatusCategory::Open);
+    assert_eq!(TicketStatusCategory::from_linear_state_type("started"), TicketStatusCategory::InProgress);
+    assert_eq!(TicketStatusCategory::from_linear_state_type("completed"), TicketStatusCategory::Done);
+    assert_eq!(TicketStatusCategory::from_linear_state_type("canceled"), TicketStatusCategory::Canceled);
+    assert_eq!(TicketStatusCategory::from_jira_category_key("new"), TicketStatusCategory::Open);
+    assert_eq!(TicketStatusCategory::from_jira_category_key("indeterminate"), TicketStatusCategory::InProgress);
+    assert_eq!(TicketStatusCategory::from_jira_category_key("done"), TicketStatusCategory::Done);
+    assert_eq!(TicketStatusCategory::InProgress.as_str(), "in_progress");
+}
+
+#[test]
+fn test_linear_label_names_resolve_to_ids() {
+    let bug_label = "6f1c2a4e-8f7b-4c1d-9a3e-2b5d7c9e1f00".to_string();
+    let profile_label = "0a9b8c7d-6e5f-4a3b-8c1d-0e9f8a7b6c5d".to_string();
+    let known = vec![(bug_label.clone(), "Bug".to_string())];
+
+    let labels = vec![profile_label.clone(), "bug".to_string(), "feedback".to_string(), bug_label.clone()];
+    assert_eq!(linear::resolve_label_ids(&labels, &known), vec![profile_label, bug_label]);
+}
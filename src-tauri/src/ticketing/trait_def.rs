@@ -50,6 +50,27 @@ pub trait TicketingIntegration: Send + Sync {
         Ok(vec![])
     }
 
+    /// Fetch the current status of a ticket created earlier
+    ///
+    /// # Arguments
+    /// * `ticket_id` - ID returned in `CreateTicketResponse::id`
+    ///
+    /// Default implementation reports that status sync is not supported.
+    fn fetch_ticket_status(&self, _ticket_id: &str) -> TicketingResult<TicketStatus> {
+        Err(TicketingError::InvalidConfig(format!(
+            "{} does not support status sync",
+            self.name()
+        )))
+    }
+
+    /// Whether the service accepts file uploads
+    ///
+    /// When false, `ticketing_create_ticket` links the files in the
+    /// description instead of passing them for upload.
+    fn supports_binary_attachments(&self) -> bool {
+        true
+    }
+
     /// Get the name of this integration (e.g., "Linear", "Jira")
     #[allow(dead_code)]
     fn name(&self) -> &str;
@@ -8,8 +8,13 @@ pub type TicketingResult<T> = Result<T, TicketingError>;
 pub enum TicketingError {
     /// Authentication failed
     AuthenticationFailed(String),
-    /// Network or API error
+    /// The service could not be reached (connection failure or timeout)
     NetworkError(String),
+    /// The service answered with a non-success HTTP status
+    HttpStatus(u16, String),
+    /// The request may have reached the service but its response could not be
+    /// read or understood
+    InvalidResponse(String),
     /// Invalid configuration
     InvalidConfig(String),
     /// Ticket creation failed
@@ -23,6 +28,8 @@ impl std::fmt::Display for TicketingError {
         match self {
             Self::AuthenticationFailed(msg) => write!(f, "Authentication failed: {}", msg),
             Self::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            Self::HttpStatus(status, body) => write!(f, "HTTP {}: {}", status, body),
+            Self::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
             Self::InvalidConfig(msg) => write!(f, "Invalid configuration: {}", msg),
             Self::CreationFailed(msg) => write!(f, "Ticket creation failed: {}", msg),
             Self::ConnectionFailed(msg) => write!(f, "Connection check failed: {}", msg),
@@ -32,6 +39,28 @@ impl std::fmt::Display for TicketingError {
 
 impl std::error::Error for TicketingError {}
 
+impl TicketingError {
+    /// Classify a failed `send()`. Only connection failures and timeouts mean
+    /// the service was not reached; anything else may have been delivered.
+    pub fn from_send(error: reqwest::Error) -> Self {
+        if error.is_connect() || error.is_timeout() {
+            Self::NetworkError(error.to_string())
+        } else {
+            Self::InvalidResponse(error.to_string())
+        }
+    }
+
+    /// Turn a non-success response into [`TicketingError::HttpStatus`].
+    pub fn from_status(response: reqwest::blocking::Response) -> Self {
+        Self::HttpStatus(response.status().as_u16(), response.text().unwrap_or_default())
+    }
+
+    /// A response body that could not be parsed.
+    pub fn from_parse(error: impl std::fmt::Display) -> Self {
+        Self::InvalidResponse(format!("Failed to parse response: {}", error))
+    }
+}
+
 /// Credentials for a ticketing integration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TicketingCredentials {
@@ -62,6 +91,92 @@ pub struct CreateTicketRequest {
     pub state_id: Option<String>,
     /// Optional Linear issue template ID to use when creating the issue
     pub template_id: Option<String>,
+    /// Bug captures to upload alongside `attachments`, with their annotated
+    /// versions. `ticketing_create_ticket` fills this from the bug when empty.
+    #[serde(default)]
+    pub captures: Vec<CaptureAttachment>,
+}
+
+impl CreateTicketRequest {
+    /// Every file to upload: `attachments`, then each capture followed by its
+    /// annotated version. Duplicates are dropped.
+    pub fn upload_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = Vec::new();
+        let captures = self
+            .captures
+            .iter()
+            .flat_map(|c| std::iter::once(&c.file_path).chain(c.annotated_path.as_ref()));
+        for path in self.attachments.iter().chain(captures) {
+            if !paths.contains(path) {
+                paths.push(path.clone());
+            }
+        }
+        paths
+    }
+
+    /// Alt text of the capture `path` is, or is the annotated version of.
+    pub fn alt_text_for(&self, path: &str) -> Option<&str> {
+        self.captures
+            .iter()
+            .find(|c| c.file_path == path || c.annotated_path.as_deref() == Some(path))
+            .and_then(|c| c.alt_text.as_deref())
+            .filter(|alt| !alt.trim().is_empty())
+    }
+
+    /// Markdown list of the captures' alt texts, for providers that attach
+    /// images without embedding them in the description. Empty when no
+    /// capture has alt text.
+    pub fn alt_text_section(&self) -> String {
+        let mut section = String::new();
+        for capture in &self.captures {
+            if let Some(alt) = self.alt_text_for(&capture.file_path) {
+                let name = std::path::Path::new(&capture.file_path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| capture.file_path.clone());
+                section.push_str(&format!("- {}: {}\n", name, alt));
+            }
+        }
+        if section.is_empty() {
+            return section;
+        }
+        format!("\n\n## Screenshot Descriptions\n\n{}", section)
+    }
+
+    /// Markdown list linking every file instead of uploading it, for providers
+    /// without binary uploads. Empty when there is nothing to attach.
+    pub fn attachment_links(&self) -> String {
+        let paths = self.upload_paths();
+        if paths.is_empty() {
+            return String::new();
+        }
+        let mut links = String::from("\n\n## Attachments\n\n");
+        for path in paths {
+            let name = std::path::Path::new(&path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone());
+            let url = format!("file:///{}", path.replace('\\', "/").trim_start_matches('/'));
+            links.push_str(&format!("- [{}]({})", name, url.replace(' ', "%20")));
+            if let Some(alt) = self.alt_text_for(&path) {
+                links.push_str(&format!(": {}", alt));
+            }
+            links.push('\n');
+        }
+        links
+    }
+}
+
+/// A capture file (and its annotated version) to attach to a ticket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureAttachment {
+    /// ID of the Capture row the uploaded URLs are recorded on
+    pub capture_id: String,
+    pub file_path: String,
+    pub annotated_path: Option<String>,
+    /// Screen-reader description of the capture, used as the image's alt text
+    #[serde(default)]
+    pub alt_text: Option<String>,
 }
 
 /// Result of uploading a single attachment
@@ -75,6 +190,20 @@ pub struct AttachmentUploadResult {
     pub message: String,
 }
 
+/// MIME type of an attachment, from its file extension
+pub(crate) fn attachment_content_type(path: &std::path::Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("mp4") => "video/mp4",
+        Some("mov") => "video/quicktime",
+        Some("webm") => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
 /// Response from creating a ticket
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateTicketResponse {
@@ -99,6 +228,66 @@ pub struct ConnectionStatus {
     pub integration_name: String,
 }
 
+/// Provider-independent group of a ticket status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TicketStatusCategory {
+    Open,
+    InProgress,
+    Done,
+    Canceled,
+}
+
+impl TicketStatusCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::InProgress => "in_progress",
+            Self::Done => "done",
+            Self::Canceled => "canceled",
+        }
+    }
+
+    /// Map a Linear workflow state type ("backlog", "started", "completed", ...)
+    pub fn from_linear_state_type(state_type: &str) -> Self {
+        match state_type {
+            "started" => Self::InProgress,
+            "completed" => Self::Done,
+            "canceled" => Self::Canceled,
+            _ => Self::Open,
+        }
+    }
+
+    /// Map a Jira status category key ("new", "indeterminate", "done")
+    pub fn from_jira_category_key(key: &str) -> Self {
+        match key {
+            "indeterminate" => Self::InProgress,
+            "done" => Self::Done,
+            _ => Self::Open,
+        }
+    }
+}
+
+/// Current status of a ticket in the provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TicketStatus {
+    /// Status name as shown in the provider (e.g., "In Review")
+    pub name: String,
+    pub category: TicketStatusCategory,
+}
+
+/// Result of syncing the ticket status of one bug
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketStatusSync {
+    pub bug_id: String,
+    /// Ticket key (e.g., "PROJ-123")
+    pub ticket_key: Option<String>,
+    /// Status now stored on the bug, if it could be fetched
+    pub status: Option<TicketStatus>,
+    /// Why the status could not be fetched
+    pub error: Option<String>,
+}
+
 /// A Linear team (returned by the teams query)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LinearTeam {
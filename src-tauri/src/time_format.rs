@@ -0,0 +1,189 @@
+//! Time-zone- and locale-aware display of stored timestamps and numbers.
+//!
+//! Timestamps are stored as RFC 3339 UTC. Each session records the tester's
+//! IANA time zone when it starts, and summaries and exports render times in
+//! that zone so testers read their own wall-clock time. Sessions without a
+//! recorded (or recognised) zone keep rendering in UTC.
+//!
+//! Date order and number separators follow the `export.locale` setting (a
+//! BCP 47 tag such as `de-DE`); unset or unknown locales keep the ISO style
+//! `2024-01-15 10:00:00`.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use rusqlite::Connection;
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Settings key: locale used by summaries, exports and templates.
+pub const LOCALE_KEY: &str = "export.locale";
+
+/// Regional formatting conventions for reports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportLocale {
+    pub tag: &'static str,
+    date: &'static str,
+    time: &'static str,
+    decimal: char,
+    group: char,
+}
+
+/// ISO 8601-style dates with `.` decimals — the format used before locales existed.
+const ISO: ExportLocale = ExportLocale { tag: "iso", date: "%Y-%m-%d", time: "%H:%M:%S", decimal: '.', group: ',' };
+
+const LOCALES: &[ExportLocale] = &[
+    ExportLocale { tag: "en-US", date: "%m/%d/%Y", time: "%I:%M:%S %p", decimal: '.', group: ',' },
+    ExportLocale { tag: "en-GB", date: "%d/%m/%Y", time: "%H:%M:%S", decimal: '.', group: ',' },
+    ExportLocale { tag: "de-DE", date: "%d.%m.%Y", time: "%H:%M:%S", decimal: ',', group: '.' },
+    ExportLocale { tag: "fr-FR", date: "%d/%m/%Y", time: "%H:%M:%S", decimal: ',', group: ' ' },
+    ExportLocale { tag: "es-ES", date: "%d/%m/%Y", time: "%H:%M:%S", decimal: ',', group: '.' },
+    ExportLocale { tag: "it-IT", date: "%d/%m/%Y", time: "%H:%M:%S", decimal: ',', group: '.' },
+    ExportLocale { tag: "nl-NL", date: "%d-%m-%Y", time: "%H:%M:%S", decimal: ',', group: '.' },
+    ExportLocale { tag: "pt-BR", date: "%d/%m/%Y", time: "%H:%M:%S", decimal: ',', group: '.' },
+    ExportLocale { tag: "pl-PL", date: "%d.%m.%Y", time: "%H:%M:%S", decimal: ',', group: ' ' },
+    ExportLocale { tag: "sv-SE", date: "%Y-%m-%d", time: "%H:%M:%S", decimal: ',', group: ' ' },
+    ExportLocale { tag: "ja-JP", date: "%Y/%m/%d", time: "%H:%M:%S", decimal: '.', group: ',' },
+    ExportLocale { tag: "zh-CN", date: "%Y/%m/%d", time: "%H:%M:%S", decimal: '.', group: ',' },
+];
+
+impl Default for ExportLocale {
+    fn default() -> Self {
+        ISO
+    }
+}
+
+impl ExportLocale {
+    /// Locale tags with dedicated formatting.
+    pub fn supported_tags() -> Vec<&'static str> {
+        LOCALES.iter().map(|l| l.tag).collect()
+    }
+
+    /// Look up a locale by tag (`de-DE`, `de_de`), falling back to the first
+    /// locale of the same language (`de`, `de-AT`), then to ISO formatting.
+    pub fn from_tag(tag: &str) -> Self {
+        let tag = tag.trim().replace('_', "-");
+        let language = tag.split('-').next().unwrap_or_default();
+        LOCALES
+            .iter()
+            .find(|l| l.tag.eq_ignore_ascii_case(&tag))
+            .or_else(|| {
+                LOCALES
+                    .iter()
+                    .find(|l| l.tag.split('-').next().is_some_and(|lang| lang.eq_ignore_ascii_case(language)))
+            })
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The locale from the `export.locale` setting.
+    pub fn from_settings(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(LOCALE_KEY)
+            .ok()
+            .flatten()
+            .map(|tag| Self::from_tag(&tag))
+            .unwrap_or_default()
+    }
+
+    /// Render an RFC 3339 timestamp in `timezone` (UTC when absent or unknown),
+    /// followed by the zone abbreviation. Values that are not RFC 3339 are
+    /// returned unchanged.
+    pub fn format_datetime(&self, timestamp: &str, timezone: Option<&str>) -> String {
+        let Ok(parsed) = DateTime::parse_from_rfc3339(timestamp) else {
+            return timestamp.to_string();
+        };
+        let tz = timezone.and_then(|name| name.parse::<Tz>().ok()).unwrap_or(Tz::UTC);
+        parsed
+            .with_timezone(&tz)
+            .format(&format!("{} {} %Z", self.date, self.time))
+            .to_string()
+    }
+
+    /// Format `value` with `decimals` fractional digits and grouped thousands.
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (integer, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+
+        let mut grouped = String::new();
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                grouped.push(self.group);
+            }
+            grouped.push(digit);
+        }
+        // No sign on values that round to zero
+        if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            grouped.insert(0, '-');
+        }
+        if !fraction.is_empty() {
+            grouped.push(self.decimal);
+            grouped.push_str(fraction);
+        }
+        grouped
+    }
+
+    /// Field separator for CSV files: `;` where `,` is the decimal mark, as
+    /// spreadsheet applications in those regions expect.
+    pub fn csv_delimiter(&self) -> char {
+        if self.decimal == ',' { ';' } else { ',' }
+    }
+}
+
+/// Parse a stored timestamp: RFC 3339, or SQLite's `datetime('now')` format
+/// (`2024-01-15 10:00:00`, UTC) used by column defaults.
+pub fn parse_utc(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|t| t.and_utc())
+        })
+}
+
+/// The machine's IANA time zone (e.g. `Europe/Berlin`), if it can be determined.
+pub fn local_timezone() -> Option<String> {
+    iana_time_zone::get_timezone()
+        .ok()
+        .filter(|name| name.parse::<Tz>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_datetime_in_zone() {
+        let iso = ExportLocale::default();
+        let ts = "2024-07-01T12:30:00Z";
+        assert_eq!(iso.format_datetime(ts, Some("Europe/Berlin")), "2024-07-01 14:30:00 CEST");
+        assert_eq!(iso.format_datetime(ts, Some("America/New_York")), "2024-07-01 08:30:00 EDT");
+        assert_eq!(iso.format_datetime("2024-01-15T12:30:00Z", Some("Europe/Berlin")), "2024-01-15 13:30:00 CET");
+        assert_eq!(iso.format_datetime(ts, None), "2024-07-01 12:30:00 UTC");
+        assert_eq!(iso.format_datetime(ts, Some("Mars/Olympus")), "2024-07-01 12:30:00 UTC");
+        assert_eq!(iso.format_datetime("2024-07-01T14:30:00+02:00", None), "2024-07-01 12:30:00 UTC");
+        assert_eq!(iso.format_datetime("not a date", Some("Europe/Berlin")), "not a date");
+    }
+
+    #[test]
+    fn test_locale_lookup_and_date_order() {
+        let ts = "2024-07-01T15:05:09Z";
+        assert_eq!(ExportLocale::from_tag("en-US").format_datetime(ts, None), "07/01/2024 03:05:09 PM UTC");
+        assert_eq!(ExportLocale::from_tag("de_de").format_datetime(ts, None), "01.07.2024 15:05:09 UTC");
+        assert_eq!(ExportLocale::from_tag("de-AT").tag, "de-DE");
+        assert_eq!(ExportLocale::from_tag("fr").tag, "fr-FR");
+        assert_eq!(ExportLocale::from_tag("xx-YY"), ExportLocale::default());
+    }
+
+    #[test]
+    fn test_format_number() {
+        let de = ExportLocale::from_tag("de-DE");
+        assert_eq!(de.format_number(1234567.891, 2), "1.234.567,89");
+        assert_eq!(de.format_number(-0.004, 2), "0,00");
+        assert_eq!(ExportLocale::default().format_number(-1234.0, 0), "-1,234");
+        assert_eq!(ExportLocale::from_tag("fr-FR").format_number(999.5, 1), "999,5");
+        assert_eq!(de.csv_delimiter(), ';');
+        assert_eq!(ExportLocale::from_tag("en-GB").csv_delimiter(), ',');
+    }
+}
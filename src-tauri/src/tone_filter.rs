@@ -0,0 +1,278 @@
+//! Softening venting in notes before they reach a ticket tracker.
+//!
+//! Raw notes sometimes carry frustration ("this stupid dialog", "wtf") that
+//! should not land in a customer-visible tracker. When enabled in the
+//! `ticketing.tone_filter` setting, [`review`] checks a description against a
+//! built-in list of profanity and loaded words plus the team's own terms, and
+//! proposes a softened text. The ticket preview shows the changed lines for
+//! approval; nothing is changed without it. Code blocks and inline code are
+//! left alone.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{SettingsOps, SettingsRepository};
+use crate::description_lint::{find_term, strip_inline_code};
+
+/// Settings key holding [`ToneFilterSettings`] as JSON.
+pub const TONE_FILTER_KEY: &str = "ticketing.tone_filter";
+
+/// Flagged words and their softer replacement; an empty replacement drops the word.
+const BUILT_IN_TERMS: &[(&str, &str)] = &[
+    ("what the hell", "what"),
+    ("the hell", ""),
+    ("wtf", ""),
+    ("ffs", ""),
+    ("fucking", ""),
+    ("fuck", ""),
+    ("goddamn", ""),
+    ("damned", ""),
+    ("damn", ""),
+    ("bloody", ""),
+    ("freaking", ""),
+    ("frigging", ""),
+    ("shitty", "poor"),
+    ("shit", "problem"),
+    ("crappy", "poor"),
+    ("crap", "poor"),
+    ("sucks", "needs improvement"),
+    ("garbage", "broken"),
+    ("stupid", "confusing"),
+    ("idiotic", "confusing"),
+    ("moronic", "confusing"),
+    ("dumb", "confusing"),
+    ("useless", "not helpful"),
+    ("ridiculous", "unexpected"),
+    ("pathetic", "inadequate"),
+    ("horrible", "poor"),
+    ("awful", "poor"),
+];
+
+/// A team-defined word or phrase and what to write instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToneTerm {
+    pub term: String,
+    /// Empty to drop the term
+    #[serde(default)]
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToneFilterSettings {
+    pub enabled: bool,
+    /// Checked before the built-in list, so they can also override its replacements
+    pub terms: Vec<ToneTerm>,
+}
+
+impl ToneFilterSettings {
+    /// Stored settings; missing or unreadable settings give the defaults.
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(TONE_FILTER_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        if self.terms.iter().any(|t| t.term.trim().is_empty()) {
+            return Err("Tone filter terms cannot be empty".to_string());
+        }
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(TONE_FILTER_KEY, &json)
+            .map_err(|e| format!("Failed to save tone filter settings: {}", e))
+    }
+}
+
+/// One flagged occurrence.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToneFlag {
+    /// The text as written
+    pub phrase: String,
+    /// Empty when the phrase is dropped
+    pub replacement: String,
+    /// 1-based line in the description
+    pub line: usize,
+}
+
+/// A line the softened text changes, for the approval diff.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToneChange {
+    /// 1-based line in the description
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// Proposed softening of a description.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToneReview {
+    /// The description with every flag applied; equal to the input when nothing was flagged
+    pub softened: String,
+    pub flags: Vec<ToneFlag>,
+    pub changes: Vec<ToneChange>,
+}
+
+/// `replacement`, capitalized when `written` starts with a capital letter.
+fn match_case(replacement: &str, written: &str) -> String {
+    let mut chars = replacement.chars();
+    match chars.next() {
+        Some(first) if written.starts_with(char::is_uppercase) => first.to_uppercase().chain(chars).collect(),
+        _ => replacement.to_string(),
+    }
+}
+
+/// Whether text continuing `out` starts a sentence.
+fn at_sentence_start(out: &str) -> bool {
+    let out = out.trim_end();
+    out.is_empty() || out.ends_with(['.', '!', '?'])
+}
+
+/// Append `text`, capitalizing its first letter when `capitalize` is set.
+fn push_text(out: &mut String, text: &str, capitalize: &mut bool) {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) if *capitalize => {
+            out.extend(first.to_uppercase());
+            out.push_str(chars.as_str());
+            *capitalize = false;
+        }
+        _ => out.push_str(text),
+    }
+}
+
+/// `line` with the flagged ranges replaced, and the flags.
+fn soften_line(line: &str, number: usize, terms: &[(String, String)]) -> (String, Vec<ToneFlag>) {
+    // ASCII lowercasing keeps byte offsets aligned with `line`
+    let lower = strip_inline_code(line).to_ascii_lowercase();
+    let mut found: Vec<(usize, usize, &str)> = Vec::new();
+    for (term, replacement) in terms {
+        let mut offset = 0;
+        while let Some(start) = find_term(&lower[offset..], term).map(|s| s + offset) {
+            let end = start + term.len();
+            if !found.iter().any(|&(s, e, _)| start < e && s < end) {
+                found.push((start, end, replacement));
+            }
+            offset = end;
+        }
+    }
+    found.sort_by_key(|&(start, _, _)| start);
+
+    let mut out = String::with_capacity(line.len());
+    let mut flags = Vec::new();
+    let mut last = 0;
+    let mut capitalize = false;
+    for (start, end, replacement) in found {
+        let written = &line[start..end];
+        flags.push(ToneFlag { phrase: written.to_string(), replacement: replacement.to_string(), line: number });
+        push_text(&mut out, &line[last..start], &mut capitalize);
+        if replacement.is_empty() {
+            // Drop the phrase with the space after it, and the comma that set it
+            // apart: "Wtf, the dialog" -> "The dialog", "again, wtf." -> "again."
+            let after = line[end..].trim_start_matches(' ');
+            if at_sentence_start(&out) {
+                capitalize = written.starts_with(char::is_uppercase);
+                last = line.len() - after.strip_prefix(',').map_or(after, |rest| rest.trim_start_matches(' ')).len();
+            } else {
+                if after.is_empty() || after.starts_with(['.', ',', '!', '?', ';', ':']) {
+                    out.truncate(out.trim_end_matches([' ', ',', ';', ':']).len());
+                }
+                last = line.len() - after.len();
+            }
+        } else {
+            push_text(&mut out, &match_case(replacement, written), &mut capitalize);
+            last = end;
+        }
+    }
+    push_text(&mut out, &line[last..], &mut capitalize);
+    (out, flags)
+}
+
+/// Flag problematic phrasing in `text` and propose a softened version.
+/// Nothing is flagged while the filter is disabled.
+pub fn review(text: &str, settings: &ToneFilterSettings) -> ToneReview {
+    if !settings.enabled {
+        return ToneReview { softened: text.to_string(), ..Default::default() };
+    }
+    let mut terms: Vec<(String, String)> = settings
+        .terms
+        .iter()
+        .map(|t| (t.term.trim().to_ascii_lowercase(), t.replacement.trim().to_string()))
+        .collect();
+    for (term, replacement) in BUILT_IN_TERMS {
+        if !terms.iter().any(|(t, _)| t == term) {
+            terms.push((term.to_string(), replacement.to_string()));
+        }
+    }
+    // Longer phrases first, so "what the hell" wins over a team's "hell"
+    terms.sort_by_key(|(term, _)| std::cmp::Reverse(term.len()));
+
+    let mut review = ToneReview::default();
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+    for (index, line) in text.split('\n').enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        if in_code_block || line.trim_start().starts_with("```") {
+            lines.push(line.to_string());
+            continue;
+        }
+        let (softened, flags) = soften_line(line, index + 1, &terms);
+        if !flags.is_empty() {
+            review.changes.push(ToneChange { line: index + 1, before: line.to_string(), after: softened.clone() });
+            review.flags.extend(flags);
+        }
+        lines.push(softened);
+    }
+    review.softened = lines.join("\n");
+    review
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> ToneFilterSettings {
+        ToneFilterSettings { enabled: true, ..Default::default() }
+    }
+
+    #[test]
+    fn test_softens_venting_and_reports_changed_lines() {
+        let text = "Stupid dialog closes again, wtf\nSteps:\n```\nstupid_flag=1\n```\nThe `crap` script is useless.";
+        let review = review(text, &enabled());
+
+        assert_eq!(
+            review.softened,
+            "Confusing dialog closes again\nSteps:\n```\nstupid_flag=1\n```\nThe `crap` script is not helpful."
+        );
+        let phrases: Vec<&str> = review.flags.iter().map(|f| f.phrase.as_str()).collect();
+        assert_eq!(phrases, vec!["Stupid", "wtf", "useless"]);
+        assert_eq!(review.changes.iter().map(|c| c.line).collect::<Vec<_>>(), vec![1, 6]);
+        assert_eq!(review.changes[0].before, "Stupid dialog closes again, wtf");
+    }
+
+    #[test]
+    fn test_team_terms_and_disabled_filter() {
+        let settings = ToneFilterSettings {
+            enabled: true,
+            terms: vec![ToneTerm { term: "as usual".to_string(), replacement: "again".to_string() }],
+        };
+        let review = review("Login broke as usual. What the hell is going on", &settings);
+        assert_eq!(review.softened, "Login broke again. What is going on");
+        assert_eq!(super::review("Wtf, the dialog closed. Why the hell?", &enabled()).softened, "The dialog closed. Why?");
+
+        let text = "This sucks";
+        assert_eq!(super::review(text, &ToneFilterSettings::default()), ToneReview {
+            softened: text.to_string(),
+            ..Default::default()
+        });
+    }
+}
@@ -0,0 +1,330 @@
+//! Accessibility-tree snapshots of the focused window.
+//!
+//! When `capture.ui_tree_snapshot` is enabled, starting a bug records the
+//! UI Automation tree of the foreground window (control types, names,
+//! AutomationIds) into `ui-tree.json` in the bug folder. The AI describer
+//! reads it back so reports name the real controls instead of guessing from
+//! pixels. Only Windows has an implementation; elsewhere the step is skipped.
+
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Settings key: `"true"` to snapshot the focused window when a bug starts.
+pub const UI_TREE_SNAPSHOT_KEY: &str = "capture.ui_tree_snapshot";
+
+/// File written into the bug folder.
+pub const UI_TREE_FILE: &str = "ui-tree.json";
+
+/// Deepest level of the tree that is walked.
+#[cfg(windows)]
+const MAX_DEPTH: usize = 12;
+
+/// Elements recorded before the walk stops (large grids can have thousands).
+#[cfg(windows)]
+const MAX_ELEMENTS: usize = 2000;
+
+/// Element lines handed to the AI describer.
+const MAX_PROMPT_LINES: usize = 150;
+
+/// One element of the accessibility tree.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiElement {
+    /// Localized control type, e.g. `button`
+    pub control_type: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub automation_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub class_name: String,
+    /// Screen rectangle as `[left, top, right, bottom]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bounds: Option<[i32; 4]>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<UiElement>,
+}
+
+/// Contents of `ui-tree.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiTreeSnapshot {
+    pub captured_at: String,
+    pub window_title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_name: Option<String>,
+    /// True when the depth or element limit cut the walk short
+    pub truncated: bool,
+    pub root: UiElement,
+}
+
+/// Whether the snapshot step is enabled.
+pub fn is_enabled(conn: &Connection) -> bool {
+    SettingsRepository::new(conn)
+        .get(UI_TREE_SNAPSHOT_KEY)
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true")
+}
+
+/// Snapshot the focused window and write it to `{bug_folder}/ui-tree.json`.
+pub fn capture_to_folder(bug_folder: &Path) -> Result<UiTreeSnapshot, String> {
+    let snapshot = snapshot_focused_window()?;
+    std::fs::create_dir_all(bug_folder).map_err(|e| format!("Cannot create {:?}: {}", bug_folder, e))?;
+    let json = serde_json::to_string_pretty(&snapshot).map_err(|e| e.to_string())?;
+    let path = bug_folder.join(UI_TREE_FILE);
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(snapshot)
+}
+
+/// Read `ui-tree.json` from a bug folder, if present.
+pub fn load(bug_folder: &Path) -> Option<UiTreeSnapshot> {
+    let json = std::fs::read_to_string(bug_folder.join(UI_TREE_FILE)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Indented outline of the named or identified elements, for prompts.
+pub fn outline(snapshot: &UiTreeSnapshot) -> String {
+    fn walk(element: &UiElement, depth: usize, lines: &mut Vec<String>) {
+        if lines.len() >= MAX_PROMPT_LINES {
+            return;
+        }
+        if !element.name.is_empty() || !element.automation_id.is_empty() {
+            let mut line = format!("{}{}", "  ".repeat(depth), element.control_type);
+            if !element.name.is_empty() {
+                line.push_str(&format!(" \"{}\"", element.name));
+            }
+            if !element.automation_id.is_empty() {
+                line.push_str(&format!(" [AutomationId={}]", element.automation_id));
+            }
+            lines.push(line);
+        }
+        for child in &element.children {
+            walk(child, depth + 1, lines);
+        }
+    }
+
+    let mut lines = vec![format!("Window: {}", snapshot.window_title)];
+    walk(&snapshot.root, 0, &mut lines);
+    if lines.len() >= MAX_PROMPT_LINES || snapshot.truncated {
+        lines.push("(tree truncated)".to_string());
+    }
+    lines.join("\n")
+}
+
+/// Snapshot the foreground window's UI Automation tree.
+#[cfg(windows)]
+pub fn snapshot_focused_window() -> Result<UiTreeSnapshot, String> {
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+    };
+    use windows::Win32::UI::Accessibility::{CUIAutomation, IUIAutomation, IUIAutomationElement, IUIAutomationTreeWalker};
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    fn element(item: &IUIAutomationElement) -> UiElement {
+        unsafe {
+            UiElement {
+                control_type: item.CurrentLocalizedControlType().map(|s| s.to_string()).unwrap_or_default(),
+                name: item.CurrentName().map(|s| s.to_string()).unwrap_or_default(),
+                automation_id: item.CurrentAutomationId().map(|s| s.to_string()).unwrap_or_default(),
+                class_name: item.CurrentClassName().map(|s| s.to_string()).unwrap_or_default(),
+                bounds: item
+                    .CurrentBoundingRectangle()
+                    .ok()
+                    .map(|r| [r.left, r.top, r.right, r.bottom]),
+                children: Vec::new(),
+            }
+        }
+    }
+
+    fn walk(
+        walker: &IUIAutomationTreeWalker,
+        item: &IUIAutomationElement,
+        depth: usize,
+        count: &mut usize,
+        truncated: &mut bool,
+    ) -> UiElement {
+        let mut node = element(item);
+        *count += 1;
+        if depth >= MAX_DEPTH {
+            *truncated = true;
+            return node;
+        }
+        let mut child = unsafe { walker.GetFirstChildElement(item) }.ok();
+        while let Some(current) = child {
+            if *count >= MAX_ELEMENTS {
+                *truncated = true;
+                break;
+            }
+            node.children.push(walk(walker, &current, depth + 1, count, truncated));
+            child = unsafe { walker.GetNextSiblingElement(&current) }.ok();
+        }
+        node
+    }
+
+    unsafe {
+        let initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+        let result = (|| {
+            let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| format!("UI Automation unavailable: {}", e))?;
+            let hwnd = GetForegroundWindow();
+            if hwnd.0.is_null() {
+                return Err("No focused window".to_string());
+            }
+            let root = automation
+                .ElementFromHandle(hwnd)
+                .map_err(|e| format!("Cannot read focused window: {}", e))?;
+            // Starting a bug from our own window would only describe this app
+            let pid = root.CurrentProcessId().ok();
+            if pid == Some(std::process::id() as i32) {
+                return Err("Focused window belongs to QA Capture".to_string());
+            }
+            let walker = automation
+                .ControlViewWalker()
+                .map_err(|e| format!("Cannot walk UI tree: {}", e))?;
+
+            let (mut count, mut truncated) = (0, false);
+            let tree = walk(&walker, &root, 0, &mut count, &mut truncated);
+            let process_name = pid.and_then(|pid| process_name(pid as u32));
+            Ok(UiTreeSnapshot {
+                captured_at: chrono::Utc::now().to_rfc3339(),
+                window_title: tree.name.clone(),
+                process_name,
+                truncated,
+                root: tree,
+            })
+        })();
+        if initialized {
+            CoUninitialize();
+        }
+        result
+    }
+}
+
+#[cfg(windows)]
+fn process_name(pid: u32) -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 260];
+        let mut len = buffer.len() as u32;
+        let ok = QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut len).is_ok();
+        let _ = CloseHandle(handle);
+        ok.then(|| {
+            let path = String::from_utf16_lossy(&buffer[..len as usize]);
+            Path::new(&path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or(path)
+        })
+    }
+}
+
+/// Screen rectangle (`[left, top, right, bottom]`) of the UI element under
+/// the mouse cursor, found by UI Automation hit-testing.
+#[cfg(windows)]
+pub fn element_rect_at_cursor() -> Result<[i32; 4], String> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+    };
+    use windows::Win32::UI::Accessibility::{CUIAutomation, IUIAutomation};
+    use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
+
+    unsafe {
+        let initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+        let result = (|| {
+            let mut cursor = POINT::default();
+            GetCursorPos(&mut cursor).map_err(|e| format!("Cannot read cursor position: {}", e))?;
+            let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| format!("UI Automation unavailable: {}", e))?;
+            let element = automation
+                .ElementFromPoint(cursor)
+                .map_err(|e| format!("No element under the cursor: {}", e))?;
+            let r = element
+                .CurrentBoundingRectangle()
+                .map_err(|e| format!("Element has no bounds: {}", e))?;
+            Ok([r.left, r.top, r.right, r.bottom])
+        })();
+        if initialized {
+            CoUninitialize();
+        }
+        result
+    }
+}
+
+/// UI Automation is Windows-only.
+#[cfg(not(windows))]
+pub fn snapshot_focused_window() -> Result<UiTreeSnapshot, String> {
+    Err("UI tree snapshots are only available on Windows".to_string())
+}
+
+/// UI Automation is Windows-only.
+#[cfg(not(windows))]
+pub fn element_rect_at_cursor() -> Result<[i32; 4], String> {
+    Err("Element hit-testing is only available on Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> UiTreeSnapshot {
+        UiTreeSnapshot {
+            captured_at: "2024-01-15T10:00:00Z".to_string(),
+            window_title: "Checkout".to_string(),
+            process_name: Some("shop.exe".to_string()),
+            truncated: false,
+            root: UiElement {
+                control_type: "window".to_string(),
+                name: "Checkout".to_string(),
+                children: vec![
+                    UiElement {
+                        control_type: "pane".to_string(),
+                        children: vec![UiElement {
+                            control_type: "button".to_string(),
+                            name: "Pay now".to_string(),
+                            automation_id: "btnPay".to_string(),
+                            bounds: Some([10, 20, 110, 50]),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    },
+                    UiElement {
+                        control_type: "edit".to_string(),
+                        automation_id: "txtCard".to_string(),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_outline_lists_named_elements_with_automation_ids() {
+        assert_eq!(
+            outline(&sample()),
+            "Window: Checkout\nwindow \"Checkout\"\n    button \"Pay now\" [AutomationId=btnPay]\n  edit [AutomationId=txtCard]"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_through_bug_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = sample();
+        std::fs::write(dir.path().join(UI_TREE_FILE), serde_json::to_string(&snapshot).unwrap()).unwrap();
+
+        assert_eq!(load(dir.path()), Some(snapshot));
+        assert_eq!(load(&dir.path().join("missing")), None);
+    }
+}
@@ -0,0 +1,271 @@
+//! Extraction of still frames from recordings via the `ffmpeg` sidecar.
+//!
+//! A frame is written as a PNG next to the bug's other captures and recorded
+//! as a derived screenshot capture (`derived_from` points at the recording),
+//! so it can be annotated and embedded in the ticket like any screenshot.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use rusqlite::Connection;
+use uuid::Uuid;
+
+use crate::database::{
+    BugOps, BugRepository, Capture, CaptureOps, CaptureRepository, CaptureType, SessionOps,
+    SessionRepository, SettingsOps, SettingsRepository,
+};
+
+/// Settings key: path to the `ffmpeg` executable.
+pub const FFMPEG_PATH_KEY: &str = "media.ffmpeg_path";
+
+/// Configured `ffmpeg` executable, defaulting to `ffmpeg` on `PATH`.
+pub fn ffmpeg_path(conn: &Connection) -> String {
+    SettingsRepository::new(conn)
+        .get(FFMPEG_PATH_KEY)
+        .ok()
+        .flatten()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "ffmpeg".to_string())
+}
+
+/// Format a millisecond offset the way ffmpeg's `-ss` expects (`12.345`).
+pub fn format_seek(timestamp_ms: i64) -> String {
+    format!("{}.{:03}", timestamp_ms / 1000, timestamp_ms % 1000)
+}
+
+/// Write the frame at `timestamp_ms` of `video` to `output` as a PNG.
+pub fn extract_frame(ffmpeg: &str, video: &Path, timestamp_ms: i64, output: &Path) -> Result<(), String> {
+    let mut command = Command::new(ffmpeg);
+    command
+        .args(["-v", "error", "-ss", &format_seek(timestamp_ms), "-i"])
+        .arg(video)
+        .args(["-frames:v", "1", "-y"])
+        .arg(output);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let result = command
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if !result.status.success() || !output.exists() {
+        return Err(format!(
+            "ffmpeg could not extract a frame at {}: {}",
+            format_seek(timestamp_ms),
+            String::from_utf8_lossy(&result.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Folder a derived capture of `source` is written to: the owning bug's
+/// folder, or the session's `_unsorted/` folder for unsorted recordings.
+pub fn destination_dir(conn: &Connection, source: &Capture) -> Result<PathBuf, String> {
+    if let Some(bug_id) = &source.bug_id {
+        let bug = BugRepository::new(conn)
+            .get(bug_id)
+            .map_err(|e| format!("Failed to get bug: {}", e))?
+            .ok_or_else(|| format!("Bug not found: {}", bug_id))?;
+        return Ok(PathBuf::from(bug.folder_path));
+    }
+
+    let session = SessionRepository::new(conn)
+        .get(&source.session_id)
+        .map_err(|e| format!("Failed to get session: {}", e))?
+        .ok_or_else(|| format!("Session not found: {}", source.session_id))?;
+    Ok(PathBuf::from(session.folder_path).join("_unsorted"))
+}
+
+/// Extract a frame from the recording `capture_id` and record it as a derived
+/// screenshot attached to the same bug. `extract` performs the actual frame
+/// extraction (normally [`extract_frame`] with the configured ffmpeg) and runs
+/// with `db` unlocked.
+pub fn create_frame_capture<F>(
+    db: &Mutex<Connection>,
+    capture_id: &str,
+    timestamp_ms: i64,
+    extract: F,
+) -> Result<Capture, String>
+where
+    F: FnOnce(&Path, &Path) -> Result<(), String>,
+{
+    let (source, dest_path, file_name) = {
+        let conn = db.lock().unwrap();
+        let source = CaptureRepository::new(&conn)
+            .get(capture_id)
+            .map_err(|e| format!("Failed to get capture: {}", e))?
+            .ok_or_else(|| format!("Capture not found: {}", capture_id))?;
+
+        if source.file_type != CaptureType::Video {
+            return Err(format!("Capture {} is not a video recording", capture_id));
+        }
+        if timestamp_ms < 0 || source.video_duration_ms.is_some_and(|d| timestamp_ms > d) {
+            return Err(format!(
+                "Timestamp {}ms is outside the recording ({}ms long)",
+                timestamp_ms,
+                source.video_duration_ms.unwrap_or(0)
+            ));
+        }
+
+        let dest_dir = destination_dir(&conn, &source)?;
+        std::fs::create_dir_all(&dest_dir)
+            .map_err(|e| format!("Cannot create folder {:?}: {}", dest_dir, e))?;
+
+        let naming = crate::capture_naming::NamingContext::for_folder(&conn, &dest_dir);
+        let capture_number = crate::next_capture_number(&dest_dir, &naming);
+        let (file_name, _) = crate::make_capture_filename(Path::new("frame.png"), capture_number, &naming);
+        (source, dest_dir.join(&file_name), file_name)
+    };
+
+    extract(Path::new(&source.file_path), &dest_path)?;
+
+    let frame = Capture {
+        id: Uuid::new_v4().to_string(),
+        bug_id: source.bug_id.clone(),
+        session_id: source.session_id.clone(),
+        file_name,
+        file_path: dest_path.to_string_lossy().to_string(),
+        file_type: CaptureType::Screenshot,
+        annotated_path: None,
+        file_size_bytes: std::fs::metadata(&dest_path).ok().map(|m| m.len() as i64),
+        is_console_capture: false,
+        parsed_content: None,
+        created_at: Utc::now().to_rfc3339(),
+        edited_at: None,
+        media_link: None,
+        video_duration_ms: None,
+        video_width: None,
+        video_height: None,
+        video_codec: None,
+        derived_from: Some(source.id.clone()),
+        frame_timestamp_ms: Some(timestamp_ms),
+        source_metadata: None,
+        alt_text: None,
+    };
+    let created = CaptureRepository::new(&db.lock().unwrap()).create(&frame);
+    if let Err(e) = created {
+        let _ = std::fs::remove_file(&dest_path);
+        return Err(format!("Failed to create capture: {}", e));
+    }
+    Ok(frame)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{Bug, BugStatus, BugType, Database, Session, SessionStatus};
+
+    fn seed(conn: &Connection, root: &Path) {
+        SessionRepository::new(conn)
+            .create(&Session {
+                id: "s-1".to_string(),
+                started_at: "2024-01-01T10:00:00Z".to_string(),
+                ended_at: None,
+                status: SessionStatus::Active,
+                folder_path: root.to_string_lossy().to_string(),
+                session_notes: None,
+                environment_json: None,
+                original_snip_path: None,
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                profile_id: None,
+                unlocked_at: None,
+                timezone: None,
+            })
+            .unwrap();
+        let bug_folder = root.join("bug_001");
+        std::fs::create_dir_all(&bug_folder).unwrap();
+        BugRepository::new(conn)
+            .create(&Bug {
+                id: "b-1".to_string(),
+                session_id: "s-1".to_string(),
+                bug_number: 1,
+                display_id: "BUG-001".to_string(),
+                bug_type: BugType::Bug,
+                status: BugStatus::Capturing,
+                folder_path: bug_folder.to_string_lossy().to_string(),
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                updated_at: "2024-01-01T10:00:00Z".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        let video = bug_folder.join("recording-001.mp4");
+        std::fs::write(&video, b"video").unwrap();
+        CaptureRepository::new(conn)
+            .create(&Capture {
+                id: "v-1".to_string(),
+                bug_id: Some("b-1".to_string()),
+                session_id: "s-1".to_string(),
+                file_name: "recording-001.mp4".to_string(),
+                file_path: video.to_string_lossy().to_string(),
+                file_type: CaptureType::Video,
+                file_size_bytes: Some(5),
+                created_at: "2024-01-01T10:00:00Z".to_string(),
+                video_duration_ms: Some(60_000),
+                video_width: Some(1920),
+                video_height: Some(1080),
+                video_codec: Some("h264".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_format_seek() {
+        assert_eq!(format_seek(0), "0.000");
+        assert_eq!(format_seek(12_345), "12.345");
+        assert_eq!(format_seek(61_005), "61.005");
+    }
+
+    #[test]
+    fn test_create_frame_capture_attaches_to_same_bug() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Mutex::new(Database::in_memory().unwrap().into_connection());
+        seed(&db.lock().unwrap(), dir.path());
+
+        let frame = create_frame_capture(&db, "v-1", 12_500, |_, out| {
+            std::fs::write(out, b"png").map_err(|e| e.to_string())
+        })
+        .unwrap();
+
+        assert_eq!(frame.bug_id.as_deref(), Some("b-1"));
+        assert_eq!(frame.file_type, CaptureType::Screenshot);
+        // The recording already occupies number 001
+        assert_eq!(frame.file_name, "capture-002.png");
+        assert_eq!(frame.derived_from.as_deref(), Some("v-1"));
+        assert_eq!(frame.frame_timestamp_ms, Some(12_500));
+        assert!(Path::new(&frame.file_path).exists());
+
+        let stored = CaptureRepository::new(&db.lock().unwrap()).list_by_bug("b-1").unwrap();
+        assert_eq!(stored.len(), 2);
+    }
+
+    #[test]
+    fn test_create_frame_capture_rejects_out_of_range_timestamp() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Mutex::new(Database::in_memory().unwrap().into_connection());
+        seed(&db.lock().unwrap(), dir.path());
+
+        let err = create_frame_capture(&db, "v-1", 90_000, |_, _| Ok(())).unwrap_err();
+        assert!(err.contains("outside the recording"));
+        assert!(create_frame_capture(&db, "v-1", -1, |_, _| Ok(())).is_err());
+    }
+
+    #[test]
+    fn test_create_frame_capture_requires_video() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Mutex::new(Database::in_memory().unwrap().into_connection());
+        seed(&db.lock().unwrap(), dir.path());
+        let frame = create_frame_capture(&db, "v-1", 0, |_, out| {
+            std::fs::write(out, b"png").map_err(|e| e.to_string())
+        })
+        .unwrap();
+
+        let err = create_frame_capture(&db, &frame.id, 0, |_, _| Ok(())).unwrap_err();
+        assert!(err.contains("not a video"));
+    }
+}
@@ -0,0 +1,197 @@
+//! Video metadata extraction via the `ffprobe` sidecar.
+//!
+//! When a recording is ingested its duration, resolution and codec are read
+//! with `ffprobe` and stored on the capture. `ffprobe` is looked up on `PATH`
+//! unless the `media.ffprobe_path` setting points elsewhere; when it is not
+//! available the columns are simply left empty.
+
+use std::path::Path;
+use std::process::Command;
+
+use rusqlite::Connection;
+use serde::Deserialize;
+
+use crate::database::{Capture, CaptureOps, CaptureRepository, SettingsOps, SettingsRepository};
+
+/// Settings key: path to the `ffprobe` executable.
+pub const FFPROBE_PATH_KEY: &str = "media.ffprobe_path";
+
+/// Codecs that ticketing systems and browsers play back reliably.
+const WIDELY_SUPPORTED_CODECS: &[&str] = &["h264", "vp8", "vp9", "av1"];
+
+/// Duration, resolution and codec of a recording.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VideoMetadata {
+    pub duration_ms: Option<i64>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub codec: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProbeOutput {
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+    format: Option<ProbeFormat>,
+}
+
+#[derive(Deserialize)]
+struct ProbeStream {
+    codec_name: Option<String>,
+    width: Option<i64>,
+    height: Option<i64>,
+    duration: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProbeFormat {
+    duration: Option<String>,
+}
+
+/// Parse the JSON printed by
+/// `ffprobe -show_entries stream=codec_name,width,height,duration:format=duration -of json`.
+pub fn parse_ffprobe_output(json: &str) -> Result<VideoMetadata, String> {
+    let output: ProbeOutput =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
+    let stream = output.streams.into_iter().next();
+
+    // Prefer the container duration; some muxers only report it per stream.
+    let duration = output
+        .format
+        .and_then(|f| f.duration)
+        .or_else(|| stream.as_ref().and_then(|s| s.duration.clone()))
+        .and_then(|d| d.parse::<f64>().ok())
+        .map(|secs| (secs * 1000.0).round() as i64);
+
+    Ok(VideoMetadata {
+        duration_ms: duration,
+        width: stream.as_ref().and_then(|s| s.width),
+        height: stream.as_ref().and_then(|s| s.height),
+        codec: stream.and_then(|s| s.codec_name),
+    })
+}
+
+/// Run `ffprobe` on `path`.
+pub fn probe(ffprobe: &str, path: &Path) -> Result<VideoMetadata, String> {
+    let mut command = Command::new(ffprobe);
+    command
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-show_entries",
+            "stream=codec_name,width,height,duration:format=duration",
+            "-of",
+            "json",
+        ])
+        .arg(path);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffprobe failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    parse_ffprobe_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Configured `ffprobe` executable, defaulting to `ffprobe` on `PATH`.
+pub fn ffprobe_path(conn: &Connection) -> String {
+    SettingsRepository::new(conn)
+        .get(FFPROBE_PATH_KEY)
+        .ok()
+        .flatten()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "ffprobe".to_string())
+}
+
+/// Store extracted metadata on a capture.
+pub fn apply_to_capture(conn: &Connection, capture_id: &str, metadata: &VideoMetadata) -> Result<Option<Capture>, String> {
+    let repo = CaptureRepository::new(conn);
+    let Some(mut capture) = repo
+        .get(capture_id)
+        .map_err(|e| format!("Failed to get capture: {}", e))?
+    else {
+        return Ok(None);
+    };
+
+    capture.video_duration_ms = metadata.duration_ms;
+    capture.video_width = metadata.width;
+    capture.video_height = metadata.height;
+    capture.video_codec = metadata.codec.clone();
+    repo.update(&capture)
+        .map_err(|e| format!("Failed to update capture: {}", e))?;
+    Ok(Some(capture))
+}
+
+/// Warning text for codecs that ticketing systems commonly refuse to play.
+pub fn codec_warning(codec: &str) -> Option<String> {
+    let codec = codec.to_lowercase();
+    if WIDELY_SUPPORTED_CODECS.contains(&codec.as_str()) {
+        None
+    } else {
+        Some(format!(
+            "Video codec '{}' may not play in ticketing systems; consider re-encoding to H.264",
+            codec
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ffprobe_output() {
+        let json = r#"{
+            "programs": [],
+            "streams": [{"codec_name": "h264", "width": 1920, "height": 1080}],
+            "format": {"duration": "12.345000"}
+        }"#;
+
+        let metadata = parse_ffprobe_output(json).unwrap();
+        assert_eq!(
+            metadata,
+            VideoMetadata {
+                duration_ms: Some(12345),
+                width: Some(1920),
+                height: Some(1080),
+                codec: Some("h264".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_ffprobe_output_falls_back_to_stream_duration() {
+        let json = r#"{"streams": [{"codec_name": "vp9", "width": 640, "height": 480, "duration": "2.5"}]}"#;
+        let metadata = parse_ffprobe_output(json).unwrap();
+        assert_eq!(metadata.duration_ms, Some(2500));
+        assert_eq!(metadata.codec.as_deref(), Some("vp9"));
+    }
+
+    #[test]
+    fn test_parse_ffprobe_output_without_video_stream() {
+        let metadata = parse_ffprobe_output(r#"{"streams": [], "format": {"duration": "N/A"}}"#).unwrap();
+        assert_eq!(metadata, VideoMetadata::default());
+        assert!(parse_ffprobe_output("not json").is_err());
+    }
+
+    #[test]
+    fn test_codec_warning() {
+        assert!(codec_warning("h264").is_none());
+        assert!(codec_warning("VP9").is_none());
+        assert!(codec_warning("hevc").unwrap().contains("hevc"));
+        assert!(codec_warning("prores").is_some());
+    }
+}
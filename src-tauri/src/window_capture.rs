@@ -0,0 +1,239 @@
+//! Window screenshots with a highlighted element.
+//!
+//! `capture_window_with_highlight` grabs a single window (the active one by
+//! default), draws a box around an element rectangle, typically the control
+//! under the cursor found by UI Automation hit-testing, and saves the result
+//! as a PNG. This produces an annotated screenshot in one action.
+//! Grabbing windows is Windows-only; the drawing is platform independent.
+
+use std::path::{Path, PathBuf};
+
+use image::{Rgba, RgbaImage};
+use uuid::Uuid;
+
+use crate::cursor_overlay::CursorOverlay;
+
+/// Highlight colour.
+pub const HIGHLIGHT_COLOR: Rgba<u8> = Rgba([230, 30, 40, 255]);
+
+/// Highlight line width in pixels.
+pub const HIGHLIGHT_THICKNESS: u32 = 4;
+
+/// Extra space left between the element and the highlight box.
+const HIGHLIGHT_PADDING: i32 = 3;
+
+/// A captured window and where it sits on screen.
+pub struct WindowShot {
+    pub image: RgbaImage,
+    /// Screen position of the image's top-left pixel
+    pub origin: (i32, i32),
+}
+
+/// Translate a screen rectangle (`[left, top, right, bottom]`) into image
+/// coordinates, padded and clipped to the image. `None` when the rectangle
+/// lies entirely outside it.
+pub fn to_image_rect(screen_rect: [i32; 4], origin: (i32, i32), width: u32, height: u32) -> Option<[u32; 4]> {
+    let [left, top, right, bottom] = screen_rect;
+    let clip = |v: i32, max: u32| v.clamp(0, max as i32 - 1) as u32;
+    let l = left - origin.0 - HIGHLIGHT_PADDING;
+    let t = top - origin.1 - HIGHLIGHT_PADDING;
+    let r = right - origin.0 + HIGHLIGHT_PADDING;
+    let b = bottom - origin.1 + HIGHLIGHT_PADDING;
+    if width == 0 || height == 0 || r <= 0 || b <= 0 || l >= width as i32 || t >= height as i32 || r <= l || b <= t {
+        return None;
+    }
+    Some([clip(l, width), clip(t, height), clip(r, width), clip(b, height)])
+}
+
+/// Draw a rectangle outline (inclusive corners) with lines growing inwards.
+pub fn draw_highlight(image: &mut RgbaImage, rect: [u32; 4], color: Rgba<u8>, thickness: u32) {
+    let [left, top, right, bottom] = rect;
+    for y in top..=bottom.min(image.height() - 1) {
+        for x in left..=right.min(image.width() - 1) {
+            let on_edge = x - left < thickness
+                || right.saturating_sub(x) < thickness
+                || y - top < thickness
+                || bottom.saturating_sub(y) < thickness;
+            if on_edge {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+/// Grab the window (`hwnd`, or the active window when `None`), highlight
+/// `element_rect` (screen coordinates; the element under the cursor when
+/// `None`), burn in `overlay` and write the PNG into `dest_dir`. Returns the
+/// file path.
+///
+/// A missing element only skips the highlight; the window is still saved.
+pub fn capture_window_with_highlight(
+    hwnd: Option<isize>,
+    element_rect: Option<[i32; 4]>,
+    overlay: &CursorOverlay,
+    dest_dir: &Path,
+) -> Result<PathBuf, String> {
+    let element_rect = match element_rect {
+        Some(rect) => Some(rect),
+        None => crate::ui_tree::element_rect_at_cursor()
+            .map_err(|e| eprintln!("No element to highlight: {}", e))
+            .ok(),
+    };
+    let mut shot = grab_window(hwnd)?;
+    let (width, height) = shot.image.dimensions();
+    if let Some(rect) = element_rect.and_then(|r| to_image_rect(r, shot.origin, width, height)) {
+        draw_highlight(&mut shot.image, rect, HIGHLIGHT_COLOR, HIGHLIGHT_THICKNESS);
+    }
+    overlay.burn_in(&mut shot.image, shot.origin);
+
+    std::fs::create_dir_all(dest_dir).map_err(|e| format!("Cannot create {:?}: {}", dest_dir, e))?;
+    let path = dest_dir.join(format!("window-{}.png", Uuid::new_v4()));
+    shot.image
+        .save_with_format(&path, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(path)
+}
+
+/// Screen bounds of a window (the active one when `hwnd` is `None`):
+/// `(left, top, width, height)`.
+#[cfg(windows)]
+pub fn window_bounds(hwnd: Option<isize>) -> Result<(i32, i32, i32, i32), String> {
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowRect};
+
+    let rect = unsafe {
+        let hwnd = match hwnd {
+            Some(handle) => HWND(handle as *mut _),
+            None => GetForegroundWindow(),
+        };
+        if hwnd.0.is_null() {
+            return Err("No window to capture".to_string());
+        }
+        let mut rect = RECT::default();
+        GetWindowRect(hwnd, &mut rect).map_err(|e| format!("Cannot read window bounds: {}", e))?;
+        rect
+    };
+    let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+    if width <= 0 || height <= 0 {
+        return Err("Window has no visible area".to_string());
+    }
+    Ok((rect.left, rect.top, width, height))
+}
+
+/// Copy a window's screen area into an image.
+#[cfg(windows)]
+pub fn grab_window(hwnd: Option<isize>) -> Result<WindowShot, String> {
+    let (left, top, width, height) = window_bounds(hwnd)?;
+    Ok(WindowShot {
+        image: grab_screen_area(left, top, width, height)?,
+        origin: (left, top),
+    })
+}
+
+/// Copy a rectangle of the virtual screen (physical pixels) into an image.
+#[cfg(windows)]
+pub fn grab_screen_area(left: i32, top: i32, width: i32, height: i32) -> Result<RgbaImage, String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Gdi::{
+        BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits, ReleaseDC,
+        SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, SRCCOPY,
+    };
+
+    unsafe {
+        let screen = GetDC(HWND::default());
+        let memory = CreateCompatibleDC(screen);
+        let bitmap = CreateCompatibleBitmap(screen, width, height);
+        let previous = SelectObject(memory, bitmap);
+        let copied = BitBlt(memory, 0, 0, width, height, screen, left, top, SRCCOPY);
+        // GetDIBits needs the bitmap deselected from the DC
+        SelectObject(memory, previous);
+
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                biHeight: -height, // top-down rows
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        let rows = GetDIBits(
+            memory,
+            bitmap,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr() as *mut _),
+            &mut info,
+            DIB_RGB_COLORS,
+        );
+
+        let _ = DeleteObject(bitmap);
+        let _ = DeleteDC(memory);
+        ReleaseDC(HWND::default(), screen);
+
+        copied.map_err(|e| format!("Failed to copy screen pixels: {}", e))?;
+        if rows == 0 {
+            return Err("Failed to read screen pixels".to_string());
+        }
+
+        // BGRA → RGBA, fully opaque
+        for px in pixels.chunks_exact_mut(4) {
+            px.swap(0, 2);
+            px[3] = 255;
+        }
+        RgbaImage::from_raw(width as u32, height as u32, pixels)
+            .ok_or_else(|| "Captured pixel buffer has the wrong size".to_string())
+    }
+}
+
+/// Window bounds are Windows-only.
+#[cfg(not(windows))]
+pub fn window_bounds(_hwnd: Option<isize>) -> Result<(i32, i32, i32, i32), String> {
+    Err("Window bounds are only available on Windows".to_string())
+}
+
+/// Window capture is Windows-only.
+#[cfg(not(windows))]
+pub fn grab_window(_hwnd: Option<isize>) -> Result<WindowShot, String> {
+    Err("Window capture is only available on Windows".to_string())
+}
+
+/// Screen capture is Windows-only.
+#[cfg(not(windows))]
+pub fn grab_screen_area(_left: i32, _top: i32, _width: i32, _height: i32) -> Result<RgbaImage, String> {
+    Err("Screen capture is only available on Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_image_rect_translates_pads_and_clips() {
+        // Window at (100, 50), 400x300; element fully inside
+        assert_eq!(to_image_rect([150, 80, 250, 120], (100, 50), 400, 300), Some([47, 27, 153, 73]));
+        // Element sticking out of the window's top-left corner
+        assert_eq!(to_image_rect([90, 40, 130, 70], (100, 50), 400, 300), Some([0, 0, 33, 23]));
+        // Element on another part of the screen
+        assert_eq!(to_image_rect([900, 900, 950, 950], (100, 50), 400, 300), None);
+        assert_eq!(to_image_rect([200, 200, 200, 200], (0, 0), 100, 100), None);
+    }
+
+    #[test]
+    fn test_draw_highlight_outlines_without_filling() {
+        let mut image = RgbaImage::from_pixel(20, 20, Rgba([255, 255, 255, 255]));
+        draw_highlight(&mut image, [2, 2, 17, 17], HIGHLIGHT_COLOR, 2);
+
+        assert_eq!(*image.get_pixel(2, 2), HIGHLIGHT_COLOR);
+        assert_eq!(*image.get_pixel(3, 10), HIGHLIGHT_COLOR);
+        assert_eq!(*image.get_pixel(17, 17), HIGHLIGHT_COLOR);
+        assert_eq!(*image.get_pixel(16, 10), HIGHLIGHT_COLOR);
+        assert_eq!(*image.get_pixel(10, 10), Rgba([255, 255, 255, 255]));
+        assert_eq!(*image.get_pixel(4, 10), Rgba([255, 255, 255, 255]));
+        assert_eq!(*image.get_pixel(1, 1), Rgba([255, 255, 255, 255]));
+    }
+}
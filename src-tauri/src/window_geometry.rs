@@ -0,0 +1,174 @@
+//! Remembered position and size of secondary windows.
+//!
+//! The last geometry of each secondary window is stored in settings under
+//! `window.geometry.<label>` when the window is destroyed and used again the
+//! next time a window with that label is built. Annotation windows have one
+//! label per image, so they share a single `annotation` entry. Saved
+//! geometry is clamped to the current monitor layout, so a window last shown
+//! on a monitor that has since been unplugged still opens on screen.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Prefix of the settings keys holding window geometry.
+pub const GEOMETRY_KEY_PREFIX: &str = "window.geometry.";
+
+/// Label prefix of annotation windows (`annotation-<image name>`).
+const ANNOTATION_LABEL_PREFIX: &str = "annotation-";
+
+/// Window position and inner size in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A monitor's area in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+impl MonitorArea {
+    fn overlap(&self, g: &WindowGeometry) -> i64 {
+        let w = (g.x + g.width as i32).min(self.x + self.width as i32) - g.x.max(self.x);
+        let h = (g.y + g.height as i32).min(self.y + self.height as i32) - g.y.max(self.y);
+        if w > 0 && h > 0 {
+            w as i64 * h as i64
+        } else {
+            0
+        }
+    }
+}
+
+/// Settings key for a window label.
+pub fn settings_key(label: &str) -> String {
+    if label.starts_with(ANNOTATION_LABEL_PREFIX) {
+        format!("{}annotation", GEOMETRY_KEY_PREFIX)
+    } else {
+        format!("{}{}", GEOMETRY_KEY_PREFIX, label)
+    }
+}
+
+pub fn load(conn: &Connection, label: &str) -> Option<WindowGeometry> {
+    SettingsRepository::new(conn)
+        .get(&settings_key(label))
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+}
+
+pub fn save(conn: &Connection, label: &str, geometry: &WindowGeometry) -> Result<(), String> {
+    let json = serde_json::to_string(geometry).map_err(|e| e.to_string())?;
+    SettingsRepository::new(conn)
+        .set(&settings_key(label), &json)
+        .map_err(|e| format!("Failed to save window geometry: {}", e))
+}
+
+/// Fit `geometry` onto the monitor it overlaps most, or the first monitor
+/// (the primary) when it is off screen entirely. The window is shrunk to the
+/// monitor if needed and moved fully inside it. Returns the clamped geometry
+/// and the chosen monitor's scale factor.
+pub fn clamp_to_monitors(geometry: WindowGeometry, monitors: &[MonitorArea]) -> (WindowGeometry, f64) {
+    let best = monitors
+        .iter()
+        .max_by_key(|m| m.overlap(&geometry))
+        .filter(|m| m.overlap(&geometry) > 0)
+        .or_else(|| monitors.first());
+    let Some(monitor) = best else {
+        return (geometry, 1.0);
+    };
+
+    let width = geometry.width.min(monitor.width);
+    let height = geometry.height.min(monitor.height);
+    let x = geometry.x.clamp(monitor.x, monitor.x + (monitor.width - width) as i32);
+    let y = geometry.y.clamp(monitor.y, monitor.y + (monitor.height - height) as i32);
+    (WindowGeometry { x, y, width, height }, monitor.scale_factor)
+}
+
+/// Monitors of the running app, primary first.
+pub fn monitor_areas<R: Runtime>(app: &AppHandle<R>) -> Vec<MonitorArea> {
+    let primary = app.primary_monitor().ok().flatten();
+    let mut monitors: Vec<tauri::Monitor> = app.available_monitors().unwrap_or_default();
+    if let Some(primary) = &primary {
+        monitors.sort_by_key(|m| m.position() != primary.position());
+    }
+    monitors
+        .iter()
+        .map(|m| MonitorArea {
+            x: m.position().x,
+            y: m.position().y,
+            width: m.size().width,
+            height: m.size().height,
+            scale_factor: m.scale_factor(),
+        })
+        .collect()
+}
+
+/// Apply remembered geometry (from [`load`]), if any, to a window about to
+/// be built. The builder works in logical pixels, so the clamped geometry is
+/// converted with the target monitor's scale factor.
+pub fn apply_to_builder<'a, R: Runtime, M: Manager<R>>(
+    builder: WebviewWindowBuilder<'a, R, M>,
+    app: &AppHandle<R>,
+    saved: Option<WindowGeometry>,
+) -> WebviewWindowBuilder<'a, R, M> {
+    let Some(saved) = saved else {
+        return builder;
+    };
+    let (g, scale) = clamp_to_monitors(saved, &monitor_areas(app));
+    builder
+        .position(g.x as f64 / scale, g.y as f64 / scale)
+        .inner_size(g.width as f64 / scale, g.height as f64 / scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(x: i32, y: i32, width: u32, height: u32) -> MonitorArea {
+        MonitorArea { x, y, width, height, scale_factor: 1.0 }
+    }
+
+    #[test]
+    fn test_settings_key_shares_annotation_windows() {
+        assert_eq!(settings_key("session-notes"), "window.geometry.session-notes");
+        assert_eq!(settings_key("annotation-capture-001-png"), "window.geometry.annotation");
+    }
+
+    #[test]
+    fn test_clamp_to_monitors() {
+        let primary = monitor(0, 0, 1920, 1080);
+        let right = MonitorArea { scale_factor: 1.5, ..monitor(1920, 0, 2560, 1440) };
+        let g = |x, y, width, height| WindowGeometry { x, y, width, height };
+
+        // Fully visible on the second monitor: kept as is
+        assert_eq!(clamp_to_monitors(g(2000, 100, 400, 300), &[primary, right]), (g(2000, 100, 400, 300), 1.5));
+        // Straddling the right edge of the primary: moved onto the monitor it overlaps most
+        assert_eq!(clamp_to_monitors(g(1800, 50, 400, 300), &[primary, right]).0, g(1920, 50, 400, 300));
+        // Second monitor unplugged: moved back onto the primary
+        assert_eq!(clamp_to_monitors(g(3000, 200, 400, 300), &[primary]), (g(1520, 200, 400, 300), 1.0));
+        // Larger than the monitor: shrunk to fit
+        assert_eq!(clamp_to_monitors(g(-50, -50, 3000, 2000), &[primary]).0, g(0, 0, 1920, 1080));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        assert_eq!(load(&conn, "session-notes"), None);
+
+        let geometry = WindowGeometry { x: 10, y: 20, width: 450, height: 380 };
+        save(&conn, "session-notes", &geometry).unwrap();
+        assert_eq!(load(&conn, "session-notes"), Some(geometry));
+    }
+}
@@ -0,0 +1,213 @@
+//! Appearance of secondary windows.
+//!
+//! The session-notes, session-status and annotation windows are created from
+//! Rust, so they cannot read the theme the user picked in the main window.
+//! The `ui.theme` setting holds a [`WindowAppearance`] that is applied when
+//! those windows are built and re-applied to open windows by
+//! `set_window_appearance`.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::window::Color;
+use tauri::{Manager, Runtime, WebviewWindow, WebviewWindowBuilder};
+
+use crate::database::{SettingsOps, SettingsRepository};
+
+/// Settings key holding [`WindowAppearance`] as JSON.
+pub const THEME_KEY: &str = "ui.theme";
+
+/// Label of the main window, which follows the frontend theme itself.
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+/// Windows drawn with a transparent background; the background colour is
+/// not applied to them.
+const TRANSPARENT_WINDOWS: &[&str] = &["session-status"];
+
+/// Lowest accepted opacity, so a window can never become invisible.
+pub const MIN_OPACITY: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    /// Follow the operating system setting
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl ThemeMode {
+    fn as_tauri(self) -> Option<tauri::Theme> {
+        match self {
+            ThemeMode::System => None,
+            ThemeMode::Light => Some(tauri::Theme::Light),
+            ThemeMode::Dark => Some(tauri::Theme::Dark),
+        }
+    }
+}
+
+/// Appearance shared by all secondary windows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WindowAppearance {
+    pub theme: ThemeMode,
+    /// `#RRGGBB` or `#RRGGBBAA`; None keeps the webview default
+    pub background_color: Option<String>,
+    pub always_on_top: bool,
+    /// 0.3–1.0; only applied on Windows
+    pub opacity: f64,
+}
+
+impl Default for WindowAppearance {
+    fn default() -> Self {
+        Self {
+            theme: ThemeMode::System,
+            background_color: None,
+            always_on_top: true,
+            opacity: 1.0,
+        }
+    }
+}
+
+impl WindowAppearance {
+    /// Stored appearance; missing or unreadable settings give the defaults.
+    pub fn load(conn: &Connection) -> Self {
+        SettingsRepository::new(conn)
+            .get(THEME_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        SettingsRepository::new(conn)
+            .set(THEME_KEY, &json)
+            .map_err(|e| format!("Failed to save window appearance: {}", e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(color) = &self.background_color {
+            parse_color(color)?;
+        }
+        if !(MIN_OPACITY..=1.0).contains(&self.opacity) {
+            return Err(format!("Opacity must be between {} and 1", MIN_OPACITY));
+        }
+        Ok(())
+    }
+
+    fn background_for(&self, label: &str) -> Option<Color> {
+        if TRANSPARENT_WINDOWS.contains(&label) {
+            return None;
+        }
+        self.background_color.as_deref().and_then(|c| parse_color(c).ok())
+    }
+
+    /// Apply the appearance to a window that is about to be built.
+    pub fn apply_to_builder<'a, R: Runtime, M: Manager<R>>(
+        &self,
+        builder: WebviewWindowBuilder<'a, R, M>,
+        label: &str,
+    ) -> WebviewWindowBuilder<'a, R, M> {
+        let builder = builder.theme(self.theme.as_tauri()).always_on_top(self.always_on_top);
+        match self.background_for(label) {
+            Some(color) => builder.background_color(color),
+            None => builder,
+        }
+    }
+
+    /// Apply the appearance to an open window, including its opacity.
+    pub fn apply_to_window<R: Runtime>(&self, window: &WebviewWindow<R>) -> Result<(), String> {
+        let label = window.label().to_string();
+        window.set_theme(self.theme.as_tauri()).map_err(|e| format!("{}: {}", label, e))?;
+        window.set_always_on_top(self.always_on_top).map_err(|e| format!("{}: {}", label, e))?;
+        if let Some(color) = self.background_for(&label) {
+            window.set_background_color(Some(color)).map_err(|e| format!("{}: {}", label, e))?;
+        }
+        self.apply_opacity(window).map_err(|e| format!("{}: {}", label, e))
+    }
+
+    /// Opacity cannot be set on the builder; call this once the window exists.
+    pub fn apply_opacity<R: Runtime>(&self, window: &WebviewWindow<R>) -> Result<(), String> {
+        set_opacity(window, self.opacity)
+    }
+}
+
+/// Parse `#RRGGBB` / `#RRGGBBAA` (leading `#` optional).
+pub fn parse_color(value: &str) -> Result<Color, String> {
+    let hex = value.trim().trim_start_matches('#');
+    let invalid = || format!("Invalid colour '{}', expected #RRGGBB or #RRGGBBAA", value);
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Ok(Color(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+/// Make the whole window translucent via a layered window. Fully opaque
+/// windows drop the layered style so they render normally again.
+#[cfg(windows)]
+fn set_opacity<R: Runtime>(window: &WebviewWindow<R>, opacity: f64) -> Result<(), String> {
+    use windows::Win32::Foundation::{COLORREF, HWND};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE, LWA_ALPHA, WS_EX_LAYERED,
+    };
+
+    let handle = window.hwnd().map_err(|e| e.to_string())?;
+    let hwnd = HWND(handle.0 as *mut _);
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        if opacity >= 1.0 {
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style & !(WS_EX_LAYERED.0 as isize));
+            return Ok(());
+        }
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED.0 as isize);
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), (opacity * 255.0).round() as u8, LWA_ALPHA)
+            .map_err(|e| format!("Failed to set opacity: {}", e))
+    }
+}
+
+/// Window opacity is Windows-only; elsewhere it is ignored.
+#[cfg(not(windows))]
+fn set_opacity<R: Runtime>(_window: &WebviewWindow<R>, _opacity: f64) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#1E1E2E").unwrap(), Color(0x1e, 0x1e, 0x2e, 255));
+        assert_eq!(parse_color("ffffff80").unwrap(), Color(255, 255, 255, 0x80));
+        assert!(parse_color("#fff").is_err());
+        assert!(parse_color("#12345z").is_err());
+    }
+
+    #[test]
+    fn test_validate_and_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::init_database(&conn).unwrap();
+        assert_eq!(WindowAppearance::load(&conn), WindowAppearance::default());
+
+        let appearance = WindowAppearance {
+            theme: ThemeMode::Dark,
+            background_color: Some("#202124".to_string()),
+            always_on_top: false,
+            opacity: 0.9,
+        };
+        appearance.validate().unwrap();
+        appearance.save(&conn).unwrap();
+        assert_eq!(WindowAppearance::load(&conn), appearance);
+
+        assert!(WindowAppearance { opacity: 0.1, ..Default::default() }.validate().is_err());
+        assert!(WindowAppearance { background_color: Some("dark".to_string()), ..Default::default() }
+            .validate()
+            .is_err());
+        assert_eq!(appearance.background_for("session-status"), None);
+        assert!(appearance.background_for("session-notes").is_some());
+    }
+}
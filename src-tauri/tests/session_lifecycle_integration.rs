@@ -385,11 +385,9 @@ fn test_capture_records_linked_to_bugs() {
         file_name: "screenshot_001.png".to_string(),
         file_path: screenshot_path.to_string_lossy().to_string(),
         file_type: CaptureType::Screenshot,
-        annotated_path: None,
         file_size_bytes: Some(fake_png.len() as i64),
-        is_console_capture: false,
-        parsed_content: None,
         created_at: chrono::Utc::now().to_rfc3339(),
+        ..Default::default()
     };
     capture_repo.create(&capture).unwrap();
 
@@ -479,11 +477,9 @@ fn test_file_watcher_simulated_detection() {
             file_name: file_name.clone(),
             file_path: file_path.to_string_lossy().to_string(),
             file_type: CaptureType::Screenshot,
-            annotated_path: None,
             file_size_bytes: Some(file_path.metadata().unwrap().len() as i64),
-            is_console_capture: false,
-            parsed_content: None,
             created_at: chrono::Utc::now().to_rfc3339(),
+            ..Default::default()
         };
         capture_repo.create(&capture).unwrap();
     }
@@ -15,6 +15,8 @@ import type {
   Setting,
   Capture,
   TicketingCredentials,
+  TicketingProvider,
+  JiraConfig,
   CreateTicketRequest,
   CreateTicketResponse,
   ConnectionStatus,
@@ -299,10 +301,15 @@ export async function ticketingAuthenticate(credentials: TicketingCredentials):
   await invoke('ticketing_authenticate', { credentials })
 }
 
+/**
+ * Create a ticket. Pass the bug's id so the backend records the ticket on the
+ * bug and applies its item-type label, tester notes and capture uploads.
+ */
 export async function ticketingCreateTicket(
-  request: CreateTicketRequest
+  request: CreateTicketRequest,
+  bugId?: string | null
 ): Promise<CreateTicketResponse> {
-  return await invoke<CreateTicketResponse>('ticketing_create_ticket', { request })
+  return await invoke<CreateTicketResponse>('ticketing_create_ticket', { request, bugId: bugId ?? null })
 }
 
 export async function ticketingCheckConnection(): Promise<ConnectionStatus> {
@@ -317,6 +324,22 @@ export async function ticketingSaveCredentials(credentials: TicketingCredentials
   await invoke('ticketing_save_credentials', { credentials })
 }
 
+export async function ticketingGetProvider(): Promise<TicketingProvider> {
+  return await invoke<TicketingProvider>('ticketing_get_provider')
+}
+
+export async function ticketingSetProvider(provider: TicketingProvider): Promise<void> {
+  await invoke('ticketing_set_provider', { provider })
+}
+
+export async function ticketingGetJiraConfig(): Promise<JiraConfig> {
+  return await invoke<JiraConfig>('ticketing_get_jira_config')
+}
+
+export async function ticketingSaveJiraConfig(config: JiraConfig): Promise<void> {
+  await invoke('ticketing_save_jira_config', { config })
+}
+
 export async function ticketingFetchTeams(): Promise<LinearTeam[]> {
   return await invoke<LinearTeam[]>('ticketing_fetch_teams')
 }
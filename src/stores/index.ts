@@ -20,6 +20,7 @@ export async function initializeStores(): Promise<void> {
     // Initialize settings first (needed by other stores)
     const settingsStore = useSettingsStore()
     settingsStore.initialize()
+    await settingsStore.setupEventListeners()
 
     // Set up event listeners for session store
     const sessionStore = useSessionStore()
@@ -54,6 +55,9 @@ export async function initializeStores(): Promise<void> {
  */
 export function cleanupStores(): void {
   try {
+    const settingsStore = useSettingsStore()
+    settingsStore.cleanupEventListeners()
+
     const sessionStore = useSessionStore()
     sessionStore.cleanupEventListeners()
 
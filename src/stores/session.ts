@@ -283,6 +283,21 @@ export const useSessionStore = defineStore('session', () => {
     })
     eventUnlisteners.value.push(unlistenSessionCreated)
 
+    // Sessions started outside this window (e.g. from the tray) emit the typed
+    // session:started event; reload so isSessionActive reflects them
+    const unlistenSessionStarted = await listen<{ sessionId: string; folderPath: string; startedAt: string }>(
+      'session:started',
+      async (event) => {
+        if (activeSession.value?.id === event.payload.sessionId) return
+        try {
+          await loadActiveSession()
+        } catch (err) {
+          console.error('Failed to load the started session:', err)
+        }
+      }
+    )
+    eventUnlisteners.value.push(unlistenSessionStarted)
+
     // Listen for session updated events
     const unlistenSessionUpdated = await listen<Session>('session-updated', (event) => {
       const session = event.payload
@@ -1,5 +1,6 @@
 import { defineStore } from 'pinia'
 import { ref, computed } from 'vue'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import type { Setting } from '../types/backend'
 import * as tauri from '../api/tauri'
 
@@ -20,6 +21,9 @@ export const SETTINGS_KEYS = {
   AUTO_START_RECORDING: 'auto_start_recording',
   CAPTURE_CONSOLE: 'capture_console',
   AI_ENABLED: 'ai_enabled',
+  // Quick toggles (matching quick_toggles.rs, also switchable from the tray)
+  CONSOLE_AUTO_PARSE: 'console.auto_parse',
+  NOTIFICATIONS_ENABLED: 'notifications.enabled',
   THEME: 'theme',
   ANNOTATION_SAVE_MODE: 'annotation_save_mode',
   AUTO_OPEN_ANNOTATION: 'auto_open_annotation',
@@ -45,6 +49,8 @@ const DEFAULT_SETTINGS: Record<SettingsKey, string> = {
   [SETTINGS_KEYS.AUTO_START_RECORDING]: 'false',
   [SETTINGS_KEYS.CAPTURE_CONSOLE]: 'true',
   [SETTINGS_KEYS.AI_ENABLED]: 'false',
+  [SETTINGS_KEYS.CONSOLE_AUTO_PARSE]: 'true',
+  [SETTINGS_KEYS.NOTIFICATIONS_ENABLED]: 'true',
   [SETTINGS_KEYS.THEME]: 'light',
   [SETTINGS_KEYS.ANNOTATION_SAVE_MODE]: 'alongside',
   [SETTINGS_KEYS.AUTO_OPEN_ANNOTATION]: 'false',
@@ -60,6 +66,7 @@ export const useSettingsStore = defineStore('settings', () => {
   const loading = ref(false)
   const error = ref<string | null>(null)
   const isDirty = ref(false)
+  const eventUnlisteners = ref<UnlistenFn[]>([])
 
   // ============================================================================
   // Getters
@@ -83,6 +90,8 @@ export const useSettingsStore = defineStore('settings', () => {
   const autoStartRecording = computed(() => settings.value[SETTINGS_KEYS.AUTO_START_RECORDING] === 'true')
   const captureConsole = computed(() => settings.value[SETTINGS_KEYS.CAPTURE_CONSOLE] === 'true')
   const aiEnabled = computed(() => settings.value[SETTINGS_KEYS.AI_ENABLED] === 'true')
+  const consoleAutoParse = computed(() => settings.value[SETTINGS_KEYS.CONSOLE_AUTO_PARSE] === 'true')
+  const notificationsEnabled = computed(() => settings.value[SETTINGS_KEYS.NOTIFICATIONS_ENABLED] === 'true')
   const theme = computed(() => settings.value[SETTINGS_KEYS.THEME])
   const annotationSaveMode = computed(() => settings.value[SETTINGS_KEYS.ANNOTATION_SAVE_MODE] as 'alongside' | 'overwrite')
   const autoOpenAnnotation = computed(() => settings.value[SETTINGS_KEYS.AUTO_OPEN_ANNOTATION] === 'true')
@@ -194,6 +203,27 @@ export const useSettingsStore = defineStore('settings', () => {
     })
   }
 
+  // ============================================================================
+  // Event Listeners
+  // ============================================================================
+
+  async function setupEventListeners(): Promise<void> {
+    // Follow settings switched from the tray menu
+    const unlistenQuickToggle = await listen<{ key: string; enabled: boolean }>(
+      'settings:quick-toggle-changed',
+      (event) => {
+        const { key, enabled } = event.payload
+        settings.value[key] = String(enabled)
+      }
+    )
+    eventUnlisteners.value.push(unlistenQuickToggle)
+  }
+
+  function cleanupEventListeners(): void {
+    eventUnlisteners.value.forEach(unlisten => unlisten())
+    eventUnlisteners.value = []
+  }
+
   // ============================================================================
   // Store Return
   // ============================================================================
@@ -220,6 +250,8 @@ export const useSettingsStore = defineStore('settings', () => {
     autoStartRecording,
     captureConsole,
     aiEnabled,
+    consoleAutoParse,
+    notificationsEnabled,
     theme,
     annotationSaveMode,
     autoOpenAnnotation,
@@ -239,5 +271,9 @@ export const useSettingsStore = defineStore('settings', () => {
 
     // Actions - Lifecycle
     initialize,
+
+    // Actions - Events
+    setupEventListeners,
+    cleanupEventListeners,
   }
 })
@@ -18,6 +18,18 @@ export interface Session {
   created_at: string
   /** The QA profile active when this session was started. Null if none. */
   profile_id: string | null
+  /** Set when a reviewed/synced session was unlocked for editing with unlock_session. */
+  unlocked_at?: string | null
+  /** IANA time zone of the tester when the session started, e.g. "Europe/Berlin". */
+  timezone?: string | null
+}
+
+/** Number of a session's bugs in each BugStatus */
+export interface BugStatusCounts {
+  capturing: number
+  captured: number
+  reviewed: number
+  ready: number
 }
 
 export interface SessionSummary {
@@ -26,10 +38,15 @@ export interface SessionSummary {
   ended_at: string | null
   status: SessionStatus
   bug_count: number
+  status_counts: BugStatusCounts
+  /** Bugs that have been through review (status reviewed or ready) */
+  reviewed_count: number
+  /** Bugs with a ticket filed in the ticketing integration */
+  ticketed_count: number
 }
 
 // Bug types
-export type BugType = 'bug' | 'feature' | 'feedback'
+export type BugType = 'bug' | 'feature' | 'feedback' | 'question'
 export type BugStatus = 'capturing' | 'captured' | 'reviewed' | 'ready'
 
 export interface Bug {
@@ -52,8 +69,19 @@ export interface Bug {
   folder_path: string
   created_at: string
   updated_at: string
+  /** Provider ID of the ticket filed for this bug, once one exists. */
+  external_ticket_id?: string | null
+  /** Human-readable ticket key, e.g. "PROJ-123". */
+  external_ticket_key?: string | null
+  external_ticket_url?: string | null
+  /** Ticket status in the provider (e.g. "In Review") as of the last status sync. */
+  external_status?: string | null
+  /** Provider-independent group of external_status. */
+  external_status_category?: TicketStatusCategory | null
 }
 
+export type TicketStatusCategory = 'open' | 'in_progress' | 'done' | 'canceled'
+
 export interface BugUpdate {
   type?: BugType
   title?: string
@@ -83,6 +111,8 @@ export interface Capture {
   is_console_capture: boolean
   parsed_content: string | null
   created_at: string
+  /** Short description of the image for screen readers and ticket attachments */
+  alt_text?: string | null
 }
 
 // Settings types
@@ -117,6 +147,16 @@ export interface TicketingCredentials {
   team_id?: string | null
 }
 
+export type TicketingProvider = 'linear' | 'jira'
+
+/** Jira Cloud settings; `api_token` is masked when read back */
+export interface JiraConfig {
+  site_url: string
+  email: string
+  api_token: string
+  project_key: string
+}
+
 export interface CreateTicketRequest {
   title: string
   description: string